@@ -0,0 +1,102 @@
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use tokio::fs;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// A single file inside a zip archive (path relative to the archive root)
+pub(crate) type ArchiveEntry = (String, Vec<u8>);
+
+/// Build an in-memory zip archive from a list of entries
+pub(crate) fn build_zip(entries: &[ArchiveEntry]) -> Result<Vec<u8>, String> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (name, bytes) in entries {
+        writer
+            .start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to archive: {}", name, e))?;
+        writer
+            .write_all(bytes)
+            .map_err(|e| format!("Failed to write {} to archive: {}", name, e))?;
+    }
+
+    let cursor = writer
+        .finish()
+        .map_err(|e| format!("Failed to finish archive: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+/// Read every file in a zip archive into memory.
+///
+/// Entries whose paths would escape the archive root (absolute paths, `..`)
+/// are rejected rather than silently skipped.
+pub(crate) fn read_zip(bytes: &[u8]) -> Result<Vec<ArchiveEntry>, String> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("Invalid archive: {}", e))?;
+    let mut entries = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+        if file.is_dir() {
+            continue;
+        }
+
+        let name = file
+            .enclosed_name()
+            .ok_or_else(|| format!("Unsafe path in archive: {}", file.name()))?
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)
+            .map_err(|e| format!("Failed to read {} from archive: {}", name, e))?;
+        entries.push((name, contents));
+    }
+
+    Ok(entries)
+}
+
+/// Read every file under `dir` into entries named `<prefix><relative path>`.
+///
+/// Top-level entries whose names are in `skip` are left out.
+pub(crate) async fn collect_dir(
+    dir: &Path,
+    prefix: &str,
+    skip: &[&str],
+) -> Result<Vec<ArchiveEntry>, String> {
+    let mut entries = Vec::new();
+    let mut pending = vec![(dir.to_path_buf(), prefix.to_string())];
+
+    while let Some((current, current_prefix)) = pending.pop() {
+        let mut read_dir = fs::read_dir(&current)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", current.display(), e))?;
+        while let Some(entry) = read_dir
+            .next_entry()
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", current.display(), e))?
+        {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if current == dir && skip.contains(&file_name.as_str()) {
+                continue;
+            }
+
+            let path = entry.path();
+            let name = format!("{}{}", current_prefix, file_name);
+            if path.is_dir() {
+                pending.push((path, format!("{}/", name)));
+            } else {
+                let contents = fs::read(&path)
+                    .await
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                entries.push((name, contents));
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(entries)
+}
@@ -0,0 +1,165 @@
+use serde_json::Value;
+
+use crate::commands::{
+    app_lock, artifact_clone, audit_log, fonts, library_storage, project_storage, session_mode,
+};
+
+// OpenDyslexic, fetched from the project's repository the first time it's
+// needed. A font imported under the same family name is used instead.
+const DYSLEXIC_FAMILY: &str = "OpenDyslexic";
+const DYSLEXIC_FACES: &[(u16, &str)] = &[
+    (
+        400,
+        "https://raw.githubusercontent.com/antijingoist/opendyslexic/master/\
+         compiled/OpenDyslexic-Regular.otf",
+    ),
+    (
+        700,
+        "https://raw.githubusercontent.com/antijingoist/opendyslexic/master/\
+         compiled/OpenDyslexic-Bold.otf",
+    ),
+];
+
+// Overrides for each profile. They go after the artifact's own styles and
+// use !important so they win over inline sizes and colors.
+const LARGE_PRINT_CSS: &str = "\
+body, body * { font-size: 18pt !important; line-height: 1.5 !important; }
+body h1 { font-size: 26pt !important; }
+body h2 { font-size: 22pt !important; }
+body h3 { font-size: 20pt !important; }
+body { max-width: none !important; }
+li, p { margin-bottom: 0.6em !important; }
+.answer-line { height: 3em !important; }
+.drawing-box { height: 3.5in !important; }
+img { max-width: 100% !important; height: auto !important; }
+@page { margin: 0.5in; }
+";
+const DYSLEXIA_CSS: &str = "\
+body, body * { font-family: 'OpenDyslexic', 'Comic Sans MS', Verdana, sans-serif !important; \
+font-style: normal !important; text-align: left !important; }
+body { letter-spacing: 0.05em !important; word-spacing: 0.16em !important; \
+line-height: 1.8 !important; }
+li, p { margin-bottom: 1em !important; max-width: 65ch; }
+.answer-line { height: 2.5em !important; }
+@media screen { body { background: #FDF6E3 !important; } }
+";
+const HIGH_CONTRAST_CSS: &str = "\
+body, body * { color: #000000 !important; background: #FFFFFF !important; \
+border-color: #000000 !important; text-shadow: none !important; box-shadow: none !important; }
+body { font-weight: 500 !important; }
+h1, h2, h3, h4, strong, b, .answer { font-weight: 800 !important; }
+.answer-line { border-bottom: 2px solid #000000 !important; }
+.drawing-box { border: 3px solid #000000 !important; }
+a { text-decoration: underline !important; }
+";
+
+// Helper to get a profile's title label and CSS
+fn profile_style(profile: &str) -> Result<(&'static str, &'static str), String> {
+    match profile {
+        "large_print" => Ok(("Large Print", LARGE_PRINT_CSS)),
+        "dyslexia" => Ok(("Dyslexia-Friendly", DYSLEXIA_CSS)),
+        "high_contrast" => Ok(("High Contrast", HIGH_CONTRAST_CSS)),
+        _ => Err(format!("Unknown accessibility profile: {}", profile)),
+    }
+}
+
+// Helper to add a profile's stylesheet at the end of <head>, or at the top
+// for fragments. Transforming an already transformed copy stacks profiles.
+fn apply_style(html: &str, profile: &str, css: &str) -> String {
+    let style = format!("<style data-accessibility=\"{}\">\n{}</style>\n", profile, css);
+    match html.to_ascii_lowercase().find("</head>") {
+        Some(index) => format!("{}{}{}", &html[..index], style, &html[index..]),
+        None => format!("{}{}", style, html),
+    }
+}
+
+// ============================================
+// Accessibility Commands
+// ============================================
+
+/// Make an accessible copy of an artifact for screen and print. `profile`
+/// is "large_print" (18pt text, larger answer spaces), "dyslexia"
+/// (OpenDyslexic embedded, wider letter, word, and line spacing, left
+/// aligned), or "high_contrast" (black on white, heavier lines). The copy is
+/// saved as a new artifact with `accessibleFrom` pointing at the source and
+/// is added to the source's project. If OpenDyslexic can't be downloaded
+/// the copy falls back to other readable fonts. Returns `{ artifactId,
+/// sourceArtifactId, profile, fontEmbedded }`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn transform_accessibility(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    profile: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    let (label, css) = profile_style(&profile)?;
+
+    let source = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    let html = source
+        .get("htmlContent")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let mut accessible_html = apply_style(html, &profile, css);
+
+    let mut font_embedded = false;
+    if profile == "dyslexia" {
+        match fonts::ensure_family(&app_handle, DYSLEXIC_FAMILY, DYSLEXIC_FACES).await {
+            Ok(()) => {
+                let families = [DYSLEXIC_FAMILY.to_string()];
+                accessible_html =
+                    fonts::embed_fonts(&app_handle, accessible_html, Some(&families)).await?;
+                font_embedded = true;
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to get OpenDyslexic"),
+        }
+    }
+
+    let mut accessible = source.clone();
+    let obj = accessible.as_object_mut().ok_or("Invalid artifact")?;
+    for key in artifact_clone::RESET_KEYS {
+        obj.remove(*key);
+    }
+    let accessible_id = uuid::Uuid::new_v4().to_string();
+    let title = source.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled");
+    obj.insert("artifactId".to_string(), Value::String(accessible_id.clone()));
+    obj.insert(
+        "title".to_string(),
+        Value::String(format!("{} ({})", title, label)),
+    );
+    obj.insert("htmlContent".to_string(), Value::String(accessible_html));
+    obj.insert("accessibleFrom".to_string(), Value::String(artifact_id.clone()));
+    obj.insert("accessibilityProfile".to_string(), Value::String(profile.clone()));
+    obj.insert(
+        "createdAt".to_string(),
+        Value::String(chrono::Utc::now().to_rfc3339()),
+    );
+
+    let content = serde_json::to_string_pretty(&accessible)
+        .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+    library_storage::write_artifact(&app_handle, &accessible, &content).await?;
+
+    if let Some(project_id) = accessible.get("projectId").and_then(|v| v.as_str()) {
+        let linked = project_storage::link_artifact(&app_handle, project_id, &accessible_id).await;
+        if let Err(e) = linked {
+            tracing::warn!(error = %e, "Failed to add accessible artifact to project");
+        }
+    }
+
+    audit_log::record(
+        &app_handle,
+        "transform_accessibility",
+        "artifact",
+        &[&accessible_id, &artifact_id],
+    )
+    .await;
+
+    let response = serde_json::json!({
+        "artifactId": accessible_id,
+        "sourceArtifactId": artifact_id,
+        "profile": profile,
+        "fontEmbedded": font_embedded,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,265 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{audit_log, locale, secrets, session_mode};
+
+// Keychain key holding the PIN config
+const PIN_KEY: &str = "app-lock.pin";
+
+// Where the PIN config was kept before it moved to the keychain
+const SECURITY_DIR: &str = "security";
+const LOCK_FILE: &str = "app-lock.json";
+
+// Failed unlock attempts allowed before a cooldown kicks in
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+const FAILED_ATTEMPT_COOLDOWN: Duration = Duration::from_secs(30);
+
+const MIN_PIN_LENGTH: usize = 4;
+
+/// PIN configuration kept in the OS keychain (only the argon2 hash is stored)
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LockConfig {
+    pin_hash: String,
+    updated_at: String,
+}
+
+#[derive(Default)]
+struct LockInner {
+    locked: bool,
+    failed_attempts: u32,
+    cooldown_until: Option<Instant>,
+}
+
+/// Managed state tracking whether the app is currently locked
+#[derive(Default)]
+pub struct AppLockState {
+    inner: Mutex<LockInner>,
+}
+
+// Helper to get the path of the lock config file from older versions
+fn get_lock_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(SECURITY_DIR).join(LOCK_FILE))
+}
+
+// Helper to read the stored PIN config, if a PIN has been set. A config
+// left on disk by an older version is moved into the keychain.
+async fn read_lock_config(app_handle: &tauri::AppHandle) -> Result<Option<LockConfig>, String> {
+    let content = match secrets::read_secret(PIN_KEY).await? {
+        Some(content) => content,
+        None => {
+            let lock_path = get_lock_path(app_handle)?;
+            if !lock_path.exists() {
+                return Ok(None);
+            }
+            let content = fs::read_to_string(&lock_path)
+                .await
+                .map_err(|e| format!("Failed to read app lock: {}", e))?;
+            secrets::write_secret(PIN_KEY, &content).await?;
+            fs::remove_file(&lock_path)
+                .await
+                .map_err(|e| format!("Failed to remove app lock: {}", e))?;
+            content
+        }
+    };
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Invalid app lock: {}", e))
+}
+
+// Helper to check a PIN against the stored hash
+fn pin_matches(config: &LockConfig, pin: &str) -> bool {
+    PasswordHash::new(&config.pin_hash)
+        .map(|hash| {
+            Argon2::default()
+                .verify_password(pin.as_bytes(), &hash)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+/// Whether a teacher PIN has been configured
+pub(crate) async fn has_pin(app_handle: &tauri::AppHandle) -> Result<bool, String> {
+    Ok(read_lock_config(app_handle).await?.is_some())
+}
+
+/// Start locked if a PIN has been configured. Called once during app setup.
+pub fn init(app_handle: &tauri::AppHandle) {
+    let has_pin = tauri::async_runtime::block_on(has_pin(app_handle)).unwrap_or(false);
+    let state = app_handle.state::<AppLockState>();
+    state.inner.lock().unwrap().locked = has_pin;
+}
+
+/// Remove the PIN without asking for it and reset the lock, for a factory
+/// reset that has already been confirmed
+pub(crate) async fn forget_pin(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    secrets::remove_secret(PIN_KEY).await?;
+    *app_handle.state::<AppLockState>().inner.lock().unwrap() = LockInner::default();
+    Ok(())
+}
+
+/// Guard used by sensitive commands: fails while the app is locked
+pub(crate) fn ensure_unlocked(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let state = app_handle.state::<AppLockState>();
+    if state.inner.lock().unwrap().locked {
+        return Err(locale::text(app_handle, "error.appLocked"));
+    }
+    Ok(())
+}
+
+// Helper to verify a PIN, enforcing the failed-attempt cooldown
+pub(crate) async fn verify_with_cooldown(app_handle: &tauri::AppHandle, pin: &str) -> Result<bool, String> {
+    let config = read_lock_config(app_handle)
+        .await?
+        .ok_or("No PIN has been set")?;
+    let state = app_handle.state::<AppLockState>();
+
+    {
+        let inner = state.inner.lock().unwrap();
+        if let Some(until) = inner.cooldown_until {
+            let now = Instant::now();
+            if now < until {
+                let seconds = ((until - now).as_secs() + 1).to_string();
+                return Err(locale::format(
+                    app_handle,
+                    "error.pinCooldown",
+                    &[("seconds", &seconds)],
+                ));
+            }
+        }
+    }
+
+    let matches = pin_matches(&config, pin);
+
+    let mut inner = state.inner.lock().unwrap();
+    if matches {
+        inner.failed_attempts = 0;
+        inner.cooldown_until = None;
+    } else {
+        inner.failed_attempts += 1;
+        if inner.failed_attempts >= MAX_FAILED_ATTEMPTS {
+            inner.failed_attempts = 0;
+            inner.cooldown_until = Some(Instant::now() + FAILED_ATTEMPT_COOLDOWN);
+        }
+    }
+    Ok(matches)
+}
+
+// ============================================
+// App Lock Commands
+// ============================================
+
+/// Get the lock status: whether a PIN is set and whether the app is locked
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_lock_status(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let has_pin = has_pin(&app_handle).await?;
+    let locked = app_handle.state::<AppLockState>().inner.lock().unwrap().locked;
+
+    let status = serde_json::json!({
+        "hasPin": has_pin,
+        "locked": locked,
+    });
+    Ok(status.to_string())
+}
+
+/// Set or change the PIN. Changing an existing PIN requires the current one.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn set_pin(
+    app_handle: tauri::AppHandle,
+    pin: String,
+    current_pin: Option<String>,
+) -> Result<(), String> {
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    if pin.chars().count() < MIN_PIN_LENGTH {
+        return Err(format!("PIN must be at least {} characters", MIN_PIN_LENGTH));
+    }
+
+    if has_pin(&app_handle).await? {
+        let current_pin = current_pin.ok_or("Current PIN is required")?;
+        if !verify_with_cooldown(&app_handle, &current_pin).await? {
+            return Err(locale::text(&app_handle, "error.incorrectPin"));
+        }
+    }
+
+    let salt = SaltString::generate(&mut OsRng);
+    let pin_hash = Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map_err(|e| format!("Failed to hash PIN: {}", e))?
+        .to_string();
+
+    let config = LockConfig {
+        pin_hash,
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let content = serde_json::to_string(&config)
+        .map_err(|e| format!("Failed to serialize app lock: {}", e))?;
+    secrets::write_secret(PIN_KEY, &content).await?;
+
+    audit_log::record(&app_handle, "set_pin", "security", &[]).await;
+
+    Ok(())
+}
+
+/// Remove the PIN (requires the current PIN) and unlock the app
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn clear_pin(app_handle: tauri::AppHandle, current_pin: String) -> Result<(), String> {
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    if !verify_with_cooldown(&app_handle, &current_pin).await? {
+        return Err(locale::text(&app_handle, "error.incorrectPin"));
+    }
+
+    secrets::remove_secret(PIN_KEY).await?;
+
+    app_handle.state::<AppLockState>().inner.lock().unwrap().locked = false;
+
+    audit_log::record(&app_handle, "clear_pin", "security", &[]).await;
+    Ok(())
+}
+
+/// Check a PIN without changing the lock state
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn verify_pin(app_handle: tauri::AppHandle, pin: String) -> Result<bool, String> {
+    verify_with_cooldown(&app_handle, &pin).await
+}
+
+/// Lock the app. Requires a PIN to have been set.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn lock_app(app_handle: tauri::AppHandle) -> Result<(), String> {
+    if !has_pin(&app_handle).await? {
+        return Err("Set a PIN before locking the app".to_string());
+    }
+
+    app_handle.state::<AppLockState>().inner.lock().unwrap().locked = true;
+    Ok(())
+}
+
+/// Unlock the app with the PIN
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn unlock_app(app_handle: tauri::AppHandle, pin: String) -> Result<(), String> {
+    if !verify_with_cooldown(&app_handle, &pin).await? {
+        return Err(locale::text(&app_handle, "error.incorrectPin"));
+    }
+
+    app_handle.state::<AppLockState>().inner.lock().unwrap().locked = false;
+    Ok(())
+}
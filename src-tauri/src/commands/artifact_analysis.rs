@@ -0,0 +1,434 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+use crate::commands::{design_pack_storage, library_storage};
+
+// Elements that never have a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements that start a new paragraph of text
+pub(crate) const BLOCK_ELEMENTS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "br", "dd", "div", "dl", "dt", "figcaption",
+    "figure", "footer", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "li", "main", "nav",
+    "ol", "p", "pre", "section", "table", "td", "th", "tr", "ul",
+];
+
+// Estimated seconds a student spends per question, by type
+const SECONDS_PER_QUESTION: &[(&str, u64)] = &[
+    ("multiple_choice", 45),
+    ("true_false", 20),
+    ("fill_blank", 45),
+    ("matching", 30),
+    ("numeric", 60),
+    ("short_answer", 90),
+    ("word_problem", 120),
+    ("drawing", 180),
+];
+const DEFAULT_SECONDS_PER_QUESTION: u64 = 60;
+
+// Young readers' reading speed, for time spent on instructions and passages
+const WORDS_PER_MINUTE: u64 = 100;
+
+// Page-count estimate: printed lines per page and the space things take
+const LINES_PER_PAGE: u64 = 45;
+const WORDS_PER_LINE: u64 = 12;
+const LINES_PER_QUESTION: u64 = 3;
+const LINES_PER_IMAGE: u64 = 10;
+
+// Thresholds for "this worksheet is long" warnings
+const LONG_MINUTES: u64 = 30;
+const LONG_PAGES: u64 = 4;
+const LONG_QUESTIONS: usize = 30;
+const LARGE_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// A start or end tag found while scanning HTML
+pub(crate) struct Tag {
+    pub(crate) name: String,
+    attrs: Vec<(String, String)>,
+    pub(crate) closing: bool,
+    pub(crate) self_closing: bool,
+}
+
+impl Tag {
+    pub(crate) fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    pub(crate) fn classes(&self) -> impl Iterator<Item = &str> {
+        self.attr("class").unwrap_or("").split_whitespace()
+    }
+}
+
+/// Scanned pieces of an HTML document. Text is kept as its byte range in
+/// the source so it can be rewritten in place.
+pub(crate) enum Token {
+    Tag(Tag),
+    Text(Range<usize>),
+}
+
+// Helper to parse a tag's attributes (`name="v"`, `name='v'`, `name=v`, `name`)
+fn parse_attrs(source: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut chars = source.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == '/').is_some() {}
+        let mut name = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !matches!(c, '=' | '/')) {
+            name.push(c.to_ascii_lowercase());
+        }
+        if name.is_empty() {
+            return attrs;
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            match chars.next_if(|c| matches!(c, '"' | '\'')) {
+                Some(quote) => {
+                    for c in chars.by_ref() {
+                        if c == quote {
+                            break;
+                        }
+                        value.push(c);
+                    }
+                }
+                None => {
+                    while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                        value.push(c);
+                    }
+                }
+            }
+        }
+        attrs.push((name, value));
+    }
+}
+
+/// Split HTML into tags and text. Comments, doctypes, and the contents of
+/// <script> and <style> are skipped.
+pub(crate) fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let offset = html.len() - rest.len();
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(offset..html.len()));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(offset..offset + start));
+        }
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let inner = &rest[1..end];
+        rest = &rest[end + 1..];
+        if inner.starts_with(['!', '?']) {
+            continue;
+        }
+
+        let closing = inner.starts_with('/');
+        let body = inner.trim_start_matches('/');
+        let name_end = body
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(body.len());
+        let name = body[..name_end].to_ascii_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+        let tag = Tag {
+            attrs: parse_attrs(&body[name_end..]),
+            self_closing: inner.ends_with('/') || VOID_ELEMENTS.contains(&name.as_str()),
+            closing,
+            name,
+        };
+
+        // Skip raw text elements entirely
+        if !tag.closing && (tag.name == "script" || tag.name == "style") {
+            let close = format!("</{}", tag.name);
+            let lower = rest.to_ascii_lowercase();
+            rest = match lower.find(&close) {
+                Some(i) => rest[i..].find('>').map_or("", |e| &rest[i + e + 1..]),
+                None => "",
+            };
+            continue;
+        }
+        tokens.push(Token::Tag(tag));
+    }
+    tokens
+}
+
+/// Decode the few entities that appear in generated text
+pub(crate) fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#039;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Byte ranges of the non-blank visible text runs in an HTML document
+pub(crate) fn text_spans(html: &str) -> Vec<Range<usize>> {
+    tokenize(html)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Text(span) => Some(span),
+            Token::Tag(_) => None,
+        })
+        .filter(|span| !html[span.clone()].trim().is_empty())
+        .collect()
+}
+
+/// The visible text of an HTML document, one entry per non-blank text run
+pub(crate) fn text_runs(html: &str) -> Vec<String> {
+    text_spans(html)
+        .into_iter()
+        .map(|span| decode_entities(&html[span]))
+        .collect()
+}
+
+/// The visible text of an HTML document as paragraphs: inline runs are
+/// joined, whitespace collapsed, and a new paragraph starts at each block
+/// element or `<br>`. Images become "Picture: <alt text>" paragraphs; the
+/// `<head>` is skipped.
+pub(crate) fn text_blocks(html: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current = String::new();
+    let mut in_head = false;
+    let flush = |current: &mut String, blocks: &mut Vec<String>| {
+        let text = current.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            blocks.push(text);
+        }
+        current.clear();
+    };
+    for token in tokenize(html) {
+        match token {
+            Token::Text(span) if !in_head => current.push_str(&decode_entities(&html[span])),
+            Token::Text(_) => {}
+            Token::Tag(tag) if tag.name == "head" => in_head = !tag.closing,
+            Token::Tag(tag) if tag.name == "img" && !in_head => {
+                flush(&mut current, &mut blocks);
+                if let Some(alt) = tag.attr("alt").filter(|alt| !alt.trim().is_empty()) {
+                    blocks.push(format!("Picture: {}", decode_entities(alt.trim())));
+                }
+            }
+            Token::Tag(tag) if BLOCK_ELEMENTS.contains(&tag.name.as_str()) => {
+                flush(&mut current, &mut blocks);
+            }
+            // Keep words in neighbouring cells and inline elements apart
+            Token::Tag(_) => current.push(' '),
+        }
+    }
+    flush(&mut current, &mut blocks);
+    blocks
+}
+
+// Helper to classify a question from its own attributes and the classes of
+// the elements inside it. Generated worksheets mark questions with
+// `class="question"`; assembled ones also carry `data-question-type`.
+fn question_type(explicit: Option<&str>, markers: &BTreeSet<String>) -> String {
+    if let Some(explicit) = explicit.filter(|t| !t.is_empty()) {
+        return explicit.to_string();
+    }
+    let has = |class: &str| markers.contains(class);
+    let question_type = if has("matching-item") {
+        "matching"
+    } else if has("true-false-options") {
+        "true_false"
+    } else if has("options") || has("choices") {
+        "multiple_choice"
+    } else if has("drawing-box") {
+        "drawing"
+    } else if has("answer-line") {
+        "fill_blank"
+    } else {
+        "short_answer"
+    };
+    question_type.to_string()
+}
+
+// Helper to estimate the size of an image source in bytes, or `None` when
+// it lives somewhere the backend can't measure
+fn image_bytes(app_handle: &tauri::AppHandle, src: &str) -> Option<u64> {
+    if let Some(data) = src.strip_prefix("data:") {
+        let (meta, payload) = data.split_once(',')?;
+        return Some(if meta.ends_with(";base64") {
+            let padding = payload.bytes().rev().take_while(|b| *b == b'=').count();
+            (payload.trim().len() * 3 / 4).saturating_sub(padding) as u64
+        } else {
+            payload.len() as u64
+        });
+    }
+
+    // pack-asset://localhost/<packId>/<name> or http://pack-asset.localhost/...
+    let path = src
+        .strip_prefix("pack-asset://localhost/")
+        .or_else(|| src.strip_prefix("http://pack-asset.localhost/"))?;
+    let decoded = percent_encoding::percent_decode_str(path).decode_utf8().ok()?;
+    let (pack_id, name) = decoded.split_once('/')?;
+    if name.split('/').any(|segment| segment.is_empty() || segment == "..") {
+        return None;
+    }
+    let assets_dir = design_pack_storage::get_assets_dir(app_handle, pack_id).ok()?;
+    std::fs::metadata(assets_dir.join(name)).ok().map(|m| m.len())
+}
+
+/// Analyze artifact HTML: question count by type, word count, estimated
+/// completion time, images (count and size), and an estimated page count,
+/// plus `warnings` for worksheets that run long
+pub(crate) fn analyze_html(app_handle: &tauri::AppHandle, html: &str) -> Value {
+    let mut word_count: u64 = 0;
+    let mut questions: BTreeMap<String, usize> = BTreeMap::new();
+    let mut question_count = 0;
+    let mut image_count = 0;
+    let mut image_total_bytes: u64 = 0;
+    let mut external_images = 0;
+    let mut page_breaks: u64 = 0;
+
+    // Open elements; the question being read is tracked by its stack depth
+    let mut stack: Vec<String> = Vec::new();
+    let mut current: Option<(usize, Option<String>, BTreeSet<String>)> = None;
+
+    for token in tokenize(html) {
+        let tag = match token {
+            Token::Text(span) => {
+                let text = decode_entities(&html[span]);
+                word_count += text
+                    .split_whitespace()
+                    .filter(|w| w.chars().any(|c| c.is_alphanumeric()))
+                    .count() as u64;
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+
+        if tag.closing {
+            if let Some(depth) = stack.iter().rposition(|name| *name == tag.name) {
+                stack.truncate(depth);
+                if let Some((_, explicit, markers)) = current.take_if(|(d, ..)| *d >= depth) {
+                    *questions
+                        .entry(question_type(explicit.as_deref(), &markers))
+                        .or_default() += 1;
+                    question_count += 1;
+                }
+            }
+            continue;
+        }
+
+        let style = tag.attr("style").unwrap_or("").to_ascii_lowercase();
+        if style.contains("page-break-before") || style.contains("break-before: page") {
+            page_breaks += 1;
+        }
+
+        if tag.name == "img" {
+            image_count += 1;
+            match tag.attr("src").and_then(|src| image_bytes(app_handle, src)) {
+                Some(bytes) => image_total_bytes += bytes,
+                None => external_images += 1,
+            }
+        }
+
+        match current.as_mut() {
+            Some((_, _, markers)) => markers.extend(tag.classes().map(String::from)),
+            None if tag.classes().any(|c| c == "question") => {
+                let explicit = tag.attr("data-question-type").map(String::from);
+                let markers = tag.classes().map(String::from).collect();
+                current = Some((stack.len(), explicit, markers));
+            }
+            None => {}
+        }
+
+        if !tag.self_closing {
+            stack.push(tag.name);
+        }
+    }
+    // A question left open by malformed HTML still counts
+    if let Some((_, explicit, markers)) = current {
+        *questions.entry(question_type(explicit.as_deref(), &markers)).or_default() += 1;
+        question_count += 1;
+    }
+
+    let question_seconds: u64 = questions
+        .iter()
+        .map(|(question_type, count)| {
+            let seconds = SECONDS_PER_QUESTION
+                .iter()
+                .find(|(t, _)| t == question_type)
+                .map_or(DEFAULT_SECONDS_PER_QUESTION, |(_, s)| *s);
+            seconds * *count as u64
+        })
+        .sum();
+    let reading_seconds = word_count * 60 / WORDS_PER_MINUTE;
+    let estimated_minutes = (question_seconds + reading_seconds).div_ceil(60);
+
+    let lines = word_count.div_ceil(WORDS_PER_LINE)
+        + question_count as u64 * LINES_PER_QUESTION
+        + image_count as u64 * LINES_PER_IMAGE;
+    let estimated_pages = lines.div_ceil(LINES_PER_PAGE).max(page_breaks + 1);
+
+    let mut warnings = Vec::new();
+    if estimated_minutes > LONG_MINUTES {
+        warnings.push("long_completion_time");
+    }
+    if estimated_pages > LONG_PAGES {
+        warnings.push("many_pages");
+    }
+    if question_count > LONG_QUESTIONS {
+        warnings.push("many_questions");
+    }
+    if image_total_bytes > LARGE_IMAGE_BYTES {
+        warnings.push("large_images");
+    }
+
+    serde_json::json!({
+        "questions": {
+            "total": question_count,
+            "byType": questions,
+        },
+        "wordCount": word_count,
+        "estimatedMinutes": estimated_minutes,
+        "images": {
+            "count": image_count,
+            "totalBytes": image_total_bytes,
+            "unmeasured": external_images,
+        },
+        "estimatedPages": estimated_pages,
+        "warnings": warnings,
+    })
+}
+
+// ============================================
+// Artifact Analysis Commands
+// ============================================
+
+/// Analyze an artifact's HTML; see `analyze_html` for what's reported
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn analyze_artifact(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+) -> Result<String, String> {
+    let artifact = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    let html = artifact
+        .get("htmlContent")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+
+    let mut response = analyze_html(&app_handle, html);
+    if let Some(obj) = response.as_object_mut() {
+        obj.insert("artifactId".to_string(), Value::String(artifact_id));
+    }
+    Ok(response.to_string())
+}
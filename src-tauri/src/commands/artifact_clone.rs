@@ -0,0 +1,103 @@
+use serde_json::Value;
+
+use crate::commands::{
+    app_lock, audit_log, design_pack_storage, learner_bundle, library_storage, project_storage,
+    session_mode,
+};
+
+/// Per-copy fields that start fresh on a clone
+pub(crate) const RESET_KEYS: &[&str] = &[
+    "favorite",
+    "rating",
+    "openCount",
+    "printCount",
+    "lastOpenedAt",
+    "lastPrintedAt",
+    "updatedAt",
+];
+
+// ============================================
+// Artifact Clone Commands
+// ============================================
+
+/// Copy an artifact under a fresh ID. `overrides` may set `title`,
+/// `designPackId`, `learnerId` (null clears it), and `projectId`. The copy
+/// records its source in `clonedFrom` and is added to its project, if any.
+/// Returns the new artifact.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn clone_artifact(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    overrides: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let overrides: Value = match overrides {
+        Some(overrides) => serde_json::from_str(&overrides)
+            .map_err(|e| format!("Invalid overrides JSON: {}", e))?,
+        None => Value::Object(serde_json::Map::new()),
+    };
+
+    let mut clone = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    let obj = clone.as_object_mut().ok_or("Invalid artifact")?;
+
+    if let Some(title) = overrides.get("title") {
+        let title = title.as_str().filter(|t| !t.trim().is_empty());
+        let title = title.ok_or("Title cannot be empty")?;
+        obj.insert("title".to_string(), Value::String(title.to_string()));
+    }
+    if let Some(pack_id) = overrides.get("designPackId").and_then(|v| v.as_str()) {
+        design_pack_storage::find_pack(&app_handle, pack_id).await?;
+        obj.insert("designPackId".to_string(), Value::String(pack_id.to_string()));
+    }
+    match overrides.get("learnerId") {
+        Some(Value::String(learner_id)) => {
+            learner_bundle::read_learner_profile(&app_handle, learner_id).await?;
+            obj.insert("learnerId".to_string(), Value::String(learner_id.clone()));
+        }
+        Some(Value::Null) => {
+            obj.insert("learnerId".to_string(), Value::Null);
+        }
+        _ => {}
+    }
+    if let Some(project_id) = overrides.get("projectId") {
+        if let Some(project_id) = project_id.as_str() {
+            project_storage::read_project(&app_handle, project_id).await?;
+        }
+        obj.insert("projectId".to_string(), project_id.clone());
+    }
+
+    let clone_id = uuid::Uuid::new_v4().to_string();
+    for key in RESET_KEYS {
+        obj.remove(*key);
+    }
+    obj.insert("artifactId".to_string(), Value::String(clone_id.clone()));
+    obj.insert("clonedFrom".to_string(), Value::String(artifact_id.clone()));
+    obj.insert(
+        "createdAt".to_string(),
+        Value::String(chrono::Utc::now().to_rfc3339()),
+    );
+
+    let content = serde_json::to_string_pretty(&clone)
+        .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+    library_storage::write_artifact(&app_handle, &clone, &content).await?;
+
+    // Keep the copy alongside its project, unless that project is gone
+    if let Some(project_id) = clone.get("projectId").and_then(|v| v.as_str()) {
+        if let Err(e) = project_storage::link_artifact(&app_handle, project_id, &clone_id).await {
+            tracing::warn!(error = %e, "Failed to add cloned artifact to project");
+        }
+    }
+
+    audit_log::record(
+        &app_handle,
+        "clone_artifact",
+        "artifact",
+        &[&clone_id, &artifact_id],
+    )
+    .await;
+
+    Ok(content)
+}
@@ -0,0 +1,107 @@
+use serde_json::{Map, Value};
+
+use crate::commands::{app_lock, audit_log, library_storage, session_mode};
+
+// Kinds of use `record_artifact_use` counts: (kind, counter, timestamp)
+const USE_KINDS: &[(&str, &str, &str)] = &[
+    ("open", "openCount", "lastOpenedAt"),
+    ("print", "printCount", "lastPrintedAt"),
+];
+
+// Helper to apply a change to an artifact and write it back (file and index)
+async fn update_artifact(
+    app_handle: &tauri::AppHandle,
+    artifact_id: &str,
+    change: impl FnOnce(&mut Map<String, Value>),
+) -> Result<Value, String> {
+    let mut artifact = library_storage::read_artifact(app_handle, artifact_id).await?;
+    change(artifact.as_object_mut().ok_or("Invalid artifact")?);
+    let content = serde_json::to_string_pretty(&artifact)
+        .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+    library_storage::write_artifact(app_handle, &artifact, &content).await?;
+    Ok(library_storage::index_entry_for(&artifact))
+}
+
+// ============================================
+// Artifact Usage Commands
+// ============================================
+
+/// Mark or unmark an artifact as a favorite. Returns the updated index entry.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn set_artifact_favorite(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    favorite: bool,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let entry = update_artifact(&app_handle, &artifact_id, |obj| {
+        obj.insert("favorite".to_string(), Value::Bool(favorite));
+    })
+    .await?;
+
+    audit_log::record(&app_handle, "set_artifact_favorite", "artifact", &[&artifact_id]).await;
+
+    Ok(entry.to_string())
+}
+
+/// Rate an artifact from 1 to 5 stars, or clear its rating with `None`.
+/// Returns the updated index entry.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn set_artifact_rating(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    rating: Option<u8>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    if rating.is_some_and(|r| !(1..=5).contains(&r)) {
+        return Err("Rating must be between 1 and 5".to_string());
+    }
+
+    let entry = update_artifact(&app_handle, &artifact_id, |obj| match rating {
+        Some(rating) => {
+            obj.insert("rating".to_string(), Value::from(rating));
+        }
+        None => {
+            obj.remove("rating");
+        }
+    })
+    .await?;
+
+    audit_log::record(&app_handle, "set_artifact_rating", "artifact", &[&artifact_id]).await;
+
+    Ok(entry.to_string())
+}
+
+/// Count an open or print of an artifact (`kind` is "open" or "print") so
+/// frequently used worksheets can be sorted to the top. Returns the updated
+/// index entry.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn record_artifact_use(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    kind: String,
+) -> Result<String, String> {
+    let (_, counter, timestamp) = USE_KINDS
+        .iter()
+        .find(|(k, ..)| *k == kind)
+        .ok_or_else(|| format!("Invalid usage kind: {}", kind))?;
+
+    let entry = update_artifact(&app_handle, &artifact_id, |obj| {
+        let count = obj.get(*counter).and_then(|v| v.as_u64()).unwrap_or(0);
+        obj.insert(counter.to_string(), Value::from(count + 1));
+        obj.insert(
+            timestamp.to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        );
+    })
+    .await?;
+
+    Ok(entry.to_string())
+}
@@ -0,0 +1,216 @@
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde_json::Value;
+use std::path::PathBuf;
+use tauri::Manager;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+use crate::commands::{app_lock, data_history, session_mode};
+
+const AUDIT_DIR: &str = "audit";
+const AUDIT_FILE: &str = "audit-log.jsonl";
+
+const DEFAULT_LIMIT: usize = 500;
+
+// Helper to get the audit log file path
+fn get_audit_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(AUDIT_DIR).join(AUDIT_FILE))
+}
+
+// Helper to append one JSON line to the audit log
+async fn append_entry(app_handle: &tauri::AppHandle, entry: &Value) -> Result<(), String> {
+    let audit_path = get_audit_path(app_handle)?;
+    if let Some(parent) = audit_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create audit directory: {}", e))?;
+    }
+
+    let mut line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&audit_path)
+        .await
+        .map_err(|e| format!("Failed to open audit log: {}", e))?;
+    file.write_all(line.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write audit log: {}", e))
+}
+
+/// Read every audit log entry, oldest first, skipping unreadable lines
+pub(crate) async fn read_entries(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let audit_path = get_audit_path(app_handle)?;
+    if !audit_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&audit_path)
+        .await
+        .map_err(|e| format!("Failed to read audit log: {}", e))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .collect())
+}
+
+/// Replace the whole audit log. Only used to erase a deleted learner's ID;
+/// everything else appends through `record`.
+pub(crate) async fn write_entries(
+    app_handle: &tauri::AppHandle,
+    entries: &[Value],
+) -> Result<(), String> {
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(
+            &serde_json::to_string(entry)
+                .map_err(|e| format!("Failed to serialize audit entry: {}", e))?,
+        );
+        content.push('\n');
+    }
+    fs::write(get_audit_path(app_handle)?, content)
+        .await
+        .map_err(|e| format!("Failed to write audit log: {}", e))
+}
+
+/// Record a storage mutation in the append-only audit log.
+///
+/// Called after the mutation succeeded. Logging is best-effort: a failure
+/// here is reported but never turns a completed mutation into an error.
+/// The change is also noted for the data history, when enabled.
+pub(crate) async fn record(
+    app_handle: &tauri::AppHandle,
+    command: &str,
+    entity_type: &str,
+    entity_ids: &[&str],
+) {
+    let actor = match session_mode::active_learner_id(app_handle) {
+        Some(learner_id) => format!("learner:{}", learner_id),
+        None => "teacher".to_string(),
+    };
+    let entry = serde_json::json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "actor": actor,
+        "command": command,
+        "entityType": entity_type,
+        "entityIds": entity_ids,
+    });
+
+    if let Err(e) = append_entry(app_handle, &entry).await {
+        tracing::warn!(command, error = %e, "Failed to write audit log entry");
+    }
+    data_history::note_change(app_handle, command, entity_type, entity_ids).await;
+}
+
+// Helper to parse a `from`/`to` bound: an RFC 3339 timestamp, or a plain
+// `YYYY-MM-DD` date taken as the start (or, for `to`, the end) of that day
+// in local time
+fn parse_bound(value: &str, end_of_day: bool) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date or timestamp: {}", value))?;
+    let time = if end_of_day {
+        date.and_hms_nano_opt(23, 59, 59, 999_999_999)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    }
+    .ok_or_else(|| format!("Invalid date: {}", value))?;
+    Ok(time
+        .and_local_timezone(Local)
+        .earliest()
+        .map_or_else(|| time.and_utc(), |local| local.with_timezone(&Utc)))
+}
+
+// ============================================
+// Audit Log Commands
+// ============================================
+
+/// Get audit log entries, newest first.
+///
+/// The query may filter by `from`/`to` (RFC 3339 timestamps, or
+/// `YYYY-MM-DD` dates covering the whole day), `entityType`, `entityId`, and
+/// `command`, and cap the result with `limit`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_audit_log(app_handle: tauri::AppHandle, query: String) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let audit_path = get_audit_path(&app_handle)?;
+
+    if !audit_path.exists() {
+        return Ok("[]".to_string());
+    }
+
+    // Parse query
+    let query_value: Value =
+        serde_json::from_str(&query).map_err(|e| format!("Invalid query JSON: {}", e))?;
+    let from = query_value.get("from").and_then(|v| v.as_str());
+    let from = from.map(|from| parse_bound(from, false)).transpose()?;
+    let to = query_value.get("to").and_then(|v| v.as_str());
+    let to = to.map(|to| parse_bound(to, true)).transpose()?;
+    let entity_type = query_value.get("entityType").and_then(|v| v.as_str());
+    let entity_id = query_value.get("entityId").and_then(|v| v.as_str());
+    let command = query_value.get("command").and_then(|v| v.as_str());
+    let limit = query_value
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map(|l| l as usize)
+        .unwrap_or(DEFAULT_LIMIT);
+
+    let content = fs::read_to_string(&audit_path)
+        .await
+        .map_err(|e| format!("Failed to read audit log: {}", e))?;
+
+    let entries: Vec<Value> = content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|entry| {
+            if from.is_some() || to.is_some() {
+                let timestamp = entry
+                    .get("timestamp")
+                    .and_then(|v| v.as_str())
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+                let Some(timestamp) = timestamp else {
+                    return false;
+                };
+                if from.is_some_and(|from| timestamp < from) {
+                    return false;
+                }
+                if to.is_some_and(|to| timestamp > to) {
+                    return false;
+                }
+            }
+            if entity_type.is_some()
+                && entry.get("entityType").and_then(|v| v.as_str()) != entity_type
+            {
+                return false;
+            }
+            if command.is_some() && entry.get("command").and_then(|v| v.as_str()) != command {
+                return false;
+            }
+            if let Some(entity_id) = entity_id {
+                let matches = entry
+                    .get("entityIds")
+                    .and_then(|v| v.as_array())
+                    .is_some_and(|ids| ids.iter().any(|id| id.as_str() == Some(entity_id)));
+                if !matches {
+                    return false;
+                }
+            }
+            true
+        })
+        .take(limit)
+        .collect();
+
+    serde_json::to_string(&entries).map_err(|e| format!("Failed to serialize audit log: {}", e))
+}
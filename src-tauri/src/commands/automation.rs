@@ -0,0 +1,522 @@
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Engine, EvalAltResult, ImmutableString, Scope};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{
+    app_lock, audit_log, library_storage, project_storage, session_mode, share_export,
+    task_manager, tray,
+};
+
+const AUTOMATION_DIR: &str = "automation";
+const SCRIPTS_FILE: &str = "scripts.json";
+const RUNS_FILE: &str = "runs.json";
+const LAST_NIGHTLY_FILE: &str = "last-nightly.json";
+
+// Hooks scripts can run on
+const ARTIFACT_SAVED: &str = "artifact_saved";
+const QUICK_CHECK_GRADED: &str = "quick_check_graded";
+const NIGHTLY: &str = "nightly";
+const HOOKS: &[&str] = &[ARTIFACT_SAVED, QUICK_CHECK_GRADED, NIGHTLY];
+
+// Limits on a script run, so a script that loops forever or builds huge
+// values is stopped instead of hanging the app or filling its memory
+const MAX_OPERATIONS: u64 = 100_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_STRING_SIZE: usize = 64 * 1024;
+const MAX_COLLECTION_SIZE: usize = 10_000;
+
+// Most actions one run may ask for
+const MAX_ACTIONS: usize = 20;
+
+// Local hour from which the nightly hook runs; it runs once a day, on the
+// first check after this hour
+const NIGHTLY_HOUR: u32 = 2;
+
+// How often the scheduler checks whether the nightly hook is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+// Oldest runs are dropped beyond this many
+const MAX_RUNS: usize = 500;
+
+const DEFAULT_LIMIT: usize = 100;
+
+/// Managed state keeping scripts from recording their runs at the same time
+#[derive(Default)]
+pub struct AutomationState {
+    lock: tokio::sync::Mutex<()>,
+}
+
+/// Something a script asked for, done once the script has finished
+enum Action {
+    ExportArtifact(PathBuf),
+    Notify(String, String),
+    ShareExport,
+}
+
+// Helper to read a string field, or "" when missing
+fn text<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+// Helper to get a file in the automation directory
+fn get_automation_path(app_handle: &tauri::AppHandle, file: &str) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(AUTOMATION_DIR).join(file))
+}
+
+// Helper to read a JSON list from the automation directory (empty if the
+// file doesn't exist)
+async fn read_list(app_handle: &tauri::AppHandle, file: &str) -> Result<Vec<Value>, String> {
+    let path = get_automation_path(app_handle, file)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", file, e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
+// Helper to write a JSON value to the automation directory
+async fn write_json(
+    app_handle: &tauri::AppHandle,
+    file: &str,
+    value: &Value,
+) -> Result<(), String> {
+    let path = get_automation_path(app_handle, file)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create automation directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", file, e))?;
+    fs::write(&path, content)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", file, e))
+}
+
+// Helper to queue an action a script asked for
+fn queue(actions: &Mutex<Vec<Action>>, action: Action) -> Result<(), Box<EvalAltResult>> {
+    let mut actions = actions.lock().unwrap();
+    if actions.len() >= MAX_ACTIONS {
+        return Err(format!("A script can ask for at most {} actions", MAX_ACTIONS).into());
+    }
+    actions.push(action);
+    Ok(())
+}
+
+// Helper to make the engine a script on `hook` runs in, with the run
+// limits and the action functions queueing into `actions`. Scripts get no
+// `import` (which would load modules from disk) or `eval`, so they can't
+// reach files or anything beyond the actions; `print` and `debug` go to
+// the log.
+fn new_engine(hook: &str, actions: Arc<Mutex<Vec<Action>>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(DummyModuleResolver::new());
+    engine.disable_symbol("eval");
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    engine.set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH);
+    engine.set_max_string_size(MAX_STRING_SIZE);
+    engine.set_max_array_size(MAX_COLLECTION_SIZE);
+    engine.set_max_map_size(MAX_COLLECTION_SIZE);
+    engine.on_print(|text| tracing::info!(target: "automation", "{}", text));
+    engine.on_debug(|text, _, _| tracing::debug!(target: "automation", "{}", text));
+
+    let queued = actions.clone();
+    let artifact_saved = hook == ARTIFACT_SAVED;
+    engine.register_fn(
+        "export_artifact",
+        move |folder: ImmutableString| -> Result<(), Box<EvalAltResult>> {
+            if !artifact_saved {
+                return Err("export_artifact only runs on the artifact_saved hook".into());
+            }
+            let folder = PathBuf::from(folder.as_str());
+            if !folder.is_absolute() {
+                return Err(format!("export_artifact needs an absolute folder: {}", folder.display())
+                    .into());
+            }
+            queue(&queued, Action::ExportArtifact(folder))
+        },
+    );
+    let queued = actions.clone();
+    engine.register_fn("notify", move |title: ImmutableString, body: ImmutableString| {
+        queue(&queued, Action::Notify(title.to_string(), body.to_string()))
+    });
+    engine.register_fn("share_export", move || queue(&actions, Action::ShareExport));
+    engine
+}
+
+// Helper to check a script before saving it
+fn validate_script(script: &Value) -> Result<(), String> {
+    if text(script, "name").trim().is_empty() {
+        return Err("Script must have a name".to_string());
+    }
+    let hook = text(script, "hook");
+    if !HOOKS.contains(&hook) {
+        return Err(format!("Unknown hook: {}", hook));
+    }
+    let source = text(script, "source");
+    if source.trim().is_empty() {
+        return Err("Script must have source code".to_string());
+    }
+    new_engine(hook, Arc::default())
+        .compile(source)
+        .map_err(|e| format!("Script doesn't compile: {}", e))?;
+    Ok(())
+}
+
+// Helper to run a script's source with `event` in scope, returning the
+// actions it asked for
+fn evaluate(hook: &str, source: &str, event: &Value) -> Result<Vec<Action>, String> {
+    let actions = Arc::new(Mutex::new(Vec::new()));
+    let engine = new_engine(hook, actions.clone());
+    let event = rhai::serde::to_dynamic(event)
+        .map_err(|e| format!("Failed to pass the event to the script: {}", e))?;
+    let mut scope = Scope::new();
+    scope.push_constant("event", event);
+    engine
+        .run_with_scope(&mut scope, source)
+        .map_err(|e| format!("Script failed: {}", e))?;
+    let queued = std::mem::take(&mut *actions.lock().unwrap());
+    Ok(queued)
+}
+
+// Helper to run one action for an event
+async fn run_action(
+    app_handle: &tauri::AppHandle,
+    action: &Action,
+    event: &Value,
+) -> Result<(), String> {
+    match action {
+        Action::ExportArtifact(folder) => {
+            let artifact_id = text(event, "artifactId");
+            let artifact = library_storage::read_artifact(app_handle, artifact_id).await?;
+            fs::create_dir_all(folder)
+                .await
+                .map_err(|e| format!("Failed to create {}: {}", folder.display(), e))?;
+            let short_id: String = artifact_id.chars().take(8).collect();
+            let title = share_export::file_name(text(&artifact, "title"), "Untitled");
+            let path = folder.join(format!("{} ({}).html", title, short_id));
+            share_export::write_file(&path, text(&artifact, "htmlContent").as_bytes()).await
+        }
+        Action::Notify(title, body) => {
+            tray::notify_in_background(app_handle, title, body).await;
+            Ok(())
+        }
+        Action::ShareExport => {
+            let task =
+                task_manager::start_task(app_handle, None, "export", "Automation share export")?;
+            let result = share_export::run_export(app_handle, &task).await;
+            task.finish(&result);
+            result.map(|_| ())
+        }
+    }
+}
+
+// Helper to run a script for an event, then the actions it asked for in
+// order, stopping at the first failure, and record the run. The script
+// runs off the async runtime since it can take up to `MAX_OPERATIONS`.
+async fn run_script(app_handle: &tauri::AppHandle, script: &Value, event: &Value) -> Value {
+    let hook = text(script, "hook").to_string();
+    let source = text(script, "source").to_string();
+    let script_event = event.clone();
+    let evaluated =
+        tauri::async_runtime::spawn_blocking(move || evaluate(&hook, &source, &script_event))
+            .await
+            .map_err(|e| format!("Script failed: {}", e))
+            .and_then(|result| result);
+
+    let mut error = None;
+    let mut done = 0;
+    match evaluated {
+        Ok(actions) => {
+            for action in &actions {
+                if let Err(e) = run_action(app_handle, action, event).await {
+                    error = Some(e);
+                    break;
+                }
+                done += 1;
+            }
+        }
+        Err(e) => error = Some(e),
+    }
+    let run = serde_json::json!({
+        "runId": uuid::Uuid::new_v4().to_string(),
+        "scriptId": script.get("scriptId"),
+        "hook": script.get("hook"),
+        "ok": error.is_none(),
+        "error": error,
+        "actions": done,
+        "ranAt": chrono::Utc::now().to_rfc3339(),
+    });
+
+    let state = app_handle.state::<AutomationState>();
+    let _guard = state.lock.lock().await;
+    let recorded = async {
+        let mut runs = read_list(app_handle, RUNS_FILE).await?;
+        runs.push(run.clone());
+        if runs.len() > MAX_RUNS {
+            runs.drain(..runs.len() - MAX_RUNS);
+        }
+        write_json(app_handle, RUNS_FILE, &Value::Array(runs)).await
+    };
+    if let Err(e) = recorded.await {
+        tracing::warn!(error = %e, "Failed to record automation run");
+    }
+    run
+}
+
+// Helper to run every enabled script on `hook`.
+// Hooks run in the background, so a slow script (e.g. one writing to a
+// network share) doesn't hold up what fired it.
+async fn run_hook(app_handle: &tauri::AppHandle, hook: &str, event: &Value) -> Result<(), String> {
+    let scripts = read_list(app_handle, SCRIPTS_FILE).await?;
+    for script in &scripts {
+        let enabled = script.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true);
+        if enabled && text(script, "hook") == hook {
+            let run = run_script(app_handle, script, event).await;
+            if let Some(e) = run.get("error").and_then(|v| v.as_str()) {
+                tracing::warn!(script = %text(script, "name"), error = %e, "Automation failed");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run the scripts hooked to a saved artifact. The event has the
+/// artifact's `artifactId`, `projectId`, `title`, `type`, `grade`, and
+/// `subject`, plus the `learnerId` of its project, if any.
+pub(crate) fn artifact_saved(app_handle: &tauri::AppHandle, artifact: &Value) {
+    let app_handle = app_handle.clone();
+    let mut event = serde_json::json!({
+        "artifactId": artifact.get("artifactId"),
+        "projectId": artifact.get("projectId"),
+        "learnerId": null,
+        "title": artifact.get("title"),
+        "type": artifact.get("type"),
+        "grade": artifact.get("grade"),
+        "subject": artifact.get("subject"),
+    });
+    tauri::async_runtime::spawn(async move {
+        let project_id = text(&event, "projectId").to_string();
+        if let Ok(project) = project_storage::read_project(&app_handle, &project_id).await {
+            event["learnerId"] = project.get("learnerId").cloned().unwrap_or(Value::Null);
+        }
+        if let Err(e) = run_hook(&app_handle, ARTIFACT_SAVED, &event).await {
+            tracing::warn!(hook = ARTIFACT_SAVED, error = %e, "Failed to run automation scripts");
+        }
+    });
+}
+
+/// Run the scripts hooked to a graded quick check. The event has the
+/// `learnerId`, and the result's `resultId`, `objectiveId`, and `score`.
+pub(crate) fn quick_check_graded(app_handle: &tauri::AppHandle, learner_id: &str, result: &Value) {
+    let event = serde_json::json!({
+        "learnerId": learner_id,
+        "resultId": result.get("resultId"),
+        "objectiveId": result.get("objectiveId"),
+        "score": result.get("score"),
+    });
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = run_hook(&app_handle, QUICK_CHECK_GRADED, &event).await {
+            tracing::warn!(
+                hook = QUICK_CHECK_GRADED,
+                error = %e,
+                "Failed to run automation scripts"
+            );
+        }
+    });
+}
+
+// Helper to run the nightly hook if it hasn't run today and it's past
+// `NIGHTLY_HOUR`. The event has today's `date`.
+async fn run_nightly_if_due(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let now = chrono::Local::now();
+    if chrono::Timelike::hour(&now) < NIGHTLY_HOUR {
+        return Ok(());
+    }
+    let today = now.date_naive().to_string();
+    let last_path = get_automation_path(app_handle, LAST_NIGHTLY_FILE)?;
+    let last: Value = match fs::read_to_string(&last_path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or(Value::Null),
+        Err(_) => Value::Null,
+    };
+    if text(&last, "date") == today {
+        return Ok(());
+    }
+
+    // Recorded first, so a script that fails isn't run again all day
+    write_json(app_handle, LAST_NIGHTLY_FILE, &serde_json::json!({ "date": today })).await?;
+    run_hook(app_handle, NIGHTLY, &serde_json::json!({ "date": today })).await
+}
+
+/// Start the background task that runs nightly scripts. Called from the
+/// app's setup.
+pub fn start_scheduler(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = run_nightly_if_due(&app_handle).await {
+                tracing::warn!(error = %e, "Failed to run nightly automation");
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// ============================================
+// Automation Commands
+// ============================================
+
+/// List automation scripts as saved by `save_automation_script`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_automation_scripts(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let scripts = read_list(&app_handle, SCRIPTS_FILE).await?;
+    serde_json::to_string(&scripts).map_err(|e| format!("Failed to serialize scripts: {}", e))
+}
+
+/// Save (create or update) an automation script: `{scriptId?, name, hook,
+/// enabled, source}`. `hook` is `artifact_saved`, `quick_check_graded`, or
+/// `nightly`, and `source` is a Rhai script run with the hook's event as
+/// the `event` map, e.g.
+/// `if event.score < 70 { notify("Quick check", "Score: " + event.score) }`.
+/// Scripts act through `export_artifact(folder)` (copies the saved
+/// artifact's HTML to an absolute folder; `artifact_saved` only),
+/// `notify(title, body)`, and `share_export()` (runs the network folder
+/// export); these run in order once the script finishes. Returns the
+/// script's ID, or an error if the source doesn't compile.
+///
+/// Scripts can't import modules, use `eval`, or touch files, and a run is
+/// stopped after `MAX_OPERATIONS` steps or when a string, array, or map
+/// grows past its limit.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn save_automation_script(
+    app_handle: tauri::AppHandle,
+    script: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut script: Value =
+        serde_json::from_str(&script).map_err(|e| format!("Invalid script JSON: {}", e))?;
+    validate_script(&script)?;
+    let script_id = match text(&script, "scriptId") {
+        "" => uuid::Uuid::new_v4().to_string(),
+        id => id.to_string(),
+    };
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let mut scripts = read_list(&app_handle, SCRIPTS_FILE).await?;
+    let existing = scripts.iter().position(|s| text(s, "scriptId") == script_id);
+    if let Some(obj) = script.as_object_mut() {
+        obj.insert("scriptId".to_string(), Value::String(script_id.clone()));
+        obj.entry("enabled").or_insert(Value::Bool(true));
+        let created_at = existing
+            .and_then(|i| scripts[i].get("createdAt").cloned())
+            .unwrap_or_else(|| Value::String(now.clone()));
+        obj.insert("createdAt".to_string(), created_at);
+        obj.insert("updatedAt".to_string(), Value::String(now));
+    }
+    match existing {
+        Some(index) => scripts[index] = script,
+        None => scripts.push(script),
+    }
+    write_json(&app_handle, SCRIPTS_FILE, &Value::Array(scripts)).await?;
+
+    audit_log::record(&app_handle, "save_automation_script", "automation", &[&script_id]).await;
+
+    Ok(script_id)
+}
+
+/// Delete an automation script
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_automation_script(
+    app_handle: tauri::AppHandle,
+    script_id: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut scripts = read_list(&app_handle, SCRIPTS_FILE).await?;
+    let count = scripts.len();
+    scripts.retain(|s| text(s, "scriptId") != script_id);
+    if scripts.len() == count {
+        return Err(format!("Script not found: {}", script_id));
+    }
+    write_json(&app_handle, SCRIPTS_FILE, &Value::Array(scripts)).await?;
+
+    audit_log::record(&app_handle, "delete_automation_script", "automation", &[&script_id]).await;
+
+    Ok(())
+}
+
+/// Run a script now with a sample `event` (JSON object), to try it out.
+/// It runs even when disabled. Returns the run as recorded.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn run_automation_script(
+    app_handle: tauri::AppHandle,
+    script_id: String,
+    event: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let event: Value = match event {
+        Some(event) => {
+            serde_json::from_str(&event).map_err(|e| format!("Invalid event JSON: {}", e))?
+        }
+        None => Value::Object(serde_json::Map::new()),
+    };
+    let scripts = read_list(&app_handle, SCRIPTS_FILE).await?;
+    let script = scripts
+        .iter()
+        .find(|s| text(s, "scriptId") == script_id)
+        .ok_or_else(|| format!("Script not found: {}", script_id))?;
+    let run = run_script(&app_handle, script, &event).await;
+
+    audit_log::record(&app_handle, "run_automation_script", "automation", &[&script_id]).await;
+
+    Ok(run.to_string())
+}
+
+/// Get recent script runs, newest first, as `[{runId, scriptId, hook, ok,
+/// error, actions, ranAt}]`, where `actions` counts the actions done.
+/// `script_id` limits them to one script; `limit` defaults to 100.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_automation_runs(
+    app_handle: tauri::AppHandle,
+    script_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let runs: Vec<Value> = read_list(&app_handle, RUNS_FILE)
+        .await?
+        .into_iter()
+        .rev()
+        .filter(|r| script_id.as_deref().is_none_or(|id| text(r, "scriptId") == id))
+        .take(limit.unwrap_or(DEFAULT_LIMIT))
+        .collect();
+    serde_json::to_string(&runs).map_err(|e| format!("Failed to serialize runs: {}", e))
+}
@@ -0,0 +1,50 @@
+use serde::Serialize;
+use tauri::ipc::Response;
+
+use crate::commands::{generation_history, library_storage};
+
+/// Encode a payload as MessagePack (maps keyed by field name) and send it as
+/// raw bytes, which the frontend receives as an `ArrayBuffer` without the
+/// JSON string round trip
+pub(crate) fn msgpack_response<T: Serialize + ?Sized>(value: &T) -> Result<Response, String> {
+    let bytes = rmp_serde::to_vec_named(value)
+        .map_err(|e| format!("Failed to encode MessagePack: {}", e))?;
+    Ok(Response::new(bytes))
+}
+
+// ============================================
+// Binary IPC Commands
+// ============================================
+
+/// Same as `get_artifact`, encoded as MessagePack for large artifacts
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_artifact_msgpack(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+) -> Result<Response, String> {
+    let artifact = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    msgpack_response(&artifact)
+}
+
+/// Same as `get_artifacts`, encoded as MessagePack
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_artifacts_msgpack(
+    app_handle: tauri::AppHandle,
+    artifact_ids: Vec<String>,
+) -> Result<Response, String> {
+    let artifacts = library_storage::read_artifacts(&app_handle, artifact_ids).await;
+    msgpack_response(&artifacts)
+}
+
+/// Same as `get_generation_history`, encoded as MessagePack
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_generation_history_msgpack(
+    app_handle: tauri::AppHandle,
+    query: Option<String>,
+) -> Result<Response, String> {
+    let entries = generation_history::query_history(&app_handle, query).await?;
+    msgpack_response(&entries)
+}
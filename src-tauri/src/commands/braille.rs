@@ -0,0 +1,549 @@
+use tokio::fs;
+
+use crate::commands::{app_lock, artifact_analysis, audit_log, library_storage, session_mode};
+
+// Standard embosser page: 40 cells by 25 lines. The first line of each page
+// holds the page number.
+const CELLS_PER_LINE: usize = 40;
+const LINES_PER_PAGE: usize = 25;
+
+// Paragraphs start in cell 3
+const PARAGRAPH_INDENT: &str = "  ";
+
+// Indicators, in North American ASCII braille
+const CAPITAL: &str = ",";
+const CAPITAL_WORD: &str = ",,";
+const NUMBER: &str = "#";
+const GRADE_1: &str = ";";
+
+// Fill-in blanks (runs of underscores) become a short line of dashes
+const BLANK: &str = "----";
+
+// Grade 2 signs used only for a whole word: the alphabetic and strong
+// wordsigns, and the shortforms
+const WORDSIGNS: &[(&str, &str)] = &[
+    ("but", "B"),
+    ("can", "C"),
+    ("do", "D"),
+    ("every", "E"),
+    ("from", "F"),
+    ("go", "G"),
+    ("have", "H"),
+    ("just", "J"),
+    ("knowledge", "K"),
+    ("like", "L"),
+    ("more", "M"),
+    ("not", "N"),
+    ("people", "P"),
+    ("quite", "Q"),
+    ("rather", "R"),
+    ("so", "S"),
+    ("that", "T"),
+    ("us", "U"),
+    ("very", "V"),
+    ("will", "W"),
+    ("it", "X"),
+    ("you", "Y"),
+    ("as", "Z"),
+    ("child", "*"),
+    ("shall", "%"),
+    ("this", "?"),
+    ("which", ":"),
+    ("out", "\\"),
+    ("still", "/"),
+    ("about", "AB"),
+    ("above", "ABV"),
+    ("according", "AC"),
+    ("across", "ACR"),
+    ("after", "AF"),
+    ("afternoon", "AFN"),
+    ("afterward", "AFW"),
+    ("again", "AG"),
+    ("against", "AG/"),
+    ("almost", "ALM"),
+    ("already", "ALR"),
+    ("also", "AL"),
+    ("although", "AL?"),
+    ("altogether", "ALT"),
+    ("always", "ALW"),
+    ("because", "2C"),
+    ("before", "2F"),
+    ("behind", "2H"),
+    ("below", "2L"),
+    ("beneath", "2N"),
+    ("beside", "2S"),
+    ("between", "2T"),
+    ("beyond", "2Y"),
+    ("blind", "BL"),
+    ("braille", "BRL"),
+    ("children", "*N"),
+    ("conceive", "3CV"),
+    ("conceiving", "3CVG"),
+    ("could", "CD"),
+    ("deceive", "DCV"),
+    ("deceiving", "DCVG"),
+    ("declare", "DCL"),
+    ("declaring", "DCLG"),
+    ("either", "EI"),
+    ("first", "F/"),
+    ("friend", "FR"),
+    ("good", "GD"),
+    ("great", "GRT"),
+    ("herself", "H]F"),
+    ("him", "HM"),
+    ("himself", "HMF"),
+    ("immediate", "IMM"),
+    ("its", "XS"),
+    ("itself", "XF"),
+    ("letter", "LR"),
+    ("little", "LL"),
+    ("much", "M*"),
+    ("must", "M/"),
+    ("myself", "MYF"),
+    ("necessary", "NEC"),
+    ("neither", "NEI"),
+    ("oneself", "\"OF"),
+    ("ourselves", "\\RVS"),
+    ("paid", "PD"),
+    ("perceive", "P]CV"),
+    ("perceiving", "P]CVG"),
+    ("perhaps", "P]H"),
+    ("quick", "QK"),
+    ("receive", "RCV"),
+    ("receiving", "RCVG"),
+    ("rejoice", "RJC"),
+    ("rejoicing", "RJCG"),
+    ("said", "SD"),
+    ("should", "%D"),
+    ("such", "S*"),
+    ("themselves", "!MVS"),
+    ("thyself", "?YF"),
+    ("today", "TD"),
+    ("together", "TGR"),
+    ("tomorrow", "TM"),
+    ("tonight", "TN"),
+    ("would", "WD"),
+    ("your", "YR"),
+    ("yourself", "YRF"),
+    ("yourselves", "YRVS"),
+];
+
+// Grade 2 lower wordsigns, used only for a word with a space (or the edge
+// of the paragraph) on both sides, since they'd be misread next to
+// punctuation
+const LOWER_WORDSIGNS: &[(&str, &str)] = &[
+    ("be", "2"),
+    ("enough", "5"),
+    ("his", "8"),
+    ("in", "9"),
+    ("was", "0"),
+    ("were", "7"),
+];
+
+// Where in a word a Grade 2 contraction may be used
+enum Place {
+    Anywhere,
+    // Not as the first letters of a word
+    NotFirst,
+    // Neither first nor last
+    Middle,
+    // Only as the first letters of a word
+    Start,
+}
+
+// Grade 2 contractions used within words: the strong contractions and
+// groupsigns, the lower groupsigns, and the initial-letter and final-letter
+// contractions. The longest that fits is used.
+const GROUPSIGNS: &[(&str, &str, Place)] = &[
+    ("and", "&", Place::Anywhere),
+    ("for", "=", Place::Anywhere),
+    ("of", "(", Place::Anywhere),
+    ("the", "!", Place::Anywhere),
+    ("with", ")", Place::Anywhere),
+    ("ch", "*", Place::Anywhere),
+    ("gh", "<", Place::Anywhere),
+    ("sh", "%", Place::Anywhere),
+    ("th", "?", Place::Anywhere),
+    ("wh", ":", Place::Anywhere),
+    ("ed", "$", Place::Anywhere),
+    ("er", "]", Place::Anywhere),
+    ("ou", "\\", Place::Anywhere),
+    ("ow", "[", Place::Anywhere),
+    ("st", "/", Place::Anywhere),
+    ("ar", ">", Place::Anywhere),
+    ("ing", "+", Place::NotFirst),
+    ("en", "5", Place::Anywhere),
+    ("in", "9", Place::Anywhere),
+    ("ea", "1", Place::Middle),
+    ("bb", "2", Place::Middle),
+    ("cc", "3", Place::Middle),
+    ("ff", "6", Place::Middle),
+    ("gg", "7", Place::Middle),
+    ("day", "\"D", Place::Start),
+    ("ever", "\"E", Place::Start),
+    ("father", "\"F", Place::Start),
+    ("here", "\"H", Place::Start),
+    ("know", "\"K", Place::Start),
+    ("lord", "\"L", Place::Start),
+    ("mother", "\"M", Place::Start),
+    ("name", "\"N", Place::Start),
+    ("one", "\"O", Place::Start),
+    ("part", "\"P", Place::Start),
+    ("question", "\"Q", Place::Start),
+    ("right", "\"R", Place::Start),
+    ("some", "\"S", Place::Start),
+    ("time", "\"T", Place::Start),
+    ("under", "\"U", Place::Start),
+    ("work", "\"W", Place::Start),
+    ("young", "\"Y", Place::Start),
+    ("there", "\"!", Place::Start),
+    ("character", "\"*", Place::Start),
+    ("through", "\"?", Place::Start),
+    ("where", "\":", Place::Start),
+    ("ought", "\"\\", Place::Start),
+    ("upon", "^U", Place::Start),
+    ("word", "^W", Place::Start),
+    ("these", "^!", Place::Start),
+    ("those", "^?", Place::Start),
+    ("whose", "^:", Place::Start),
+    ("cannot", "_C", Place::Start),
+    ("had", "_H", Place::Start),
+    ("many", "_M", Place::Start),
+    ("spirit", "_S", Place::Start),
+    ("their", "_!", Place::Start),
+    ("world", "_W", Place::Start),
+    ("ound", ".D", Place::NotFirst),
+    ("ance", ".E", Place::NotFirst),
+    ("sion", ".N", Place::NotFirst),
+    ("less", ".S", Place::NotFirst),
+    ("ount", ".T", Place::NotFirst),
+    ("ence", ";E", Place::NotFirst),
+    ("ong", ";G", Place::NotFirst),
+    ("ful", ";L", Place::NotFirst),
+    ("tion", ";N", Place::NotFirst),
+    ("ness", ";S", Place::NotFirst),
+    ("ment", ";T", Place::NotFirst),
+    ("ity", ";Y", Place::NotFirst),
+];
+
+// Helper to fold characters braille has no simple sign for onto plain
+// ASCII: accented letters onto their base letter and curly apostrophes onto
+// straight ones
+fn fold(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'A',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'È' | 'É' | 'Ê' | 'Ë' => 'E',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' => 'O',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'Ù' | 'Ú' | 'Û' | 'Ü' => 'U',
+        'ñ' => 'n',
+        'Ñ' => 'N',
+        'ç' => 'c',
+        'Ç' => 'C',
+        '‘' | '’' => '\'',
+        _ => c,
+    }
+}
+
+// Helper to get the UEB sign for a punctuation mark or symbol, or "" for
+// characters without one. `opening` tells opening and closing straight
+// double quotes apart.
+fn punctuation(c: char, opening: bool) -> &'static str {
+    match c {
+        ',' => "1",
+        ';' => "2",
+        ':' => "3",
+        '.' => "4",
+        '!' => "6",
+        '?' => "8",
+        '\'' => "'",
+        '-' => "-",
+        '–' | '—' => ",-",
+        '"' if opening => "8",
+        '"' => "0",
+        '“' => "8",
+        '”' => "0",
+        '(' => "\"<",
+        ')' => "\">",
+        '[' => ".<",
+        ']' => ".>",
+        '/' => "_/",
+        '&' => "@&",
+        '$' => "@S",
+        '%' => ".0",
+        '+' => "\"6",
+        '=' => "\"7",
+        '×' => "\"8",
+        '÷' => "\"/",
+        '*' => "\"9",
+        '<' => "@<",
+        '>' => "@>",
+        '@' => "@A",
+        '#' => "_?",
+        _ => "",
+    }
+}
+
+// Helper to get the letter sign (a-j) for a digit
+fn digit(d: char) -> char {
+    match d {
+        '0' => 'J',
+        _ => (b'A' + (d as u8 - b'1')) as char,
+    }
+}
+
+// Helper to find the whole-word sign for a word, if Grade 2 has one.
+// Lower wordsigns need the word to stand alone between spaces.
+fn whole_word_sign(lower: &str, standalone: bool) -> Option<&'static str> {
+    let lower_signs = LOWER_WORDSIGNS.iter().filter(|_| standalone);
+    WORDSIGNS
+        .iter()
+        .chain(lower_signs)
+        .find(|(word, _)| *word == lower)
+        .map(|(_, sign)| *sign)
+}
+
+// Helper to find the longest Grade 2 contraction that may be used at
+// `start` of a lowercased word. A contraction can't span a capital letter
+// after its first, since the capital needs its own indicator.
+fn groupsign_at(
+    lower: &str,
+    start: usize,
+    capital: impl Fn(usize) -> bool,
+) -> Option<(&'static str, &'static str)> {
+    let after_letter = start > 0 && lower.as_bytes()[start - 1].is_ascii_alphabetic();
+    GROUPSIGNS
+        .iter()
+        .filter(|(group, _, place)| {
+            let end = start + group.len();
+            lower[start..].starts_with(group)
+                && match place {
+                    Place::Anywhere => true,
+                    Place::NotFirst => after_letter,
+                    Place::Middle => after_letter && end < lower.len(),
+                    Place::Start => start == 0,
+                }
+                && !(start + 1..end).any(&capital)
+        })
+        .max_by_key(|(group, _, _)| group.len())
+        .map(|(group, sign, _)| (*group, *sign))
+}
+
+// Helper to translate one word (ASCII letters with apostrophes between
+// them), contracted for Grade 2. `standalone` says whether it has a space
+// (or the edge of the paragraph) on both sides.
+fn translate_word(word: &str, contracted: bool, standalone: bool) -> String {
+    let lower = word.to_ascii_lowercase();
+    let letters: Vec<char> = word.chars().collect();
+    let letter_count = letters.iter().filter(|c| c.is_ascii_alphabetic()).count();
+    let all_caps = letter_count >= 2 && !letters.iter().any(|c| c.is_ascii_lowercase());
+    let capital = |i: usize| !all_caps && letters[i].is_ascii_uppercase();
+
+    let mut out = String::new();
+    if all_caps {
+        out.push_str(CAPITAL_WORD);
+    }
+    if contracted && !(1..letters.len()).any(capital) {
+        if let Some(sign) = whole_word_sign(&lower, standalone) {
+            if capital(0) {
+                out.push_str(CAPITAL);
+            }
+            out.push_str(sign);
+            return out;
+        }
+    }
+
+    let mut body = String::new();
+    let mut spelled = true;
+    let mut i = 0;
+    while i < letters.len() {
+        if capital(i) {
+            body.push_str(CAPITAL);
+        }
+        match groupsign_at(&lower, i, capital).filter(|_| contracted) {
+            Some((group, sign)) => {
+                body.push_str(sign);
+                i += group.len();
+                spelled = false;
+            }
+            None => {
+                body.push(letters[i].to_ascii_uppercase());
+                i += 1;
+            }
+        }
+    }
+    // Letters spelling a wordsign or shortform (such as a lone "b", or "gd")
+    // would be read as that word
+    let letters_only = body.replace(CAPITAL, "");
+    if contracted && spelled && WORDSIGNS.iter().any(|(_, sign)| *sign == letters_only) {
+        out.push_str(GRADE_1);
+    }
+    out.push_str(&body);
+    out
+}
+
+// Helper to translate a paragraph of print into ASCII braille, contracted
+// for Grade 2
+fn translate(text: &str, contracted: bool) -> String {
+    let chars: Vec<char> = text.chars().map(fold).collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii_alphabetic() {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_alphabetic()
+                    || (chars[i] == '\''
+                        && chars.get(i + 1).is_some_and(|n| n.is_ascii_alphabetic())))
+            {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let standalone = (start == 0 || chars[start - 1].is_whitespace())
+                && chars.get(i).is_none_or(|n| n.is_whitespace());
+            let word = translate_word(&word, contracted, standalone);
+            // Letters a-j straight after a number would read as digits
+            let after_number = start > 0 && chars[start - 1].is_ascii_digit();
+            let letter_digit = word.starts_with(|l: char| ('A'..='J').contains(&l));
+            if after_number && letter_digit {
+                out.push_str(GRADE_1);
+            }
+            out.push_str(&word);
+        } else if c.is_ascii_digit() {
+            out.push_str(NUMBER);
+            while let Some(&d) = chars.get(i) {
+                let next_is_digit = chars.get(i + 1).is_some_and(|n| n.is_ascii_digit());
+                match d {
+                    _ if d.is_ascii_digit() => out.push(digit(d)),
+                    ',' if next_is_digit => out.push('1'),
+                    '.' if next_is_digit => out.push('4'),
+                    _ => break,
+                }
+                i += 1;
+            }
+        } else if c == '_' {
+            while chars.get(i) == Some(&'_') {
+                i += 1;
+            }
+            out.push_str(BLANK);
+        } else {
+            if c.is_whitespace() {
+                if !out.is_empty() && !out.ends_with(' ') {
+                    out.push(' ');
+                }
+            } else {
+                let opening = i == 0 || chars[i - 1].is_whitespace();
+                out.push_str(punctuation(c, opening));
+            }
+            i += 1;
+        }
+    }
+    out.trim_end().to_string()
+}
+
+// Helper to wrap translated paragraphs into lines, indenting the first
+// line of each. Words too long for a line are split across lines.
+fn wrap(paragraphs: &[String]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in paragraphs {
+        let mut line = PARAGRAPH_INDENT.to_string();
+        for word in paragraph.split(' ') {
+            let mut word = word;
+            if line.trim().is_empty() {
+                // Nothing on the line yet besides the indent
+            } else if line.len() + 1 + word.len() <= CELLS_PER_LINE {
+                line.push(' ');
+            } else {
+                lines.push(std::mem::take(&mut line));
+            }
+            while line.len() + word.len() > CELLS_PER_LINE {
+                let (head, tail) = word.split_at(CELLS_PER_LINE - line.len());
+                line.push_str(head);
+                lines.push(std::mem::take(&mut line));
+                word = tail;
+            }
+            line.push_str(word);
+        }
+        if !line.trim().is_empty() {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+// Helper to lay lines out on braille pages: CRLF line endings, a form feed
+// after each page, and the page number in braille at the right of each
+// page's first line. Returns the file contents and the page count.
+fn paginate(lines: &[String]) -> (String, usize) {
+    let mut brf = String::new();
+    let pages: Vec<&[String]> = lines.chunks(LINES_PER_PAGE - 1).collect();
+    for (index, page) in pages.iter().enumerate() {
+        let number = translate(&(index + 1).to_string(), false);
+        brf.push_str(&format!("{:>width$}\r\n", number, width = CELLS_PER_LINE));
+        for line in page.iter() {
+            brf.push_str(line);
+            brf.push_str("\r\n");
+        }
+        brf.push('\u{c}');
+    }
+    (brf, pages.len())
+}
+
+// ============================================
+// Braille Commands
+// ============================================
+
+/// Export an artifact's text as a braille-ready file (.brf) for embossers
+/// and refreshable displays: North American ASCII braille, 40 cells by 25
+/// lines per page. `grade` is 2 (contracted UEB, the default) or 1
+/// (uncontracted). Grade 2 uses the wordsigns, shortforms, and strong,
+/// lower, initial-letter, and final-letter contractions, each only where
+/// UEB places it in a word; it doesn't know the words where a contraction
+/// would bridge syllables and UEB spells the letters out instead, so those
+/// are worth a proofread. Images are replaced by their alt text. Returns
+/// the number of braille pages written.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn export_brf(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    output_path: String,
+    grade: Option<u8>,
+) -> Result<usize, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let contracted = match grade.unwrap_or(2) {
+        1 => false,
+        2 => true,
+        other => return Err(format!("Unsupported braille grade: {}", other)),
+    };
+    let artifact = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    let html = artifact
+        .get("htmlContent")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+
+    let paragraphs: Vec<String> = artifact_analysis::text_blocks(html)
+        .iter()
+        .map(|block| translate(block, contracted))
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect();
+    if paragraphs.is_empty() {
+        return Err("The artifact has no text to translate".to_string());
+    }
+    let (brf, pages) = paginate(&wrap(&paragraphs));
+    fs::write(&output_path, brf)
+        .await
+        .map_err(|e| format!("Failed to write braille file: {}", e))?;
+
+    audit_log::record(&app_handle, "export_brf", "artifact", &[&artifact_id]).await;
+
+    Ok(pages)
+}
@@ -0,0 +1,350 @@
+use chrono::{Datelike, Days, NaiveDate};
+use serde_json::Value;
+use std::path::PathBuf;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, learner_storage, library_storage, session_mode};
+
+/// Each learner's schedule, kept in their learner directory so it travels
+/// with learner bundles and is removed with the learner
+pub(crate) const CALENDAR_FILE: &str = "calendar.json";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Longest line allowed in an iCalendar file, in bytes, before folding
+const ICS_LINE_LIMIT: usize = 75;
+
+// Helper to get a learner's calendar file path
+fn get_calendar_path(app_handle: &tauri::AppHandle, learner_id: &str) -> Result<PathBuf, String> {
+    Ok(learner_storage::get_learner_dir(app_handle, learner_id)?.join(CALENDAR_FILE))
+}
+
+/// Read a learner's calendar entries, in stored order
+pub(crate) async fn read_entries(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+) -> Result<Vec<Value>, String> {
+    let calendar_path = get_calendar_path(app_handle, learner_id)?;
+    if !calendar_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&calendar_path)
+        .await
+        .map_err(|e| format!("Failed to read calendar: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
+// Helper to write a learner's calendar entries
+async fn write_entries(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+    entries: &[Value],
+) -> Result<(), String> {
+    let calendar_path = get_calendar_path(app_handle, learner_id)?;
+    if let Some(parent) = calendar_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create learner directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize calendar: {}", e))?;
+    fs::write(&calendar_path, content)
+        .await
+        .map_err(|e| format!("Failed to write calendar: {}", e))
+}
+
+// Helper to parse a `YYYY-MM-DD` date
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, DATE_FORMAT).map_err(|_| format!("Invalid date: {}", date))
+}
+
+// Helper to read an entry's ID
+fn entry_id(entry: &Value) -> Option<&str> {
+    entry.get("entryId").and_then(|v| v.as_str())
+}
+
+// Helper to read an entry's date
+fn entry_date(entry: &Value) -> Option<NaiveDate> {
+    entry
+        .get("date")
+        .and_then(|v| v.as_str())
+        .and_then(|d| NaiveDate::parse_from_str(d, DATE_FORMAT).ok())
+}
+
+// Helper to get a learner's entries from `from` to `to` (both inclusive),
+// ordered by date
+async fn entries_between(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<Value>, String> {
+    let mut entries: Vec<Value> = read_entries(app_handle, learner_id)
+        .await?
+        .into_iter()
+        .filter(|e| entry_date(e).is_some_and(|d| d >= from && d <= to))
+        .collect();
+    entries.sort_by_key(entry_date);
+    Ok(entries)
+}
+
+// Helper to escape text for an iCalendar property value
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// Helper to append an iCalendar content line, folding it on character
+// boundaries so no line is longer than the limit
+fn push_ics_line(ics: &mut String, line: &str) {
+    let mut rest = line;
+    let mut limit = ICS_LINE_LIMIT;
+    while rest.len() > limit {
+        let mut split = limit;
+        while !rest.is_char_boundary(split) {
+            split -= 1;
+        }
+        ics.push_str(&rest[..split]);
+        ics.push_str("\r\n ");
+        rest = &rest[split..];
+        // Continuation lines start with a space
+        limit = ICS_LINE_LIMIT - 1;
+    }
+    ics.push_str(rest);
+    ics.push_str("\r\n");
+}
+
+// Helper to build an iCalendar file with an all-day event per entry
+fn build_ics(entries: &[Value]) -> String {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut ics = String::new();
+    push_ics_line(&mut ics, "BEGIN:VCALENDAR");
+    push_ics_line(&mut ics, "VERSION:2.0");
+    push_ics_line(&mut ics, "PRODID:-//TA Teachers Assistant//Lesson Calendar//EN");
+    push_ics_line(&mut ics, "CALSCALE:GREGORIAN");
+    for entry in entries {
+        let (Some(id), Some(date)) = (entry_id(entry), entry_date(entry)) else {
+            continue;
+        };
+        let Some(next_day) = date.checked_add_days(Days::new(1)) else {
+            continue;
+        };
+        let title = entry.get("title").and_then(|v| v.as_str()).unwrap_or("Lesson");
+        push_ics_line(&mut ics, "BEGIN:VEVENT");
+        push_ics_line(&mut ics, &format!("UID:{}@teachers-assistant", id));
+        push_ics_line(&mut ics, &format!("DTSTAMP:{}", stamp));
+        push_ics_line(&mut ics, &format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+        push_ics_line(&mut ics, &format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")));
+        push_ics_line(&mut ics, &format!("SUMMARY:{}", ics_escape(title)));
+        if let Some(notes) = entry.get("notes").and_then(|v| v.as_str()) {
+            push_ics_line(&mut ics, &format!("DESCRIPTION:{}", ics_escape(notes)));
+        }
+        if entry.get("completed").and_then(|v| v.as_bool()).unwrap_or(false) {
+            push_ics_line(&mut ics, "STATUS:CONFIRMED");
+        }
+        push_ics_line(&mut ics, "END:VEVENT");
+    }
+    push_ics_line(&mut ics, "END:VCALENDAR");
+    ics
+}
+
+// ============================================
+// Calendar Commands
+// ============================================
+
+/// Get a learner's entries for the week (Monday to Sunday) containing
+/// `date` (`YYYY-MM-DD`), ordered by date
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_calendar_week(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    date: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_learner_allowed(&app_handle, &learner_id)?;
+
+    let date = parse_date(&date)?;
+    let monday = date - Days::new(u64::from(date.weekday().num_days_from_monday()));
+    let sunday = monday + Days::new(6);
+    let entries = entries_between(&app_handle, &learner_id, monday, sunday).await?;
+    serde_json::to_string(&entries).map_err(|e| format!("Failed to serialize calendar: {}", e))
+}
+
+/// Get a learner's entries for a month (1-12), ordered by date
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_calendar_month(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    year: i32,
+    month: u32,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_learner_allowed(&app_handle, &learner_id)?;
+
+    let first = NaiveDate::from_ymd_opt(year, month, 1)
+        .ok_or_else(|| format!("Invalid month: {}-{}", year, month))?;
+    let last = first
+        .checked_add_months(chrono::Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .ok_or_else(|| format!("Invalid month: {}-{}", year, month))?;
+    let entries = entries_between(&app_handle, &learner_id, first, last).await?;
+    serde_json::to_string(&entries).map_err(|e| format!("Failed to serialize calendar: {}", e))
+}
+
+/// Schedule an artifact or assignment for a learner (create or update). The
+/// entry needs a `date` (`YYYY-MM-DD`) and a `title`, and may have an
+/// `artifactId` (which must exist in the library), an `assignmentId`,
+/// `notes`, and `completed`. A missing `entryId` creates a new entry.
+/// Returns the saved entry.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn save_calendar_entry(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    entry: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut new_entry: Value =
+        serde_json::from_str(&entry).map_err(|e| format!("Invalid calendar entry JSON: {}", e))?;
+    parse_date(new_entry.get("date").and_then(|v| v.as_str()).unwrap_or(""))?;
+    let title = new_entry.get("title").and_then(|v| v.as_str()).unwrap_or("");
+    if title.trim().is_empty() {
+        return Err("Calendar entry must have a title".to_string());
+    }
+    if let Some(artifact_id) = new_entry.get("artifactId").and_then(|v| v.as_str()) {
+        library_storage::read_artifact(&app_handle, artifact_id)
+            .await
+            .map_err(|_| format!("Artifact not found: {}", artifact_id))?;
+    }
+
+    let mut entries = read_entries(&app_handle, &learner_id).await?;
+    let id = entry_id(&new_entry)
+        .map(String::from)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let existing = entries.iter().position(|e| entry_id(e) == Some(id.as_str()));
+    let now = chrono::Utc::now().to_rfc3339();
+    if let Some(obj) = new_entry.as_object_mut() {
+        let created_at = existing
+            .and_then(|i| entries[i].get("createdAt").cloned())
+            .unwrap_or_else(|| Value::String(now.clone()));
+        obj.insert("entryId".to_string(), Value::String(id.clone()));
+        obj.insert("learnerId".to_string(), Value::String(learner_id.clone()));
+        obj.insert("createdAt".to_string(), created_at);
+        obj.insert("updatedAt".to_string(), Value::String(now));
+    }
+
+    match existing {
+        Some(i) => entries[i] = new_entry.clone(),
+        None => entries.push(new_entry.clone()),
+    }
+    write_entries(&app_handle, &learner_id, &entries).await?;
+
+    audit_log::record(&app_handle, "save_calendar_entry", "calendar", &[&learner_id, &id]).await;
+
+    Ok(new_entry.to_string())
+}
+
+/// Move an entry to another date (`YYYY-MM-DD`), e.g. after dragging it in
+/// the calendar. Returns the updated entry.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn reschedule_calendar_entry(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    entry_id: String,
+    date: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let date = parse_date(&date)?;
+    let mut entries = read_entries(&app_handle, &learner_id).await?;
+    let entry = entries
+        .iter_mut()
+        .find(|e| self::entry_id(e) == Some(entry_id.as_str()))
+        .ok_or_else(|| format!("Calendar entry not found: {}", entry_id))?;
+    if let Some(obj) = entry.as_object_mut() {
+        obj.insert("date".to_string(), Value::String(date.format(DATE_FORMAT).to_string()));
+        obj.insert(
+            "updatedAt".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        );
+    }
+    let updated = entry.clone();
+    write_entries(&app_handle, &learner_id, &entries).await?;
+
+    audit_log::record(
+        &app_handle,
+        "reschedule_calendar_entry",
+        "calendar",
+        &[&learner_id, &entry_id],
+    )
+    .await;
+
+    Ok(updated.to_string())
+}
+
+/// Remove an entry from a learner's calendar
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_calendar_entry(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    entry_id: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut entries = read_entries(&app_handle, &learner_id).await?;
+    let before = entries.len();
+    entries.retain(|e| self::entry_id(e) != Some(entry_id.as_str()));
+    if entries.len() != before {
+        write_entries(&app_handle, &learner_id, &entries).await?;
+    }
+
+    audit_log::record(
+        &app_handle,
+        "delete_calendar_entry",
+        "calendar",
+        &[&learner_id, &entry_id],
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Export a learner's entries from `from` to `to` (`YYYY-MM-DD`, both
+/// inclusive) as an iCalendar (.ics) file of all-day events. Returns the
+/// number of events written.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn export_calendar_ics(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    from: String,
+    to: String,
+    output_path: String,
+) -> Result<usize, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let (from, to) = (parse_date(&from)?, parse_date(&to)?);
+    if from > to {
+        return Err("The start date must not be after the end date".to_string());
+    }
+    let entries = entries_between(&app_handle, &learner_id, from, to).await?;
+    fs::write(&output_path, build_ics(&entries))
+        .await
+        .map_err(|e| format!("Failed to write calendar file: {}", e))?;
+
+    audit_log::record(&app_handle, "export_calendar_ics", "calendar", &[&learner_id]).await;
+
+    Ok(entries.len())
+}
@@ -0,0 +1,401 @@
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+use tokio::fs;
+
+use crate::archive;
+use crate::commands::{app_lock, audit_log, image_cache, session_mode, worksheet_assembly};
+
+const CLIPART_DIR: &str = "clipart";
+
+// Built-in packs compiled into the binary. They are read from here, never
+// copied to disk, so an update ships its new clipart.
+const BUILT_IN_PACKS: &[&[u8]] = &[
+    include_bytes!("../../clipart/school.json"),
+    include_bytes!("../../clipart/nature.json"),
+    include_bytes!("../../clipart/shapes.json"),
+];
+
+// Largest SVG accepted on import
+const MAX_SVG_BYTES: usize = 1024 * 1024;
+
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+// Helper to get the directory of imported packs
+fn get_clipart_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(CLIPART_DIR))
+}
+
+// Helper to get an imported pack's file, rejecting IDs that could escape the
+// clipart directory
+fn get_pack_path(app_handle: &tauri::AppHandle, pack_id: &str) -> Result<PathBuf, String> {
+    if pack_id.is_empty() || !pack_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid clipart pack ID: {}", pack_id));
+    }
+    Ok(get_clipart_dir(app_handle)?.join(format!("{}.json", pack_id)))
+}
+
+// Helper to read a string field, or "" when missing
+fn text<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+// Helper to read every pack: built-in ones first, then imported ones
+async fn read_packs(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let mut packs = BUILT_IN_PACKS
+        .iter()
+        .map(|bytes| {
+            serde_json::from_slice(bytes).map_err(|e| format!("Invalid built-in clipart: {}", e))
+        })
+        .collect::<Result<Vec<Value>, String>>()?;
+
+    let clipart_dir = get_clipart_dir(app_handle)?;
+    if !clipart_dir.exists() {
+        return Ok(packs);
+    }
+    let mut read_dir = fs::read_dir(&clipart_dir)
+        .await
+        .map_err(|e| format!("Failed to read clipart directory: {}", e))?;
+    let mut imported = Vec::new();
+    while let Ok(Some(entry)) = read_dir.next_entry().await {
+        let path = entry.path();
+        if path.extension().is_some_and(|e| e == "json") {
+            match fs::read_to_string(&path).await.map(|c| serde_json::from_str(&c)) {
+                Ok(Ok(pack)) => imported.push(pack),
+                _ => tracing::warn!(path = %path.display(), "Skipping unreadable clipart pack"),
+            }
+        }
+    }
+    imported.sort_by(|a: &Value, b: &Value| text(a, "name").cmp(text(b, "name")));
+    packs.extend(imported);
+    Ok(packs)
+}
+
+// Helper to check that imported SVG text is a plain drawing. Clipart is
+// only ever shown through <img>, where scripts don't run, but a file with
+// scripts or external references isn't clipart.
+fn validate_svg(svg: &str) -> Result<(), String> {
+    if svg.len() > MAX_SVG_BYTES {
+        return Err("SVG is too large".to_string());
+    }
+    let lower = svg.to_lowercase();
+    if !lower.contains("<svg") {
+        return Err("Not an SVG image".to_string());
+    }
+    let scripted = ["<script", "javascript:", "<foreignobject"];
+    if scripted.iter().any(|marker| lower.contains(marker)) {
+        return Err("SVG contains scripts".to_string());
+    }
+    Ok(())
+}
+
+// Helper to derive search tags from a file name, e.g. "red-apple_2" gives
+// ["red", "apple"]
+fn tags_from_name(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !word.chars().all(|c| c.is_ascii_digit()))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// Helper to turn a file stem into a display name, e.g. "red-apple" gives
+// "Red apple"
+fn display_name(stem: &str) -> String {
+    let words = tags_from_name(stem).join(" ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => stem.to_string(),
+    }
+}
+
+// Helper to read the (file name, SVG text) pairs from an import source: a
+// zip of SVGs or a folder of them
+async fn read_import_svgs(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let mut files = Vec::new();
+    if path.is_dir() {
+        let mut read_dir = fs::read_dir(path)
+            .await
+            .map_err(|e| format!("Failed to read clipart folder: {}", e))?;
+        while let Ok(Some(entry)) = read_dir.next_entry().await {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.to_lowercase().ends_with(".svg") {
+                let contents = fs::read(entry.path())
+                    .await
+                    .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+                files.push((name, contents));
+            }
+        }
+    } else {
+        let bytes = fs::read(path)
+            .await
+            .map_err(|e| format!("Failed to read clipart pack: {}", e))?;
+        for (name, contents) in archive::read_zip(&bytes)? {
+            if name.to_lowercase().ends_with(".svg") && !name.starts_with("__MACOSX/") {
+                files.push((name, contents));
+            }
+        }
+    }
+    Ok(files
+        .into_iter()
+        .filter_map(|(name, contents)| String::from_utf8(contents).ok().map(|svg| (name, svg)))
+        .collect())
+}
+
+// Helper to build the search result for an item of a pack
+fn item_result(pack: &Value, item: &Value) -> Value {
+    serde_json::json!({
+        "clipartId": item.get("clipartId"),
+        "packId": pack.get("packId"),
+        "packName": pack.get("name"),
+        "theme": pack.get("theme"),
+        "name": item.get("name"),
+        "tags": item.get("tags"),
+        "dataUri": image_cache::data_uri("image/svg+xml", text(item, "svg").as_bytes()),
+    })
+}
+
+// Helper to score an item against the query words: 0 unless every word
+// matches its name or a tag. Whole-word matches count more than partial.
+fn match_score(item: &Value, words: &[String]) -> u32 {
+    let name = text(item, "name").to_lowercase();
+    let name_words = tags_from_name(&name);
+    let tags: Vec<String> = item
+        .get("tags")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|t| t.as_str().map(str::to_lowercase))
+        .collect();
+
+    let mut score = 0;
+    for word in words {
+        let word_score = if name_words.contains(word) {
+            3
+        } else if tags.contains(word) {
+            2
+        } else if name.contains(word.as_str()) || tags.iter().any(|t| t.contains(word.as_str())) {
+            1
+        } else {
+            return 0;
+        };
+        score += word_score;
+    }
+    score.max(1)
+}
+
+/// Look up clipart items by ID (`{packId}/{name}`), in the order given.
+/// Unknown IDs are skipped.
+pub(crate) async fn find_items(
+    app_handle: &tauri::AppHandle,
+    clipart_ids: &[String],
+) -> Result<Vec<Value>, String> {
+    let packs = read_packs(app_handle).await?;
+    Ok(clipart_ids
+        .iter()
+        .filter_map(|id| {
+            packs
+                .iter()
+                .flat_map(|pack| pack.get("items").and_then(|v| v.as_array()).into_iter().flatten())
+                .find(|item| text(item, "clipartId") == id)
+                .cloned()
+        })
+        .collect())
+}
+
+/// Render clipart items as a row of decorations for a printable document.
+/// Each SVG goes in an <img>, so nothing in it can run.
+pub(crate) fn render_decorations(items: &[Value]) -> String {
+    if items.is_empty() {
+        return String::new();
+    }
+    let images: String = items
+        .iter()
+        .map(|item| {
+            format!(
+                "<img src=\"{}\" alt=\"{}\">",
+                image_cache::data_uri("image/svg+xml", text(item, "svg").as_bytes()),
+                worksheet_assembly::escape_html(text(item, "name"))
+            )
+        })
+        .collect();
+    format!("<div class=\"clipart\">{}</div>\n", images)
+}
+
+// ============================================
+// Clipart Commands
+// ============================================
+
+/// List clipart packs as `[{packId, name, theme, builtIn, itemCount}]`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_clipart_packs(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let summaries: Vec<Value> = read_packs(&app_handle)
+        .await?
+        .iter()
+        .map(|pack| {
+            serde_json::json!({
+                "packId": pack.get("packId"),
+                "name": pack.get("name"),
+                "theme": pack.get("theme"),
+                "builtIn": pack.get("builtIn").and_then(|v| v.as_bool()).unwrap_or(false),
+                "itemCount": pack.get("items").and_then(|v| v.as_array()).map_or(0, Vec::len),
+            })
+        })
+        .collect();
+    serde_json::to_string(&summaries).map_err(|e| format!("Failed to serialize clipart: {}", e))
+}
+
+/// Search clipart by name and tags; every word of `query` must match. An
+/// empty query lists everything. `theme` limits results to packs with that
+/// theme (e.g. "school", "nature", "math"). Returns up to `limit` (default
+/// 50) results, best first, as `[{clipartId, packId, packName, theme, name,
+/// tags, dataUri}]`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn search_clipart(
+    app_handle: tauri::AppHandle,
+    query: String,
+    theme: Option<String>,
+    limit: Option<usize>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let words = tags_from_name(&query);
+    let theme = theme.filter(|t| !t.trim().is_empty());
+    let packs = read_packs(&app_handle).await?;
+
+    let mut matches: Vec<(u32, &str, Value)> = Vec::new();
+    for pack in &packs {
+        if theme.as_deref().is_some_and(|t| !text(pack, "theme").eq_ignore_ascii_case(t.trim())) {
+            continue;
+        }
+        for item in pack.get("items").and_then(|v| v.as_array()).into_iter().flatten() {
+            let score = match_score(item, &words);
+            if score > 0 {
+                matches.push((score, text(item, "name"), item_result(pack, item)));
+            }
+        }
+    }
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+    let results: Vec<Value> = matches
+        .into_iter()
+        .take(limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+        .map(|(_, _, result)| result)
+        .collect();
+
+    serde_json::to_string(&results).map_err(|e| format!("Failed to serialize clipart: {}", e))
+}
+
+/// Import a clipart pack from a zip of SVG files or a folder of them. Item
+/// names and search tags come from the file names. `name` defaults to the
+/// zip or folder name; `theme` groups the pack for `search_clipart`. Files
+/// that aren't plain SVG drawings are skipped. Returns `{packId, imported,
+/// skipped}`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn import_clipart_pack(
+    app_handle: tauri::AppHandle,
+    path: String,
+    name: Option<String>,
+    theme: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let source = PathBuf::from(&path);
+    let files = read_import_svgs(&source).await?;
+    let pack_id = format!("clipart-{}", uuid::Uuid::new_v4());
+    let theme = theme.map(|t| t.trim().to_lowercase()).unwrap_or_default();
+
+    let mut items = Vec::new();
+    let mut skipped = 0;
+    for (file_name, svg) in files {
+        if let Err(e) = validate_svg(&svg) {
+            tracing::warn!(file = %file_name, error = %e, "Skipping clipart file");
+            skipped += 1;
+            continue;
+        }
+        let stem = Path::new(&file_name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut tags = tags_from_name(&stem);
+        if !theme.is_empty() && !tags.contains(&theme) {
+            tags.push(theme.clone());
+        }
+        items.push(serde_json::json!({
+            "clipartId": format!("{}/{}", pack_id, items.len() + 1),
+            "name": display_name(&stem),
+            "tags": tags,
+            "svg": svg,
+        }));
+    }
+    if items.is_empty() {
+        return Err("No SVG clipart found to import".to_string());
+    }
+
+    let pack_name = name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| {
+        source
+            .file_stem()
+            .map(|s| display_name(&s.to_string_lossy()))
+            .unwrap_or_else(|| "Imported clipart".to_string())
+    });
+    let imported = items.len();
+    let pack = serde_json::json!({
+        "packId": pack_id,
+        "name": pack_name,
+        "theme": theme,
+        "builtIn": false,
+        "items": items,
+        "createdAt": chrono::Utc::now().to_rfc3339(),
+    });
+    let pack_path = get_pack_path(&app_handle, &pack_id)?;
+    fs::create_dir_all(get_clipart_dir(&app_handle)?)
+        .await
+        .map_err(|e| format!("Failed to create clipart directory: {}", e))?;
+    fs::write(&pack_path, pack.to_string())
+        .await
+        .map_err(|e| format!("Failed to write clipart pack: {}", e))?;
+
+    audit_log::record(&app_handle, "import_clipart_pack", "clipart_pack", &[&pack_id]).await;
+
+    let summary = serde_json::json!({
+        "packId": pack_id,
+        "imported": imported,
+        "skipped": skipped,
+    });
+    Ok(summary.to_string())
+}
+
+/// Delete an imported clipart pack. Built-in packs can't be deleted.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_clipart_pack(
+    app_handle: tauri::AppHandle,
+    pack_id: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    if pack_id.starts_with("builtin-") {
+        return Err("Built-in clipart packs can't be deleted".to_string());
+    }
+    let pack_path = get_pack_path(&app_handle, &pack_id)?;
+    if !pack_path.exists() {
+        return Err(format!("Clipart pack not found: {}", pack_id));
+    }
+    fs::remove_file(&pack_path)
+        .await
+        .map_err(|e| format!("Failed to delete clipart pack: {}", e))?;
+
+    audit_log::record(&app_handle, "delete_clipart_pack", "clipart_pack", &[&pack_id]).await;
+
+    Ok(())
+}
@@ -0,0 +1,312 @@
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::storage_events::{self, StorageEvent};
+use crate::commands::{app_lock, audit_log, session_mode};
+
+const COLLECTIONS_DIR: &str = "collections";
+const COLLECTIONS_FILE: &str = "collections.json";
+
+// Helper to get the collections file path
+fn get_collections_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(COLLECTIONS_DIR).join(COLLECTIONS_FILE))
+}
+
+// Helper to read every collection
+async fn read_collections(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let collections_path = get_collections_path(app_handle)?;
+    if !collections_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&collections_path)
+        .await
+        .map_err(|e| format!("Failed to read collections: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
+// Helper to write every collection
+async fn write_collections(
+    app_handle: &tauri::AppHandle,
+    collections: &[Value],
+) -> Result<(), String> {
+    let collections_path = get_collections_path(app_handle)?;
+    if let Some(parent) = collections_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create collections directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(collections)
+        .map_err(|e| format!("Failed to serialize collections: {}", e))?;
+    fs::write(&collections_path, content)
+        .await
+        .map_err(|e| format!("Failed to write collections: {}", e))
+}
+
+// Helper to get a collection's ID
+fn collection_id(collection: &Value) -> Option<&str> {
+    collection.get("collectionId").and_then(|v| v.as_str())
+}
+
+// Helper to get a collection's parent ID
+fn parent_id(collection: &Value) -> Option<&str> {
+    collection.get("parentId").and_then(|v| v.as_str())
+}
+
+// Helper to find a collection's position by ID
+fn position(collections: &[Value], id: &str) -> Result<usize, String> {
+    collections
+        .iter()
+        .position(|c| collection_id(c) == Some(id))
+        .ok_or_else(|| format!("Collection not found: {}", id))
+}
+
+// Helper to check that giving `id` the parent `parent` doesn't nest a
+// collection inside itself
+fn check_parent(collections: &[Value], id: &str, parent: &str) -> Result<(), String> {
+    let mut current = Some(parent.to_string());
+    let mut visited = BTreeSet::new();
+    while let Some(ancestor) = current {
+        if ancestor == id || !visited.insert(ancestor.clone()) {
+            return Err("A collection cannot be nested inside itself".to_string());
+        }
+        let index = position(collections, &ancestor)?;
+        current = parent_id(&collections[index]).map(String::from);
+    }
+    Ok(())
+}
+
+// Helper to add or remove artifact IDs on a collection
+async fn update_membership(
+    app_handle: &tauri::AppHandle,
+    id: &str,
+    artifact_ids: &[String],
+    add: bool,
+) -> Result<Value, String> {
+    let mut collections = read_collections(app_handle).await?;
+    let index = position(&collections, id)?;
+    let obj = collections[index]
+        .as_object_mut()
+        .ok_or("Invalid collection")?;
+
+    let members = obj
+        .entry("artifactIds")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if let Some(arr) = members.as_array_mut() {
+        if add {
+            for artifact_id in artifact_ids {
+                let value = Value::String(artifact_id.clone());
+                if !arr.contains(&value) {
+                    arr.push(value);
+                }
+            }
+        } else {
+            arr.retain(|v| !v.as_str().is_some_and(|a| artifact_ids.iter().any(|r| r == a)));
+        }
+    }
+    obj.insert(
+        "updatedAt".to_string(),
+        Value::String(chrono::Utc::now().to_rfc3339()),
+    );
+
+    let updated = collections[index].clone();
+    write_collections(app_handle, &collections).await?;
+    storage_events::emit(app_handle, StorageEvent::CollectionUpdated(id));
+    Ok(updated)
+}
+
+/// Remove an artifact ID from every collection that lists it
+pub(crate) async fn remove_artifact_references(
+    app_handle: &tauri::AppHandle,
+    artifact_id: &str,
+) -> Result<(), String> {
+    let mut collections = read_collections(app_handle).await?;
+    let mut changed = Vec::new();
+    for collection in collections.iter_mut() {
+        if let Some(arr) = collection.get_mut("artifactIds").and_then(|v| v.as_array_mut()) {
+            let before = arr.len();
+            arr.retain(|v| v.as_str() != Some(artifact_id));
+            if arr.len() != before {
+                changed.extend(collection_id(collection).map(String::from));
+            }
+        }
+    }
+    if !changed.is_empty() {
+        write_collections(app_handle, &collections).await?;
+        for id in &changed {
+            storage_events::emit(app_handle, StorageEvent::CollectionUpdated(id));
+        }
+    }
+    Ok(())
+}
+
+// ============================================
+// Collection Commands
+// ============================================
+
+/// Get all collections. Each has `collectionId`, `name`, an optional
+/// `parentId` for nesting, and `artifactIds`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_collections(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let collections = read_collections(&app_handle).await?;
+    serde_json::to_string(&collections)
+        .map_err(|e| format!("Failed to serialize collections: {}", e))
+}
+
+/// Save a collection (create or update). Membership is kept from the stored
+/// copy when the incoming collection omits `artifactIds`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn save_collection(
+    app_handle: tauri::AppHandle,
+    collection: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut new_collection: Value = serde_json::from_str(&collection)
+        .map_err(|e| format!("Invalid collection JSON: {}", e))?;
+    let id = collection_id(&new_collection)
+        .ok_or("Collection must have a collectionId")?
+        .to_string();
+    let name = new_collection.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    if name.trim().is_empty() {
+        return Err("Collection must have a name".to_string());
+    }
+
+    let mut collections = read_collections(&app_handle).await?;
+    if let Some(parent) = parent_id(&new_collection) {
+        check_parent(&collections, &id, parent)?;
+    }
+
+    let existing = collections.iter().position(|c| collection_id(c) == Some(id.as_str()));
+    let now = chrono::Utc::now().to_rfc3339();
+    if let Some(obj) = new_collection.as_object_mut() {
+        let stored = existing.map(|i| &collections[i]);
+        if !obj.contains_key("artifactIds") {
+            let members = stored
+                .and_then(|c| c.get("artifactIds").cloned())
+                .unwrap_or_else(|| Value::Array(Vec::new()));
+            obj.insert("artifactIds".to_string(), members);
+        }
+        let created_at = stored
+            .and_then(|c| c.get("createdAt").cloned())
+            .unwrap_or_else(|| Value::String(now.clone()));
+        obj.insert("createdAt".to_string(), created_at);
+        obj.insert("updatedAt".to_string(), Value::String(now));
+    }
+
+    match existing {
+        Some(i) => collections[i] = new_collection,
+        None => collections.push(new_collection),
+    }
+    write_collections(&app_handle, &collections).await?;
+    storage_events::emit(&app_handle, StorageEvent::CollectionUpdated(&id));
+
+    audit_log::record(&app_handle, "save_collection", "collection", &[&id]).await;
+
+    Ok(())
+}
+
+/// Delete a collection. Its sub-collections move up to its parent; the
+/// artifacts themselves stay in the library.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_collection(
+    app_handle: tauri::AppHandle,
+    collection_id: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut collections = read_collections(&app_handle).await?;
+    let Ok(index) = position(&collections, &collection_id) else {
+        return Ok(());
+    };
+    let removed = collections.remove(index);
+    let new_parent = removed.get("parentId").cloned().unwrap_or(Value::Null);
+
+    for child in collections
+        .iter_mut()
+        .filter(|c| parent_id(c) == Some(collection_id.as_str()))
+    {
+        if let Some(obj) = child.as_object_mut() {
+            obj.insert("parentId".to_string(), new_parent.clone());
+        }
+    }
+    write_collections(&app_handle, &collections).await?;
+    storage_events::emit(&app_handle, StorageEvent::CollectionDeleted(&collection_id));
+
+    audit_log::record(&app_handle, "delete_collection", "collection", &[&collection_id]).await;
+
+    Ok(())
+}
+
+/// Add artifacts to a collection. Returns the updated collection.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn add_to_collection(
+    app_handle: tauri::AppHandle,
+    collection_id: String,
+    artifact_ids: Vec<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let updated = update_membership(&app_handle, &collection_id, &artifact_ids, true).await?;
+
+    audit_log::record(&app_handle, "add_to_collection", "collection", &[&collection_id]).await;
+
+    Ok(updated.to_string())
+}
+
+/// Remove artifacts from a collection. Returns the updated collection.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn remove_from_collection(
+    app_handle: tauri::AppHandle,
+    collection_id: String,
+    artifact_ids: Vec<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let updated = update_membership(&app_handle, &collection_id, &artifact_ids, false).await?;
+
+    audit_log::record(
+        &app_handle,
+        "remove_from_collection",
+        "collection",
+        &[&collection_id],
+    )
+    .await;
+
+    Ok(updated.to_string())
+}
+
+/// Get the IDs of the collections an artifact belongs to
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_artifact_collections(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+) -> Result<String, String> {
+    let collections = read_collections(&app_handle).await?;
+    let ids: Vec<&str> = collections
+        .iter()
+        .filter(|c| {
+            c.get("artifactIds")
+                .and_then(|v| v.as_array())
+                .is_some_and(|arr| arr.iter().any(|v| v.as_str() == Some(&artifact_id)))
+        })
+        .filter_map(collection_id)
+        .collect();
+    serde_json::to_string(&ids).map_err(|e| format!("Failed to serialize collections: {}", e))
+}
@@ -0,0 +1,87 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::commands::http_client;
+
+/// Event sent when the internet becomes reachable
+pub(crate) const ONLINE_EVENT: &str = "network://online";
+
+/// Event sent when the internet stops being reachable
+pub(crate) const OFFLINE_EVENT: &str = "network://offline";
+
+// How often the monitor checks
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// Short so a check on a dead network doesn't hang the caller
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Managed state holding the result of the last connectivity check
+#[derive(Default)]
+pub struct ConnectivityState {
+    online: Mutex<Option<bool>>,
+}
+
+// Helper to check whether the internet can be reached, through the proxy
+// if one is configured
+async fn probe() -> bool {
+    let client = match http_client::builder().timeout(CHECK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to create HTTP client");
+            return false;
+        }
+    };
+    match client.get(http_client::CHECK_URL).send().await {
+        Ok(response) => response.status().is_success(),
+        Err(e) => {
+            tracing::debug!(error = %e, "Connectivity check failed");
+            false
+        }
+    }
+}
+
+// Helper to check connectivity, record the result, and send an event when
+// it changed since the last check
+async fn check(app_handle: &tauri::AppHandle) -> bool {
+    let online = probe().await;
+    let previous = {
+        let state = app_handle.state::<ConnectivityState>();
+        let mut last = state.online.lock().unwrap();
+        last.replace(online)
+    };
+    if previous != Some(online) {
+        tracing::info!(online, "Network connectivity changed");
+        let event = if online { ONLINE_EVENT } else { OFFLINE_EVENT };
+        if let Err(e) = app_handle.emit(event, ()) {
+            tracing::warn!(error = %e, "Failed to emit connectivity event");
+        }
+    }
+    online
+}
+
+/// Start the background monitor that checks connectivity every 30 seconds
+/// and sends `network://online` or `network://offline` when it changes. The
+/// first check always sends one, so listeners learn the starting state.
+pub fn start_monitor(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            check(&app_handle).await;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// ============================================
+// Connectivity Commands
+// ============================================
+
+/// Check now whether the internet can be reached, so online-only features
+/// can disable themselves instead of timing out. Sends a connectivity event
+/// if the answer changed.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn is_online(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    Ok(check(&app_handle).await)
+}
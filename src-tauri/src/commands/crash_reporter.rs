@@ -0,0 +1,343 @@
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+use tokio::fs;
+use tracing::field::{Field, Visit};
+use tracing::span;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+const CRASHES_DIR: &str = "crashes";
+const SESSION_FILE: &str = "session.json";
+const CRASH_FILE_PREFIX: &str = "crash-";
+// Reports the user has seen are renamed with this suffix
+const SEEN_SUFFIX: &str = ".seen.json";
+
+// Recent commands and errors kept for crash context
+const CONTEXT_CAPACITY: usize = 20;
+// Crash reports kept on disk before the oldest are deleted
+const MAX_CRASH_REPORTS: usize = 10;
+
+// Prefix of the span targets created by `#[tracing::instrument]` on commands
+const COMMAND_TARGET_PREFIX: &str = concat!(env!("CARGO_CRATE_NAME"), "::commands::");
+
+#[derive(Default)]
+struct CrashContext {
+    recent_commands: VecDeque<Value>,
+    recent_errors: VecDeque<Value>,
+}
+
+fn push_bounded(queue: &mut VecDeque<Value>, value: Value) {
+    if queue.len() == CONTEXT_CAPACITY {
+        queue.pop_front();
+    }
+    queue.push_back(value);
+}
+
+/// Managed state shared by the crash layer and the panic hook
+pub struct CrashReporterState {
+    context: Arc<Mutex<CrashContext>>,
+    // Whether the previous run ended without a clean exit
+    previous_run_unclean: Mutex<bool>,
+}
+
+/// Tracing layer that remembers recent commands and command errors
+pub struct CrashContextLayer {
+    context: Arc<Mutex<CrashContext>>,
+}
+
+// Collects an event's fields into a single line
+#[derive(Default)]
+struct FieldCollector(String);
+
+impl Visit for FieldCollector {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        let _ = write!(self.0, "{}={:?}", field.name(), value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        let _ = write!(self.0, "{}={}", field.name(), value);
+    }
+}
+
+impl<S> Layer<S> for CrashContextLayer
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let entry = serde_json::json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "command": span.name(),
+        });
+        push_bounded(&mut self.context.lock().unwrap().recent_commands, entry);
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        let command = ctx
+            .event_scope(event)
+            .and_then(|scope| scope.from_root().next())
+            .map(|span| span.name());
+
+        let mut fields = FieldCollector::default();
+        event.record(&mut fields);
+        let entry = serde_json::json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "command": command,
+            "target": event.metadata().target(),
+            "message": fields.0,
+        });
+        push_bounded(&mut self.context.lock().unwrap().recent_errors, entry);
+    }
+}
+
+/// Create the crash context layer (to install in the subscriber) and the
+/// state the panic hook reads from. The layer only sees command spans and
+/// error events.
+pub fn layer<S>() -> (impl Layer<S>, CrashReporterState)
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    let context = Arc::new(Mutex::new(CrashContext::default()));
+    let layer = CrashContextLayer {
+        context: context.clone(),
+    }
+    .with_filter(tracing_subscriber::filter::filter_fn(|metadata| {
+        if metadata.is_span() {
+            metadata.target().starts_with(COMMAND_TARGET_PREFIX)
+        } else {
+            *metadata.level() == tracing::Level::ERROR
+        }
+    }));
+    let state = CrashReporterState {
+        context,
+        previous_run_unclean: Mutex::new(false),
+    };
+    (layer, state)
+}
+
+// Helper to get the crashes directory
+fn get_crashes_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(CRASHES_DIR))
+}
+
+// Helper to write the session marker (sync: also used while exiting)
+fn write_session_marker(crashes_dir: &Path, clean_exit: bool) -> std::io::Result<()> {
+    std::fs::create_dir_all(crashes_dir)?;
+    let marker = serde_json::json!({
+        "cleanExit": clean_exit,
+        "updatedAt": chrono::Utc::now().to_rfc3339(),
+    });
+    std::fs::write(crashes_dir.join(SESSION_FILE), marker.to_string())
+}
+
+// Helper to build and write a crash report from inside the panic hook
+fn write_crash_report(
+    crashes_dir: &Path,
+    app_version: &str,
+    context: &Mutex<CrashContext>,
+    info: &std::panic::PanicHookInfo<'_>,
+) -> std::io::Result<()> {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Unknown panic".to_string());
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+
+    // The panic may have happened while the context lock was held
+    let (recent_commands, recent_errors) = match context.try_lock() {
+        Ok(ctx) => (
+            ctx.recent_commands.iter().cloned().collect::<Vec<_>>(),
+            ctx.recent_errors.iter().cloned().collect::<Vec<_>>(),
+        ),
+        Err(_) => (Vec::new(), Vec::new()),
+    };
+
+    let now = chrono::Utc::now();
+    let report = serde_json::json!({
+        "timestamp": now.to_rfc3339(),
+        "appVersion": app_version,
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "thread": std::thread::current().name().unwrap_or("unnamed"),
+        "message": message,
+        "location": location,
+        "backtrace": std::backtrace::Backtrace::force_capture().to_string(),
+        "recentCommands": recent_commands,
+        "recentErrors": recent_errors,
+    });
+
+    std::fs::create_dir_all(crashes_dir)?;
+    let file_name = format!(
+        "{}{}.json",
+        CRASH_FILE_PREFIX,
+        now.format("%Y%m%d-%H%M%S-%3f")
+    );
+    let content = serde_json::to_string_pretty(&report).unwrap_or_default();
+    std::fs::write(crashes_dir.join(file_name), content)
+}
+
+// Helper to list crash report files, newest first
+fn list_crash_reports(crashes_dir: &Path) -> Vec<PathBuf> {
+    let mut reports: Vec<PathBuf> = std::fs::read_dir(crashes_dir)
+        .map(|dir| {
+            dir.filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(CRASH_FILE_PREFIX) && n.ends_with(".json"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Names are timestamps, so they sort chronologically
+    reports.sort();
+    reports.reverse();
+    reports
+}
+
+/// Install the panic hook, detect whether the previous run crashed, and mark
+/// this run as in progress. Called once during app setup, after logging.
+pub fn init(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let crashes_dir = get_crashes_dir(app_handle)?;
+    let state = app_handle.state::<CrashReporterState>();
+
+    // A marker left without `cleanExit` means the last run died
+    let previous_unclean = std::fs::read_to_string(crashes_dir.join(SESSION_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .is_some_and(|marker| marker.get("cleanExit").and_then(|v| v.as_bool()) == Some(false));
+    *state.previous_run_unclean.lock().unwrap() = previous_unclean;
+    if previous_unclean {
+        tracing::warn!("Previous run did not exit cleanly");
+    }
+
+    write_session_marker(&crashes_dir, false)
+        .map_err(|e| format!("Failed to write session marker: {}", e))?;
+
+    // Drop old reports
+    for old in list_crash_reports(&crashes_dir).into_iter().skip(MAX_CRASH_REPORTS) {
+        let _ = std::fs::remove_file(old);
+    }
+
+    let context = state.context.clone();
+    let app_version = app_handle.package_info().version.to_string();
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        tracing::error!(panic = %info, "Application panicked");
+        if let Err(e) = write_crash_report(&crashes_dir, &app_version, &context, info) {
+            eprintln!("Failed to write crash report: {}", e);
+        }
+        default_hook(info);
+    }));
+
+    Ok(())
+}
+
+/// Record that the app is exiting normally. Called from the exit event.
+pub fn mark_clean_exit(app_handle: &tauri::AppHandle) {
+    let result = get_crashes_dir(app_handle)
+        .and_then(|dir| write_session_marker(&dir, true).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        tracing::warn!(error = %e, "Failed to record clean exit");
+    }
+}
+
+// ============================================
+// Crash Report Commands
+// ============================================
+
+/// Get the newest crash report the user hasn't dismissed (or null), and
+/// whether the previous run ended unexpectedly
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_last_crash_report(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let crashes_dir = get_crashes_dir(&app_handle)?;
+    let previous_unclean = *app_handle
+        .state::<CrashReporterState>()
+        .previous_run_unclean
+        .lock()
+        .unwrap();
+
+    let unseen = list_crash_reports(&crashes_dir).into_iter().find(|path| {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| !n.ends_with(SEEN_SUFFIX))
+    });
+
+    let report = match unseen {
+        Some(path) => {
+            let content = fs::read_to_string(&path)
+                .await
+                .map_err(|e| format!("Failed to read crash report: {}", e))?;
+            let mut report: Value = serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid crash report: {}", e))?;
+            if let Some(obj) = report.as_object_mut() {
+                let report_id = path.file_stem().map(|s| s.to_string_lossy().to_string());
+                obj.insert("reportId".to_string(), serde_json::json!(report_id));
+            }
+            Some(report)
+        }
+        None => None,
+    };
+
+    let response = serde_json::json!({
+        "previousRunUnclean": previous_unclean,
+        "report": report,
+    });
+    Ok(response.to_string())
+}
+
+/// Mark a crash report as seen so it isn't offered again
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn dismiss_crash_report(
+    app_handle: tauri::AppHandle,
+    report_id: String,
+) -> Result<(), String> {
+    if !report_id.starts_with(CRASH_FILE_PREFIX) || report_id.contains(['/', '\\', '.']) {
+        return Err(format!("Invalid crash report ID: {}", report_id));
+    }
+
+    let crashes_dir = get_crashes_dir(&app_handle)?;
+    let report_path = crashes_dir.join(format!("{}.json", report_id));
+    if !report_path.exists() {
+        return Ok(());
+    }
+
+    fs::rename(
+        &report_path,
+        crashes_dir.join(format!("{}{}", report_id, SEEN_SUFFIX)),
+    )
+    .await
+    .map_err(|e| format!("Failed to dismiss crash report: {}", e))?;
+
+    // The user has acknowledged the last crash
+    *app_handle
+        .state::<CrashReporterState>()
+        .previous_run_unclean
+        .lock()
+        .unwrap() = false;
+
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use git2::{IndexAddOption, Oid, Repository, Signature};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::commands::{
+    app_lock, audit_log, image_cache, index_cache, local_image, logging, session_mode,
+    settings_storage,
+};
+
+// How long to wait after a change before committing, so a burst of changes
+// (and the index writes they delay) lands in one commit
+const COMMIT_DELAY: Duration = Duration::from_secs(5);
+
+// Author of history commits
+const AUTHOR_NAME: &str = "TA Teachers Assistant";
+const AUTHOR_EMAIL: &str = "history@teachers-assistant.local";
+
+const DEFAULT_LIMIT: usize = 100;
+
+/// Directory of the history repository, inside the app data directory
+pub(crate) const HISTORY_DIR: &str = ".git";
+
+/// Managed state collecting the changes waiting to be committed, and
+/// serializing commits and checkouts
+#[derive(Default)]
+pub struct DataHistoryState {
+    pending: Mutex<Vec<String>>,
+    lock: tokio::sync::Mutex<()>,
+}
+
+// Helper to get the app data directory, where the repository lives
+fn get_app_data_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+// Helper to describe an audited change, e.g. "Save artifact (a1b2c3)"
+fn change_message(command: &str, entity_type: &str, entity_ids: &[&str]) -> String {
+    let words = command.replace('_', " ");
+    let mut message: String = words
+        .chars()
+        .take(1)
+        .flat_map(char::to_uppercase)
+        .chain(words.chars().skip(1))
+        .collect();
+    if entity_ids.is_empty() {
+        message.push_str(&format!(" ({})", entity_type));
+    } else {
+        message.push_str(&format!(" ({} {})", entity_type, entity_ids.join(", ")));
+    }
+    message
+}
+
+// Helper to open the history repository, creating it on first use. Logs,
+// image models, and cached images (large, and downloaded or generated
+// again) are kept out through the repository's exclude file, which isn't
+// versioned, so restoring a snapshot can't change it.
+fn open_or_init(app_data_dir: &Path, logs_dir_name: &str) -> Result<Repository, git2::Error> {
+    let repo = match Repository::open(app_data_dir) {
+        Ok(repo) => repo,
+        Err(_) => Repository::init(app_data_dir)?,
+    };
+    let exclude: String = [logs_dir_name, local_image::MODELS_DIR, image_cache::CACHE_DIR]
+        .iter()
+        .map(|dir| format!("/{}/\n", dir))
+        .collect();
+    let exclude_path = repo.path().join("info").join("exclude");
+    if std::fs::read_to_string(&exclude_path).ok().as_deref() != Some(exclude.as_str()) {
+        let written = std::fs::create_dir_all(repo.path().join("info"))
+            .and_then(|_| std::fs::write(&exclude_path, exclude));
+        if let Err(e) = written {
+            tracing::warn!(error = %e, "Failed to write history exclude file");
+        }
+    }
+    Ok(repo)
+}
+
+// Helper to commit everything in the app data directory. Returns the new
+// commit's ID, or None when nothing changed.
+fn commit_all(repo: &Repository, message: &str) -> Result<Option<Oid>, git2::Error> {
+    let mut index = repo.index()?;
+    index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+    // Picks up deleted files, which add_all leaves in the index
+    index.update_all(["*"], None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    if parent.as_ref().is_some_and(|p| p.tree_id() == tree.id()) {
+        return Ok(None);
+    }
+    let author = Signature::now(AUTHOR_NAME, AUTHOR_EMAIL)?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &author, &author, message, &tree, &parents)
+        .map(Some)
+}
+
+// Helper to commit the pending changes, one commit for the batch
+async fn commit_pending(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let state = app_handle.state::<DataHistoryState>();
+    let _guard = state.lock.lock().await;
+    let messages = std::mem::take(&mut *state.pending.lock().unwrap());
+    let Some(first) = messages.first() else {
+        return Ok(());
+    };
+    let message = match messages.len() {
+        1 => first.clone(),
+        n => format!("{} and {} more changes\n\n- {}", first, n - 1, messages.join("\n- ")),
+    };
+
+    // Delayed index writes belong in this commit
+    index_cache::flush(app_handle).await?;
+    let app_data_dir = get_app_data_dir(app_handle)?;
+    let logs_dir = logging::get_logs_dir(app_handle)?;
+    tokio::task::spawn_blocking(move || {
+        let logs_dir_name = logs_dir.file_name().unwrap_or_default().to_string_lossy();
+        let repo = open_or_init(&app_data_dir, &logs_dir_name)?;
+        commit_all(&repo, &message)
+    })
+    .await
+    .map_err(|e| format!("History task failed: {}", e))?
+    .map_err(|e| format!("Failed to commit history: {}", e))?;
+    Ok(())
+}
+
+/// Note a completed change for the history, when it is enabled. The change
+/// is committed a few seconds later together with any that follow it.
+/// Called by `audit_log::record` for every audited change.
+pub(crate) async fn note_change(
+    app_handle: &tauri::AppHandle,
+    command: &str,
+    entity_type: &str,
+    entity_ids: &[&str],
+) {
+    let enabled = settings_storage::load_settings(app_handle)
+        .await
+        .is_ok_and(|settings| settings.history.enabled);
+    if !enabled {
+        return;
+    }
+
+    let message = change_message(command, entity_type, entity_ids);
+    let first = {
+        let state = app_handle.state::<DataHistoryState>();
+        let mut pending = state.pending.lock().unwrap();
+        pending.push(message);
+        pending.len() == 1
+    };
+    // The first pending change schedules the commit for the whole batch
+    if first {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(COMMIT_DELAY).await;
+            if let Err(e) = commit_pending(&app_handle).await {
+                tracing::warn!(error = %e, "Failed to record data history");
+            }
+        });
+    }
+}
+
+/// Delete the whole history, so data that was just erased can't be read
+/// back from it or restored by `checkout_snapshot`. When history is still
+/// enabled it starts again from the current data. Called after a learner
+/// is deleted or anonymized; `reason` goes in the new first commit.
+pub(crate) async fn purge(app_handle: &tauri::AppHandle, reason: &str) -> Result<(), String> {
+    let state = app_handle.state::<DataHistoryState>();
+    let _guard = state.lock.lock().await;
+    // The new first commit covers changes still waiting to be committed
+    state.pending.lock().unwrap().clear();
+
+    let app_data_dir = get_app_data_dir(app_handle)?;
+    let history_dir = app_data_dir.join(HISTORY_DIR);
+    if history_dir.exists() {
+        tokio::fs::remove_dir_all(&history_dir)
+            .await
+            .map_err(|e| format!("Failed to delete data history: {}", e))?;
+        tracing::warn!(reason, "Deleted data history");
+    }
+
+    let enabled = settings_storage::load_settings(app_handle)
+        .await
+        .is_ok_and(|settings| settings.history.enabled);
+    if !enabled {
+        return Ok(());
+    }
+    index_cache::flush(app_handle).await?;
+    let logs_dir = logging::get_logs_dir(app_handle)?;
+    let message = format!("Start history again after {}", reason);
+    tokio::task::spawn_blocking(move || {
+        let logs_dir_name = logs_dir.file_name().unwrap_or_default().to_string_lossy();
+        let repo = open_or_init(&app_data_dir, &logs_dir_name)?;
+        commit_all(&repo, &message)
+    })
+    .await
+    .map_err(|e| format!("History task failed: {}", e))?
+    .map_err(|e| format!("Failed to commit history: {}", e))?;
+    Ok(())
+}
+
+// ============================================
+// Data History Commands
+// ============================================
+
+/// List the history of the app data, newest first, as `[{commitId,
+/// message, time}]` (at most `limit`, default 100). Empty until history is
+/// enabled in settings and something changes.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_history(
+    app_handle: tauri::AppHandle,
+    limit: Option<usize>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let app_data_dir = get_app_data_dir(&app_handle)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    let history = tokio::task::spawn_blocking(move || -> Result<Vec<serde_json::Value>, _> {
+        let Ok(repo) = Repository::open(&app_data_dir) else {
+            return Ok(Vec::new());
+        };
+        if repo.head().is_err() {
+            return Ok(Vec::new());
+        }
+        let mut walk = repo.revwalk()?;
+        walk.push_head()?;
+        let mut history = Vec::new();
+        for oid in walk.take(limit) {
+            let commit = repo.find_commit(oid?)?;
+            let time = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+                .map(|t| t.to_rfc3339());
+            history.push(serde_json::json!({
+                "commitId": commit.id().to_string(),
+                "message": commit.message().unwrap_or("").trim_end(),
+                "time": time,
+            }));
+        }
+        Ok(history)
+    })
+    .await
+    .map_err(|e| format!("History task failed: {}", e))?
+    .map_err(|e: git2::Error| format!("Failed to read history: {}", e))?;
+
+    serde_json::to_string(&history).map_err(|e| format!("Failed to serialize history: {}", e))
+}
+
+/// Put the app data back the way it was at a commit from `list_history`.
+/// Changes not yet committed are committed first, and the restore is
+/// itself committed, so it can be undone the same way. Logs are untouched.
+/// Returns the new commit's ID; restart the app afterwards.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn checkout_snapshot(
+    app_handle: tauri::AppHandle,
+    commit_id: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let oid = Oid::from_str(&commit_id).map_err(|_| format!("Invalid commit ID: {}", commit_id))?;
+    commit_pending(&app_handle).await?;
+
+    let state = app_handle.state::<DataHistoryState>();
+    let _guard = state.lock.lock().await;
+    index_cache::flush(&app_handle).await?;
+    let app_data_dir = get_app_data_dir(&app_handle)?;
+    let logs_dir = logging::get_logs_dir(&app_handle)?;
+    let head = tokio::task::spawn_blocking(move || -> Result<Oid, git2::Error> {
+        // Refreshes the exclude file, so the checkout leaves excluded files be
+        let logs_dir_name = logs_dir.file_name().unwrap_or_default().to_string_lossy();
+        let repo = open_or_init(&app_data_dir, &logs_dir_name)?;
+        let snapshot = repo.find_commit(oid)?;
+        // Record anything changed outside audited commands before replacing it
+        commit_all(&repo, "Save changes before restoring a snapshot")?;
+
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout.force().remove_untracked(true);
+        repo.checkout_tree(snapshot.as_object(), Some(&mut checkout))?;
+        let summary = snapshot.summary().unwrap_or("");
+        let short_id = &commit_id[..commit_id.len().min(8)];
+        commit_all(&repo, &format!("Restore snapshot {} ({})", short_id, summary))?;
+        // Unchanged when the snapshot matches the current data
+        let head = repo.head()?.peel_to_commit()?.id();
+        Ok(head)
+    })
+    .await
+    .map_err(|e| format!("History task failed: {}", e))?
+    .map_err(|e| format!("Failed to restore snapshot: {}", e))?;
+
+    tracing::warn!(commit = %oid, "Restored data history snapshot");
+    audit_log::record(&app_handle, "checkout_snapshot", "app_data", &[&oid.to_string()]).await;
+
+    Ok(head.to_string())
+}
@@ -0,0 +1,122 @@
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::commands::{project_storage, tray};
+
+/// URL scheme the app handles, as in `teachersassistant://artifact/{id}`.
+/// Must match `plugins.deep-link.desktop.schemes` in `tauri.conf.json`.
+pub(crate) const SCHEME: &str = "teachersassistant";
+
+/// Event telling the frontend which view a link should open
+pub(crate) const OPEN_EVENT: &str = "deep-link://open";
+
+/// Where a link points, sent to the frontend as `{"view": ..., ...}`
+#[derive(Clone, Serialize)]
+#[serde(tag = "view", rename_all = "camelCase", rename_all_fields = "camelCase")]
+enum Route {
+    Artifact { artifact_id: String },
+    Project { project_id: String },
+    /// A file to offer for import; the frontend asks before importing it
+    Import { path: String },
+}
+
+/// Managed state holding the last link the frontend hasn't picked up yet,
+/// e.g. the one the app was launched with
+#[derive(Default)]
+pub struct DeepLinkState {
+    pending: Mutex<Option<Route>>,
+}
+
+// Helper to read an ID from the path of a link like `artifact/{id}`
+fn id_from_path(url: &tauri::Url) -> Result<String, String> {
+    let id = url.path().trim_matches('/');
+    // Artifact IDs follow the same file-name-safe rules as project IDs
+    if !project_storage::is_valid_project_id(id) {
+        return Err(format!("Invalid ID in link: {}", url));
+    }
+    Ok(id.to_string())
+}
+
+// Helper to work out where a link points
+fn parse(url: &tauri::Url) -> Result<Route, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Unsupported link: {}", url));
+    }
+    match url.host_str() {
+        Some("artifact") => Ok(Route::Artifact {
+            artifact_id: id_from_path(url)?,
+        }),
+        Some("project") => Ok(Route::Project {
+            project_id: id_from_path(url)?,
+        }),
+        Some("import") => {
+            let path = url
+                .query_pairs()
+                .find(|(key, _)| key == "path")
+                .map(|(_, value)| value.into_owned())
+                .ok_or_else(|| format!("Import link has no path: {}", url))?;
+            if !Path::new(&path).is_absolute() || !Path::new(&path).is_file() {
+                return Err(format!("File not found: {}", path));
+            }
+            Ok(Route::Import { path })
+        }
+        _ => Err(format!("Unsupported link: {}", url)),
+    }
+}
+
+// Helper to route opened links: bring the window forward and tell the
+// frontend where to go, keeping the last one in case it isn't listening yet
+fn handle_urls(app_handle: &tauri::AppHandle, urls: Vec<tauri::Url>) {
+    for url in urls {
+        let route = match parse(&url) {
+            Ok(route) => route,
+            Err(e) => {
+                tracing::warn!(error = %e, "Ignoring deep link");
+                continue;
+            }
+        };
+        *app_handle.state::<DeepLinkState>().pending.lock().unwrap() = Some(route.clone());
+        tray::show_main_window(app_handle);
+        if let Err(e) = app_handle.emit(OPEN_EVENT, &route) {
+            tracing::warn!(error = %e, "Failed to emit deep link");
+        }
+    }
+}
+
+/// Start handling `teachersassistant://` links, including the one the app
+/// was launched with. Called from the app's setup.
+pub fn init(app_handle: &tauri::AppHandle) {
+    let deep_link = app_handle.deep_link();
+
+    // Installers register the scheme; this covers portable and dev builds
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = deep_link.register_all() {
+        tracing::warn!(error = %e, "Failed to register link scheme");
+    }
+
+    match deep_link.get_current() {
+        Ok(Some(urls)) => handle_urls(app_handle, urls),
+        Ok(None) => {}
+        Err(e) => tracing::warn!(error = %e, "Failed to read launch link"),
+    }
+
+    let listener_handle = app_handle.clone();
+    deep_link.on_open_url(move |event| handle_urls(&listener_handle, event.urls()));
+}
+
+// ============================================
+// Deep Link Commands
+// ============================================
+
+/// Take the last opened link the frontend hasn't handled yet, as a route
+/// like `{"view": "artifact", "artifactId": "..."}`, or `null`. Call on
+/// startup; links opened later also arrive as `deep-link://open` events.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn take_pending_deep_link(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let route = app_handle.state::<DeepLinkState>().pending.lock().unwrap().take();
+    serde_json::to_string(&route).map_err(|e| format!("Failed to serialize link: {}", e))
+}
@@ -0,0 +1,315 @@
+use serde_json::Value;
+use std::path::Path;
+use tokio::fs;
+
+use crate::commands::{
+    app_lock, audit_log, design_pack_storage, learner_storage, library_storage, project_storage,
+    session_mode,
+};
+
+/// Every demo record ID starts with this prefix so the set can be removed cleanly
+const DEMO_ID_PREFIX: &str = "demo-";
+
+// Helper to check whether a record's ID field marks it as demo data
+fn is_demo(value: &Value, id_key: &str) -> bool {
+    value
+        .get(id_key)
+        .and_then(|v| v.as_str())
+        .is_some_and(|id| id.starts_with(DEMO_ID_PREFIX))
+}
+
+// Helper to read a JSON array file, treating a missing or invalid file as empty
+async fn read_array(path: &Path) -> Vec<Value> {
+    match fs::read_to_string(path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()),
+        Err(_) => Vec::new(),
+    }
+}
+
+// Helper to write a JSON array file, creating its directory if needed
+async fn write_array(path: &Path, items: &[Value]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(items)
+        .map_err(|e| format!("Failed to serialize demo data: {}", e))?;
+    fs::write(path, content)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// Helper to drop demo records from an array file. Returns how many were removed.
+async fn remove_demo_entries(path: &Path, id_key: &str) -> Result<usize, String> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    let mut items = read_array(path).await;
+    let before = items.len();
+    items.retain(|item| !is_demo(item, id_key));
+    let removed = before - items.len();
+    if removed > 0 {
+        write_array(path, &items).await?;
+    }
+    Ok(removed)
+}
+
+// Helper to append records to an array file
+async fn append_entries(path: &Path, new_items: Vec<Value>) -> Result<(), String> {
+    let mut items = read_array(path).await;
+    items.extend(new_items);
+    write_array(path, &items).await
+}
+
+fn demo_learners(now: &str) -> Vec<Value> {
+    vec![
+        serde_json::json!({
+            "learnerId": "demo-learner-ava",
+            "displayName": "Ava (Demo)",
+            "grade": "1",
+            "avatarEmoji": "🦊",
+            "preferences": {
+                "favoriteSubjects": ["math", "science"],
+                "sessionDuration": 30,
+                "visualLearner": true
+            },
+            "adultConfidence": "intermediate",
+            "createdAt": now,
+            "updatedAt": now
+        }),
+        serde_json::json!({
+            "learnerId": "demo-learner-leo",
+            "displayName": "Leo (Demo)",
+            "grade": "K",
+            "avatarEmoji": "🐢",
+            "preferences": {
+                "favoriteSubjects": ["reading"],
+                "sessionDuration": 15,
+                "visualLearner": false
+            },
+            "adultConfidence": "novice",
+            "createdAt": now,
+            "updatedAt": now
+        }),
+    ]
+}
+
+fn demo_design_pack(now: &str) -> Value {
+    serde_json::json!({
+        "packId": "demo-pack-bright",
+        "name": "Bright & Playful (Demo)",
+        "description": "Sample design pack with a cheerful palette",
+        "items": [
+            {
+                "itemId": "demo-pack-item-1",
+                "type": "text",
+                "title": "Style notes",
+                "content": "Large friendly fonts, rounded boxes, plenty of white space."
+            }
+        ],
+        "parsedSummary": {
+            "palette": ["#FF8A5B", "#FFD166", "#06D6A0", "#118AB2"],
+            "tone": "playful",
+            "typography": "rounded sans-serif",
+            "styleHints": ["large headings", "simple icons"]
+        },
+        "createdAt": now,
+        "updatedAt": now
+    })
+}
+
+// (artifact ID, type, title, body)
+const DEMO_ARTIFACTS: &[(&str, &str, &str, &str)] = &[
+    (
+        "demo-artifact-student-page",
+        "student_page",
+        "Counting to 20 - Student Page",
+        "<h1>Count the apples</h1><p>How many apples are in each basket?</p>",
+    ),
+    (
+        "demo-artifact-teacher-script",
+        "teacher_script",
+        "Counting to 20 - Teacher Script",
+        "<h1>Teacher Script</h1><p>Start by counting aloud together from 1 to 20.</p>",
+    ),
+    (
+        "demo-artifact-answer-key",
+        "answer_key",
+        "Counting to 20 - Answer Key",
+        "<h1>Answer Key</h1><ol><li>7</li><li>12</li><li>18</li></ol>",
+    ),
+    (
+        "demo-artifact-lesson-plan",
+        "lesson_plan",
+        "Counting to 20 - Lesson Plan",
+        "<h1>Lesson Plan</h1><p>Objective: count objects up to 20.</p>",
+    ),
+];
+
+const DEMO_PROJECT_ID: &str = "demo-project-counting";
+const DEMO_OBJECTIVE_ID: &str = "K.MATH.COUNT.1_20";
+
+fn demo_project(now: &str) -> Value {
+    let artifact_ids: Vec<&str> = DEMO_ARTIFACTS.iter().map(|(id, ..)| *id).collect();
+    serde_json::json!({
+        "projectId": DEMO_PROJECT_ID,
+        "type": "learning_path",
+        "name": "Counting to 20 (Demo)",
+        "description": "Sample learning path project",
+        "grade": "K",
+        "gradeBand": "K",
+        "subjectFocus": ["math"],
+        "learnerId": "demo-learner-leo",
+        "linkedObjectiveIds": [DEMO_OBJECTIVE_ID],
+        "defaultDesignPackId": "demo-pack-bright",
+        "artifactIds": artifact_ids,
+        "status": "completed",
+        "lastActivityDate": now,
+        "createdAt": now,
+        "updatedAt": now
+    })
+}
+
+// Helper to remove all demo records. Returns counts per entity type.
+async fn remove_all_demo_data(app_handle: &tauri::AppHandle) -> Result<Value, String> {
+    // Learners (profiles plus their data directories)
+    let profiles_path = learner_storage::get_profiles_path(app_handle)?;
+    let demo_learner_ids: Vec<String> = read_array(&profiles_path)
+        .await
+        .iter()
+        .filter(|p| is_demo(p, "learnerId"))
+        .filter_map(|p| p.get("learnerId").and_then(|v| v.as_str()).map(String::from))
+        .collect();
+    let learners = remove_demo_entries(&profiles_path, "learnerId").await?;
+    for learner_id in &demo_learner_ids {
+        let learner_dir = learner_storage::get_learner_dir(app_handle, learner_id)?;
+        if learner_dir.exists() {
+            fs::remove_dir_all(&learner_dir)
+                .await
+                .map_err(|e| format!("Failed to delete learner data: {}", e))?;
+        }
+    }
+
+    let mut packs = design_pack_storage::read_packs(app_handle).await?;
+    let before = packs.len();
+    packs.retain(|p| !is_demo(p, "packId"));
+    let design_packs = before - packs.len();
+    if design_packs > 0 {
+        design_pack_storage::write_packs(app_handle, packs).await?;
+    }
+
+    // Projects (index entries plus project files)
+    let mut projects = 0;
+    for project in project_storage::read_index(app_handle).await? {
+        if !is_demo(&project, "projectId") {
+            continue;
+        }
+        if let Some(project_id) = project.get("projectId").and_then(|v| v.as_str()) {
+            if project_storage::remove_project(app_handle, project_id).await? {
+                projects += 1;
+            }
+        }
+    }
+
+    // Artifacts (index entries plus artifact files)
+    let mut entries = library_storage::read_index_entries(app_handle).await?;
+    let before = entries.len();
+    entries.retain(|a| !is_demo(a, "artifactId"));
+    let artifacts = before - entries.len();
+    if artifacts > 0 {
+        library_storage::write_index_entries(app_handle, entries).await?;
+    }
+    let artifacts_dir = library_storage::get_artifacts_dir(app_handle)?;
+    for (artifact_id, ..) in DEMO_ARTIFACTS {
+        let artifact_path = artifacts_dir.join(format!("{}.json", artifact_id));
+        if artifact_path.exists() {
+            fs::remove_file(&artifact_path)
+                .await
+                .map_err(|e| format!("Failed to delete artifact: {}", e))?;
+        }
+    }
+
+    Ok(serde_json::json!({
+        "learners": learners,
+        "designPacks": design_packs,
+        "projects": projects,
+        "artifacts": artifacts,
+    }))
+}
+
+// ============================================
+// Demo Data Commands
+// ============================================
+
+/// Populate the app with sample learners, a design pack, a project, and
+/// artifacts. Any existing demo data is replaced. Returns counts created.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn seed_demo_data(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    remove_all_demo_data(&app_handle).await?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let learners = demo_learners(&now);
+    let learner_count = learners.len();
+    for learner in &learners {
+        if let Some(learner_id) = learner.get("learnerId").and_then(|v| v.as_str()) {
+            let learner_dir = learner_storage::get_learner_dir(&app_handle, learner_id)?;
+            fs::create_dir_all(&learner_dir)
+                .await
+                .map_err(|e| format!("Failed to create learner directory: {}", e))?;
+        }
+    }
+    append_entries(&learner_storage::get_profiles_path(&app_handle)?, learners).await?;
+
+    design_pack_storage::write_pack(&app_handle, demo_design_pack(&now)).await?;
+
+    project_storage::write_project(&app_handle, &demo_project(&now)).await?;
+
+    for (i, (artifact_id, artifact_type, title, body)) in DEMO_ARTIFACTS.iter().enumerate() {
+        let artifact = serde_json::json!({
+            "artifactId": artifact_id,
+            "projectId": DEMO_PROJECT_ID,
+            "jobId": format!("demo-job-{}", i + 1),
+            "type": artifact_type,
+            "title": title,
+            "htmlContent": body,
+            "grade": "K",
+            "subject": "math",
+            "objectiveTags": [DEMO_OBJECTIVE_ID],
+            "objectiveId": DEMO_OBJECTIVE_ID,
+            "designPackId": "demo-pack-bright",
+            "createdAt": now,
+        });
+        library_storage::write_artifact(&app_handle, &artifact, &artifact.to_string()).await?;
+    }
+
+    audit_log::record(&app_handle, "seed_demo_data", "demo_data", &[]).await;
+
+    let summary = serde_json::json!({
+        "learners": learner_count,
+        "designPacks": 1,
+        "projects": 1,
+        "artifacts": DEMO_ARTIFACTS.len(),
+    });
+    Ok(summary.to_string())
+}
+
+/// Remove all demo data, leaving user-created records untouched.
+/// Returns counts removed.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn remove_demo_data(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let summary = remove_all_demo_data(&app_handle).await?;
+
+    audit_log::record(&app_handle, "remove_demo_data", "demo_data", &[]).await;
+
+    Ok(summary.to_string())
+}
@@ -0,0 +1,289 @@
+use base64::Engine;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::UriSchemeContext;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, design_pack_storage, session_mode};
+
+/// URI scheme that serves design pack assets to the webview
+pub const ASSET_SCHEME: &str = "pack-asset";
+
+// Characters escaped in each path segment of an asset URL
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'?')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`');
+
+// Helper to get the MIME type for an asset by extension
+fn mime_type(name: &str) -> &'static str {
+    let extension = Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+// Helper to validate an asset name (a relative path inside the assets dir)
+fn validate_asset_name(name: &str) -> Result<(), String> {
+    let path = Path::new(name);
+    let valid = !name.is_empty()
+        && name.len() <= 255
+        && path.components().all(|c| matches!(c, Component::Normal(_)));
+    if !valid {
+        return Err(format!("Invalid asset name: {}", name));
+    }
+    Ok(())
+}
+
+// Helper to build the URL the webview loads an asset from
+fn asset_url(pack_id: &str, name: &str) -> String {
+    let path: Vec<String> = std::iter::once(pack_id)
+        .chain(name.split('/'))
+        .map(|segment| utf8_percent_encode(segment, SEGMENT).to_string())
+        .collect();
+    // Windows webviews only load custom schemes through http://<scheme>.localhost
+    if cfg!(windows) {
+        format!("http://{}.localhost/{}", ASSET_SCHEME, path.join("/"))
+    } else {
+        format!("{}://localhost/{}", ASSET_SCHEME, path.join("/"))
+    }
+}
+
+// Helper to read a file as a data URI
+async fn data_uri(path: &Path, name: &str) -> Result<String, String> {
+    let contents = fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(contents);
+    Ok(format!("data:{};base64,{}", mime_type(name), encoded))
+}
+
+// Helper to map a request path (`/<packId>/<asset name>`) to a file on disk
+fn resolve_request_path(
+    app_handle: &tauri::AppHandle,
+    path: &str,
+) -> Result<(PathBuf, String), String> {
+    let decoded = percent_decode_str(path.trim_start_matches('/'))
+        .decode_utf8()
+        .map_err(|_| "Invalid asset path".to_string())?;
+    let (pack_id, name) = decoded.split_once('/').ok_or("Invalid asset path")?;
+    validate_asset_name(name)?;
+    let assets_dir = design_pack_storage::get_assets_dir(app_handle, pack_id)?;
+    Ok((assets_dir.join(name), name.to_string()))
+}
+
+/// The file behind a `pack-asset://` (or `http://pack-asset.localhost`)
+/// URL, for rendering outside the webview
+pub(crate) fn resolve_url(app_handle: &tauri::AppHandle, url: &str) -> Option<PathBuf> {
+    let path = url
+        .strip_prefix("pack-asset://localhost")
+        .or_else(|| url.strip_prefix("http://pack-asset.localhost"))?;
+    resolve_request_path(app_handle, path).ok().map(|(path, _)| path)
+}
+
+/// Serve `pack-asset://localhost/<packId>/<asset name>` requests from the
+/// pack's assets directory. Registered as a URI scheme protocol at startup.
+pub fn handle_asset_request(
+    ctx: UriSchemeContext<'_, tauri::Wry>,
+    request: Request<Vec<u8>>,
+) -> Response<Vec<u8>> {
+    let resolved = resolve_request_path(ctx.app_handle(), request.uri().path());
+    let response = resolved.and_then(|(path, name)| {
+        let contents = std::fs::read(&path).map_err(|_| format!("Asset not found: {}", name))?;
+        Response::builder()
+            .header(header::CONTENT_TYPE, mime_type(&name))
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .body(contents)
+            .map_err(|e| format!("Failed to build response: {}", e))
+    });
+
+    response.unwrap_or_else(|e| {
+        tracing::debug!(error = %e, "Design pack asset request failed");
+        Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Vec::new())
+            .unwrap_or_default()
+    })
+}
+
+// ============================================
+// Design Pack Asset Commands
+// ============================================
+
+/// Attach a file (logo, border art, font, background) to a design pack.
+/// The file is copied into the pack's assets directory and listed on the
+/// pack under `assets`; an asset with the same name is replaced. Returns the
+/// asset entry.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn add_design_pack_asset(
+    app_handle: tauri::AppHandle,
+    pack_id: String,
+    source_path: String,
+    name: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut pack = design_pack_storage::find_pack(&app_handle, &pack_id).await?;
+
+    let name = match name {
+        Some(name) => name,
+        None => Path::new(&source_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or("Invalid source path")?,
+    };
+    validate_asset_name(&name)?;
+    let kind = design_pack_storage::asset_kind(&name)
+        .ok_or_else(|| format!("Unsupported design pack asset: {}", name))?;
+
+    let contents = fs::read(&source_path)
+        .await
+        .map_err(|e| format!("Failed to read asset: {}", e))?;
+    if contents.len() > design_pack_storage::MAX_ASSET_BYTES {
+        return Err(format!("Design pack asset is too large: {}", name));
+    }
+
+    let target = design_pack_storage::get_assets_dir(&app_handle, &pack_id)?.join(&name);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create design pack directory: {}", e))?;
+    }
+    fs::write(&target, &contents)
+        .await
+        .map_err(|e| format!("Failed to write design pack asset: {}", e))?;
+
+    let entry = serde_json::json!({
+        "name": name,
+        "kind": kind,
+        "sizeBytes": contents.len(),
+    });
+    let obj = pack.as_object_mut().ok_or("Invalid design pack")?;
+    let mut assets: Vec<Value> = obj
+        .get("assets")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    assets.retain(|a| a.get("name").and_then(|v| v.as_str()) != Some(name.as_str()));
+    assets.push(entry.clone());
+    obj.insert("assets".to_string(), Value::Array(assets));
+    obj.insert(
+        "updatedAt".to_string(),
+        Value::String(chrono::Utc::now().to_rfc3339()),
+    );
+    design_pack_storage::write_pack(&app_handle, pack).await?;
+
+    audit_log::record(&app_handle, "add_design_pack_asset", "design_pack", &[&pack_id]).await;
+
+    Ok(entry.to_string())
+}
+
+/// Remove an asset from a design pack
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn remove_design_pack_asset(
+    app_handle: tauri::AppHandle,
+    pack_id: String,
+    name: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    validate_asset_name(&name)?;
+
+    let mut pack = design_pack_storage::find_pack(&app_handle, &pack_id).await?;
+
+    let path = design_pack_storage::get_assets_dir(&app_handle, &pack_id)?.join(&name);
+    if path.exists() {
+        fs::remove_file(&path)
+            .await
+            .map_err(|e| format!("Failed to remove design pack asset: {}", e))?;
+    }
+
+    if let Some(obj) = pack.as_object_mut() {
+        if let Some(assets) = obj.get_mut("assets").and_then(|v| v.as_array_mut()) {
+            assets.retain(|a| a.get("name").and_then(|v| v.as_str()) != Some(name.as_str()));
+        }
+        obj.insert(
+            "updatedAt".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        );
+    }
+    design_pack_storage::write_pack(&app_handle, pack).await?;
+
+    audit_log::record(&app_handle, "remove_design_pack_asset", "design_pack", &[&pack_id]).await;
+
+    Ok(())
+}
+
+/// Resolve a design pack's assets for rendering. Returns `{ assets, preview }`
+/// mapping asset names to `pack-asset://` URLs, or to data URIs when `inline`
+/// is set (for output rendered outside the webview, such as PDFs).
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn resolve_design_pack_assets(
+    app_handle: tauri::AppHandle,
+    pack_id: String,
+    inline: bool,
+) -> Result<String, String> {
+    let pack = design_pack_storage::find_pack(&app_handle, &pack_id).await?;
+    let pack_dir = design_pack_storage::get_pack_dir(&app_handle, &pack_id)?;
+    let assets_dir = design_pack_storage::get_assets_dir(&app_handle, &pack_id)?;
+
+    let mut assets: BTreeMap<String, String> = BTreeMap::new();
+    let names = pack
+        .get("assets")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|a| a.get("name").and_then(|v| v.as_str()));
+    for name in names {
+        let path = assets_dir.join(name);
+        if validate_asset_name(name).is_err() || !path.is_file() {
+            continue;
+        }
+        let resolved = if inline {
+            data_uri(&path, name).await?
+        } else {
+            asset_url(&pack_id, name)
+        };
+        assets.insert(name.to_string(), resolved);
+    }
+
+    // The preview lives outside the assets directory and is always inlined
+    let mut preview = None;
+    if let Some(name) = pack.get("preview").and_then(|v| v.as_str()) {
+        let path = pack_dir.join(name);
+        if Path::new(name).components().count() == 1 && path.is_file() {
+            preview = Some(data_uri(&path, name).await?);
+        }
+    }
+
+    let response = serde_json::json!({
+        "assets": assets,
+        "preview": preview,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,208 @@
+use base64::Engine;
+use serde_json::Value;
+use std::path::Path;
+use std::time::SystemTime;
+use tokio::fs;
+
+use crate::commands::design_pack_storage;
+
+// Cached preview, stored next to the pack's assets directory
+const PREVIEW_FILE: &str = "rendered-preview.png";
+
+// Preview size in pixels (US Letter proportions)
+const WIDTH: u32 = 300;
+const HEIGHT: u32 = 388;
+
+// Colors used when a pack's palette has fewer entries
+const FALLBACK_PALETTE: [[u8; 3]; 4] = [
+    [0x1F, 0x29, 0x37],
+    [0x4B, 0x55, 0x63],
+    [0xE5, 0xE7, 0xEB],
+    [0x9C, 0xA3, 0xAF],
+];
+const WHITE: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const TEXT_GRAY: [u8; 3] = [0xD1, 0xD5, 0xDB];
+
+/// An RGB image being drawn
+struct Canvas {
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(background: [u8; 3]) -> Self {
+        Self {
+            pixels: background.repeat((WIDTH * HEIGHT) as usize),
+        }
+    }
+
+    fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: [u8; 3]) {
+        for row in y..(y + h).min(HEIGHT) {
+            for col in x..(x + w).min(WIDTH) {
+                let i = ((row * WIDTH + col) * 3) as usize;
+                self.pixels[i..i + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    fn stroke_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: [u8; 3]) {
+        self.fill_rect(x, y, w, 2, color);
+        self.fill_rect(x, y + h - 2, w, 2, color);
+        self.fill_rect(x, y, 2, h, color);
+        self.fill_rect(x + w - 2, y, 2, h, color);
+    }
+
+    fn fill_circle(&mut self, cx: u32, cy: u32, r: u32, color: [u8; 3]) {
+        let r2 = (r * r) as i64;
+        for row in cy.saturating_sub(r)..(cy + r).min(HEIGHT) {
+            for col in cx.saturating_sub(r)..(cx + r).min(WIDTH) {
+                let dx = col as i64 - cx as i64;
+                let dy = row as i64 - cy as i64;
+                if dx * dx + dy * dy <= r2 {
+                    self.fill_rect(col, row, 1, 1, color);
+                }
+            }
+        }
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, String> {
+        let mut output = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut output, WIDTH, HEIGHT);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder
+                .write_header()
+                .map_err(|e| format!("Failed to encode preview: {}", e))?;
+            writer
+                .write_image_data(&self.pixels)
+                .map_err(|e| format!("Failed to encode preview: {}", e))?;
+        }
+        Ok(output)
+    }
+}
+
+// Helper to parse a `#RRGGBB` or `#RGB` color
+pub(crate) fn parse_hex_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.trim().strip_prefix('#')?;
+    let expanded: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+// Helper to pick the pack's palette, padded with fallback colors
+fn pack_palette(pack: &Value) -> [[u8; 3]; 4] {
+    let mut palette = FALLBACK_PALETTE;
+    let colors = pack
+        .get("parsedSummary")
+        .and_then(|s| s.get("palette"))
+        .and_then(|p| p.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c.as_str().and_then(parse_hex_color));
+    for (slot, color) in palette.iter_mut().zip(colors) {
+        *slot = color;
+    }
+    palette
+}
+
+// Helper to draw a sample worksheet: header, title, numbered questions with
+// answer lines, a boxed activity, and a palette footer
+fn render_sample_worksheet(pack: &Value) -> Result<Vec<u8>, String> {
+    let [primary, secondary, tint, accent] = pack_palette(pack);
+    let mut canvas = Canvas::new(WHITE);
+
+    // Header band with a title block and name line
+    canvas.fill_rect(0, 0, WIDTH, 44, primary);
+    canvas.fill_rect(18, 14, 150, 16, WHITE);
+    canvas.fill_rect(190, 26, 92, 2, WHITE);
+
+    // Instructions
+    canvas.fill_rect(18, 58, 220, 6, TEXT_GRAY);
+    canvas.fill_rect(18, 70, 170, 6, TEXT_GRAY);
+
+    // Numbered questions with answer lines
+    for i in 0..4 {
+        let y = 94 + i * 44;
+        canvas.fill_circle(30, y + 8, 9, accent);
+        canvas.fill_rect(48, y + 2, 200 - i * 20, 6, TEXT_GRAY);
+        canvas.fill_rect(48, y + 28, 230, 2, secondary);
+    }
+
+    // Boxed activity on a tinted background
+    canvas.fill_rect(18, 276, 264, 70, tint);
+    canvas.stroke_rect(18, 276, 264, 70, secondary);
+    canvas.fill_rect(30, 290, 120, 6, primary);
+    canvas.fill_rect(30, 304, 200, 6, TEXT_GRAY);
+    canvas.fill_rect(30, 318, 160, 6, TEXT_GRAY);
+
+    // Palette swatches in the footer
+    for (i, color) in [primary, secondary, tint, accent].into_iter().enumerate() {
+        canvas.fill_rect(18 + i as u32 * 26, 360, 20, 14, color);
+    }
+
+    canvas.encode()
+}
+
+// Helper to check whether the cached preview is newer than the pack
+fn cache_is_fresh(preview_path: &Path, pack: &Value) -> bool {
+    let Ok(modified) = std::fs::metadata(preview_path).and_then(|m| m.modified()) else {
+        return false;
+    };
+    let updated_at = pack
+        .get("updatedAt")
+        .and_then(|v| v.as_str())
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok());
+    match updated_at {
+        Some(updated_at) => SystemTime::from(updated_at) <= modified,
+        None => true,
+    }
+}
+
+// ============================================
+// Design Pack Preview Commands
+// ============================================
+
+/// Render a sample worksheet with the pack's palette applied and cache it as
+/// a PNG per pack. The cache is reused until the pack is updated. Returns
+/// `{ path, dataUri, cached }`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn render_pack_preview(
+    app_handle: tauri::AppHandle,
+    pack_id: String,
+) -> Result<String, String> {
+    let pack = design_pack_storage::find_pack(&app_handle, &pack_id).await?;
+    let pack_dir = design_pack_storage::get_pack_dir(&app_handle, &pack_id)?;
+    let preview_path = pack_dir.join(PREVIEW_FILE);
+
+    let cached = cache_is_fresh(&preview_path, &pack);
+    let bytes = if cached {
+        fs::read(&preview_path)
+            .await
+            .map_err(|e| format!("Failed to read pack preview: {}", e))?
+    } else {
+        // Drawing and encoding are CPU-bound, keep them off the async runtime
+        let bytes = tauri::async_runtime::spawn_blocking(move || render_sample_worksheet(&pack))
+            .await
+            .map_err(|e| format!("Failed to render pack preview: {}", e))??;
+        fs::create_dir_all(&pack_dir)
+            .await
+            .map_err(|e| format!("Failed to create design pack directory: {}", e))?;
+        fs::write(&preview_path, &bytes)
+            .await
+            .map_err(|e| format!("Failed to write pack preview: {}", e))?;
+        bytes
+    };
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    let response = serde_json::json!({
+        "path": preview_path.to_string_lossy(),
+        "dataUri": format!("data:image/png;base64,{}", encoded),
+        "cached": cached,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,130 @@
+use serde_json::Value;
+
+use crate::commands::{
+    app_lock, audit_log, design_pack_storage, library_storage, session_mode, task_manager,
+    worksheet_assembly,
+};
+
+// Marks the <style> block a re-skin adds, so the next one can replace it
+const PACK_STYLE_OPEN: &str = "<style data-design-pack>";
+const STYLE_CLOSE: &str = "</style>";
+
+// Helper to restyle HTML the backend didn't render itself (e.g. generated
+// worksheets): the pack's font and colors go in a marked <style> block at
+// the end of <head>, replacing the block from any earlier re-skin
+fn apply_pack_styles(html: &str, pack: &Value) -> String {
+    let [primary, secondary, tint, accent] = worksheet_assembly::pack_palette(Some(pack));
+    let font = worksheet_assembly::pack_font(Some(pack));
+    let styles = format!(
+        "{PACK_STYLE_OPEN}\n\
+         body {{ font-family: {font}; color: {primary}; }}\n\
+         h1, h2, h3 {{ color: {primary}; }}\n\
+         th {{ background: {tint}; }}\n\
+         .answer-line {{ border-color: {secondary}; }}\n\
+         .question::marker {{ color: {accent}; }}\n\
+         {STYLE_CLOSE}\n"
+    );
+
+    let mut html = html.to_string();
+    if let Some(start) = html.find(PACK_STYLE_OPEN) {
+        let end = html[start..]
+            .find(STYLE_CLOSE)
+            .map_or(html.len(), |i| start + i + STYLE_CLOSE.len());
+        let end = if html[end..].starts_with('\n') { end + 1 } else { end };
+        html.replace_range(start..end, "");
+    }
+    // Tag names are ASCII, so offsets in the lowercased copy line up
+    match html.to_ascii_lowercase().find("</head>") {
+        Some(head_end) => html.insert_str(head_end, &styles),
+        None => html.insert_str(0, &styles),
+    }
+    html
+}
+
+// ============================================
+// Design Pack Reapply Commands
+// ============================================
+
+/// Re-skin artifacts with a different design pack without regenerating
+/// them. Documents the backend rendered (assembled worksheets, answer keys,
+/// companion pages) are rendered again around their existing content; other
+/// HTML gets the pack's font and colors as a style override. Each artifact's
+/// `designPackId` is updated. Runs as a "reskin" task (ID `operation_id`
+/// when given); when cancelled, the artifacts not yet reached are left as
+/// they were. Returns `{ packId,
+/// updated, failed }`, where `failed` lists `{ artifactId, error }`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn reapply_design_pack(
+    app_handle: tauri::AppHandle,
+    artifact_ids: Vec<String>,
+    pack_id: String,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let pack = design_pack_storage::find_pack(&app_handle, &pack_id).await?;
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let task = task_manager::start_task(&app_handle, operation_id, "reskin", "Apply design pack")?;
+    let total = Some(artifact_ids.len() as u64);
+    let mut updated = Vec::new();
+    let mut failed = Vec::new();
+    for (i, artifact_id) in artifact_ids.iter().enumerate() {
+        if task.is_cancelled() {
+            break;
+        }
+        task.progress(i as u64, total, None);
+        let result: Result<(), String> = async {
+            let mut artifact = library_storage::read_artifact(&app_handle, artifact_id).await?;
+            let html = artifact
+                .get("htmlContent")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let title = artifact
+                .get("title")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Untitled");
+            let restyled = match worksheet_assembly::document_body(html) {
+                Some(body) => worksheet_assembly::render_document(title, body, Some(&pack)),
+                None => apply_pack_styles(html, &pack),
+            };
+
+            let obj = artifact.as_object_mut().ok_or("Invalid artifact")?;
+            obj.insert("htmlContent".to_string(), Value::String(restyled));
+            obj.insert("designPackId".to_string(), Value::String(pack_id.clone()));
+            obj.insert("updatedAt".to_string(), Value::String(now.clone()));
+
+            let content = serde_json::to_string_pretty(&artifact)
+                .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+            library_storage::write_artifact(&app_handle, &artifact, &content).await
+        }
+        .await;
+
+        match result {
+            Ok(()) => updated.push(artifact_id.clone()),
+            Err(error) => {
+                tracing::warn!(artifact_id = %artifact_id, error = %error, "Failed to re-skin");
+                failed.push(serde_json::json!({ "artifactId": artifact_id, "error": error }));
+            }
+        }
+    }
+
+    let outcome = if task.is_cancelled() {
+        Err(task_manager::CANCELLED.to_string())
+    } else {
+        Ok(())
+    };
+    task.finish(&outcome);
+
+    let audited_ids: Vec<&str> = updated.iter().map(String::as_str).collect();
+    audit_log::record(&app_handle, "reapply_design_pack", "artifact", &audited_ids).await;
+
+    let response = serde_json::json!({
+        "packId": pack_id,
+        "updated": updated,
+        "failed": failed,
+    });
+    Ok(response.to_string())
+}
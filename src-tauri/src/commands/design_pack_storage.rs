@@ -1,12 +1,41 @@
 use serde_json::Value;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
 use tokio::fs;
 
+use crate::archive::{self, ArchiveEntry};
+use crate::commands::storage_events::{self, StorageEvent};
+use crate::commands::{app_lock, audit_log, disk_space, index_cache, session_mode};
+
 const DESIGN_PACKS_DIR: &str = "design-packs";
 const INDEX_FILE: &str = "packs.json";
+// Per-pack folder holding bundled assets: design-packs/{id}/assets/
+const ASSETS_DIR: &str = "assets";
+
+const PACK_ZIP_FORMAT: &str = "ta-design-pack";
+const PACK_ZIP_VERSION: u64 = 1;
+
+// Paths inside a design pack zip
+const MANIFEST_ENTRY: &str = "manifest.json";
+pub(crate) const PACK_ENTRY: &str = "pack.json";
+const ASSETS_PREFIX: &str = "assets/";
+const PREVIEW_STEM: &str = "preview";
+
+// File types a pack may bundle, by extension
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "woff", "woff2"];
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg"];
+pub(crate) const MAX_ASSET_BYTES: usize = 20 * 1024 * 1024;
+
+// Built-in packs compiled into the binary
+const DEFAULT_PACKS: &[&[u8]] = &[
+    include_bytes!("../../default-packs/classroom-classic.json"),
+    include_bytes!("../../default-packs/bright-and-playful.json"),
+    include_bytes!("../../default-packs/calm-nature.json"),
+];
 
 // Helper to get the design packs directory
-fn get_packs_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn get_packs_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     let app_data_dir = app_handle
         .path()
         .app_data_dir()
@@ -15,48 +44,168 @@ fn get_packs_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
 }
 
 // Helper to get the index file path
-fn get_index_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn get_index_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(get_packs_dir(app_handle)?.join(INDEX_FILE))
 }
 
+// Helper to validate a pack ID before using it in a path
+pub(crate) fn validate_pack_id(pack_id: &str) -> Result<(), String> {
+    let valid = !pack_id.is_empty()
+        && pack_id.len() <= 128
+        && pack_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if !valid {
+        return Err(format!("Invalid pack ID: {}", pack_id));
+    }
+    Ok(())
+}
+
+// Helper to get a pack's own directory (assets and preview)
+pub(crate) fn get_pack_dir(
+    app_handle: &tauri::AppHandle,
+    pack_id: &str,
+) -> Result<PathBuf, String> {
+    validate_pack_id(pack_id)?;
+    Ok(get_packs_dir(app_handle)?.join(pack_id))
+}
+
+// Helper to get the directory holding a pack's bundled assets
+pub(crate) fn get_assets_dir(
+    app_handle: &tauri::AppHandle,
+    pack_id: &str,
+) -> Result<PathBuf, String> {
+    Ok(get_pack_dir(app_handle, pack_id)?.join(ASSETS_DIR))
+}
+
+// Helper to find a design pack in the index
+pub(crate) async fn find_pack(
+    app_handle: &tauri::AppHandle,
+    pack_id: &str,
+) -> Result<Value, String> {
+    read_packs(app_handle)
+        .await?
+        .into_iter()
+        .find(|p| p.get("packId").and_then(|v| v.as_str()) == Some(pack_id))
+        .ok_or_else(|| format!("Design pack not found: {}", pack_id))
+}
+
+// Helper to read every design pack in the index
+pub(crate) async fn read_packs(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let index_path = get_index_path(app_handle)?;
+    let index = index_cache::read_index(app_handle, &index_path, "design packs").await?;
+    Ok(index
+        .as_deref()
+        .and_then(|index| index.as_array())
+        .cloned()
+        .unwrap_or_default())
+}
+
+/// Replace every design pack in the index
+pub(crate) async fn write_packs(
+    app_handle: &tauri::AppHandle,
+    packs: Vec<Value>,
+) -> Result<(), String> {
+    let index_path = get_index_path(app_handle)?;
+    index_cache::write_index(app_handle, &index_path, Value::Array(packs), "design packs").await
+}
+
+// Helper to insert or replace a pack in the index
+pub(crate) async fn write_pack(
+    app_handle: &tauri::AppHandle,
+    new_pack: Value,
+) -> Result<(), String> {
+    let pack_id = new_pack
+        .get("packId")
+        .and_then(|v| v.as_str())
+        .ok_or("Pack must have a packId")?
+        .to_string();
+
+    let mut packs = read_packs(app_handle).await?;
+
+    // Find and update existing pack, or add new one
+    let mut found = false;
+    for pack in packs.iter_mut() {
+        if pack.get("packId").and_then(|v| v.as_str()) == Some(pack_id.as_str()) {
+            *pack = new_pack.clone();
+            found = true;
+            break;
+        }
+    }
+    if !found {
+        packs.push(new_pack);
+    }
+
+    write_packs(app_handle, packs).await?;
+    storage_events::emit(app_handle, StorageEvent::DesignPackUpdated(&pack_id));
+    Ok(())
+}
+
+// Helper to classify a bundled file by extension
+pub(crate) fn asset_kind(name: &str) -> Option<&'static str> {
+    let extension = Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())?;
+    if FONT_EXTENSIONS.contains(&extension.as_str()) {
+        Some("font")
+    } else if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        Some("image")
+    } else {
+        None
+    }
+}
+
+// Helper to parse the built-in packs
+fn default_packs() -> Result<Vec<Value>, String> {
+    DEFAULT_PACKS
+        .iter()
+        .map(|bytes| {
+            serde_json::from_slice(bytes).map_err(|e| format!("Invalid built-in pack: {}", e))
+        })
+        .collect()
+}
+
+/// Seed the built-in design packs on first launch (when no pack index exists
+/// yet). Called once during app setup.
+pub fn seed_default_packs(app_handle: &tauri::AppHandle) {
+    let result = get_index_path(app_handle).and_then(|index_path| {
+        if index_path.exists() {
+            return Ok(());
+        }
+        if let Some(parent) = index_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create design packs directory: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(&default_packs()?)
+            .map_err(|e| format!("Failed to serialize design packs: {}", e))?;
+        std::fs::write(&index_path, content)
+            .map_err(|e| format!("Failed to write design packs: {}", e))
+    });
+    if let Err(e) = result {
+        tracing::error!(error = %e, "Failed to seed built-in design packs");
+    }
+}
+
 // ============================================
 // Design Pack Commands
 // ============================================
 
 /// Get all design packs
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn get_design_packs(app_handle: tauri::AppHandle) -> Result<String, String> {
-    let index_path = get_index_path(&app_handle)?;
-
-    // If file doesn't exist, return empty array
-    if !index_path.exists() {
-        return Ok("[]".to_string());
-    }
-
-    fs::read_to_string(&index_path)
-        .await
-        .map_err(|e| format!("Failed to read design packs: {}", e))
+    let packs = read_packs(&app_handle).await?;
+    serde_json::to_string(&packs).map_err(|e| format!("Failed to serialize design packs: {}", e))
 }
 
 /// Get a specific design pack by ID
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn get_design_pack(
     app_handle: tauri::AppHandle,
     pack_id: String,
 ) -> Result<String, String> {
-    let index_path = get_index_path(&app_handle)?;
-
-    if !index_path.exists() {
-        return Err(format!("Design pack not found: {}", pack_id));
-    }
-
-    let content = fs::read_to_string(&index_path)
-        .await
-        .map_err(|e| format!("Failed to read design packs: {}", e))?;
-
-    let packs: Vec<Value> = serde_json::from_str(&content).unwrap_or_else(|_| Vec::new());
-
-    for pack in packs {
+    for pack in read_packs(&app_handle).await? {
         if pack.get("packId").and_then(|v| v.as_str()) == Some(&pack_id) {
             return serde_json::to_string(&pack)
                 .map_err(|e| format!("Failed to serialize pack: {}", e));
@@ -68,17 +217,13 @@ pub async fn get_design_pack(
 
 /// Save a design pack (create or update)
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn save_design_pack(
     app_handle: tauri::AppHandle,
     pack: String,
 ) -> Result<(), String> {
-    let packs_dir = get_packs_dir(&app_handle)?;
-    let index_path = get_index_path(&app_handle)?;
-
-    // Create directory if it doesn't exist
-    fs::create_dir_all(&packs_dir)
-        .await
-        .map_err(|e| format!("Failed to create design packs directory: {}", e))?;
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
 
     // Parse the incoming pack
     let new_pack: Value =
@@ -87,68 +232,305 @@ pub async fn save_design_pack(
     let pack_id = new_pack
         .get("packId")
         .and_then(|v| v.as_str())
-        .ok_or("Pack must have a packId")?;
+        .ok_or("Pack must have a packId")?
+        .to_string();
 
-    // Read existing packs
-    let mut packs: Vec<Value> = if index_path.exists() {
-        let content = fs::read_to_string(&index_path)
-            .await
-            .map_err(|e| format!("Failed to read design packs: {}", e))?;
-        serde_json::from_str(&content).unwrap_or_else(|_| Vec::new())
-    } else {
-        Vec::new()
-    };
-
-    // Find and update existing pack, or add new one
-    let mut found = false;
-    for pack in packs.iter_mut() {
-        if pack.get("packId").and_then(|v| v.as_str()) == Some(pack_id) {
-            *pack = new_pack.clone();
-            found = true;
-            break;
-        }
-    }
-    if !found {
-        packs.push(new_pack);
-    }
+    write_pack(&app_handle, new_pack).await?;
 
-    // Write packs back
-    let content = serde_json::to_string_pretty(&packs)
-        .map_err(|e| format!("Failed to serialize design packs: {}", e))?;
-    fs::write(&index_path, content)
-        .await
-        .map_err(|e| format!("Failed to write design packs: {}", e))?;
+    audit_log::record(&app_handle, "save_design_pack", "design_pack", &[&pack_id]).await;
 
     Ok(())
 }
 
 /// Delete a design pack
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn delete_design_pack(
     app_handle: tauri::AppHandle,
     pack_id: String,
 ) -> Result<(), String> {
-    let index_path = get_index_path(&app_handle)?;
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
 
-    if !index_path.exists() {
-        return Ok(());
+    let mut packs = read_packs(&app_handle).await?;
+
+    // Remove the pack, leaving a missing index missing
+    let before = packs.len();
+    packs.retain(|p| p.get("packId").and_then(|v| v.as_str()) != Some(&pack_id));
+    if packs.len() != before {
+        write_packs(&app_handle, packs).await?;
+    }
+
+    // Remove bundled assets and preview
+    if let Ok(pack_dir) = get_pack_dir(&app_handle, &pack_id) {
+        if pack_dir.exists() {
+            fs::remove_dir_all(&pack_dir)
+                .await
+                .map_err(|e| format!("Failed to remove design pack assets: {}", e))?;
+        }
     }
 
-    let content = fs::read_to_string(&index_path)
+    storage_events::emit(&app_handle, StorageEvent::DesignPackDeleted(&pack_id));
+    audit_log::record(&app_handle, "delete_design_pack", "design_pack", &[&pack_id]).await;
+
+    Ok(())
+}
+
+/// Import a design pack zip (`manifest.json`, `pack.json`, files under
+/// `assets/`, and an optional `preview.<ext>`). Assets are stored under
+/// `design-packs/{id}/assets/` and listed on the pack as `assets`. A pack
+/// whose ID is already in use is imported under a fresh ID. Returns a JSON
+/// summary.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn import_design_pack_zip(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let bytes = fs::read(&path)
         .await
-        .map_err(|e| format!("Failed to read design packs: {}", e))?;
+        .map_err(|e| format!("Failed to read design pack: {}", e))?;
+    let summary = install_pack_zip(&app_handle, &bytes).await?;
 
-    let mut packs: Vec<Value> = serde_json::from_str(&content).unwrap_or_else(|_| Vec::new());
+    let pack_id = summary.get("packId").and_then(|v| v.as_str()).unwrap_or_default();
+    audit_log::record(&app_handle, "import_design_pack_zip", "design_pack", &[pack_id]).await;
 
-    // Remove the pack
-    packs.retain(|p| p.get("packId").and_then(|v| v.as_str()) != Some(&pack_id));
+    Ok(summary.to_string())
+}
+
+/// Install a design pack zip's contents. Shared by `import_design_pack_zip`
+/// and project bundle import. Returns the import summary.
+pub(crate) async fn install_pack_zip(
+    app_handle: &tauri::AppHandle,
+    bytes: &[u8],
+) -> Result<Value, String> {
+    let entries: HashMap<String, Vec<u8>> = archive::read_zip(bytes)?.into_iter().collect();
+
+    // Validate the manifest
+    let manifest: Value = entries
+        .get(MANIFEST_ENTRY)
+        .ok_or("Design pack is missing its manifest")
+        .and_then(|b| serde_json::from_slice(b).map_err(|_| "Invalid design pack manifest"))?;
+    if manifest.get("format").and_then(|v| v.as_str()) != Some(PACK_ZIP_FORMAT) {
+        return Err("File is not a design pack".to_string());
+    }
+    let version = manifest.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > PACK_ZIP_VERSION {
+        return Err(format!("Unsupported design pack version: {}", version));
+    }
+
+    // Validate the pack itself
+    let mut pack: Value = entries
+        .get(PACK_ENTRY)
+        .ok_or("Design pack is missing pack.json")
+        .and_then(|b| serde_json::from_slice(b).map_err(|_| "Invalid pack.json"))?;
+    let pack_obj = pack.as_object_mut().ok_or("pack.json must be an object")?;
+    let bundled_id = pack_obj
+        .get("packId")
+        .and_then(|v| v.as_str())
+        .ok_or("Pack must have a packId")?
+        .to_string();
+    validate_pack_id(&bundled_id)?;
+    let name = pack_obj.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    if name.trim().is_empty() {
+        return Err("Pack must have a name".to_string());
+    }
+    if !pack_obj.get("items").is_some_and(|v| v.is_array()) {
+        pack_obj.insert("items".to_string(), serde_json::json!([]));
+    }
+
+    // Validate bundled files before writing anything
+    let mut assets: Vec<(&str, &Vec<u8>, &'static str)> = Vec::new();
+    let mut preview: Option<(&str, &Vec<u8>)> = None;
+    for (name, contents) in &entries {
+        if name == MANIFEST_ENTRY || name == PACK_ENTRY {
+            continue;
+        }
+        if contents.len() > MAX_ASSET_BYTES {
+            return Err(format!("Design pack file is too large: {}", name));
+        }
+        if let Some(asset_name) = name.strip_prefix(ASSETS_PREFIX) {
+            let kind = asset_kind(asset_name)
+                .ok_or_else(|| format!("Unsupported design pack asset: {}", name))?;
+            assets.push((asset_name, contents, kind));
+        } else if Path::new(name).file_stem().and_then(|s| s.to_str()) == Some(PREVIEW_STEM)
+            && !name.contains('/')
+            && asset_kind(name) == Some("image")
+        {
+            preview = Some((name.as_str(), contents));
+        } else {
+            return Err(format!("Unexpected file in design pack: {}", name));
+        }
+    }
+    assets.sort_by(|a, b| a.0.cmp(b.0));
+
+    // Keep existing packs intact
+    let existing = read_packs(app_handle).await?;
+    let taken = existing
+        .iter()
+        .any(|p| p.get("packId").and_then(|v| v.as_str()) == Some(bundled_id.as_str()));
+    let pack_id = if taken {
+        uuid::Uuid::new_v4().to_string()
+    } else {
+        bundled_id
+    };
+
+    // Write assets and preview into a fresh pack directory
+    let pack_dir = get_pack_dir(app_handle, &pack_id)?;
+    if pack_dir.exists() {
+        fs::remove_dir_all(&pack_dir)
+            .await
+            .map_err(|e| format!("Failed to clear design pack directory: {}", e))?;
+    }
+    let assets_dir = pack_dir.join(ASSETS_DIR);
+    fs::create_dir_all(&assets_dir)
+        .await
+        .map_err(|e| format!("Failed to create design pack directory: {}", e))?;
+
+    let mut asset_list = Vec::new();
+    for (name, contents, kind) in &assets {
+        let target = assets_dir.join(name);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create design pack directory: {}", e))?;
+        }
+        fs::write(&target, contents)
+            .await
+            .map_err(|e| format!("Failed to write design pack asset: {}", e))?;
+        asset_list.push(serde_json::json!({
+            "name": name,
+            "kind": kind,
+            "sizeBytes": contents.len(),
+        }));
+    }
+    if let Some((name, contents)) = preview {
+        fs::write(pack_dir.join(name), contents)
+            .await
+            .map_err(|e| format!("Failed to write design pack preview: {}", e))?;
+    }
 
-    // Write packs back
-    let content = serde_json::to_string_pretty(&packs)
-        .map_err(|e| format!("Failed to serialize design packs: {}", e))?;
-    fs::write(&index_path, content)
+    let now = chrono::Utc::now().to_rfc3339();
+    if let Some(obj) = pack.as_object_mut() {
+        obj.insert("packId".to_string(), Value::String(pack_id.clone()));
+        obj.insert("assets".to_string(), Value::Array(asset_list));
+        obj.insert(
+            "preview".to_string(),
+            preview.map_or(Value::Null, |(name, _)| Value::String(name.to_string())),
+        );
+        obj.entry("createdAt").or_insert_with(|| Value::String(now.clone()));
+        obj.insert("updatedAt".to_string(), Value::String(now));
+    }
+    let name = pack.get("name").cloned().unwrap_or(Value::Null);
+    write_pack(app_handle, pack).await?;
+
+    Ok(serde_json::json!({
+        "packId": pack_id,
+        "name": name,
+        "assetCount": assets.len(),
+        "hasPreview": preview.is_some(),
+        "remapped": taken,
+    }))
+}
+
+/// Export a design pack as a zip (manifest, pack JSON, assets, and preview)
+/// that `import_design_pack_zip` can read, for sharing with colleagues
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn export_design_pack(
+    app_handle: tauri::AppHandle,
+    pack_id: String,
+    output_path: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let bundle = build_pack_zip(&app_handle, &pack_id).await?;
+    disk_space::ensure_available(Path::new(&output_path), bundle.len() as u64)?;
+    fs::write(&output_path, bundle)
         .await
-        .map_err(|e| format!("Failed to write design packs: {}", e))?;
+        .map_err(|e| format!("Failed to write design pack: {}", e))?;
+
+    audit_log::record(&app_handle, "export_design_pack", "design_pack", &[&pack_id]).await;
 
     Ok(())
 }
+
+/// Build the zip `export_design_pack` writes. Shared with project bundle
+/// export.
+pub(crate) async fn build_pack_zip(
+    app_handle: &tauri::AppHandle,
+    pack_id: &str,
+) -> Result<Vec<u8>, String> {
+    let pack = find_pack(app_handle, pack_id).await?;
+    let pack_dir = get_pack_dir(app_handle, pack_id)?;
+
+    let manifest = serde_json::json!({
+        "format": PACK_ZIP_FORMAT,
+        "version": PACK_ZIP_VERSION,
+        "appVersion": app_handle.package_info().version.to_string(),
+        "exportedAt": chrono::Utc::now().to_rfc3339(),
+    });
+    let mut entries: Vec<ArchiveEntry> = vec![
+        (
+            MANIFEST_ENTRY.to_string(),
+            serde_json::to_vec_pretty(&manifest)
+                .map_err(|e| format!("Failed to serialize manifest: {}", e))?,
+        ),
+        (
+            PACK_ENTRY.to_string(),
+            serde_json::to_vec_pretty(&pack)
+                .map_err(|e| format!("Failed to serialize pack: {}", e))?,
+        ),
+    ];
+
+    // Bundled assets
+    let assets_dir = pack_dir.join(ASSETS_DIR);
+    if assets_dir.exists() {
+        entries.extend(archive::collect_dir(&assets_dir, ASSETS_PREFIX, &[]).await?);
+    }
+
+    // Preview image
+    let preview = pack
+        .get("preview")
+        .and_then(|v| v.as_str())
+        .filter(|name| Path::new(name).file_name() == Some(std::ffi::OsStr::new(name)));
+    if let Some(preview) = preview {
+        let preview_path = pack_dir.join(preview);
+        if preview_path.is_file() {
+            let contents = fs::read(&preview_path)
+                .await
+                .map_err(|e| format!("Failed to read design pack preview: {}", e))?;
+            entries.push((preview.to_string(), contents));
+        }
+    }
+
+    archive::build_zip(&entries)
+}
+
+/// Restore the built-in design packs, re-adding any that were deleted and
+/// resetting any that were edited. Other packs are left alone. Returns the
+/// number of packs restored.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn restore_default_packs(app_handle: tauri::AppHandle) -> Result<usize, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let packs = default_packs()?;
+    let mut pack_ids = Vec::new();
+    for pack in packs {
+        if let Some(pack_id) = pack.get("packId").and_then(|v| v.as_str()) {
+            pack_ids.push(pack_id.to_string());
+        }
+        write_pack(&app_handle, pack).await?;
+    }
+
+    let ids: Vec<&str> = pack_ids.iter().map(String::as_str).collect();
+    audit_log::record(&app_handle, "restore_default_packs", "design_pack", &ids).await;
+
+    Ok(pack_ids.len())
+}
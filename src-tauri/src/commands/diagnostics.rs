@@ -0,0 +1,228 @@
+use serde_json::Value;
+use std::path::Path;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::archive::{self, ArchiveEntry};
+use crate::commands::{
+    app_lock, design_pack_storage, index_cache, learner_storage, library_storage, logging,
+    ollama, project_storage, session_mode, settings_storage,
+};
+
+// Recent log files included in a diagnostics bundle
+const MAX_BUNDLED_LOG_FILES: usize = 3;
+
+// Index fields that carry no learner PII or free text. Everything else
+// (titles, names, learner IDs, notes) is dropped from bundled index metadata.
+const SAFE_INDEX_KEYS: &[&str] = &[
+    "id",
+    "artifactId",
+    "projectId",
+    "jobId",
+    "designPackId",
+    "type",
+    "grade",
+    "subject",
+    "status",
+    "version",
+    "createdAt",
+    "updatedAt",
+];
+
+// Helper to probe the configured Ollama endpoint
+async fn ollama_status(endpoint: &str) -> Value {
+    match ollama::get_version(endpoint).await {
+        Ok(version) => serde_json::json!({
+            "endpoint": endpoint,
+            "reachable": true,
+            "version": version,
+            "models": ollama::list_models(endpoint).await.unwrap_or_default(),
+        }),
+        Err(e) => serde_json::json!({
+            "endpoint": endpoint,
+            "reachable": false,
+            "error": e,
+        }),
+    }
+}
+
+// Helper to count files and bytes under a directory
+async fn dir_usage(dir: &Path) -> (u64, u64) {
+    let mut files = 0;
+    let mut bytes = 0;
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let Ok(mut entries) = fs::read_dir(&current).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(metadata) = entry.metadata().await else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                files += 1;
+                bytes += metadata.len();
+            }
+        }
+    }
+
+    (files, bytes)
+}
+
+// Helper to summarize disk usage per top-level app data directory
+async fn storage_usage(app_data_dir: &Path) -> Result<Vec<Value>, String> {
+    let mut usage = Vec::new();
+
+    if !app_data_dir.exists() {
+        return Ok(usage);
+    }
+
+    let mut entries = fs::read_dir(app_data_dir)
+        .await
+        .map_err(|e| format!("Failed to read app data directory: {}", e))?;
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| format!("Failed to read app data directory: {}", e))?
+    {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let (files, bytes) = dir_usage(&path).await;
+        usage.push(serde_json::json!({
+            "directory": entry.file_name().to_string_lossy(),
+            "files": files,
+            "bytes": bytes,
+        }));
+    }
+
+    usage.sort_by(|a, b| a["directory"].as_str().cmp(&b["directory"].as_str()));
+    Ok(usage)
+}
+
+// Helper to keep only the safe scalar fields of an index entry
+fn sanitize_index_entry(entry: &Value) -> Value {
+    let mut sanitized = serde_json::Map::new();
+    if let Some(obj) = entry.as_object() {
+        for key in SAFE_INDEX_KEYS {
+            if let Some(value) = obj.get(*key) {
+                if !value.is_object() && !value.is_array() {
+                    sanitized.insert(key.to_string(), value.clone());
+                }
+            }
+        }
+    }
+    Value::Object(sanitized)
+}
+
+// Helper to read an index file (a bare array, or an object holding the array
+// under `list_key`) and return its sanitized entries
+async fn sanitized_index(index_path: &Path, list_key: Option<&str>) -> Vec<Value> {
+    let Ok(content) = fs::read_to_string(index_path).await else {
+        return Vec::new();
+    };
+    let Ok(index) = serde_json::from_str::<Value>(&content) else {
+        return Vec::new();
+    };
+    let list = match list_key {
+        Some(key) => index.get(key),
+        None => Some(&index),
+    };
+
+    list.and_then(|v| v.as_array())
+        .map(|entries| entries.iter().map(sanitize_index_entry).collect())
+        .unwrap_or_default()
+}
+
+// Helper to count learner profiles without reading any of their fields
+async fn learner_count(app_handle: &tauri::AppHandle) -> usize {
+    let Ok(profiles_path) = learner_storage::get_profiles_path(app_handle) else {
+        return 0;
+    };
+    fs::read_to_string(&profiles_path)
+        .await
+        .ok()
+        .and_then(|content| serde_json::from_str::<Vec<Value>>(&content).ok())
+        .map(|profiles| profiles.len())
+        .unwrap_or(0)
+}
+
+// Helper to serialize a JSON value as a pretty-printed archive entry
+fn json_entry(name: &str, value: &impl serde::Serialize) -> Result<ArchiveEntry, String> {
+    let content = serde_json::to_vec_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+    Ok((name.to_string(), content))
+}
+
+// ============================================
+// Diagnostics Commands
+// ============================================
+
+/// Write a zip for bug reports with app/OS info, Ollama status, storage usage,
+/// recent logs, and index metadata stripped of learner PII
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn create_diagnostics_bundle(
+    app_handle: tauri::AppHandle,
+    output_path: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let settings = settings_storage::load_settings(&app_handle).await?;
+
+    let summary = serde_json::json!({
+        "generatedAt": chrono::Utc::now().to_rfc3339(),
+        "app": {
+            "name": app_handle.package_info().name,
+            "version": app_handle.package_info().version.to_string(),
+        },
+        "os": {
+            "os": std::env::consts::OS,
+            "family": std::env::consts::FAMILY,
+            "arch": std::env::consts::ARCH,
+        },
+        "ollama": ollama_status(&settings.ollama.endpoint).await,
+        "storage": storage_usage(&app_data_dir).await?,
+        "learnerCount": learner_count(&app_handle).await,
+    });
+
+    if let Err(e) = index_cache::flush(&app_handle).await {
+        tracing::warn!(error = %e, "Bundling indices as last written");
+    }
+    let library_index = library_storage::get_index_path(&app_handle)?;
+    let projects_index = project_storage::get_index_path(&app_handle)?;
+    let packs_index = design_pack_storage::get_index_path(&app_handle)?;
+
+    let mut entries = vec![
+        json_entry("diagnostics.json", &summary)?,
+        json_entry(
+            "indexes/library.json",
+            &sanitized_index(&library_index, Some("artifacts")).await,
+        )?,
+        json_entry("indexes/projects.json", &sanitized_index(&projects_index, None).await)?,
+        json_entry("indexes/design-packs.json", &sanitized_index(&packs_index, None).await)?,
+    ];
+    entries.extend(logging::recent_log_entries(&app_handle, MAX_BUNDLED_LOG_FILES).await?);
+
+    let bundle = archive::build_zip(&entries)?;
+
+    // Create parent directories if they don't exist
+    if let Some(parent) = Path::new(&output_path).parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+
+    fs::write(&output_path, bundle)
+        .await
+        .map_err(|e| format!("Failed to write diagnostics bundle: {}", e))
+}
@@ -1,8 +1,13 @@
-use tauri_plugin_shell::ShellExt;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::commands::session_mode;
 
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn open_folder(app: tauri::AppHandle, path: String) -> Result<(), String> {
-    app.shell()
-        .open(&path, None)
+    session_mode::ensure_teacher_mode(&app)?;
+
+    app.opener()
+        .open_path(&path, None::<&str>)
         .map_err(|e| e.to_string())
 }
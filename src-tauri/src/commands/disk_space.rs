@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+// Free space left over after a write, so a large export doesn't leave the
+// system with nothing
+const RESERVE_BYTES: u64 = 100 * 1024 * 1024;
+
+// Helper to describe a byte count for people
+fn describe_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let mb = bytes as f64 / MB;
+    if mb >= 1024.0 {
+        format!("{:.1} GB", mb / 1024.0)
+    } else {
+        format!("{:.0} MB", mb.ceil())
+    }
+}
+
+// Helper to measure free space where `path` will be written. The path may
+// not exist yet, so the nearest existing ancestor is measured.
+fn available_space(path: &Path) -> std::io::Result<(PathBuf, u64)> {
+    let target = path.ancestors().find(|p| p.exists()).unwrap_or(path);
+    Ok((target.to_path_buf(), fs2::available_space(target)?))
+}
+
+// Helper to get the directory Ollama keeps its models in
+fn ollama_models_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    if let Some(dir) = std::env::var_os("OLLAMA_MODELS").filter(|d| !d.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+    let home_dir = app_handle
+        .path()
+        .home_dir()
+        .map_err(|e| format!("Failed to get home directory: {}", e))?;
+    Ok(home_dir.join(".ollama").join("models"))
+}
+
+/// Check there's room to write `needed` bytes at `path` before starting.
+/// When there isn't, the error is JSON the frontend can show: `{ code:
+/// "insufficient_disk_space", message, neededBytes, availableBytes, path
+/// }`. If free space can't be read, the write goes ahead.
+pub(crate) fn ensure_available(path: &Path, needed: u64) -> Result<(), String> {
+    let (target, available) = match available_space(path) {
+        Ok(space) => space,
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to read free disk space");
+            return Ok(());
+        }
+    };
+    let required = needed.saturating_add(RESERVE_BYTES);
+    if available >= required {
+        return Ok(());
+    }
+
+    let error = serde_json::json!({
+        "code": "insufficient_disk_space",
+        "message": format!(
+            "Not enough disk space: {} needed, {} available",
+            describe_size(required),
+            describe_size(available)
+        ),
+        "neededBytes": required,
+        "availableBytes": available,
+        "path": target.to_string_lossy(),
+    });
+    Err(error.to_string())
+}
+
+// ============================================
+// Disk Space Commands
+// ============================================
+
+/// Check whether there's room for a download or export of `needed_bytes`
+/// before starting it, such as an Ollama model pull of a known size.
+/// `location` is `ollama` for the Ollama model directory, a folder or file
+/// path, or omitted for the app data directory. Returns `{ path,
+/// neededBytes, availableBytes, enough }`; `neededBytes` includes a reserve
+/// left free for the system.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn check_disk_space(
+    app_handle: tauri::AppHandle,
+    needed_bytes: u64,
+    location: Option<String>,
+) -> Result<String, String> {
+    let path = match location.as_deref() {
+        Some("ollama") => ollama_models_dir(&app_handle)?,
+        Some(path) => PathBuf::from(path),
+        None => app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to get app data directory: {}", e))?,
+    };
+
+    let (target, available) = available_space(&path)
+        .map_err(|e| format!("Failed to read free disk space: {}", e))?;
+    let required = needed_bytes.saturating_add(RESERVE_BYTES);
+    let response = serde_json::json!({
+        "path": target.to_string_lossy(),
+        "neededBytes": required,
+        "availableBytes": available,
+        "enough": available >= required,
+    });
+    Ok(response.to_string())
+}
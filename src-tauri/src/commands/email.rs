@@ -0,0 +1,234 @@
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri_plugin_opener::OpenerExt;
+use tokio::fs;
+
+use crate::commands::settings_storage::{self, EmailSettings};
+use crate::commands::{app_lock, audit_log, library_storage, pdf_export, secrets, session_mode};
+
+/// Keychain key of the SMTP password; set it with `set_smtp_password`
+pub(crate) const SMTP_PASSWORD_KEY: &str = "email.smtp_password";
+
+// How long to wait on the SMTP server
+const SMTP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// An email to send, with files attached
+pub(crate) struct OutgoingEmail {
+    pub(crate) to: Vec<String>,
+    pub(crate) subject: String,
+    pub(crate) body: String,
+    pub(crate) attachments: Vec<PathBuf>,
+}
+
+// Helper to parse a recipient or sender address
+fn parse_mailbox(address: &str) -> Result<Mailbox, String> {
+    address
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid email address: {}", address))
+}
+
+// Helper to pick an attachment's content type from its extension
+fn content_type(path: &Path) -> ContentType {
+    let mime_type = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("pdf") => "application/pdf",
+        Some(ext) if ext.eq_ignore_ascii_case("html") => "text/html",
+        Some(ext) if ext.eq_ignore_ascii_case("csv") => "text/csv",
+        _ => "application/octet-stream",
+    };
+    ContentType::parse(mime_type).unwrap_or(ContentType::TEXT_PLAIN)
+}
+
+// Helper to build the message, reading the attachments
+async fn build_message(
+    settings: &EmailSettings,
+    recipients: &[Mailbox],
+    email: &OutgoingEmail,
+) -> Result<Message, String> {
+    let from = settings
+        .from_address
+        .as_deref()
+        .or(settings.username.as_deref())
+        .ok_or("Set a from address in the email settings")?;
+    let mut builder = Message::builder().from(parse_mailbox(from)?).subject(&email.subject);
+    for recipient in recipients {
+        builder = builder.to(recipient.clone());
+    }
+
+    let mut parts = MultiPart::mixed().singlepart(SinglePart::plain(email.body.clone()));
+    for path in &email.attachments {
+        let contents = fs::read(path)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        parts = parts.singlepart(Attachment::new(name).body(contents, content_type(path)));
+    }
+    builder
+        .multipart(parts)
+        .map_err(|e| format!("Failed to build email: {}", e))
+}
+
+// Helper to send a message through the configured SMTP server
+async fn send_smtp(settings: &EmailSettings, host: &str, message: Message) -> Result<(), String> {
+    let builder = match settings.security.as_str() {
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+        "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+        "none" => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)),
+        other => return Err(format!("Invalid SMTP security: {}", other)),
+    }
+    .map_err(|e| format!("Failed to set up SMTP: {}", e))?;
+    let mut builder = builder.port(settings.smtp_port).timeout(Some(SMTP_TIMEOUT));
+    if let Some(username) = &settings.username {
+        let password = secrets::read_secret(SMTP_PASSWORD_KEY)
+            .await?
+            .ok_or("Set the SMTP password in the email settings")?;
+        builder = builder.credentials(Credentials::new(username.clone(), password));
+    }
+    builder
+        .build()
+        .send(message)
+        .await
+        .map_err(|e| format!("Failed to send email: {}", e))?;
+    Ok(())
+}
+
+// Helper to open a prefilled email in the user's mail app. A mailto link
+// can't carry attachments, so the user attaches them by hand.
+fn open_mailto(
+    app_handle: &tauri::AppHandle,
+    recipients: &[Mailbox],
+    email: &OutgoingEmail,
+) -> Result<(), String> {
+    let to: Vec<String> = recipients
+        .iter()
+        .map(|r| utf8_percent_encode(r.email.as_ref(), NON_ALPHANUMERIC).to_string())
+        .collect();
+    let url = format!(
+        "mailto:{}?subject={}&body={}",
+        to.join(","),
+        utf8_percent_encode(&email.subject, NON_ALPHANUMERIC),
+        utf8_percent_encode(&email.body, NON_ALPHANUMERIC)
+    );
+    app_handle
+        .opener()
+        .open_url(&url, None::<&str>)
+        .map_err(|e| format!("Failed to open mail app: {}", e))
+}
+
+/// Send an email through the SMTP server in settings, or open it prefilled
+/// in the user's mail app when none is set. Returns `{method, attachments}`
+/// where `method` is "smtp" or "mailto"; with "mailto" the frontend should
+/// tell the user which files to attach.
+pub(crate) async fn send(
+    app_handle: &tauri::AppHandle,
+    email: &OutgoingEmail,
+) -> Result<Value, String> {
+    if email.to.is_empty() {
+        return Err("Add at least one recipient".to_string());
+    }
+    let recipients = email
+        .to
+        .iter()
+        .map(|address| parse_mailbox(address))
+        .collect::<Result<Vec<_>, _>>()?;
+    for path in &email.attachments {
+        if !path.is_absolute() || !path.is_file() {
+            return Err(format!("File not found: {}", path.display()));
+        }
+    }
+
+    let settings = settings_storage::load_settings(app_handle).await?.email;
+    let host = settings.smtp_host.as_deref().filter(|h| !h.trim().is_empty());
+    let method = match host {
+        Some(host) => {
+            let message = build_message(&settings, &recipients, email).await?;
+            send_smtp(&settings, host.trim(), message).await?;
+            "smtp"
+        }
+        None => {
+            open_mailto(app_handle, &recipients, email)?;
+            "mailto"
+        }
+    };
+
+    let attachments: Vec<String> = email
+        .attachments
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    Ok(serde_json::json!({
+        "method": method,
+        "attachments": attachments,
+    }))
+}
+
+// ============================================
+// Email Commands
+// ============================================
+
+/// Store the SMTP password in the OS keychain, or clear it when `password`
+/// is empty or omitted
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn set_smtp_password(
+    app_handle: tauri::AppHandle,
+    password: Option<String>,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    match password.filter(|p| !p.is_empty()) {
+        Some(password) => secrets::write_secret(SMTP_PASSWORD_KEY, &password).await?,
+        None => secrets::remove_secret(SMTP_PASSWORD_KEY).await?,
+    }
+
+    audit_log::record(&app_handle, "set_smtp_password", "secret", &[]).await;
+
+    Ok(())
+}
+
+/// Email an artifact's exported PDF (at `pdf_path`, written by `export_pdf`
+/// for this artifact) to `to`. The subject
+/// defaults to the artifact's title. Sent over SMTP when configured,
+/// otherwise opened in the user's mail app; returns `{method, attachments}`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn send_artifact_email(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    pdf_path: String,
+    to: Vec<String>,
+    subject: Option<String>,
+    body: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let pdf_path = pdf_export::exported_pdf(&app_handle, &artifact_id, &pdf_path)?;
+    let artifact = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    let title = artifact
+        .get("title")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Worksheet")
+        .to_string();
+    let email = OutgoingEmail {
+        to,
+        subject: subject.filter(|s| !s.trim().is_empty()).unwrap_or(title),
+        body: body.unwrap_or_default(),
+        attachments: vec![pdf_path],
+    };
+    let result = send(&app_handle, &email).await?;
+
+    audit_log::record(&app_handle, "send_artifact_email", "artifact", &[&artifact_id]).await;
+
+    Ok(result.to_string())
+}
@@ -0,0 +1,215 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokio::fs;
+
+use crate::archive;
+use crate::commands::{
+    app_lock, audit_log, data_history, disk_space, email, google_drive, http_client, image_cache,
+    index_cache, local_image, logging, mcp_server, oauth_device, onedrive, rest_api, secrets,
+    session_mode, settings_storage, task_manager,
+};
+
+/// Text the user must type to request a factory reset
+const CONFIRMATION_PHRASE: &str = "RESET";
+
+// How long an issued confirmation token stays valid
+const TOKEN_TTL: Duration = Duration::from_secs(120);
+
+// Keychain entries the app keeps besides the PIN and sign-in tokens, removed
+// by a reset
+const SECRET_KEYS: [&str; 4] = [
+    rest_api::TOKEN_KEY,
+    mcp_server::TOKEN_KEY,
+    email::SMTP_PASSWORD_KEY,
+    http_client::PROXY_PASSWORD_KEY,
+];
+
+// Default backup folder (inside Documents) when no backup directory is set
+const DEFAULT_BACKUP_DIR: &str = "TA Backups";
+
+struct PendingReset {
+    token: String,
+    issued: Instant,
+}
+
+/// Managed state holding the outstanding factory reset token, if any
+#[derive(Default)]
+pub struct FactoryResetState {
+    pending: Mutex<Option<PendingReset>>,
+}
+
+/// Where backups are written: the directory in settings, or a folder in
+/// Documents. It lies outside the app data directory, so the pre-reset
+/// backup survives the wipe.
+pub(crate) async fn get_backup_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let settings = settings_storage::load_settings(app_handle).await?;
+    if let Some(dir) = settings.backup.directory {
+        return Ok(PathBuf::from(dir));
+    }
+
+    let documents_dir = app_handle
+        .path()
+        .document_dir()
+        .map_err(|e| format!("Failed to get documents directory: {}", e))?;
+    Ok(documents_dir.join(DEFAULT_BACKUP_DIR))
+}
+
+// Helper to zip the app data directory into the backup file. Image models
+// and cached images are left out, as they can be downloaded or generated
+// again, and so is the data history, which may hold erased learner data.
+async fn write_backup(app_data_dir: &Path, backup_path: &Path) -> Result<(), String> {
+    let entries = if app_data_dir.exists() {
+        let skipped = [
+            local_image::MODELS_DIR,
+            image_cache::CACHE_DIR,
+            data_history::HISTORY_DIR,
+        ];
+        archive::collect_dir(app_data_dir, "", &skipped).await?
+    } else {
+        Vec::new()
+    };
+    let bundle = archive::build_zip(&entries)?;
+    disk_space::ensure_available(backup_path, bundle.len() as u64)?;
+    fs::write(backup_path, bundle)
+        .await
+        .map_err(|e| format!("Failed to write backup: {}", e))
+}
+
+// ============================================
+// Factory Reset Commands
+// ============================================
+
+/// Request a factory reset. The user must type the confirmation phrase;
+/// returns a short-lived token that `factory_reset` requires.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn request_factory_reset(
+    app_handle: tauri::AppHandle,
+    confirmation_text: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    if confirmation_text.trim() != CONFIRMATION_PHRASE {
+        return Err(format!("Type {} to confirm the factory reset", CONFIRMATION_PHRASE));
+    }
+
+    let token = uuid::Uuid::new_v4().to_string();
+    let state = app_handle.state::<FactoryResetState>();
+    *state.pending.lock().unwrap() = Some(PendingReset {
+        token: token.clone(),
+        issued: Instant::now(),
+    });
+
+    let response = serde_json::json!({
+        "token": token,
+        "expiresInSeconds": TOKEN_TTL.as_secs(),
+    });
+    Ok(response.to_string())
+}
+
+/// Back up and then wipe the app data directory. Requires a token from
+/// `request_factory_reset`; each token works once. Logs are kept so the
+/// reset itself can be diagnosed. The PIN, server tokens, passwords, and
+/// sign-in tokens are removed from the keychain (secrets stored under
+/// `user.` keys with `set_secret` can't be listed and are left for the
+/// frontend to delete), the app is unlocked, and the local servers follow the default
+/// settings again. The backup runs as a cancellable "backup"
+/// task (ID `operation_id` when given); cancelling it leaves the data
+/// untouched. Returns the backup file path.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn factory_reset(
+    app_handle: tauri::AppHandle,
+    confirmation_token: String,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    // Consume the token whether or not it matches
+    let pending = app_handle
+        .state::<FactoryResetState>()
+        .pending
+        .lock()
+        .unwrap()
+        .take();
+    match pending {
+        Some(p) if p.token == confirmation_token && p.issued.elapsed() <= TOKEN_TTL => {}
+        Some(p) if p.token == confirmation_token => {
+            return Err("Factory reset confirmation expired, please request it again".to_string())
+        }
+        _ => return Err("Invalid factory reset confirmation".to_string()),
+    }
+
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let logs_dir = logging::get_logs_dir(&app_handle)?;
+
+    // Back up everything first; abort the reset if the backup fails
+    index_cache::flush(&app_handle).await?;
+    let backup_dir = get_backup_dir(&app_handle).await?;
+    fs::create_dir_all(&backup_dir)
+        .await
+        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    let backup_path = backup_dir.join(format!(
+        "ta-factory-reset-{}.zip",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    let task =
+        task_manager::start_task(&app_handle, operation_id, "backup", "Factory reset backup")?;
+    let backup = task.cancellable(write_backup(&app_data_dir, &backup_path)).await;
+    // Past this point the wipe runs to the end; a half-wiped directory is
+    // worse than an unwanted reset
+    let backup = backup.and_then(|_| task.check_cancelled());
+    task.finish(&backup);
+    if backup.is_err() {
+        let _ = fs::remove_file(&backup_path).await;
+    }
+    backup?;
+
+    // Wipe
+    if app_data_dir.exists() {
+        let mut dir = fs::read_dir(&app_data_dir)
+            .await
+            .map_err(|e| format!("Failed to read app data directory: {}", e))?;
+        while let Some(entry) = dir
+            .next_entry()
+            .await
+            .map_err(|e| format!("Failed to read app data directory: {}", e))?
+        {
+            let path = entry.path();
+            if path == logs_dir {
+                continue;
+            }
+            let removed = if path.is_dir() {
+                fs::remove_dir_all(&path).await
+            } else {
+                fs::remove_file(&path).await
+            };
+            removed.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+    }
+
+    // Forget what's kept outside the data directory
+    index_cache::clear(&app_handle);
+    session_mode::reset(&app_handle);
+    app_lock::forget_pin(&app_handle).await?;
+    for key in SECRET_KEYS {
+        secrets::remove_secret(key).await?;
+    }
+    oauth_device::disconnect(&google_drive::PROVIDER).await?;
+    oauth_device::disconnect(&onedrive::PROVIDER).await?;
+    rest_api::apply(&app_handle).await;
+    mcp_server::apply(&app_handle).await;
+
+    let backup_path = backup_path.to_string_lossy().to_string();
+    tracing::warn!(backup = %backup_path, "Factory reset completed");
+    audit_log::record(&app_handle, "factory_reset", "app_data", &[]).await;
+
+    Ok(backup_path)
+}
@@ -1,8 +1,18 @@
 use std::fs;
 use std::path::Path;
 
+use crate::commands::{app_lock, session_mode};
+
 #[tauri::command]
-pub async fn save_file(path: String, content: String) -> Result<(), String> {
+#[tracing::instrument(skip_all, err)]
+pub async fn save_file(
+    app_handle: tauri::AppHandle,
+    path: String,
+    content: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
     let path = Path::new(&path);
 
     // Create parent directories if they don't exist
@@ -14,6 +24,10 @@ pub async fn save_file(path: String, content: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn read_file(path: String) -> Result<String, String> {
+#[tracing::instrument(skip_all, err)]
+pub async fn read_file(app_handle: tauri::AppHandle, path: String) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
     fs::read_to_string(&path).map_err(|e| e.to_string())
 }
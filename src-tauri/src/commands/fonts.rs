@@ -0,0 +1,550 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, design_pack_storage, http_client, session_mode};
+
+const FONTS_DIR: &str = "fonts";
+const INDEX_FILE: &str = "index.json";
+
+// Google Fonts CSS API; the user agent decides which font format it serves
+const GOOGLE_FONTS_CSS_URL: &str = "https://fonts.googleapis.com/css2";
+const WOFF2_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+     (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Weights fetched when a pack names a font without listing weights
+const DEFAULT_WEIGHTS: &[u16] = &[400, 700];
+// Unicode subsets worth keeping for printed worksheets
+const KEPT_SUBSETS: &[&str] = &["latin", "latin-ext"];
+
+/// A single stored font file (one family/weight/style/subset)
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FontFace {
+    family: String,
+    weight: u16,
+    style: String,
+    unicode_range: Option<String>,
+    file_name: String,
+    source: String,
+    added_at: String,
+}
+
+/// An @font-face rule parsed from Google Fonts CSS
+struct RemoteFace {
+    style: String,
+    weight: u16,
+    unicode_range: Option<String>,
+    url: String,
+    subset: Option<String>,
+}
+
+// Helper to get the fonts directory
+fn get_fonts_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(FONTS_DIR))
+}
+
+// Helper to validate a font family name
+fn validate_family(family: &str) -> Result<(), String> {
+    let valid = !family.trim().is_empty()
+        && family.len() <= 64
+        && family
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_'));
+    if !valid {
+        return Err(format!("Invalid font family: {}", family));
+    }
+    Ok(())
+}
+
+// Helper to get a font file's extension and CSS format name
+fn font_format(name: &str) -> Option<(&'static str, &'static str)> {
+    let extension = Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())?;
+    match extension.as_str() {
+        "woff2" => Some(("woff2", "woff2")),
+        "woff" => Some(("woff", "woff")),
+        "ttf" => Some(("ttf", "truetype")),
+        "otf" => Some(("otf", "opentype")),
+        _ => None,
+    }
+}
+
+// Helper to build a stored file name for a face
+fn face_file_name(family: &str, weight: u16, style: &str, subset: &str, extension: &str) -> String {
+    let slug: String = family
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    format!("{}-{}-{}-{}.{}", slug, weight, style, subset, extension)
+}
+
+// Helper to read the font index
+async fn read_index(fonts_dir: &Path) -> Vec<FontFace> {
+    match fs::read_to_string(fonts_dir.join(INDEX_FILE)).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// Helper to write the font index
+async fn write_index(fonts_dir: &Path, faces: &[FontFace]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(faces)
+        .map_err(|e| format!("Failed to serialize font index: {}", e))?;
+    fs::write(fonts_dir.join(INDEX_FILE), content)
+        .await
+        .map_err(|e| format!("Failed to write font index: {}", e))
+}
+
+// Helper to store a font file and add (or replace) its index entry
+async fn store_face(fonts_dir: &Path, face: FontFace, contents: &[u8]) -> Result<(), String> {
+    fs::create_dir_all(fonts_dir)
+        .await
+        .map_err(|e| format!("Failed to create fonts directory: {}", e))?;
+    fs::write(fonts_dir.join(&face.file_name), contents)
+        .await
+        .map_err(|e| format!("Failed to write font file: {}", e))?;
+
+    let mut faces = read_index(fonts_dir).await;
+    faces.retain(|f| f.file_name != face.file_name);
+    faces.push(face);
+    write_index(fonts_dir, &faces).await
+}
+
+// Helper to parse the @font-face rules in a Google Fonts stylesheet.
+// Each rule is preceded by a `/* subset */` comment.
+fn parse_font_faces(css: &str) -> Vec<RemoteFace> {
+    let chunks: Vec<&str> = css.split("@font-face").collect();
+    let mut faces = Vec::new();
+
+    for (i, chunk) in chunks.iter().enumerate().skip(1) {
+        let subset = chunks[i - 1].rfind("/*").and_then(|start| {
+            let comment = &chunks[i - 1][start + 2..];
+            comment.find("*/").map(|end| comment[..end].trim().to_string())
+        });
+        let Some(body) = chunk
+            .split_once('{')
+            .and_then(|(_, rest)| rest.split_once('}'))
+            .map(|(body, _)| body)
+        else {
+            continue;
+        };
+
+        let mut style = "normal".to_string();
+        let mut weight = 400;
+        let mut unicode_range = None;
+        let mut url = None;
+        for declaration in body.split(';') {
+            let Some((key, value)) = declaration.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "font-style" => style = value.to_string(),
+                "font-weight" => weight = value.parse().unwrap_or(400),
+                "unicode-range" => unicode_range = Some(value.to_string()),
+                "src" => {
+                    url = value
+                        .split_once("url(")
+                        .and_then(|(_, rest)| rest.split_once(')'))
+                        .map(|(url, _)| url.trim_matches(|c| c == '\'' || c == '"').to_string())
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(url) = url {
+            faces.push(RemoteFace {
+                style,
+                weight,
+                unicode_range,
+                url,
+                subset: subset.clone(),
+            });
+        }
+    }
+
+    faces
+}
+
+// Helper to download a family from Google Fonts. Returns the faces stored.
+async fn download_family(
+    fonts_dir: &Path,
+    family: &str,
+    weights: &[u16],
+) -> Result<usize, String> {
+    let client = http_client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .user_agent(WOFF2_USER_AGENT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let weight_list: Vec<String> = weights.iter().map(|w| w.to_string()).collect();
+    let query = format!("{}:wght@{}", family, weight_list.join(";"));
+    let css = client
+        .get(GOOGLE_FONTS_CSS_URL)
+        .query(&[("family", query.as_str()), ("display", "swap")])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch {} from Google Fonts: {}", family, e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to fetch {} from Google Fonts: {}", family, e))?;
+
+    let mut stored = 0;
+    for remote in parse_font_faces(&css) {
+        let subset = remote.subset.as_deref().unwrap_or("all");
+        if remote.subset.is_some() && !KEPT_SUBSETS.contains(&subset) {
+            continue;
+        }
+        let extension = font_format(&remote.url).map_or("woff2", |(ext, _)| ext);
+        let bytes = client
+            .get(&remote.url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to download {}: {}", family, e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to download {}: {}", family, e))?;
+
+        let face = FontFace {
+            family: family.to_string(),
+            weight: remote.weight,
+            file_name: face_file_name(family, remote.weight, &remote.style, subset, extension),
+            style: remote.style,
+            unicode_range: remote.unicode_range,
+            source: "google".to_string(),
+            added_at: chrono::Utc::now().to_rfc3339(),
+        };
+        store_face(fonts_dir, face, &bytes).await?;
+        stored += 1;
+    }
+
+    if stored == 0 {
+        return Err(format!("Google Fonts has no files for {}", family));
+    }
+    Ok(stored)
+}
+
+// Helper to read the fonts a pack names: strings or `{ family, weights }`
+fn pack_font_requests(pack: &Value) -> Vec<(String, Vec<u16>)> {
+    pack.get("fonts")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|font| match font {
+            Value::String(family) => Some((family.clone(), DEFAULT_WEIGHTS.to_vec())),
+            Value::Object(obj) => {
+                let family = obj.get("family")?.as_str()?.to_string();
+                let weights: Vec<u16> = obj
+                    .get("weights")
+                    .and_then(|v| v.as_array())
+                    .map(|ws| ws.iter().filter_map(|w| w.as_u64()).map(|w| w as u16).collect())
+                    .unwrap_or_default();
+                let weights = if weights.is_empty() {
+                    DEFAULT_WEIGHTS.to_vec()
+                } else {
+                    weights
+                };
+                Some((family, weights))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Make sure a family's normal-style faces are stored, downloading each
+/// missing `(weight, url)` face
+pub(crate) async fn ensure_family(
+    app_handle: &tauri::AppHandle,
+    family: &str,
+    faces: &[(u16, &str)],
+) -> Result<(), String> {
+    let fonts_dir = get_fonts_dir(app_handle)?;
+    let existing = read_index(&fonts_dir).await;
+    let client = http_client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    for (weight, url) in faces {
+        if existing.iter().any(|f| f.family == family && f.weight == *weight) {
+            continue;
+        }
+        let bytes = client
+            .get(*url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to download {}: {}", family, e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to download {}: {}", family, e))?;
+        let extension = font_format(url).map_or("otf", |(ext, _)| ext);
+        let face = FontFace {
+            file_name: face_file_name(family, *weight, "normal", "all", extension),
+            family: family.to_string(),
+            weight: *weight,
+            style: "normal".to_string(),
+            unicode_range: None,
+            source: "web".to_string(),
+            added_at: chrono::Utc::now().to_rfc3339(),
+        };
+        store_face(&fonts_dir, face, &bytes).await?;
+    }
+    Ok(())
+}
+
+/// Embed stored fonts into an HTML document as base64 `@font-face` rules so
+/// it prints correctly without internet access or installed fonts. Embeds
+/// `families` if given, otherwise every stored family the HTML mentions.
+pub(crate) async fn embed_fonts(
+    app_handle: &tauri::AppHandle,
+    html: String,
+    families: Option<&[String]>,
+) -> Result<String, String> {
+    let fonts_dir = get_fonts_dir(app_handle)?;
+    let html_lower = html.to_ascii_lowercase();
+    let faces: Vec<FontFace> = read_index(&fonts_dir)
+        .await
+        .into_iter()
+        .filter(|f| match families {
+            Some(families) => families.iter().any(|family| family == &f.family),
+            None => html_lower.contains(&f.family.to_ascii_lowercase()),
+        })
+        .collect();
+    if faces.is_empty() {
+        return Ok(html);
+    }
+
+    let mut css = String::from("<style data-embedded-fonts>\n");
+    for face in &faces {
+        let Some((extension, format)) = font_format(&face.file_name) else {
+            continue;
+        };
+        let Ok(contents) = fs::read(fonts_dir.join(&face.file_name)).await else {
+            tracing::warn!(file = %face.file_name, "Stored font file is missing");
+            continue;
+        };
+        let encoded = base64::engine::general_purpose::STANDARD.encode(contents);
+        css.push_str(&format!(
+            "@font-face {{ font-family: '{}'; font-style: {}; font-weight: {}; \
+             src: url(data:font/{};base64,{}) format('{}');{} }}\n",
+            face.family,
+            face.style,
+            face.weight,
+            extension,
+            encoded,
+            format,
+            face.unicode_range
+                .as_ref()
+                .map(|range| format!(" unicode-range: {};", range))
+                .unwrap_or_default(),
+        ));
+    }
+    css.push_str("</style>\n");
+
+    // Put the rules at the end of <head>, or at the top for fragments
+    let result = match html_lower.find("</head>") {
+        Some(index) => format!("{}{}{}", &html[..index], css, &html[index..]),
+        None => format!("{}{}", css, html),
+    };
+    Ok(result)
+}
+
+// ============================================
+// Font Commands
+// ============================================
+
+/// Get every stored font face
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_fonts(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let faces = read_index(&get_fonts_dir(&app_handle)?).await;
+    serde_json::to_string(&faces).map_err(|e| format!("Failed to serialize fonts: {}", e))
+}
+
+/// Store a local font file (TTF, OTF, WOFF, WOFF2) under a family name
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn import_font_file(
+    app_handle: tauri::AppHandle,
+    path: String,
+    family: String,
+    weight: Option<u16>,
+    style: Option<String>,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    validate_family(&family)?;
+
+    let (extension, _) = font_format(&path).ok_or("Unsupported font file type")?;
+    let style = style.unwrap_or_else(|| "normal".to_string());
+    if style != "normal" && style != "italic" {
+        return Err(format!("Invalid font style: {}", style));
+    }
+    let weight = weight.unwrap_or(400);
+
+    let contents = fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read font file: {}", e))?;
+
+    let face = FontFace {
+        file_name: face_file_name(&family, weight, &style, "all", extension),
+        family: family.clone(),
+        weight,
+        style,
+        unicode_range: None,
+        source: "file".to_string(),
+        added_at: chrono::Utc::now().to_rfc3339(),
+    };
+    store_face(&get_fonts_dir(&app_handle)?, face, &contents).await?;
+
+    audit_log::record(&app_handle, "import_font_file", "font", &[&family]).await;
+
+    Ok(())
+}
+
+/// Make every font a design pack uses available offline: font files bundled
+/// as pack assets are stored directly, and families listed under the pack's
+/// `fonts` are downloaded from Google Fonts. Returns
+/// `{ stored, families, failed }`; families that fail are listed, not fatal.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn sync_pack_fonts(
+    app_handle: tauri::AppHandle,
+    pack_id: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let pack = design_pack_storage::find_pack(&app_handle, &pack_id).await?;
+    let fonts_dir = get_fonts_dir(&app_handle)?;
+    let mut stored = 0;
+    let mut families = Vec::new();
+    let mut failed = Vec::new();
+
+    // Font files bundled with the pack (family taken from the file name)
+    let assets_dir = design_pack_storage::get_assets_dir(&app_handle, &pack_id)?;
+    let font_assets = pack
+        .get("assets")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|a| a.get("kind").and_then(|v| v.as_str()) == Some("font"))
+        .filter_map(|a| a.get("name").and_then(|v| v.as_str()));
+    for name in font_assets {
+        let Some((extension, _)) = font_format(name) else {
+            continue;
+        };
+        let family: String = Path::new(name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().replace(['-', '_'], " "))
+            .unwrap_or_default();
+        if validate_family(&family).is_err() {
+            failed.push(serde_json::json!({ "family": name, "error": "Invalid font name" }));
+            continue;
+        }
+        let Ok(contents) = fs::read(assets_dir.join(name)).await else {
+            failed.push(serde_json::json!({ "family": family, "error": "Asset file missing" }));
+            continue;
+        };
+        let face = FontFace {
+            file_name: face_file_name(&family, 400, "normal", "all", extension),
+            family: family.clone(),
+            weight: 400,
+            style: "normal".to_string(),
+            unicode_range: None,
+            source: format!("pack:{}", pack_id),
+            added_at: chrono::Utc::now().to_rfc3339(),
+        };
+        store_face(&fonts_dir, face, &contents).await?;
+        stored += 1;
+        families.push(family);
+    }
+
+    // Families named by the pack, downloaded unless already stored
+    let existing = read_index(&fonts_dir).await;
+    for (family, weights) in pack_font_requests(&pack) {
+        if let Err(e) = validate_family(&family) {
+            failed.push(serde_json::json!({ "family": family, "error": e }));
+            continue;
+        }
+        let missing: Vec<u16> = weights
+            .into_iter()
+            .filter(|w| !existing.iter().any(|f| f.family == family && f.weight == *w))
+            .collect();
+        if missing.is_empty() {
+            families.push(family);
+            continue;
+        }
+        match download_family(&fonts_dir, &family, &missing).await {
+            Ok(count) => {
+                stored += count;
+                families.push(family);
+            }
+            Err(e) => {
+                tracing::warn!(family = %family, error = %e, "Failed to download font");
+                failed.push(serde_json::json!({ "family": family, "error": e }));
+            }
+        }
+    }
+
+    if stored > 0 {
+        audit_log::record(&app_handle, "sync_pack_fonts", "design_pack", &[&pack_id]).await;
+    }
+
+    let response = serde_json::json!({
+        "stored": stored,
+        "families": families,
+        "failed": failed,
+    });
+    Ok(response.to_string())
+}
+
+/// Delete every stored face of a font family
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_font(app_handle: tauri::AppHandle, family: String) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let fonts_dir = get_fonts_dir(&app_handle)?;
+    let mut faces = read_index(&fonts_dir).await;
+    for face in faces.iter().filter(|f| f.family == family) {
+        let path = fonts_dir.join(&face.file_name);
+        if path.exists() {
+            fs::remove_file(&path)
+                .await
+                .map_err(|e| format!("Failed to delete font file: {}", e))?;
+        }
+    }
+    faces.retain(|f| f.family != family);
+    write_index(&fonts_dir, &faces).await?;
+
+    audit_log::record(&app_handle, "delete_font", "font", &[&family]).await;
+
+    Ok(())
+}
+
+/// Embed stored fonts into an HTML document; see `embed_fonts`. Returns the
+/// updated HTML.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn embed_fonts_in_html(
+    app_handle: tauri::AppHandle,
+    html: String,
+    families: Option<Vec<String>>,
+) -> Result<String, String> {
+    embed_fonts(&app_handle, html, families.as_deref()).await
+}
@@ -0,0 +1,224 @@
+use serde_json::Value;
+
+use crate::commands::{
+    app_lock, artifact_analysis, audit_log, generation_history, library_storage, ollama,
+    reading_level, session_mode, settings_storage, task_manager,
+};
+
+// Readability may run this many grades above the target before losing points
+const READABILITY_SLACK: f64 = 1.0;
+
+// Points lost per grade of readability beyond the slack
+const READABILITY_PENALTY: f64 = 0.25;
+
+const JUDGE_SYSTEM_PROMPT: &str = "You grade classroom worksheets for teachers. \
+    Reply with a single whole number from 1 (unusable) to 5 (ready to print), nothing else.";
+
+// Helper to build one rubric check
+fn check(name: &str, score: f64, detail: String) -> Value {
+    let score = score.clamp(0.0, 1.0);
+    serde_json::json!({
+        "name": name,
+        "score": (score * 100.0).round() / 100.0,
+        "passed": score >= 0.75,
+        "detail": detail,
+    })
+}
+
+// Helper to ask a second model for an overall 1-5 grade, as a 0-1 score
+async fn judge(
+    endpoint: &str,
+    model: &str,
+    prompt: &str,
+    grade: Option<&str>,
+    text: &str,
+) -> Result<f64, String> {
+    let request = format!(
+        "The teacher asked for: {}\nGrade level: {}\n\nWorksheet text:\n{}",
+        prompt,
+        grade.unwrap_or("unspecified"),
+        text
+    );
+    let reply = ollama::generate(endpoint, model, JUDGE_SYSTEM_PROMPT, &request).await?;
+    let rating = reply
+        .chars()
+        .find_map(|c| c.to_digit(10))
+        .filter(|n| (1..=5).contains(n))
+        .ok_or_else(|| format!("Judge gave no rating: {}", reply))?;
+    Ok((rating - 1) as f64 / 4.0)
+}
+
+// ============================================
+// Generation Evaluation Commands
+// ============================================
+
+/// Score a recorded generation against a rubric and store the result on
+/// its history entry as `evaluation`.
+///
+/// Deterministic checks cover objective coverage, item count against the
+/// requested `questionCount`, answer-key presence, and readability against
+/// the requested grade; checks the request gives nothing to compare with
+/// are left out. `options` may set `judge: true` (and `judgeModel`) to add a
+/// second-model rating, which runs as a cancellable task named by
+/// `operationId` when given. Returns `{ generationId, artifactId, checks,
+/// overallScore, evaluatedAt }`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn evaluate_generation(
+    app_handle: tauri::AppHandle,
+    generation_id: String,
+    options: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let options: Value = match options {
+        Some(options) => serde_json::from_str(&options)
+            .map_err(|e| format!("Invalid options JSON: {}", e))?,
+        None => Value::Object(serde_json::Map::new()),
+    };
+
+    let mut history = generation_history::read_history(&app_handle).await?;
+    let index = history
+        .iter()
+        .position(|e| generation_history::entry_id(e) == Some(generation_id.as_str()))
+        .ok_or_else(|| format!("Generation not found: {}", generation_id))?;
+    let entry = &history[index];
+    let parameters = entry.get("parameters").cloned().unwrap_or(Value::Null);
+    let request_options = parameters.get("options");
+
+    let mut artifacts = Vec::new();
+    for id in entry
+        .get("artifactIds")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+    {
+        match library_storage::read_artifact(&app_handle, id).await {
+            Ok(artifact) => artifacts.push(artifact),
+            Err(e) => tracing::warn!(artifact_id = %id, error = %e, "Skipping missing artifact"),
+        }
+    }
+    let artifact_type = |a: &Value| a.get("type").and_then(|v| v.as_str()).map(String::from);
+    let student_page = artifacts
+        .iter()
+        .find(|a| artifact_type(a).as_deref() == Some("student_page"))
+        .or_else(|| artifacts.first())
+        .ok_or("This generation has no artifacts left to evaluate")?;
+    let html = student_page
+        .get("htmlContent")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let analysis = artifact_analysis::analyze_html(&app_handle, html);
+    let text = artifact_analysis::text_runs(html).join(" ");
+
+    let mut checks = Vec::new();
+
+    let tags: Vec<&str> = artifacts
+        .iter()
+        .filter_map(|a| a.get("objectiveTags").and_then(|v| v.as_array()))
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .collect();
+    match parameters.get("objectiveId").and_then(|v| v.as_str()) {
+        Some(objective) if tags.contains(&objective) => checks.push(check(
+            "objective_coverage",
+            1.0,
+            format!("Tagged with {}", objective),
+        )),
+        Some(objective) => checks.push(check(
+            "objective_coverage",
+            0.0,
+            format!("Not tagged with {}", objective),
+        )),
+        None if !tags.is_empty() => {
+            checks.push(check("objective_coverage", 1.0, format!("{} objective tags", tags.len())))
+        }
+        None => {}
+    }
+
+    let actual = analysis["questions"]["total"].as_u64().unwrap_or(0);
+    if let Some(expected) = request_options
+        .and_then(|o| o.get("questionCount"))
+        .and_then(|v| v.as_u64())
+        .filter(|n| *n > 0)
+    {
+        let off_by = actual.abs_diff(expected) as f64;
+        checks.push(check(
+            "item_count",
+            1.0 - off_by / expected as f64,
+            format!("{} of {} requested questions", actual, expected),
+        ));
+    }
+
+    let wants_answer_key = request_options
+        .and_then(|o| o.get("includeAnswerKey"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    if wants_answer_key {
+        let present = artifacts
+            .iter()
+            .any(|a| artifact_type(a).as_deref() == Some("answer_key"));
+        let detail = if present { "Answer key included" } else { "No answer key" };
+        checks.push(check(
+            "answer_key",
+            if present { 1.0 } else { 0.0 },
+            detail.to_string(),
+        ));
+    }
+
+    let grade = parameters.get("grade").and_then(|v| v.as_str());
+    if let Some(target) = grade.and_then(|g| reading_level::parse_grade(g).ok()) {
+        let score = reading_level::readability_grade(&text);
+        let over = (score - target as f64 - READABILITY_SLACK).max(0.0);
+        checks.push(check(
+            "readability",
+            1.0 - over * READABILITY_PENALTY,
+            format!("Reads at about grade {:.1}", score),
+        ));
+    }
+
+    if options.get("judge").and_then(|v| v.as_bool()) == Some(true) {
+        let settings = settings_storage::load_settings(&app_handle).await?;
+        let model = options
+            .get("judgeModel")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or(settings.ollama.default_model.clone())
+            .unwrap_or_else(|| ollama::RECOMMENDED_MODEL.to_string());
+        let prompt = entry.get("prompt").and_then(|v| v.as_str()).unwrap_or("");
+        let operation_id = options.get("operationId").and_then(|v| v.as_str()).map(String::from);
+        let task = task_manager::start_task(&app_handle, operation_id, "evaluation", "Judge")?;
+        let score = task
+            .cancellable(judge(&settings.ollama.endpoint, &model, prompt, grade, &text))
+            .await;
+        task.finish(&score);
+        let score = score?;
+        checks.push(check("judge", score, format!("Rated by {}", model)));
+    }
+
+    let scores: Vec<f64> = checks.iter().filter_map(|c| c["score"].as_f64()).collect();
+    let overall = if scores.is_empty() {
+        Value::Null
+    } else {
+        let mean = scores.iter().sum::<f64>() / scores.len() as f64;
+        Value::from((mean * 100.0).round() / 100.0)
+    };
+
+    let evaluation = serde_json::json!({
+        "generationId": generation_id,
+        "artifactId": student_page.get("artifactId"),
+        "checks": checks,
+        "overallScore": overall,
+        "evaluatedAt": chrono::Utc::now().to_rfc3339(),
+    });
+    if let Some(obj) = history[index].as_object_mut() {
+        obj.insert("evaluation".to_string(), evaluation.clone());
+    }
+    generation_history::write_history(&app_handle, history).await?;
+
+    audit_log::record(&app_handle, "evaluate_generation", "generation", &[&generation_id]).await;
+
+    Ok(evaluation.to_string())
+}
@@ -0,0 +1,207 @@
+use serde_json::Value;
+use std::path::PathBuf;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, session_mode};
+
+const HISTORY_DIR: &str = "generation-history";
+const HISTORY_FILE: &str = "history.json";
+
+// Oldest runs are dropped beyond this many
+const MAX_ENTRIES: usize = 2000;
+
+const DEFAULT_LIMIT: usize = 100;
+
+// Request fields a replay may change
+const REPLAY_KEYS: &[&str] = &["prompt", "model", "provider", "projectId"];
+
+// Helper to get the generation history file path
+fn get_history_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(HISTORY_DIR).join(HISTORY_FILE))
+}
+
+/// Read every recorded generation, oldest first
+pub(crate) async fn read_history(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let history_path = get_history_path(app_handle)?;
+    if !history_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&history_path)
+        .await
+        .map_err(|e| format!("Failed to read generation history: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
+/// Write the generation history, keeping only the newest `MAX_ENTRIES` runs
+pub(crate) async fn write_history(
+    app_handle: &tauri::AppHandle,
+    mut history: Vec<Value>,
+) -> Result<(), String> {
+    let history_path = get_history_path(app_handle)?;
+    if let Some(parent) = history_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create generation history directory: {}", e))?;
+    }
+    if history.len() > MAX_ENTRIES {
+        history.drain(..history.len() - MAX_ENTRIES);
+    }
+    let content = serde_json::to_string_pretty(&history)
+        .map_err(|e| format!("Failed to serialize generation history: {}", e))?;
+    fs::write(&history_path, content)
+        .await
+        .map_err(|e| format!("Failed to write generation history: {}", e))
+}
+
+/// Get a recorded generation's ID
+pub(crate) fn entry_id(entry: &Value) -> Option<&str> {
+    entry.get("generationId").and_then(|v| v.as_str())
+}
+
+// Helper to check whether a run produced the given artifact
+fn produced(entry: &Value, artifact_id: &str) -> bool {
+    entry
+        .get("artifactIds")
+        .and_then(|v| v.as_array())
+        .is_some_and(|ids| ids.iter().any(|id| id.as_str() == Some(artifact_id)))
+}
+
+/// Recorded generations matching a `get_generation_history` query, newest
+/// first
+pub(crate) async fn query_history(
+    app_handle: &tauri::AppHandle,
+    query: Option<String>,
+) -> Result<Vec<Value>, String> {
+    let query: Value = match query {
+        Some(query) => {
+            serde_json::from_str(&query).map_err(|e| format!("Invalid query JSON: {}", e))?
+        }
+        None => Value::Object(serde_json::Map::new()),
+    };
+    let project_id = query.get("projectId").and_then(|v| v.as_str());
+    let artifact_id = query.get("artifactId").and_then(|v| v.as_str());
+    let model = query.get("model").and_then(|v| v.as_str());
+    let limit = query
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_LIMIT, |n| n as usize);
+
+    let history = read_history(app_handle).await?;
+    Ok(history
+        .into_iter()
+        .rev()
+        .filter(|e| {
+            project_id.is_none_or(|p| e.get("projectId").and_then(|v| v.as_str()) == Some(p))
+        })
+        .filter(|e| artifact_id.is_none_or(|a| produced(e, a)))
+        .filter(|e| model.is_none_or(|m| e.get("model").and_then(|v| v.as_str()) == Some(m)))
+        .take(limit)
+        .collect())
+}
+
+// ============================================
+// Generation History Commands
+// ============================================
+
+/// Record a finished generation run: `prompt`, `model`, `provider`,
+/// `parameters` (grade, subject, options, and so on), `durationMs`,
+/// `tokens` produced, `projectId`, the resulting `artifactIds`, and
+/// `replayOf` when it re-ran an earlier generation. Returns the new
+/// `generationId`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn record_generation(
+    app_handle: tauri::AppHandle,
+    entry: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut entry: Value = serde_json::from_str(&entry)
+        .map_err(|e| format!("Invalid generation JSON: {}", e))?;
+    let obj = entry.as_object_mut().ok_or("Generation must be an object")?;
+    let prompt = obj.get("prompt").and_then(|v| v.as_str()).unwrap_or("");
+    if prompt.trim().is_empty() {
+        return Err("Generation must have a prompt".to_string());
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    obj.insert("generationId".to_string(), Value::String(id.clone()));
+    obj.entry("artifactIds").or_insert_with(|| Value::Array(Vec::new()));
+    obj.insert(
+        "createdAt".to_string(),
+        Value::String(chrono::Utc::now().to_rfc3339()),
+    );
+
+    let mut history = read_history(&app_handle).await?;
+    history.push(entry);
+    write_history(&app_handle, history).await?;
+
+    audit_log::record(&app_handle, "record_generation", "generation", &[&id]).await;
+
+    Ok(id)
+}
+
+/// Get recorded generations, newest first. The query may filter by
+/// `projectId`, `artifactId` (runs that produced it), and `model`, and set a
+/// `limit` (default 100).
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_generation_history(
+    app_handle: tauri::AppHandle,
+    query: Option<String>,
+) -> Result<String, String> {
+    let entries = query_history(&app_handle, query).await?;
+    serde_json::to_string(&entries)
+        .map_err(|e| format!("Failed to serialize generation history: {}", e))
+}
+
+/// Build a request that re-runs a recorded generation. `overrides` may
+/// replace `prompt`, `model`, `provider`, and `projectId`, and its
+/// `parameters` are merged over the recorded ones. The request carries
+/// `replayOf`; the frontend submits it to the generation service and then
+/// records the run with `record_generation` as usual.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn replay_generation(
+    app_handle: tauri::AppHandle,
+    generation_id: String,
+    overrides: Option<String>,
+) -> Result<String, String> {
+    let overrides: Value = match overrides {
+        Some(overrides) => serde_json::from_str(&overrides)
+            .map_err(|e| format!("Invalid overrides JSON: {}", e))?,
+        None => Value::Object(serde_json::Map::new()),
+    };
+
+    let history = read_history(&app_handle).await?;
+    let original = history
+        .iter()
+        .find(|e| entry_id(e) == Some(generation_id.as_str()))
+        .ok_or_else(|| format!("Generation not found: {}", generation_id))?;
+
+    let mut request = serde_json::Map::new();
+    for key in REPLAY_KEYS {
+        let value = overrides.get(*key).or_else(|| original.get(*key));
+        if let Some(value) = value {
+            request.insert(key.to_string(), value.clone());
+        }
+    }
+    let mut parameters = original
+        .get("parameters")
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default();
+    if let Some(changes) = overrides.get("parameters").and_then(|v| v.as_object()) {
+        parameters.extend(changes.clone());
+    }
+    request.insert("parameters".to_string(), Value::Object(parameters));
+    request.insert("replayOf".to_string(), Value::String(generation_id));
+
+    Ok(Value::Object(request).to_string())
+}
@@ -0,0 +1,121 @@
+use chrono::Timelike;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::commands::generation_history;
+
+/// Generation runs, tokens produced, and wall-clock time for one bucket
+#[derive(Default)]
+struct Usage {
+    runs: u64,
+    tokens: u64,
+    duration_ms: u64,
+}
+
+impl Usage {
+    fn add(&mut self, entry: &Value) {
+        self.runs += 1;
+        self.tokens += entry.get("tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+        self.duration_ms += entry.get("durationMs").and_then(|v| v.as_u64()).unwrap_or(0);
+    }
+
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "runs": self.runs,
+            "tokens": self.tokens,
+            "durationMs": self.duration_ms,
+        })
+    }
+}
+
+// Helper to parse a `YYYY-MM-DD` query bound
+fn parse_date(query: &Value, key: &str) -> Result<Option<chrono::NaiveDate>, String> {
+    match query.get(key).and_then(|v| v.as_str()) {
+        Some(date) => chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|e| format!("Invalid {} date: {}", key, e)),
+        None => Ok(None),
+    }
+}
+
+// ============================================
+// Generation Usage Commands
+// ============================================
+
+/// Summarize generation runs, tokens produced, and wall-clock time in total,
+/// per project, per local calendar day, and per local hour of day (to show
+/// when the machine is busiest). The query may set `from`/`to` dates
+/// (`YYYY-MM-DD`, inclusive) and a `projectId`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_generation_usage(
+    app_handle: tauri::AppHandle,
+    query: Option<String>,
+) -> Result<String, String> {
+    let query: Value = match query {
+        Some(query) => {
+            serde_json::from_str(&query).map_err(|e| format!("Invalid query JSON: {}", e))?
+        }
+        None => Value::Object(serde_json::Map::new()),
+    };
+    let from = parse_date(&query, "from")?;
+    let to = parse_date(&query, "to")?;
+    let project_id = query.get("projectId").and_then(|v| v.as_str());
+
+    let mut total = Usage::default();
+    let mut by_project: BTreeMap<Option<String>, Usage> = BTreeMap::new();
+    let mut by_day: BTreeMap<chrono::NaiveDate, Usage> = BTreeMap::new();
+    let mut by_hour: BTreeMap<u32, Usage> = BTreeMap::new();
+
+    for entry in generation_history::read_history(&app_handle).await? {
+        let Some(created_at) = entry
+            .get("createdAt")
+            .and_then(|v| v.as_str())
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        else {
+            continue;
+        };
+        let local = created_at.with_timezone(&chrono::Local);
+        let day = local.date_naive();
+        if from.is_some_and(|from| day < from) || to.is_some_and(|to| day > to) {
+            continue;
+        }
+        let entry_project = entry.get("projectId").and_then(|v| v.as_str());
+        if project_id.is_some_and(|p| entry_project != Some(p)) {
+            continue;
+        }
+
+        total.add(&entry);
+        by_project.entry(entry_project.map(String::from)).or_default().add(&entry);
+        by_day.entry(day).or_default().add(&entry);
+        by_hour.entry(local.hour()).or_default().add(&entry);
+    }
+
+    let with_key = |key: &str, value: Value, usage: &Usage| {
+        let mut json = usage.to_json();
+        if let Some(obj) = json.as_object_mut() {
+            obj.insert(key.to_string(), value);
+        }
+        json
+    };
+    let by_project: Vec<Value> = by_project
+        .iter()
+        .map(|(id, usage)| with_key("projectId", serde_json::json!(id), usage))
+        .collect();
+    let by_day: Vec<Value> = by_day
+        .iter()
+        .map(|(day, usage)| with_key("date", Value::String(day.to_string()), usage))
+        .collect();
+    let by_hour: Vec<Value> = by_hour
+        .iter()
+        .map(|(hour, usage)| with_key("hour", Value::from(*hour), usage))
+        .collect();
+
+    let response = serde_json::json!({
+        "total": total.to_json(),
+        "byProject": by_project,
+        "byDay": by_day,
+        "byHour": by_hour,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,389 @@
+use serde_json::Value;
+use std::path::Path;
+use std::time::Duration;
+use tokio::fs;
+
+use crate::commands::oauth_device::{self, Provider};
+use crate::commands::{
+    app_lock, audit_log, http_client, remote_backup, session_mode, task_manager,
+};
+
+// Only files the app created are visible to it with this scope
+pub(crate) static PROVIDER: Provider = Provider {
+    name: "Google Drive",
+    key: "google_drive",
+    device_url: "https://oauth2.googleapis.com/device/code",
+    token_url: "https://oauth2.googleapis.com/token",
+    client_id: option_env!("TA_GOOGLE_CLIENT_ID"),
+    // Google requires the (non-confidential) secret of installed-app clients
+    client_secret: option_env!("TA_GOOGLE_CLIENT_SECRET"),
+    scope: "https://www.googleapis.com/auth/drive.file",
+};
+
+const FILES_URL: &str = "https://www.googleapis.com/drive/v3/files";
+const UPLOAD_URL: &str = "https://www.googleapis.com/upload/drive/v3/files";
+
+// Folder in the user's Drive that uploads go to
+const APP_FOLDER: &str = "TA Teachers Assistant";
+const FOLDER_MIME_TYPE: &str = "application/vnd.google-apps.folder";
+
+// Backups can be large; allow slow connections
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+// Helper to create an HTTP client for Drive requests
+fn client() -> Result<reqwest::Client, String> {
+    http_client::builder()
+        .timeout(TRANSFER_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+// Helper to send a Drive request and fail on an error status
+async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Google Drive is not reachable: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Google Drive returned HTTP {}", response.status().as_u16()));
+    }
+    Ok(response)
+}
+
+// Helper to send a Drive request and read the JSON reply
+async fn send_json(request: reqwest::RequestBuilder) -> Result<Value, String> {
+    send(request)
+        .await?
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Invalid response from Google Drive: {}", e))
+}
+
+// Helper to find the app folder, creating it on first use
+async fn app_folder_id(client: &reqwest::Client, token: &str) -> Result<String, String> {
+    let query = format!(
+        "name = '{}' and mimeType = '{}' and 'root' in parents and trashed = false",
+        APP_FOLDER, FOLDER_MIME_TYPE
+    );
+    let found = send_json(
+        client
+            .get(FILES_URL)
+            .bearer_auth(token)
+            .query(&[("q", query.as_str()), ("fields", "files(id)")]),
+    )
+    .await?;
+    if let Some(id) = found.pointer("/files/0/id").and_then(|v| v.as_str()) {
+        return Ok(id.to_string());
+    }
+
+    let metadata = serde_json::json!({ "name": APP_FOLDER, "mimeType": FOLDER_MIME_TYPE });
+    let created = send_json(client.post(FILES_URL).bearer_auth(token).json(&metadata)).await?;
+    created
+        .get("id")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .ok_or_else(|| "Google Drive didn't return the new folder".to_string())
+}
+
+// Helper to upload a file into the app folder with a resumable upload,
+// which (unlike a multipart one) isn't limited to small files
+async fn upload(
+    client: &reqwest::Client,
+    token: &str,
+    name: &str,
+    mime_type: &str,
+    contents: Vec<u8>,
+) -> Result<Value, String> {
+    let folder_id = app_folder_id(client, token).await?;
+    let metadata = serde_json::json!({
+        "name": name,
+        "mimeType": mime_type,
+        "parents": [folder_id],
+    });
+    let session = send(
+        client
+            .post(UPLOAD_URL)
+            .bearer_auth(token)
+            .query(&[("uploadType", "resumable"), ("fields", "id,name,size,createdTime")])
+            .header("X-Upload-Content-Type", mime_type)
+            .json(&metadata),
+    )
+    .await?;
+    let session_url = session
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|v| v.to_str().ok())
+        .ok_or("Google Drive didn't start the upload")?
+        .to_string();
+    send_json(client.put(session_url).body(contents)).await
+}
+
+// Helper to describe an uploaded or listed file for the frontend
+fn file_summary(file: &Value) -> Value {
+    serde_json::json!({
+        "fileId": file.get("id"),
+        "name": file.get("name"),
+        // Drive reports sizes as strings
+        "size": file
+            .get("size")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<u64>().ok()),
+        "createdTime": file.get("createdTime"),
+    })
+}
+
+// Helper to check a Drive file ID before putting it in a URL
+fn validate_file_id(file_id: &str) -> Result<(), String> {
+    let valid = !file_id.is_empty()
+        && file_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if !valid {
+        return Err(format!("Invalid Google Drive file ID: {}", file_id));
+    }
+    Ok(())
+}
+
+// ============================================
+// Google Drive Commands
+// ============================================
+
+/// Whether this build can use Google Drive and whether the user is signed
+/// in, as `{available, connected}`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_google_drive_status(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let status = serde_json::json!({
+        "available": PROVIDER.client_id.is_some_and(|id| !id.is_empty()),
+        "connected": oauth_device::is_connected(&PROVIDER).await?,
+    });
+    Ok(status.to_string())
+}
+
+/// Start signing in to Google Drive. Returns `{deviceCode, userCode,
+/// verificationUri, expiresIn}`: show the user the code and the page to
+/// enter it on, then call `finish_google_drive_sign_in` with `deviceCode`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn start_google_drive_sign_in(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    Ok(oauth_device::start(&PROVIDER).await?.to_string())
+}
+
+/// Wait for the user to approve the sign-in, then keep the refresh token in
+/// the OS keychain. Runs as a cancellable "sign_in" task (ID `operation_id`
+/// when given) until approved, declined, or the code expires.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn finish_google_drive_sign_in(
+    app_handle: tauri::AppHandle,
+    device_code: String,
+    operation_id: Option<String>,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let task = task_manager::start_task(
+        &app_handle,
+        operation_id,
+        "sign_in",
+        "Google Drive sign-in",
+    )?;
+    let result = oauth_device::wait_for_approval(&PROVIDER, &task, &device_code).await;
+    task.finish(&result);
+    result?;
+
+    audit_log::record(&app_handle, "finish_google_drive_sign_in", "remote_target", &[]).await;
+
+    Ok(())
+}
+
+/// Sign out of Google Drive, forgetting the stored token. Files already
+/// uploaded stay in Drive.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn disconnect_google_drive(app_handle: tauri::AppHandle) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    oauth_device::disconnect(&PROVIDER).await?;
+
+    audit_log::record(&app_handle, "disconnect_google_drive", "remote_target", &[]).await;
+
+    Ok(())
+}
+
+/// Upload a file (e.g. an exported PDF) to the app's folder in Google Drive.
+/// Runs as a cancellable "export" task (ID `operation_id` when given).
+/// Returns `{fileId, name, size, createdTime}`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn upload_to_drive(
+    app_handle: tauri::AppHandle,
+    path: String,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let file_path = Path::new(&path);
+    let name = file_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .filter(|_| file_path.is_absolute() && file_path.is_file())
+        .ok_or_else(|| format!("File not found: {}", path))?;
+    let mime_type = match file_path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("pdf") => "application/pdf",
+        Some(ext) if ext.eq_ignore_ascii_case("zip") => "application/zip",
+        _ => "application/octet-stream",
+    };
+
+    let task = task_manager::start_task(&app_handle, operation_id, "export", "Upload to Drive")?;
+    let result = task
+        .cancellable(async {
+            let contents = fs::read(file_path)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let token = oauth_device::access_token(&PROVIDER).await?;
+            upload(&client()?, &token, &name, mime_type, contents).await
+        })
+        .await;
+    task.finish(&result);
+    let file = result?;
+
+    audit_log::record(&app_handle, "upload_to_drive", "remote_target", &[&name]).await;
+
+    Ok(file_summary(&file).to_string())
+}
+
+/// Back up the app data (without logs) to the app's folder in Google Drive.
+/// Runs as a cancellable "backup" task (ID `operation_id` when given).
+/// Returns `{fileId, name, size, createdTime}`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn backup_to_drive(
+    app_handle: tauri::AppHandle,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let name = remote_backup::backup_file_name();
+    let task = task_manager::start_task(&app_handle, operation_id, "backup", "Backup to Drive")?;
+    let result = task
+        .cancellable(async {
+            let token = oauth_device::access_token(&PROVIDER).await?;
+            let contents = remote_backup::build_backup(&app_handle).await?;
+            upload(&client()?, &token, &name, "application/zip", contents).await
+        })
+        .await;
+    task.finish(&result);
+    let file = result?;
+
+    audit_log::record(&app_handle, "backup_to_drive", "remote_target", &[&name]).await;
+
+    Ok(file_summary(&file).to_string())
+}
+
+/// List the backups in the app's Google Drive folder, newest first, as
+/// `[{fileId, name, size, createdTime}]`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_remote_backups(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let token = oauth_device::access_token(&PROVIDER).await?;
+    let client = client()?;
+    let folder_id = app_folder_id(&client, &token).await?;
+    let query = format!(
+        "'{}' in parents and name contains '{}' and trashed = false",
+        folder_id,
+        remote_backup::BACKUP_PREFIX
+    );
+    let listed = send_json(client.get(FILES_URL).bearer_auth(&token).query(&[
+        ("q", query.as_str()),
+        ("orderBy", "createdTime desc"),
+        ("pageSize", "1000"),
+        ("fields", "files(id,name,size,createdTime)"),
+    ]))
+    .await?;
+
+    let backups: Vec<Value> = listed
+        .get("files")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|f| {
+            f.get("name")
+                .and_then(|v| v.as_str())
+                .is_some_and(remote_backup::is_backup_name)
+        })
+        .map(file_summary)
+        .collect();
+    serde_json::to_string(&backups).map_err(|e| format!("Failed to serialize backups: {}", e))
+}
+
+/// Restore a backup from Google Drive over the app data. The current data
+/// is saved to the local backup folder first; files not in the backup are
+/// kept. Runs as a cancellable "backup" task (ID `operation_id` when given)
+/// while downloading. Returns `{safetyBackupPath, restoredFiles}`; restart
+/// the app afterwards.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn restore_from_drive(
+    app_handle: tauri::AppHandle,
+    file_id: String,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    validate_file_id(&file_id)?;
+
+    let task = task_manager::start_task(&app_handle, operation_id, "backup", "Restore from Drive")?;
+    let download = task
+        .cancellable(async {
+            let token = oauth_device::access_token(&PROVIDER).await?;
+            let client = client()?;
+            let file_url = format!("{}/{}", FILES_URL, file_id);
+            let metadata = send_json(
+                client
+                    .get(&file_url)
+                    .bearer_auth(&token)
+                    .query(&[("fields", "name")]),
+            )
+            .await?;
+            let name = metadata.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            if !remote_backup::is_backup_name(name) {
+                return Err(format!("Not a backup: {}", name));
+            }
+            let response =
+                send(client.get(&file_url).bearer_auth(&token).query(&[("alt", "media")])).await?;
+            response
+                .bytes()
+                .await
+                .map_err(|e| format!("Failed to download backup: {}", e))
+        })
+        .await;
+    // Once downloaded, the restore runs to the end
+    let restored = match download.and_then(|b| task.check_cancelled().map(|_| b)) {
+        Ok(bytes) => remote_backup::restore_backup(&app_handle, &bytes).await,
+        Err(e) => Err(e),
+    };
+    task.finish(&restored);
+    let (safety_backup_path, restored_files) = restored?;
+
+    tracing::warn!(file_id = %file_id, "Restored backup from Google Drive");
+    audit_log::record(&app_handle, "restore_from_drive", "app_data", &[&file_id]).await;
+
+    let result = serde_json::json!({
+        "safetyBackupPath": safety_backup_path,
+        "restoredFiles": restored_files,
+    });
+    Ok(result.to_string())
+}
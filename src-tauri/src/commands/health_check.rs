@@ -0,0 +1,202 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{
+    design_pack_storage, index_cache, learner_storage, library_storage, ollama, project_storage,
+    settings_storage,
+};
+
+// Below this much free space on the app data volume, saves start to fail
+const MIN_FREE_DISK_BYTES: u64 = 500 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Result of a single subsystem check
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HealthCheck {
+    name: &'static str,
+    status: CheckStatus,
+    message: String,
+}
+
+impl HealthCheck {
+    fn new(name: &'static str, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+// Helper to verify the app data directory exists and accepts writes
+async fn check_app_data_writable(app_data_dir: &Path) -> HealthCheck {
+    const NAME: &str = "appDataWritable";
+    let probe_path = app_data_dir.join(".health-check");
+
+    let result = async {
+        fs::create_dir_all(app_data_dir).await?;
+        fs::write(&probe_path, b"ok").await?;
+        fs::remove_file(&probe_path).await
+    }
+    .await;
+
+    match result {
+        Ok(()) => HealthCheck::new(NAME, CheckStatus::Ok, app_data_dir.display().to_string()),
+        Err(e) => HealthCheck::new(
+            NAME,
+            CheckStatus::Error,
+            format!("Cannot write to {}: {}", app_data_dir.display(), e),
+        ),
+    }
+}
+
+// Helper to verify each index file that exists parses as JSON
+async fn check_indices(indices: &[(&str, PathBuf)]) -> HealthCheck {
+    const NAME: &str = "indicesParseable";
+    let mut problems = Vec::new();
+
+    for (label, path) in indices {
+        if !path.exists() {
+            continue;
+        }
+        match fs::read_to_string(path).await {
+            Ok(content) => {
+                if let Err(e) = serde_json::from_str::<serde_json::Value>(&content) {
+                    problems.push(format!("{}: {}", label, e));
+                }
+            }
+            Err(e) => problems.push(format!("{}: {}", label, e)),
+        }
+    }
+
+    if problems.is_empty() {
+        HealthCheck::new(NAME, CheckStatus::Ok, "All indices are readable")
+    } else {
+        HealthCheck::new(NAME, CheckStatus::Error, problems.join("; "))
+    }
+}
+
+// Helper to check free space on the app data volume
+fn check_disk_space(app_data_dir: &Path) -> HealthCheck {
+    const NAME: &str = "freeDiskSpace";
+    // The directory may not exist yet on first run; measure its parent instead
+    let target = app_data_dir
+        .ancestors()
+        .find(|p| p.exists())
+        .unwrap_or(app_data_dir);
+
+    match fs2::available_space(target) {
+        Ok(free) if free >= MIN_FREE_DISK_BYTES => HealthCheck::new(
+            NAME,
+            CheckStatus::Ok,
+            format!("{} MB free", free / (1024 * 1024)),
+        ),
+        Ok(free) => HealthCheck::new(
+            NAME,
+            CheckStatus::Warning,
+            format!(
+                "Only {} MB free (recommended at least {} MB)",
+                free / (1024 * 1024),
+                MIN_FREE_DISK_BYTES / (1024 * 1024)
+            ),
+        ),
+        Err(e) => HealthCheck::new(
+            NAME,
+            CheckStatus::Warning,
+            format!("Failed to read free disk space: {}", e),
+        ),
+    }
+}
+
+// ============================================
+// Health Check Commands
+// ============================================
+
+/// Check each subsystem and return a status report. The overall status is
+/// the worst status of any individual check.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn run_health_check(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let settings = settings_storage::load_settings(&app_handle).await?;
+
+    let mut checks = vec![check_app_data_writable(&app_data_dir).await];
+
+    if let Err(e) = index_cache::flush(&app_handle).await {
+        tracing::warn!(error = %e, "Checking indices as last written");
+    }
+    let indices = [
+        ("library", library_storage::get_index_path(&app_handle)?),
+        ("projects", project_storage::get_index_path(&app_handle)?),
+        ("designPacks", design_pack_storage::get_index_path(&app_handle)?),
+        ("learnerProfiles", learner_storage::get_profiles_path(&app_handle)?),
+    ];
+    checks.push(check_indices(&indices).await);
+
+    // Ollama is optional (cloud generation still works), so problems are warnings
+    let endpoint = &settings.ollama.endpoint;
+    match ollama::get_version(endpoint).await {
+        Ok(version) => {
+            checks.push(HealthCheck::new(
+                "ollamaReachable",
+                CheckStatus::Ok,
+                format!("Ollama {} at {}", version, endpoint),
+            ));
+
+            let required = settings
+                .ollama
+                .default_model
+                .clone()
+                .unwrap_or_else(|| ollama::RECOMMENDED_MODEL.to_string());
+            checks.push(match ollama::list_models(endpoint).await {
+                Ok(models) if ollama::has_model(&models, &required) => HealthCheck::new(
+                    "requiredModels",
+                    CheckStatus::Ok,
+                    format!("{} is installed", required),
+                ),
+                Ok(_) => HealthCheck::new(
+                    "requiredModels",
+                    CheckStatus::Warning,
+                    format!("{} is not installed", required),
+                ),
+                Err(e) => HealthCheck::new("requiredModels", CheckStatus::Warning, e),
+            });
+        }
+        Err(e) => {
+            checks.push(HealthCheck::new("ollamaReachable", CheckStatus::Warning, e));
+            checks.push(HealthCheck::new(
+                "requiredModels",
+                CheckStatus::Warning,
+                "Skipped because Ollama is not reachable",
+            ));
+        }
+    }
+
+    checks.push(check_disk_space(&app_data_dir));
+
+    let overall = checks
+        .iter()
+        .map(|c| c.status)
+        .max()
+        .unwrap_or(CheckStatus::Ok);
+    let report = serde_json::json!({
+        "status": overall,
+        "checkedAt": chrono::Utc::now().to_rfc3339(),
+        "checks": checks,
+    });
+
+    Ok(report.to_string())
+}
@@ -0,0 +1,169 @@
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use crate::commands::{app_lock, audit_log, secrets, session_mode};
+use crate::commands::settings_storage::{self, ProxySettings};
+
+/// Keychain key holding the proxy password
+pub(crate) const PROXY_PASSWORD_KEY: &str = "proxy.password";
+
+// Hosts never sent through a manual proxy, so the local Ollama server stays
+// reachable
+const LOCAL_HOSTS: &str = "localhost,127.0.0.1,::1";
+
+/// Page fetched to check the internet can be reached; it returns an empty
+/// 204
+pub(crate) const CHECK_URL: &str = "https://clients3.google.com/generate_204";
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How outgoing requests reach the network
+#[derive(Clone)]
+pub(crate) enum ProxyConfig {
+    /// reqwest's default: the proxy from the environment or OS
+    System,
+    /// No proxy
+    Direct,
+    Manual(Box<reqwest::Proxy>),
+}
+
+// The proxy every client is built with. It's process-wide rather than
+// managed state since many callers (like the Ollama helpers) have no app
+// handle.
+static PROXY: RwLock<ProxyConfig> = RwLock::new(ProxyConfig::System);
+
+// Helper to create a client builder for `config`
+fn builder_for(config: &ProxyConfig) -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder();
+    match config {
+        ProxyConfig::System => builder,
+        ProxyConfig::Direct => builder.no_proxy(),
+        ProxyConfig::Manual(proxy) => builder.proxy(proxy.as_ref().clone()),
+    }
+}
+
+/// Start building an HTTP client that goes through the configured proxy.
+/// Every outgoing client should be built with this.
+pub(crate) fn builder() -> reqwest::ClientBuilder {
+    builder_for(&PROXY.read().unwrap())
+}
+
+/// Build the proxy configuration for `settings`, reading the password from
+/// the keychain when a username is set. Fails on an unknown mode or an
+/// invalid proxy URL.
+pub(crate) async fn proxy_config(settings: &ProxySettings) -> Result<ProxyConfig, String> {
+    match settings.mode.as_str() {
+        "system" => Ok(ProxyConfig::System),
+        "none" => Ok(ProxyConfig::Direct),
+        "manual" => {
+            let url = settings.url.trim();
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err("Proxy URL must start with http:// or https://".to_string());
+            }
+            let mut proxy =
+                reqwest::Proxy::all(url).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+            let bypass = match settings.bypass.trim() {
+                "" => LOCAL_HOSTS.to_string(),
+                hosts => format!("{},{}", LOCAL_HOSTS, hosts),
+            };
+            proxy = proxy.no_proxy(reqwest::NoProxy::from_string(&bypass));
+            if !settings.username.is_empty() {
+                let password = secrets::read_secret(PROXY_PASSWORD_KEY).await?.unwrap_or_default();
+                proxy = proxy.basic_auth(&settings.username, &password);
+            }
+            Ok(ProxyConfig::Manual(Box::new(proxy)))
+        }
+        mode => Err(format!("Unknown proxy mode: {}", mode)),
+    }
+}
+
+/// Use `config` for every client built from now on
+pub(crate) fn apply(config: ProxyConfig) {
+    *PROXY.write().unwrap() = config;
+}
+
+/// Apply the saved proxy settings at startup. Until they're loaded, clients
+/// use the system proxy.
+pub fn init(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let config = match settings_storage::load_settings(&app_handle).await {
+            Ok(settings) => proxy_config(&settings.proxy).await,
+            Err(e) => Err(e),
+        };
+        match config {
+            Ok(config) => apply(config),
+            Err(e) => tracing::warn!(error = %e, "Failed to apply proxy settings"),
+        }
+    });
+}
+
+// ============================================
+// HTTP Client Commands
+// ============================================
+
+/// Store the proxy password in the OS keychain, or clear it when
+/// `password` is empty or omitted, and apply it to the saved proxy
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn set_proxy_password(
+    app_handle: tauri::AppHandle,
+    password: Option<String>,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    match password.filter(|p| !p.is_empty()) {
+        Some(password) => secrets::write_secret(PROXY_PASSWORD_KEY, &password).await?,
+        None => secrets::remove_secret(PROXY_PASSWORD_KEY).await?,
+    }
+    let settings = settings_storage::load_settings(&app_handle).await?;
+    apply(proxy_config(&settings.proxy).await?);
+
+    audit_log::record(&app_handle, "set_proxy_password", "secret", &[]).await;
+
+    Ok(())
+}
+
+/// Check that the internet can be reached through a proxy. `proxy` is a
+/// proxy settings object to try before saving it; without it the saved
+/// settings are tested. Returns `{ ok, status, elapsedMs, error }`; a
+/// connection failure is reported in `error` rather than failing the
+/// command.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn test_proxy(
+    app_handle: tauri::AppHandle,
+    proxy: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let settings: ProxySettings = match proxy {
+        Some(proxy) => {
+            serde_json::from_str(&proxy).map_err(|e| format!("Invalid proxy JSON: {}", e))?
+        }
+        None => settings_storage::load_settings(&app_handle).await?.proxy,
+    };
+    let config = proxy_config(&settings).await?;
+    let client = builder_for(&config)
+        .timeout(TEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let started = Instant::now();
+    let response = match client.get(CHECK_URL).send().await {
+        Ok(response) => serde_json::json!({
+            "ok": response.status().is_success(),
+            "status": response.status().as_u16(),
+            "elapsedMs": started.elapsed().as_millis() as u64,
+            "error": (!response.status().is_success())
+                .then(|| format!("HTTP {}", response.status().as_u16())),
+        }),
+        Err(e) => serde_json::json!({
+            "ok": false,
+            "status": null,
+            "elapsedMs": started.elapsed().as_millis() as u64,
+            "error": e.to_string(),
+        }),
+    };
+    Ok(response.to_string())
+}
@@ -0,0 +1,308 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, session_mode, settings_storage};
+
+/// Directory of cached generated images. Everything in it can be generated
+/// again, so backups and data history leave it out.
+pub(crate) const CACHE_DIR: &str = "image-cache";
+const INDEX_FILE: &str = "index.json";
+
+/// Managed state serializing updates to the cache index
+#[derive(Default)]
+pub struct ImageCacheState {
+    lock: tokio::sync::Mutex<()>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry {
+    mime_type: String,
+    size: u64,
+    created_at: String,
+    last_used_at: String,
+}
+
+/// `image-cache/index.json`: cached images by key, plus lookup counts
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct CacheIndex {
+    entries: BTreeMap<String, CacheEntry>,
+    hits: u64,
+    misses: u64,
+}
+
+/// Cache key of an image request: a SHA-256 of the provider (e.g.
+/// "local:sdxl-turbo"), the prompt, and any provider parameters (size,
+/// steps, style) that change the result
+pub(crate) fn cache_key(provider: &str, prompt: &str, params: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [provider, prompt.trim(), params] {
+        hasher.update(part.as_bytes());
+        // Separator, so ("ab", "c") and ("a", "bc") differ
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Split a base64 `data:` URI into its MIME type and bytes
+pub(crate) fn decode_data_uri(uri: &str) -> Result<(String, Vec<u8>), String> {
+    let (meta, payload) = uri
+        .strip_prefix("data:")
+        .and_then(|data| data.split_once(','))
+        .ok_or("Expected a data URI")?;
+    let mime_type = meta
+        .strip_suffix(";base64")
+        .ok_or("Expected a base64 data URI")?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(payload.trim())
+        .map_err(|e| format!("Invalid base64 data: {}", e))?;
+    Ok((mime_type.to_string(), bytes))
+}
+
+/// Build a base64 `data:` URI
+pub(crate) fn data_uri(mime_type: &str, bytes: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    format!("data:{};base64,{}", mime_type, encoded)
+}
+
+// Helper to get the cache directory
+fn get_cache_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(CACHE_DIR))
+}
+
+// Helper to get the file holding a cached image. Keys are hex digests, so
+// they are safe as file names.
+fn get_image_path(app_handle: &tauri::AppHandle, key: &str) -> Result<PathBuf, String> {
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid image cache key: {}", key));
+    }
+    Ok(get_cache_dir(app_handle)?.join(format!("{}.bin", key)))
+}
+
+// Helper to read the cache index, or an empty one
+async fn read_index(app_handle: &tauri::AppHandle) -> Result<CacheIndex, String> {
+    let index_path = get_cache_dir(app_handle)?.join(INDEX_FILE);
+    Ok(match fs::read_to_string(&index_path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => CacheIndex::default(),
+    })
+}
+
+// Helper to write the cache index
+async fn write_index(app_handle: &tauri::AppHandle, index: &CacheIndex) -> Result<(), String> {
+    let cache_dir = get_cache_dir(app_handle)?;
+    fs::create_dir_all(&cache_dir)
+        .await
+        .map_err(|e| format!("Failed to create image cache directory: {}", e))?;
+    let content = serde_json::to_string(index)
+        .map_err(|e| format!("Failed to serialize image cache index: {}", e))?;
+    fs::write(cache_dir.join(INDEX_FILE), content)
+        .await
+        .map_err(|e| format!("Failed to write image cache index: {}", e))
+}
+
+/// Look up a cached image by key, returning its MIME type and bytes and
+/// marking it recently used. Counts a hit or a miss.
+pub(crate) async fn get(
+    app_handle: &tauri::AppHandle,
+    key: &str,
+) -> Result<Option<(String, Vec<u8>)>, String> {
+    let image_path = get_image_path(app_handle, key)?;
+    let state = app_handle.state::<ImageCacheState>();
+    let _guard = state.lock.lock().await;
+
+    let mut index = read_index(app_handle).await?;
+    let found = match index.entries.get_mut(key) {
+        Some(entry) => match fs::read(&image_path).await {
+            Ok(bytes) => {
+                entry.last_used_at = chrono::Utc::now().to_rfc3339();
+                Some((entry.mime_type.clone(), bytes))
+            }
+            Err(_) => None,
+        },
+        None => None,
+    };
+    if found.is_some() {
+        index.hits += 1;
+    } else {
+        // Drops an entry whose file has gone missing
+        index.entries.remove(key);
+        index.misses += 1;
+    }
+    write_index(app_handle, &index).await?;
+    Ok(found)
+}
+
+/// Store an image under a key, then evict the least recently used images
+/// until the cache fits the size cap in settings
+pub(crate) async fn put(
+    app_handle: &tauri::AppHandle,
+    key: &str,
+    mime_type: &str,
+    bytes: &[u8],
+) -> Result<(), String> {
+    let image_path = get_image_path(app_handle, key)?;
+    let max_bytes = settings_storage::load_settings(app_handle)
+        .await?
+        .image_cache
+        .max_size_mb
+        .saturating_mul(1024 * 1024);
+    let state = app_handle.state::<ImageCacheState>();
+    let _guard = state.lock.lock().await;
+
+    let mut index = read_index(app_handle).await?;
+    fs::create_dir_all(get_cache_dir(app_handle)?)
+        .await
+        .map_err(|e| format!("Failed to create image cache directory: {}", e))?;
+    fs::write(&image_path, bytes)
+        .await
+        .map_err(|e| format!("Failed to write cached image: {}", e))?;
+    let now = chrono::Utc::now().to_rfc3339();
+    index.entries.insert(
+        key.to_string(),
+        CacheEntry {
+            mime_type: mime_type.to_string(),
+            size: bytes.len() as u64,
+            created_at: now.clone(),
+            last_used_at: now,
+        },
+    );
+
+    let mut total: u64 = index.entries.values().map(|e| e.size).sum();
+    if total > max_bytes {
+        let mut by_use: Vec<(String, String, u64)> = index
+            .entries
+            .iter()
+            .map(|(k, e)| (e.last_used_at.clone(), k.clone(), e.size))
+            .collect();
+        by_use.sort();
+        for (_, evicted, size) in by_use {
+            if total <= max_bytes {
+                break;
+            }
+            // The new image stays even when it alone is over the cap
+            if evicted == key {
+                continue;
+            }
+            if let Err(e) = fs::remove_file(get_image_path(app_handle, &evicted)?).await {
+                tracing::warn!(error = %e, "Failed to evict cached image");
+            }
+            index.entries.remove(&evicted);
+            total -= size;
+        }
+    }
+    write_index(app_handle, &index).await
+}
+
+// ============================================
+// Image Cache Commands
+// ============================================
+
+/// Look up a previously generated image for the same request. Returns
+/// `{key, dataUri}`, or null on a miss.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_cached_image(
+    app_handle: tauri::AppHandle,
+    provider: String,
+    prompt: String,
+    params: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let key = cache_key(&provider, &prompt, params.as_deref().unwrap_or(""));
+    let response = match get(&app_handle, &key).await? {
+        Some((mime_type, bytes)) => serde_json::json!({
+            "key": key,
+            "dataUri": data_uri(&mime_type, &bytes),
+        }),
+        None => serde_json::Value::Null,
+    };
+    Ok(response.to_string())
+}
+
+/// Cache an image generated by any provider (`data_uri` in base64), keyed
+/// by the request that produced it. Returns the cache key.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn put_cached_image(
+    app_handle: tauri::AppHandle,
+    provider: String,
+    prompt: String,
+    params: Option<String>,
+    data_uri: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let (mime_type, bytes) = decode_data_uri(&data_uri)?;
+    if !mime_type.starts_with("image/") {
+        return Err(format!("Not an image: {}", mime_type));
+    }
+    let key = cache_key(&provider, &prompt, params.as_deref().unwrap_or(""));
+    put(&app_handle, &key, &mime_type, &bytes).await?;
+    Ok(key)
+}
+
+/// Get image cache statistics: `{entries, totalBytes, maxBytes, hits,
+/// misses, hitRate}`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_image_cache_stats(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let max_size_mb = settings_storage::load_settings(&app_handle)
+        .await?
+        .image_cache
+        .max_size_mb;
+    let index = read_index(&app_handle).await?;
+    let lookups = index.hits + index.misses;
+    let hit_rate = if lookups == 0 {
+        0.0
+    } else {
+        index.hits as f64 / lookups as f64
+    };
+
+    let stats = serde_json::json!({
+        "entries": index.entries.len(),
+        "totalBytes": index.entries.values().map(|e| e.size).sum::<u64>(),
+        "maxBytes": max_size_mb.saturating_mul(1024 * 1024),
+        "hits": index.hits,
+        "misses": index.misses,
+        "hitRate": hit_rate,
+    });
+    Ok(stats.to_string())
+}
+
+/// Delete every cached image and reset the statistics
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn clear_image_cache(app_handle: tauri::AppHandle) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let state = app_handle.state::<ImageCacheState>();
+    let _guard = state.lock.lock().await;
+    let cache_dir = get_cache_dir(&app_handle)?;
+    if cache_dir.exists() {
+        fs::remove_dir_all(&cache_dir)
+            .await
+            .map_err(|e| format!("Failed to clear image cache: {}", e))?;
+    }
+
+    audit_log::record(&app_handle, "clear_image_cache", "image_cache", &[]).await;
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use base64::Engine;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use std::io::Cursor;
+
+use crate::commands::{app_lock, image_cache, session_mode};
+
+const DEFAULT_MAX_SIDE: u32 = 1600;
+const DEFAULT_MAX_BYTES: usize = 300 * 1024;
+
+// Qualities tried, best first, before shrinking the image further
+const QUALITIES: [u8; 6] = [85, 75, 65, 55, 45, 35];
+
+// Each shrink keeps this share of the width and height
+const SHRINK_FACTOR: f64 = 0.75;
+
+// Below this side length an image is no use in an artifact
+const MIN_SIDE: u32 = 64;
+
+/// A compressed image
+pub(crate) struct CompressedImage {
+    pub mime_type: &'static str,
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+// Helper to encode an image at one quality
+fn encode(image: &DynamicImage, format: &str, quality: u8) -> Result<Vec<u8>, String> {
+    match format {
+        "webp" => {
+            let rgba = image.to_rgba8();
+            let encoded = webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height())
+                .encode(f32::from(quality));
+            Ok(encoded.to_vec())
+        }
+        "jpeg" => {
+            // JPEG has no transparency; transparent areas become white
+            let mut rgb = image::RgbImage::from_pixel(
+                image.width(),
+                image.height(),
+                image::Rgb([255, 255, 255]),
+            );
+            for (x, y, pixel) in image.to_rgba8().enumerate_pixels() {
+                let [r, g, b, a] = pixel.0;
+                let blend = |c: u8| ((c as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+                rgb.put_pixel(x, y, image::Rgb([blend(r), blend(g), blend(b)]));
+            }
+            let mut output = Vec::new();
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut output, quality)
+                .encode_image(&rgb)
+                .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+            Ok(output)
+        }
+        other => Err(format!("Unsupported image format: {}", other)),
+    }
+}
+
+/// Compress an image (PNG, JPEG, GIF, or WebP) to WebP or JPEG (`format`
+/// "webp" or "jpeg"). It is first scaled down to fit `max_width` by
+/// `max_height`, then encoded at falling qualities, and shrunk further
+/// until it is at most `max_bytes`. CPU-bound; call from a blocking task.
+pub(crate) fn compress(
+    bytes: &[u8],
+    format: &str,
+    max_width: u32,
+    max_height: u32,
+    max_bytes: usize,
+) -> Result<CompressedImage, String> {
+    let mime_type = match format {
+        "webp" => "image/webp",
+        "jpeg" => "image/jpeg",
+        other => return Err(format!("Unsupported image format: {}", other)),
+    };
+    let mut image = image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .decode()
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    if image.width() > max_width || image.height() > max_height {
+        image = image.resize(max_width, max_height, FilterType::Lanczos3);
+    }
+
+    loop {
+        for quality in QUALITIES {
+            let encoded = encode(&image, format, quality)?;
+            if encoded.len() <= max_bytes {
+                return Ok(CompressedImage {
+                    mime_type,
+                    bytes: encoded,
+                    width: image.width(),
+                    height: image.height(),
+                });
+            }
+        }
+        let width = (image.width() as f64 * SHRINK_FACTOR) as u32;
+        let height = (image.height() as f64 * SHRINK_FACTOR) as u32;
+        if width.min(height) < MIN_SIDE {
+            return Err(format!(
+                "Image can't be compressed under {} KB",
+                max_bytes / 1024
+            ));
+        }
+        image = image.resize(width, height, FilterType::Lanczos3);
+    }
+}
+
+// ============================================
+// Image Compression Commands
+// ============================================
+
+/// Compress a base64 image (a `data:` URI or bare base64) for use in
+/// artifacts and exports. `format` is "webp" (default) or "jpeg";
+/// `max_width` and `max_height` default to 1600 and `max_bytes` to 300 KB.
+/// Returns `{dataUri, mimeType, width, height, bytes, originalBytes}`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn compress_image(
+    app_handle: tauri::AppHandle,
+    data: String,
+    format: Option<String>,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+    max_bytes: Option<usize>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let original = if data.starts_with("data:") {
+        image_cache::decode_data_uri(&data)?.1
+    } else {
+        base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .map_err(|e| format!("Invalid base64 data: {}", e))?
+    };
+    let format = format.unwrap_or_else(|| "webp".to_string());
+    let max_width = max_width.unwrap_or(DEFAULT_MAX_SIDE).max(MIN_SIDE);
+    let max_height = max_height.unwrap_or(DEFAULT_MAX_SIDE).max(MIN_SIDE);
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_MAX_BYTES);
+
+    let original_bytes = original.len();
+    let compressed = tauri::async_runtime::spawn_blocking(move || {
+        compress(&original, &format, max_width, max_height, max_bytes)
+    })
+    .await
+    .map_err(|e| format!("Failed to compress image: {}", e))??;
+
+    let response = serde_json::json!({
+        "dataUri": image_cache::data_uri(compressed.mime_type, &compressed.bytes),
+        "mimeType": compressed.mime_type,
+        "width": compressed.width,
+        "height": compressed.height,
+        "bytes": compressed.bytes.len(),
+        "originalBytes": original_bytes,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,420 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{Manager, UriSchemeContext};
+use tokio::fs;
+
+use crate::commands::{
+    app_lock, audit_log, image_cache, image_compression, index_cache, session_mode,
+};
+
+/// URI scheme that serves library images to the webview, so artifacts can
+/// reference them as `library-image://localhost/<imageId>` (or
+/// `.../<imageId>/thumb` for the thumbnail)
+pub const IMAGE_SCHEME: &str = "library-image";
+
+const IMAGES_DIR: &str = "images";
+const INDEX_FILE: &str = "index.json";
+/// File in an image's directory holding the image as it was added
+pub(crate) const ORIGINAL_FILE: &str = "original";
+const THUMBNAIL_FILE: &str = "thumb.webp";
+
+// Thumbnails fit in this square and stay under this size
+const THUMBNAIL_SIDE: u32 = 256;
+const THUMBNAIL_MAX_BYTES: usize = 64 * 1024;
+
+/// Largest image accepted into the library
+pub(crate) const MAX_IMAGE_BYTES: usize = 30 * 1024 * 1024;
+
+// Helper to get the image library directory
+fn get_images_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(IMAGES_DIR))
+}
+
+/// Get an image's directory, rejecting IDs that could escape the library
+pub(crate) fn get_image_dir(
+    app_handle: &tauri::AppHandle,
+    image_id: &str,
+) -> Result<PathBuf, String> {
+    if image_id.is_empty() || !image_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid image ID: {}", image_id));
+    }
+    Ok(get_images_dir(app_handle)?.join(image_id))
+}
+
+/// Read the image entries in the library index
+pub(crate) async fn read_entries(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let index_path = get_images_dir(app_handle)?.join(INDEX_FILE);
+    let index = index_cache::read_index(app_handle, &index_path, "image library index").await?;
+    Ok(index
+        .as_deref()
+        .and_then(|index| index.get("images"))
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default())
+}
+
+/// Write the image entries to the library index
+pub(crate) async fn write_entries(
+    app_handle: &tauri::AppHandle,
+    entries: Vec<Value>,
+) -> Result<(), String> {
+    let index = serde_json::json!({
+        "version": 1,
+        "lastUpdated": chrono::Utc::now().to_rfc3339(),
+        "images": entries,
+    });
+    let index_path = get_images_dir(app_handle)?.join(INDEX_FILE);
+    index_cache::write_index(app_handle, &index_path, index, "image library index").await
+}
+
+// Helper to trim, lowercase, and dedupe tags
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Add the URLs an artifact can use (`url`, `thumbnailUrl`) to an entry
+pub(crate) fn with_urls(mut entry: Value) -> Value {
+    let image_id = entry.get("imageId").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    if let Some(obj) = entry.as_object_mut() {
+        let url = format!("{}://localhost/{}", IMAGE_SCHEME, image_id);
+        obj.insert("thumbnailUrl".to_string(), Value::String(format!("{}/thumb", url)));
+        obj.insert("url".to_string(), Value::String(url));
+    }
+    entry
+}
+
+/// Add an image (PNG, JPEG, GIF, or WebP) to the library: the original is
+/// kept as is and a WebP thumbnail is made. Used by `import_images` and by
+/// anything else that captures pictures. Returns the new entry.
+pub(crate) async fn add_image(
+    app_handle: &tauri::AppHandle,
+    bytes: Vec<u8>,
+    file_name: &str,
+    tags: &[String],
+) -> Result<Value, String> {
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(format!("Image is too large: {}", file_name));
+    }
+    let format = image::guess_format(&bytes)
+        .map_err(|_| format!("Unsupported image format: {}", file_name))?;
+    let (width, height) = image::ImageReader::with_format(Cursor::new(&bytes), format)
+        .into_dimensions()
+        .map_err(|e| format!("Failed to read image {}: {}", file_name, e))?;
+
+    // Decoding and resizing are CPU-bound, keep them off the async runtime
+    let original = bytes.clone();
+    let thumbnail = tauri::async_runtime::spawn_blocking(move || {
+        image_compression::compress(
+            &original,
+            "webp",
+            THUMBNAIL_SIDE,
+            THUMBNAIL_SIDE,
+            THUMBNAIL_MAX_BYTES,
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to make thumbnail: {}", e))??;
+
+    let image_id = uuid::Uuid::new_v4().to_string();
+    let image_dir = get_image_dir(app_handle, &image_id)?;
+    fs::create_dir_all(&image_dir)
+        .await
+        .map_err(|e| format!("Failed to create image directory: {}", e))?;
+    fs::write(image_dir.join(ORIGINAL_FILE), &bytes)
+        .await
+        .map_err(|e| format!("Failed to write image: {}", e))?;
+    fs::write(image_dir.join(THUMBNAIL_FILE), &thumbnail.bytes)
+        .await
+        .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+
+    let title = Path::new(file_name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let now = chrono::Utc::now().to_rfc3339();
+    let entry = serde_json::json!({
+        "imageId": image_id,
+        "title": title,
+        "fileName": file_name,
+        "tags": normalize_tags(tags),
+        "mimeType": format.to_mime_type(),
+        "width": width,
+        "height": height,
+        "size": bytes.len(),
+        "createdAt": now,
+        "updatedAt": now,
+    });
+    let mut entries = read_entries(app_handle).await?;
+    entries.push(entry.clone());
+    write_entries(app_handle, entries).await?;
+    Ok(entry)
+}
+
+// Helper to resolve a `library-image://` request path to a file and its
+// MIME type
+fn resolve_request(
+    app_handle: &tauri::AppHandle,
+    path: &str,
+) -> Result<(PathBuf, &'static str), String> {
+    let path = path.trim_start_matches('/');
+    let (image_id, thumbnail) = match path.strip_suffix("/thumb") {
+        Some(image_id) => (image_id, true),
+        None => (path, false),
+    };
+    let image_dir = get_image_dir(app_handle, image_id)?;
+    if thumbnail {
+        return Ok((image_dir.join(THUMBNAIL_FILE), "image/webp"));
+    }
+    let file_path = image_dir.join(ORIGINAL_FILE);
+    // Only the first bytes are needed to tell the format
+    let mut head = [0u8; 32];
+    let read = std::fs::File::open(&file_path)
+        .and_then(|mut file| std::io::Read::read(&mut file, &mut head))
+        .map_err(|_| format!("Image not found: {}", image_id))?;
+    let mime_type = image::guess_format(&head[..read])
+        .map(|format| format.to_mime_type())
+        .unwrap_or("application/octet-stream");
+    Ok((file_path, mime_type))
+}
+
+/// The file behind a `library-image://` (or `http://library-image.localhost`)
+/// URL, for rendering outside the webview
+pub(crate) fn resolve_url(app_handle: &tauri::AppHandle, url: &str) -> Option<PathBuf> {
+    let path = url
+        .strip_prefix("library-image://localhost")
+        .or_else(|| url.strip_prefix("http://library-image.localhost"))?;
+    resolve_request(app_handle, path).ok().map(|(path, _)| path)
+}
+
+/// Serve `library-image://localhost/<imageId>[/thumb]` requests from the
+/// image library. Registered as a URI scheme protocol at startup.
+pub fn handle_image_request(
+    ctx: UriSchemeContext<'_, tauri::Wry>,
+    request: Request<Vec<u8>>,
+) -> Response<Vec<u8>> {
+    let resolved = resolve_request(ctx.app_handle(), request.uri().path());
+    let response = resolved.and_then(|(path, mime_type)| {
+        let contents = std::fs::read(&path).map_err(|_| "Image not found".to_string())?;
+        Response::builder()
+            .header(header::CONTENT_TYPE, mime_type)
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .body(contents)
+            .map_err(|e| format!("Failed to build response: {}", e))
+    });
+
+    response.unwrap_or_else(|e| {
+        tracing::debug!(error = %e, "Library image request failed");
+        Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Vec::new())
+            .unwrap_or_default()
+    })
+}
+
+// ============================================
+// Image Library Commands
+// ============================================
+
+/// Import photos or scans (PNG, JPEG, GIF, or WebP) into the image library,
+/// each tagged with `tags`. Returns the new entries, each with `url` and
+/// `thumbnailUrl` for use in artifacts.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn import_images(
+    app_handle: tauri::AppHandle,
+    paths: Vec<String>,
+    tags: Option<Vec<String>>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let tags = tags.unwrap_or_default();
+    let mut imported = Vec::new();
+    for path in &paths {
+        let bytes = fs::read(path)
+            .await
+            .map_err(|e| format!("Failed to read image {}: {}", path, e))?;
+        let file_name = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        imported.push(with_urls(add_image(&app_handle, bytes, &file_name, &tags).await?));
+    }
+
+    let image_ids: Vec<&str> = imported
+        .iter()
+        .filter_map(|e| e.get("imageId").and_then(|v| v.as_str()))
+        .collect();
+    audit_log::record(&app_handle, "import_images", "image", &image_ids).await;
+
+    serde_json::to_string(&imported).map_err(|e| format!("Failed to serialize images: {}", e))
+}
+
+/// List library images, newest first. `query` matches words in the title
+/// or file name, or a tag; `tag` keeps only images with that tag.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_images(
+    app_handle: tauri::AppHandle,
+    query: Option<String>,
+    tag: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let words: Vec<String> = query
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+    let tag = tag.map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty());
+
+    let mut images: Vec<Value> = read_entries(&app_handle)
+        .await?
+        .into_iter()
+        .filter(|entry| {
+            let tags: Vec<&str> = entry
+                .get("tags")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|t| t.as_str())
+                .collect();
+            if tag.as_deref().is_some_and(|t| !tags.contains(&t)) {
+                return false;
+            }
+            let haystack = ["title", "fileName"]
+                .iter()
+                .filter_map(|key| entry.get(*key).and_then(|v| v.as_str()))
+                .chain(tags.iter().copied())
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase();
+            words.iter().all(|word| haystack.contains(word.as_str()))
+        })
+        .map(with_urls)
+        .collect();
+    images.reverse();
+
+    serde_json::to_string(&images).map_err(|e| format!("Failed to serialize images: {}", e))
+}
+
+/// List the tags used in the image library with how many images have each,
+/// as `{tag: count}`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_image_tags(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for entry in read_entries(&app_handle).await? {
+        for tag in entry.get("tags").and_then(|v| v.as_array()).into_iter().flatten() {
+            if let Some(tag) = tag.as_str() {
+                *counts.entry(tag.to_string()).or_default() += 1;
+            }
+        }
+    }
+    serde_json::to_string(&counts).map_err(|e| format!("Failed to serialize tags: {}", e))
+}
+
+/// Get a library image as a data URI, for exports that must stand alone.
+/// `thumbnail` gives the small WebP version instead of the original.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_image_data(
+    app_handle: tauri::AppHandle,
+    image_id: String,
+    thumbnail: Option<bool>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let image_dir = get_image_dir(&app_handle, &image_id)?;
+    let (path, mime_type) = if thumbnail.unwrap_or(false) {
+        (image_dir.join(THUMBNAIL_FILE), "image/webp".to_string())
+    } else {
+        let entry = read_entries(&app_handle)
+            .await?
+            .into_iter()
+            .find(|e| e.get("imageId").and_then(|v| v.as_str()) == Some(&image_id))
+            .ok_or_else(|| format!("Image not found: {}", image_id))?;
+        let mime_type = entry.get("mimeType").and_then(|v| v.as_str()).unwrap_or("");
+        (image_dir.join(ORIGINAL_FILE), mime_type.to_string())
+    };
+    let bytes = fs::read(&path)
+        .await
+        .map_err(|_| format!("Image not found: {}", image_id))?;
+    Ok(image_cache::data_uri(&mime_type, &bytes))
+}
+
+/// Change a library image's title and/or tags. Returns the updated entry.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn update_image(
+    app_handle: tauri::AppHandle,
+    image_id: String,
+    title: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut entries = read_entries(&app_handle).await?;
+    let entry = entries
+        .iter_mut()
+        .find(|e| e.get("imageId").and_then(|v| v.as_str()) == Some(&image_id))
+        .and_then(|e| e.as_object_mut())
+        .ok_or_else(|| format!("Image not found: {}", image_id))?;
+    if let Some(title) = title {
+        entry.insert("title".to_string(), Value::String(title.trim().to_string()));
+    }
+    if let Some(tags) = tags {
+        entry.insert("tags".to_string(), serde_json::json!(normalize_tags(&tags)));
+    }
+    entry.insert("updatedAt".to_string(), Value::String(chrono::Utc::now().to_rfc3339()));
+    let updated = with_urls(Value::Object(entry.clone()));
+    write_entries(&app_handle, entries).await?;
+
+    audit_log::record(&app_handle, "update_image", "image", &[&image_id]).await;
+
+    Ok(updated.to_string())
+}
+
+/// Delete a library image. Artifacts still referencing it show a broken
+/// image.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_image(app_handle: tauri::AppHandle, image_id: String) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let image_dir = get_image_dir(&app_handle, &image_id)?;
+    let mut entries = read_entries(&app_handle).await?;
+    let before = entries.len();
+    entries.retain(|e| e.get("imageId").and_then(|v| v.as_str()) != Some(&image_id));
+    if entries.len() == before {
+        return Err(format!("Image not found: {}", image_id));
+    }
+    write_entries(&app_handle, entries).await?;
+    if image_dir.exists() {
+        fs::remove_dir_all(&image_dir)
+            .await
+            .map_err(|e| format!("Failed to delete image: {}", e))?;
+    }
+
+    audit_log::record(&app_handle, "delete_image", "image", &[&image_id]).await;
+
+    Ok(())
+}
@@ -0,0 +1,275 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::Manager;
+use tokio::fs;
+
+// How long a changed index waits for more changes before it is written
+const FLUSH_DELAY: Duration = Duration::from_millis(500);
+
+// Longest a changed index stays unwritten while changes keep coming
+const MAX_FLUSH_DELAY: Duration = Duration::from_secs(5);
+
+/// A change not yet written to disk
+#[derive(Clone, Copy)]
+struct Pending {
+    version: u64,
+    what: &'static str,
+}
+
+struct CachedIndex {
+    modified: Option<SystemTime>,
+    len: u64,
+    value: Arc<Value>,
+    pending: Option<Pending>,
+}
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<PathBuf, CachedIndex>,
+    // Bumped on every write so a scheduled flush can tell if more came in
+    version: u64,
+    dirty_since: Option<Instant>,
+}
+
+/// Managed cache of parsed index files (library, projects, design packs),
+/// keyed by path. A clean entry is reused only while the file's modification
+/// time and size are unchanged, so edits made outside the app are picked up
+/// too. Writes are held here and written to disk once they stop coming.
+#[derive(Default)]
+pub struct IndexCacheState {
+    cache: Mutex<Cache>,
+    // Serializes flushes so an older value is never written over a newer one
+    flush_lock: tokio::sync::Mutex<()>,
+}
+
+// Helper to store a parsed index against the file's current metadata,
+// unless a change made meanwhile is waiting to be written
+fn store(
+    app_handle: &tauri::AppHandle,
+    path: &Path,
+    metadata: &std::fs::Metadata,
+    value: Arc<Value>,
+) {
+    let state = app_handle.state::<IndexCacheState>();
+    let mut cache = state.cache.lock().unwrap();
+    if cache.entries.get(path).is_some_and(|c| c.pending.is_some()) {
+        return;
+    }
+    cache.entries.insert(
+        path.to_path_buf(),
+        CachedIndex {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+            value,
+            pending: None,
+        },
+    );
+}
+
+/// The cached parse of an index file if it is current (or holds changes not
+/// yet written), without reading the file
+pub(crate) async fn read_cached(
+    app_handle: &tauri::AppHandle,
+    path: &Path,
+) -> Option<Arc<Value>> {
+    let state = app_handle.state::<IndexCacheState>();
+    if let Some(cached) = state.cache.lock().unwrap().entries.get(path) {
+        if cached.pending.is_some() {
+            return Some(cached.value.clone());
+        }
+    }
+
+    let metadata = fs::metadata(path).await.ok()?;
+    let modified = metadata.modified().ok();
+    let cache = state.cache.lock().unwrap();
+    cache
+        .entries
+        .get(path)
+        .filter(|c| modified.is_some() && c.modified == modified && c.len == metadata.len())
+        .map(|c| c.value.clone())
+}
+
+/// Read and parse an index file, reusing the cached parse while the file is
+/// unchanged and returning changes not yet written. Returns `None` if the
+/// file doesn't exist and `Value::Null` if it isn't valid JSON. `what` names
+/// the index in error messages.
+pub(crate) async fn read_index(
+    app_handle: &tauri::AppHandle,
+    path: &Path,
+    what: &str,
+) -> Result<Option<Arc<Value>>, String> {
+    if let Some(cached) = read_cached(app_handle, path).await {
+        return Ok(Some(cached));
+    }
+
+    let metadata = match fs::metadata(path).await {
+        Ok(metadata) => metadata,
+        Err(_) => {
+            invalidate(app_handle, path);
+            return Ok(None);
+        }
+    };
+
+    let content = fs::read_to_string(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", what, e))?;
+    let value = Arc::new(serde_json::from_str(&content).unwrap_or(Value::Null));
+    store(app_handle, path, &metadata, value.clone());
+    Ok(Some(value))
+}
+
+/// Replace an index. Reads see the new value right away; the file is
+/// written once writes have paused for `FLUSH_DELAY` (or after
+/// `MAX_FLUSH_DELAY` of steady writes), by `flush_indices`, or on exit, so
+/// a burst of saves rewrites it once. `what` names the index in error
+/// messages.
+pub(crate) async fn write_index(
+    app_handle: &tauri::AppHandle,
+    path: &Path,
+    value: Value,
+    what: &'static str,
+) -> Result<(), String> {
+    let version = {
+        let state = app_handle.state::<IndexCacheState>();
+        let mut cache = state.cache.lock().unwrap();
+        cache.version += 1;
+        let version = cache.version;
+        cache.dirty_since.get_or_insert_with(Instant::now);
+        let (modified, len) = cache
+            .entries
+            .get(path)
+            .map_or((None, 0), |c| (c.modified, c.len));
+        cache.entries.insert(
+            path.to_path_buf(),
+            CachedIndex {
+                modified,
+                len,
+                value: Arc::new(value),
+                pending: Some(Pending { version, what }),
+            },
+        );
+        version
+    };
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FLUSH_DELAY).await;
+        let due = {
+            let state = app_handle.state::<IndexCacheState>();
+            let cache = state.cache.lock().unwrap();
+            cache.version == version
+                || cache
+                    .dirty_since
+                    .is_some_and(|since| since.elapsed() >= MAX_FLUSH_DELAY)
+        };
+        if due {
+            if let Err(e) = flush(&app_handle).await {
+                tracing::error!(error = %e, "Failed to write indices");
+            }
+        }
+    });
+    Ok(())
+}
+
+// Helper to write one index file and return its new metadata
+async fn write_file(path: &Path, value: &Value, what: &str) -> Result<std::fs::Metadata, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create {} directory: {}", what, e))?;
+    }
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", what, e))?;
+    fs::write(path, content)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", what, e))?;
+    fs::metadata(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", what, e))
+}
+
+/// Write every index change still held in memory. A failed write stays
+/// pending and is retried on the next flush; the first error is returned.
+pub(crate) async fn flush(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let state = app_handle.state::<IndexCacheState>();
+    let _guard = state.flush_lock.lock().await;
+
+    let pending: Vec<(PathBuf, Arc<Value>, Pending)> = {
+        let mut cache = state.cache.lock().unwrap();
+        cache.dirty_since = None;
+        cache
+            .entries
+            .iter()
+            .filter_map(|(path, c)| Some((path.clone(), c.value.clone(), c.pending?)))
+            .collect()
+    };
+
+    let mut first_error = None;
+    for (path, value, written) in pending {
+        let result = write_file(&path, &value, written.what).await;
+        let mut cache = state.cache.lock().unwrap();
+        match result {
+            Ok(metadata) => {
+                // Leave it pending if it changed again while being written
+                if let Some(cached) = cache
+                    .entries
+                    .get_mut(&path)
+                    .filter(|c| c.pending.is_some_and(|p| p.version == written.version))
+                {
+                    cached.pending = None;
+                    cached.modified = metadata.modified().ok();
+                    cached.len = metadata.len();
+                }
+            }
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Failed to write index");
+                cache.dirty_since.get_or_insert_with(Instant::now);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
+/// Write pending index changes before the app exits. Called from the exit
+/// handler.
+pub fn flush_on_exit(app_handle: &tauri::AppHandle) {
+    if let Err(e) = tauri::async_runtime::block_on(flush(app_handle)) {
+        tracing::error!(error = %e, "Failed to write indices on exit");
+    }
+}
+
+/// Forget every cached index, for a factory reset that has wiped the files.
+/// Call `flush` first so no pending change is lost.
+pub(crate) fn clear(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<IndexCacheState>();
+    let mut cache = state.cache.lock().unwrap();
+    cache.entries.clear();
+    cache.dirty_since = None;
+}
+
+/// Forget the cached parse of an index file written without `write_index`.
+/// Call `flush` before such a write so a pending change doesn't replace it.
+pub(crate) fn invalidate(app_handle: &tauri::AppHandle, path: &Path) {
+    let state = app_handle.state::<IndexCacheState>();
+    let mut cache = state.cache.lock().unwrap();
+    if cache.entries.get(path).is_some_and(|c| c.pending.is_none()) {
+        cache.entries.remove(path);
+    }
+}
+
+// ============================================
+// Index Cache Commands
+// ============================================
+
+/// Write index changes still held in memory to disk now, e.g. before the
+/// frontend copies the data folder. Index writes are otherwise delayed
+/// briefly so bursts of saves are coalesced.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn flush_indices(app_handle: tauri::AppHandle) -> Result<(), String> {
+    flush(&app_handle).await
+}
@@ -0,0 +1,477 @@
+use axum::extract::{Form, Query, State};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+use crate::commands::{
+    app_lock, audit_log, image_cache, local_http, quick_check_session, session_mode,
+    worksheet_assembly,
+};
+
+/// Event sent with `{ sessionId, result }` when the student submits
+pub(crate) const SUBMITTED_EVENT: &str = "lan-quick-check://submitted";
+
+// Digits in the join code a student types on their device
+const JOIN_CODE_DIGITS: usize = 6;
+
+// Wrong join codes accepted before the server shuts down, so the code
+// can't be guessed
+const MAX_WRONG_CODES: usize = 20;
+
+// Time allowed for one request, so a stalled one can't hold the server up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+const PAGE_STYLE: &str = "\
+body { font-family: system-ui, sans-serif; max-width: 40em; margin: 0 auto; padding: 1em; \
+font-size: 1.15em; }
+fieldset { border: 1px solid #ccc; border-radius: 8px; margin: 0 0 1em; padding: 0.75em; }
+legend { font-weight: bold; padding: 0 0.25em; }
+label { display: block; padding: 0.5em 0; }
+input[type=radio] { transform: scale(1.4); margin-right: 0.75em; }
+input[type=text] { font-size: 1.5em; width: 6em; letter-spacing: 0.2em; }
+button { font-size: 1.1em; padding: 0.6em 1.5em; }
+.error { color: #B91C1C; }
+";
+
+/// The quick check being served
+struct LanServer {
+    session_id: String,
+    join_code: String,
+    url: String,
+    joined: bool,
+    wrong_codes: usize,
+    result: Option<Value>,
+    cancel: CancellationToken,
+}
+
+/// Managed state holding the quick check served over the local network, if
+/// any. Only one is served at a time.
+#[derive(Default)]
+pub struct LanQuickCheckState {
+    server: Mutex<Option<LanServer>>,
+}
+
+/// What the server shows and the session it opens when the student joins.
+/// `questions`, without answers, are filled in once the session is open.
+struct Quiz {
+    title: String,
+    session_id: String,
+    learner_id: String,
+    objective_id: String,
+    artifact_id: String,
+    time_limit_seconds: Option<u64>,
+    questions: tokio::sync::Mutex<Option<Vec<Value>>>,
+}
+
+// Helper to find the address of the interface with the default route.
+// Connecting a UDP socket picks it without sending anything.
+fn routed_address() -> Option<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    let address = socket.local_addr().ok()?.ip();
+    (!address.is_loopback() && !address.is_unspecified()).then_some(address)
+}
+
+// Helper to find this computer's address on the local network. A classroom
+// network may have no default route, so the interfaces are searched when
+// there isn't one, preferring private addresses.
+fn local_address() -> Result<std::net::IpAddr, String> {
+    if let Some(address) = routed_address() {
+        return Ok(address);
+    }
+    let interfaces = if_addrs::get_if_addrs()
+        .map_err(|e| format!("Failed to find network address: {}", e))?;
+    interfaces
+        .iter()
+        .filter_map(|interface| match interface.ip() {
+            std::net::IpAddr::V4(ip) if !ip.is_loopback() && !ip.is_unspecified() => Some(ip),
+            _ => None,
+        })
+        .min_by_key(|ip| (!ip.is_private(), ip.is_link_local()))
+        .map(std::net::IpAddr::V4)
+        .ok_or_else(|| "This computer isn't connected to a network".to_string())
+}
+
+// Helper to wrap page content in a minimal mobile-friendly document
+fn render_page(title: &str, content: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>\n{style}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n{content}</body>\n</html>\n",
+        title = worksheet_assembly::escape_html(title),
+        style = PAGE_STYLE,
+    )
+}
+
+// Helper to render the page asking for the join code
+fn render_join(quiz: &Quiz, error: Option<&str>) -> String {
+    let error = error
+        .map(|e| format!("<p class=\"error\">{}</p>\n", worksheet_assembly::escape_html(e)))
+        .unwrap_or_default();
+    let content = format!(
+        "{}<form method=\"get\" action=\"/\">\n\
+         <label>Join code <input type=\"text\" name=\"code\" inputmode=\"numeric\" \
+         autocomplete=\"off\" autofocus></label>\n\
+         <button type=\"submit\">Join</button>\n</form>\n",
+        error
+    );
+    render_page(&quiz.title, &content)
+}
+
+// Helper to render the questions as a form that posts the answers back
+fn render_quiz(quiz: &Quiz, questions: &[Value], join_code: &str) -> String {
+    let escape = worksheet_assembly::escape_html;
+    let mut content = String::new();
+    if let Some(seconds) = quiz.time_limit_seconds {
+        content.push_str(&format!(
+            "<p>You have {} minute{} once you start.</p>\n",
+            seconds.div_ceil(60),
+            if seconds.div_ceil(60) == 1 { "" } else { "s" }
+        ));
+    }
+    content.push_str("<form method=\"post\" action=\"/submit\">\n");
+    content.push_str(&format!(
+        "<input type=\"hidden\" name=\"code\" value=\"{}\">\n",
+        escape(join_code)
+    ));
+    for (index, question) in questions.iter().enumerate() {
+        let text = question.get("questionText").and_then(|v| v.as_str()).unwrap_or("");
+        content.push_str(&format!(
+            "<fieldset>\n<legend>{}. {}</legend>\n",
+            index + 1,
+            escape(text)
+        ));
+        let options = question.get("options").and_then(|v| v.as_array());
+        for (option_index, option) in options.into_iter().flatten().enumerate() {
+            content.push_str(&format!(
+                "<label><input type=\"radio\" name=\"q{}\" value=\"{}\">{}</label>\n",
+                index,
+                option_index,
+                escape(option.as_str().unwrap_or(""))
+            ));
+        }
+        content.push_str("</fieldset>\n");
+    }
+    content.push_str("<button type=\"submit\">Turn in</button>\n</form>\n");
+    render_page(&quiz.title, &content)
+}
+
+/// What the server's handlers share
+#[derive(Clone)]
+struct LanContext {
+    app_handle: tauri::AppHandle,
+    quiz: Arc<Quiz>,
+}
+
+// Helper to check a join code against the server's, counting wrong codes
+// and shutting the server down after too many
+fn check_code(app_handle: &tauri::AppHandle, code: Option<&String>) -> Result<String, String> {
+    let state = app_handle.state::<LanQuickCheckState>();
+    let mut server = state.server.lock().unwrap();
+    let server = server
+        .as_mut()
+        .filter(|server| !server.cancel.is_cancelled())
+        .ok_or("This quick check has ended")?;
+    match code {
+        Some(code) if code.trim() == server.join_code => Ok(server.join_code.clone()),
+        None => Err(String::new()),
+        Some(_) => {
+            server.wrong_codes += 1;
+            if server.wrong_codes >= MAX_WRONG_CODES {
+                tracing::warn!("Too many wrong join codes; stopping LAN quick check");
+                server.cancel.cancel();
+            }
+            Err("That join code isn't right. Check it and try again.".to_string())
+        }
+    }
+}
+
+// Helper to open the quick check session the first time the student joins,
+// so its clock starts when they see the questions. Returns the questions.
+async fn open(context: &LanContext) -> Result<Vec<Value>, String> {
+    let quiz = &context.quiz;
+    let mut questions = quiz.questions.lock().await;
+    if let Some(questions) = questions.as_ref() {
+        return Ok(questions.clone());
+    }
+    let session = quick_check_session::open_session(
+        &context.app_handle,
+        quiz.session_id.clone(),
+        quiz.learner_id.clone(),
+        quiz.objective_id.clone(),
+        &quiz.artifact_id,
+        quiz.time_limit_seconds,
+    )
+    .await?;
+    let opened = session
+        .get("questions")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    *questions = Some(opened.clone());
+
+    let state = context.app_handle.state::<LanQuickCheckState>();
+    if let Some(server) = state.server.lock().unwrap().as_mut() {
+        server.joined = true;
+    }
+    Ok(opened)
+}
+
+// Helper to answer `GET /`: the join page, or the questions once the join
+// code is right
+async fn join(
+    State(context): State<LanContext>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Html<String> {
+    let opened = match check_code(&context.app_handle, params.get("code")) {
+        Ok(join_code) => open(&context).await.map(|questions| (questions, join_code)),
+        Err(e) => Err(e),
+    };
+    match opened {
+        Ok((questions, join_code)) => Html(render_quiz(&context.quiz, &questions, &join_code)),
+        Err(e) => {
+            let error = Some(e.as_str()).filter(|e| !e.is_empty());
+            Html(render_join(&context.quiz, error))
+        }
+    }
+}
+
+// Helper to answer `POST /submit`: the student turning in their answers
+async fn turn_in(
+    State(context): State<LanContext>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Response {
+    let quiz = &context.quiz;
+    if let Err(e) = check_code(&context.app_handle, form.get("code")) {
+        return (StatusCode::FORBIDDEN, Html(render_join(quiz, Some(&e)))).into_response();
+    }
+    let Some(questions) = quiz.questions.lock().await.clone() else {
+        let e = "Join the quick check before turning in answers";
+        return (StatusCode::FORBIDDEN, Html(render_join(quiz, Some(e)))).into_response();
+    };
+    let answers: HashMap<String, usize> = questions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, question)| {
+            let question_id = question.get("questionId")?.as_str()?;
+            let selected = form.get(&format!("q{}", index))?.parse().ok()?;
+            Some((question_id.to_string(), selected))
+        })
+        .collect();
+    let page = submit(&context.app_handle, &answers).await;
+    Html(render_page(&quiz.title, &page)).into_response()
+}
+
+// Helper to grade and save a student's answers, ending the session whether
+// or not they're accepted. Returns the page content to show the student.
+async fn submit(app_handle: &tauri::AppHandle, answers: &HashMap<String, usize>) -> String {
+    let session_id = {
+        let state = app_handle.state::<LanQuickCheckState>();
+        let server = state.server.lock().unwrap();
+        match server.as_ref() {
+            Some(server) => server.session_id.clone(),
+            None => return "<p>This quick check has ended.</p>\n".to_string(),
+        }
+    };
+
+    let submitted = quick_check_session::submit_session(app_handle, &session_id, answers).await;
+    let state = app_handle.state::<LanQuickCheckState>();
+    if let Some(server) = state.server.lock().unwrap().as_mut() {
+        server.cancel.cancel();
+        if let Ok(response) = &submitted {
+            server.result = response.get("result").cloned();
+        }
+    }
+
+    match submitted {
+        Ok(response) => {
+            let result = response.get("result").cloned().unwrap_or_default();
+            let event = serde_json::json!({ "sessionId": session_id, "result": result });
+            if let Err(e) = app_handle.emit(SUBMITTED_EVENT, &event) {
+                tracing::warn!(error = %e, "Failed to emit LAN quick check result");
+            }
+            let count = |key: &str| result.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+            format!(
+                "<p>Your answers are in. You got {} out of {}.</p>\n",
+                count("correctAnswers"),
+                count("totalQuestions")
+            )
+        }
+        Err(e) => format!(
+            "<p class=\"error\">{}</p>\n",
+            worksheet_assembly::escape_html(&e)
+        ),
+    }
+}
+
+// Helper to serve requests until the session ends or is stopped
+async fn serve(
+    app_handle: tauri::AppHandle,
+    listener: TcpListener,
+    quiz: Arc<Quiz>,
+    cancel: CancellationToken,
+) {
+    let router = Router::new()
+        .route("/", get(join))
+        .route("/submit", post(turn_in))
+        .fallback(|| async { (StatusCode::NOT_FOUND, Html("Not found")) })
+        .with_state(LanContext { app_handle, quiz });
+    if let Err(e) = local_http::serve(listener, router, REQUEST_TIMEOUT, cancel).await {
+        tracing::warn!(error = %e, "LAN quick check server failed");
+    }
+    tracing::info!("LAN quick check server stopped");
+}
+
+// ============================================
+// LAN Quick Check Commands
+// ============================================
+
+/// Serve a quick check to a student's tablet or Chromebook over the local
+/// network, on an assembled worksheet as for `start_quick_check_session`. The student
+/// opens the URL (or scans the QR code, which includes the join code),
+/// answers in their browser, and the graded result is saved to their quick
+/// check history and sent as a `lan-quick-check://submitted` event. The
+/// session, and any time limit, starts when the student joins. The server
+/// shuts down once the answers are in, or after too many wrong join
+/// codes. Returns `{ sessionId, joinCode, url, joinUrl, qrCode }`, with
+/// `qrCode` an SVG data URI.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn start_lan_quick_check(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    objective_id: String,
+    artifact_id: String,
+    title: Option<String>,
+    time_limit_seconds: Option<u64>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let active = {
+        let state = app_handle.state::<LanQuickCheckState>();
+        let server = state.server.lock().unwrap();
+        server.as_ref().is_some_and(|s| !s.cancel.is_cancelled())
+    };
+    if active {
+        return Err("A quick check is already being served; stop it first".to_string());
+    }
+
+    let address = local_address()?;
+    let listener = TcpListener::bind((std::net::Ipv4Addr::UNSPECIFIED, 0))
+        .await
+        .map_err(|e| format!("Failed to start LAN server: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to start LAN server: {}", e))?
+        .port();
+
+    quick_check_session::check_questions(&app_handle, &artifact_id).await?;
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let quiz = Arc::new(Quiz {
+        title: title
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| "Quick Check".to_string()),
+        session_id: session_id.clone(),
+        learner_id: learner_id.clone(),
+        objective_id,
+        artifact_id,
+        time_limit_seconds,
+        questions: tokio::sync::Mutex::new(None),
+    });
+
+    let random = uuid::Uuid::new_v4().as_u64_pair().0;
+    let join_code = format!(
+        "{:0width$}",
+        random % 10u64.pow(JOIN_CODE_DIGITS as u32),
+        width = JOIN_CODE_DIGITS
+    );
+    let url = format!("http://{}:{}/", address, port);
+    let join_url = format!("{}?code={}", url, join_code);
+    let qr_code = qrcode::QrCode::new(join_url.as_bytes())
+        .map_err(|e| format!("Failed to make QR code: {}", e))?
+        .render::<qrcode::render::svg::Color>()
+        .min_dimensions(240, 240)
+        .build();
+
+    let cancel = CancellationToken::new();
+    {
+        let state = app_handle.state::<LanQuickCheckState>();
+        *state.server.lock().unwrap() = Some(LanServer {
+            session_id: session_id.clone(),
+            join_code: join_code.clone(),
+            url: url.clone(),
+            joined: false,
+            wrong_codes: 0,
+            result: None,
+            cancel: cancel.clone(),
+        });
+    }
+    tauri::async_runtime::spawn(serve(app_handle.clone(), listener, quiz, cancel));
+
+    audit_log::record(
+        &app_handle,
+        "start_lan_quick_check",
+        "quick_check",
+        &[&learner_id, &session_id],
+    )
+    .await;
+
+    let response = serde_json::json!({
+        "sessionId": session_id,
+        "joinCode": join_code,
+        "url": url,
+        "joinUrl": join_url,
+        "qrCode": image_cache::data_uri("image/svg+xml", qr_code.as_bytes()),
+    });
+    Ok(response.to_string())
+}
+
+/// Get the state of the LAN quick check: `{ active, sessionId, url,
+/// joinCode, joined, submitted, result }`, or `{ active: false }` when none
+/// has been started
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_lan_quick_check_status(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let state = app_handle.state::<LanQuickCheckState>();
+    let server = state.server.lock().unwrap();
+    let response = match server.as_ref() {
+        Some(server) => serde_json::json!({
+            "active": !server.cancel.is_cancelled(),
+            "sessionId": server.session_id,
+            "url": server.url,
+            "joinCode": server.join_code,
+            "joined": server.joined,
+            "submitted": server.result.is_some(),
+            "result": server.result,
+        }),
+        None => serde_json::json!({ "active": false }),
+    };
+    Ok(response.to_string())
+}
+
+/// Stop serving the LAN quick check. Answers not yet turned in are
+/// discarded.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn stop_lan_quick_check(app_handle: tauri::AppHandle) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let state = app_handle.state::<LanQuickCheckState>();
+    if let Some(server) = state.server.lock().unwrap().take() {
+        server.cancel.cancel();
+    }
+    Ok(())
+}
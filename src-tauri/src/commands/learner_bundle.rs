@@ -0,0 +1,452 @@
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use tokio::fs;
+
+use crate::archive::{self, ArchiveEntry};
+use crate::commands::storage_events::{self, StorageEvent};
+use crate::commands::{
+    app_lock, audit_log, disk_space, learner_storage, lesson_plans, library_storage,
+    project_storage, session_mode,
+};
+
+const BUNDLE_FORMAT: &str = "ta-learner-bundle";
+const BUNDLE_VERSION: u64 = 1;
+
+// Paths inside the bundle archive
+const MANIFEST_ENTRY: &str = "manifest.json";
+const PROFILE_ENTRY: &str = "profile.json";
+const LEARNER_DATA_PREFIX: &str = "learner/";
+const ARTIFACTS_PREFIX: &str = "artifacts/";
+
+// Helper to find a learner's profile in profiles.json
+pub(crate) async fn read_learner_profile(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+) -> Result<Value, String> {
+    let profiles_path = learner_storage::get_profiles_path(app_handle)?;
+
+    if !profiles_path.exists() {
+        return Err(format!("Learner not found: {}", learner_id));
+    }
+
+    let content = fs::read_to_string(&profiles_path)
+        .await
+        .map_err(|e| format!("Failed to read profiles: {}", e))?;
+    let profiles: Vec<Value> = serde_json::from_str(&content).unwrap_or_else(|_| Vec::new());
+
+    profiles
+        .into_iter()
+        .find(|p| p.get("learnerId").and_then(|v| v.as_str()) == Some(learner_id))
+        .ok_or_else(|| format!("Learner not found: {}", learner_id))
+}
+
+// Helper to collect the IDs of every artifact linked to a learner's projects,
+// with the artifacts their lesson plans link to
+pub(crate) async fn collect_learner_artifact_ids(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+) -> Result<BTreeSet<String>, String> {
+    let projects = project_storage::read_index(app_handle).await?;
+    let mut artifact_ids = BTreeSet::new();
+
+    for project in projects
+        .iter()
+        .filter(|p| p.get("learnerId").and_then(|v| v.as_str()) == Some(learner_id))
+    {
+        if let Some(ids) = project.get("artifactIds").and_then(|v| v.as_array()) {
+            artifact_ids.extend(ids.iter().filter_map(|id| id.as_str()).map(String::from));
+        }
+    }
+
+    lesson_plans::with_linked_artifacts(app_handle, artifact_ids).await
+}
+
+// ============================================
+// Learner Bundle Commands
+// ============================================
+
+/// Export a learner's profile, mastery, quick checks, assignments, and
+/// used artifacts as a single zip bundle
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn export_learner_bundle(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    output_path: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let profile = read_learner_profile(&app_handle, &learner_id).await?;
+    let learner_dir = learner_storage::get_learner_dir(&app_handle, &learner_id)?;
+    let artifacts_dir = library_storage::get_artifacts_dir(&app_handle)?;
+
+    let mut entries: Vec<ArchiveEntry> = Vec::new();
+
+    let profile_content = serde_json::to_vec_pretty(&profile)
+        .map_err(|e| format!("Failed to serialize profile: {}", e))?;
+    entries.push((PROFILE_ENTRY.to_string(), profile_content));
+
+    // Every file in the learner directory (mastery, quick checks, assignments, ...)
+    if learner_dir.exists() {
+        let mut dir = fs::read_dir(&learner_dir)
+            .await
+            .map_err(|e| format!("Failed to read learner directory: {}", e))?;
+        while let Some(entry) = dir
+            .next_entry()
+            .await
+            .map_err(|e| format!("Failed to read learner directory: {}", e))?
+        {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let contents = fs::read(&path)
+                .await
+                .map_err(|e| format!("Failed to read learner data: {}", e))?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            entries.push((format!("{}{}", LEARNER_DATA_PREFIX, name), contents));
+        }
+    }
+
+    // Artifacts used by the learner's projects
+    let mut exported_artifacts = Vec::new();
+    for artifact_id in collect_learner_artifact_ids(&app_handle, &learner_id).await? {
+        let artifact_path = artifacts_dir.join(format!("{}.json", artifact_id));
+        if !artifact_path.exists() {
+            continue;
+        }
+        let contents = fs::read(&artifact_path)
+            .await
+            .map_err(|e| format!("Failed to read artifact: {}", e))?;
+        entries.push((format!("{}{}.json", ARTIFACTS_PREFIX, artifact_id), contents));
+        exported_artifacts.push(artifact_id);
+    }
+
+    let manifest = serde_json::json!({
+        "format": BUNDLE_FORMAT,
+        "version": BUNDLE_VERSION,
+        "exportedAt": chrono::Utc::now().to_rfc3339(),
+        "learnerId": learner_id,
+        "artifactIds": exported_artifacts,
+    });
+    let manifest_content = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    entries.insert(0, (MANIFEST_ENTRY.to_string(), manifest_content));
+
+    let bundle = archive::build_zip(&entries)?;
+    disk_space::ensure_available(Path::new(&output_path), bundle.len() as u64)?;
+
+    // Create parent directories if they don't exist
+    if let Some(parent) = Path::new(&output_path).parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+
+    fs::write(&output_path, bundle)
+        .await
+        .map_err(|e| format!("Failed to write learner bundle: {}", e))?;
+
+    Ok(())
+}
+
+// Helper to replace every string equal to a remapped ID, at any depth
+pub(crate) fn remap_ids(value: &mut Value, id_map: &HashMap<String, String>) {
+    match value {
+        Value::String(s) => {
+            if let Some(new_id) = id_map.get(s.as_str()) {
+                *s = new_id.clone();
+            }
+        }
+        Value::Array(arr) => arr.iter_mut().for_each(|v| remap_ids(v, id_map)),
+        Value::Object(obj) => obj.values_mut().for_each(|v| remap_ids(v, id_map)),
+        _ => {}
+    }
+}
+
+// Helper to check a learner data file name from a bundle stays inside the
+// learner's directory: no separators and no leading dot
+fn is_safe_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 128
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+// Helper to merge imported mastery into existing mastery, keeping the most
+// recently updated record for objectives present in both
+fn merge_mastery(existing: &mut Value, imported: &Value) {
+    let imported_objectives = match imported.get("objectives").and_then(|v| v.as_object()) {
+        Some(objectives) => objectives,
+        None => return,
+    };
+
+    if let Some(obj) = existing.as_object_mut() {
+        let objectives = obj
+            .entry("objectives")
+            .or_insert_with(|| Value::Object(serde_json::Map::new()));
+        if let Some(obj_map) = objectives.as_object_mut() {
+            for (objective_id, incoming) in imported_objectives {
+                match obj_map.get_mut(objective_id) {
+                    Some(current) => {
+                        let current_updated = current.get("lastUpdated").and_then(|v| v.as_str());
+                        let incoming_updated = incoming.get("lastUpdated").and_then(|v| v.as_str());
+                        let attempts = current
+                            .get("attempts")
+                            .and_then(|v| v.as_u64())
+                            .max(incoming.get("attempts").and_then(|v| v.as_u64()));
+
+                        if incoming_updated > current_updated {
+                            *current = incoming.clone();
+                        }
+                        if let (Some(attempts), Some(record)) = (attempts, current.as_object_mut()) {
+                            record.insert("attempts".to_string(), Value::from(attempts));
+                        }
+                    }
+                    None => {
+                        obj_map.insert(objective_id.clone(), incoming.clone());
+                    }
+                }
+            }
+        }
+
+        // Keep the later of the two session dates
+        let imported_session = imported.get("lastSessionDate").and_then(|v| v.as_str());
+        let existing_session = obj.get("lastSessionDate").and_then(|v| v.as_str());
+        if imported_session > existing_session {
+            if let Some(date) = imported_session {
+                obj.insert("lastSessionDate".to_string(), Value::String(date.to_string()));
+            }
+        }
+    }
+}
+
+// Helper to merge an imported learner data file into an existing one.
+// List-shaped stores are unioned; mastery uses per-objective merging;
+// anything else keeps the existing file.
+fn merge_learner_file(file_name: &str, existing: &mut Value, imported: Value) {
+    if file_name == learner_storage::MASTERY_FILE {
+        merge_mastery(existing, &imported);
+        return;
+    }
+
+    if let (Some(current), Value::Array(incoming)) = (existing.as_array_mut(), imported) {
+        for item in incoming {
+            // Quick check results and calendar entries carry their own IDs
+            let id_key = ["resultId", "entryId"]
+                .into_iter()
+                .find(|key| item.get(key).and_then(|v| v.as_str()).is_some());
+            let duplicate = match id_key {
+                Some(key) => current.iter().any(|c| c.get(key) == item.get(key)),
+                None => current.contains(&item),
+            };
+            if !duplicate {
+                current.push(item);
+            }
+        }
+    }
+}
+
+/// Import a learner bundle produced by `export_learner_bundle`.
+///
+/// When the bundle's learner ID is already in use, the data is merged into
+/// that learner only if `merge_existing` is true (the teacher confirmed it's
+/// the same learner); otherwise it's imported as a new learner with a fresh
+/// ID. Artifacts whose IDs collide with different existing content are
+/// remapped. Bundles with IDs that aren't safe file names are rejected.
+/// Returns a JSON summary; `existingLearner` says whether the ID was in use.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn import_learner_bundle(
+    app_handle: tauri::AppHandle,
+    path: String,
+    merge_existing: Option<bool>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let bytes = fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read learner bundle: {}", e))?;
+    let entries: HashMap<String, Vec<u8>> = archive::read_zip(&bytes)?.into_iter().collect();
+
+    // Validate the manifest
+    let manifest: Value = entries
+        .get(MANIFEST_ENTRY)
+        .ok_or("Learner bundle is missing its manifest")
+        .and_then(|b| serde_json::from_slice(b).map_err(|_| "Invalid learner bundle manifest"))?;
+    if manifest.get("format").and_then(|v| v.as_str()) != Some(BUNDLE_FORMAT) {
+        return Err("File is not a learner bundle".to_string());
+    }
+    let version = manifest.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > BUNDLE_VERSION {
+        return Err(format!("Unsupported learner bundle version: {}", version));
+    }
+
+    let mut profile: Value = entries
+        .get(PROFILE_ENTRY)
+        .ok_or("Learner bundle is missing its profile")
+        .and_then(|b| serde_json::from_slice(b).map_err(|_| "Invalid learner bundle profile"))?;
+    let bundle_learner_id = profile
+        .get("learnerId")
+        .and_then(|v| v.as_str())
+        .ok_or("Profile must have a learnerId")?
+        .to_string();
+    if !library_storage::is_safe_id(&bundle_learner_id) {
+        return Err(format!("Invalid learner ID in bundle: {}", bundle_learner_id));
+    }
+
+    let mut id_map: HashMap<String, String> = HashMap::new();
+
+    // Merge into an existing learner with the same ID only when the teacher
+    // confirmed it's the same child; otherwise import alongside them
+    let existing_learner = read_learner_profile(&app_handle, &bundle_learner_id)
+        .await
+        .is_ok();
+    let merge_into_existing = existing_learner && merge_existing.unwrap_or(false);
+    if existing_learner && !merge_into_existing {
+        id_map.insert(bundle_learner_id.clone(), uuid::Uuid::new_v4().to_string());
+    }
+
+    // Remap artifact IDs that collide with different existing artifacts
+    let artifacts_dir = library_storage::get_artifacts_dir(&app_handle)?;
+    let mut artifacts: Vec<Value> = Vec::new();
+    for (name, contents) in entries.iter().filter(|(n, _)| n.starts_with(ARTIFACTS_PREFIX)) {
+        let artifact: Value = serde_json::from_slice(contents)
+            .map_err(|e| format!("Invalid artifact {} in bundle: {}", name, e))?;
+        let artifact_id = artifact
+            .get("artifactId")
+            .and_then(|v| v.as_str())
+            .ok_or("Artifact must have an artifactId")?
+            .to_string();
+        if !library_storage::is_safe_id(&artifact_id) {
+            return Err(format!("Invalid artifact ID in bundle: {}", artifact_id));
+        }
+
+        let existing_path = artifacts_dir.join(format!("{}.json", artifact_id));
+        if existing_path.exists() {
+            let existing_content = fs::read_to_string(&existing_path)
+                .await
+                .map_err(|e| format!("Failed to read artifact: {}", e))?;
+            let existing: Value = serde_json::from_str(&existing_content).unwrap_or(Value::Null);
+            if existing == artifact {
+                // Already in the library, nothing to import
+                continue;
+            }
+            id_map.insert(artifact_id, uuid::Uuid::new_v4().to_string());
+        }
+        artifacts.push(artifact);
+    }
+
+    // Write artifacts with remapped IDs
+    let mut imported_artifacts = Vec::new();
+    for mut artifact in artifacts {
+        remap_ids(&mut artifact, &id_map);
+        let content = serde_json::to_string_pretty(&artifact)
+            .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+        library_storage::write_artifact(&app_handle, &artifact, &content).await?;
+        if let Some(artifact_id) = artifact.get("artifactId").and_then(|v| v.as_str()) {
+            imported_artifacts.push(artifact_id.to_string());
+        }
+    }
+
+    // Save the profile (unless merging into the same learner)
+    remap_ids(&mut profile, &id_map);
+    let learner_id = profile
+        .get("learnerId")
+        .and_then(|v| v.as_str())
+        .unwrap_or(&bundle_learner_id)
+        .to_string();
+
+    let learners_dir = learner_storage::get_learners_dir(&app_handle)?;
+    fs::create_dir_all(&learners_dir)
+        .await
+        .map_err(|e| format!("Failed to create learners directory: {}", e))?;
+
+    if !merge_into_existing {
+        let profiles_path = learner_storage::get_profiles_path(&app_handle)?;
+        let mut profiles: Vec<Value> = if profiles_path.exists() {
+            let content = fs::read_to_string(&profiles_path)
+                .await
+                .map_err(|e| format!("Failed to read profiles: {}", e))?;
+            serde_json::from_str(&content).unwrap_or_else(|_| Vec::new())
+        } else {
+            Vec::new()
+        };
+        profiles.push(profile);
+
+        let content = serde_json::to_string_pretty(&profiles)
+            .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+        fs::write(&profiles_path, content)
+            .await
+            .map_err(|e| format!("Failed to write profiles: {}", e))?;
+    }
+
+    // Write or merge learner data files
+    let learner_dir = learner_storage::get_learner_dir(&app_handle, &learner_id)?;
+    fs::create_dir_all(&learner_dir)
+        .await
+        .map_err(|e| format!("Failed to create learner directory: {}", e))?;
+
+    for (name, contents) in entries.iter() {
+        let file_name = match name.strip_prefix(LEARNER_DATA_PREFIX) {
+            Some(file_name) if is_safe_file_name(file_name) => file_name,
+            _ => continue,
+        };
+        let target_path = learner_dir.join(file_name);
+
+        let mut imported: Value = match serde_json::from_slice(contents) {
+            Ok(value) => value,
+            Err(_) => {
+                // Non-JSON files are copied only if absent
+                if !target_path.exists() {
+                    fs::write(&target_path, contents)
+                        .await
+                        .map_err(|e| format!("Failed to write learner data: {}", e))?;
+                }
+                continue;
+            }
+        };
+        remap_ids(&mut imported, &id_map);
+
+        let merged = if target_path.exists() {
+            let content = fs::read_to_string(&target_path)
+                .await
+                .map_err(|e| format!("Failed to read learner data: {}", e))?;
+            match serde_json::from_str::<Value>(&content) {
+                Ok(mut existing) => {
+                    merge_learner_file(file_name, &mut existing, imported);
+                    existing
+                }
+                Err(_) => imported,
+            }
+        } else {
+            imported
+        };
+
+        let content = serde_json::to_string_pretty(&merged)
+            .map_err(|e| format!("Failed to serialize learner data: {}", e))?;
+        fs::write(&target_path, content)
+            .await
+            .map_err(|e| format!("Failed to write learner data: {}", e))?;
+    }
+
+    storage_events::emit(&app_handle, StorageEvent::LearnerUpdated(&learner_id));
+
+    let mut audited_ids = vec![learner_id.as_str()];
+    audited_ids.extend(imported_artifacts.iter().map(String::as_str));
+    audit_log::record(&app_handle, "import_learner_bundle", "learner", &audited_ids).await;
+
+    let summary = serde_json::json!({
+        "learnerId": learner_id,
+        "existingLearner": existing_learner,
+        "mergedIntoExisting": merge_into_existing,
+        "importedArtifactIds": imported_artifacts,
+        "remappedIds": id_map,
+    });
+    Ok(summary.to_string())
+}
@@ -0,0 +1,627 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::Path;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::archive::{self, ArchiveEntry};
+use crate::commands::storage_events::{self, StorageEvent};
+use crate::commands::{
+    app_lock, audit_log, data_history, design_pack_storage, disk_space, generation_history,
+    image_library, learner_bundle, learner_storage, library_storage, moderation_log,
+    project_storage, reminders, session_mode,
+};
+
+// Fields holding a learner's name
+const NAME_KEYS: &[&str] = &["displayName", "learnerName", "studentName", "firstName", "lastName"];
+// Fields holding a date of birth (reduced to the birth year)
+const BIRTH_DATE_KEYS: &[&str] = &["dateOfBirth", "dob", "birthDate", "birthday"];
+// Free-text fields written by adults about the learner
+const NOTE_KEYS: &[&str] = &["notes", "parentNotes", "teacherNotes", "comment", "comments"];
+
+const REDACTED: &str = "[redacted]";
+
+// Stands in for a deleted learner's ID in the audit log
+const ERASED_ID: &str = "[deleted]";
+
+// Salt pseudonyms are derived with, kept so they stay the same over time
+const PRIVACY_DIR: &str = "privacy";
+const SALT_FILE: &str = "pseudonym-salt";
+
+// Helper to read the pseudonym salt, creating it the first time
+async fn pseudonym_salt(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let salt_path = app_data_dir.join(PRIVACY_DIR).join(SALT_FILE);
+    if let Ok(salt) = fs::read_to_string(&salt_path).await {
+        if !salt.trim().is_empty() {
+            return Ok(salt.trim().to_string());
+        }
+    }
+
+    if let Some(parent) = salt_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create privacy directory: {}", e))?;
+    }
+    let salt = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    fs::write(&salt_path, &salt)
+        .await
+        .map_err(|e| format!("Failed to write pseudonym salt: {}", e))?;
+    Ok(salt)
+}
+
+// Helper to derive a learner's pseudonym: SHA-256 of the stored salt and
+// their ID, so it stays the same across releases but can't be worked out
+// from the ID alone
+async fn pseudonym_for(app_handle: &tauri::AppHandle, learner_id: &str) -> Result<String, String> {
+    let salt = pseudonym_salt(app_handle).await?;
+    let digest = Sha256::new()
+        .chain_update(salt.as_bytes())
+        .chain_update(learner_id.as_bytes())
+        .finalize();
+    Ok(format!("Learner {:02X}{:02X}{:02X}", digest[0], digest[1], digest[2]))
+}
+
+// Helper to collect the names to replace in free text: each name field of
+// a profile and the words in it, longest first so a full name goes before
+// its parts. Single letters are left, as they'd match too much.
+fn name_tokens(profile: &Value) -> Vec<String> {
+    let mut names: Vec<String> = NAME_KEYS
+        .iter()
+        .filter_map(|key| profile.get(*key).and_then(|v| v.as_str()))
+        .flat_map(|name| std::iter::once(name.trim()).chain(name.split_whitespace()))
+        .filter(|name| name.chars().count() >= 2)
+        .map(String::from)
+        .collect();
+    names.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    names.dedup();
+    names
+}
+
+// Helper to get the length in `text` of a case-insensitive match of
+// `prefix` at its start, if there is one
+fn match_ignoring_case(text: &str, prefix: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    for p in prefix.chars() {
+        let (_, c) = chars.next()?;
+        if !c.to_lowercase().eq(p.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(chars.next().map_or(text.len(), |(i, _)| i))
+}
+
+// Helper to replace a name in free text with the pseudonym, matching whole
+// words in any case, so a learner called "Al" doesn't change "Algebra"
+fn replace_name(text: &str, name: &str, pseudonym: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut previous: Option<char> = None;
+    while let Some(c) = rest.chars().next() {
+        if !previous.is_some_and(char::is_alphanumeric) {
+            if let Some(len) = match_ignoring_case(rest, name) {
+                if !rest[len..].chars().next().is_some_and(char::is_alphanumeric) {
+                    out.push_str(pseudonym);
+                    previous = pseudonym.chars().last();
+                    rest = &rest[len..];
+                    continue;
+                }
+            }
+        }
+        out.push(c);
+        previous = Some(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+// Helper to scrub PII from a JSON value in place, replacing `names` (from
+// `name_tokens`) in free text. Scores, states, dates of activity, and IDs
+// are left untouched so analytics still work.
+fn scrub_value(value: &mut Value, names: &[String], pseudonym: &str) {
+    match value {
+        Value::Object(obj) => {
+            for (key, field) in obj.iter_mut() {
+                if NAME_KEYS.contains(&key.as_str()) && field.is_string() {
+                    *field = Value::String(pseudonym.to_string());
+                } else if BIRTH_DATE_KEYS.contains(&key.as_str()) {
+                    let year = field.as_str().and_then(|d| d.get(..4)).map(String::from);
+                    *field = year.map(Value::String).unwrap_or(Value::Null);
+                } else if NOTE_KEYS.contains(&key.as_str()) {
+                    if field.as_str().is_some_and(|s| !s.is_empty()) {
+                        *field = Value::String(REDACTED.to_string());
+                    }
+                } else {
+                    scrub_value(field, names, pseudonym);
+                }
+            }
+        }
+        Value::Array(arr) => arr
+            .iter_mut()
+            .for_each(|v| scrub_value(v, names, pseudonym)),
+        Value::String(s) => {
+            for name in names {
+                *s = replace_name(s, name, pseudonym);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Helper to check whether a JSON value contains a string equal to `id`
+fn references_id(value: &Value, id: &str) -> bool {
+    match value {
+        Value::String(s) => s == id,
+        Value::Array(arr) => arr.iter().any(|v| references_id(v, id)),
+        Value::Object(obj) => obj.values().any(|v| references_id(v, id)),
+        _ => false,
+    }
+}
+
+// Helper to check whether an entry in a shared store (reminders, generation
+// history, moderation log, image library) is about a learner. Image tags are
+// stored lowercased, so the lowercased ID counts too.
+fn is_about(entry: &Value, learner_id: &str) -> bool {
+    references_id(entry, learner_id) || references_id(entry, &learner_id.to_lowercase())
+}
+
+// Helper to check whether an audit entry is about a learner or was made in
+// their session
+fn is_audit_about(entry: &Value, learner_id: &str) -> bool {
+    let actor = entry.get("actor").and_then(|v| v.as_str());
+    actor == Some(&format!("learner:{}", learner_id)) || references_id(entry, learner_id)
+}
+
+// Helper to scrub the entries about a learner in place. Returns whether
+// there were any.
+fn scrub_entries(
+    entries: &mut [Value],
+    learner_id: &str,
+    names: &[String],
+    pseudonym: &str,
+) -> bool {
+    let mut scrubbed = false;
+    for entry in entries.iter_mut().filter(|e| is_about(e, learner_id)) {
+        scrub_value(entry, names, pseudonym);
+        scrubbed = true;
+    }
+    scrubbed
+}
+
+// Helper to add a store's records about a learner to an export, listed by
+// title in the summary when they have one
+fn add_records(
+    entries: &mut Vec<ArchiveEntry>,
+    summary: &mut String,
+    heading: &str,
+    file: &str,
+    records: &[Value],
+) -> Result<(), String> {
+    let _ = writeln!(summary, "## {} ({})", heading, records.len());
+    let _ = writeln!(summary);
+    for title in records.iter().filter_map(|r| r.get("title").and_then(|v| v.as_str())) {
+        let _ = writeln!(summary, "- {}", title);
+    }
+    if records.is_empty() {
+        return Ok(());
+    }
+    let _ = writeln!(summary);
+    let content = serde_json::to_vec_pretty(records)
+        .map_err(|e| format!("Failed to serialize {}: {}", file, e))?;
+    entries.push((format!("data/{}", file), content));
+    Ok(())
+}
+
+/// Remove what the shared stores hold about a deleted learner: their
+/// reminders, generation history and moderation entries, and images tagged
+/// with them. Their ID is blanked out of the audit log, keeping the entries,
+/// and the data history is purged so older snapshots don't keep them.
+pub(crate) async fn erase_learner_records(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+) -> Result<(), String> {
+    let mut stored = reminders::read_reminders(app_handle).await?;
+    let before = stored.len();
+    stored.retain(|r| !is_about(r, learner_id));
+    if stored.len() != before {
+        reminders::write_reminders(app_handle, &stored).await?;
+    }
+
+    let mut history = generation_history::read_history(app_handle).await?;
+    let before = history.len();
+    history.retain(|h| !is_about(h, learner_id));
+    if history.len() != before {
+        generation_history::write_history(app_handle, history).await?;
+    }
+
+    let mut log = moderation_log::read_log(app_handle).await?;
+    let before = log.len();
+    log.retain(|d| !is_about(d, learner_id));
+    if log.len() != before {
+        moderation_log::write_log(app_handle, log).await?;
+    }
+
+    let (tagged, images): (Vec<Value>, Vec<Value>) = image_library::read_entries(app_handle)
+        .await?
+        .into_iter()
+        .partition(|i| is_about(i, learner_id));
+    if !tagged.is_empty() {
+        image_library::write_entries(app_handle, images).await?;
+        for image in &tagged {
+            let image_id = image.get("imageId").and_then(|v| v.as_str()).unwrap_or("");
+            let Ok(image_dir) = image_library::get_image_dir(app_handle, image_id) else {
+                continue;
+            };
+            if image_dir.exists() {
+                fs::remove_dir_all(&image_dir)
+                    .await
+                    .map_err(|e| format!("Failed to delete image: {}", e))?;
+            }
+        }
+    }
+
+    let mut audit = audit_log::read_entries(app_handle).await?;
+    let actor = format!("learner:{}", learner_id);
+    let id_map = HashMap::from([(learner_id.to_string(), ERASED_ID.to_string())]);
+    let mut erased = false;
+    for entry in audit.iter_mut().filter(|e| is_audit_about(e, learner_id)) {
+        if let Some(obj) = entry.as_object_mut() {
+            if obj.get("actor").and_then(|v| v.as_str()) == Some(&actor) {
+                obj.insert("actor".to_string(), Value::String(format!("learner:{}", ERASED_ID)));
+            }
+        }
+        learner_bundle::remap_ids(entry, &id_map);
+        erased = true;
+    }
+    if erased {
+        audit_log::write_entries(app_handle, &audit).await?;
+    }
+    data_history::purge(app_handle, "a learner was deleted").await
+}
+
+// ============================================
+// Privacy Commands
+// ============================================
+
+/// Replace a learner's name, date of birth, and notes with pseudonyms across
+/// the profile, every file in their learner directory (mastery, quick
+/// checks, assignments, calendar), and the reminders, generation history,
+/// moderation log, and image library entries about them. The audit log only
+/// holds IDs and is left as is. The data history is purged, since older
+/// snapshots still hold the real details. Returns the pseudonym that was
+/// applied.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn anonymize_learner(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let profiles_path = learner_storage::get_profiles_path(&app_handle)?;
+
+    if !profiles_path.exists() {
+        return Err(format!("Learner not found: {}", learner_id));
+    }
+
+    let content = fs::read_to_string(&profiles_path)
+        .await
+        .map_err(|e| format!("Failed to read profiles: {}", e))?;
+    let mut profiles: Vec<Value> = serde_json::from_str(&content).unwrap_or_else(|_| Vec::new());
+
+    let profile = profiles
+        .iter_mut()
+        .find(|p| p.get("learnerId").and_then(|v| v.as_str()) == Some(&learner_id))
+        .ok_or_else(|| format!("Learner not found: {}", learner_id))?;
+
+    let pseudonym = pseudonym_for(&app_handle, &learner_id).await?;
+    let names = name_tokens(profile);
+
+    scrub_value(profile, &names, &pseudonym);
+    if let Some(obj) = profile.as_object_mut() {
+        obj.insert(
+            "anonymizedAt".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        );
+    }
+
+    let content = serde_json::to_string_pretty(&profiles)
+        .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    fs::write(&profiles_path, content)
+        .await
+        .map_err(|e| format!("Failed to write profiles: {}", e))?;
+
+    // Scrub every JSON file in the learner directory
+    let learner_dir = learner_storage::get_learner_dir(&app_handle, &learner_id)?;
+    if learner_dir.exists() {
+        let mut dir = fs::read_dir(&learner_dir)
+            .await
+            .map_err(|e| format!("Failed to read learner directory: {}", e))?;
+        while let Some(entry) = dir
+            .next_entry()
+            .await
+            .map_err(|e| format!("Failed to read learner directory: {}", e))?
+        {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+
+            let content = fs::read_to_string(&path)
+                .await
+                .map_err(|e| format!("Failed to read learner data: {}", e))?;
+            let mut data: Value = match serde_json::from_str(&content) {
+                Ok(data) => data,
+                Err(_) => continue,
+            };
+            scrub_value(&mut data, &names, &pseudonym);
+
+            let content = serde_json::to_string_pretty(&data)
+                .map_err(|e| format!("Failed to serialize learner data: {}", e))?;
+            fs::write(&path, content)
+                .await
+                .map_err(|e| format!("Failed to write learner data: {}", e))?;
+        }
+    }
+
+    // Scrub what the shared stores hold about the learner
+    let mut stored = reminders::read_reminders(&app_handle).await?;
+    if scrub_entries(&mut stored, &learner_id, &names, &pseudonym) {
+        reminders::write_reminders(&app_handle, &stored).await?;
+    }
+    let mut history = generation_history::read_history(&app_handle).await?;
+    if scrub_entries(&mut history, &learner_id, &names, &pseudonym) {
+        generation_history::write_history(&app_handle, history).await?;
+    }
+    let mut log = moderation_log::read_log(&app_handle).await?;
+    if scrub_entries(&mut log, &learner_id, &names, &pseudonym) {
+        moderation_log::write_log(&app_handle, log).await?;
+    }
+    let mut images = image_library::read_entries(&app_handle).await?;
+    if scrub_entries(&mut images, &learner_id, &names, &pseudonym) {
+        image_library::write_entries(&app_handle, images).await?;
+    }
+
+    data_history::purge(&app_handle, "a learner was anonymized").await?;
+
+    storage_events::emit(&app_handle, StorageEvent::LearnerUpdated(&learner_id));
+    audit_log::record(&app_handle, "anonymize_learner", "learner", &[&learner_id]).await;
+
+    Ok(pseudonym)
+}
+
+/// Collect everything stored about a learner into a zip containing the raw
+/// JSON plus a human-readable SUMMARY.md: the profile, learner data files
+/// (including the calendar), projects, artifacts, and design packs that
+/// reference the learner ID, their reminders, the generation history and
+/// moderation log entries for them or their artifacts, images tagged with
+/// them, and the audit log entries about them or made in their session
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn export_all_data_for_learner(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    output_path: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let profile = learner_bundle::read_learner_profile(&app_handle, &learner_id).await?;
+    let display_name = profile
+        .get("displayName")
+        .and_then(|v| v.as_str())
+        .unwrap_or("(unnamed)")
+        .to_string();
+
+    let mut entries: Vec<ArchiveEntry> = Vec::new();
+    let mut summary = String::new();
+    let _ = writeln!(summary, "# Data stored about {}", display_name);
+    let _ = writeln!(summary);
+    let _ = writeln!(summary, "- Learner ID: `{}`", learner_id);
+    let _ = writeln!(summary, "- Exported at: {}", chrono::Utc::now().to_rfc3339());
+    let _ = writeln!(summary);
+
+    // Profile
+    let content = serde_json::to_vec_pretty(&profile)
+        .map_err(|e| format!("Failed to serialize profile: {}", e))?;
+    entries.push(("data/profile.json".to_string(), content));
+    let _ = writeln!(summary, "## Profile");
+    let _ = writeln!(summary);
+    if let Some(obj) = profile.as_object() {
+        for (key, value) in obj {
+            let _ = writeln!(summary, "- {}: {}", key, value);
+        }
+    }
+    let _ = writeln!(summary);
+
+    // Learner data files (mastery, quick checks, assignments, ...)
+    let _ = writeln!(summary, "## Learner records");
+    let _ = writeln!(summary);
+    let learner_dir = learner_storage::get_learner_dir(&app_handle, &learner_id)?;
+    if learner_dir.exists() {
+        let mut dir = fs::read_dir(&learner_dir)
+            .await
+            .map_err(|e| format!("Failed to read learner directory: {}", e))?;
+        while let Some(entry) = dir
+            .next_entry()
+            .await
+            .map_err(|e| format!("Failed to read learner directory: {}", e))?
+        {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let contents = fs::read(&path)
+                .await
+                .map_err(|e| format!("Failed to read learner data: {}", e))?;
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            let description = match serde_json::from_slice::<Value>(&contents) {
+                Ok(Value::Array(items)) => format!("{} records", items.len()),
+                Ok(value) => match value.get("objectives").and_then(|v| v.as_object()) {
+                    Some(objectives) => format!("{} objectives tracked", objectives.len()),
+                    None => "1 record".to_string(),
+                },
+                Err(_) => format!("{} bytes", contents.len()),
+            };
+            let _ = writeln!(summary, "- `{}`: {}", name, description);
+            entries.push((format!("data/learner/{}", name), contents));
+        }
+    }
+    let _ = writeln!(summary);
+
+    // Projects referencing the learner
+    let mut projects = Vec::new();
+    for entry in project_storage::read_index(&app_handle).await? {
+        let full = match entry.get("projectId").and_then(|v| v.as_str()) {
+            Some(project_id) => project_storage::read_project(&app_handle, project_id).await.ok(),
+            None => None,
+        };
+        let project = full.unwrap_or(entry);
+        if references_id(&project, &learner_id) {
+            projects.push(project);
+        }
+    }
+    let _ = writeln!(summary, "## Projects ({})", projects.len());
+    let _ = writeln!(summary);
+    for project in &projects {
+        let name = project.get("name").and_then(|v| v.as_str()).unwrap_or("(untitled)");
+        let _ = writeln!(summary, "- {}", name);
+    }
+    let _ = writeln!(summary);
+    let content = serde_json::to_vec_pretty(&projects)
+        .map_err(|e| format!("Failed to serialize projects: {}", e))?;
+    entries.push(("data/projects.json".to_string(), content));
+
+    // Artifacts linked through projects or referencing the learner directly
+    let linked_ids = learner_bundle::collect_learner_artifact_ids(&app_handle, &learner_id).await?;
+    let artifacts_dir = library_storage::get_artifacts_dir(&app_handle)?;
+    let mut artifact_titles = Vec::new();
+    let mut exported_ids: HashSet<String> = HashSet::new();
+    if artifacts_dir.exists() {
+        let mut dir = fs::read_dir(&artifacts_dir)
+            .await
+            .map_err(|e| format!("Failed to read artifacts directory: {}", e))?;
+        while let Some(entry) = dir
+            .next_entry()
+            .await
+            .map_err(|e| format!("Failed to read artifacts directory: {}", e))?
+        {
+            let path = entry.path();
+            let artifact_id = match path.file_stem().and_then(|s| s.to_str()) {
+                Some(id) if path.extension().and_then(|e| e.to_str()) == Some("json") => {
+                    id.to_string()
+                }
+                _ => continue,
+            };
+            let contents = fs::read(&path)
+                .await
+                .map_err(|e| format!("Failed to read artifact: {}", e))?;
+            let artifact: Value = serde_json::from_slice(&contents).unwrap_or(Value::Null);
+            if !linked_ids.contains(&artifact_id) && !references_id(&artifact, &learner_id) {
+                continue;
+            }
+            let title = artifact
+                .get("title")
+                .and_then(|v| v.as_str())
+                .unwrap_or("(untitled)")
+                .to_string();
+            artifact_titles.push(title);
+            entries.push((format!("data/artifacts/{}.json", artifact_id), contents));
+            exported_ids.insert(artifact_id);
+        }
+    }
+    let _ = writeln!(summary, "## Artifacts ({})", artifact_titles.len());
+    let _ = writeln!(summary);
+    for title in &artifact_titles {
+        let _ = writeln!(summary, "- {}", title);
+    }
+    let _ = writeln!(summary);
+
+    // Design packs referencing the learner
+    let packs: Vec<Value> = design_pack_storage::read_packs(&app_handle)
+        .await?
+        .into_iter()
+        .filter(|pack| references_id(pack, &learner_id))
+        .collect();
+    if !packs.is_empty() {
+        let _ = writeln!(summary, "## Design packs ({})", packs.len());
+        let _ = writeln!(summary);
+        for pack in &packs {
+            let name = pack.get("name").and_then(|v| v.as_str()).unwrap_or("(untitled)");
+            let _ = writeln!(summary, "- {}", name);
+        }
+        let _ = writeln!(summary);
+        let content = serde_json::to_vec_pretty(&packs)
+            .map_err(|e| format!("Failed to serialize design packs: {}", e))?;
+        entries.push(("data/design-packs.json".to_string(), content));
+    }
+
+    // Records in the shared stores
+    let touches = |entry: &Value| {
+        is_about(entry, &learner_id) || exported_ids.iter().any(|id| references_id(entry, id))
+    };
+    let stored: Vec<Value> = reminders::read_reminders(&app_handle)
+        .await?
+        .into_iter()
+        .filter(|r| is_about(r, &learner_id))
+        .collect();
+    add_records(&mut entries, &mut summary, "Reminders", "reminders.json", &stored)?;
+    let history: Vec<Value> = generation_history::read_history(&app_handle)
+        .await?
+        .into_iter()
+        .filter(|h| touches(h))
+        .collect();
+    let file = "generation-history.json";
+    add_records(&mut entries, &mut summary, "Generation history", file, &history)?;
+    let log: Vec<Value> = moderation_log::read_log(&app_handle)
+        .await?
+        .into_iter()
+        .filter(|d| touches(d))
+        .collect();
+    add_records(&mut entries, &mut summary, "Moderation log", "moderation-log.json", &log)?;
+    let images: Vec<Value> = image_library::read_entries(&app_handle)
+        .await?
+        .into_iter()
+        .filter(|i| is_about(i, &learner_id))
+        .collect();
+    add_records(&mut entries, &mut summary, "Images", "images.json", &images)?;
+    for image in &images {
+        let image_id = image.get("imageId").and_then(|v| v.as_str()).unwrap_or("");
+        let Ok(image_dir) = image_library::get_image_dir(&app_handle, image_id) else {
+            continue;
+        };
+        if let Ok(contents) = fs::read(image_dir.join(image_library::ORIGINAL_FILE)).await {
+            entries.push((format!("data/images/{}", image_id), contents));
+        }
+    }
+    let audit: Vec<Value> = audit_log::read_entries(&app_handle)
+        .await?
+        .into_iter()
+        .filter(|e| is_audit_about(e, &learner_id))
+        .collect();
+    add_records(&mut entries, &mut summary, "Audit log", "audit-log.json", &audit)?;
+
+    entries.insert(0, ("SUMMARY.md".to_string(), summary.into_bytes()));
+    let bundle = archive::build_zip(&entries)?;
+    disk_space::ensure_available(Path::new(&output_path), bundle.len() as u64)?;
+
+    // Create parent directories if they don't exist
+    if let Some(parent) = Path::new(&output_path).parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+
+    fs::write(&output_path, bundle)
+        .await
+        .map_err(|e| format!("Failed to write learner data export: {}", e))?;
+
+    Ok(())
+}
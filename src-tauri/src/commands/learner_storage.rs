@@ -1,14 +1,21 @@
-use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::path::PathBuf;
+use tauri::Manager;
 use tokio::fs;
 
+use crate::commands::storage_events::{self, StorageEvent};
+use crate::commands::{
+    app_lock, audit_log, automation, learner_privacy, project_activity, quick_check_session,
+    session_mode,
+};
+use crate::json_stream;
+
 const LEARNERS_DIR: &str = "learners";
 const PROFILES_FILE: &str = "profiles.json";
-const MASTERY_FILE: &str = "mastery.json";
+pub(crate) const MASTERY_FILE: &str = "mastery.json";
 
 // Helper to get the learners directory
-fn get_learners_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn get_learners_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     let app_data_dir = app_handle
         .path()
         .app_data_dir()
@@ -17,22 +24,37 @@ fn get_learners_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
 }
 
 // Helper to get the profiles file path
-fn get_profiles_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn get_profiles_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(get_learners_dir(app_handle)?.join(PROFILES_FILE))
 }
 
 // Helper to get a learner's data directory
-fn get_learner_dir(app_handle: &tauri::AppHandle, learner_id: &str) -> Result<PathBuf, String> {
+pub(crate) fn get_learner_dir(app_handle: &tauri::AppHandle, learner_id: &str) -> Result<PathBuf, String> {
     Ok(get_learners_dir(app_handle)?.join(learner_id))
 }
 
+/// Read every learner profile; empty if none are stored
+pub(crate) async fn read_profiles(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let profiles_path = get_profiles_path(app_handle)?;
+    if !profiles_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&profiles_path)
+        .await
+        .map_err(|e| format!("Failed to read profiles: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
 // ============================================
 // Profile Commands
 // ============================================
 
 /// Get all learner profiles
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn get_learner_profiles(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
     let profiles_path = get_profiles_path(&app_handle)?;
 
     // If file doesn't exist, return empty array
@@ -40,17 +62,34 @@ pub async fn get_learner_profiles(app_handle: tauri::AppHandle) -> Result<String
         return Ok("[]".to_string());
     }
 
-    fs::read_to_string(&profiles_path)
+    let content = fs::read_to_string(&profiles_path)
         .await
-        .map_err(|e| format!("Failed to read profiles: {}", e))
+        .map_err(|e| format!("Failed to read profiles: {}", e))?;
+
+    // During a learner session only the active learner is visible
+    if let Some(active_id) = session_mode::active_learner_id(&app_handle) {
+        let profiles: Vec<Value> = serde_json::from_str(&content).unwrap_or_else(|_| Vec::new());
+        let filtered: Vec<&Value> = profiles
+            .iter()
+            .filter(|p| p.get("learnerId").and_then(|v| v.as_str()) == Some(&active_id))
+            .collect();
+        return serde_json::to_string(&filtered)
+            .map_err(|e| format!("Failed to serialize profiles: {}", e));
+    }
+
+    Ok(content)
 }
 
 /// Save a learner profile (upsert)
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn save_learner_profile(
     app_handle: tauri::AppHandle,
     profile: String,
 ) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
     let learners_dir = get_learners_dir(&app_handle)?;
     let profiles_path = get_profiles_path(&app_handle)?;
 
@@ -104,15 +143,22 @@ pub async fn save_learner_profile(
         .await
         .map_err(|e| format!("Failed to create learner directory: {}", e))?;
 
+    storage_events::emit(&app_handle, StorageEvent::LearnerUpdated(learner_id));
+    audit_log::record(&app_handle, "save_learner_profile", "learner", &[learner_id]).await;
+
     Ok(())
 }
 
 /// Delete a learner profile and all associated data
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn delete_learner_profile(
     app_handle: tauri::AppHandle,
     learner_id: String,
 ) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
     let profiles_path = get_profiles_path(&app_handle)?;
     let learner_dir = get_learner_dir(&app_handle, &learner_id)?;
 
@@ -140,6 +186,13 @@ pub async fn delete_learner_profile(
             .map_err(|e| format!("Failed to delete learner data: {}", e))?;
     }
 
+    // And what other stores hold about them
+    learner_privacy::erase_learner_records(&app_handle, &learner_id).await?;
+
+    storage_events::emit(&app_handle, StorageEvent::LearnerDeleted(&learner_id));
+    // The ID has just been erased from the audit log, so it isn't recorded
+    audit_log::record(&app_handle, "delete_learner_profile", "learner", &[]).await;
+
     Ok(())
 }
 
@@ -149,10 +202,14 @@ pub async fn delete_learner_profile(
 
 /// Get mastery data for a learner
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn get_learner_mastery(
     app_handle: tauri::AppHandle,
     learner_id: String,
 ) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_learner_allowed(&app_handle, &learner_id)?;
+
     let learner_dir = get_learner_dir(&app_handle, &learner_id)?;
     let mastery_path = learner_dir.join(MASTERY_FILE);
 
@@ -171,13 +228,18 @@ pub async fn get_learner_mastery(
         .map_err(|e| format!("Failed to read mastery data: {}", e))
 }
 
-/// Save mastery data for a specific objective
+/// Save mastery data for a specific objective. Teacher mode only, so a
+/// learner can't set their own mastery.
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn save_objective_mastery(
     app_handle: tauri::AppHandle,
     learner_id: String,
     objective_mastery: String,
 ) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
     let learner_dir = get_learner_dir(&app_handle, &learner_id)?;
     let mastery_path = learner_dir.join(MASTERY_FILE);
 
@@ -235,16 +297,24 @@ pub async fn save_objective_mastery(
         .await
         .map_err(|e| format!("Failed to write mastery data: {}", e))?;
 
+    storage_events::emit(&app_handle, StorageEvent::LearnerUpdated(&learner_id));
+    audit_log::record(&app_handle, "save_objective_mastery", "mastery", &[&learner_id]).await;
+
     Ok(())
 }
 
-/// Save complete mastery data for a learner (bulk update)
+/// Save complete mastery data for a learner (bulk update). Teacher mode
+/// only, like `save_objective_mastery`.
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn save_learner_mastery(
     app_handle: tauri::AppHandle,
     learner_id: String,
     mastery_data: String,
 ) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
     let learner_dir = get_learner_dir(&app_handle, &learner_id)?;
     let mastery_path = learner_dir.join(MASTERY_FILE);
 
@@ -262,6 +332,9 @@ pub async fn save_learner_mastery(
         .await
         .map_err(|e| format!("Failed to write mastery data: {}", e))?;
 
+    storage_events::emit(&app_handle, StorageEvent::LearnerUpdated(&learner_id));
+    audit_log::record(&app_handle, "save_learner_mastery", "mastery", &[&learner_id]).await;
+
     Ok(())
 }
 
@@ -269,15 +342,19 @@ pub async fn save_learner_mastery(
 // Quick Check Commands (Phase 2)
 // ============================================
 
-const QUICK_CHECKS_FILE: &str = "quick-checks.json";
+pub(crate) const QUICK_CHECKS_FILE: &str = "quick-checks.json";
 
 /// Get quick check history for a learner
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn get_quick_check_history(
     app_handle: tauri::AppHandle,
     learner_id: String,
     objective_id: Option<String>,
 ) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_learner_allowed(&app_handle, &learner_id)?;
+
     let learner_dir = get_learner_dir(&app_handle, &learner_id)?;
     let checks_path = learner_dir.join(QUICK_CHECKS_FILE);
 
@@ -286,32 +363,63 @@ pub async fn get_quick_check_history(
         return Ok("[]".to_string());
     }
 
-    let content = fs::read_to_string(&checks_path)
-        .await
-        .map_err(|e| format!("Failed to read quick check history: {}", e))?;
-
-    // Filter by objective_id if provided
+    // Filter by objective_id if provided, one result at a time so a long
+    // history is never parsed whole
     if let Some(obj_id) = objective_id {
-        let checks: Vec<Value> = serde_json::from_str(&content).unwrap_or_else(|_| Vec::new());
-        let filtered: Vec<&Value> = checks
-            .iter()
-            .filter(|c| c.get("objectiveId").and_then(|v| v.as_str()) == Some(&obj_id))
-            .collect();
+        let filtered = json_stream::filter_array(&checks_path, None, move |c| {
+            c.get("objectiveId").and_then(|v| v.as_str()) == Some(&obj_id)
+        })
+        .await?;
         return serde_json::to_string(&filtered)
             .map_err(|e| format!("Failed to serialize filtered history: {}", e));
     }
 
-    Ok(content)
+    fs::read_to_string(&checks_path)
+        .await
+        .map_err(|e| format!("Failed to read quick check history: {}", e))
 }
 
-/// Save a quick check result
+/// Save a quick check result. With `timing_token` from
+/// `start_timed_session`, the backend's timing is recorded in the result
+/// (see `quick_check_session::apply_timing`). In learner mode a timing token
+/// is required, so a learner can't save a score of their own making; graded
+/// quick checks are saved by `submit_quick_check_session`.
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn save_quick_check_result(
     app_handle: tauri::AppHandle,
     learner_id: String,
     result: String,
+    timing_token: Option<String>,
 ) -> Result<(), String> {
-    let learner_dir = get_learner_dir(&app_handle, &learner_id)?;
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_learner_allowed(&app_handle, &learner_id)?;
+
+    // Parse the incoming result
+    let mut new_result: Value =
+        serde_json::from_str(&result).map_err(|e| format!("Invalid result JSON: {}", e))?;
+
+    if timing_token.is_none() && session_mode::active_learner_id(&app_handle).is_some() {
+        return Err("Results can't be saved directly in learner mode".to_string());
+    }
+    if let Some(token) = timing_token {
+        quick_check_session::apply_timing(&app_handle, &token, &learner_id, &mut new_result)?;
+    }
+
+    append_quick_check_result(&app_handle, &learner_id, new_result).await?;
+
+    audit_log::record(&app_handle, "save_quick_check_result", "quick_check", &[&learner_id]).await;
+
+    Ok(())
+}
+
+/// Append a result to a learner's quick check history
+pub(crate) async fn append_quick_check_result(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+    new_result: Value,
+) -> Result<(), String> {
+    let learner_dir = get_learner_dir(app_handle, learner_id)?;
     let checks_path = learner_dir.join(QUICK_CHECKS_FILE);
 
     // Create directory if it doesn't exist
@@ -319,10 +427,6 @@ pub async fn save_quick_check_result(
         .await
         .map_err(|e| format!("Failed to create learner directory: {}", e))?;
 
-    // Parse the incoming result
-    let new_result: Value =
-        serde_json::from_str(&result).map_err(|e| format!("Invalid result JSON: {}", e))?;
-
     // Read existing history
     let mut history: Vec<Value> = if checks_path.exists() {
         let content = fs::read_to_string(&checks_path)
@@ -334,7 +438,7 @@ pub async fn save_quick_check_result(
     };
 
     // Add new result
-    history.push(new_result);
+    history.push(new_result.clone());
 
     // Write history back
     let content = serde_json::to_string_pretty(&history)
@@ -343,5 +447,9 @@ pub async fn save_quick_check_result(
         .await
         .map_err(|e| format!("Failed to write quick check history: {}", e))?;
 
+    storage_events::emit(app_handle, StorageEvent::LearnerUpdated(learner_id));
+    project_activity::record_quick_check(app_handle, learner_id, &new_result).await;
+    automation::quick_check_graded(app_handle, learner_id, &new_result);
+
     Ok(())
 }
@@ -0,0 +1,379 @@
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tokio::fs;
+
+use crate::archive;
+use crate::commands::{
+    audit_log, design_pack_storage, index_cache, learner_storage, library_storage,
+    project_storage,
+};
+
+/// Event emitted as legacy data is imported
+const PROGRESS_EVENT: &str = "legacy-migration-progress";
+
+// App data folders used by older builds, looked up next to the current one
+const LEGACY_APP_DIR_NAMES: &[&str] = &["TA Teachers Assistant", "ta-teachers-assistant"];
+
+// Written into a legacy folder once its data has been imported
+const MIGRATED_MARKER: &str = ".ta-migrated";
+
+// Pre-Issue-#20 builds kept flat files in the app data root
+const FLAT_LIBRARY_INDEX: &str = "library-index.json";
+const FLAT_PROJECTS: &str = "local-projects.json";
+const FLAT_ARTIFACT_PREFIX: &str = "artifact-";
+// Flat files are renamed with this suffix once imported
+const MIGRATED_SUFFIX: &str = ".migrated";
+
+/// Where legacy data was found
+enum LegacySource {
+    // Flat pre-Issue-#20 files in the current app data root
+    Flat(PathBuf),
+    // A whole app data folder from an older build
+    AppDir(PathBuf),
+}
+
+/// Managed state with the outcome of the startup migration
+pub struct LegacyMigrationState {
+    status: Mutex<Value>,
+}
+
+impl Default for LegacyMigrationState {
+    fn default() -> Self {
+        Self {
+            status: Mutex::new(serde_json::json!({ "state": "idle" })),
+        }
+    }
+}
+
+// Helper to update the stored status and emit it as a progress event
+fn report(app_handle: &tauri::AppHandle, status: Value) {
+    if let Err(e) = app_handle.emit(PROGRESS_EVENT, &status) {
+        tracing::warn!(error = %e, "Failed to emit migration progress");
+    }
+    *app_handle
+        .state::<LegacyMigrationState>()
+        .status
+        .lock()
+        .unwrap() = status;
+}
+
+// Helper to read a JSON array file (missing or invalid means empty)
+async fn read_array(path: &Path) -> Vec<Value> {
+    match fs::read_to_string(path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()),
+        Err(_) => Vec::new(),
+    }
+}
+
+// Helper to add legacy entries whose ID isn't already present. Returns the
+// number added.
+async fn merge_entries(
+    current_path: &Path,
+    legacy_entries: Vec<Value>,
+    id_key: &str,
+) -> Result<usize, String> {
+    if legacy_entries.is_empty() {
+        return Ok(0);
+    }
+
+    let mut entries = read_array(current_path).await;
+    let existing: HashSet<String> = entries
+        .iter()
+        .filter_map(|e| e.get(id_key).and_then(|v| v.as_str()).map(String::from))
+        .collect();
+
+    let before = entries.len();
+    entries.extend(legacy_entries.into_iter().filter(|e| {
+        e.get(id_key)
+            .and_then(|v| v.as_str())
+            .is_some_and(|id| !existing.contains(id))
+    }));
+    let added = entries.len() - before;
+
+    if added > 0 {
+        if let Some(parent) = current_path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(&entries)
+            .map_err(|e| format!("Failed to serialize entries: {}", e))?;
+        fs::write(current_path, content)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", current_path.display(), e))?;
+    }
+    Ok(added)
+}
+
+// Helper to list legacy artifact files for a source
+async fn legacy_artifact_files(source: &LegacySource) -> Vec<PathBuf> {
+    let (dir, prefix) = match source {
+        LegacySource::Flat(root) => (root.clone(), FLAT_ARTIFACT_PREFIX),
+        LegacySource::AppDir(root) => (root.join("library").join("artifacts"), ""),
+    };
+
+    let mut files = Vec::new();
+    let Ok(mut entries) = fs::read_dir(&dir).await else {
+        return files;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with(prefix) && name.ends_with(".json") {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    files
+}
+
+// Helper to find every legacy source that hasn't been imported yet
+fn find_sources(app_data_dir: &Path) -> Vec<LegacySource> {
+    let mut sources = Vec::new();
+
+    let has_flat_files = app_data_dir.join(FLAT_LIBRARY_INDEX).exists()
+        || app_data_dir.join(FLAT_PROJECTS).exists()
+        || std::fs::read_dir(app_data_dir)
+            .map(|dir| {
+                dir.filter_map(|e| e.ok()).any(|e| {
+                    let name = e.file_name().to_string_lossy().to_string();
+                    name.starts_with(FLAT_ARTIFACT_PREFIX) && name.ends_with(".json")
+                })
+            })
+            .unwrap_or(false);
+    if has_flat_files {
+        sources.push(LegacySource::Flat(app_data_dir.to_path_buf()));
+    }
+
+    if let Some(parent) = app_data_dir.parent() {
+        for name in LEGACY_APP_DIR_NAMES {
+            let dir = parent.join(name);
+            if dir != app_data_dir && dir.is_dir() && !dir.join(MIGRATED_MARKER).exists() {
+                sources.push(LegacySource::AppDir(dir));
+            }
+        }
+    }
+
+    sources
+}
+
+// Helper to import one legacy source. Returns counts imported.
+async fn import_source(
+    app_handle: &tauri::AppHandle,
+    source: &LegacySource,
+    progress: &mut (usize, usize),
+) -> Result<Value, String> {
+    let source_root = match source {
+        LegacySource::Flat(root) | LegacySource::AppDir(root) => root.clone(),
+    };
+    let existing_artifacts = library_storage::get_artifacts_dir(app_handle)?;
+
+    // Artifacts (written through the library so the index stays in sync)
+    let mut artifacts = 0;
+    for path in legacy_artifact_files(source).await {
+        progress.0 += 1;
+        report(
+            app_handle,
+            serde_json::json!({
+                "state": "running",
+                "stage": "artifacts",
+                "source": source_root.display().to_string(),
+                "current": progress.0,
+                "total": progress.1,
+            }),
+        );
+
+        let Ok(content) = fs::read_to_string(&path).await else {
+            continue;
+        };
+        let Ok(artifact) = serde_json::from_str::<Value>(&content) else {
+            tracing::warn!(path = %path.display(), "Skipping unreadable legacy artifact");
+            continue;
+        };
+        let Some(artifact_id) = artifact.get("artifactId").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if existing_artifacts.join(format!("{}.json", artifact_id)).exists() {
+            continue;
+        }
+        library_storage::write_artifact(app_handle, &artifact, &content).await?;
+        artifacts += 1;
+    }
+
+    // Projects (older app dirs may keep the full project in its own file)
+    let legacy_projects_path = match source {
+        LegacySource::Flat(root) => root.join(FLAT_PROJECTS),
+        LegacySource::AppDir(root) => root.join("projects").join("projects.json"),
+    };
+    let existing_projects: HashSet<String> = project_storage::read_index(app_handle)
+        .await?
+        .iter()
+        .filter_map(|p| p.get("projectId").and_then(|v| v.as_str()).map(String::from))
+        .collect();
+    let mut projects = 0;
+    for mut project in read_array(&legacy_projects_path).await {
+        let Some(project_id) = project.get("projectId").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if existing_projects.contains(project_id)
+            || !project_storage::is_valid_project_id(project_id)
+        {
+            continue;
+        }
+        let project_file = legacy_projects_path.with_file_name(format!("{}.json", project_id));
+        if let Ok(content) = fs::read_to_string(&project_file).await {
+            if let Ok(full) = serde_json::from_str::<Value>(&content) {
+                project = full;
+            }
+        }
+        project_storage::write_project(app_handle, &project).await?;
+        projects += 1;
+    }
+
+    // Design packs and learners only exist as folders in older app dirs
+    let mut design_packs = 0;
+    let mut learners = 0;
+    if let LegacySource::AppDir(root) = source {
+        index_cache::flush(app_handle).await?;
+        let packs_path = design_pack_storage::get_index_path(app_handle)?;
+        design_packs = merge_entries(
+            &packs_path,
+            read_array(&root.join("design-packs").join("packs.json")).await,
+            "packId",
+        )
+        .await?;
+        index_cache::invalidate(app_handle, &packs_path);
+
+        let legacy_learners_dir = root.join("learners");
+        let legacy_profiles = read_array(&legacy_learners_dir.join("profiles.json")).await;
+        let profiles_path = learner_storage::get_profiles_path(app_handle)?;
+        let existing: HashSet<String> = read_array(&profiles_path)
+            .await
+            .iter()
+            .filter_map(|p| p.get("learnerId").and_then(|v| v.as_str()).map(String::from))
+            .collect();
+        // Copy each new learner's data folder before adding the profile
+        for profile in &legacy_profiles {
+            let Some(learner_id) = profile.get("learnerId").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            if existing.contains(learner_id) {
+                continue;
+            }
+            let legacy_dir = legacy_learners_dir.join(learner_id);
+            if legacy_dir.is_dir() {
+                let learner_dir = learner_storage::get_learner_dir(app_handle, learner_id)?;
+                fs::create_dir_all(&learner_dir)
+                    .await
+                    .map_err(|e| format!("Failed to create learner directory: {}", e))?;
+                for (name, contents) in archive::collect_dir(&legacy_dir, "", &[]).await? {
+                    let target = learner_dir.join(&name);
+                    if let Some(parent) = target.parent() {
+                        fs::create_dir_all(parent)
+                            .await
+                            .map_err(|e| format!("Failed to create directory: {}", e))?;
+                    }
+                    fs::write(&target, contents)
+                        .await
+                        .map_err(|e| format!("Failed to write learner data: {}", e))?;
+                }
+            }
+        }
+        learners = merge_entries(&profiles_path, legacy_profiles, "learnerId").await?;
+    }
+
+    // Mark the source as imported without deleting anything
+    match source {
+        LegacySource::Flat(root) => {
+            let mut consumed = legacy_artifact_files(source).await;
+            consumed.push(root.join(FLAT_LIBRARY_INDEX));
+            consumed.push(root.join(FLAT_PROJECTS));
+            for path in consumed.into_iter().filter(|p| p.exists()) {
+                let mut renamed = path.clone().into_os_string();
+                renamed.push(MIGRATED_SUFFIX);
+                fs::rename(&path, &renamed)
+                    .await
+                    .map_err(|e| format!("Failed to mark {} as migrated: {}", path.display(), e))?;
+            }
+        }
+        LegacySource::AppDir(root) => {
+            fs::write(root.join(MIGRATED_MARKER), chrono::Utc::now().to_rfc3339())
+                .await
+                .map_err(|e| format!("Failed to mark {} as migrated: {}", root.display(), e))?;
+        }
+    }
+
+    Ok(serde_json::json!({
+        "source": source_root.display().to_string(),
+        "artifacts": artifacts,
+        "projects": projects,
+        "designPacks": design_packs,
+        "learners": learners,
+    }))
+}
+
+// Helper to import every legacy source found
+async fn migrate(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let sources = find_sources(&app_data_dir);
+    if sources.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut total = 0;
+    for source in &sources {
+        total += legacy_artifact_files(source).await.len();
+    }
+    let mut progress = (0, total);
+
+    let mut imported = Vec::new();
+    for source in &sources {
+        imported.push(import_source(app_handle, source, &mut progress).await?);
+    }
+    Ok(imported)
+}
+
+/// Look for data left by older builds and import it in the background.
+/// Progress is emitted as `legacy-migration-progress` events and kept for
+/// `get_legacy_migration_status`. Called once during app setup.
+pub fn start(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        match migrate(&app_handle).await {
+            Ok(imported) if imported.is_empty() => {
+                report(&app_handle, serde_json::json!({ "state": "idle" }));
+            }
+            Ok(imported) => {
+                tracing::info!(sources = imported.len(), "Imported legacy data");
+                audit_log::record(&app_handle, "legacy_migration", "app_data", &[]).await;
+                report(
+                    &app_handle,
+                    serde_json::json!({ "state": "completed", "imported": imported }),
+                );
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Legacy data migration failed");
+                report(&app_handle, serde_json::json!({ "state": "failed", "error": e }));
+            }
+        }
+    });
+}
+
+// ============================================
+// Legacy Migration Commands
+// ============================================
+
+/// Get the status of the startup legacy data import (the latest progress
+/// event), for a UI that started listening after it began
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_legacy_migration_status(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let state = app_handle.state::<LegacyMigrationState>();
+    let status = state.status.lock().unwrap().clone();
+    Ok(status.to_string())
+}
@@ -0,0 +1,144 @@
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::commands::library_storage;
+
+/// Artifact type of a lesson plan
+pub(crate) const LESSON_PLAN_TYPE: &str = "lesson_plan";
+
+// What a linked artifact is for in the lesson
+const LINK_ROLES: &[&str] = &["warm_up", "worksheet", "quick_check", "activity", "exit_ticket"];
+
+// Helper to check whether an artifact (or index entry) is a lesson plan
+fn is_lesson_plan(artifact: &Value) -> bool {
+    artifact.get("type").and_then(|v| v.as_str()) == Some(LESSON_PLAN_TYPE)
+}
+
+/// The IDs of the artifacts a lesson plan links to, in order; empty for
+/// other artifacts
+pub(crate) fn linked_artifact_ids(artifact: &Value) -> Vec<String> {
+    if !is_lesson_plan(artifact) {
+        return Vec::new();
+    }
+    artifact
+        .get("linkedArtifacts")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|link| link.get("artifactId").and_then(|v| v.as_str()).map(String::from))
+        .collect()
+}
+
+/// Check a lesson plan's links and materials before it is saved. Each entry
+/// of `linkedArtifacts` needs a known `role` and the `artifactId` of another
+/// artifact in the library that isn't itself a lesson plan; `materials` is
+/// a list of names or of objects with a `name`. Other artifacts pass as-is.
+pub(crate) async fn validate(
+    app_handle: &tauri::AppHandle,
+    artifact: &Value,
+) -> Result<(), String> {
+    if !is_lesson_plan(artifact) {
+        return Ok(());
+    }
+    let plan_id = artifact.get("artifactId").and_then(|v| v.as_str());
+
+    let links = match artifact.get("linkedArtifacts") {
+        None | Some(Value::Null) => &Vec::new(),
+        Some(Value::Array(links)) => links,
+        Some(_) => return Err("linkedArtifacts must be a list".to_string()),
+    };
+    for link in links {
+        let role = link.get("role").and_then(|v| v.as_str()).unwrap_or("");
+        if !LINK_ROLES.contains(&role) {
+            return Err(format!("Invalid lesson plan link role: {}", role));
+        }
+        let linked_id = link
+            .get("artifactId")
+            .and_then(|v| v.as_str())
+            .ok_or("Each lesson plan link must have an artifactId")?;
+        if Some(linked_id) == plan_id {
+            return Err("A lesson plan can't link to itself".to_string());
+        }
+        let linked = library_storage::read_artifact(app_handle, linked_id)
+            .await
+            .map_err(|_| format!("Linked artifact not found: {}", linked_id))?;
+        if is_lesson_plan(&linked) {
+            return Err(format!("A lesson plan can't link to another lesson plan: {}", linked_id));
+        }
+    }
+
+    match artifact.get("materials") {
+        None | Some(Value::Null) => {}
+        Some(Value::Array(materials)) => {
+            let named = |m: &Value| match m {
+                Value::String(name) => !name.trim().is_empty(),
+                other => other
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|name| !name.trim().is_empty()),
+            };
+            if !materials.iter().all(named) {
+                return Err("Each material must have a name".to_string());
+            }
+        }
+        Some(_) => return Err("materials must be a list".to_string()),
+    }
+    Ok(())
+}
+
+/// Add the artifacts linked from any lesson plans among `artifact_ids`, so
+/// exports carry a plan's warm-ups, worksheets, and quick checks with it
+pub(crate) async fn with_linked_artifacts(
+    app_handle: &tauri::AppHandle,
+    mut artifact_ids: BTreeSet<String>,
+) -> Result<BTreeSet<String>, String> {
+    // The index records each artifact's type, so only plans are read in full
+    let plan_ids: Vec<String> = library_storage::read_index_entries(app_handle)
+        .await?
+        .iter()
+        .filter(|entry| is_lesson_plan(entry))
+        .filter_map(|entry| entry.get("artifactId").and_then(|v| v.as_str()))
+        .filter(|id| artifact_ids.contains(*id))
+        .map(String::from)
+        .collect();
+    for plan in library_storage::read_artifacts(app_handle, plan_ids).await {
+        artifact_ids.extend(linked_artifact_ids(&plan));
+    }
+    Ok(artifact_ids)
+}
+
+// ============================================
+// Lesson Plan Commands
+// ============================================
+
+/// Get a lesson plan with its links resolved: each entry of
+/// `linkedArtifacts` gains the full `artifact`, or `missing: true` if it
+/// has since been deleted
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_lesson_plan(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+) -> Result<String, String> {
+    let mut plan = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    if !is_lesson_plan(&plan) {
+        return Err(format!("Not a lesson plan: {}", artifact_id));
+    }
+
+    let linked = library_storage::read_artifacts(&app_handle, linked_artifact_ids(&plan)).await;
+    let links = plan.get_mut("linkedArtifacts").and_then(|v| v.as_array_mut());
+    let with_ids = links
+        .into_iter()
+        .flatten()
+        .filter(|link| link.get("artifactId").and_then(|v| v.as_str()).is_some());
+    for (link, artifact) in with_ids.zip(linked) {
+        if let Some(obj) = link.as_object_mut() {
+            if artifact.is_null() {
+                obj.insert("missing".to_string(), Value::Bool(true));
+            } else {
+                obj.insert("artifact".to_string(), artifact);
+            }
+        }
+    }
+    Ok(plan.to_string())
+}
@@ -0,0 +1,227 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, index_cache, library_storage, session_mode};
+
+// Unparseable or mismatched artifact files are moved here by auto-fix
+const QUARANTINE_DIR: &str = "quarantine";
+
+/// Problems found by `verify_library_integrity`, grouped by category
+#[derive(Default)]
+struct IntegrityReport {
+    index_unreadable: bool,
+    // Index entries with no artifact file
+    missing_files: Vec<String>,
+    // Artifact files with no index entry
+    orphaned_files: Vec<String>,
+    // Artifact files that aren't valid JSON
+    unparseable_files: Vec<String>,
+    // Artifact files whose artifactId doesn't match the file name
+    id_mismatches: Vec<Value>,
+    // artifactIds listed more than once in the index
+    duplicate_entries: Vec<String>,
+}
+
+impl IntegrityReport {
+    fn issue_count(&self) -> usize {
+        usize::from(self.index_unreadable)
+            + self.missing_files.len()
+            + self.orphaned_files.len()
+            + self.unparseable_files.len()
+            + self.id_mismatches.len()
+            + self.duplicate_entries.len()
+    }
+}
+
+// Helper to move a broken artifact file aside rather than deleting it
+async fn quarantine(path: &Path) -> Result<(), String> {
+    let library_dir = path
+        .parent()
+        .and_then(|artifacts_dir| artifacts_dir.parent())
+        .ok_or("Invalid artifact path")?;
+    let quarantine_dir = library_dir.join(QUARANTINE_DIR);
+    fs::create_dir_all(&quarantine_dir)
+        .await
+        .map_err(|e| format!("Failed to create quarantine directory: {}", e))?;
+
+    let file_name = path.file_name().ok_or("Invalid artifact path")?;
+    fs::rename(path, quarantine_dir.join(file_name))
+        .await
+        .map_err(|e| format!("Failed to quarantine {}: {}", path.display(), e))
+}
+
+// ============================================
+// Library Integrity Commands
+// ============================================
+
+/// Cross-check the library index against artifact files and return a
+/// categorized report. With `auto_fix`, the index is rebuilt to match the
+/// valid files: missing entries are dropped, orphaned files are re-indexed,
+/// duplicates collapse to the last entry, and broken files are moved to
+/// `library/quarantine/`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn verify_library_integrity(
+    app_handle: tauri::AppHandle,
+    auto_fix: bool,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    if auto_fix {
+        session_mode::ensure_teacher_mode(&app_handle)?;
+    }
+
+    // Check what's on disk, including index changes not yet written
+    index_cache::flush(&app_handle).await?;
+    let index_path = library_storage::get_index_path(&app_handle)?;
+    let artifacts_dir = library_storage::get_artifacts_dir(&app_handle)?;
+    let mut report = IntegrityReport::default();
+
+    // Read the index
+    let mut index: Value = serde_json::json!({
+        "version": 1,
+        "lastUpdated": chrono::Utc::now().to_rfc3339(),
+        "artifacts": []
+    });
+    if index_path.exists() {
+        let content = fs::read_to_string(&index_path)
+            .await
+            .map_err(|e| format!("Failed to read library index: {}", e))?;
+        match serde_json::from_str::<Value>(&content) {
+            Ok(parsed) if parsed.get("artifacts").is_some_and(|a| a.is_array()) => {
+                index = parsed
+            }
+            _ => report.index_unreadable = true,
+        }
+    }
+    let entries: Vec<Value> = index
+        .get("artifacts")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    // Index entries keyed by ID (last one wins), noting duplicates
+    let mut indexed: BTreeMap<String, Value> = BTreeMap::new();
+    for entry in entries {
+        let Some(artifact_id) = entry.get("artifactId").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let artifact_id = artifact_id.to_string();
+        if indexed.insert(artifact_id.clone(), entry).is_some()
+            && !report.duplicate_entries.contains(&artifact_id)
+        {
+            report.duplicate_entries.push(artifact_id);
+        }
+    }
+
+    // Artifact files keyed by file stem, keeping the parsed artifact when valid
+    let mut valid_files: BTreeMap<String, Value> = BTreeMap::new();
+    let mut broken_paths: Vec<PathBuf> = Vec::new();
+    let mut seen_stems: HashSet<String> = HashSet::new();
+    if artifacts_dir.exists() {
+        let mut dir = fs::read_dir(&artifacts_dir)
+            .await
+            .map_err(|e| format!("Failed to read artifacts directory: {}", e))?;
+        while let Some(entry) = dir
+            .next_entry()
+            .await
+            .map_err(|e| format!("Failed to read artifacts directory: {}", e))?
+        {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            seen_stems.insert(stem.clone());
+
+            let parsed = fs::read_to_string(&path)
+                .await
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+            let Some(artifact) = parsed else {
+                report.unparseable_files.push(stem);
+                broken_paths.push(path);
+                continue;
+            };
+
+            let artifact_id = artifact.get("artifactId").and_then(|v| v.as_str());
+            if artifact_id != Some(stem.as_str()) {
+                report.id_mismatches.push(serde_json::json!({
+                    "fileName": stem,
+                    "artifactId": artifact_id,
+                }));
+                broken_paths.push(path);
+                continue;
+            }
+
+            if !indexed.contains_key(&stem) {
+                report.orphaned_files.push(stem.clone());
+            }
+            valid_files.insert(stem, artifact);
+        }
+    }
+
+    for artifact_id in indexed.keys() {
+        if !seen_stems.contains(artifact_id) {
+            report.missing_files.push(artifact_id.clone());
+        }
+    }
+
+    let issues = report.issue_count();
+    let mut fixed = false;
+
+    if auto_fix && issues > 0 {
+        for path in &broken_paths {
+            quarantine(path).await?;
+        }
+
+        // Keep the existing entry for indexed files, re-index orphans
+        let rebuilt: Vec<Value> = valid_files
+            .iter()
+            .map(|(artifact_id, artifact)| {
+                indexed
+                    .get(artifact_id)
+                    .cloned()
+                    .unwrap_or_else(|| library_storage::index_entry_for(artifact))
+            })
+            .collect();
+        if let Some(obj) = index.as_object_mut() {
+            obj.insert("artifacts".to_string(), Value::Array(rebuilt));
+            obj.insert(
+                "lastUpdated".to_string(),
+                Value::String(chrono::Utc::now().to_rfc3339()),
+            );
+        }
+
+        if let Some(parent) = index_path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create library directory: {}", e))?;
+        }
+        let index_content = serde_json::to_string_pretty(&index)
+            .map_err(|e| format!("Failed to serialize index: {}", e))?;
+        fs::write(&index_path, index_content)
+            .await
+            .map_err(|e| format!("Failed to write library index: {}", e))?;
+        index_cache::invalidate(&app_handle, &index_path);
+        fixed = true;
+
+        audit_log::record(&app_handle, "verify_library_integrity", "library", &[]).await;
+    }
+
+    let response = serde_json::json!({
+        "healthy": issues == 0,
+        "issueCount": issues,
+        "fixed": fixed,
+        "indexUnreadable": report.index_unreadable,
+        "missingFiles": report.missing_files,
+        "orphanedFiles": report.orphaned_files,
+        "unparseableFiles": report.unparseable_files,
+        "idMismatches": report.id_mismatches,
+        "duplicateEntries": report.duplicate_entries,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,243 @@
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::Path;
+use tokio::fs;
+
+use crate::commands::worksheet_assembly::escape_html;
+use crate::commands::{
+    app_lock, audit_log, disk_space, library_storage, parent_portal, session_mode, share_export,
+    task_manager,
+};
+
+// Folder in the site holding one page per artifact
+const PAGES_DIR: &str = "artifacts";
+
+// Artifacts read at a time, between progress updates
+const CHUNK_SIZE: usize = 25;
+
+// Link added to the top of each artifact page, hidden when printing
+const BACK_LINK: &str = "<style>@media print { .library-back { display: none; } }</style>\n\
+     <p class=\"library-back\" style=\"font-family: Arial, Helvetica, sans-serif;\">\
+     <a href=\"../index.html\">&larr; Library</a></p>\n";
+
+// Filters the index page offers, as (artifact field, label)
+const FILTERS: [(&str, &str); 3] = [("subject", "Subject"), ("grade", "Grade"), ("type", "Type")];
+
+// Script filtering the index rows as the search box and filters change;
+// the rows carry what they're matched on as data attributes
+const FILTER_SCRIPT: &str = r#"<script>
+(function () {
+  var search = document.getElementById("search");
+  var filters = Array.prototype.slice.call(document.querySelectorAll("select[data-filter]"));
+  var rows = Array.prototype.slice.call(document.querySelectorAll("tr[data-title]"));
+  var count = document.getElementById("count");
+  function update() {
+    var words = search.value.toLowerCase().split(/\s+/).filter(Boolean);
+    var shown = 0;
+    rows.forEach(function (row) {
+      var match = words.every(function (word) {
+        return row.getAttribute("data-title").indexOf(word) !== -1;
+      }) && filters.every(function (filter) {
+        return !filter.value || row.getAttribute("data-" + filter.dataset.filter) === filter.value;
+      });
+      row.hidden = !match;
+      if (match) shown++;
+    });
+    count.textContent = shown + " of " + rows.length;
+  }
+  search.addEventListener("input", update);
+  filters.forEach(function (filter) { filter.addEventListener("change", update); });
+  update();
+})();
+</script>
+"#;
+
+// Helper to read a string field, or "" when missing
+fn text<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+// Helper to add the link back to the index just inside an artifact's
+// `<body>`, or at the top when it has none
+fn with_back_link(html: &str) -> String {
+    let body_start = html
+        .to_ascii_lowercase()
+        .find("<body")
+        .and_then(|start| html[start..].find('>').map(|end| start + end + 1));
+    match body_start {
+        Some(at) => format!("{}\n{}{}", &html[..at], BACK_LINK, &html[at..]),
+        None => format!("{}{}", BACK_LINK, html),
+    }
+}
+
+// Helper to render a filter's drop-down from the values the artifacts use
+fn filter_select(field: &str, label: &str, values: &BTreeSet<String>) -> String {
+    let mut html = format!(
+        "<label>{label} <select data-filter=\"{field}\"><option value=\"\">All</option>",
+        label = label,
+        field = field,
+    );
+    for value in values {
+        html.push_str(&format!(
+            "<option value=\"{}\">{}</option>",
+            escape_html(value),
+            escape_html(value)
+        ));
+    }
+    html.push_str("</select></label>\n");
+    html
+}
+
+// Helper to render the index page: a search box, filters, and a row per
+// artifact. The styles and script are inline so the site works from any
+// folder without a server.
+fn render_index(rows: &[(Value, String)], generated: &str) -> String {
+    let mut filters = String::new();
+    for (field, label) in FILTERS {
+        let values: BTreeSet<String> = rows
+            .iter()
+            .map(|(artifact, _)| text(artifact, field).to_string())
+            .filter(|value| !value.is_empty())
+            .collect();
+        filters.push_str(&filter_select(field, label, &values));
+    }
+
+    let mut table = String::from(
+        "<table>\n<tr><th>Title</th><th>Type</th><th>Grade</th><th>Subject</th>\
+         <th>Created</th></tr>\n",
+    );
+    for (artifact, file) in rows {
+        let title = Some(text(artifact, "title")).filter(|t| !t.is_empty());
+        let title = title.unwrap_or("Untitled");
+        let href = format!("{}/{}", PAGES_DIR, utf8_percent_encode(file, NON_ALPHANUMERIC));
+        table.push_str(&format!(
+            "<tr data-title=\"{}\" data-subject=\"{}\" data-grade=\"{}\" data-type=\"{}\">\
+             <td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&title.to_lowercase()),
+            escape_html(text(artifact, "subject")),
+            escape_html(text(artifact, "grade")),
+            escape_html(text(artifact, "type")),
+            escape_html(&href),
+            escape_html(title),
+            escape_html(text(artifact, "type")),
+            escape_html(text(artifact, "grade")),
+            escape_html(text(artifact, "subject")),
+            escape_html(&parent_portal::display_date(text(artifact, "createdAt"))),
+        ));
+    }
+    table.push_str("</table>\n");
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>Library</title>\n<style>\n\
+         body {{ font-family: Arial, Helvetica, sans-serif; color: #1F2937; \
+         max-width: 64em; margin: 2em auto; padding: 0 1em; }}\n\
+         .filters {{ display: flex; flex-wrap: wrap; gap: 1em; align-items: center; \
+         margin-bottom: 1em; }}\n\
+         #search {{ flex: 1; min-width: 12em; padding: 0.4em; }}\n\
+         #count {{ color: #6B7280; }}\n\
+         table {{ border-collapse: collapse; width: 100%; }}\n\
+         th, td {{ text-align: left; padding: 0.4em; border-bottom: 1px solid #E5E7EB; }}\n\
+         footer {{ margin-top: 3em; color: #9CA3AF; font-size: 0.85em; }}\n\
+         </style>\n</head>\n<body>\n<h1>Library</h1>\n<div class=\"filters\">\n\
+         <input id=\"search\" type=\"search\" placeholder=\"Search titles\">\n\
+         {filters}<span id=\"count\"></span>\n</div>\n{table}\
+         <footer>Updated {generated}</footer>\n{script}</body>\n</html>\n",
+        filters = filters,
+        table = table,
+        generated = generated,
+        script = FILTER_SCRIPT,
+    )
+}
+
+// ============================================
+// Library Site Commands
+// ============================================
+
+/// Export the whole library as a static website in `out_dir`: an
+/// `index.html` listing every artifact with a search box and subject,
+/// grade, and type filters, plus a page per artifact under `artifacts/`.
+/// The pages need no server, so the site can be copied to a USB stick or
+/// a school intranet. Runs as a cancellable "export" task (ID
+/// `operation_id` when given). Returns the path of `index.html`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn export_library_site(
+    app_handle: tauri::AppHandle,
+    out_dir: String,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let out_dir = Path::new(&out_dir);
+    if !out_dir.is_absolute() {
+        return Err(format!("Output folder must be an absolute path: {}", out_dir.display()));
+    }
+    let pages_dir = out_dir.join(PAGES_DIR);
+
+    let artifact_ids: Vec<String> = library_storage::read_index_entries(&app_handle)
+        .await?
+        .iter()
+        .map(|entry| text(entry, "artifactId").to_string())
+        .filter(|artifact_id| !artifact_id.is_empty())
+        .collect();
+
+    let task = task_manager::start_task(&app_handle, operation_id, "export", "Library site")?;
+    let total = Some(artifact_ids.len() as u64);
+    let result: Result<usize, String> = async {
+        fs::create_dir_all(&pages_dir)
+            .await
+            .map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+        let mut rows = Vec::new();
+        for (i, chunk) in artifact_ids.chunks(CHUNK_SIZE).enumerate() {
+            task.check_cancelled()?;
+            task.progress((i * CHUNK_SIZE) as u64, total, Some("Writing artifact pages"));
+
+            let artifacts = library_storage::read_artifacts(&app_handle, chunk.to_vec()).await;
+            let pages: Vec<(Value, String, String)> = artifacts
+                .into_iter()
+                .filter(|artifact| !text(artifact, "htmlContent").is_empty())
+                .map(|artifact| {
+                    // The ID keeps artifacts with the same title apart
+                    let short_id: String = text(&artifact, "artifactId").chars().take(8).collect();
+                    let title = share_export::file_name(text(&artifact, "title"), "Untitled");
+                    let file = format!("{} ({}).html", title, short_id);
+                    let html = with_back_link(text(&artifact, "htmlContent"));
+                    (artifact, file, html)
+                })
+                .collect();
+
+            let needed: usize = pages.iter().map(|(_, _, html)| html.len()).sum();
+            disk_space::ensure_available(&pages_dir, needed as u64)?;
+            for (mut artifact, file, html) in pages {
+                fs::write(pages_dir.join(&file), html)
+                    .await
+                    .map_err(|e| format!("Failed to write {}: {}", file, e))?;
+                // The index only needs the listing fields
+                if let Some(obj) = artifact.as_object_mut() {
+                    obj.remove("htmlContent");
+                }
+                rows.push((artifact, file));
+            }
+        }
+
+        task.check_cancelled()?;
+        rows.sort_by(|(a, _), (b, _)| text(b, "createdAt").cmp(text(a, "createdAt")));
+        let generated = chrono::Local::now().format("%b %-d, %Y %H:%M").to_string();
+        fs::write(out_dir.join("index.html"), render_index(&rows, &generated))
+            .await
+            .map_err(|e| format!("Failed to write index.html: {}", e))?;
+        Ok(rows.len())
+    }
+    .await;
+    task.finish(&result);
+    result?;
+
+    audit_log::record(&app_handle, "export_library_site", "library", &[]).await;
+
+    Ok(out_dir.join("index.html").to_string_lossy().to_string())
+}
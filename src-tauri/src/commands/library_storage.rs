@@ -1,13 +1,65 @@
+use futures_util::stream::{self, StreamExt};
 use serde_json::Value;
 use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
 use tokio::fs;
 
+use crate::commands::storage_events::{self, StorageEvent};
+use crate::commands::{
+    app_lock, audit_log, automation, collections, index_cache, lesson_plans, project_storage,
+    session_mode,
+};
+use crate::json_stream;
+
 const LIBRARY_DIR: &str = "library";
 const INDEX_FILE: &str = "index.json";
 const ARTIFACTS_DIR: &str = "artifacts";
 
+/// Event carrying one chunk of an artifact sent by `get_artifact_stream`
+pub(crate) const ARTIFACT_CHUNK_EVENT: &str = "artifact://chunk";
+
+// Artifacts larger than this are better fetched with `get_artifact_stream`
+const STREAM_THRESHOLD: u64 = 2 * 1024 * 1024;
+
+// Size of each streamed chunk, in bytes
+const STREAM_CHUNK_SIZE: usize = 256 * 1024;
+
+// Artifact files `get_artifacts` reads at once
+const MAX_CONCURRENT_READS: usize = 8;
+
+// Fields maintained by the backend (favorites, ratings, usage counters).
+// Copied into the index and kept when a save from the frontend omits them.
+const USAGE_KEYS: &[&str] = &[
+    "favorite",
+    "rating",
+    "openCount",
+    "printCount",
+    "lastOpenedAt",
+    "lastPrintedAt",
+];
+
+/// Check an ID is safe to use as a file name: 1 to 128 ASCII letters,
+/// digits, `-` or `_`
+pub(crate) fn is_safe_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+}
+
+// Helper to get an artifact's file path, refusing IDs that aren't safe as
+// file names (such as ones with `..` or path separators)
+fn get_artifact_path(app_handle: &tauri::AppHandle, artifact_id: &str) -> Result<PathBuf, String> {
+    if !is_safe_id(artifact_id) {
+        return Err(format!("Invalid artifact ID: {}", artifact_id));
+    }
+    Ok(get_artifacts_dir(app_handle)?.join(format!("{}.json", artifact_id)))
+}
+
 // Helper to get the library directory
-fn get_library_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn get_library_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     let app_data_dir = app_handle
         .path()
         .app_data_dir()
@@ -16,12 +68,12 @@ fn get_library_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
 }
 
 // Helper to get the index file path
-fn get_index_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn get_index_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(get_library_dir(app_handle)?.join(INDEX_FILE))
 }
 
 // Helper to get the artifacts directory
-fn get_artifacts_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn get_artifacts_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(get_library_dir(app_handle)?.join(ARTIFACTS_DIR))
 }
 
@@ -31,46 +83,30 @@ fn get_artifacts_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
 
 /// Get the library index (list of all artifacts)
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn get_library_index(app_handle: tauri::AppHandle) -> Result<String, String> {
-    let index_path = get_index_path(&app_handle)?;
-
-    // If file doesn't exist, return default structure
-    if !index_path.exists() {
-        let default = serde_json::json!({
-            "version": 1,
-            "lastUpdated": chrono::Utc::now().to_rfc3339(),
-            "artifacts": []
-        });
-        return Ok(default.to_string());
-    }
-
-    fs::read_to_string(&index_path)
-        .await
-        .map_err(|e| format!("Failed to read library index: {}", e))
+    // A missing index comes back as an empty one
+    Ok(read_index(&app_handle).await?.to_string())
 }
 
 /// Save the library index
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn save_library_index(
     app_handle: tauri::AppHandle,
     index: String,
 ) -> Result<(), String> {
-    let library_dir = get_library_dir(&app_handle)?;
-    let index_path = get_index_path(&app_handle)?;
-
-    // Create directory if it doesn't exist
-    fs::create_dir_all(&library_dir)
-        .await
-        .map_err(|e| format!("Failed to create library directory: {}", e))?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
 
     // Validate JSON
-    let _: Value =
+    let index: Value =
         serde_json::from_str(&index).map_err(|e| format!("Invalid index JSON: {}", e))?;
 
     // Write index
-    fs::write(&index_path, &index)
-        .await
-        .map_err(|e| format!("Failed to write library index: {}", e))?;
+    let index_path = get_index_path(&app_handle)?;
+    index_cache::write_index(&app_handle, &index_path, index, "library index").await?;
+
+    audit_log::record(&app_handle, "save_library_index", "library_index", &[]).await;
 
     Ok(())
 }
@@ -79,76 +115,255 @@ pub async fn save_library_index(
 // Artifact Commands
 // ============================================
 
-/// Get a specific artifact by ID
+/// Get a specific artifact by ID. With `stream_large` set, an artifact
+/// over the streaming threshold isn't sent; instead the result is
+/// `{ artifactId, size, streamRequired: true }` and the caller should use
+/// `get_artifact_stream`.
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn get_artifact(
     app_handle: tauri::AppHandle,
     artifact_id: String,
+    stream_large: Option<bool>,
 ) -> Result<String, String> {
-    let artifacts_dir = get_artifacts_dir(&app_handle)?;
-    let artifact_path = artifacts_dir.join(format!("{}.json", artifact_id));
+    let artifact_path = get_artifact_path(&app_handle, &artifact_id)?;
 
     if !artifact_path.exists() {
         return Err(format!("Artifact not found: {}", artifact_id));
     }
 
+    if stream_large == Some(true) {
+        let size = fs::metadata(&artifact_path)
+            .await
+            .map_err(|e| format!("Failed to read artifact: {}", e))?
+            .len();
+        if size > STREAM_THRESHOLD {
+            let response = serde_json::json!({
+                "artifactId": artifact_id,
+                "size": size,
+                "streamRequired": true,
+            });
+            return Ok(response.to_string());
+        }
+    }
+
     fs::read_to_string(&artifact_path)
         .await
         .map_err(|e| format!("Failed to read artifact: {}", e))
 }
 
+/// Send an artifact's JSON to the frontend in chunks, as
+/// `artifact://chunk` events carrying `{ streamId, artifactId, index,
+/// total, data }`, so a large artifact doesn't cross IPC as one string.
+/// Listen before invoking and join the `data` of chunks `0..total` in
+/// order. `stream_id` tags the events (a fresh ID is used when it's
+/// `None`). Returns `{ streamId, artifactId, size, chunks }` once every
+/// chunk has been sent.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_artifact_stream(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    stream_id: Option<String>,
+) -> Result<String, String> {
+    let artifact_path = get_artifact_path(&app_handle, &artifact_id)?;
+    if !artifact_path.exists() {
+        return Err(format!("Artifact not found: {}", artifact_id));
+    }
+    let content = fs::read_to_string(&artifact_path)
+        .await
+        .map_err(|e| format!("Failed to read artifact: {}", e))?;
+    let stream_id = stream_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    // Split on character boundaries so every chunk is valid UTF-8
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < content.len() {
+        let mut end = (start + STREAM_CHUNK_SIZE).min(content.len());
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        chunks.push(&content[start..end]);
+        start = end;
+    }
+
+    for (index, data) in chunks.iter().enumerate() {
+        let chunk = serde_json::json!({
+            "streamId": stream_id,
+            "artifactId": artifact_id,
+            "index": index,
+            "total": chunks.len(),
+            "data": data,
+        });
+        app_handle
+            .emit(ARTIFACT_CHUNK_EVENT, chunk)
+            .map_err(|e| format!("Failed to send artifact chunk: {}", e))?;
+    }
+
+    let response = serde_json::json!({
+        "streamId": stream_id,
+        "artifactId": artifact_id,
+        "size": content.len(),
+        "chunks": chunks.len(),
+    });
+    Ok(response.to_string())
+}
+
+/// Read several artifacts, a few files at a time, in the order of
+/// `artifact_ids`. Missing or unreadable artifacts come back as `null`.
+pub(crate) async fn read_artifacts(
+    app_handle: &tauri::AppHandle,
+    artifact_ids: Vec<String>,
+) -> Vec<Value> {
+    stream::iter(artifact_ids)
+        .map(|artifact_id| {
+            let app_handle = app_handle.clone();
+            async move {
+                match read_artifact(&app_handle, &artifact_id).await {
+                    Ok(artifact) => artifact,
+                    Err(e) => {
+                        tracing::warn!(artifact_id = %artifact_id, error = %e, "Skipping artifact");
+                        Value::Null
+                    }
+                }
+            }
+        })
+        .buffered(MAX_CONCURRENT_READS)
+        .collect()
+        .await
+}
+
+/// Get several artifacts in one call, reading their files concurrently.
+/// Returns a JSON array in the order of `artifact_ids`, with `null` for any
+/// artifact that is missing or unreadable.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_artifacts(
+    app_handle: tauri::AppHandle,
+    artifact_ids: Vec<String>,
+) -> Result<String, String> {
+    let artifacts = read_artifacts(&app_handle, artifact_ids).await;
+    serde_json::to_string(&artifacts).map_err(|e| format!("Failed to serialize artifacts: {}", e))
+}
+
 /// Save an artifact (create or update)
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn save_artifact(
     app_handle: tauri::AppHandle,
     artifact: String,
 ) -> Result<(), String> {
-    let library_dir = get_library_dir(&app_handle)?;
-    let artifacts_dir = get_artifacts_dir(&app_handle)?;
-    let index_path = get_index_path(&app_handle)?;
-
-    // Create directories if they don't exist
-    fs::create_dir_all(&artifacts_dir)
-        .await
-        .map_err(|e| format!("Failed to create artifacts directory: {}", e))?;
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
 
     // Parse the incoming artifact
-    let artifact_value: Value =
+    let mut artifact_value: Value =
         serde_json::from_str(&artifact).map_err(|e| format!("Invalid artifact JSON: {}", e))?;
+    lesson_plans::validate(&app_handle, &artifact_value).await?;
+
+    // Keep favorites, ratings, and usage counters the incoming copy lacks
+    let artifact_id = artifact_value.get("artifactId").and_then(|v| v.as_str());
+    let existing = match artifact_id {
+        Some(artifact_id) => read_artifact(&app_handle, artifact_id).await.ok(),
+        None => None,
+    };
+    let mut carried = false;
+    if let (Some(existing), Some(obj)) = (existing, artifact_value.as_object_mut()) {
+        for key in USAGE_KEYS {
+            if let Some(value) = existing.get(*key).filter(|_| !obj.contains_key(*key)) {
+                obj.insert(key.to_string(), value.clone());
+                carried = true;
+            }
+        }
+    }
+
+    if carried {
+        let content = serde_json::to_string_pretty(&artifact_value)
+            .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+        write_artifact(&app_handle, &artifact_value, &content).await?;
+    } else {
+        write_artifact(&app_handle, &artifact_value, &artifact).await?;
+    }
 
     let artifact_id = artifact_value
         .get("artifactId")
         .and_then(|v| v.as_str())
-        .ok_or("Artifact must have an artifactId")?;
+        .unwrap_or_default();
+    audit_log::record(&app_handle, "save_artifact", "artifact", &[artifact_id]).await;
+    automation::artifact_saved(&app_handle, &artifact_value);
 
-    // Save the full artifact to its own file
-    let artifact_path = artifacts_dir.join(format!("{}.json", artifact_id));
-    fs::write(&artifact_path, &artifact)
+    Ok(())
+}
+
+// Helper to read a full artifact from its file
+pub(crate) async fn read_artifact(
+    app_handle: &tauri::AppHandle,
+    artifact_id: &str,
+) -> Result<Value, String> {
+    let artifact_path = get_artifact_path(app_handle, artifact_id)?;
+    if !artifact_path.exists() {
+        return Err(format!("Artifact not found: {}", artifact_id));
+    }
+    let content = fs::read_to_string(&artifact_path)
         .await
-        .map_err(|e| format!("Failed to write artifact: {}", e))?;
+        .map_err(|e| format!("Failed to read artifact: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid artifact JSON: {}", e))
+}
 
-    // Update the index
-    let mut index: Value = if index_path.exists() {
-        let content = fs::read_to_string(&index_path)
-            .await
-            .map_err(|e| format!("Failed to read library index: {}", e))?;
-        serde_json::from_str(&content).unwrap_or_else(|_| {
-            serde_json::json!({
-                "version": 1,
-                "lastUpdated": chrono::Utc::now().to_rfc3339(),
-                "artifacts": []
-            })
-        })
-    } else {
-        serde_json::json!({
+// Helper to read the library index, or an empty one if it is missing or invalid
+async fn read_index(app_handle: &tauri::AppHandle) -> Result<Value, String> {
+    let index_path = get_index_path(app_handle)?;
+    match index_cache::read_index(app_handle, &index_path, "library index").await? {
+        Some(index) if index.is_object() => Ok(Value::clone(&index)),
+        _ => Ok(serde_json::json!({
             "version": 1,
             "lastUpdated": chrono::Utc::now().to_rfc3339(),
             "artifacts": []
-        })
-    };
+        })),
+    }
+}
+
+// Helper to write the library index, stamping `lastUpdated`
+async fn write_index(app_handle: &tauri::AppHandle, mut index: Value) -> Result<(), String> {
+    if let Some(obj) = index.as_object_mut() {
+        obj.insert(
+            "lastUpdated".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        );
+    }
+    let index_path = get_index_path(app_handle)?;
+    index_cache::write_index(app_handle, &index_path, index, "library index").await
+}
+
+// Helper to read the artifact entries in the library index
+pub(crate) async fn read_index_entries(
+    app_handle: &tauri::AppHandle,
+) -> Result<Vec<Value>, String> {
+    let index_path = get_index_path(app_handle)?;
+    let index = index_cache::read_index(app_handle, &index_path, "library index").await?;
+    Ok(index
+        .as_deref()
+        .and_then(|index| index.get("artifacts"))
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default())
+}
+
+// Helper to replace the artifact entries in the library index
+pub(crate) async fn write_index_entries(
+    app_handle: &tauri::AppHandle,
+    entries: Vec<Value>,
+) -> Result<(), String> {
+    let index = serde_json::json!({
+        "version": 1,
+        "artifacts": entries,
+    });
+    write_index(app_handle, index).await
+}
 
-    // Create index entry (metadata only, no HTML content)
-    let index_entry = serde_json::json!({
+/// Build the library index entry for an artifact (metadata only, no HTML content)
+pub(crate) fn index_entry_for(artifact_value: &Value) -> Value {
+    serde_json::json!({
         "artifactId": artifact_value.get("artifactId"),
         "projectId": artifact_value.get("projectId"),
         "jobId": artifact_value.get("jobId"),
@@ -156,10 +371,79 @@ pub async fn save_artifact(
         "title": artifact_value.get("title"),
         "grade": artifact_value.get("grade"),
         "subject": artifact_value.get("subject"),
-        "objectiveTags": artifact_value.get("objectiveTags"),
+        "objectiveTags": artifact_value
+            .get("objectiveTags")
+            .filter(|v| v.is_array())
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!([])),
         "designPackId": artifact_value.get("designPackId"),
+        "clonedFrom": artifact_value.get("clonedFrom"),
         "createdAt": artifact_value.get("createdAt"),
-    });
+        "favorite": artifact_value.get("favorite").and_then(|v| v.as_bool()).unwrap_or(false),
+        "rating": artifact_value.get("rating"),
+        "openCount": artifact_value.get("openCount").and_then(|v| v.as_u64()).unwrap_or(0),
+        "printCount": artifact_value.get("printCount").and_then(|v| v.as_u64()).unwrap_or(0),
+        "lastOpenedAt": artifact_value.get("lastOpenedAt"),
+        "lastPrintedAt": artifact_value.get("lastPrintedAt"),
+    })
+}
+
+/// Delete an artifact file and its library index entry
+pub(crate) async fn remove_artifact(
+    app_handle: &tauri::AppHandle,
+    artifact_id: &str,
+) -> Result<(), String> {
+    let artifact_path = get_artifact_path(app_handle, artifact_id)?;
+
+    // Delete artifact file if it exists
+    if artifact_path.exists() {
+        fs::remove_file(&artifact_path)
+            .await
+            .map_err(|e| format!("Failed to delete artifact file: {}", e))?;
+    }
+
+    // Update index
+    let mut index = read_index(app_handle).await?;
+    if let Some(arr) = index.get_mut("artifacts").and_then(|v| v.as_array_mut()) {
+        let before = arr.len();
+        arr.retain(|a| a.get("artifactId").and_then(|v| v.as_str()) != Some(artifact_id));
+        if arr.len() != before {
+            write_index(app_handle, index).await?;
+        }
+    }
+
+    storage_events::emit(app_handle, StorageEvent::ArtifactDeleted(artifact_id));
+    Ok(())
+}
+
+/// Write an artifact file and upsert its entry in the library index.
+/// `content` is written verbatim so callers can preserve the caller's formatting.
+pub(crate) async fn write_artifact(
+    app_handle: &tauri::AppHandle,
+    artifact_value: &Value,
+    content: &str,
+) -> Result<(), String> {
+    let artifacts_dir = get_artifacts_dir(app_handle)?;
+
+    // Create directories if they don't exist
+    fs::create_dir_all(&artifacts_dir)
+        .await
+        .map_err(|e| format!("Failed to create artifacts directory: {}", e))?;
+
+    let artifact_id = artifact_value
+        .get("artifactId")
+        .and_then(|v| v.as_str())
+        .ok_or("Artifact must have an artifactId")?;
+
+    // Save the full artifact to its own file
+    let artifact_path = get_artifact_path(app_handle, artifact_id)?;
+    fs::write(&artifact_path, content)
+        .await
+        .map_err(|e| format!("Failed to write artifact: {}", e))?;
+
+    // Update the index
+    let mut index = read_index(app_handle).await?;
+    let index_entry = index_entry_for(artifact_value);
 
     // Update artifacts array in index
     if let Some(artifacts) = index.get_mut("artifacts") {
@@ -171,177 +455,174 @@ pub async fn save_artifact(
         }
     }
 
-    // Update lastUpdated
-    if let Some(obj) = index.as_object_mut() {
-        obj.insert(
-            "lastUpdated".to_string(),
-            Value::String(chrono::Utc::now().to_rfc3339()),
-        );
-    }
-
-    // Write index
-    let index_content = serde_json::to_string_pretty(&index)
-        .map_err(|e| format!("Failed to serialize index: {}", e))?;
-    fs::write(&index_path, index_content)
-        .await
-        .map_err(|e| format!("Failed to write library index: {}", e))?;
-
+    write_index(app_handle, index).await?;
+    storage_events::emit(app_handle, StorageEvent::ArtifactSaved(artifact_id));
     Ok(())
 }
 
-/// Delete an artifact
+/// Delete an artifact and remove it from every project and collection
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn delete_artifact(
     app_handle: tauri::AppHandle,
     artifact_id: String,
 ) -> Result<(), String> {
-    let artifacts_dir = get_artifacts_dir(&app_handle)?;
-    let index_path = get_index_path(&app_handle)?;
-    let artifact_path = artifacts_dir.join(format!("{}.json", artifact_id));
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
 
-    // Delete artifact file if it exists
-    if artifact_path.exists() {
-        fs::remove_file(&artifact_path)
-            .await
-            .map_err(|e| format!("Failed to delete artifact file: {}", e))?;
+    remove_artifact(&app_handle, &artifact_id).await?;
+
+    // Drop the artifact from any project or collection that lists it
+    project_storage::remove_artifact_references(&app_handle, &artifact_id).await?;
+    collections::remove_artifact_references(&app_handle, &artifact_id).await?;
+
+    audit_log::record(&app_handle, "delete_artifact", "artifact", &[&artifact_id]).await;
+
+    Ok(())
+}
+
+// Helper to check an index entry against the `search_artifacts` filters
+fn matches_query(artifact: &Value, query: &Value) -> bool {
+    // Project ID filter
+    if let Some(project_id) = query.get("projectId").and_then(|v| v.as_str()) {
+        if artifact.get("projectId").and_then(|v| v.as_str()) != Some(project_id) {
+            return false;
+        }
     }
 
-    // Update index
-    if index_path.exists() {
-        let content = fs::read_to_string(&index_path)
-            .await
-            .map_err(|e| format!("Failed to read library index: {}", e))?;
-        let mut index: Value = serde_json::from_str(&content).unwrap_or_else(|_| {
-            serde_json::json!({
-                "version": 1,
-                "lastUpdated": chrono::Utc::now().to_rfc3339(),
-                "artifacts": []
-            })
-        });
+    // Grade filter
+    if let Some(grade) = query.get("grade").and_then(|v| v.as_str()) {
+        if artifact.get("grade").and_then(|v| v.as_str()) != Some(grade) {
+            return false;
+        }
+    }
 
-        if let Some(artifacts) = index.get_mut("artifacts") {
-            if let Some(arr) = artifacts.as_array_mut() {
-                arr.retain(|a| a.get("artifactId").and_then(|v| v.as_str()) != Some(&artifact_id));
+    // Subject filter
+    if let Some(subject) = query.get("subject").and_then(|v| v.as_str()) {
+        if artifact.get("subject").and_then(|v| v.as_str()) != Some(subject) {
+            return false;
+        }
+    }
+
+    // Type filter
+    if let Some(artifact_type) = query.get("type").and_then(|v| v.as_str()) {
+        if artifact.get("type").and_then(|v| v.as_str()) != Some(artifact_type) {
+            return false;
+        }
+    }
+
+    // Objective tag filter
+    if let Some(objective_tag) = query.get("objectiveTag").and_then(|v| v.as_str()) {
+        if let Some(tags) = artifact.get("objectiveTags").and_then(|v| v.as_array()) {
+            let has_tag = tags.iter().any(|t| t.as_str() == Some(objective_tag));
+            if !has_tag {
+                return false;
             }
+        } else {
+            return false;
         }
+    }
 
-        if let Some(obj) = index.as_object_mut() {
-            obj.insert(
-                "lastUpdated".to_string(),
-                Value::String(chrono::Utc::now().to_rfc3339()),
-            );
+    // Design pack ID filter
+    if let Some(pack_id) = query.get("designPackId").and_then(|v| v.as_str()) {
+        if artifact.get("designPackId").and_then(|v| v.as_str()) != Some(pack_id) {
+            return false;
         }
+    }
 
-        let index_content = serde_json::to_string_pretty(&index)
-            .map_err(|e| format!("Failed to serialize index: {}", e))?;
-        fs::write(&index_path, index_content)
-            .await
-            .map_err(|e| format!("Failed to write library index: {}", e))?;
+    // Favorites filter
+    if query.get("favorite").and_then(|v| v.as_bool()) == Some(true)
+        && artifact.get("favorite").and_then(|v| v.as_bool()) != Some(true)
+    {
+        return false;
     }
 
-    Ok(())
+    // Minimum rating filter
+    if let Some(min_rating) = query.get("minRating").and_then(|v| v.as_u64()) {
+        let rating = artifact.get("rating").and_then(|v| v.as_u64()).unwrap_or(0);
+        if rating < min_rating {
+            return false;
+        }
+    }
+
+    // Search text filter (title)
+    if let Some(search_text) = query.get("searchText").and_then(|v| v.as_str()) {
+        let search_lower = search_text.to_lowercase();
+        if let Some(title) = artifact.get("title").and_then(|v| v.as_str()) {
+            if !title.to_lowercase().contains(&search_lower) {
+                return false;
+            }
+        } else {
+            return false;
+        }
+    }
+
+    true
 }
 
-/// Search artifacts with filters
+/// Search artifacts with filters. `sortBy` orders results by `favorite`,
+/// `rating`, `usage` (opens plus prints), `lastOpened`, `createdAt`, or
+/// `title`.
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn search_artifacts(
     app_handle: tauri::AppHandle,
     query: String,
 ) -> Result<String, String> {
-    let index_path = get_index_path(&app_handle)?;
-
-    if !index_path.exists() {
-        return Ok("[]".to_string());
-    }
-
     // Parse query
     let query_value: Value =
         serde_json::from_str(&query).map_err(|e| format!("Invalid query JSON: {}", e))?;
 
-    // Read index
-    let content = fs::read_to_string(&index_path)
+    // Read index. A large one that isn't cached is filtered as it's read
+    // instead of being parsed whole.
+    let index_path = get_index_path(&app_handle)?;
+    let large = fs::metadata(&index_path)
         .await
-        .map_err(|e| format!("Failed to read library index: {}", e))?;
-    let index: Value = serde_json::from_str(&content).unwrap_or_else(|_| {
-        serde_json::json!({
-            "version": 1,
-            "lastUpdated": chrono::Utc::now().to_rfc3339(),
-            "artifacts": []
-        })
-    });
-
-    let artifacts = index.get("artifacts").and_then(|v| v.as_array());
-    if artifacts.is_none() {
+        .is_ok_and(|m| m.len() > json_stream::STREAMING_THRESHOLD);
+    let index = match index_cache::read_cached(&app_handle, &index_path).await {
+        Some(index) => index,
+        None if large => {
+            let query = query_value.clone();
+            let matches = json_stream::filter_array(&index_path, Some("artifacts"), move |a| {
+                matches_query(a, &query)
+            })
+            .await?;
+            Arc::new(serde_json::json!({ "artifacts": matches }))
+        }
+        None => match index_cache::read_index(&app_handle, &index_path, "library index").await? {
+            Some(index) => index,
+            None => return Ok("[]".to_string()),
+        },
+    };
+    let Some(artifacts) = index.get("artifacts").and_then(|v| v.as_array()) else {
         return Ok("[]".to_string());
-    }
-    let artifacts = artifacts.unwrap();
+    };
 
     // Apply filters
-    let filtered: Vec<&Value> = artifacts
+    let mut filtered: Vec<&Value> = artifacts
         .iter()
-        .filter(|artifact| {
-            // Project ID filter
-            if let Some(project_id) = query_value.get("projectId").and_then(|v| v.as_str()) {
-                if artifact.get("projectId").and_then(|v| v.as_str()) != Some(project_id) {
-                    return false;
-                }
-            }
-
-            // Grade filter
-            if let Some(grade) = query_value.get("grade").and_then(|v| v.as_str()) {
-                if artifact.get("grade").and_then(|v| v.as_str()) != Some(grade) {
-                    return false;
-                }
-            }
-
-            // Subject filter
-            if let Some(subject) = query_value.get("subject").and_then(|v| v.as_str()) {
-                if artifact.get("subject").and_then(|v| v.as_str()) != Some(subject) {
-                    return false;
-                }
-            }
-
-            // Type filter
-            if let Some(artifact_type) = query_value.get("type").and_then(|v| v.as_str()) {
-                if artifact.get("type").and_then(|v| v.as_str()) != Some(artifact_type) {
-                    return false;
-                }
-            }
-
-            // Objective tag filter
-            if let Some(objective_tag) = query_value.get("objectiveTag").and_then(|v| v.as_str()) {
-                if let Some(tags) = artifact.get("objectiveTags").and_then(|v| v.as_array()) {
-                    let has_tag = tags.iter().any(|t| t.as_str() == Some(objective_tag));
-                    if !has_tag {
-                        return false;
-                    }
-                } else {
-                    return false;
-                }
-            }
-
-            // Design pack ID filter
-            if let Some(pack_id) = query_value.get("designPackId").and_then(|v| v.as_str()) {
-                if artifact.get("designPackId").and_then(|v| v.as_str()) != Some(pack_id) {
-                    return false;
-                }
-            }
-
-            // Search text filter (title)
-            if let Some(search_text) = query_value.get("searchText").and_then(|v| v.as_str()) {
-                let search_lower = search_text.to_lowercase();
-                if let Some(title) = artifact.get("title").and_then(|v| v.as_str()) {
-                    if !title.to_lowercase().contains(&search_lower) {
-                        return false;
-                    }
-                } else {
-                    return false;
-                }
-            }
-
-            true
-        })
+        .filter(|artifact| matches_query(artifact, &query_value))
         .collect();
 
+    // Optional ordering, highest/most recent first
+    let number = |a: &Value, key: &str| a.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let text = |a: &Value, key: &str| a.get(key).and_then(|v| v.as_str()).map(String::from);
+    match query_value.get("sortBy").and_then(|v| v.as_str()) {
+        Some("favorite") => filtered.sort_by_key(|a| {
+            std::cmp::Reverse(a.get("favorite").and_then(|v| v.as_bool()).unwrap_or(false))
+        }),
+        Some("rating") => filtered.sort_by_key(|a| std::cmp::Reverse(number(a, "rating"))),
+        Some("usage") => filtered.sort_by_key(|a| {
+            std::cmp::Reverse(number(a, "openCount") + number(a, "printCount"))
+        }),
+        Some("lastOpened") => {
+            filtered.sort_by_key(|a| std::cmp::Reverse(text(a, "lastOpenedAt")))
+        }
+        Some("createdAt") => filtered.sort_by_key(|a| std::cmp::Reverse(text(a, "createdAt"))),
+        Some("title") => filtered.sort_by_key(|a| text(a, "title").map(|t| t.to_lowercase())),
+        Some(other) => return Err(format!("Invalid sort: {}", other)),
+        None => {}
+    }
+
     serde_json::to_string(&filtered).map_err(|e| format!("Failed to serialize results: {}", e))
 }
@@ -0,0 +1,95 @@
+use axum::extract::{DefaultBodyLimit, Request};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::IntoResponse;
+use axum::serve::Listener;
+use axum::Router;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_io_timeout::TimeoutStream;
+use tokio_util::sync::CancellationToken;
+
+// Largest request body read, so an oversized one can't hold a server up
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+// Time a connection may go without sending anything before it's dropped, so
+// a stalled client can't hold it open
+const READ_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A listener whose connections are dropped when the client stops sending
+/// for `READ_TIMEOUT`
+struct TimedListener(TcpListener);
+
+impl Listener for TimedListener {
+    type Io = Pin<Box<TimeoutStream<TcpStream>>>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        let (stream, address) = Listener::accept(&mut self.0).await;
+        let mut stream = TimeoutStream::new(stream);
+        stream.set_read_timeout(Some(READ_TIMEOUT));
+        (Box::pin(stream), address)
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        self.0.local_addr()
+    }
+}
+
+/// Serve `router` on `listener` until `cancel` is cancelled. Connections
+/// that stall are dropped, request bodies are capped, a request that takes
+/// longer than `timeout` gets a 408, and responses aren't cached.
+pub(crate) async fn serve(
+    listener: TcpListener,
+    router: Router,
+    timeout: Duration,
+    cancel: CancellationToken,
+) -> std::io::Result<()> {
+    let router = router
+        .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
+        .layer(middleware::from_fn(move |request: Request, next: Next| async move {
+            let mut response = match tokio::time::timeout(timeout, next.run(request)).await {
+                Ok(response) => response,
+                Err(_) => StatusCode::REQUEST_TIMEOUT.into_response(),
+            };
+            response
+                .headers_mut()
+                .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+            response
+        }));
+    axum::serve(TimedListener(listener), router)
+        .with_graceful_shutdown(cancel.cancelled_owned())
+        .await
+}
+
+/// Check a request is addressed to this computer by a localhost name, so a
+/// web page can't reach a local server through a DNS name that resolves to
+/// 127.0.0.1
+pub(crate) fn is_local_host(headers: &HeaderMap, port: u16) -> bool {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    host == format!("127.0.0.1:{}", port) || host == format!("localhost:{}", port)
+}
+
+/// Check a request's `Authorization: Bearer` token, comparing without
+/// stopping at the first difference so timing doesn't reveal how much of a
+/// guess was right
+pub(crate) fn has_token(headers: &HeaderMap, token: &str) -> bool {
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or("")
+        .trim();
+    given.len() == token.len()
+        && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Make a random token for a local server
+pub(crate) fn new_token() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
@@ -0,0 +1,603 @@
+use candle_core::{DType, Device, Module, Tensor, D};
+use candle_transformers::models::stable_diffusion::{self, StableDiffusionConfig};
+use reqwest::StatusCode;
+use serde_json::Value;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokenizers::Tokenizer;
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::commands::{
+    app_lock, audit_log, disk_space, http_client, image_cache, session_mode, task_manager,
+};
+
+/// Directory of downloaded image models. They can be downloaded again, so
+/// backups and data history leave it out.
+pub(crate) const MODELS_DIR: &str = "image-models";
+
+/// The on-device image model: SDXL-Turbo, which gives usable pictures in a
+/// single denoising step, so generation stays practical on a CPU
+pub(crate) const MODEL_ID: &str = "sdxl-turbo";
+
+// Files making up the model: local name, Hugging Face repository, and path
+// in that repository. The half-precision weights halve the download and are
+// kept in half precision when loaded, except the small autoencoder's.
+const MODEL_FILES: &[(&str, &str, &str)] = &[
+    (
+        "tokenizer.json",
+        "openai/clip-vit-large-patch14",
+        "tokenizer.json",
+    ),
+    (
+        "tokenizer_2.json",
+        "laion/CLIP-ViT-bigG-14-laion2B-39B-b160k",
+        "tokenizer.json",
+    ),
+    (
+        "text_encoder.safetensors",
+        "stabilityai/sdxl-turbo",
+        "text_encoder/model.fp16.safetensors",
+    ),
+    (
+        "text_encoder_2.safetensors",
+        "stabilityai/sdxl-turbo",
+        "text_encoder_2/model.fp16.safetensors",
+    ),
+    (
+        "unet.safetensors",
+        "stabilityai/sdxl-turbo",
+        "unet/diffusion_pytorch_model.fp16.safetensors",
+    ),
+    (
+        "vae.safetensors",
+        "stabilityai/sdxl-turbo",
+        "vae/diffusion_pytorch_model.fp16.safetensors",
+    ),
+];
+
+// Least time between progress reports while downloading, so a multi-GB
+// file doesn't flood the frontend with updates
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+// Latent scaling factor of the SDXL autoencoder
+const VAE_SCALE: f64 = 0.13025;
+
+const DEFAULT_SIZE: usize = 512;
+const MIN_SIZE: usize = 256;
+const MAX_SIZE: usize = 1024;
+
+// SDXL-Turbo is trained for 1 to 4 steps; more don't help
+const DEFAULT_STEPS: usize = 1;
+const MAX_STEPS: usize = 4;
+
+/// Hash a model file is published with on Hugging Face
+enum FileHash {
+    /// SHA-256 of a file kept in Git LFS (the weights)
+    Sha256(String),
+    /// Git blob ID (SHA-1 of the blob) of a small file kept in Git itself
+    GitBlob(String),
+}
+
+/// A model file as published on Hugging Face
+struct PublishedFile {
+    size: u64,
+    hash: FileHash,
+}
+
+// Helper to get the directory holding the model's files
+fn get_model_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(MODELS_DIR).join(MODEL_ID))
+}
+
+// Helper to clamp a requested image side to a supported multiple of 8
+fn image_side(requested: Option<usize>) -> usize {
+    let side = requested.unwrap_or(DEFAULT_SIZE).clamp(MIN_SIZE, MAX_SIZE);
+    side - side % 8
+}
+
+// Helper to look up a model file's size and hash with the Hugging Face API
+async fn published_file(
+    client: &reqwest::Client,
+    repo: &str,
+    file: &str,
+) -> Result<PublishedFile, String> {
+    let url = match file.rsplit_once('/') {
+        Some((dir, _)) => format!("https://huggingface.co/api/models/{}/tree/main/{}", repo, dir),
+        None => format!("https://huggingface.co/api/models/{}/tree/main", repo),
+    };
+    let entries: Vec<Value> = client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to look up {}: {}", file, e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to look up {}: {}", file, e))?;
+    let entry = entries
+        .iter()
+        .find(|e| e.get("path").and_then(|v| v.as_str()) == Some(file))
+        .ok_or_else(|| format!("{} is not published in {}", file, repo))?;
+
+    let field = |value: Option<&Value>, key: &str| value?.get(key).cloned();
+    let lfs = entry.get("lfs");
+    let (size, hash) = match (field(lfs, "size"), field(lfs, "oid")) {
+        (Some(size), Some(Value::String(oid))) => (size, FileHash::Sha256(oid)),
+        _ => match (field(Some(entry), "size"), field(Some(entry), "oid")) {
+            (Some(size), Some(Value::String(oid))) => (size, FileHash::GitBlob(oid)),
+            _ => return Err(format!("No hash is published for {}", file)),
+        },
+    };
+    let size = size
+        .as_u64()
+        .ok_or_else(|| format!("No size is published for {}", file))?;
+    Ok(PublishedFile { size, hash })
+}
+
+// Helper to hash a file, after `prefix`, as lowercase hex
+async fn hash_file<D: Digest>(path: &Path, prefix: &[u8]) -> Result<String, String> {
+    let mut file = fs::File::open(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut hasher = D::new();
+    hasher.update(prefix);
+    let mut buffer = vec![0; 1 << 20];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+// Helper to check a downloaded file against its published hash
+async fn matches_hash(path: &Path, published: &PublishedFile) -> Result<bool, String> {
+    match &published.hash {
+        FileHash::Sha256(expected) => {
+            Ok(hash_file::<Sha256>(path, b"").await?.eq_ignore_ascii_case(expected))
+        }
+        FileHash::GitBlob(expected) => {
+            let header = format!("blob {}\0", published.size);
+            let actual = hash_file::<Sha1>(path, header.as_bytes()).await?;
+            Ok(actual.eq_ignore_ascii_case(expected))
+        }
+    }
+}
+
+// Helper to download one model file, writing to a `.part` file and renaming
+// it when complete so an interrupted download is never mistaken for a
+// finished one. A `.part` file left by an earlier attempt is resumed with a
+// `Range` request. The file is checked against its published hash before
+// it's renamed into place. `on_bytes` is called with the bytes received so
+// far, at most every `PROGRESS_INTERVAL` and once at the end.
+async fn download_file(
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+    published: &PublishedFile,
+    task: &task_manager::TaskHandle,
+    mut on_bytes: impl FnMut(u64),
+) -> Result<(), String> {
+    let part_path = path.with_extension("part");
+    let resume_from = fs::metadata(&part_path).await.map_or(0, |m| m.len());
+    let mut request = client.get(url);
+    if resume_from > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", resume_from));
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+
+    // A `.part` file that was already complete has nothing left to fetch
+    let complete = response.status() == StatusCode::RANGE_NOT_SATISFIABLE
+        && resume_from == published.size;
+    if !complete {
+        if !response.status().is_success() {
+            return Err(format!(
+                "Failed to download {}: HTTP {}",
+                url,
+                response.status().as_u16()
+            ));
+        }
+
+        // Servers that ignore the range send the whole file again
+        let resumed = response.status() == StatusCode::PARTIAL_CONTENT;
+        let mut file = if resumed {
+            fs::OpenOptions::new().append(true).open(&part_path).await
+        } else {
+            fs::File::create(&part_path).await
+        }
+        .map_err(|e| format!("Failed to create {}: {}", part_path.display(), e))?;
+        let mut received = if resumed { resume_from } else { 0 };
+        let mut reported = Instant::now();
+        while let Some(chunk) = task
+            .cancellable(async {
+                response
+                    .chunk()
+                    .await
+                    .map_err(|e| format!("Failed to download {}: {}", url, e))
+            })
+            .await?
+        {
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write {}: {}", part_path.display(), e))?;
+            received += chunk.len() as u64;
+            if reported.elapsed() >= PROGRESS_INTERVAL {
+                on_bytes(received);
+                reported = Instant::now();
+            }
+        }
+        on_bytes(received);
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", part_path.display(), e))?;
+    }
+
+    if !matches_hash(&part_path, published).await? {
+        // Start over next time rather than resuming a bad file
+        let _ = fs::remove_file(&part_path).await;
+        return Err(format!(
+            "Downloaded {} doesn't match its published hash; try again",
+            path.display()
+        ));
+    }
+    fs::rename(&part_path, path)
+        .await
+        .map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
+
+// Helper to encode a prompt with one of the two CLIP text encoders. The
+// tokens are padded to the encoder's full context length.
+fn text_embeddings(
+    prompt: &str,
+    tokenizer_path: &Path,
+    weights_path: &Path,
+    clip_config: &stable_diffusion::clip::Config,
+    device: &Device,
+) -> Result<Tensor, String> {
+    let tokenizer = Tokenizer::from_file(tokenizer_path)
+        .map_err(|e| format!("Failed to load tokenizer: {}", e))?;
+    let pad_token = clip_config.pad_with.as_deref().unwrap_or("<|endoftext|>");
+    let pad_id = *tokenizer
+        .get_vocab(true)
+        .get(pad_token)
+        .ok_or("Tokenizer is missing its padding token")?;
+
+    let mut tokens = tokenizer
+        .encode(prompt, true)
+        .map_err(|e| format!("Failed to tokenize prompt: {}", e))?
+        .get_ids()
+        .to_vec();
+    let max_tokens = clip_config.max_position_embeddings;
+    if tokens.len() > max_tokens {
+        return Err(format!(
+            "Prompt is too long ({} tokens, at most {})",
+            tokens.len(),
+            max_tokens
+        ));
+    }
+    tokens.resize(max_tokens, pad_id);
+
+    let tokens = Tensor::new(tokens.as_slice(), device)
+        .and_then(|t| t.unsqueeze(0))
+        .map_err(|e| format!("Failed to prepare prompt: {}", e))?;
+    let text_model =
+        stable_diffusion::build_clip_transformer(clip_config, weights_path, device, DType::F16)
+            .map_err(|e| format!("Failed to load text encoder: {}", e))?;
+    text_model
+        .forward(&tokens)
+        .map_err(|e| format!("Failed to encode prompt: {}", e))
+}
+
+// Helper to run the whole pipeline: encode the prompt, denoise random
+// latents for `steps` steps, and decode them to RGB pixels. Blocking and
+// CPU-heavy; checks for cancellation between stages.
+fn run_pipeline(
+    model_dir: &Path,
+    prompt: &str,
+    width: usize,
+    height: usize,
+    steps: usize,
+    task: &task_manager::TaskHandle,
+) -> Result<Vec<u8>, String> {
+    let device = Device::Cpu;
+    let config = StableDiffusionConfig::sdxl_turbo(None, Some(height), Some(width));
+    let model_err = |e: candle_core::Error| format!("Image generation failed: {}", e);
+    // Stages reported to the task: two text encoders, each step, decoding
+    let total = steps as u64 + 3;
+
+    task.progress(0, Some(total), Some("Reading prompt"));
+    let first = text_embeddings(
+        prompt,
+        &model_dir.join("tokenizer.json"),
+        &model_dir.join("text_encoder.safetensors"),
+        &config.clip,
+        &device,
+    )?;
+    task.check_cancelled()?;
+    task.progress(1, Some(total), Some("Reading prompt"));
+    let clip2 = config.clip2.as_ref().ok_or("Model is missing its second text encoder")?;
+    let second = text_embeddings(
+        prompt,
+        &model_dir.join("tokenizer_2.json"),
+        &model_dir.join("text_encoder_2.safetensors"),
+        clip2,
+        &device,
+    )?;
+    let embeddings = Tensor::cat(&[first, second], D::Minus1).map_err(model_err)?;
+    task.check_cancelled()?;
+
+    let unet = config
+        .build_unet(model_dir.join("unet.safetensors"), &device, 4, false, DType::F16)
+        .map_err(|e| format!("Failed to load image model: {}", e))?;
+    let mut scheduler = config.build_scheduler(steps).map_err(model_err)?;
+    let mut latents = Tensor::randn(0f32, 1f32, (1, 4, height / 8, width / 8), &device)
+        .and_then(|t| t * scheduler.init_noise_sigma())
+        .map_err(model_err)?;
+    let timesteps = scheduler.timesteps().to_vec();
+    for (i, timestep) in timesteps.into_iter().enumerate() {
+        task.progress(2 + i as u64, Some(total), Some("Drawing"));
+        // The scheduler works in f32 and the UNet in f16
+        let input = scheduler
+            .scale_model_input(latents.clone(), timestep)
+            .and_then(|t| t.to_dtype(DType::F16))
+            .map_err(model_err)?;
+        let noise = unet
+            .forward(&input, timestep as f64, &embeddings)
+            .and_then(|t| t.to_dtype(DType::F32))
+            .map_err(model_err)?;
+        latents = scheduler.step(&noise, timestep, &latents).map_err(model_err)?;
+        task.check_cancelled()?;
+    }
+    drop(unet);
+
+    task.progress(total - 1, Some(total), Some("Finishing"));
+    // The SDXL autoencoder overflows in f16, so it runs in f32
+    let vae = config
+        .build_vae(model_dir.join("vae.safetensors"), &device, DType::F32)
+        .map_err(|e| format!("Failed to load image model: {}", e))?;
+    let image = (latents / VAE_SCALE)
+        .and_then(|l| vae.decode(&l))
+        // From [-1, 1] to [0, 1]
+        .and_then(|t| t.affine(0.5, 0.5))
+        .and_then(|t| t.clamp(0f32, 1f32))
+        .and_then(|t| t * 255.)
+        .and_then(|t| t.to_dtype(DType::U8))
+        .and_then(|t| t.squeeze(0))
+        // Channels first to interleaved RGB rows
+        .and_then(|t| t.permute((1, 2, 0)))
+        .and_then(|t| t.flatten_all())
+        .and_then(|t| t.to_vec1::<u8>())
+        .map_err(model_err)?;
+    Ok(image)
+}
+
+// Helper to encode RGB pixels as a PNG
+fn encode_png(pixels: &[u8], width: usize, height: usize) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut output, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("Failed to encode image: {}", e))?;
+        writer
+            .write_image_data(pixels)
+            .map_err(|e| format!("Failed to encode image: {}", e))?;
+    }
+    Ok(output)
+}
+
+// ============================================
+// Local Image Generation Commands
+// ============================================
+
+/// Get whether the on-device image model is downloaded, as `{modelId,
+/// installed, files: [{name, downloaded, size}]}`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_local_image_model_status(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let model_dir = get_model_dir(&app_handle)?;
+    let mut files = Vec::new();
+    for (name, _, _) in MODEL_FILES {
+        let size = fs::metadata(model_dir.join(name)).await.ok().map(|m| m.len());
+        files.push(serde_json::json!({
+            "name": name,
+            "downloaded": size.is_some(),
+            "size": size,
+        }));
+    }
+    let installed = files.iter().all(|f| f["downloaded"] == true);
+
+    let status = serde_json::json!({
+        "modelId": MODEL_ID,
+        "installed": installed,
+        "files": files,
+    });
+    Ok(status.to_string())
+}
+
+/// Download the on-device image model (about 7 GB) from Hugging Face. Files
+/// already downloaded are skipped and a partly downloaded file is resumed
+/// where it stopped. Each file is checked against the hash Hugging Face
+/// publishes for it (SHA-256 for the weights, the Git blob ID for the
+/// tokenizers) before it's used. Fails up front when there isn't room for
+/// what's left to download. Runs as a cancellable "download" task (ID
+/// `operation_id` when given) reporting megabytes received.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn download_local_image_model(
+    app_handle: tauri::AppHandle,
+    operation_id: Option<String>,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let model_dir = get_model_dir(&app_handle)?;
+    fs::create_dir_all(&model_dir)
+        .await
+        .map_err(|e| format!("Failed to create image model directory: {}", e))?;
+
+    let task = task_manager::start_task(
+        &app_handle,
+        operation_id,
+        "download",
+        "Download image model",
+    )?;
+    let result = async {
+        let client = http_client::builder()
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let mut pending = Vec::new();
+        for (name, repo, file) in MODEL_FILES {
+            let path = model_dir.join(name);
+            if !path.exists() {
+                pending.push((name, repo, file, path, published_file(&client, repo, file).await?));
+            }
+        }
+
+        // Make sure everything left fits before the first byte is written
+        let mut needed = 0;
+        for (_, _, _, path, published) in &pending {
+            let part = fs::metadata(path.with_extension("part")).await.map_or(0, |m| m.len());
+            needed += published.size.saturating_sub(part);
+        }
+        disk_space::ensure_available(&model_dir, needed)?;
+
+        let total = pending.len() as u64;
+        for (i, (name, repo, file, path, published)) in pending.iter().enumerate() {
+            task.check_cancelled()?;
+            let url = format!("https://huggingface.co/{}/resolve/main/{}", repo, file);
+            download_file(&client, &url, path, published, &task, |bytes| {
+                let message = format!("{} ({} MB)", name, bytes / 1_000_000);
+                task.progress(i as u64, Some(total), Some(&message));
+            })
+            .await?;
+        }
+        Ok(())
+    }
+    .await;
+    task.finish(&result);
+    result?;
+
+    audit_log::record(&app_handle, "download_local_image_model", "image_model", &[MODEL_ID])
+        .await;
+
+    Ok(())
+}
+
+/// Delete the downloaded on-device image model to free its disk space
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_local_image_model(app_handle: tauri::AppHandle) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let model_dir = get_model_dir(&app_handle)?;
+    if model_dir.exists() {
+        fs::remove_dir_all(&model_dir)
+            .await
+            .map_err(|e| format!("Failed to delete image model: {}", e))?;
+    }
+
+    audit_log::record(&app_handle, "delete_local_image_model", "image_model", &[MODEL_ID]).await;
+
+    Ok(())
+}
+
+/// Generate an illustration from `prompt` entirely on this device, with
+/// the model from `download_local_image_model`. `width` and `height`
+/// default to 512 (256 to 1024, rounded down to a multiple of 8); `steps`
+/// defaults to 1 (at most 4). Runs on the CPU, so expect tens of seconds
+/// or more per image. Runs as a cancellable "image_generation" task (ID
+/// `operation_id` when given). A request made before is answered from the
+/// image cache. Returns `{dataUri, width, height, cached}` with a PNG data
+/// URI.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn generate_image_local(
+    app_handle: tauri::AppHandle,
+    prompt: String,
+    width: Option<usize>,
+    height: Option<usize>,
+    steps: Option<usize>,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let prompt = prompt.trim().to_string();
+    if prompt.is_empty() {
+        return Err("Describe the image to generate".to_string());
+    }
+    let model_dir = get_model_dir(&app_handle)?;
+    if MODEL_FILES.iter().any(|(name, _, _)| !model_dir.join(name).exists()) {
+        return Err("Download the image model first".to_string());
+    }
+    let width = image_side(width);
+    let height = image_side(height);
+    let steps = steps.unwrap_or(DEFAULT_STEPS).clamp(1, MAX_STEPS);
+
+    // The same request has given the same kind of picture before; reuse it
+    let provider = format!("local:{}", MODEL_ID);
+    let params = format!("{}x{}:{}", width, height, steps);
+    let cache_key = image_cache::cache_key(&provider, &prompt, &params);
+    if let Some((mime_type, bytes)) = image_cache::get(&app_handle, &cache_key).await? {
+        let response = serde_json::json!({
+            "dataUri": image_cache::data_uri(&mime_type, &bytes),
+            "width": width,
+            "height": height,
+            "cached": true,
+        });
+        return Ok(response.to_string());
+    }
+
+    let task = task_manager::start_task(
+        &app_handle,
+        operation_id,
+        "image_generation",
+        "Generate image",
+    )?;
+    let worker_task = task.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let pixels = run_pipeline(&model_dir, &prompt, width, height, steps, &worker_task)?;
+        encode_png(&pixels, width, height)
+    })
+    .await
+    .map_err(|e| format!("Image generation failed: {}", e))
+    .and_then(|r| r);
+    task.finish(&result);
+    let png = result?;
+    image_cache::put(&app_handle, &cache_key, "image/png", &png).await?;
+
+    let response = serde_json::json!({
+        "dataUri": image_cache::data_uri("image/png", &png),
+        "width": width,
+        "height": height,
+        "cached": false,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,260 @@
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, session_mode, settings_storage};
+
+const LOCALES_DIR: &str = "locales";
+
+/// Language used when none is set, and for keys a language doesn't have
+pub(crate) const DEFAULT_LANGUAGE: &str = "en";
+
+// Translation bundles shipped with the app, as (language code, bundle)
+const BUNDLES: &[(&str, &[u8])] = &[
+    ("en", include_bytes!("../../locales/en.json")),
+    ("es", include_bytes!("../../locales/es.json")),
+];
+
+type Strings = BTreeMap<String, String>;
+
+/// Managed state holding the strings of the current language, with
+/// English filling in missing keys
+pub struct LocaleState {
+    strings: Mutex<Strings>,
+}
+
+impl Default for LocaleState {
+    fn default() -> Self {
+        Self {
+            strings: Mutex::new(bundle(DEFAULT_LANGUAGE)),
+        }
+    }
+}
+
+// Helper to read a bundled language's strings (empty if there's none)
+fn bundle(language: &str) -> Strings {
+    BUNDLES
+        .iter()
+        .find(|(code, _)| *code == language)
+        .and_then(|(_, bytes)| serde_json::from_slice(bytes).ok())
+        .unwrap_or_default()
+}
+
+/// Check a language code looks like "es" or "es-MX"
+pub(crate) fn validate_language(language: &str) -> Result<(), String> {
+    let mut parts = language.split('-');
+    let primary = parts.next().unwrap_or("");
+    let valid = (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_lowercase())
+        && parts.all(|p| {
+            (2..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    if !valid {
+        return Err(format!("Invalid language code: {}", language));
+    }
+    Ok(())
+}
+
+// Helper to get the file holding a language's user overrides
+fn get_overrides_path(app_handle: &tauri::AppHandle, language: &str) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(LOCALES_DIR).join(format!("{}.json", language)))
+}
+
+// Helper to read a language's user overrides (empty if there are none)
+async fn read_overrides(
+    app_handle: &tauri::AppHandle,
+    language: &str,
+) -> Result<Strings, String> {
+    let path = get_overrides_path(app_handle, language)?;
+    if !path.exists() {
+        return Ok(Strings::new());
+    }
+    let content = fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read locale overrides: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_default())
+}
+
+// Helper to merge a language's strings: English, then the bundled
+// language, then the user's overrides
+async fn strings_for(app_handle: &tauri::AppHandle, language: &str) -> Result<Strings, String> {
+    validate_language(language)?;
+    let mut strings = bundle(DEFAULT_LANGUAGE);
+    strings.extend(bundle(language));
+    strings.extend(read_overrides(app_handle, language).await?);
+    Ok(strings)
+}
+
+/// Load the strings of the language set in settings
+pub(crate) async fn reload(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let settings = settings_storage::load_settings(app_handle).await?;
+    let strings = strings_for(app_handle, &settings.locale.language).await?;
+    let state = app_handle.state::<LocaleState>();
+    *state.strings.lock().unwrap() = strings;
+    Ok(())
+}
+
+/// Load the current language's strings at startup. Until they're loaded,
+/// strings are in English.
+pub fn init(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = reload(&app_handle).await {
+            tracing::warn!(error = %e, "Failed to load locale strings");
+        }
+    });
+}
+
+/// Get a string in the current language, or `None` when no language has it
+pub(crate) fn lookup(app_handle: &tauri::AppHandle, key: &str) -> Option<String> {
+    let state = app_handle.state::<LocaleState>();
+    let strings = state.strings.lock().unwrap();
+    strings.get(key).cloned()
+}
+
+/// Get a string in the current language, falling back to the key itself
+pub(crate) fn text(app_handle: &tauri::AppHandle, key: &str) -> String {
+    lookup(app_handle, key).unwrap_or_else(|| key.to_string())
+}
+
+/// Get a string in the current language with each `{name}` placeholder
+/// filled in from `args`
+pub(crate) fn format(
+    app_handle: &tauri::AppHandle,
+    key: &str,
+    args: &[(&str, &str)],
+) -> String {
+    args.iter().fold(text(app_handle, key), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// Format a date with the current language's `format.*` pattern for `key`.
+/// An override with an invalid pattern falls back to the English one.
+pub(crate) fn format_date(app_handle: &tauri::AppHandle, key: &str, date: NaiveDate) -> String {
+    let mut formatted = String::new();
+    if write!(formatted, "{}", date.format(&text(app_handle, key))).is_ok() {
+        return formatted;
+    }
+    let pattern = bundle(DEFAULT_LANGUAGE).remove(key).unwrap_or_default();
+    date.format(&pattern).to_string()
+}
+
+// ============================================
+// Locale Commands
+// ============================================
+
+/// Get the languages with a bundled translation or user overrides, as
+/// `[{ code, name, bundled }]`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_available_locales(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let mut codes: Vec<(String, bool)> =
+        BUNDLES.iter().map(|(code, _)| (code.to_string(), true)).collect();
+    let overrides_dir = get_overrides_path(&app_handle, DEFAULT_LANGUAGE)?
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    if let Ok(mut dir) = fs::read_dir(&overrides_dir).await {
+        while let Ok(Some(entry)) = dir.next_entry().await {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(code) = name.strip_suffix(".json") else {
+                continue;
+            };
+            if validate_language(code).is_ok() && !codes.iter().any(|(c, _)| c == code) {
+                codes.push((code.to_string(), false));
+            }
+        }
+    }
+
+    let mut locales = Vec::new();
+    for (code, bundled) in codes {
+        // The language's own name, not the English fallback's
+        let mut own = bundle(&code);
+        own.extend(read_overrides(&app_handle, &code).await?);
+        let name = own.remove("language.name").unwrap_or_else(|| code.clone());
+        locales.push(serde_json::json!({ "code": code, "name": name, "bundled": bundled }));
+    }
+    serde_json::to_string(&locales).map_err(|e| format!("Failed to serialize locales: {}", e))
+}
+
+/// Get every string for `lang` (the current language when omitted) as a
+/// `{ key: text }` object: English, overlaid with the language's bundled
+/// translation and then the user's overrides
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_locale_strings(
+    app_handle: tauri::AppHandle,
+    lang: Option<String>,
+) -> Result<String, String> {
+    let language = match lang {
+        Some(lang) => lang,
+        None => settings_storage::load_settings(&app_handle).await?.locale.language,
+    };
+    let strings = strings_for(&app_handle, &language).await?;
+    serde_json::to_string(&strings).map_err(|e| format!("Failed to serialize strings: {}", e))
+}
+
+/// Get the user's overrides for `lang` as a `{ key: text }` object
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_locale_overrides(
+    app_handle: tauri::AppHandle,
+    lang: String,
+) -> Result<String, String> {
+    validate_language(&lang)?;
+    let overrides = read_overrides(&app_handle, &lang).await?;
+    serde_json::to_string(&overrides)
+        .map_err(|e| format!("Failed to serialize overrides: {}", e))
+}
+
+/// Replace the user's overrides for `lang` with a `{ key: text }` object,
+/// e.g. to use a co-op's own terms for mastery levels. Overrides for a
+/// language with no bundled translation add that language. An empty object
+/// removes the overrides.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn save_locale_overrides(
+    app_handle: tauri::AppHandle,
+    lang: String,
+    overrides: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    validate_language(&lang)?;
+
+    let overrides: Strings = serde_json::from_str(&overrides)
+        .map_err(|e| format!("Overrides must be an object of strings: {}", e))?;
+    let path = get_overrides_path(&app_handle, &lang)?;
+    if overrides.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)
+                .await
+                .map_err(|e| format!("Failed to remove locale overrides: {}", e))?;
+        }
+    } else {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create locales directory: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(&overrides)
+            .map_err(|e| format!("Failed to serialize overrides: {}", e))?;
+        fs::write(&path, content)
+            .await
+            .map_err(|e| format!("Failed to write locale overrides: {}", e))?;
+    }
+    reload(&app_handle).await?;
+
+    audit_log::record(&app_handle, "save_locale_overrides", "locale", &[&lang]).await;
+
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tauri::Manager;
+use tokio::fs;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::Layer;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+use crate::archive::{self, ArchiveEntry};
+use crate::commands::{
+    app_lock, audit_log, crash_reporter, performance, session_mode, settings_storage,
+    usage_stats,
+};
+
+const LOGS_DIR: &str = "logs";
+const LOG_FILE_PREFIX: &str = "ta";
+const LOG_FILE_SUFFIX: &str = "log";
+
+// Daily log files kept on disk before the oldest are deleted
+const MAX_LOG_FILES: usize = 14;
+// Most recent log files included by export_logs
+const MAX_EXPORTED_LOG_FILES: usize = 7;
+
+/// Managed state for the logging subsystem
+pub struct LoggingState {
+    level_handle: reload::Handle<LevelFilter, Registry>,
+    // Flushes buffered log lines when the app exits
+    _guard: WorkerGuard,
+}
+
+// Helper to get the logs directory
+pub(crate) fn get_logs_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(LOGS_DIR))
+}
+
+// Helper to parse a level name ("error", "warn", "info", "debug", "trace", "off")
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(level).map_err(|_| format!("Invalid log level: {}", level))
+}
+
+/// Install the global subscriber: a daily-rotated file under `app_data/logs/`
+/// plus stderr, filtered at the level stored in settings, and the usage
+/// stats, crash context, and performance layers. Called once during app setup.
+pub fn init(app_handle: &tauri::AppHandle) -> Result<LoggingState, String> {
+    let logs_dir = get_logs_dir(app_handle)?;
+    std::fs::create_dir_all(&logs_dir)
+        .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&logs_dir)
+        .map_err(|e| format!("Failed to create log file: {}", e))?;
+    let (file_writer, guard) = tracing_appender::non_blocking(appender);
+
+    let level = tauri::async_runtime::block_on(settings_storage::load_settings(app_handle))
+        .ok()
+        .and_then(|settings| parse_level(&settings.logging.level).ok())
+        .unwrap_or(LevelFilter::INFO);
+    let (level_filter, level_handle) = reload::Layer::new(level);
+    let (usage_layer, usage_state) = usage_stats::layer();
+    let (crash_layer, crash_state) = crash_reporter::layer();
+    let (performance_layer, performance_state) = performance::layer();
+
+    // The level only applies to log output; usage counting sees every command
+    let output_layer = fmt::layer()
+        .with_writer(file_writer)
+        .with_ansi(false)
+        .and_then(fmt::layer().with_writer(std::io::stderr))
+        .with_filter(level_filter);
+
+    tracing_subscriber::registry()
+        .with(output_layer)
+        .with(usage_layer)
+        .with(crash_layer)
+        .with(performance_layer)
+        .try_init()
+        .map_err(|e| format!("Failed to initialize logging: {}", e))?;
+    app_handle.manage(usage_state);
+    app_handle.manage(crash_state);
+    app_handle.manage(performance_state);
+
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "Logging initialized");
+
+    Ok(LoggingState {
+        level_handle,
+        _guard: guard,
+    })
+}
+
+// Helper to list log files, newest first
+async fn list_log_files(logs_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+
+    if !logs_dir.exists() {
+        return Ok(files);
+    }
+
+    let mut dir = fs::read_dir(logs_dir)
+        .await
+        .map_err(|e| format!("Failed to read logs directory: {}", e))?;
+    while let Some(entry) = dir
+        .next_entry()
+        .await
+        .map_err(|e| format!("Failed to read logs directory: {}", e))?
+    {
+        let path = entry.path();
+        let is_log = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with(LOG_FILE_PREFIX) && n.ends_with(LOG_FILE_SUFFIX));
+        if is_log && path.is_file() {
+            files.push(path);
+        }
+    }
+
+    // Rotated file names end in the date, so they sort chronologically
+    files.sort();
+    files.reverse();
+    Ok(files)
+}
+
+/// Read the most recent log files as archive entries under `logs/`
+pub(crate) async fn recent_log_entries(
+    app_handle: &tauri::AppHandle,
+    max_files: usize,
+) -> Result<Vec<ArchiveEntry>, String> {
+    let logs_dir = get_logs_dir(app_handle)?;
+    let mut entries = Vec::new();
+
+    for path in list_log_files(&logs_dir).await?.into_iter().take(max_files) {
+        let contents = fs::read(&path)
+            .await
+            .map_err(|e| format!("Failed to read log file: {}", e))?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        entries.push((format!("logs/{}", name), contents));
+    }
+
+    Ok(entries)
+}
+
+// ============================================
+// Logging Commands
+// ============================================
+
+/// Get the current log level
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_log_level(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let state = app_handle.state::<LoggingState>();
+    state
+        .level_handle
+        .with_current(|level| level.to_string().to_lowercase())
+        .map_err(|e| format!("Failed to read log level: {}", e))
+}
+
+/// Change the log level at runtime and remember it in settings
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn set_log_level(app_handle: tauri::AppHandle, level: String) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let filter = parse_level(&level)?;
+    app_handle
+        .state::<LoggingState>()
+        .level_handle
+        .reload(filter)
+        .map_err(|e| format!("Failed to change log level: {}", e))?;
+
+    let mut settings = settings_storage::load_settings(&app_handle).await?;
+    settings.logging.level = filter.to_string().to_lowercase();
+    settings_storage::write_settings(&app_handle, &settings).await?;
+
+    audit_log::record(&app_handle, "set_log_level", "settings", &[]).await;
+
+    tracing::info!(level = %filter, "Log level changed");
+    Ok(())
+}
+
+/// Export the most recent log files as a zip
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn export_logs(app_handle: tauri::AppHandle, output_path: String) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let entries = recent_log_entries(&app_handle, MAX_EXPORTED_LOG_FILES).await?;
+    if entries.is_empty() {
+        return Err("No log files to export".to_string());
+    }
+    let bundle = archive::build_zip(&entries)?;
+
+    // Create parent directories if they don't exist
+    if let Some(parent) = Path::new(&output_path).parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+
+    fs::write(&output_path, bundle)
+        .await
+        .map_err(|e| format!("Failed to write log export: {}", e))
+}
@@ -0,0 +1,156 @@
+use serde_json::Value;
+
+use crate::commands::{app_lock, learner_storage, session_mode};
+use crate::json_stream;
+
+// Score counted as mastery, matching the frontend's mastery calculation
+const MASTERY_SCORE: f64 = 80.0;
+
+// Score below which a stalled learner needs help
+const STRUGGLING_SCORE: f64 = 50.0;
+
+// Only the most recent quick checks are fitted, so old struggles don't mask
+// recent progress
+const RECENT_SESSIONS: usize = 8;
+
+// Quick checks needed before a trend means anything
+const MIN_SESSIONS: usize = 2;
+
+// Points per session a trend must gain or lose to count as moving
+const TREND_THRESHOLD: f64 = 2.0;
+
+// Estimates further out than this many sessions aren't reported
+const MAX_ETA_SESSIONS: f64 = 20.0;
+
+// Helper to read a quick check's score out of 100, from `score` or its
+// counts
+fn result_score(result: &Value) -> Option<f64> {
+    if let Some(score) = result.get("score").and_then(|v| v.as_f64()) {
+        return Some(score);
+    }
+    let total = result.get("totalQuestions").and_then(|v| v.as_f64())?;
+    let correct = result.get("correctAnswers").and_then(|v| v.as_f64())?;
+    (total > 0.0).then(|| correct / total * 100.0)
+}
+
+// Helper to fit a least-squares line through the scores in session order,
+// returning (slope, intercept, r²)
+fn fit(scores: &[f64]) -> (f64, f64, f64) {
+    let n = scores.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = scores.iter().sum::<f64>() / n;
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for (i, y) in scores.iter().enumerate() {
+        let (dx, dy) = (i as f64 - mean_x, y - mean_y);
+        sxy += dx * dy;
+        sxx += dx * dx;
+        syy += dy * dy;
+    }
+    let slope = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+    let r_squared = if sxx > 0.0 && syy > 0.0 { sxy * sxy / (sxx * syy) } else { 0.0 };
+    (slope, mean_y - slope * mean_x, r_squared)
+}
+
+// ============================================
+// Mastery Prediction Commands
+// ============================================
+
+/// Estimate how many more quick checks a learner needs to master an
+/// objective, from a line fitted through their recent quick check scores.
+///
+/// `status` is `insufficient_data` (fewer than two quick checks),
+/// `mastered`, `on_track` (with `sessionsToMastery`), or `stalled` (no
+/// estimate within 20 sessions). `trend` is `improving`, `flat`, or
+/// `declining`; `needsIntervention` is set when scores are declining, or
+/// stalled below 50%.
+///
+/// Returns `{ learnerId, objectiveId, status, sessions, recentScores,
+/// currentEstimate, slope, rSquared, trend, sessionsToMastery,
+/// needsIntervention }`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn predict_mastery_eta(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    objective_id: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_learner_allowed(&app_handle, &learner_id)?;
+
+    let checks_path = learner_storage::get_learner_dir(&app_handle, &learner_id)?
+        .join(learner_storage::QUICK_CHECKS_FILE);
+    let wanted = objective_id.clone();
+    let mut checks = json_stream::filter_array(&checks_path, None, move |c| {
+        c.get("objectiveId").and_then(|v| v.as_str()) == Some(&wanted)
+    })
+    .await?;
+    checks.sort_by(|a, b| {
+        let created = |c: &Value| c.get("createdAt").and_then(|v| v.as_str()).map(String::from);
+        created(a).cmp(&created(b))
+    });
+
+    let recent: Vec<(&Value, f64)> = checks
+        .iter()
+        .filter_map(|c| result_score(c).map(|score| (c, score)))
+        .collect();
+    let recent = &recent[recent.len().saturating_sub(RECENT_SESSIONS)..];
+    let scores: Vec<f64> = recent.iter().map(|(_, score)| *score).collect();
+    let recent_scores: Vec<Value> = recent
+        .iter()
+        .map(|(c, score)| {
+            serde_json::json!({ "createdAt": c.get("createdAt"), "score": score })
+        })
+        .collect();
+
+    if scores.len() < MIN_SESSIONS {
+        let response = serde_json::json!({
+            "learnerId": learner_id,
+            "objectiveId": objective_id,
+            "status": "insufficient_data",
+            "sessions": checks.len(),
+            "recentScores": recent_scores,
+            "needsIntervention": false,
+        });
+        return Ok(response.to_string());
+    }
+
+    let (slope, intercept, r_squared) = fit(&scores);
+    let current = (intercept + slope * (scores.len() - 1) as f64).clamp(0.0, 100.0);
+    let trend = if slope >= TREND_THRESHOLD {
+        "improving"
+    } else if slope <= -TREND_THRESHOLD {
+        "declining"
+    } else {
+        "flat"
+    };
+
+    // Mastered once the latest score and the fitted trend both reach it
+    let latest = scores[scores.len() - 1];
+    let (status, sessions_to_mastery) = if latest >= MASTERY_SCORE && current >= MASTERY_SCORE {
+        ("mastered", Some(0))
+    } else {
+        let eta = ((MASTERY_SCORE - current) / slope).ceil().max(1.0);
+        if slope > 0.0 && eta <= MAX_ETA_SESSIONS {
+            ("on_track", Some(eta as u64))
+        } else {
+            ("stalled", None)
+        }
+    };
+    let needs_intervention = status != "mastered"
+        && (trend == "declining" || (status == "stalled" && current < STRUGGLING_SCORE));
+
+    let response = serde_json::json!({
+        "learnerId": learner_id,
+        "objectiveId": objective_id,
+        "status": status,
+        "sessions": checks.len(),
+        "recentScores": recent_scores,
+        "currentEstimate": (current * 10.0).round() / 10.0,
+        "slope": (slope * 10.0).round() / 10.0,
+        "rSquared": (r_squared * 100.0).round() / 100.0,
+        "trend": trend,
+        "sessionsToMastery": sessions_to_mastery,
+        "needsIntervention": needs_intervention,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,695 @@
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tokio::fs;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+use crate::commands::settings_storage::{self, McpSettings, McpToolSettings};
+use crate::commands::{
+    app_lock, artifact_analysis, audit_log, library_storage, local_http, secrets, session_mode,
+    tray,
+};
+
+/// Event sent with `{ consentId, tool, arguments }` when an assistant calls
+/// a tool set to "ask"; answer it with `answer_mcp_consent`
+pub(crate) const CONSENT_EVENT: &str = "mcp://consent-requested";
+
+/// Event sent with the request when an assistant queues a generation; pick
+/// it up with `take_mcp_generation_requests`
+pub(crate) const GENERATION_EVENT: &str = "mcp://generation-requested";
+
+/// Keychain key holding the token assistants send
+pub(crate) const TOKEN_KEY: &str = "mcp.token";
+
+const MCP_DIR: &str = "mcp";
+const REQUESTS_FILE: &str = "generation-requests.json";
+
+// Oldest generation requests are dropped beyond this many
+const MAX_REQUESTS: usize = 200;
+
+// How long a call waits for the teacher to approve it before it's refused,
+// and the time allowed for a whole request, which includes that wait
+const CONSENT_TIMEOUT: Duration = Duration::from_secs(120);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(150);
+
+const DEFAULT_LIMIT: usize = 20;
+
+// Protocol versions understood, newest first
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// Tools set in `mcp.tools`. `get_generation_status` follows
+// `enqueue_generation`'s setting but never asks (see `McpToolSettings`).
+const TOOLS: &[&str] = &["search_library", "get_artifact", "enqueue_generation"];
+const PERMISSIONS: &[&str] = &["off", "ask", "allow"];
+
+/// The running MCP server
+struct McpServer {
+    port: u16,
+    cancel: CancellationToken,
+}
+
+/// Managed state holding the MCP server, if it's running, why it last
+/// failed to start, and calls waiting for the teacher's approval. `requests`
+/// keeps changes to the generation requests from running at the same time.
+#[derive(Default)]
+pub struct McpServerState {
+    server: Mutex<Option<McpServer>>,
+    error: Mutex<Option<String>>,
+    consents: Mutex<HashMap<String, oneshot::Sender<bool>>>,
+    requests: tokio::sync::Mutex<()>,
+}
+
+// Helper to read a string field, or "" when missing
+fn text<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// Check every tool named in MCP settings exists and has a known permission
+pub(crate) fn validate_settings(settings: &McpSettings) -> Result<(), String> {
+    for (tool, tool_settings) in &settings.tools {
+        if !TOOLS.contains(&tool.as_str()) {
+            return Err(format!("Unknown MCP tool: {}", tool));
+        }
+        if !PERMISSIONS.contains(&tool_settings.permission.as_str()) {
+            return Err(format!("Unknown MCP permission: {}", tool_settings.permission));
+        }
+    }
+    Ok(())
+}
+
+// Helper to read the token, creating one the first time
+async fn mcp_token() -> Result<String, String> {
+    if let Some(token) = secrets::read_secret(TOKEN_KEY).await? {
+        return Ok(token);
+    }
+    let token = local_http::new_token();
+    secrets::write_secret(TOKEN_KEY, &token).await?;
+    Ok(token)
+}
+
+// Helper to get the generation requests file
+fn get_requests_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(MCP_DIR).join(REQUESTS_FILE))
+}
+
+// Helper to read the queued generation requests, oldest first
+async fn read_requests(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let requests_path = get_requests_path(app_handle)?;
+    if !requests_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&requests_path)
+        .await
+        .map_err(|e| format!("Failed to read generation requests: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
+// Helper to write the generation requests, keeping only the newest
+// `MAX_REQUESTS`
+async fn write_requests(
+    app_handle: &tauri::AppHandle,
+    mut requests: Vec<Value>,
+) -> Result<(), String> {
+    let requests_path = get_requests_path(app_handle)?;
+    if let Some(parent) = requests_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create MCP directory: {}", e))?;
+    }
+    if requests.len() > MAX_REQUESTS {
+        requests.drain(..requests.len() - MAX_REQUESTS);
+    }
+    let content = serde_json::to_string_pretty(&requests)
+        .map_err(|e| format!("Failed to serialize generation requests: {}", e))?;
+    fs::write(&requests_path, content)
+        .await
+        .map_err(|e| format!("Failed to write generation requests: {}", e))
+}
+
+// Helper to describe the tools assistants may see: those not set to "off"
+fn tool_definitions(settings: &McpSettings) -> Vec<Value> {
+    let string = |description: &str| {
+        serde_json::json!({ "type": "string", "description": description })
+    };
+    let enabled = |tool: &str| settings.tools.get(tool).is_some_and(|t| t.permission != "off");
+    let mut tools = Vec::new();
+    if enabled("search_library") {
+        tools.push(serde_json::json!({
+            "name": "search_library",
+            "description": "Search the teacher's library of worksheets, lesson plans, and \
+                            other artifacts. Returns titles, IDs, and metadata.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": string("Words to find in artifact titles"),
+                    "subject": string("Subject, e.g. Math"),
+                    "grade": string("Grade, e.g. 2"),
+                    "type": string("Artifact type, e.g. student_page"),
+                    "limit": { "type": "integer", "description": "Most results to return" },
+                },
+            },
+        }));
+    }
+    if enabled("get_artifact") {
+        tools.push(serde_json::json!({
+            "name": "get_artifact",
+            "description": "Get one library artifact's content by ID, as text or HTML.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "artifactId": string("ID from search_library"),
+                    "format": { "type": "string", "enum": ["text", "html"] },
+                },
+                "required": ["artifactId"],
+            },
+        }));
+    }
+    if enabled("enqueue_generation") {
+        tools.push(serde_json::json!({
+            "name": "enqueue_generation",
+            "description": "Ask the app to generate new teaching material from a prompt. \
+                            The app runs it in the background; check on it with \
+                            get_generation_status.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "prompt": string("What to create"),
+                    "projectId": string("Project to add the result to"),
+                    "subject": string("Subject, e.g. Math"),
+                    "grade": string("Grade, e.g. 2"),
+                },
+                "required": ["prompt"],
+            },
+        }));
+        tools.push(serde_json::json!({
+            "name": "get_generation_status",
+            "description": "Check a generation queued with enqueue_generation.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "requestId": string("ID from enqueue_generation") },
+                "required": ["requestId"],
+            },
+        }));
+    }
+    tools
+}
+
+// Helper to check a project is within a tool's scope
+fn in_scope(tool_settings: &McpToolSettings, project_id: &str) -> bool {
+    tool_settings.project_ids.is_empty()
+        || tool_settings.project_ids.iter().any(|p| p == project_id)
+}
+
+// Helper to ask the teacher to approve a call, refusing it if they don't
+// answer in time
+async fn ask_consent(app_handle: &tauri::AppHandle, tool: &str, arguments: &Value) -> bool {
+    let consent_id = uuid::Uuid::new_v4().to_string();
+    let (sender, receiver) = oneshot::channel();
+    let state = app_handle.state::<McpServerState>();
+    state.consents.lock().unwrap().insert(consent_id.clone(), sender);
+
+    let payload = serde_json::json!({
+        "consentId": consent_id,
+        "tool": tool,
+        "arguments": arguments,
+    });
+    if let Err(e) = app_handle.emit(CONSENT_EVENT, payload) {
+        tracing::warn!(error = %e, "Failed to send MCP consent event");
+    }
+    tray::show_main_window(app_handle);
+
+    let approved = tokio::time::timeout(CONSENT_TIMEOUT, receiver).await;
+    state.consents.lock().unwrap().remove(&consent_id);
+    matches!(approved, Ok(Ok(true)))
+}
+
+// Helper to run a tool the teacher has allowed, returning its text output
+async fn run_tool(
+    app_handle: &tauri::AppHandle,
+    tool: &str,
+    arguments: &Value,
+    tool_settings: &McpToolSettings,
+) -> Result<String, String> {
+    match tool {
+        "search_library" => {
+            let query = serde_json::json!({
+                "searchText": arguments.get("query"),
+                "subject": arguments.get("subject"),
+                "grade": arguments.get("grade"),
+                "type": arguments.get("type"),
+            });
+            let limit = arguments
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .map_or(DEFAULT_LIMIT, |n| n as usize);
+            let results =
+                library_storage::search_artifacts(app_handle.clone(), query.to_string()).await?;
+            let results: Vec<Value> = serde_json::from_str(&results).unwrap_or_default();
+            let results: Vec<Value> = results
+                .iter()
+                .filter(|a| in_scope(tool_settings, text(a, "projectId")))
+                .take(limit)
+                .map(|a| {
+                    serde_json::json!({
+                        "artifactId": a.get("artifactId"),
+                        "title": a.get("title"),
+                        "type": a.get("type"),
+                        "grade": a.get("grade"),
+                        "subject": a.get("subject"),
+                        "createdAt": a.get("createdAt"),
+                    })
+                })
+                .collect();
+            Ok(Value::Array(results).to_string())
+        }
+        "get_artifact" => {
+            let artifact_id = text(arguments, "artifactId");
+            let artifact = library_storage::read_artifact(app_handle, artifact_id)
+                .await
+                .ok()
+                .filter(|a| in_scope(tool_settings, text(a, "projectId")))
+                .ok_or_else(|| format!("Artifact not found: {}", artifact_id))?;
+            let html = text(&artifact, "htmlContent");
+            let content = match text(arguments, "format") {
+                "html" => html.to_string(),
+                _ => artifact_analysis::text_blocks(html).join("\n\n"),
+            };
+            let result = serde_json::json!({
+                "artifactId": artifact_id,
+                "title": artifact.get("title"),
+                "type": artifact.get("type"),
+                "grade": artifact.get("grade"),
+                "subject": artifact.get("subject"),
+                "content": content,
+            });
+            Ok(result.to_string())
+        }
+        "enqueue_generation" => {
+            let prompt = text(arguments, "prompt").trim();
+            if prompt.is_empty() {
+                return Err("A prompt is required".to_string());
+            }
+            let project_id = text(arguments, "projectId");
+            if !tool_settings.project_ids.is_empty() && !in_scope(tool_settings, project_id) {
+                return Err(format!(
+                    "Choose one of these projects: {}",
+                    tool_settings.project_ids.join(", ")
+                ));
+            }
+            let request_id = uuid::Uuid::new_v4().to_string();
+            let request = serde_json::json!({
+                "requestId": request_id,
+                "status": "pending",
+                "prompt": prompt,
+                "projectId": Some(project_id).filter(|p| !p.is_empty()),
+                "subject": arguments.get("subject"),
+                "grade": arguments.get("grade"),
+                "artifactId": null,
+                "error": null,
+                "createdAt": chrono::Utc::now().to_rfc3339(),
+            });
+            {
+                let state = app_handle.state::<McpServerState>();
+                let _guard = state.requests.lock().await;
+                let mut requests = read_requests(app_handle).await?;
+                requests.push(request.clone());
+                write_requests(app_handle, requests).await?;
+            }
+            if let Err(e) = app_handle.emit(GENERATION_EVENT, &request) {
+                tracing::warn!(error = %e, "Failed to send MCP generation event");
+            }
+            Ok(serde_json::json!({ "requestId": request_id, "status": "pending" }).to_string())
+        }
+        "get_generation_status" => {
+            let request_id = text(arguments, "requestId");
+            let request = read_requests(app_handle)
+                .await?
+                .into_iter()
+                .find(|r| text(r, "requestId") == request_id)
+                .ok_or_else(|| format!("Generation request not found: {}", request_id))?;
+            let status = serde_json::json!({
+                "requestId": request_id,
+                "status": request.get("status"),
+                "artifactId": request.get("artifactId"),
+                "error": request.get("error"),
+            });
+            Ok(status.to_string())
+        }
+        other => Err(format!("Unknown tool: {}", other)),
+    }
+}
+
+// Helper to answer a `tools/call`: check the tool's permission, ask the
+// teacher when it's set to "ask", then run it. Tool failures are results
+// with `isError`, as the protocol expects.
+async fn call_tool(app_handle: &tauri::AppHandle, params: &Value) -> Result<Value, String> {
+    let tool = text(params, "name");
+    let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+    let settings = settings_storage::load_settings(app_handle).await?.mcp;
+    let setting_name = match tool {
+        "get_generation_status" => "enqueue_generation",
+        tool => tool,
+    };
+    let tool_settings = settings.tools.get(setting_name).cloned().unwrap_or_default();
+
+    let outcome = if tool_settings.permission == "off" {
+        Err(format!("Tool isn't available: {}", tool))
+    } else if tool_settings.permission == "ask"
+        && tool != "get_generation_status"
+        && !ask_consent(app_handle, tool, &arguments).await
+    {
+        Err("The teacher didn't approve this request".to_string())
+    } else {
+        audit_log::record(app_handle, "mcp_tool_call", "mcp", &[tool]).await;
+        run_tool(app_handle, tool, &arguments, &tool_settings).await
+    };
+    let (output, is_error) = match outcome {
+        Ok(output) => (output, false),
+        Err(e) => (e, true),
+    };
+    Ok(serde_json::json!({
+        "content": [{ "type": "text", "text": output }],
+        "isError": is_error,
+    }))
+}
+
+// Helper to answer one JSON-RPC request. Returns None for a notification,
+// which gets no response.
+async fn dispatch(app_handle: &tauri::AppHandle, message: &Value) -> Option<Value> {
+    let id = message.get("id").cloned()?;
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let result = match text(message, "method") {
+        "initialize" => {
+            let requested = text(&params, "protocolVersion");
+            let version = PROTOCOL_VERSIONS
+                .iter()
+                .find(|v| **v == requested)
+                .unwrap_or(&PROTOCOL_VERSIONS[0]);
+            Ok(serde_json::json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": { "listChanged": false } },
+                "serverInfo": {
+                    "name": "teachers-assistant",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            }))
+        }
+        "ping" => Ok(serde_json::json!({})),
+        "tools/list" => match settings_storage::load_settings(app_handle).await {
+            Ok(settings) => Ok(serde_json::json!({ "tools": tool_definitions(&settings.mcp) })),
+            Err(e) => Err((-32603, e)),
+        },
+        "tools/call" => call_tool(app_handle, &params).await.map_err(|e| (-32603, e)),
+        method => Err((-32601, format!("Method not found: {}", method))),
+    };
+    Some(match result {
+        Ok(result) => serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }),
+    })
+}
+
+/// What the server's handlers share
+#[derive(Clone)]
+struct McpContext {
+    app_handle: tauri::AppHandle,
+    port: u16,
+}
+
+// Helper to check every request's host, origin, and token before it's
+// routed
+async fn authorize(State(context): State<McpContext>, request: Request, next: Next) -> Response {
+    let port = context.port;
+    let origin = request.headers().get(header::ORIGIN).map(|o| o.to_str().unwrap_or(""));
+    let local_origin = origin.is_none_or(|o| {
+        o == format!("http://127.0.0.1:{}", port) || o == format!("http://localhost:{}", port)
+    });
+    if !local_http::is_local_host(request.headers(), port) || !local_origin {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let token = match mcp_token().await {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read MCP token");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if !local_http::has_token(request.headers(), &token) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+// Helper to answer a `POST /mcp`: handle the JSON-RPC message it carries.
+// There's no server-sent event stream; every response comes back as JSON.
+async fn handle(State(context): State<McpContext>, body: String) -> Response {
+    let app_handle = &context.app_handle;
+    let response = match serde_json::from_str::<Value>(&body) {
+        Ok(message) if app_lock::ensure_unlocked(app_handle).is_err() => {
+            message.get("id").map(|id| {
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32000, "message": "The app is locked" },
+                })
+            })
+        }
+        Ok(message) => dispatch(app_handle, &message).await,
+        Err(e) => Some(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": -32700, "message": format!("Parse error: {}", e) },
+        })),
+    };
+    match response {
+        Some(response) => Json(response).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+// Helper to serve requests until the server is stopped
+async fn serve(
+    app_handle: tauri::AppHandle,
+    listener: TcpListener,
+    port: u16,
+    cancel: CancellationToken,
+) {
+    let context = McpContext { app_handle, port };
+    let router = Router::new()
+        .route("/mcp", post(handle))
+        .layer(middleware::from_fn_with_state(context.clone(), authorize))
+        .with_state(context);
+    if let Err(e) = local_http::serve(listener, router, REQUEST_TIMEOUT, cancel).await {
+        tracing::warn!(error = %e, "MCP server failed");
+    }
+    tracing::info!("MCP server stopped");
+}
+
+// Helper to start the server on `port`, or stop it when `port` is None,
+// leaving it alone when it's already as wanted
+async fn run_on(app_handle: &tauri::AppHandle, port: Option<u16>) -> Result<(), String> {
+    let state = app_handle.state::<McpServerState>();
+    {
+        let mut server = state.server.lock().unwrap();
+        if server.as_ref().map(|s| s.port) == port {
+            return Ok(());
+        }
+        if let Some(server) = server.take() {
+            server.cancel.cancel();
+        }
+    }
+    let Some(port) = port else {
+        return Ok(());
+    };
+
+    let listener = TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port))
+        .await
+        .map_err(|e| format!("Failed to start MCP server on port {}: {}", port, e))?;
+    let cancel = CancellationToken::new();
+    *state.server.lock().unwrap() = Some(McpServer {
+        port,
+        cancel: cancel.clone(),
+    });
+    tauri::async_runtime::spawn(serve(app_handle.clone(), listener, port, cancel));
+    tracing::info!(port, "MCP server started");
+    Ok(())
+}
+
+/// Start, stop, or move the MCP server to match settings. A failure (e.g.
+/// the port is taken) is kept for `get_mcp_server_status` rather than
+/// failing the caller.
+pub(crate) async fn apply(app_handle: &tauri::AppHandle) {
+    let result = match settings_storage::load_settings(app_handle).await {
+        Ok(settings) => run_on(app_handle, settings.mcp.enabled.then_some(settings.mcp.port)).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = &result {
+        tracing::warn!(error = %e, "Failed to apply MCP server settings");
+    }
+    *app_handle.state::<McpServerState>().error.lock().unwrap() = result.err();
+}
+
+/// Start the MCP server at startup if it's enabled in settings
+pub fn init(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        apply(&app_handle).await;
+    });
+}
+
+// ============================================
+// MCP Server Commands
+// ============================================
+
+/// Get the state of the MCP server: `{ enabled, running, url, error, tools
+/// }`. The server is turned on with `mcp.enabled` in settings and speaks
+/// the Model Context Protocol over HTTP at `http://127.0.0.1:<mcp.port>/mcp`,
+/// with the token from `get_mcp_token` as a bearer token. Each tool in
+/// `mcp.tools` is off until the teacher sets it to "ask" or "allow";
+/// `get_generation_status` comes with `enqueue_generation` and runs without
+/// asking.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_mcp_server_status(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let settings = settings_storage::load_settings(&app_handle).await?;
+    let state = app_handle.state::<McpServerState>();
+    let port = state.server.lock().unwrap().as_ref().map(|s| s.port);
+    let error = state.error.lock().unwrap().clone();
+    let status = serde_json::json!({
+        "enabled": settings.mcp.enabled,
+        "running": port.is_some(),
+        "url": port.map(|port| format!("http://127.0.0.1:{}/mcp", port)),
+        "error": error,
+        "tools": settings.mcp.tools,
+    });
+    Ok(status.to_string())
+}
+
+/// Get the token assistants send to the MCP server, creating it the first
+/// time
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_mcp_token(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    mcp_token().await
+}
+
+/// Replace the MCP token, so assistants using the old one lose access.
+/// Returns the new token.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn regenerate_mcp_token(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let token = local_http::new_token();
+    secrets::write_secret(TOKEN_KEY, &token).await?;
+
+    audit_log::record(&app_handle, "regenerate_mcp_token", "mcp", &[]).await;
+
+    Ok(token)
+}
+
+/// Approve or refuse a tool call sent as an `mcp://consent-requested`
+/// event. Calls not answered within two minutes are refused.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn answer_mcp_consent(
+    app_handle: tauri::AppHandle,
+    consent_id: String,
+    approved: bool,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let state = app_handle.state::<McpServerState>();
+    let sender = state
+        .consents
+        .lock()
+        .unwrap()
+        .remove(&consent_id)
+        .ok_or("This request has already been answered or has expired")?;
+    let _ = sender.send(approved);
+    Ok(())
+}
+
+/// Take the generation requests assistants have queued, marking them
+/// `taken`. The frontend runs each through the generation service and
+/// reports back with `complete_mcp_generation_request`. Returns
+/// `[{requestId, prompt, projectId, subject, grade, createdAt}]`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn take_mcp_generation_requests(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let state = app_handle.state::<McpServerState>();
+    let _guard = state.requests.lock().await;
+    let mut requests = read_requests(&app_handle).await?;
+    let mut taken = Vec::new();
+    for request in requests.iter_mut().filter(|r| text(r, "status") == "pending") {
+        request["status"] = Value::String("taken".to_string());
+        taken.push(request.clone());
+    }
+    if !taken.is_empty() {
+        write_requests(&app_handle, requests).await?;
+    }
+    serde_json::to_string(&taken).map_err(|e| format!("Failed to serialize requests: {}", e))
+}
+
+/// Record how a queued generation turned out: the `artifact_id` it saved,
+/// or the `error` that stopped it. Assistants see this through
+/// `get_generation_status`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn complete_mcp_generation_request(
+    app_handle: tauri::AppHandle,
+    request_id: String,
+    artifact_id: Option<String>,
+    error: Option<String>,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let state = app_handle.state::<McpServerState>();
+    let _guard = state.requests.lock().await;
+    let mut requests = read_requests(&app_handle).await?;
+    let request = requests
+        .iter_mut()
+        .find(|r| text(r, "requestId") == request_id)
+        .ok_or_else(|| format!("Generation request not found: {}", request_id))?;
+    let status = if error.is_some() { "failed" } else { "completed" };
+    request["status"] = Value::String(status.to_string());
+    request["artifactId"] = serde_json::json!(artifact_id);
+    request["error"] = serde_json::json!(error);
+    request["completedAt"] = Value::String(chrono::Utc::now().to_rfc3339());
+    write_requests(&app_handle, requests).await?;
+
+    audit_log::record(&app_handle, "complete_mcp_generation_request", "mcp", &[&request_id])
+        .await;
+
+    Ok(())
+}
@@ -0,0 +1,358 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::archive;
+use crate::commands::{design_pack_storage, learner_storage, library_storage, project_storage};
+
+const SCHEMA_DIR: &str = "schema";
+const VERSIONS_FILE: &str = "versions.json";
+const BACKUPS_DIR: &str = "backups";
+
+// Data written before versions were tracked is treated as this version
+const BASELINE_VERSION: u32 = 1;
+
+/// A data store whose layout is versioned
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Store {
+    Library,
+    Projects,
+    DesignPacks,
+    Learners,
+}
+
+const STORES: &[Store] = &[
+    Store::Library,
+    Store::Projects,
+    Store::DesignPacks,
+    Store::Learners,
+];
+
+impl Store {
+    fn name(self) -> &'static str {
+        match self {
+            Store::Library => "library",
+            Store::Projects => "projects",
+            Store::DesignPacks => "designPacks",
+            Store::Learners => "learners",
+        }
+    }
+
+    fn dir(self, app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+        match self {
+            Store::Library => library_storage::get_library_dir(app_handle),
+            Store::Projects => project_storage::get_projects_dir(app_handle),
+            Store::DesignPacks => design_pack_storage::get_packs_dir(app_handle),
+            Store::Learners => learner_storage::get_learners_dir(app_handle),
+        }
+    }
+
+    // Latest version this build understands
+    fn current_version(self) -> u32 {
+        MIGRATIONS
+            .iter()
+            .filter(|m| m.store == self)
+            .map(|m| m.to_version)
+            .max()
+            .unwrap_or(BASELINE_VERSION)
+    }
+}
+
+/// One step that upgrades a store from `to_version - 1` to `to_version`.
+/// `run` receives the store's directory.
+struct Migration {
+    store: Store,
+    to_version: u32,
+    description: &'static str,
+    run: fn(&Path) -> Result<(), String>,
+}
+
+/// All migrations, in the order they run. Append new steps here; never edit
+/// or reorder a step that has shipped.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        store: Store::Library,
+        to_version: 2,
+        description: "Normalize missing objectiveTags in the library index to []",
+        run: library_v2_objective_tags,
+    },
+    Migration {
+        store: Store::Projects,
+        to_version: 2,
+        description: "Split projects.json into per-project files plus a lightweight index",
+        run: projects_v2_split_files,
+    },
+];
+
+// ============================================
+// Migration Steps
+// ============================================
+
+fn library_v2_objective_tags(library_dir: &Path) -> Result<(), String> {
+    let index_path = library_dir.join("index.json");
+    if !index_path.exists() {
+        return Ok(());
+    }
+
+    let content = std::fs::read_to_string(&index_path)
+        .map_err(|e| format!("Failed to read library index: {}", e))?;
+    let mut index: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid library index: {}", e))?;
+
+    if let Some(artifacts) = index.get_mut("artifacts").and_then(|v| v.as_array_mut()) {
+        for artifact in artifacts.iter_mut() {
+            if let Some(obj) = artifact.as_object_mut() {
+                let has_tags = obj.get("objectiveTags").is_some_and(|v| v.is_array());
+                if !has_tags {
+                    obj.insert("objectiveTags".to_string(), serde_json::json!([]));
+                }
+            }
+        }
+    }
+
+    let content = serde_json::to_string_pretty(&index)
+        .map_err(|e| format!("Failed to serialize library index: {}", e))?;
+    std::fs::write(&index_path, content)
+        .map_err(|e| format!("Failed to write library index: {}", e))
+}
+
+fn projects_v2_split_files(projects_dir: &Path) -> Result<(), String> {
+    let index_path = projects_dir.join("projects.json");
+    if !index_path.exists() {
+        return Ok(());
+    }
+
+    let content = std::fs::read_to_string(&index_path)
+        .map_err(|e| format!("Failed to read projects: {}", e))?;
+    let projects: Vec<serde_json::Value> =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid projects index: {}", e))?;
+
+    let mut index = Vec::with_capacity(projects.len());
+    for project in projects {
+        let project_id = project.get("projectId").and_then(|v| v.as_str());
+        match project_id.filter(|id| project_storage::is_valid_project_id(id)) {
+            Some(project_id) => {
+                let content = serde_json::to_string_pretty(&project)
+                    .map_err(|e| format!("Failed to serialize project: {}", e))?;
+                std::fs::write(projects_dir.join(format!("{}.json", project_id)), content)
+                    .map_err(|e| format!("Failed to write project {}: {}", project_id, e))?;
+                index.push(project_storage::index_entry_for(&project));
+            }
+            // Can't be given its own file; keep the full project in the index
+            None => index.push(project),
+        }
+    }
+
+    let content = serde_json::to_string_pretty(&index)
+        .map_err(|e| format!("Failed to serialize projects: {}", e))?;
+    std::fs::write(&index_path, content).map_err(|e| format!("Failed to write projects: {}", e))
+}
+
+// ============================================
+// Migration Runner
+// ============================================
+
+/// A completed migration, kept in the versions file
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MigrationRecord {
+    store: String,
+    from_version: u32,
+    to_version: u32,
+    description: String,
+    backup_path: Option<String>,
+    migrated_at: String,
+}
+
+/// Schema version of each store plus the migration history
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SchemaVersions {
+    stores: BTreeMap<String, u32>,
+    history: Vec<MigrationRecord>,
+}
+
+// Helper to get the versions file path
+fn get_versions_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(SCHEMA_DIR).join(VERSIONS_FILE))
+}
+
+// Helper to read the versions file
+fn read_versions(versions_path: &Path) -> Result<SchemaVersions, String> {
+    if !versions_path.exists() {
+        return Ok(SchemaVersions::default());
+    }
+    let content = std::fs::read_to_string(versions_path)
+        .map_err(|e| format!("Failed to read schema versions: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid schema versions: {}", e))
+}
+
+// Helper to write the versions file
+fn write_versions(versions_path: &Path, versions: &SchemaVersions) -> Result<(), String> {
+    if let Some(parent) = versions_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create schema directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(versions)
+        .map_err(|e| format!("Failed to serialize schema versions: {}", e))?;
+    std::fs::write(versions_path, content)
+        .map_err(|e| format!("Failed to write schema versions: {}", e))
+}
+
+// Helper to zip a store directory before migrating it
+fn backup_store(
+    app_handle: &tauri::AppHandle,
+    store: Store,
+    store_dir: &Path,
+    from_version: u32,
+) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let backups_dir = app_data_dir.join(BACKUPS_DIR);
+    std::fs::create_dir_all(&backups_dir)
+        .map_err(|e| format!("Failed to create backups directory: {}", e))?;
+
+    let entries = tauri::async_runtime::block_on(archive::collect_dir(store_dir, "", &[]))?;
+    let bundle = archive::build_zip(&entries)?;
+    let backup_path = backups_dir.join(format!(
+        "pre-migration-{}-v{}-{}.zip",
+        store.name(),
+        from_version,
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    std::fs::write(&backup_path, bundle).map_err(|e| format!("Failed to write backup: {}", e))?;
+    Ok(backup_path)
+}
+
+/// Bring every store up to the current schema version. Called once during
+/// app setup, before any command can touch the data.
+///
+/// Each store is backed up before its first migration step. A failing step
+/// stops that store at its last good version; other stores still migrate.
+pub fn run(app_handle: &tauri::AppHandle) {
+    if let Err(e) = run_migrations(app_handle) {
+        tracing::error!(error = %e, "Schema migrations did not run");
+    }
+}
+
+fn run_migrations(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let versions_path = get_versions_path(app_handle)?;
+    let mut versions = read_versions(&versions_path)?;
+    let mut changed = false;
+
+    for &store in STORES {
+        let store_dir = store.dir(app_handle)?;
+        let current = store.current_version();
+
+        let recorded = match versions.stores.get(store.name()) {
+            // No data yet (fresh install or store never used): nothing to migrate
+            _ if !store_dir.exists() => current,
+            Some(&version) => version,
+            // Data from before versions were tracked
+            None => BASELINE_VERSION,
+        };
+
+        if recorded > current {
+            tracing::error!(
+                store = store.name(),
+                version = recorded,
+                supported = current,
+                "Data was written by a newer version of the app; leaving it untouched"
+            );
+            continue;
+        }
+
+        let mut version = recorded;
+        let mut backup_path: Option<String> = None;
+        for migration in MIGRATIONS
+            .iter()
+            .filter(|m| m.store == store && m.to_version > recorded)
+        {
+            if backup_path.is_none() {
+                match backup_store(app_handle, store, &store_dir, version) {
+                    Ok(path) => backup_path = Some(path.to_string_lossy().to_string()),
+                    Err(e) => {
+                        tracing::error!(
+                            store = store.name(),
+                            error = %e,
+                            "Pre-migration backup failed"
+                        );
+                        break;
+                    }
+                }
+            }
+
+            if let Err(e) = (migration.run)(&store_dir) {
+                tracing::error!(
+                    store = store.name(),
+                    to_version = migration.to_version,
+                    error = %e,
+                    "Migration failed"
+                );
+                break;
+            }
+
+            tracing::info!(
+                store = store.name(),
+                to_version = migration.to_version,
+                "{}",
+                migration.description
+            );
+            versions.history.push(MigrationRecord {
+                store: store.name().to_string(),
+                from_version: version,
+                to_version: migration.to_version,
+                description: migration.description.to_string(),
+                backup_path: backup_path.clone(),
+                migrated_at: chrono::Utc::now().to_rfc3339(),
+            });
+            version = migration.to_version;
+        }
+
+        if versions.stores.get(store.name()) != Some(&version) {
+            versions.stores.insert(store.name().to_string(), version);
+            changed = true;
+        }
+    }
+
+    if changed {
+        write_versions(&versions_path, &versions)?;
+    }
+    Ok(())
+}
+
+// ============================================
+// Migration Commands
+// ============================================
+
+/// Get each store's schema version, the version this build expects, and the
+/// migration history
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_schema_versions(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let versions = read_versions(&get_versions_path(&app_handle)?)?;
+
+    let stores: BTreeMap<&str, serde_json::Value> = STORES
+        .iter()
+        .map(|store| {
+            let status = serde_json::json!({
+                "version": versions.stores.get(store.name()),
+                "currentVersion": store.current_version(),
+            });
+            (store.name(), status)
+        })
+        .collect();
+
+    let response = serde_json::json!({
+        "stores": stores,
+        "history": versions.history,
+    });
+    Ok(response.to_string())
+}
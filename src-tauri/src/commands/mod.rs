@@ -4,3 +4,93 @@ pub mod learner_storage;
 pub mod library_storage;
 pub mod design_pack_storage;
 pub mod project_storage;
+pub mod learner_bundle;
+pub mod learner_privacy;
+pub mod app_lock;
+pub mod secrets;
+pub mod session_mode;
+pub mod quick_check_session;
+pub mod audit_log;
+pub mod settings_storage;
+pub mod logging;
+pub mod diagnostics;
+pub mod ollama;
+pub mod health_check;
+pub mod factory_reset;
+pub mod demo_data;
+pub mod usage_stats;
+pub mod crash_reporter;
+pub mod performance;
+pub mod storage_compaction;
+pub mod library_integrity;
+pub mod migrations;
+pub mod legacy_migration;
+pub mod design_pack_assets;
+pub mod design_pack_preview;
+pub mod fonts;
+pub mod project_activity;
+pub mod project_stats;
+pub mod project_bundle;
+pub mod question_bank;
+pub mod worksheet_assembly;
+pub mod question_variants;
+pub mod artifact_usage;
+pub mod tag_management;
+pub mod collections;
+pub mod artifact_analysis;
+pub mod artifact_clone;
+pub mod vocabulary;
+pub mod reading_level;
+pub mod design_pack_reapply;
+pub mod generation_history;
+pub mod generation_usage;
+pub mod prompt_comparison;
+pub mod generation_evaluation;
+pub mod prompt_presets;
+pub mod task_manager;
+pub mod index_cache;
+pub mod binary_ipc;
+pub mod storage_events;
+pub mod tray;
+pub mod deep_link;
+pub mod reminders;
+pub mod calendar;
+pub mod pacing;
+pub mod lesson_plans;
+pub mod oauth_device;
+pub mod remote_backup;
+pub mod google_drive;
+pub mod onedrive;
+pub mod email;
+pub mod parent_portal;
+pub mod weekly_digest;
+pub mod data_history;
+pub mod share_export;
+pub mod local_image;
+pub mod image_cache;
+pub mod image_compression;
+pub mod clipart;
+pub mod image_library;
+pub mod webcam;
+pub mod scanner;
+pub mod palette_extraction;
+pub mod braille;
+pub mod accessibility;
+pub mod print_layout;
+pub mod pdf_export;
+pub mod print_preview;
+pub mod seeded_random;
+pub mod lan_quick_check;
+pub mod standards_alignment;
+pub mod mastery_prediction;
+pub mod moderation_log;
+pub mod disk_space;
+pub mod http_client;
+pub mod connectivity;
+pub mod locale;
+pub mod plugins;
+pub mod automation;
+pub mod local_http;
+pub mod rest_api;
+pub mod mcp_server;
+pub mod library_site;
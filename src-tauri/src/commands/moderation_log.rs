@@ -0,0 +1,240 @@
+use serde_json::Value;
+use std::path::PathBuf;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, library_storage, session_mode};
+
+const MODERATION_DIR: &str = "moderation";
+const LOG_FILE: &str = "decisions.json";
+
+// Oldest decisions are dropped beyond this many
+const MAX_ENTRIES: usize = 2000;
+
+const DEFAULT_LIMIT: usize = 100;
+
+// What the filter did to the content
+const ACTIONS: &[&str] = &["modified", "blocked"];
+
+// Helper to get the moderation log file path
+fn get_log_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(MODERATION_DIR).join(LOG_FILE))
+}
+
+/// Read every recorded decision, oldest first
+pub(crate) async fn read_log(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let log_path = get_log_path(app_handle)?;
+    if !log_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&log_path)
+        .await
+        .map_err(|e| format!("Failed to read moderation log: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
+/// Write the moderation log, keeping only the newest `MAX_ENTRIES`
+/// decisions
+pub(crate) async fn write_log(
+    app_handle: &tauri::AppHandle,
+    mut log: Vec<Value>,
+) -> Result<(), String> {
+    let log_path = get_log_path(app_handle)?;
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create moderation directory: {}", e))?;
+    }
+    if log.len() > MAX_ENTRIES {
+        log.drain(..log.len() - MAX_ENTRIES);
+    }
+    let content = serde_json::to_string_pretty(&log)
+        .map_err(|e| format!("Failed to serialize moderation log: {}", e))?;
+    fs::write(&log_path, content)
+        .await
+        .map_err(|e| format!("Failed to write moderation log: {}", e))
+}
+
+// Helper to get a recorded decision's ID
+fn entry_id(entry: &Value) -> Option<&str> {
+    entry.get("decisionId").and_then(|v| v.as_str())
+}
+
+// Helper to put a decision's original content back in its artifact, when
+// the artifact still holds what the filter produced. Returns whether it did.
+async fn restore_original(app_handle: &tauri::AppHandle, entry: &Value) -> Result<bool, String> {
+    let (Some(artifact_id), Some(original)) = (
+        entry.get("artifactId").and_then(|v| v.as_str()),
+        entry.get("original").and_then(|v| v.as_str()),
+    ) else {
+        return Ok(false);
+    };
+    let Ok(mut artifact) = library_storage::read_artifact(app_handle, artifact_id).await else {
+        return Ok(false);
+    };
+    let current = artifact.get("htmlContent").and_then(|v| v.as_str());
+    if current != entry.get("modified").and_then(|v| v.as_str()) {
+        return Ok(false);
+    }
+
+    if let Some(obj) = artifact.as_object_mut() {
+        obj.insert("htmlContent".to_string(), Value::String(original.to_string()));
+        obj.insert(
+            "updatedAt".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        );
+    }
+    let content = serde_json::to_string_pretty(&artifact)
+        .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+    library_storage::write_artifact(app_handle, &artifact, &content).await?;
+    Ok(true)
+}
+
+// ============================================
+// Moderation Log Commands
+// ============================================
+
+/// Record that the content filter changed or blocked generated content:
+/// `action` (`modified` or `blocked`), `reason`, the `original` content,
+/// the `modified` content for `modified`, and optionally the `filter` that
+/// acted, `generationId`, and `artifactId`. Returns the new `decisionId`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn record_moderation_decision(
+    app_handle: tauri::AppHandle,
+    decision: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut decision: Value = serde_json::from_str(&decision)
+        .map_err(|e| format!("Invalid decision JSON: {}", e))?;
+    let obj = decision.as_object_mut().ok_or("Decision must be an object")?;
+    let action = obj.get("action").and_then(|v| v.as_str()).unwrap_or("");
+    if !ACTIONS.contains(&action) {
+        return Err(format!("Unknown moderation action: {}", action));
+    }
+    if action == "modified" && !obj.get("modified").is_some_and(Value::is_string) {
+        return Err("A modified decision must include the modified content".to_string());
+    }
+    let reason = obj.get("reason").and_then(|v| v.as_str()).unwrap_or("");
+    if reason.trim().is_empty() {
+        return Err("Decision must have a reason".to_string());
+    }
+    if !obj.get("original").is_some_and(Value::is_string) {
+        return Err("Decision must include the original content".to_string());
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    obj.insert("decisionId".to_string(), Value::String(id.clone()));
+    obj.insert("overridden".to_string(), Value::Bool(false));
+    obj.insert(
+        "createdAt".to_string(),
+        Value::String(chrono::Utc::now().to_rfc3339()),
+    );
+
+    let mut log = read_log(&app_handle).await?;
+    log.push(decision);
+    write_log(&app_handle, log).await?;
+
+    audit_log::record(&app_handle, "record_moderation_decision", "moderation", &[&id]).await;
+
+    Ok(id)
+}
+
+/// Get recorded moderation decisions, newest first. The query may filter by
+/// `action`, `artifactId`, `generationId`, and `overridden`, and set a
+/// `limit` (default 100).
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_moderation_log(
+    app_handle: tauri::AppHandle,
+    query: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let query: Value = match query {
+        Some(query) => {
+            serde_json::from_str(&query).map_err(|e| format!("Invalid query JSON: {}", e))?
+        }
+        None => Value::Object(serde_json::Map::new()),
+    };
+    let field_matches = |entry: &Value, key: &str| match query.get(key).filter(|v| !v.is_null()) {
+        Some(wanted) => entry.get(key) == Some(wanted),
+        None => true,
+    };
+    let limit = query
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_LIMIT, |n| n as usize);
+
+    let log = read_log(&app_handle).await?;
+    let entries: Vec<Value> = log
+        .into_iter()
+        .rev()
+        .filter(|e| {
+            ["action", "artifactId", "generationId", "overridden"]
+                .iter()
+                .all(|key| field_matches(e, key))
+        })
+        .take(limit)
+        .collect();
+    serde_json::to_string(&entries)
+        .map_err(|e| format!("Failed to serialize moderation log: {}", e))
+}
+
+/// Override a moderation decision after review. The decision is marked
+/// `overridden` with the teacher's `note`; when it names an artifact that
+/// still holds the filtered content, the original is put back. Returns the
+/// updated decision with `restored` saying whether the artifact changed, so
+/// the frontend can use `original` itself when it didn't (as for blocked
+/// content that was never saved).
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn override_moderation_decision(
+    app_handle: tauri::AppHandle,
+    decision_id: String,
+    note: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut log = read_log(&app_handle).await?;
+    let index = log
+        .iter()
+        .position(|e| entry_id(e) == Some(decision_id.as_str()))
+        .ok_or_else(|| format!("Moderation decision not found: {}", decision_id))?;
+    if log[index].get("overridden").and_then(|v| v.as_bool()) == Some(true) {
+        return Err("This decision has already been overridden".to_string());
+    }
+
+    let restored = restore_original(&app_handle, &log[index]).await?;
+    if let Some(obj) = log[index].as_object_mut() {
+        obj.insert("overridden".to_string(), Value::Bool(true));
+        obj.insert(
+            "overriddenAt".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        );
+        obj.insert("overrideNote".to_string(), serde_json::json!(note));
+    }
+    let mut response = log[index].clone();
+    write_log(&app_handle, log).await?;
+
+    audit_log::record(
+        &app_handle,
+        "override_moderation_decision",
+        "moderation",
+        &[&decision_id],
+    )
+    .await;
+
+    if let Some(obj) = response.as_object_mut() {
+        obj.insert("restored".to_string(), Value::Bool(restored));
+    }
+    Ok(response.to_string())
+}
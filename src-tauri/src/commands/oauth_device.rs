@@ -0,0 +1,178 @@
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::commands::{http_client, secrets, task_manager};
+
+// How long a single request to an OAuth endpoint may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// How often to poll for approval when the provider doesn't say
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// Extra wait the provider asks for when it answers "slow_down" (RFC 8628)
+const SLOW_DOWN_STEP: Duration = Duration::from_secs(5);
+
+/// An OAuth provider that supports the device authorization grant
+/// (RFC 8628), where the user approves the app on another device by
+/// entering a short code
+pub(crate) struct Provider {
+    /// Name shown in errors, e.g. "Google Drive"
+    pub(crate) name: &'static str,
+    /// Names the keychain entry holding the refresh token
+    pub(crate) key: &'static str,
+    pub(crate) device_url: &'static str,
+    pub(crate) token_url: &'static str,
+    /// Set at build time; a build without it can't connect
+    pub(crate) client_id: Option<&'static str>,
+    /// Only for providers that require one from installed apps
+    pub(crate) client_secret: Option<&'static str>,
+    pub(crate) scope: &'static str,
+}
+
+// Helper to get the keychain key of a provider's refresh token
+fn token_key(provider: &Provider) -> String {
+    format!("oauth.{}.refresh_token", provider.key)
+}
+
+// Helper to get the provider's client ID, or explain that it is missing
+fn client_id(provider: &Provider) -> Result<&'static str, String> {
+    provider
+        .client_id
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| format!("{} isn't available in this build", provider.name))
+}
+
+// Helper to POST a form to an OAuth endpoint and read the JSON reply. Error
+// replies are returned as Ok so callers can read the `error` code.
+async fn post_form(url: &str, form: &[(&str, &str)]) -> Result<Value, String> {
+    let client = http_client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .post(url)
+        .form(form)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+    response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Invalid response from {}: {}", url, e))
+}
+
+// Helper to read a string field from an OAuth reply
+fn field<'a>(reply: &'a Value, name: &str) -> Option<&'a str> {
+    reply.get(name).and_then(|v| v.as_str())
+}
+
+// Helper to turn an OAuth error reply into a message
+fn error_message(provider: &Provider, reply: &Value) -> String {
+    let detail = field(reply, "error_description")
+        .or_else(|| field(reply, "error"))
+        .unwrap_or("unexpected response");
+    format!("{} sign-in failed: {}", provider.name, detail)
+}
+
+// Helper to add the client credentials to a token request
+fn with_client<'a>(
+    provider: &'a Provider,
+    mut form: Vec<(&'a str, &'a str)>,
+) -> Result<Vec<(&'a str, &'a str)>, String> {
+    form.push(("client_id", client_id(provider)?));
+    if let Some(secret) = provider.client_secret {
+        form.push(("client_secret", secret));
+    }
+    Ok(form)
+}
+
+/// Start signing in: returns `{deviceCode, userCode, verificationUri,
+/// expiresIn}`. The user enters `userCode` at `verificationUri`; pass
+/// `deviceCode` to `wait_for_approval`.
+pub(crate) async fn start(provider: &Provider) -> Result<Value, String> {
+    let form = [("client_id", client_id(provider)?), ("scope", provider.scope)];
+    let reply = post_form(provider.device_url, &form).await?;
+    let device_code = field(&reply, "device_code").ok_or_else(|| error_message(provider, &reply))?;
+    let user_code = field(&reply, "user_code").ok_or_else(|| error_message(provider, &reply))?;
+    // Google calls it verification_url, RFC 8628 verification_uri
+    let verification_uri = field(&reply, "verification_uri")
+        .or_else(|| field(&reply, "verification_url"))
+        .ok_or_else(|| error_message(provider, &reply))?;
+
+    Ok(serde_json::json!({
+        "deviceCode": device_code,
+        "userCode": user_code,
+        "verificationUri": verification_uri,
+        "expiresIn": reply.get("expires_in"),
+    }))
+}
+
+/// Poll until the user approves (or denies) the sign-in started with
+/// `start`, then keep the refresh token in the keychain. Stops when the
+/// task is cancelled or the code expires.
+pub(crate) async fn wait_for_approval(
+    provider: &Provider,
+    task: &task_manager::TaskHandle,
+    device_code: &str,
+) -> Result<(), String> {
+    let mut interval = DEFAULT_POLL_INTERVAL;
+    loop {
+        task.cancellable(async {
+            tokio::time::sleep(interval).await;
+            Ok(())
+        })
+        .await?;
+
+        let form = with_client(
+            provider,
+            vec![
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("device_code", device_code),
+            ],
+        )?;
+        let reply = post_form(provider.token_url, &form).await?;
+        match field(&reply, "error") {
+            None => {
+                let refresh_token = field(&reply, "refresh_token")
+                    .ok_or_else(|| error_message(provider, &reply))?;
+                return secrets::write_secret(&token_key(provider), refresh_token).await;
+            }
+            Some("authorization_pending") => {}
+            Some("slow_down") => interval += SLOW_DOWN_STEP,
+            Some("access_denied") => return Err(format!("{} sign-in was declined", provider.name)),
+            Some("expired_token") => {
+                return Err(format!("{} sign-in code expired, please start again", provider.name))
+            }
+            Some(_) => return Err(error_message(provider, &reply)),
+        }
+    }
+}
+
+/// Get a fresh access token from the stored refresh token
+pub(crate) async fn access_token(provider: &Provider) -> Result<String, String> {
+    let refresh_token = secrets::read_secret(&token_key(provider))
+        .await?
+        .ok_or_else(|| format!("Not signed in to {}", provider.name))?;
+    let form = with_client(
+        provider,
+        vec![("grant_type", "refresh_token"), ("refresh_token", &refresh_token)],
+    )?;
+    let reply = post_form(provider.token_url, &form).await?;
+    let access_token =
+        field(&reply, "access_token").ok_or_else(|| error_message(provider, &reply))?;
+    // Some providers rotate the refresh token on every use
+    if let Some(rotated) = field(&reply, "refresh_token").filter(|t| *t != refresh_token) {
+        secrets::write_secret(&token_key(provider), rotated).await?;
+    }
+    Ok(access_token.to_string())
+}
+
+/// Whether a refresh token is stored for the provider
+pub(crate) async fn is_connected(provider: &Provider) -> Result<bool, String> {
+    Ok(secrets::read_secret(&token_key(provider)).await?.is_some())
+}
+
+/// Forget the provider's refresh token
+pub(crate) async fn disconnect(provider: &Provider) -> Result<(), String> {
+    secrets::remove_secret(&token_key(provider)).await
+}
@@ -0,0 +1,170 @@
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::commands::http_client;
+
+// Short timeout so status checks don't hang when Ollama isn't running
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+// Generation can take a while on modest hardware
+const GENERATE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Model recommended on first launch when no default is configured
+pub(crate) const RECOMMENDED_MODEL: &str = "llama3.2";
+
+// Helper to build an HTTP client for talking to Ollama
+fn probe_client() -> Result<reqwest::Client, String> {
+    http_client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+// Helper to GET a JSON endpoint on the Ollama server
+async fn get_json(endpoint: &str, path: &str) -> Result<Value, String> {
+    let url = format!("{}{}", endpoint.trim_end_matches('/'), path);
+    let response = probe_client()?
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable at {}: {}", endpoint, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Ollama returned HTTP {}", response.status().as_u16()));
+    }
+
+    response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Invalid response from Ollama: {}", e))
+}
+
+/// Get the version of the Ollama server at `endpoint`
+pub(crate) async fn get_version(endpoint: &str) -> Result<String, String> {
+    let body = get_json(endpoint, "/api/version").await?;
+    Ok(body
+        .get("version")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown")
+        .to_string())
+}
+
+/// List the names of models installed on the Ollama server at `endpoint`
+pub(crate) async fn list_models(endpoint: &str) -> Result<Vec<String>, String> {
+    let body = get_json(endpoint, "/api/tags").await?;
+    Ok(body
+        .get("models")
+        .and_then(|v| v.as_array())
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|m| m.get("name").and_then(|v| v.as_str()))
+                .map(|name| name.to_string())
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Whether `model` is in `installed`. A name without a tag matches any tag
+/// of that model (e.g. "llama3.2" matches "llama3.2:latest").
+pub(crate) fn has_model(installed: &[String], model: &str) -> bool {
+    installed.iter().any(|name| {
+        name == model || (!model.contains(':') && name.split(':').next() == Some(model))
+    })
+}
+
+/// Run a single non-streaming completion on the Ollama server at `endpoint`
+/// and return the generated text
+pub(crate) async fn generate(
+    endpoint: &str,
+    model: &str,
+    system: &str,
+    prompt: &str,
+) -> Result<String, String> {
+    generate_with_options(endpoint, model, system, prompt, &Value::Null).await
+}
+
+/// Like `generate`, with model parameters (`temperature`, `top_p`, ...)
+/// passed through as Ollama `options` when `options` is an object
+pub(crate) async fn generate_with_options(
+    endpoint: &str,
+    model: &str,
+    system: &str,
+    prompt: &str,
+    options: &Value,
+) -> Result<String, String> {
+    let mut request = serde_json::json!({
+        "model": model,
+        "system": system,
+        "prompt": prompt,
+        "stream": false,
+    });
+    if let (Some(obj), true) = (request.as_object_mut(), options.is_object()) {
+        obj.insert("options".to_string(), options.clone());
+    }
+
+    let client = http_client::builder()
+        .timeout(GENERATE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let url = format!("{}/api/generate", endpoint.trim_end_matches('/'));
+    let response = client
+        .post(&url)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable at {}: {}", endpoint, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Ollama returned HTTP {}", response.status().as_u16()));
+    }
+
+    let body = response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Invalid response from Ollama: {}", e))?;
+    body.get("response")
+        .and_then(|v| v.as_str())
+        .map(|text| text.trim().to_string())
+        .ok_or_else(|| "Invalid response from Ollama: missing response".to_string())
+}
+
+/// Embed each of `inputs` with `model` on the Ollama server at `endpoint`,
+/// returning one vector per input in order
+pub(crate) async fn embed(
+    endpoint: &str,
+    model: &str,
+    inputs: &[String],
+) -> Result<Vec<Vec<f32>>, String> {
+    let request = serde_json::json!({ "model": model, "input": inputs });
+
+    let client = http_client::builder()
+        .timeout(GENERATE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let url = format!("{}/api/embed", endpoint.trim_end_matches('/'));
+    let response = client
+        .post(&url)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable at {}: {}", endpoint, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Ollama returned HTTP {}", response.status().as_u16()));
+    }
+
+    let body = response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Invalid response from Ollama: {}", e))?;
+    let embeddings: Vec<Vec<f32>> = body
+        .get("embeddings")
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .ok_or_else(|| "Invalid response from Ollama: missing embeddings".to_string())?;
+    if embeddings.len() != inputs.len() {
+        return Err("Invalid response from Ollama: wrong number of embeddings".to_string());
+    }
+    Ok(embeddings)
+}
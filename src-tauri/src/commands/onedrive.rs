@@ -0,0 +1,367 @@
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::Value;
+use std::path::Path;
+use std::time::Duration;
+use tokio::fs;
+
+use crate::commands::oauth_device::{self, Provider};
+use crate::commands::{
+    app_lock, audit_log, http_client, remote_backup, session_mode, task_manager,
+};
+
+// The app folder scope limits the app to its own folder in the user's
+// OneDrive; offline_access is what returns a refresh token
+pub(crate) static PROVIDER: Provider = Provider {
+    name: "OneDrive",
+    key: "onedrive",
+    device_url: "https://login.microsoftonline.com/common/oauth2/v2.0/devicecode",
+    token_url: "https://login.microsoftonline.com/common/oauth2/v2.0/token",
+    client_id: option_env!("TA_MICROSOFT_CLIENT_ID"),
+    client_secret: None,
+    scope: "Files.ReadWrite.AppFolder offline_access",
+};
+
+const DRIVE_URL: &str = "https://graph.microsoft.com/v1.0/me/drive";
+
+// Upload sessions take the file in pieces; Graph wants multiples of 320 KiB
+const CHUNK_SIZE: usize = 32 * 320 * 1024;
+
+// Backups can be large; allow slow connections
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+// Helper to create an HTTP client for Graph requests
+fn client() -> Result<reqwest::Client, String> {
+    http_client::builder()
+        .timeout(TRANSFER_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+// Helper to send a Graph request and fail on an error status
+async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("OneDrive is not reachable: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("OneDrive returned HTTP {}", response.status().as_u16()));
+    }
+    Ok(response)
+}
+
+// Helper to send a Graph request and read the JSON reply
+async fn send_json(request: reqwest::RequestBuilder) -> Result<Value, String> {
+    send(request)
+        .await?
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Invalid response from OneDrive: {}", e))
+}
+
+// Helper to upload a file into the app folder through an upload session,
+// which (unlike a simple upload) isn't limited to small files. A name that
+// is already taken gets a number added.
+async fn upload(
+    client: &reqwest::Client,
+    token: &str,
+    task: &task_manager::TaskHandle,
+    name: &str,
+    contents: Vec<u8>,
+) -> Result<Value, String> {
+    let session_url = format!(
+        "{}/special/approot:/{}:/createUploadSession",
+        DRIVE_URL,
+        utf8_percent_encode(name, NON_ALPHANUMERIC)
+    );
+    let body = serde_json::json!({
+        "item": { "@microsoft.graph.conflictBehavior": "rename" },
+    });
+    let session = send_json(client.post(session_url).bearer_auth(token).json(&body)).await?;
+    let upload_url = session
+        .get("uploadUrl")
+        .and_then(|v| v.as_str())
+        .ok_or("OneDrive didn't start the upload")?;
+
+    // The upload URL carries its own authorization
+    let total = contents.len();
+    let chunks = total.div_ceil(CHUNK_SIZE).max(1);
+    let mut item = Value::Null;
+    for (i, chunk) in contents.chunks(CHUNK_SIZE).enumerate() {
+        task.check_cancelled()?;
+        task.progress(i as u64, Some(chunks as u64), Some("Uploading"));
+        let start = i * CHUNK_SIZE;
+        let range = format!("bytes {}-{}/{}", start, start + chunk.len() - 1, total);
+        item = send_json(
+            client
+                .put(upload_url)
+                .header(reqwest::header::CONTENT_RANGE, range)
+                .body(chunk.to_vec()),
+        )
+        .await?;
+    }
+    // The last piece's reply is the new file
+    if item.get("id").is_none() {
+        return Err("OneDrive didn't finish the upload".to_string());
+    }
+    Ok(item)
+}
+
+// Helper to describe an uploaded or listed file for the frontend, in the
+// same shape as Google Drive files
+fn file_summary(item: &Value) -> Value {
+    serde_json::json!({
+        "fileId": item.get("id"),
+        "name": item.get("name"),
+        "size": item.get("size"),
+        "createdTime": item.get("createdDateTime"),
+    })
+}
+
+// Helper to check a OneDrive item ID before putting it in a URL. Personal
+// accounts use IDs like `A1B2C3D4E5F6!105`.
+fn validate_item_id(item_id: &str) -> Result<(), String> {
+    let valid = !item_id.is_empty()
+        && item_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '!' | '-' | '_'));
+    if !valid {
+        return Err(format!("Invalid OneDrive item ID: {}", item_id));
+    }
+    Ok(())
+}
+
+// ============================================
+// OneDrive Commands
+// ============================================
+
+/// Whether this build can use OneDrive and whether the user is signed in,
+/// as `{available, connected}`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_onedrive_status(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let status = serde_json::json!({
+        "available": PROVIDER.client_id.is_some_and(|id| !id.is_empty()),
+        "connected": oauth_device::is_connected(&PROVIDER).await?,
+    });
+    Ok(status.to_string())
+}
+
+/// Start signing in to OneDrive with a Microsoft account (personal or
+/// school). Returns `{deviceCode, userCode, verificationUri, expiresIn}`:
+/// show the user the code and the page to enter it on, then call
+/// `finish_onedrive_sign_in` with `deviceCode`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn start_onedrive_sign_in(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    Ok(oauth_device::start(&PROVIDER).await?.to_string())
+}
+
+/// Wait for the user to approve the sign-in, then keep the refresh token in
+/// the OS keychain. Runs as a cancellable "sign_in" task (ID `operation_id`
+/// when given) until approved, declined, or the code expires.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn finish_onedrive_sign_in(
+    app_handle: tauri::AppHandle,
+    device_code: String,
+    operation_id: Option<String>,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let task = task_manager::start_task(&app_handle, operation_id, "sign_in", "OneDrive sign-in")?;
+    let result = oauth_device::wait_for_approval(&PROVIDER, &task, &device_code).await;
+    task.finish(&result);
+    result?;
+
+    audit_log::record(&app_handle, "finish_onedrive_sign_in", "remote_target", &[]).await;
+
+    Ok(())
+}
+
+/// Sign out of OneDrive, forgetting the stored token. Files already
+/// uploaded stay in OneDrive.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn disconnect_onedrive(app_handle: tauri::AppHandle) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    oauth_device::disconnect(&PROVIDER).await?;
+
+    audit_log::record(&app_handle, "disconnect_onedrive", "remote_target", &[]).await;
+
+    Ok(())
+}
+
+/// Upload a file (e.g. an exported PDF) to the app's OneDrive folder
+/// (`Apps/<app name>`). Runs as a cancellable "export" task (ID
+/// `operation_id` when given). Returns `{fileId, name, size, createdTime}`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn upload_to_onedrive(
+    app_handle: tauri::AppHandle,
+    path: String,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let file_path = Path::new(&path);
+    let name = file_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .filter(|_| file_path.is_absolute() && file_path.is_file())
+        .ok_or_else(|| format!("File not found: {}", path))?;
+
+    let task =
+        task_manager::start_task(&app_handle, operation_id, "export", "Upload to OneDrive")?;
+    let result = task
+        .cancellable(async {
+            let contents = fs::read(file_path)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let token = oauth_device::access_token(&PROVIDER).await?;
+            upload(&client()?, &token, &task, &name, contents).await
+        })
+        .await;
+    task.finish(&result);
+    let item = result?;
+
+    audit_log::record(&app_handle, "upload_to_onedrive", "remote_target", &[&name]).await;
+
+    Ok(file_summary(&item).to_string())
+}
+
+/// Back up the app data (without logs) to the app's OneDrive folder. Runs
+/// as a cancellable "backup" task (ID `operation_id` when given). Returns
+/// `{fileId, name, size, createdTime}`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn backup_to_onedrive(
+    app_handle: tauri::AppHandle,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let name = remote_backup::backup_file_name();
+    let task =
+        task_manager::start_task(&app_handle, operation_id, "backup", "Backup to OneDrive")?;
+    let result = task
+        .cancellable(async {
+            let token = oauth_device::access_token(&PROVIDER).await?;
+            let contents = remote_backup::build_backup(&app_handle).await?;
+            upload(&client()?, &token, &task, &name, contents).await
+        })
+        .await;
+    task.finish(&result);
+    let item = result?;
+
+    audit_log::record(&app_handle, "backup_to_onedrive", "remote_target", &[&name]).await;
+
+    Ok(file_summary(&item).to_string())
+}
+
+/// List the backups in the app's OneDrive folder, newest first, as
+/// `[{fileId, name, size, createdTime}]`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_onedrive_backups(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let token = oauth_device::access_token(&PROVIDER).await?;
+    let client = client()?;
+    let mut backups = Vec::new();
+    let mut page_url = Some(format!(
+        "{}/special/approot/children?$select=id,name,size,createdDateTime&$top=200",
+        DRIVE_URL
+    ));
+    while let Some(url) = page_url.take() {
+        let page = send_json(client.get(&url).bearer_auth(&token)).await?;
+        let items = page.get("value").and_then(|v| v.as_array()).into_iter().flatten();
+        backups.extend(
+            items
+                .filter(|item| {
+                    item.get("name")
+                        .and_then(|v| v.as_str())
+                        .is_some_and(remote_backup::is_backup_name)
+                })
+                .map(file_summary),
+        );
+        page_url = page
+            .get("@odata.nextLink")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+    }
+    // Timestamps are ISO 8601 in UTC, so they sort as strings
+    backups.sort_by(|a, b| {
+        let created = |v: &Value| v.get("createdTime").and_then(|t| t.as_str()).map(String::from);
+        created(b).cmp(&created(a))
+    });
+    serde_json::to_string(&backups).map_err(|e| format!("Failed to serialize backups: {}", e))
+}
+
+/// Restore a backup from OneDrive over the app data. The current data is
+/// saved to the local backup folder first; files not in the backup are
+/// kept. Runs as a cancellable "backup" task (ID `operation_id` when given)
+/// while downloading. Returns `{safetyBackupPath, restoredFiles}`; restart
+/// the app afterwards.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn restore_from_onedrive(
+    app_handle: tauri::AppHandle,
+    file_id: String,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    validate_item_id(&file_id)?;
+
+    let task =
+        task_manager::start_task(&app_handle, operation_id, "backup", "Restore from OneDrive")?;
+    let download = task
+        .cancellable(async {
+            let token = oauth_device::access_token(&PROVIDER).await?;
+            let client = client()?;
+            let item_url = format!("{}/items/{}", DRIVE_URL, file_id);
+            let item =
+                send_json(client.get(format!("{}?$select=name", item_url)).bearer_auth(&token))
+                    .await?;
+            let name = item.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            if !remote_backup::is_backup_name(name) {
+                return Err(format!("Not a backup: {}", name));
+            }
+            // Graph redirects to a download URL, which the client follows
+            let response =
+                send(client.get(format!("{}/content", item_url)).bearer_auth(&token)).await?;
+            response
+                .bytes()
+                .await
+                .map_err(|e| format!("Failed to download backup: {}", e))
+        })
+        .await;
+    // Once downloaded, the restore runs to the end
+    let restored = match download.and_then(|b| task.check_cancelled().map(|_| b)) {
+        Ok(bytes) => remote_backup::restore_backup(&app_handle, &bytes).await,
+        Err(e) => Err(e),
+    };
+    task.finish(&restored);
+    let (safety_backup_path, restored_files) = restored?;
+
+    tracing::warn!(file_id = %file_id, "Restored backup from OneDrive");
+    audit_log::record(&app_handle, "restore_from_onedrive", "app_data", &[&file_id]).await;
+
+    let result = serde_json::json!({
+        "safetyBackupPath": safety_backup_path,
+        "restoredFiles": restored_files,
+    });
+    Ok(result.to_string())
+}
@@ -0,0 +1,284 @@
+use chrono::NaiveDate;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, calendar, library_storage, project_stats, session_mode};
+
+const PACING_DIR: &str = "pacing";
+const GUIDES_FILE: &str = "guides.json";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Helper to get the pacing guides file path
+fn get_guides_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(PACING_DIR).join(GUIDES_FILE))
+}
+
+// Helper to read every pacing guide
+async fn read_guides(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let guides_path = get_guides_path(app_handle)?;
+    if !guides_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&guides_path)
+        .await
+        .map_err(|e| format!("Failed to read pacing guides: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
+// Helper to write every pacing guide
+async fn write_guides(app_handle: &tauri::AppHandle, guides: &[Value]) -> Result<(), String> {
+    let guides_path = get_guides_path(app_handle)?;
+    if let Some(parent) = guides_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create pacing directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(guides)
+        .map_err(|e| format!("Failed to serialize pacing guides: {}", e))?;
+    fs::write(&guides_path, content)
+        .await
+        .map_err(|e| format!("Failed to write pacing guides: {}", e))
+}
+
+// Helper to read a guide's term
+fn guide_term(guide: &Value) -> Option<&str> {
+    guide.get("term").and_then(|v| v.as_str())
+}
+
+// Helper to read a guide's weeks as (week number, objective IDs), in order
+fn guide_weeks(guide: &Value) -> Vec<(u64, Vec<String>)> {
+    let mut weeks: Vec<(u64, Vec<String>)> = guide
+        .get("weeks")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|w| {
+                    let week = w.get("week").and_then(|v| v.as_u64())?;
+                    let objectives = w.get("objectiveIds").and_then(|v| v.as_array())?;
+                    let objectives = objectives.iter().filter_map(|v| v.as_str());
+                    Some((week, objectives.map(String::from).collect()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    weeks.sort_by_key(|(week, _)| *week);
+    weeks
+}
+
+// Helper to check a guide from the frontend: a term, a start date, and
+// numbered weeks each listing objective IDs
+fn validate_guide(guide: &Value) -> Result<(), String> {
+    if guide_term(guide).is_none_or(|t| t.trim().is_empty()) {
+        return Err("Pacing guide must have a term".to_string());
+    }
+    let start_date = guide.get("startDate").and_then(|v| v.as_str()).unwrap_or("");
+    NaiveDate::parse_from_str(start_date, DATE_FORMAT)
+        .map_err(|_| format!("Invalid start date: {}", start_date))?;
+    let weeks = guide
+        .get("weeks")
+        .and_then(|v| v.as_array())
+        .ok_or("Pacing guide must have weeks")?;
+    let mut seen = BTreeSet::new();
+    for week in weeks {
+        let number = week
+            .get("week")
+            .and_then(|v| v.as_u64())
+            .filter(|n| *n >= 1)
+            .ok_or("Each week must have a week number from 1")?;
+        if !seen.insert(number) {
+            return Err(format!("Week {} is listed twice", number));
+        }
+        if !week.get("objectiveIds").is_some_and(Value::is_array) {
+            return Err(format!("Week {} must have objectiveIds", number));
+        }
+    }
+    Ok(())
+}
+
+// Helper to find the objectives a learner has finished by way of completed
+// calendar entries, from the objective tags of the scheduled artifacts
+async fn completed_assignment_objectives(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+) -> Result<BTreeSet<String>, String> {
+    let artifact_ids: Vec<String> = calendar::read_entries(app_handle, learner_id)
+        .await?
+        .iter()
+        .filter(|e| e.get("completed").and_then(|v| v.as_bool()).unwrap_or(false))
+        .filter_map(|e| e.get("artifactId").and_then(|v| v.as_str()).map(String::from))
+        .collect();
+    let artifacts = library_storage::read_artifacts(app_handle, artifact_ids).await;
+    Ok(artifacts
+        .iter()
+        .filter_map(|a| a.get("objectiveTags").and_then(|v| v.as_array()))
+        .flatten()
+        .filter_map(|t| t.as_str().map(String::from))
+        .collect())
+}
+
+// ============================================
+// Pacing Guide Commands
+// ============================================
+
+/// Get every pacing guide
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_pacing_guides(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let guides = read_guides(&app_handle).await?;
+    serde_json::to_string(&guides).map_err(|e| format!("Failed to serialize pacing guides: {}", e))
+}
+
+/// Save a pacing guide, replacing any guide for the same term. A guide has
+/// a `term` (e.g. "2026 Fall"), a `startDate` (`YYYY-MM-DD`, the first day
+/// of week 1), and `weeks`, each a `week` number with the `objectiveIds`
+/// planned for it.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn save_pacing_guide(app_handle: tauri::AppHandle, guide: String) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut new_guide: Value =
+        serde_json::from_str(&guide).map_err(|e| format!("Invalid pacing guide JSON: {}", e))?;
+    validate_guide(&new_guide)?;
+    let term = guide_term(&new_guide).unwrap_or_default().to_string();
+
+    let mut guides = read_guides(&app_handle).await?;
+    let existing = guides.iter().position(|g| guide_term(g) == Some(term.as_str()));
+    let now = chrono::Utc::now().to_rfc3339();
+    if let Some(obj) = new_guide.as_object_mut() {
+        let created_at = existing
+            .and_then(|i| guides[i].get("createdAt").cloned())
+            .unwrap_or_else(|| Value::String(now.clone()));
+        obj.insert("createdAt".to_string(), created_at);
+        obj.insert("updatedAt".to_string(), Value::String(now));
+    }
+    match existing {
+        Some(i) => guides[i] = new_guide,
+        None => guides.push(new_guide),
+    }
+    write_guides(&app_handle, &guides).await?;
+
+    audit_log::record(&app_handle, "save_pacing_guide", "pacing_guide", &[&term]).await;
+
+    Ok(())
+}
+
+/// Delete the pacing guide for a term
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_pacing_guide(app_handle: tauri::AppHandle, term: String) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut guides = read_guides(&app_handle).await?;
+    let before = guides.len();
+    guides.retain(|g| guide_term(g) != Some(term.as_str()));
+    if guides.len() != before {
+        write_guides(&app_handle, &guides).await?;
+    }
+
+    audit_log::record(&app_handle, "delete_pacing_guide", "pacing_guide", &[&term]).await;
+
+    Ok(())
+}
+
+/// Compare a learner's progress with a term's pacing guide.
+///
+/// An objective counts as done when the learner has mastered it or has
+/// completed a calendar entry whose artifact is tagged with it. Weeks are
+/// done in order while all their objectives are; the status is `ahead`,
+/// `on_track`, or `behind` by comparing the weeks done with the weeks
+/// elapsed since the start date, and `weeksAhead` is the difference
+/// (negative when behind). Each objective is listed with its week and
+/// whether (and how) it was done.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_pacing_status(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    term: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_learner_allowed(&app_handle, &learner_id)?;
+
+    let guide = read_guides(&app_handle)
+        .await?
+        .into_iter()
+        .find(|g| guide_term(g) == Some(term.as_str()))
+        .ok_or_else(|| format!("Pacing guide not found: {}", term))?;
+    let start_date = guide
+        .get("startDate")
+        .and_then(|v| v.as_str())
+        .and_then(|d| NaiveDate::parse_from_str(d, DATE_FORMAT).ok())
+        .ok_or("Pacing guide has an invalid start date")?;
+    let weeks = guide_weeks(&guide);
+    let total_weeks = weeks.last().map_or(0, |(week, _)| *week);
+
+    // Week 1 starts on the start date; weeks before the current one have elapsed
+    let days = (chrono::Local::now().date_naive() - start_date).num_days();
+    let current_week = if days < 0 { 0 } else { days as u64 / 7 + 1 };
+    let weeks_elapsed = current_week.saturating_sub(1).min(total_weeks);
+
+    let mastery = project_stats::read_mastery(&app_handle, &learner_id).await?;
+    let from_assignments = completed_assignment_objectives(&app_handle, &learner_id).await?;
+
+    let mut objectives = Vec::new();
+    let mut weeks_done = 0;
+    let mut in_order = true;
+    for (week, objective_ids) in &weeks {
+        let mut week_done = true;
+        for objective_id in objective_ids {
+            let mastered = mastery
+                .get(objective_id)
+                .and_then(|m| m.get("state"))
+                .and_then(|v| v.as_str())
+                == Some("mastered");
+            let source = if mastered {
+                Some("mastery")
+            } else if from_assignments.contains(objective_id) {
+                Some("assignment")
+            } else {
+                None
+            };
+            week_done &= source.is_some();
+            objectives.push(serde_json::json!({
+                "objectiveId": objective_id,
+                "week": week,
+                "done": source.is_some(),
+                "doneBy": source,
+            }));
+        }
+        in_order &= week_done;
+        if in_order {
+            weeks_done = *week;
+        }
+    }
+
+    let weeks_ahead = weeks_done as i64 - weeks_elapsed as i64;
+    let status = match weeks_ahead {
+        n if n > 0 => "ahead",
+        0 => "on_track",
+        _ => "behind",
+    };
+    let result = serde_json::json!({
+        "learnerId": learner_id,
+        "term": term,
+        "currentWeek": current_week,
+        "totalWeeks": total_weeks,
+        "weeksElapsed": weeks_elapsed,
+        "weeksDone": weeks_done,
+        "weeksAhead": weeks_ahead,
+        "status": status,
+        "objectives": objectives,
+    });
+    Ok(result.to_string())
+}
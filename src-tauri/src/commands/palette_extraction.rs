@@ -0,0 +1,321 @@
+use image::imageops::FilterType;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::commands::{app_lock, session_mode};
+
+// Images are shrunk to fit this square before clustering; more pixels
+// don't change the palette
+const SAMPLE_SIDE: u32 = 128;
+
+// Dominant colors found in the image
+const CLUSTER_COUNT: usize = 6;
+const CLUSTER_ROUNDS: usize = 12;
+
+// WCAG contrast ratios: normal text, and large text or graphics
+const TEXT_CONTRAST: f64 = 4.5;
+const GRAPHIC_CONTRAST: f64 = 3.0;
+
+// Lightness of the tint used behind drawing areas
+const TINT_LIGHTNESS: f64 = 0.94;
+
+type Rgb = [f64; 3];
+
+// Dominant colors with their share of the image's pixels
+type Clusters = Vec<(Rgb, f64)>;
+
+// Helper to format a color as `#RRGGBB`
+fn hex(color: Rgb) -> String {
+    let [r, g, b] = color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+// Helper to get a color's WCAG relative luminance
+fn luminance(color: Rgb) -> f64 {
+    let [r, g, b] = color.map(|c| {
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    });
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+// Helper to get the WCAG contrast ratio of two colors
+fn contrast(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+// Helper to convert RGB to hue (0-360), saturation, and lightness
+fn to_hsl([r, g, b]: Rgb) -> [f64; 3] {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    if max == min {
+        return [0.0, 0.0, lightness];
+    }
+    let delta = max - min;
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    [hue, saturation.min(1.0), lightness]
+}
+
+// Helper to convert hue, saturation, and lightness back to RGB
+fn from_hsl([hue, saturation, lightness]: [f64; 3]) -> Rgb {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let m = lightness - chroma / 2.0;
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    [r + m, g + m, b + m]
+}
+
+// Helper to darken a color, keeping its hue, until it reaches `ratio`
+// contrast against white
+fn darken_for_white(color: Rgb, ratio: f64) -> Rgb {
+    let [hue, saturation, mut lightness] = to_hsl(color);
+    let mut adjusted = color;
+    while contrast(adjusted, [1.0; 3]) < ratio && lightness > 0.0 {
+        lightness = (lightness - 0.02).max(0.0);
+        adjusted = from_hsl([hue, saturation, lightness]);
+    }
+    adjusted
+}
+
+// Helper to get the squared distance between two colors
+fn distance(a: Rgb, b: Rgb) -> f64 {
+    (0..3).map(|i| (a[i] - b[i]).powi(2)).sum()
+}
+
+// Helper to group pixels into dominant colors with k-means. Starts from
+// the farthest-point spread so the result doesn't depend on chance.
+// Returns (color, share of pixels), most common first.
+fn dominant_colors(pixels: &[Rgb]) -> Clusters {
+    let mean = pixels.iter().fold([0.0; 3], |acc, p| {
+        [acc[0] + p[0], acc[1] + p[1], acc[2] + p[2]]
+    });
+    let mean = mean.map(|c| c / pixels.len() as f64);
+    let mut centers = vec![mean];
+    while centers.len() < CLUSTER_COUNT {
+        let farthest = pixels.iter().copied().max_by(|a, b| {
+            let da = centers.iter().map(|c| distance(*a, *c)).fold(f64::MAX, f64::min);
+            let db = centers.iter().map(|c| distance(*b, *c)).fold(f64::MAX, f64::min);
+            da.total_cmp(&db)
+        });
+        match farthest {
+            Some(p) if !centers.contains(&p) => centers.push(p),
+            _ => break,
+        }
+    }
+
+    let nearest = |p: Rgb, centers: &[Rgb]| {
+        (0..centers.len())
+            .min_by(|&a, &b| distance(p, centers[a]).total_cmp(&distance(p, centers[b])))
+            .unwrap_or(0)
+    };
+    let mut counts = vec![0usize; centers.len()];
+    for _ in 0..CLUSTER_ROUNDS {
+        let mut sums = vec![[0.0; 3]; centers.len()];
+        counts = vec![0; centers.len()];
+        for &p in pixels {
+            let i = nearest(p, &centers);
+            for c in 0..3 {
+                sums[i][c] += p[c];
+            }
+            counts[i] += 1;
+        }
+        for (i, center) in centers.iter_mut().enumerate() {
+            if counts[i] > 0 {
+                *center = sums[i].map(|s| s / counts[i] as f64);
+            }
+        }
+    }
+
+    let mut clusters: Clusters = centers
+        .into_iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .map(|(center, count)| (center, count as f64 / pixels.len() as f64))
+        .collect();
+    clusters.sort_by(|a, b| b.1.total_cmp(&a.1));
+    clusters
+}
+
+// Helper to pick the design pack palette (primary, secondary, tint,
+// accent) from the dominant colors. Primary is the most common colorful
+// color darkened for readable text; accent the most saturated color of a
+// different hue.
+fn design_palette(dominant: &[(Rgb, f64)]) -> [Rgb; 4] {
+    let colorful = |c: &Rgb| {
+        let [_, saturation, lightness] = to_hsl(*c);
+        saturation > 0.2 && lightness > 0.1 && lightness < 0.9
+    };
+    let base = dominant
+        .iter()
+        .map(|(c, _)| *c)
+        .find(colorful)
+        .or_else(|| dominant.first().map(|(c, _)| *c))
+        .unwrap_or([0.12, 0.16, 0.22]);
+    let [base_hue, base_saturation, _] = to_hsl(base);
+
+    let primary = darken_for_white(base, TEXT_CONTRAST + 2.0);
+    let secondary = darken_for_white(
+        from_hsl([base_hue, base_saturation * 0.4, 0.45]),
+        TEXT_CONTRAST,
+    );
+    let tint = from_hsl([base_hue, base_saturation.min(0.6), TINT_LIGHTNESS]);
+    let hue_gap = |c: &Rgb| {
+        let gap = (to_hsl(*c)[0] - base_hue).abs();
+        gap.min(360.0 - gap)
+    };
+    let accent = dominant
+        .iter()
+        .map(|(c, _)| *c)
+        .filter(|c| colorful(c) && hue_gap(c) > 30.0)
+        .max_by(|a, b| to_hsl(*a)[1].total_cmp(&to_hsl(*b)[1]))
+        // Without a second hue, use the complement of the base color
+        .unwrap_or_else(|| {
+            from_hsl([(base_hue + 180.0) % 360.0, base_saturation.max(0.5), 0.5])
+        });
+    let accent = darken_for_white(accent, GRAPHIC_CONTRAST);
+
+    [primary, secondary, tint, accent]
+}
+
+// Helper to pick readable text for a background: black, white, or the
+// palette's primary, whichever contrasts most
+fn text_pairing(background: Rgb, primary: Rgb) -> Value {
+    let (text, ratio) = [[0.0; 3], [1.0; 3], primary]
+        .into_iter()
+        .map(|text| (text, contrast(background, text)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or(([0.0; 3], contrast(background, [0.0; 3])));
+    serde_json::json!({
+        "background": hex(background),
+        "text": hex(text),
+        "contrast": (ratio * 100.0).round() / 100.0,
+        "passesAA": ratio >= TEXT_CONTRAST,
+    })
+}
+
+// Helper to read an image and cluster it. CPU-bound; runs on a blocking
+// thread.
+fn analyze(path: &Path) -> Result<(Clusters, [Rgb; 4]), String> {
+    let image = image::ImageReader::open(path)
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .decode()
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .resize(SAMPLE_SIDE, SAMPLE_SIDE, FilterType::Triangle)
+        .to_rgba8();
+    // Transparent areas (around a logo) aren't part of its colors
+    let pixels: Vec<Rgb> = image
+        .pixels()
+        .filter(|p| p.0[3] >= 128)
+        .map(|p| [p.0[0], p.0[1], p.0[2]].map(|c| c as f64 / 255.0))
+        .collect();
+    if pixels.is_empty() {
+        return Err("Image has no visible colors".to_string());
+    }
+    let dominant = dominant_colors(&pixels);
+    let palette = design_palette(&dominant);
+    Ok((dominant, palette))
+}
+
+// ============================================
+// Palette Extraction Commands
+// ============================================
+
+/// Derive a design pack palette from an image such as a logo or photo.
+/// Returns `{pack, dominantColors: [{color, share}], pairings: [{background,
+/// text, contrast, passesAA}]}`, where `pack` is an unsaved draft design
+/// pack (primary, secondary, tint, accent) to edit and pass to
+/// `save_design_pack`. The primary and secondary colors are darkened as
+/// needed to be readable as text on white.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn extract_palette(
+    app_handle: tauri::AppHandle,
+    image_path: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let path = Path::new(&image_path).to_path_buf();
+    let (dominant, palette) = tauri::async_runtime::spawn_blocking(move || analyze(&path))
+        .await
+        .map_err(|e| format!("Failed to extract palette: {}", e))??;
+
+    let name = Path::new(&image_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Image".to_string());
+    let mean_saturation = dominant
+        .iter()
+        .map(|(c, share)| to_hsl(*c)[1] * share)
+        .sum::<f64>();
+    let tone = if mean_saturation > 0.5 {
+        "playful"
+    } else if mean_saturation < 0.2 {
+        "calm"
+    } else {
+        "friendly"
+    };
+    let now = chrono::Utc::now().to_rfc3339();
+    let pack = serde_json::json!({
+        "packId": uuid::Uuid::new_v4().to_string(),
+        "name": format!("{} Theme", name),
+        "description": format!("Colors taken from {}.", name),
+        "items": [],
+        "parsedSummary": {
+            "palette": palette.map(hex),
+            "tone": tone,
+            "typography": "Simple sans-serif headings with large, readable body text",
+            "styleHints": [
+                "Headings and rules in the primary color",
+                "Accent color for numbers and highlights",
+                "Tinted backgrounds for drawing areas",
+            ],
+        },
+        "builtIn": false,
+        "createdAt": now,
+        "updatedAt": now,
+    });
+
+    let dominant_colors: Vec<Value> = dominant
+        .iter()
+        .map(|(color, share)| {
+            serde_json::json!({
+                "color": hex(*color),
+                "share": (share * 1000.0).round() / 1000.0,
+            })
+        })
+        .collect();
+    let pairings: Vec<Value> = palette
+        .iter()
+        .chain(dominant.iter().map(|(c, _)| c))
+        .map(|background| text_pairing(*background, palette[0]))
+        .collect();
+
+    let response = serde_json::json!({
+        "pack": pack,
+        "dominantColors": dominant_colors,
+        "pairings": pairings,
+    });
+    Ok(response.to_string())
+}
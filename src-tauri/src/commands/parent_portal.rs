@@ -0,0 +1,312 @@
+use chrono::{Days, NaiveDate};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tokio::fs;
+
+use crate::commands::worksheet_assembly::escape_html;
+use crate::commands::{
+    app_lock, audit_log, calendar, learner_bundle, learner_storage, locale, project_stats,
+    reminders, session_mode,
+};
+
+// How far back "recent work" goes, and how far ahead "upcoming" looks
+const RECENT_DAYS: u64 = 30;
+const UPCOMING_DAYS: u64 = 30;
+
+// Pages of the site, as (file name, navigation label)
+const PAGES: [(&str, &str); 3] = [
+    ("index.html", "Overview"),
+    ("progress.html", "Progress"),
+    ("work.html", "Recent Work"),
+];
+
+// Mastery states in the order the summary lists them, with their labels
+const STATES: [(&str, &str); 4] = [
+    ("mastered", "Mastered"),
+    ("in_progress", "In progress"),
+    ("needs_review", "Needs review"),
+    ("not_started", "Not started"),
+];
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Helper to read a string field, or "" when missing
+fn text<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// Show an RFC 3339 timestamp or `YYYY-MM-DD` date as a local date
+pub(crate) fn display_date(timestamp: &str) -> String {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(timestamp) {
+        return time.with_timezone(&chrono::Local).format("%b %-d, %Y").to_string();
+    }
+    NaiveDate::parse_from_str(timestamp, DATE_FORMAT)
+        .map(|date| date.format("%b %-d, %Y").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+/// Label a mastery state for people in the current language, e.g. "Needs
+/// review"
+pub(crate) fn state_label(app_handle: &tauri::AppHandle, state: &str) -> String {
+    if let Some(label) = locale::lookup(app_handle, &format!("mastery.{}", state)) {
+        return label;
+    }
+    STATES
+        .iter()
+        .find(|(key, _)| *key == state)
+        .map_or(state, |(_, label)| label)
+        .to_string()
+}
+
+/// Render an HTML table of text cells, or the `empty` note when there are
+/// no rows
+pub(crate) fn table(headers: &[&str], rows: &[Vec<String>], empty: &str) -> String {
+    if rows.is_empty() {
+        return format!("<p class=\"empty\">{}</p>\n", escape_html(empty));
+    }
+    let mut html = String::from("<table>\n<tr>");
+    for header in headers {
+        html.push_str(&format!("<th>{}</th>", escape_html(header)));
+    }
+    html.push_str("</tr>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str(&format!("<td>{}</td>", escape_html(cell)));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+// Helper to wrap a page's content with the shared header, navigation, and
+// styles; everything is inline so the site works from any folder
+fn render_page(learner_name: &str, generated: &str, current: &str, body: &str) -> String {
+    let nav: String = PAGES
+        .iter()
+        .map(|(file, label)| {
+            let class = if *file == current { " class=\"current\"" } else { "" };
+            format!("<a href=\"{}\"{}>{}</a>", file, class, label)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{name}</title>\n<style>\n\
+         body {{ font-family: Arial, Helvetica, sans-serif; color: #1F2937; \
+         max-width: 48em; margin: 2em auto; padding: 0 1em; }}\n\
+         nav a {{ margin-right: 1em; color: #4B5563; }}\n\
+         nav a.current {{ font-weight: bold; color: #1F2937; }}\n\
+         table {{ border-collapse: collapse; width: 100%; margin-bottom: 1.5em; }}\n\
+         th, td {{ text-align: left; padding: 0.4em; border-bottom: 1px solid #E5E7EB; }}\n\
+         .empty {{ color: #9CA3AF; }}\n\
+         footer {{ margin-top: 3em; color: #9CA3AF; font-size: 0.85em; }}\n\
+         </style>\n</head>\n<body>\n<header>\n<h1>{name}</h1>\n<nav>{nav}</nav>\n\
+         </header>\n{body}<footer>Updated {generated}</footer>\n</body>\n</html>\n",
+        name = escape_html(learner_name),
+    )
+}
+
+// ============================================
+// Parent Portal Commands
+// ============================================
+
+/// Export a learner's progress as a static website in `out_dir`: an
+/// overview (mastery summary and what's coming up in the next 30 days),
+/// a progress page listing every tracked objective, and the work of the
+/// last 30 days. The pages link to each other and need no server, so they
+/// can be opened from a shared drive. Returns the path of `index.html`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn export_parent_portal(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    out_dir: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let out_dir = Path::new(&out_dir);
+    if !out_dir.is_absolute() {
+        return Err(format!("Output folder must be an absolute path: {}", out_dir.display()));
+    }
+
+    let profile = learner_bundle::read_learner_profile(&app_handle, &learner_id).await?;
+    let learner_name = profile
+        .get("displayName")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Learner");
+    let mastery = project_stats::read_mastery(&app_handle, &learner_id).await?;
+    let entries = calendar::read_entries(&app_handle, &learner_id).await?;
+    let learner_dir = learner_storage::get_learner_dir(&app_handle, &learner_id)?;
+    let checks_path = learner_dir.join(learner_storage::QUICK_CHECKS_FILE);
+    let checks: Vec<Value> = match fs::read_to_string(&checks_path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()),
+        Err(_) => Vec::new(),
+    };
+
+    let now = chrono::Local::now();
+    let today = now.date_naive();
+    let recent_from = today - Days::new(RECENT_DAYS);
+    let upcoming_to = today + Days::new(UPCOMING_DAYS);
+    let generated = now.format("%b %-d, %Y %H:%M").to_string();
+    let entry_date = |e: &Value| NaiveDate::parse_from_str(text(e, "date"), DATE_FORMAT).ok();
+    let is_completed = |e: &Value| e.get("completed").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    // Overview: counts by state per subject, then what's coming up
+    let mut by_subject: BTreeMap<&str, BTreeMap<&str, usize>> = BTreeMap::new();
+    for record in mastery.values() {
+        let subject = Some(text(record, "subject")).filter(|s| !s.is_empty());
+        let state = Some(text(record, "state")).filter(|s| !s.is_empty());
+        *by_subject
+            .entry(subject.unwrap_or("Other"))
+            .or_default()
+            .entry(state.unwrap_or("not_started"))
+            .or_default() += 1;
+    }
+    let summary_rows: Vec<Vec<String>> = by_subject
+        .iter()
+        .map(|(subject, counts)| {
+            let mut row = vec![subject.to_string()];
+            row.extend(STATES.iter().map(|(state, _)| {
+                counts.get(state).copied().unwrap_or(0).to_string()
+            }));
+            row
+        })
+        .collect();
+    let mut summary_headers = vec!["Subject"];
+    summary_headers.extend(STATES.iter().map(|(_, label)| *label));
+
+    let mut upcoming: Vec<(NaiveDate, String, String)> = entries
+        .iter()
+        .filter(|e| !is_completed(e))
+        .filter_map(|e| {
+            let date = entry_date(e).filter(|d| *d >= today && *d <= upcoming_to)?;
+            Some((date, "Lesson".to_string(), text(e, "title").to_string()))
+        })
+        .collect();
+    for reminder in reminders::read_reminders(&app_handle).await? {
+        let for_learner = text(&reminder, "learnerId") == learner_id;
+        let dismissed = reminder.get("dismissedAt").is_some_and(|v| !v.is_null());
+        if text(&reminder, "kind") != "review_due" || !for_learner || dismissed {
+            continue;
+        }
+        // Overdue reviews are still coming up, as of today
+        let due = chrono::DateTime::parse_from_rfc3339(text(&reminder, "dueAt"))
+            .ok()
+            .map(|t| t.with_timezone(&chrono::Local).date_naive().max(today));
+        if let Some(date) = due.filter(|d| *d <= upcoming_to) {
+            upcoming.push((date, "Review".to_string(), text(&reminder, "title").to_string()));
+        }
+    }
+    upcoming.sort();
+    let upcoming_rows: Vec<Vec<String>> = upcoming
+        .into_iter()
+        .map(|(date, kind, title)| vec![date.format("%a %b %-d").to_string(), kind, title])
+        .collect();
+    let review_rows: Vec<Vec<String>> = mastery
+        .iter()
+        .filter(|(_, record)| text(record, "state") == "needs_review")
+        .map(|(objective_id, record)| {
+            vec![objective_id.clone(), text(record, "subject").to_string()]
+        })
+        .collect();
+
+    let overview = format!(
+        "<h2>Progress Summary</h2>\n{}<h2>Coming Up</h2>\n{}<h2>Needs Review</h2>\n{}",
+        table(&summary_headers, &summary_rows, "No objectives tracked yet."),
+        table(&["Date", "Type", "What"], &upcoming_rows, "Nothing scheduled."),
+        table(&["Objective", "Subject"], &review_rows, "Nothing needs review."),
+    );
+
+    // Progress: every tracked objective
+    let mut objectives: Vec<(&String, &Value)> = mastery.iter().collect();
+    objectives.sort_by_key(|(objective_id, record)| (text(record, "subject"), *objective_id));
+    let progress_rows: Vec<Vec<String>> = objectives
+        .iter()
+        .map(|(objective_id, record)| {
+            let score = record
+                .get("lastScore")
+                .and_then(|v| v.as_f64())
+                .map(|s| format!("{:.0}%", s))
+                .unwrap_or_default();
+            vec![
+                objective_id.to_string(),
+                text(record, "subject").to_string(),
+                state_label(&app_handle, text(record, "state")),
+                score,
+                display_date(text(record, "lastUpdated")),
+            ]
+        })
+        .collect();
+    let progress = format!(
+        "<h2>Objectives</h2>\n{}",
+        table(
+            &["Objective", "Subject", "Status", "Last score", "Updated"],
+            &progress_rows,
+            "No objectives tracked yet.",
+        )
+    );
+
+    // Recent work: completed lessons and quick checks, newest first
+    let mut completed: Vec<(NaiveDate, &str)> = entries
+        .iter()
+        .filter(|e| is_completed(e))
+        .filter_map(|e| {
+            let date = entry_date(e).filter(|d| *d >= recent_from && *d <= today)?;
+            Some((date, text(e, "title")))
+        })
+        .collect();
+    completed.sort_by(|a, b| b.cmp(a));
+    let completed_rows: Vec<Vec<String>> = completed
+        .into_iter()
+        .map(|(date, title)| vec![date.format("%b %-d, %Y").to_string(), title.to_string()])
+        .collect();
+    let mut recent_checks: Vec<&Value> = checks
+        .iter()
+        .filter(|c| {
+            chrono::DateTime::parse_from_rfc3339(text(c, "createdAt"))
+                .is_ok_and(|t| t.with_timezone(&chrono::Local).date_naive() >= recent_from)
+        })
+        .collect();
+    recent_checks.sort_by_key(|c| std::cmp::Reverse(text(c, "createdAt")));
+    let check_rows: Vec<Vec<String>> = recent_checks
+        .iter()
+        .map(|c| {
+            let score = c.get("score").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let correct = c.get("correctAnswers").and_then(|v| v.as_u64()).unwrap_or(0);
+            let total = c.get("totalQuestions").and_then(|v| v.as_u64()).unwrap_or(0);
+            vec![
+                display_date(text(c, "createdAt")),
+                text(c, "objectiveId").to_string(),
+                format!("{:.0}% ({} of {})", score, correct, total),
+            ]
+        })
+        .collect();
+    let work = format!(
+        "<h2>Completed Lessons</h2>\n{}<h2>Quick Checks</h2>\n{}",
+        table(&["Date", "Lesson"], &completed_rows, "No lessons completed recently."),
+        table(
+            &["Date", "Objective", "Score"],
+            &check_rows,
+            "No quick checks taken recently.",
+        ),
+    );
+
+    fs::create_dir_all(out_dir)
+        .await
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+    for ((file, _), body) in PAGES.iter().zip([overview, progress, work]) {
+        let html = render_page(learner_name, &generated, file, &body);
+        fs::write(out_dir.join(file), html)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", file, e))?;
+    }
+
+    audit_log::record(&app_handle, "export_parent_portal", "learner", &[&learner_id]).await;
+
+    Ok(out_dir.join(PAGES[0].0).to_string_lossy().to_string())
+}
@@ -0,0 +1,217 @@
+use printpdf::path::PaintMode;
+use printpdf::{
+    Color, ColorBits, ColorSpace, Image, ImageTransform, ImageXObject, Line, Mm, PdfDocument,
+    Point, Pt, Px, Rect, Rgb, TextMatrix,
+};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::print_layout::{self, Document, Mark, PrintFonts};
+use crate::commands::settings_storage::PrintLayoutSettings;
+use crate::commands::{app_lock, audit_log, disk_space, session_mode};
+
+/// Managed state remembering the PDFs exported while the app runs, by
+/// artifact, so only those can be emailed
+#[derive(Default)]
+pub struct PdfExportState {
+    exported: Mutex<HashMap<String, Vec<PathBuf>>>,
+}
+
+/// Check that `path` is a PDF exported from `artifact_id` by `export_pdf`
+/// while the app has been running, returning its canonical path
+pub(crate) fn exported_pdf(
+    app_handle: &tauri::AppHandle,
+    artifact_id: &str,
+    path: &str,
+) -> Result<PathBuf, String> {
+    let not_exported = || "Only a PDF exported from this artifact can be attached".to_string();
+    let path = Path::new(path);
+    if !path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")) {
+        return Err(not_exported());
+    }
+    let path = path
+        .canonicalize()
+        .map_err(|_| format!("File not found: {}", path.display()))?;
+    let state = app_handle.state::<PdfExportState>();
+    let exported = state.exported.lock().unwrap();
+    exported
+        .get(artifact_id)
+        .filter(|paths| paths.contains(&path))
+        .map(|_| path)
+        .ok_or_else(not_exported)
+}
+
+// Helper to convert points to the millimetres printpdf positions things in
+fn mm(points: f32) -> Mm {
+    Mm::from(Pt(points))
+}
+
+// Helper to write a laid-out document as a PDF, embedding the print fonts
+fn write_pdf(document: &Document, fonts: &PrintFonts, title: &str) -> Result<Vec<u8>, String> {
+    let (width, height) = (document.width, document.height);
+    let (pdf, first_page, first_layer) =
+        PdfDocument::new(title, mm(width), mm(height), "Content");
+    let regular = pdf
+        .add_external_font(Cursor::new(&fonts.regular.data))
+        .map_err(|e| format!("Failed to embed font: {}", e))?;
+    let bold = pdf
+        .add_external_font(Cursor::new(&fonts.bold.data))
+        .map_err(|e| format!("Failed to embed font: {}", e))?;
+
+    for (index, page) in document.pages.iter().enumerate() {
+        let (page_index, layer_index) = if index == 0 {
+            (first_page, first_layer)
+        } else {
+            pdf.add_page(mm(width), mm(height), "Content")
+        };
+        let layer = pdf.get_page(page_index).get_layer(layer_index);
+        layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+        layer.set_outline_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+        layer.set_outline_thickness(print_layout::STROKE_WIDTH);
+
+        // PDF measures up from the bottom of the page
+        for mark in &page.marks {
+            match mark {
+                Mark::Text {
+                    x,
+                    y,
+                    size,
+                    bold: is_bold,
+                    text,
+                } => {
+                    let font = if *is_bold { &bold } else { &regular };
+                    layer.use_text(text.as_str(), *size, mm(*x), mm(height - y), font);
+                }
+                Mark::Line { x1, y1, x2, y2 } => layer.add_line(Line {
+                    points: vec![
+                        (Point::new(mm(*x1), mm(height - y1)), false),
+                        (Point::new(mm(*x2), mm(height - y2)), false),
+                    ],
+                    is_closed: false,
+                }),
+                Mark::Rect {
+                    x,
+                    y,
+                    width,
+                    height: rect_height,
+                } => layer.add_rect(
+                    Rect::new(
+                        mm(*x),
+                        mm(height - y - rect_height),
+                        mm(x + width),
+                        mm(height - y),
+                    )
+                    .with_mode(PaintMode::Stroke),
+                ),
+                Mark::Image {
+                    x,
+                    y,
+                    width,
+                    height: image_height,
+                    image,
+                } => {
+                    let object = ImageXObject {
+                        width: Px(image.width() as usize),
+                        height: Px(image.height() as usize),
+                        color_space: ColorSpace::Rgb,
+                        bits_per_component: ColorBits::Bit8,
+                        interpolate: true,
+                        image_data: image.as_raw().clone(),
+                        image_filter: None,
+                        smask: None,
+                        clipping_bbox: None,
+                    };
+                    // At 72 DPI one pixel is one point, so scale by the
+                    // placed size over the pixel size
+                    let transform = ImageTransform {
+                        translate_x: Some(mm(*x)),
+                        translate_y: Some(mm(height - y - image_height)),
+                        scale_x: Some(width / image.width() as f32),
+                        scale_y: Some(image_height / image.height() as f32),
+                        dpi: Some(72.0),
+                        ..Default::default()
+                    };
+                    Image::from(object).add_to_layer(layer.clone(), transform);
+                }
+                Mark::Watermark {
+                    x,
+                    y,
+                    size,
+                    angle,
+                    text,
+                } => {
+                    let gray = print_layout::WATERMARK_GRAY;
+                    let (sin, cos) = angle.sin_cos();
+                    layer.set_fill_color(Color::Rgb(Rgb::new(gray, gray, gray, None)));
+                    layer.begin_text_section();
+                    layer.set_font(&bold, *size);
+                    layer.set_text_matrix(TextMatrix::Raw([cos, sin, -sin, cos, *x, height - y]));
+                    layer.write_text(text.as_str(), &bold);
+                    layer.end_text_section();
+                    layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+                }
+            }
+        }
+    }
+
+    pdf.save_to_bytes()
+        .map_err(|e| format!("Failed to write PDF: {}", e))
+}
+
+// ============================================
+// PDF Export Commands
+// ============================================
+
+/// Export an artifact as a PDF laid out by the backend: page size,
+/// orientation, margins, header, footer, and page numbers come from
+/// `layout` when given, otherwise from the `printLayout` settings. With
+/// `learner_id`, that learner's name fills `{learner}` in the header and
+/// footer. Text uses an installed sans-serif font, embedded in the file.
+/// Returns the number of pages written. The file can then be emailed with
+/// `send_artifact_email`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn export_pdf(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    output_path: String,
+    layout: Option<PrintLayoutSettings>,
+    learner_id: Option<String>,
+) -> Result<usize, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let (document, title) =
+        print_layout::layout_artifact(&app_handle, &artifact_id, layout, learner_id.as_deref())
+            .await?;
+    let pages = document.pages.len();
+
+    let handle = app_handle.clone();
+    let pdf = tauri::async_runtime::spawn_blocking(move || {
+        let fonts = print_layout::fonts(&handle)?;
+        write_pdf(&document, &fonts, &title)
+    })
+    .await
+    .map_err(|e| format!("Failed to export PDF: {}", e))??;
+
+    disk_space::ensure_available(Path::new(&output_path), pdf.len() as u64)?;
+    fs::write(&output_path, pdf)
+        .await
+        .map_err(|e| format!("Failed to write PDF file: {}", e))?;
+    if let Ok(path) = Path::new(&output_path).canonicalize() {
+        let state = app_handle.state::<PdfExportState>();
+        let mut exported = state.exported.lock().unwrap();
+        let paths = exported.entry(artifact_id.clone()).or_default();
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    audit_log::record(&app_handle, "export_pdf", "artifact", &[&artifact_id]).await;
+
+    Ok(pages)
+}
@@ -0,0 +1,213 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::{Manager, Runtime};
+use tracing::span;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+// Command timings kept in memory (oldest are dropped first)
+const RING_CAPACITY: usize = 1000;
+
+// Prefix of the span targets created by `#[tracing::instrument]` on commands
+const COMMAND_TARGET_PREFIX: &str = concat!(env!("CARGO_CRATE_NAME"), "::commands::");
+
+/// Timing of a single command invocation
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommandTiming {
+    command: String,
+    started_at: String,
+    duration_ms: f64,
+    payload_bytes: Option<usize>,
+    success: bool,
+}
+
+#[derive(Default)]
+struct PerformanceData {
+    timings: VecDeque<CommandTiming>,
+    // Payload sizes seen by the invoke handler, waiting for their command span
+    pending_payloads: HashMap<String, VecDeque<usize>>,
+}
+
+/// Managed state holding recent command timings
+pub struct PerformanceState {
+    data: Arc<Mutex<PerformanceData>>,
+}
+
+// Per-span data stored in the span's extensions
+struct SpanTiming {
+    started: Instant,
+    started_at: chrono::DateTime<chrono::Utc>,
+    payload_bytes: Option<usize>,
+    failed: bool,
+}
+
+/// Tracing layer that times command spans and notes whether they failed
+pub struct PerformanceLayer {
+    data: Arc<Mutex<PerformanceData>>,
+}
+
+impl<S> Layer<S> for PerformanceLayer
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let payload_bytes = self
+            .data
+            .lock()
+            .unwrap()
+            .pending_payloads
+            .get_mut(span.name())
+            .and_then(|queue| queue.pop_front());
+        span.extensions_mut().insert(SpanTiming {
+            started: Instant::now(),
+            started_at: chrono::Utc::now(),
+            payload_bytes,
+            failed: false,
+        });
+    }
+
+    // `#[tracing::instrument(err)]` emits an error event inside the span on failure
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.event_span(event) {
+            if let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>() {
+                timing.failed = true;
+            }
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(timing) = extensions.get::<SpanTiming>() else {
+            return;
+        };
+
+        let entry = CommandTiming {
+            command: span.name().to_string(),
+            started_at: timing.started_at.to_rfc3339(),
+            duration_ms: timing.started.elapsed().as_secs_f64() * 1000.0,
+            payload_bytes: timing.payload_bytes,
+            success: !timing.failed,
+        };
+        let mut data = self.data.lock().unwrap();
+        if data.timings.len() == RING_CAPACITY {
+            data.timings.pop_front();
+        }
+        data.timings.push_back(entry);
+    }
+}
+
+/// Create the performance layer (to install in the subscriber) and the state
+/// that reads its timings. The layer only sees command spans and errors.
+pub fn layer<S>() -> (impl Layer<S>, PerformanceState)
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    let data = Arc::new(Mutex::new(PerformanceData::default()));
+    let layer = PerformanceLayer { data: data.clone() }.with_filter(
+        tracing_subscriber::filter::filter_fn(|metadata| {
+            if metadata.is_span() {
+                metadata.target().starts_with(COMMAND_TARGET_PREFIX)
+            } else {
+                *metadata.level() == tracing::Level::ERROR
+            }
+        }),
+    );
+    (layer, PerformanceState { data })
+}
+
+/// Wrap the app's invoke handler to note each command's request payload
+/// size. The size is matched to the command's span when it starts.
+pub fn track_payloads<R, H>(handler: H) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    H: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let payload_bytes = match invoke.message.payload() {
+            InvokeBody::Json(value) => value.to_string().len(),
+            InvokeBody::Raw(bytes) => bytes.len(),
+        };
+        if let Some(state) = invoke.message.webview_ref().try_state::<PerformanceState>() {
+            let mut data = state.data.lock().unwrap();
+            let queue = data
+                .pending_payloads
+                .entry(invoke.message.command().to_string())
+                .or_default();
+            // Commands rejected before their span starts would otherwise pile up
+            if queue.len() < RING_CAPACITY {
+                queue.push_back(payload_bytes);
+            }
+        }
+        handler(invoke)
+    }
+}
+
+// Helper to get the value at a percentile of sorted durations
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let index = ((sorted.len() - 1) as f64 * pct).round() as usize;
+    sorted[index]
+}
+
+// ============================================
+// Performance Metrics Commands
+// ============================================
+
+/// Get recent command timings and a per-command summary (count, failures,
+/// average/p95/max duration, largest payload), slowest commands first
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_performance_metrics(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let timings: Vec<CommandTiming> = {
+        let state = app_handle.state::<PerformanceState>();
+        let data = state.data.lock().unwrap();
+        data.timings.iter().cloned().collect()
+    };
+
+    let mut by_command: BTreeMap<&str, Vec<&CommandTiming>> = BTreeMap::new();
+    for timing in &timings {
+        by_command.entry(&timing.command).or_default().push(timing);
+    }
+
+    let mut summary: Vec<serde_json::Value> = by_command
+        .into_iter()
+        .map(|(command, entries)| {
+            let mut durations: Vec<f64> = entries.iter().map(|t| t.duration_ms).collect();
+            durations.sort_by(|a, b| a.total_cmp(b));
+            let total: f64 = durations.iter().sum();
+            serde_json::json!({
+                "command": command,
+                "count": entries.len(),
+                "failures": entries.iter().filter(|t| !t.success).count(),
+                "avgMs": total / entries.len() as f64,
+                "p95Ms": percentile(&durations, 0.95),
+                "maxMs": durations.last().copied().unwrap_or(0.0),
+                "maxPayloadBytes": entries.iter().filter_map(|t| t.payload_bytes).max(),
+            })
+        })
+        .collect();
+    summary.sort_by(|a, b| {
+        let a = a["p95Ms"].as_f64().unwrap_or(0.0);
+        let b = b["p95Ms"].as_f64().unwrap_or(0.0);
+        b.total_cmp(&a)
+    });
+
+    let response = serde_json::json!({
+        "summary": summary,
+        "recent": timings,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,680 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+use tokio::fs;
+
+use crate::archive;
+use crate::commands::{
+    app_lock, artifact_analysis, audit_log, library_storage, session_mode, settings_storage,
+};
+
+const PLUGINS_DIR: &str = "plugins";
+const REGISTRY_FILE: &str = "registry.json";
+const MANIFEST_FILE: &str = "plugin.json";
+const SIGNATURE_FILE: &str = "plugin.json.minisig";
+
+// Largest file accepted in a plugin package
+const MAX_FILE_BYTES: usize = 16 * 1024 * 1024;
+
+// Capabilities a plugin can ask for, as (name, what it allows)
+const CAPABILITIES: &[(&str, &str)] = &[
+    ("artifacts.read", "Read library artifacts"),
+    ("files.write", "Write files to folders you choose"),
+    ("learners.read", "Read learner answers to grade them"),
+];
+
+// What each kind of contribution needs to run
+const EXPORTER_CAPABILITIES: &[&str] = &["artifacts.read", "files.write"];
+const GRADER_CAPABILITIES: &[&str] = &["learners.read"];
+
+// Installed plugins' state, keyed by plugin ID
+type Registry = BTreeMap<String, Value>;
+
+// Helper to read a string field, or "" when missing
+fn text<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+// Helper to read an array field, or nothing when missing
+fn items<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    value.get(key).and_then(|v| v.as_array()).into_iter().flatten()
+}
+
+// Helper to get the plugins directory
+fn get_plugins_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(PLUGINS_DIR))
+}
+
+// Helper to check a plugin or contribution ID is safe to use as a folder
+// name, e.g. "music-theory"
+fn validate_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        return Err(format!("Invalid plugin ID: {}", id));
+    }
+    Ok(())
+}
+
+// Helper to get an installed plugin's folder
+fn get_plugin_dir(app_handle: &tauri::AppHandle, plugin_id: &str) -> Result<PathBuf, String> {
+    validate_id(plugin_id)?;
+    Ok(get_plugins_dir(app_handle)?.join(plugin_id))
+}
+
+// Helper to read the installed plugins' state
+async fn read_registry(app_handle: &tauri::AppHandle) -> Result<Registry, String> {
+    let registry_path = get_plugins_dir(app_handle)?.join(REGISTRY_FILE);
+    if !registry_path.exists() {
+        return Ok(Registry::new());
+    }
+    let content = fs::read_to_string(&registry_path)
+        .await
+        .map_err(|e| format!("Failed to read plugin registry: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_default())
+}
+
+// Helper to write the installed plugins' state
+async fn write_registry(app_handle: &tauri::AppHandle, registry: &Registry) -> Result<(), String> {
+    let plugins_dir = get_plugins_dir(app_handle)?;
+    fs::create_dir_all(&plugins_dir)
+        .await
+        .map_err(|e| format!("Failed to create plugins directory: {}", e))?;
+    let content = serde_json::to_string_pretty(registry)
+        .map_err(|e| format!("Failed to serialize plugin registry: {}", e))?;
+    fs::write(plugins_dir.join(REGISTRY_FILE), content)
+        .await
+        .map_err(|e| format!("Failed to write plugin registry: {}", e))
+}
+
+/// Check each trusted plugin key is a minisign public key
+pub(crate) fn validate_trusted_keys(keys: &[String]) -> Result<(), String> {
+    for key in keys {
+        minisign_verify::PublicKey::from_base64(key.trim())
+            .map_err(|e| format!("Invalid plugin signing key {}: {}", key, e))?;
+    }
+    Ok(())
+}
+
+// Helper to check a manifest's minisign signature against the trusted keys
+fn verify_signature(
+    manifest: &[u8],
+    signature: &str,
+    trusted_keys: &[String],
+) -> Result<(), String> {
+    let signature = minisign_verify::Signature::decode(signature)
+        .map_err(|e| format!("Invalid plugin signature: {}", e))?;
+    let signed = trusted_keys.iter().any(|key| {
+        minisign_verify::PublicKey::from_base64(key.trim())
+            .is_ok_and(|key| key.verify(manifest, &signature, false).is_ok())
+    });
+    if !signed {
+        return Err("Plugin isn't signed by a trusted key".to_string());
+    }
+    Ok(())
+}
+
+// Helper to check a manifest's fields. Contributions are artifact types,
+// exporters, and graders; exporters and graders are either declarative
+// ("template" and "rules") or a WASM module listed in `files`.
+fn validate_manifest(manifest: &Value) -> Result<(), String> {
+    validate_id(text(manifest, "id"))?;
+    if text(manifest, "name").trim().is_empty() || text(manifest, "version").is_empty() {
+        return Err("Plugin manifest must have a name and version".to_string());
+    }
+    for capability in items(manifest, "capabilities") {
+        let capability = capability.as_str().unwrap_or("");
+        if !CAPABILITIES.iter().any(|(name, _)| *name == capability) {
+            return Err(format!("Unknown plugin capability: {}", capability));
+        }
+    }
+    let files = manifest.get("files").and_then(|v| v.as_object());
+    let checks: [(&str, &[&str]); 3] = [
+        ("artifactTypes", &[]),
+        ("exporters", &["template", "wasm"]),
+        ("graders", &["rules", "wasm"]),
+    ];
+    for (key, kinds) in checks {
+        for contribution in items(manifest, key) {
+            validate_id(text(contribution, "id"))?;
+            if kinds.is_empty() {
+                continue;
+            }
+            let kind = text(contribution, "kind");
+            if !kinds.contains(&kind) {
+                return Err(format!("Unknown {} kind: {}", key, kind));
+            }
+            let module = text(contribution, "module");
+            if kind == "wasm" && !files.is_some_and(|f| f.contains_key(module)) {
+                return Err(format!("WASM module isn't listed in files: {}", module));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Helper to check a plugin's files against the SHA-256 hashes in its
+// signed manifest, so a signature covers the whole plugin
+fn verify_files(manifest: &Value, files: &BTreeMap<String, Vec<u8>>) -> Result<(), String> {
+    let listed = manifest.get("files").and_then(|v| v.as_object());
+    for (name, hash) in listed.into_iter().flatten() {
+        let contents = files
+            .get(name)
+            .ok_or_else(|| format!("Plugin file is missing: {}", name))?;
+        let actual = format!("{:x}", Sha256::digest(contents));
+        if !hash.as_str().is_some_and(|h| h.eq_ignore_ascii_case(&actual)) {
+            return Err(format!("Plugin file doesn't match its signature: {}", name));
+        }
+    }
+    Ok(())
+}
+
+// Helper to verify a plugin's files (by name) and return its manifest
+fn verify_plugin(
+    files: &BTreeMap<String, Vec<u8>>,
+    trusted_keys: &[String],
+) -> Result<Value, String> {
+    let manifest_bytes = files.get(MANIFEST_FILE).ok_or("Plugin has no plugin.json")?;
+    let signature = files
+        .get(SIGNATURE_FILE)
+        .ok_or("Plugin has no signature (plugin.json.minisig)")?;
+    verify_signature(manifest_bytes, &String::from_utf8_lossy(signature), trusted_keys)?;
+    let manifest: Value = serde_json::from_slice(manifest_bytes)
+        .map_err(|e| format!("Invalid plugin manifest: {}", e))?;
+    validate_manifest(&manifest)?;
+    verify_files(&manifest, files)?;
+    Ok(manifest)
+}
+
+// Helper to read the files of a plugin package: a zip or a folder. Only
+// top-level files are read; a zip with a single top folder is read from
+// inside it.
+async fn read_package(path: &Path) -> Result<BTreeMap<String, Vec<u8>>, String> {
+    let mut entries = Vec::new();
+    if path.is_dir() {
+        let mut read_dir = fs::read_dir(path)
+            .await
+            .map_err(|e| format!("Failed to read plugin folder: {}", e))?;
+        while let Ok(Some(entry)) = read_dir.next_entry().await {
+            if entry.file_type().await.is_ok_and(|t| t.is_file()) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let contents = fs::read(entry.path())
+                    .await
+                    .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+                entries.push((name, contents));
+            }
+        }
+    } else {
+        let bytes = fs::read(path)
+            .await
+            .map_err(|e| format!("Failed to read plugin package: {}", e))?;
+        entries = archive::read_zip(&bytes)?
+            .into_iter()
+            .filter(|(name, _)| !name.starts_with("__MACOSX/") && !name.ends_with('/'))
+            .collect();
+        let prefix = entries
+            .first()
+            .and_then(|(name, _)| name.split_once('/'))
+            .map(|(folder, _)| format!("{}/", folder));
+        if let Some(prefix) = prefix.filter(|p| entries.iter().all(|(n, _)| n.starts_with(p))) {
+            for (name, _) in &mut entries {
+                *name = name[prefix.len()..].to_string();
+            }
+        }
+    }
+
+    let mut files = BTreeMap::new();
+    for (name, contents) in entries {
+        if name.contains(['/', '\\']) || name.starts_with('.') {
+            continue;
+        }
+        if contents.len() > MAX_FILE_BYTES {
+            return Err(format!("Plugin file is too large: {}", name));
+        }
+        files.insert(name, contents);
+    }
+    Ok(files)
+}
+
+// Helper to load and verify an installed plugin, returning its manifest
+async fn load_plugin(app_handle: &tauri::AppHandle, plugin_id: &str) -> Result<Value, String> {
+    let plugin_dir = get_plugin_dir(app_handle, plugin_id)?;
+    if !plugin_dir.exists() {
+        return Err(format!("Plugin not found: {}", plugin_id));
+    }
+    let settings = settings_storage::load_settings(app_handle).await?;
+    let files = read_package(&plugin_dir).await?;
+    let manifest = verify_plugin(&files, &settings.plugins.trusted_keys)?;
+    if text(&manifest, "id") != plugin_id {
+        return Err(format!("Plugin folder doesn't match its ID: {}", plugin_id));
+    }
+    Ok(manifest)
+}
+
+// Helper to list the capabilities a plugin has been granted
+fn granted(registry: &Registry, plugin_id: &str) -> Vec<String> {
+    registry
+        .get(plugin_id)
+        .map(|state| items(state, "granted").filter_map(|c| c.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+// Helper to list the capabilities a plugin asks for but hasn't been granted
+fn missing_capabilities(manifest: &Value, granted: &[String]) -> Vec<String> {
+    items(manifest, "capabilities")
+        .filter_map(|c| c.as_str())
+        .filter(|c| !granted.iter().any(|g| g == c))
+        .map(String::from)
+        .collect()
+}
+
+// Helper to load an enabled plugin and find one of its contributions,
+// checking it has been granted what that kind of contribution needs
+async fn enabled_contribution(
+    app_handle: &tauri::AppHandle,
+    plugin_id: &str,
+    key: &str,
+    contribution_id: &str,
+    needs: &[&str],
+) -> Result<Value, String> {
+    let registry = read_registry(app_handle).await?;
+    let enabled = registry.get(plugin_id).and_then(|s| s.get("enabled")).and_then(|v| v.as_bool());
+    if enabled != Some(true) {
+        return Err(format!("Plugin isn't enabled: {}", plugin_id));
+    }
+    let manifest = load_plugin(app_handle, plugin_id).await?;
+    let granted = granted(&registry, plugin_id);
+    if let Some(capability) = needs.iter().find(|c| !granted.iter().any(|g| g == *c)) {
+        return Err(format!("Plugin hasn't been granted {}: {}", capability, plugin_id));
+    }
+    let contribution = items(&manifest, key)
+        .find(|c| text(c, "id") == contribution_id)
+        .cloned()
+        .ok_or_else(|| format!("Plugin {} has no {}: {}", plugin_id, key, contribution_id))?;
+    if text(&contribution, "kind") == "wasm" {
+        return Err("WASM plugins can't run yet: this build has no WebAssembly runtime".to_string());
+    }
+    Ok(contribution)
+}
+
+// Helper to fill an exporter template: `{{field}}` for each of the
+// artifact's text fields, and `{{text}}` for its visible text
+fn fill_template(template: &str, artifact: &Value) -> String {
+    let html = text(artifact, "htmlContent");
+    let visible_text = artifact_analysis::text_blocks(html).join("\n\n");
+    let mut output = template.replace("{{text}}", &visible_text);
+    for (key, value) in artifact.as_object().into_iter().flatten() {
+        if let Some(value) = value.as_str() {
+            output = output.replace(&format!("{{{{{}}}}}", key), value);
+        }
+    }
+    output
+}
+
+// Helper to normalize an answer for comparison
+fn normalize_answer(answer: &str, case_sensitive: bool) -> String {
+    let answer = answer.split_whitespace().collect::<Vec<_>>().join(" ");
+    if case_sensitive {
+        answer
+    } else {
+        answer.to_lowercase()
+    }
+}
+
+// ============================================
+// Plugin Commands
+// ============================================
+
+/// List installed plugins as `[{id, name, version, description, author,
+/// capabilities, granted, missingCapabilities, enabled, verified, error}]`.
+/// A plugin whose signature or files no longer check out (e.g. after its
+/// key was removed from `plugins.trustedKeys`) is listed with `verified`
+/// false and the reason in `error`, and doesn't run. Also returns the known
+/// capabilities with their descriptions.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_plugins(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let registry = read_registry(&app_handle).await?;
+    let mut plugins = Vec::new();
+    for (plugin_id, state) in &registry {
+        let enabled = state.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false);
+        let granted = granted(&registry, plugin_id);
+        let plugin = match load_plugin(&app_handle, plugin_id).await {
+            Ok(manifest) => serde_json::json!({
+                "id": plugin_id,
+                "name": manifest.get("name"),
+                "version": manifest.get("version"),
+                "description": manifest.get("description"),
+                "author": manifest.get("author"),
+                "capabilities": manifest.get("capabilities"),
+                "granted": granted,
+                "missingCapabilities": missing_capabilities(&manifest, &granted),
+                "enabled": enabled,
+                "verified": true,
+                "error": null,
+            }),
+            Err(e) => serde_json::json!({
+                "id": plugin_id,
+                "granted": granted,
+                "enabled": enabled,
+                "verified": false,
+                "error": e,
+            }),
+        };
+        plugins.push(plugin);
+    }
+    let capabilities: Vec<Value> = CAPABILITIES
+        .iter()
+        .map(|(name, description)| serde_json::json!({ "name": name, "description": description }))
+        .collect();
+    let result = serde_json::json!({ "plugins": plugins, "capabilities": capabilities });
+    Ok(result.to_string())
+}
+
+/// Install a plugin from a zip or folder holding `plugin.json`, its
+/// minisign signature `plugin.json.minisig`, and any files the manifest
+/// lists with their SHA-256 hashes. The signature must be from a key in
+/// `plugins.trustedKeys`. A new plugin starts disabled with no capabilities
+/// granted; reinstalling (e.g. an update) keeps the grants it still asks
+/// for, and stays enabled only if it needs nothing more. Returns the
+/// plugin's ID.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn install_plugin(app_handle: tauri::AppHandle, path: String) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let files = read_package(Path::new(&path)).await?;
+    let settings = settings_storage::load_settings(&app_handle).await?;
+    let manifest = verify_plugin(&files, &settings.plugins.trusted_keys)?;
+    let plugin_id = text(&manifest, "id").to_string();
+
+    let plugin_dir = get_plugin_dir(&app_handle, &plugin_id)?;
+    if plugin_dir.exists() {
+        fs::remove_dir_all(&plugin_dir)
+            .await
+            .map_err(|e| format!("Failed to remove old plugin files: {}", e))?;
+    }
+    fs::create_dir_all(&plugin_dir)
+        .await
+        .map_err(|e| format!("Failed to create plugin directory: {}", e))?;
+    for (name, contents) in &files {
+        fs::write(plugin_dir.join(name), contents)
+            .await
+            .map_err(|e| format!("Failed to write plugin file {}: {}", name, e))?;
+    }
+
+    let mut registry = read_registry(&app_handle).await?;
+    let granted: Vec<String> = granted(&registry, &plugin_id)
+        .into_iter()
+        .filter(|g| items(&manifest, "capabilities").any(|c| c.as_str() == Some(g)))
+        .collect();
+    let was_enabled = registry
+        .get(&plugin_id)
+        .and_then(|s| s.get("enabled"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let enabled = was_enabled && missing_capabilities(&manifest, &granted).is_empty();
+    registry.insert(
+        plugin_id.clone(),
+        serde_json::json!({
+            "enabled": enabled,
+            "granted": granted,
+            "version": manifest.get("version"),
+            "installedAt": chrono::Utc::now().to_rfc3339(),
+        }),
+    );
+    write_registry(&app_handle, &registry).await?;
+
+    audit_log::record(&app_handle, "install_plugin", "plugin", &[&plugin_id]).await;
+
+    Ok(plugin_id)
+}
+
+/// Remove an installed plugin and its files
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn uninstall_plugin(
+    app_handle: tauri::AppHandle,
+    plugin_id: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let plugin_dir = get_plugin_dir(&app_handle, &plugin_id)?;
+    let mut registry = read_registry(&app_handle).await?;
+    if registry.remove(&plugin_id).is_none() && !plugin_dir.exists() {
+        return Err(format!("Plugin not found: {}", plugin_id));
+    }
+    if plugin_dir.exists() {
+        fs::remove_dir_all(&plugin_dir)
+            .await
+            .map_err(|e| format!("Failed to remove plugin files: {}", e))?;
+    }
+    write_registry(&app_handle, &registry).await?;
+
+    audit_log::record(&app_handle, "uninstall_plugin", "plugin", &[&plugin_id]).await;
+
+    Ok(())
+}
+
+/// Grant a plugin some of the capabilities its manifest asks for, replacing
+/// its previous grants. Revoking one it needs disables the plugin.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn set_plugin_capabilities(
+    app_handle: tauri::AppHandle,
+    plugin_id: String,
+    granted: Vec<String>,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let manifest = load_plugin(&app_handle, &plugin_id).await?;
+    if let Some(capability) = granted
+        .iter()
+        .find(|g| !items(&manifest, "capabilities").any(|c| c.as_str() == Some(g.as_str())))
+    {
+        return Err(format!("Plugin doesn't ask for {}: {}", capability, plugin_id));
+    }
+    let mut registry = read_registry(&app_handle).await?;
+    let state = registry
+        .get_mut(&plugin_id)
+        .and_then(|s| s.as_object_mut())
+        .ok_or_else(|| format!("Plugin not found: {}", plugin_id))?;
+    if !missing_capabilities(&manifest, &granted).is_empty() {
+        state.insert("enabled".to_string(), Value::Bool(false));
+    }
+    state.insert("granted".to_string(), serde_json::json!(granted));
+    write_registry(&app_handle, &registry).await?;
+
+    audit_log::record(&app_handle, "set_plugin_capabilities", "plugin", &[&plugin_id]).await;
+
+    Ok(())
+}
+
+/// Enable or disable a plugin. It can only be enabled once its signature
+/// checks out and every capability it asks for has been granted.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn set_plugin_enabled(
+    app_handle: tauri::AppHandle,
+    plugin_id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut registry = read_registry(&app_handle).await?;
+    if enabled {
+        let manifest = load_plugin(&app_handle, &plugin_id).await?;
+        let missing = missing_capabilities(&manifest, &granted(&registry, &plugin_id));
+        if !missing.is_empty() {
+            return Err(format!("Grant the plugin's capabilities first: {}", missing.join(", ")));
+        }
+    }
+    let state = registry
+        .get_mut(&plugin_id)
+        .and_then(|s| s.as_object_mut())
+        .ok_or_else(|| format!("Plugin not found: {}", plugin_id))?;
+    state.insert("enabled".to_string(), Value::Bool(enabled));
+    write_registry(&app_handle, &registry).await?;
+
+    audit_log::record(&app_handle, "set_plugin_enabled", "plugin", &[&plugin_id]).await;
+
+    Ok(())
+}
+
+/// Get what enabled plugins add, as `{artifactTypes, exporters, graders}`;
+/// each entry is from the plugin's manifest with its `pluginId` and
+/// `runnable` (false for WASM, which this build can't run yet). Graders
+/// come without their `rules`, which hold the accepted answers.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_plugin_contributions(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let registry = read_registry(&app_handle).await?;
+    let mut contributions = serde_json::json!({
+        "artifactTypes": [],
+        "exporters": [],
+        "graders": [],
+    });
+    for (plugin_id, state) in &registry {
+        if state.get("enabled").and_then(|v| v.as_bool()) != Some(true) {
+            continue;
+        }
+        let manifest = match load_plugin(&app_handle, plugin_id).await {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                tracing::warn!(plugin = %plugin_id, error = %e, "Skipping plugin");
+                continue;
+            }
+        };
+        for key in ["artifactTypes", "exporters", "graders"] {
+            for contribution in items(&manifest, key) {
+                let mut contribution = contribution.clone();
+                let runnable = text(&contribution, "kind") != "wasm";
+                if let Some(obj) = contribution.as_object_mut() {
+                    obj.insert("pluginId".to_string(), Value::String(plugin_id.clone()));
+                    obj.insert("runnable".to_string(), Value::Bool(runnable));
+                    if key == "graders" {
+                        obj.remove("rules");
+                    }
+                }
+                if let Some(list) = contributions[key].as_array_mut() {
+                    list.push(contribution);
+                }
+            }
+        }
+    }
+    Ok(contributions.to_string())
+}
+
+/// Export an artifact with a plugin's exporter to `out_path` (absolute).
+/// Template exporters fill `{{field}}` with the artifact's text fields and
+/// `{{text}}` with its visible text. Needs the `artifacts.read` and
+/// `files.write` capabilities. Returns the written path.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn run_plugin_exporter(
+    app_handle: tauri::AppHandle,
+    plugin_id: String,
+    exporter_id: String,
+    artifact_id: String,
+    out_path: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let exporter = enabled_contribution(
+        &app_handle,
+        &plugin_id,
+        "exporters",
+        &exporter_id,
+        EXPORTER_CAPABILITIES,
+    )
+    .await?;
+    let out_path = PathBuf::from(out_path);
+    if !out_path.is_absolute() {
+        return Err(format!("Output path must be absolute: {}", out_path.display()));
+    }
+    let artifact = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    let output = fill_template(text(&exporter, "template"), &artifact);
+    fs::write(&out_path, output)
+        .await
+        .map_err(|e| format!("Failed to write export: {}", e))?;
+
+    audit_log::record(&app_handle, "run_plugin_exporter", "artifact", &[&artifact_id]).await;
+
+    Ok(out_path.to_string_lossy().to_string())
+}
+
+/// Grade answers (`{questionId: answer}`) with a plugin's grader. Rules
+/// graders match each answer against the rule's `accept` list, ignoring
+/// spacing and (unless `caseSensitive`) case, worth `points` (default 1).
+/// Needs the `learners.read` capability. Returns `{score, earned,
+/// possible, results: [{questionId, correct, points}]}` with `score` as a
+/// percentage.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn run_plugin_grader(
+    app_handle: tauri::AppHandle,
+    plugin_id: String,
+    grader_id: String,
+    answers: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let grader = enabled_contribution(
+        &app_handle,
+        &plugin_id,
+        "graders",
+        &grader_id,
+        GRADER_CAPABILITIES,
+    )
+    .await?;
+    let answers: BTreeMap<String, String> = serde_json::from_str(&answers)
+        .map_err(|e| format!("Invalid answers JSON: {}", e))?;
+
+    let mut earned = 0.0;
+    let mut possible = 0.0;
+    let mut results = Vec::new();
+    for rule in items(&grader, "rules") {
+        let question_id = text(rule, "questionId");
+        let points = rule.get("points").and_then(|v| v.as_f64()).unwrap_or(1.0);
+        let case_sensitive = rule.get("caseSensitive").and_then(|v| v.as_bool()).unwrap_or(false);
+        let correct = answers.get(question_id).is_some_and(|answer| {
+            let answer = normalize_answer(answer, case_sensitive);
+            items(rule, "accept")
+                .filter_map(|a| a.as_str())
+                .any(|accepted| normalize_answer(accepted, case_sensitive) == answer)
+        });
+        possible += points;
+        if correct {
+            earned += points;
+        }
+        results.push(serde_json::json!({
+            "questionId": question_id,
+            "correct": correct,
+            "points": if correct { points } else { 0.0 },
+        }));
+    }
+    let score = if possible > 0.0 { earned / possible * 100.0 } else { 0.0 };
+    let result = serde_json::json!({
+        "score": score,
+        "earned": earned,
+        "possible": possible,
+        "results": results,
+    });
+    Ok(result.to_string())
+}
@@ -0,0 +1,860 @@
+use ab_glyph::{Font, FontVec};
+use image::RgbImage;
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+use crate::commands::artifact_analysis::{self, Tag, Token};
+use crate::commands::settings_storage::{self, PrintLayoutSettings};
+use crate::commands::{
+    design_pack_assets, image_cache, image_library, learner_storage, library_storage,
+};
+
+const POINTS_PER_INCH: f32 = 72.0;
+const POINTS_PER_CSS_PIXEL: f32 = 0.75;
+
+// Page sizes in points, portrait
+const LETTER: (f32, f32) = (612.0, 792.0);
+const A4: (f32, f32) = (595.28, 841.89);
+
+// Margins are limited so there's always room for content
+const MAX_MARGIN_INCHES: f32 = 3.0;
+const MIN_CONTENT_INCHES: f32 = 2.0;
+
+// Type sizes in points, and line height as a multiple of the size
+const BODY_SIZE: f32 = 12.0;
+const HEADING_SIZES: [f32; 3] = [20.0, 16.0, 14.0];
+const BAND_SIZE: f32 = 9.0;
+const LINE_HEIGHT: f32 = 1.4;
+
+// Worksheet parts, in points
+const LIST_INDENT: f32 = 24.0;
+const ANSWER_LINE_HEIGHT: f32 = 28.0;
+const DRAWING_BOX_HEIGHT: f32 = 180.0;
+const CLIPART_HEIGHT: f32 = 0.9 * POINTS_PER_INCH;
+const BLOCK_GAP: f32 = 8.0;
+
+/// Width of rules, answer lines, and boxes, in points
+pub(crate) const STROKE_WIDTH: f32 = 0.75;
+
+/// Gray level of watermark text, from 0 (black) to 1 (white)
+pub(crate) const WATERMARK_GRAY: f32 = 0.82;
+
+// Watermarks are sized to span most of the page diagonal, up to this size
+const WATERMARK_MAX_SIZE: f32 = 72.0;
+const WATERMARK_SPAN: f32 = 0.8;
+
+// SVG images are drawn at this many pixels per point so they stay sharp
+const SVG_SCALE: f32 = 3.0;
+
+// System fonts tried in order; the generic sans-serif font is the fallback.
+// Only faces stored alone in their file (not in a collection) can be
+// embedded in PDFs.
+const FONT_FAMILIES: &[&str] = &[
+    "Arial",
+    "Helvetica",
+    "Liberation Sans",
+    "Arimo",
+    "DejaVu Sans",
+    "Noto Sans",
+    "Verdana",
+];
+
+/// A font the print pipeline measures, draws, and embeds text with
+pub(crate) struct PrintFont {
+    pub(crate) data: Vec<u8>,
+    pub(crate) font: FontVec,
+}
+
+impl PrintFont {
+    /// Width of `text` set at `size` points
+    pub(crate) fn width(&self, text: &str, size: f32) -> f32 {
+        let units = self.font.units_per_em().unwrap_or(1000.0);
+        let advance: f32 = text
+            .chars()
+            .map(|c| self.font.h_advance_unscaled(self.font.glyph_id(c)))
+            .sum();
+        advance * size / units
+    }
+}
+
+/// The regular and bold faces used for every printed page
+pub(crate) struct PrintFonts {
+    pub(crate) regular: PrintFont,
+    pub(crate) bold: PrintFont,
+}
+
+/// Managed state caching the print fonts, since finding them means
+/// scanning every installed font
+#[derive(Default)]
+pub struct PrintLayoutState {
+    fonts: Mutex<Option<Arc<PrintFonts>>>,
+}
+
+/// Something drawn on a page. Positions are in points from the top-left
+/// corner; text is placed by its baseline.
+pub(crate) enum Mark {
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        bold: bool,
+        text: String,
+    },
+    Line {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+    },
+    Rect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+    Image {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        image: Arc<RgbImage>,
+    },
+    /// Gray text along a line rising at `angle` radians, starting from the
+    /// baseline point `x`, `y`
+    Watermark {
+        x: f32,
+        y: f32,
+        size: f32,
+        angle: f32,
+        text: String,
+    },
+}
+
+/// One laid-out page
+#[derive(Default)]
+pub(crate) struct Page {
+    pub(crate) marks: Vec<Mark>,
+}
+
+/// An artifact laid out on pages, ready to be written as a PDF or drawn
+pub(crate) struct Document {
+    pub(crate) width: f32,
+    pub(crate) height: f32,
+    pub(crate) pages: Vec<Page>,
+}
+
+/// A piece of the artifact, in reading order
+enum Block {
+    Text {
+        text: String,
+        size: f32,
+        bold: bool,
+        indent: f32,
+        marker: Option<String>,
+    },
+    AnswerLine {
+        indent: f32,
+    },
+    DrawingBox,
+    Image {
+        image: Arc<RgbImage>,
+        width: f32,
+        height: f32,
+    },
+    Rule,
+    PageBreak,
+}
+
+// Helper to load one system font face for `weight`, trying the preferred
+// families and then the generic sans-serif
+fn find_font(database: &fontdb::Database, weight: fontdb::Weight) -> Option<PrintFont> {
+    let families = FONT_FAMILIES
+        .iter()
+        .map(|name| fontdb::Family::Name(name))
+        .chain([fontdb::Family::SansSerif]);
+    for family in families {
+        let query = fontdb::Query {
+            families: &[family],
+            weight,
+            ..Default::default()
+        };
+        let Some(id) = database.query(&query) else {
+            continue;
+        };
+        let face = database.with_face_data(id, |data, index| (data.to_vec(), index));
+        if let Some((data, 0)) = face {
+            if let Ok(font) = FontVec::try_from_vec(data.clone()) {
+                return Some(PrintFont { data, font });
+            }
+        }
+    }
+    None
+}
+
+/// The print fonts, found among the installed fonts on first use
+pub(crate) fn fonts(app_handle: &tauri::AppHandle) -> Result<Arc<PrintFonts>, String> {
+    let state = app_handle.state::<PrintLayoutState>();
+    let mut cached = state
+        .fonts
+        .lock()
+        .map_err(|_| "Print fonts are unavailable".to_string())?;
+    if let Some(fonts) = cached.as_ref() {
+        return Ok(fonts.clone());
+    }
+
+    let mut database = fontdb::Database::new();
+    database.load_system_fonts();
+    let regular = find_font(&database, fontdb::Weight::NORMAL)
+        .ok_or("No usable font is installed for printing")?;
+    // Without a bold face, headings are set in the regular one
+    let bold = match find_font(&database, fontdb::Weight::BOLD) {
+        Some(bold) => bold,
+        None => PrintFont {
+            data: regular.data.clone(),
+            font: FontVec::try_from_vec(regular.data.clone())
+                .map_err(|e| format!("Failed to load print font: {}", e))?,
+        },
+    };
+    let fonts = Arc::new(PrintFonts { regular, bold });
+    *cached = Some(fonts.clone());
+    Ok(fonts)
+}
+
+// Helper to get the page size in points for the layout's paper and
+// orientation
+fn page_size(layout: &PrintLayoutSettings) -> Result<(f32, f32), String> {
+    let (width, height) = match layout.page_size.as_str() {
+        "letter" => LETTER,
+        "a4" => A4,
+        other => return Err(format!("Unsupported page size: {}", other)),
+    };
+    match layout.orientation.as_str() {
+        "portrait" => Ok((width, height)),
+        "landscape" => Ok((height, width)),
+        other => Err(format!("Unsupported orientation: {}", other)),
+    }
+}
+
+// Helper to fill in a header, footer, or watermark template
+fn fill_template(
+    template: &str,
+    artifact_id: &str,
+    title: &str,
+    learner: Option<&str>,
+) -> String {
+    let date = chrono::Local::now().format("%B %-d, %Y").to_string();
+    template
+        .replace("{title}", title)
+        .replace("{learner}", learner.unwrap_or(""))
+        .replace("{date}", &date)
+        .replace("{artifactId}", artifact_id)
+        .trim()
+        .to_string()
+}
+
+// Helper to flatten decoded pixels onto white; printed pages have no
+// transparency
+fn flatten(image: image::RgbaImage) -> RgbImage {
+    RgbImage::from_fn(image.width(), image.height(), |x, y| {
+        let [r, g, b, a] = image.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+        image::Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+// Helper to draw an SVG at `SVG_SCALE`, returning the pixels and the
+// SVG's own size in points
+fn render_svg(bytes: &[u8]) -> Option<(RgbImage, f32, f32)> {
+    let tree = resvg::usvg::Tree::from_data(bytes, &resvg::usvg::Options::default()).ok()?;
+    let size = tree.size();
+    let width = (size.width() * SVG_SCALE).ceil() as u32;
+    let height = (size.height() * SVG_SCALE).ceil() as u32;
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height)?;
+    let transform = resvg::tiny_skia::Transform::from_scale(SVG_SCALE, SVG_SCALE);
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+    // Pixmap colors are premultiplied, so blending onto white is an add
+    let pixels = pixmap.data();
+    let image = RgbImage::from_fn(width, height, |x, y| {
+        let i = ((y * width + x) * 4) as usize;
+        let white = 255 - pixels[i + 3];
+        image::Rgb([0, 1, 2].map(|c| pixels[i + c].saturating_add(white)))
+    });
+    Some((image, size.width(), size.height()))
+}
+
+// Helper to load an <img> source (a data URI, library image, or design pack
+// asset), returning the pixels and the natural size in points
+fn load_image(app_handle: &tauri::AppHandle, src: &str) -> Option<(RgbImage, f32, f32)> {
+    let bytes = if src.starts_with("data:") {
+        image_cache::decode_data_uri(src).ok()?.1
+    } else {
+        let path = image_library::resolve_url(app_handle, src)
+            .or_else(|| design_pack_assets::resolve_url(app_handle, src))?;
+        std::fs::read(path).ok()?
+    };
+    if let Ok(decoded) = image::load_from_memory(&bytes) {
+        let (width, height) = (decoded.width(), decoded.height());
+        let image = flatten(decoded.to_rgba8());
+        return Some((
+            image,
+            width as f32 * POINTS_PER_CSS_PIXEL,
+            height as f32 * POINTS_PER_CSS_PIXEL,
+        ));
+    }
+    render_svg(&bytes)
+}
+
+// Helper to read a pixel size from an attribute such as `width="120"`
+fn pixel_attr(tag: &Tag, name: &str) -> Option<f32> {
+    let value = tag.attr(name)?.trim().trim_end_matches("px");
+    value.parse::<f32>().ok().filter(|v| *v > 0.0)
+}
+
+// Helper to turn artifact HTML into blocks. Headings, list items, answer
+// lines, drawing boxes, images, rules, and page breaks are recognized;
+// other elements become plain paragraphs.
+fn parse_blocks(app_handle: &tauri::AppHandle, html: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut text = String::new();
+    let mut size = BODY_SIZE;
+    let mut bold = false;
+    let mut marker: Option<String> = None;
+    // Open lists (with the last number used for ordered ones) and open
+    // elements (marking clipart rows)
+    let mut lists: Vec<Option<usize>> = Vec::new();
+    let mut open: Vec<(String, bool)> = Vec::new();
+    let mut in_head = false;
+
+    let flush = |text: &mut String,
+                 marker: &mut Option<String>,
+                 size: f32,
+                 bold: bool,
+                 indent: f32,
+                 blocks: &mut Vec<Block>| {
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        text.clear();
+        if collapsed.is_empty() {
+            return;
+        }
+        blocks.push(Block::Text {
+            text: collapsed,
+            size,
+            bold,
+            indent,
+            marker: marker.take(),
+        });
+    };
+
+    for token in artifact_analysis::tokenize(html) {
+        let indent = LIST_INDENT * lists.len() as f32;
+        let tag = match token {
+            Token::Text(span) => {
+                if !in_head {
+                    text.push_str(&artifact_analysis::decode_entities(&html[span]));
+                }
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+        if tag.name == "head" {
+            in_head = !tag.closing;
+            continue;
+        }
+        if in_head {
+            continue;
+        }
+
+        if tag.closing {
+            if let Some(index) = open.iter().rposition(|(name, _)| *name == tag.name) {
+                open.truncate(index);
+            }
+        } else if !tag.self_closing {
+            let clipart = tag.classes().any(|c| c == "clipart");
+            open.push((tag.name.clone(), clipart));
+        }
+        let style = tag.attr("style").unwrap_or("").to_ascii_lowercase();
+        let page_break = style.contains("page-break-before")
+            || style.contains("break-before: page")
+            || tag.classes().any(|c| c == "page-break");
+        if !tag.closing && page_break {
+            flush(&mut text, &mut marker, size, bold, indent, &mut blocks);
+            blocks.push(Block::PageBreak);
+        }
+
+        match tag.name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                flush(&mut text, &mut marker, size, bold, indent, &mut blocks);
+                let level = tag.name[1..].parse::<usize>().unwrap_or(1);
+                (size, bold) = if tag.closing {
+                    (BODY_SIZE, false)
+                } else {
+                    (*HEADING_SIZES.get(level - 1).unwrap_or(&BODY_SIZE), true)
+                };
+            }
+            "ol" | "ul" => {
+                flush(&mut text, &mut marker, size, bold, indent, &mut blocks);
+                if tag.closing {
+                    lists.pop();
+                } else {
+                    lists.push((tag.name == "ol").then_some(0));
+                }
+            }
+            "li" => {
+                flush(&mut text, &mut marker, size, bold, indent, &mut blocks);
+                if !tag.closing {
+                    marker = Some(match lists.last_mut() {
+                        Some(Some(number)) => {
+                            *number += 1;
+                            format!("{}.", number)
+                        }
+                        _ => "•".to_string(),
+                    });
+                }
+            }
+            "img" => {
+                flush(&mut text, &mut marker, size, bold, indent, &mut blocks);
+                let src = tag.attr("src").unwrap_or("");
+                match load_image(app_handle, src) {
+                    Some((image, natural_width, natural_height)) => {
+                        let in_clipart = open.iter().any(|(_, clipart)| *clipart);
+                        let (width, height) = match (
+                            pixel_attr(&tag, "width"),
+                            pixel_attr(&tag, "height"),
+                        ) {
+                            (Some(w), Some(h)) => {
+                                (w * POINTS_PER_CSS_PIXEL, h * POINTS_PER_CSS_PIXEL)
+                            }
+                            (Some(w), None) => {
+                                let w = w * POINTS_PER_CSS_PIXEL;
+                                (w, w * natural_height / natural_width)
+                            }
+                            (None, Some(h)) => {
+                                let h = h * POINTS_PER_CSS_PIXEL;
+                                (h * natural_width / natural_height, h)
+                            }
+                            (None, None) if in_clipart => (
+                                CLIPART_HEIGHT * natural_width / natural_height,
+                                CLIPART_HEIGHT,
+                            ),
+                            (None, None) => (natural_width, natural_height),
+                        };
+                        blocks.push(Block::Image {
+                            image: Arc::new(image),
+                            width,
+                            height,
+                        });
+                    }
+                    None => {
+                        // An image that can't be drawn is replaced by its
+                        // description
+                        if let Some(alt) = tag.attr("alt").filter(|a| !a.trim().is_empty()) {
+                            text.push_str(&format!("[{}]", alt.trim()));
+                            flush(&mut text, &mut marker, size, bold, indent, &mut blocks);
+                        }
+                    }
+                }
+            }
+            "hr" => {
+                flush(&mut text, &mut marker, size, bold, indent, &mut blocks);
+                blocks.push(Block::Rule);
+            }
+            _ if !tag.closing && tag.classes().any(|c| c == "answer-line") => {
+                flush(&mut text, &mut marker, size, bold, indent, &mut blocks);
+                blocks.push(Block::AnswerLine { indent });
+            }
+            _ if !tag.closing && tag.classes().any(|c| c == "drawing-box") => {
+                flush(&mut text, &mut marker, size, bold, indent, &mut blocks);
+                blocks.push(Block::DrawingBox);
+            }
+            name if artifact_analysis::BLOCK_ELEMENTS.contains(&name) => {
+                flush(&mut text, &mut marker, size, bold, indent, &mut blocks);
+            }
+            // Inline elements don't break words; form fields and the like
+            // still separate them
+            "a" | "abbr" | "b" | "code" | "em" | "i" | "mark" | "s" | "small" | "span"
+            | "strong" | "sub" | "sup" | "u" => {}
+            _ => text.push(' '),
+        }
+    }
+    flush(&mut text, &mut marker, size, bold, 0.0, &mut blocks);
+    blocks
+}
+
+// Helper to break text into lines no wider than `width`. A word too long
+// for a line is split wherever it runs out of room.
+fn wrap(font: &PrintFont, text: &str, size: f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if font.width(&candidate, size) <= width {
+            line = candidate;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            line.push(c);
+            if line.chars().count() > 1 && font.width(&line, size) > width {
+                line.pop();
+                lines.push(std::mem::replace(&mut line, c.to_string()));
+            }
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Places blocks down the content area of each page
+struct Flow<'a> {
+    fonts: &'a PrintFonts,
+    pages: Vec<Page>,
+    page: Page,
+    y: f32,
+    top: f32,
+    bottom: f32,
+    left: f32,
+    right: f32,
+    // The row images are being placed in: (next x, top, tallest height)
+    image_row: Option<(f32, f32, f32)>,
+}
+
+impl Flow<'_> {
+    fn new_page(&mut self) {
+        self.end_image_row();
+        self.pages.push(std::mem::take(&mut self.page));
+        self.y = self.top;
+    }
+
+    // Start a new page unless `height` fits below the cursor. Something
+    // taller than a page is placed at the top of one anyway.
+    fn make_room(&mut self, height: f32) {
+        if self.y + height > self.bottom && self.y > self.top {
+            self.new_page();
+        }
+    }
+
+    fn end_image_row(&mut self) {
+        if let Some((_, top, height)) = self.image_row.take() {
+            self.y = top + height + BLOCK_GAP;
+        }
+    }
+
+    fn text(&mut self, text: &str, size: f32, bold: bool, indent: f32, marker: Option<&str>) {
+        let font = if bold {
+            &self.fonts.bold
+        } else {
+            &self.fonts.regular
+        };
+        let line_height = size * LINE_HEIGHT;
+        let left = self.left + indent;
+        // Headings get room above them, except at the top of a page
+        if bold && size > BODY_SIZE && self.y > self.top {
+            self.y += size * 0.5;
+        }
+        for (index, line) in wrap(font, text, size, self.right - left).into_iter().enumerate() {
+            self.make_room(line_height);
+            let baseline = self.y + size;
+            if let (0, Some(marker)) = (index, marker) {
+                // Markers hang in the indent, right-aligned before the text
+                let marker_width = self.fonts.regular.width(marker, size);
+                self.page.marks.push(Mark::Text {
+                    x: (left - marker_width - size * 0.4).max(self.left),
+                    y: baseline,
+                    size,
+                    bold: false,
+                    text: marker.to_string(),
+                });
+            }
+            self.page.marks.push(Mark::Text {
+                x: left,
+                y: baseline,
+                size,
+                bold,
+                text: line,
+            });
+            self.y += line_height;
+        }
+        self.y += size * 0.5;
+    }
+
+    fn image(&mut self, image: Arc<RgbImage>, width: f32, height: f32) {
+        // Shrink to fit the content area, keeping the proportions
+        let content_width = self.right - self.left;
+        let content_height = self.bottom - self.top;
+        let scale = (content_width / width).min(content_height / height).min(1.0);
+        let (width, height) = (width * scale, height * scale);
+
+        // Images side by side share a row while they fit across the page
+        let (x, top) = match self.image_row {
+            Some((x, top, _)) if x + width <= self.right => (x, top),
+            _ => {
+                self.end_image_row();
+                self.make_room(height);
+                (self.left, self.y)
+            }
+        };
+        self.page.marks.push(Mark::Image {
+            x,
+            y: top,
+            width,
+            height,
+            image,
+        });
+        let tallest = self.image_row.map_or(height, |(_, _, tallest)| tallest.max(height));
+        self.image_row = Some((x + width + BLOCK_GAP, top, tallest));
+    }
+
+    fn place(&mut self, block: Block) {
+        if !matches!(block, Block::Image { .. }) {
+            self.end_image_row();
+        }
+        match block {
+            Block::Text {
+                text,
+                size,
+                bold,
+                indent,
+                marker,
+            } => self.text(&text, size, bold, indent, marker.as_deref()),
+            Block::AnswerLine { indent } => {
+                self.make_room(ANSWER_LINE_HEIGHT);
+                let y = self.y + ANSWER_LINE_HEIGHT - 4.0;
+                self.page.marks.push(Mark::Line {
+                    x1: self.left + indent,
+                    y1: y,
+                    x2: self.right,
+                    y2: y,
+                });
+                self.y += ANSWER_LINE_HEIGHT + 4.0;
+            }
+            Block::DrawingBox => {
+                let height = DRAWING_BOX_HEIGHT.min(self.bottom - self.top);
+                self.make_room(height);
+                self.page.marks.push(Mark::Rect {
+                    x: self.left,
+                    y: self.y,
+                    width: self.right - self.left,
+                    height,
+                });
+                self.y += height + BLOCK_GAP;
+            }
+            Block::Image {
+                image,
+                width,
+                height,
+            } => self.image(image, width, height),
+            Block::Rule => {
+                self.make_room(BLOCK_GAP * 2.0);
+                let y = self.y + BLOCK_GAP;
+                self.page.marks.push(Mark::Line {
+                    x1: self.left,
+                    y1: y,
+                    x2: self.right,
+                    y2: y,
+                });
+                self.y += BLOCK_GAP * 2.0;
+            }
+            Block::PageBreak => {
+                if !self.page.marks.is_empty() {
+                    self.new_page();
+                }
+            }
+        }
+    }
+}
+
+// Helper to shorten band text to fit `width`, ending it with an ellipsis
+fn fit(font: &PrintFont, text: &str, size: f32, width: f32) -> String {
+    if font.width(text, size) <= width {
+        return text.to_string();
+    }
+    let mut fitted: String = text.to_string();
+    while !fitted.is_empty() && font.width(&format!("{}…", fitted), size) > width {
+        fitted.pop();
+    }
+    format!("{}…", fitted.trim_end())
+}
+
+// Helper to place a watermark centered on the page, rising along the
+// diagonal from bottom-left to top-right
+fn watermark_mark(font: &PrintFont, text: &str, width: f32, height: f32) -> Mark {
+    let angle = height.atan2(width);
+    let span = width.hypot(height) * WATERMARK_SPAN;
+    let size = (span / font.width(text, 1.0).max(1.0)).min(WATERMARK_MAX_SIZE);
+    let half = font.width(text, size) / 2.0;
+    let (sin, cos) = angle.sin_cos();
+    // Start half the text back from the center, dropping the baseline so the
+    // letters straddle the diagonal
+    let drop = size * 0.35;
+    Mark::Watermark {
+        x: width / 2.0 - half * cos + drop * sin,
+        y: height / 2.0 + half * sin + drop * cos,
+        size,
+        angle,
+        text: text.to_string(),
+    }
+}
+
+/// Lay out artifact HTML on pages following `layout`: paper size and
+/// orientation, margins, the header and footer (drawn inside the top and
+/// bottom margins, with `learner`, `title`, and `artifact_id` filled in),
+/// "Page N of M" numbers, and the watermark. This is the one layout both PDF
+/// export and print preview use, so the preview matches what's printed.
+pub(crate) fn layout_document(
+    app_handle: &tauri::AppHandle,
+    html: &str,
+    artifact_id: &str,
+    title: &str,
+    learner: Option<&str>,
+    layout: &PrintLayoutSettings,
+) -> Result<Document, String> {
+    let (width, height) = page_size(layout)?;
+    let margins = [
+        layout.margin_top,
+        layout.margin_bottom,
+        layout.margin_left,
+        layout.margin_right,
+    ];
+    if margins.iter().any(|m| !(0.0..=MAX_MARGIN_INCHES).contains(m)) {
+        return Err(format!("Margins must be between 0 and {} inches", MAX_MARGIN_INCHES));
+    }
+    let [top, bottom, left, right] = margins.map(|m| m * POINTS_PER_INCH);
+    let min_content = MIN_CONTENT_INCHES * POINTS_PER_INCH;
+    if width - left - right < min_content || height - top - bottom < min_content {
+        return Err("The margins leave too little room for content".to_string());
+    }
+
+    let fonts = fonts(app_handle)?;
+    let mut flow = Flow {
+        fonts: &fonts,
+        pages: Vec::new(),
+        page: Page::default(),
+        y: top,
+        top,
+        bottom: height - bottom,
+        left,
+        right: width - right,
+        image_row: None,
+    };
+    for block in parse_blocks(app_handle, html) {
+        flow.place(block);
+    }
+    flow.new_page();
+    let mut pages = flow.pages;
+    // A trailing page break leaves an empty last page
+    if pages.len() > 1 && pages.last().is_some_and(|p| p.marks.is_empty()) {
+        pages.pop();
+    }
+
+    let header = fill_template(&layout.header, artifact_id, title, learner);
+    let footer = fill_template(&layout.footer, artifact_id, title, learner);
+    let watermark = fill_template(&layout.watermark, artifact_id, title, learner);
+    let band_width = width - left - right;
+    let header_y = (top * 0.6).max(BAND_SIZE);
+    let footer_y = height - (bottom * 0.4).max(BAND_SIZE * 0.5);
+    let page_count = pages.len();
+    for (index, page) in pages.iter_mut().enumerate() {
+        if !watermark.is_empty() {
+            // Drawn first so the page's content sits on top of it
+            let mark = watermark_mark(&fonts.bold, &watermark, width, height);
+            page.marks.insert(0, mark);
+        }
+        if !header.is_empty() {
+            page.marks.push(Mark::Text {
+                x: left,
+                y: header_y,
+                size: BAND_SIZE,
+                bold: false,
+                text: fit(&fonts.regular, &header, BAND_SIZE, band_width),
+            });
+        }
+        let mut footer_width = band_width;
+        if layout.page_numbers {
+            let number = format!("Page {} of {}", index + 1, page_count);
+            let number_width = fonts.regular.width(&number, BAND_SIZE);
+            page.marks.push(Mark::Text {
+                x: width - right - number_width,
+                y: footer_y,
+                size: BAND_SIZE,
+                bold: false,
+                text: number,
+            });
+            footer_width -= number_width + BAND_SIZE * 2.0;
+        }
+        if !footer.is_empty() && footer_width > 0.0 {
+            page.marks.push(Mark::Text {
+                x: left,
+                y: footer_y,
+                size: BAND_SIZE,
+                bold: false,
+                text: fit(&fonts.regular, &footer, BAND_SIZE, footer_width),
+            });
+        }
+    }
+
+    Ok(Document {
+        width,
+        height,
+        pages,
+    })
+}
+
+// Helper to get a learner's display name, for headers and footers
+async fn learner_name(app_handle: &tauri::AppHandle, learner_id: &str) -> Result<String, String> {
+    learner_storage::read_profiles(app_handle)
+        .await?
+        .iter()
+        .find(|p| p.get("learnerId").and_then(|v| v.as_str()) == Some(learner_id))
+        .and_then(|p| p.get("displayName").and_then(|v| v.as_str()))
+        .map(|name| name.to_string())
+        .ok_or_else(|| format!("Learner not found: {}", learner_id))
+}
+
+/// Load an artifact and lay it out with `layout`, or the `printLayout`
+/// settings when it's `None`. With `learner_id`, that learner's name fills
+/// `{learner}` in the header and footer. Returns the document and the
+/// artifact's title.
+pub(crate) async fn layout_artifact(
+    app_handle: &tauri::AppHandle,
+    artifact_id: &str,
+    layout: Option<PrintLayoutSettings>,
+    learner_id: Option<&str>,
+) -> Result<(Document, String), String> {
+    let layout = match layout {
+        Some(layout) => layout,
+        None => settings_storage::load_settings(app_handle).await?.print_layout,
+    };
+    let learner = match learner_id {
+        Some(learner_id) => Some(learner_name(app_handle, learner_id).await?),
+        None => None,
+    };
+    let artifact = library_storage::read_artifact(app_handle, artifact_id).await?;
+    let html = artifact
+        .get("htmlContent")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let title = artifact
+        .get("title")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Untitled")
+        .to_string();
+
+    let handle = app_handle.clone();
+    let artifact_id = artifact_id.to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        let document =
+            layout_document(&handle, &html, &artifact_id, &title, learner.as_deref(), &layout)?;
+        Ok((document, title))
+    })
+    .await
+    .map_err(|e| format!("Failed to lay out pages: {}", e))?
+}
@@ -0,0 +1,280 @@
+use ab_glyph::{point, Font, OutlineCurve, PxScale};
+use resvg::tiny_skia::{
+    FillRule, FilterQuality, IntSize, Paint, PathBuilder, Pixmap, PixmapPaint, Rect, Stroke,
+    Transform,
+};
+
+use crate::commands::print_layout::{self, Document, Mark, Page, PrintFonts};
+use crate::commands::settings_storage::PrintLayoutSettings;
+use crate::commands::{app_lock, image_cache};
+
+// Preview resolution: screen resolution by default, limited so a page
+// image stays a reasonable size
+const DEFAULT_DPI: f32 = 96.0;
+const MIN_DPI: f32 = 36.0;
+const MAX_DPI: f32 = 200.0;
+
+// Helper to draw a line of text in black with its glyph outlines, placed
+// and advanced exactly as the PDF sets it (em-sized, without kerning)
+fn draw_text(
+    pixmap: &mut Pixmap,
+    fonts: &PrintFonts,
+    x: f32,
+    y: f32,
+    size: f32,
+    bold: bool,
+    text: &str,
+) {
+    let font = if bold { &fonts.bold.font } else { &fonts.regular.font };
+    let units = font.units_per_em().unwrap_or(1000.0);
+    let em = size / units;
+    // ab_glyph scales by the font's height rather than its em
+    let scale = PxScale::from(size * font.height_unscaled() / units);
+
+    let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let pixels = pixmap.data_mut();
+    let mut pen = x;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        let glyph = id.with_scale_and_position(scale, point(pen, y));
+        pen += font.h_advance_unscaled(id) * em;
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < 0 || py < 0 || px >= width || py >= height {
+                return;
+            }
+            // The page is opaque, so darkening by coverage blends in black
+            let i = ((py * width + px) * 4) as usize;
+            let keep = 1.0 - coverage.clamp(0.0, 1.0);
+            for channel in &mut pixels[i..i + 3] {
+                *channel = (*channel as f32 * keep).round() as u8;
+            }
+        });
+    }
+}
+
+// Helper to draw a watermark: the glyph outlines, in font units with y up,
+// are filled in gray after rotating them onto the page
+fn draw_watermark(
+    pixmap: &mut Pixmap,
+    fonts: &PrintFonts,
+    x: f32,
+    y: f32,
+    size: f32,
+    angle: f32,
+    text: &str,
+) {
+    let font = &fonts.bold.font;
+    let em = size / font.units_per_em().unwrap_or(1000.0);
+    let mut path = PathBuilder::new();
+    let mut pen = 0.0;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let Some(outline) = font.outline(id) {
+            // Curves share end points, so a new contour starts wherever a
+            // curve doesn't begin at the last point
+            let mut last = None;
+            for curve in &outline.curves {
+                let (start, end) = match curve {
+                    OutlineCurve::Line(p0, p1) => (*p0, *p1),
+                    OutlineCurve::Quad(p0, _, p2) => (*p0, *p2),
+                    OutlineCurve::Cubic(p0, _, _, p3) => (*p0, *p3),
+                };
+                if last != Some(start) {
+                    path.move_to(pen + start.x, start.y);
+                }
+                match curve {
+                    OutlineCurve::Line(_, p1) => path.line_to(pen + p1.x, p1.y),
+                    OutlineCurve::Quad(_, p1, p2) => {
+                        path.quad_to(pen + p1.x, p1.y, pen + p2.x, p2.y)
+                    }
+                    OutlineCurve::Cubic(_, p1, p2, p3) => {
+                        path.cubic_to(pen + p1.x, p1.y, pen + p2.x, p2.y, pen + p3.x, p3.y)
+                    }
+                }
+                last = Some(end);
+            }
+        }
+        pen += font.h_advance_unscaled(id);
+    }
+    let Some(path) = path.finish() else {
+        return;
+    };
+
+    let gray = (print_layout::WATERMARK_GRAY * 255.0).round() as u8;
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(gray, gray, gray, 255);
+    paint.anti_alias = true;
+    // Font units to pixels: scale by the em, rotate, and flip y
+    let (sin, cos) = angle.sin_cos();
+    let transform = Transform::from_row(em * cos, -em * sin, -em * sin, -em * cos, x, y);
+    pixmap.fill_path(&path, &paint, FillRule::Winding, transform, None);
+}
+
+// Helper to draw one laid-out page at `scale` pixels per point
+fn render_page(
+    document: &Document,
+    page: &Page,
+    fonts: &PrintFonts,
+    scale: f32,
+) -> Result<Pixmap, String> {
+    let width = (document.width * scale).round() as u32;
+    let height = (document.height * scale).round() as u32;
+    let mut pixmap = Pixmap::new(width, height).ok_or("Invalid page size")?;
+    pixmap.fill(resvg::tiny_skia::Color::WHITE);
+
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(0, 0, 0, 255);
+    paint.anti_alias = true;
+    let stroke = Stroke {
+        width: print_layout::STROKE_WIDTH * scale,
+        ..Default::default()
+    };
+    let transform = Transform::from_scale(scale, scale);
+
+    for mark in &page.marks {
+        match mark {
+            Mark::Text {
+                x,
+                y,
+                size,
+                bold,
+                text,
+            } => draw_text(
+                &mut pixmap,
+                fonts,
+                x * scale,
+                y * scale,
+                size * scale,
+                *bold,
+                text,
+            ),
+            Mark::Line { x1, y1, x2, y2 } => {
+                let mut path = PathBuilder::new();
+                path.move_to(*x1, *y1);
+                path.line_to(*x2, *y2);
+                if let Some(path) = path.finish() {
+                    pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+                }
+            }
+            Mark::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                if let Some(rect) = Rect::from_xywh(*x, *y, *width, *height) {
+                    let path = PathBuilder::from_rect(rect);
+                    pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+                }
+            }
+            Mark::Image {
+                x,
+                y,
+                width,
+                height,
+                image,
+            } => {
+                let size = IntSize::from_wh(image.width(), image.height())
+                    .ok_or("Invalid image size")?;
+                let rgba = image
+                    .pixels()
+                    .flat_map(|p| [p.0[0], p.0[1], p.0[2], 255])
+                    .collect();
+                let source = Pixmap::from_vec(rgba, size).ok_or("Invalid image size")?;
+                let placement = Transform::from_row(
+                    width * scale / image.width() as f32,
+                    0.0,
+                    0.0,
+                    height * scale / image.height() as f32,
+                    x * scale,
+                    y * scale,
+                );
+                let image_paint = PixmapPaint {
+                    quality: FilterQuality::Bicubic,
+                    ..Default::default()
+                };
+                pixmap.draw_pixmap(0, 0, source.as_ref(), &image_paint, placement, None);
+            }
+            Mark::Watermark {
+                x,
+                y,
+                size,
+                angle,
+                text,
+            } => draw_watermark(
+                &mut pixmap,
+                fonts,
+                x * scale,
+                y * scale,
+                size * scale,
+                *angle,
+                text,
+            ),
+        }
+    }
+    Ok(pixmap)
+}
+
+// ============================================
+// Print Preview Commands
+// ============================================
+
+/// Preview how an artifact will print: it's laid out by the same pipeline
+/// as `export_pdf` (with `layout` or the `printLayout` settings, and
+/// `learner_id`'s name in the header and footer) and each page is drawn as
+/// a PNG at `dpi` (96 by default). Returns `{ pageCount, pageWidth,
+/// pageHeight, dpi, pages }`, with the page size in points and `pages` as
+/// PNG data URIs.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn print_preview(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    layout: Option<PrintLayoutSettings>,
+    learner_id: Option<String>,
+    dpi: Option<f32>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let dpi = dpi.unwrap_or(DEFAULT_DPI);
+    if !(MIN_DPI..=MAX_DPI).contains(&dpi) {
+        return Err(format!("DPI must be between {} and {}", MIN_DPI, MAX_DPI));
+    }
+    let (document, _) =
+        print_layout::layout_artifact(&app_handle, &artifact_id, layout, learner_id.as_deref())
+            .await?;
+
+    let handle = app_handle.clone();
+    let (pages, page_width, page_height) = tauri::async_runtime::spawn_blocking(move || {
+        let fonts = print_layout::fonts(&handle)?;
+        let scale = dpi / 72.0;
+        let pages = document
+            .pages
+            .iter()
+            .map(|page| {
+                let png = render_page(&document, page, &fonts, scale)?
+                    .encode_png()
+                    .map_err(|e| format!("Failed to encode preview: {}", e))?;
+                Ok(image_cache::data_uri("image/png", &png))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok::<_, String>((pages, document.width, document.height))
+    })
+    .await
+    .map_err(|e| format!("Failed to render preview: {}", e))??;
+
+    let response = serde_json::json!({
+        "pageCount": pages.len(),
+        "pageWidth": page_width,
+        "pageHeight": page_height,
+        "dpi": dpi,
+        "pages": pages,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,200 @@
+use serde_json::Value;
+use std::path::PathBuf;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, project_storage, session_mode};
+
+// Per-project timelines: projects/activity/{id}.json
+const ACTIVITY_DIR: &str = "activity";
+
+// Oldest events are dropped beyond this
+const MAX_EVENTS: usize = 500;
+
+// Event types that can be recorded
+pub(crate) const ARTIFACT_ADDED: &str = "artifact_added";
+pub(crate) const ARTIFACT_REMOVED: &str = "artifact_removed";
+pub(crate) const GENERATION_RUN: &str = "generation_run";
+pub(crate) const QUICK_CHECK_COMPLETED: &str = "quick_check_completed";
+pub(crate) const NOTE_ADDED: &str = "note_added";
+
+// Event types the frontend may record directly; the others are recorded by
+// the backend as they happen
+const CLIENT_EVENT_TYPES: &[&str] = &[GENERATION_RUN, NOTE_ADDED];
+
+// Helper to get the path of a project's timeline file
+fn get_timeline_path(app_handle: &tauri::AppHandle, project_id: &str) -> Result<PathBuf, String> {
+    if !project_storage::is_valid_project_id(project_id) {
+        return Err(format!("Invalid project ID: {}", project_id));
+    }
+    Ok(project_storage::get_projects_dir(app_handle)?
+        .join(ACTIVITY_DIR)
+        .join(format!("{}.json", project_id)))
+}
+
+// Helper to read a project's timeline (oldest first)
+pub(crate) async fn read_timeline(app_handle: &tauri::AppHandle, project_id: &str) -> Vec<Value> {
+    let Ok(path) = get_timeline_path(app_handle, project_id) else {
+        return Vec::new();
+    };
+    match fs::read_to_string(&path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Append an event to a project's timeline. Returns the event.
+pub(crate) async fn record(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+    event_type: &str,
+    details: Value,
+) -> Result<Value, String> {
+    let path = get_timeline_path(app_handle, project_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create activity directory: {}", e))?;
+    }
+
+    let event = serde_json::json!({
+        "eventId": uuid::Uuid::new_v4().to_string(),
+        "type": event_type,
+        "at": chrono::Utc::now().to_rfc3339(),
+        "details": details,
+    });
+
+    let mut events = read_timeline(app_handle, project_id).await;
+    events.push(event.clone());
+    if events.len() > MAX_EVENTS {
+        events.drain(..events.len() - MAX_EVENTS);
+    }
+
+    let content = serde_json::to_string_pretty(&events)
+        .map_err(|e| format!("Failed to serialize timeline: {}", e))?;
+    fs::write(&path, content)
+        .await
+        .map_err(|e| format!("Failed to write timeline: {}", e))?;
+    Ok(event)
+}
+
+/// Record a completed quick check on the projects it belongs to: the
+/// result's `projectId` if set, otherwise the learner's projects that link
+/// the result's objective. Failures are logged, not returned.
+pub(crate) async fn record_quick_check(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+    result: &Value,
+) {
+    let objective_id = result.get("objectiveId").and_then(|v| v.as_str());
+
+    let project_ids: Vec<String> = match result.get("projectId").and_then(|v| v.as_str()) {
+        Some(project_id) => vec![project_id.to_string()],
+        None => project_storage::read_index(app_handle)
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter(|p| p.get("learnerId").and_then(|v| v.as_str()) == Some(learner_id))
+            .filter(|p| {
+                p.get("linkedObjectiveIds")
+                    .and_then(|v| v.as_array())
+                    .is_some_and(|ids| ids.iter().any(|id| id.as_str() == objective_id))
+            })
+            .filter_map(|p| p.get("projectId").and_then(|v| v.as_str()).map(String::from))
+            .collect(),
+    };
+
+    let details = serde_json::json!({
+        "resultId": result.get("resultId"),
+        "learnerId": learner_id,
+        "objectiveId": objective_id,
+        "score": result.get("score"),
+    });
+    for project_id in project_ids {
+        let recorded =
+            record(app_handle, &project_id, QUICK_CHECK_COMPLETED, details.clone()).await;
+        if let Err(e) = recorded {
+            tracing::warn!(project_id = %project_id, error = %e, "Failed to record activity");
+        }
+    }
+}
+
+/// Delete a project's timeline
+pub(crate) async fn remove_timeline(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+) -> Result<(), String> {
+    let path = get_timeline_path(app_handle, project_id)?;
+    if path.exists() {
+        fs::remove_file(&path)
+            .await
+            .map_err(|e| format!("Failed to delete timeline: {}", e))?;
+    }
+    Ok(())
+}
+
+// ============================================
+// Project Activity Commands
+// ============================================
+
+/// Get a project's activity timeline, newest first
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_project_timeline(
+    app_handle: tauri::AppHandle,
+    project_id: String,
+    limit: Option<usize>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    // Fails with "Project not found" for unknown projects
+    project_storage::read_project(&app_handle, &project_id).await?;
+
+    let mut events = read_timeline(&app_handle, &project_id).await;
+    events.reverse();
+    if let Some(limit) = limit {
+        events.truncate(limit);
+    }
+    serde_json::to_string(&events).map_err(|e| format!("Failed to serialize timeline: {}", e))
+}
+
+/// Record a frontend-driven event (`generation_run` or `note_added`) on a
+/// project's timeline. Also bumps the project's `lastActivityDate`.
+/// `generation_run` details should include `durationMs` for project stats.
+/// Returns the recorded event.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn record_project_activity(
+    app_handle: tauri::AppHandle,
+    project_id: String,
+    event_type: String,
+    details: Option<Value>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    if !CLIENT_EVENT_TYPES.contains(&event_type.as_str()) {
+        return Err(format!("Unsupported activity type: {}", event_type));
+    }
+
+    let mut project = project_storage::read_project(&app_handle, &project_id).await?;
+    let event = record(
+        &app_handle,
+        &project_id,
+        &event_type,
+        details.unwrap_or(Value::Null),
+    )
+    .await?;
+
+    if let Some(obj) = project.as_object_mut() {
+        obj.insert(
+            "lastActivityDate".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        );
+    }
+    project_storage::write_project(&app_handle, &project).await?;
+
+    audit_log::record(&app_handle, "record_project_activity", "project", &[&project_id]).await;
+
+    Ok(event.to_string())
+}
@@ -0,0 +1,341 @@
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use tokio::fs;
+
+use crate::archive::{self, ArchiveEntry};
+use crate::commands::{
+    app_lock, audit_log, design_pack_storage, disk_space, learner_bundle, lesson_plans,
+    library_storage, project_storage, session_mode, task_manager,
+};
+
+const BUNDLE_FORMAT: &str = "ta-project-bundle";
+const BUNDLE_VERSION: u64 = 1;
+
+// Paths inside the bundle archive
+const MANIFEST_ENTRY: &str = "manifest.json";
+const PROJECT_ENTRY: &str = "project.json";
+const ARTIFACTS_PREFIX: &str = "artifacts/";
+// Each pack is nested as its own design pack zip: packs/{packId}.zip
+const PACKS_PREFIX: &str = "packs/";
+
+// Pack fields that change on every import and are ignored when comparing
+const PACK_VOLATILE_KEYS: &[&str] = &["createdAt", "updatedAt"];
+
+// Helper to read a full artifact from the library
+async fn read_artifact(app_handle: &tauri::AppHandle, artifact_id: &str) -> Option<Value> {
+    let artifacts_dir = library_storage::get_artifacts_dir(app_handle).ok()?;
+    let content = fs::read_to_string(artifacts_dir.join(format!("{}.json", artifact_id)))
+        .await
+        .ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+// Helper to compare two packs, ignoring import timestamps
+fn same_pack(a: &Value, b: &Value) -> bool {
+    let strip = |pack: &Value| {
+        let mut pack = pack.clone();
+        if let Some(obj) = pack.as_object_mut() {
+            for key in PACK_VOLATILE_KEYS {
+                obj.remove(*key);
+            }
+        }
+        pack
+    };
+    strip(a) == strip(b)
+}
+
+// Helper to build a project bundle and write it to `output_path`,
+// reporting progress on `task`
+async fn write_project_bundle(
+    app_handle: &tauri::AppHandle,
+    task: &task_manager::TaskHandle,
+    project_id: &str,
+    output_path: &str,
+) -> Result<(), String> {
+    let project = project_storage::read_project(app_handle, project_id).await?;
+
+    // Artifacts the project lists plus any that point back at it, and the
+    // ones their lesson plans link to
+    let mut artifact_ids: BTreeSet<String> = project
+        .get("artifactIds")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+    artifact_ids.extend(
+        library_storage::read_index_entries(app_handle)
+            .await?
+            .iter()
+            .filter(|a| a.get("projectId").and_then(|v| v.as_str()) == Some(project_id))
+            .filter_map(|a| a.get("artifactId").and_then(|v| v.as_str()).map(String::from)),
+    );
+    let artifact_ids = lesson_plans::with_linked_artifacts(app_handle, artifact_ids).await?;
+
+    let mut entries: Vec<ArchiveEntry> = Vec::new();
+    let project_content = serde_json::to_vec_pretty(&project)
+        .map_err(|e| format!("Failed to serialize project: {}", e))?;
+    entries.push((PROJECT_ENTRY.to_string(), project_content));
+
+    let mut pack_ids: BTreeSet<String> = BTreeSet::new();
+    if let Some(pack_id) = project.get("defaultDesignPackId").and_then(|v| v.as_str()) {
+        pack_ids.insert(pack_id.to_string());
+    }
+
+    // One step per artifact and pack, plus writing the bundle
+    let total = Some((artifact_ids.len() + pack_ids.len() + 1) as u64);
+    let mut step = 0;
+
+    let mut exported_artifacts = Vec::new();
+    for artifact_id in artifact_ids {
+        task.check_cancelled()?;
+        step += 1;
+        task.progress(step, total, Some("Exporting artifacts"));
+        let Some(artifact) = read_artifact(app_handle, &artifact_id).await else {
+            continue;
+        };
+        if let Some(pack_id) = artifact.get("designPackId").and_then(|v| v.as_str()) {
+            pack_ids.insert(pack_id.to_string());
+        }
+        let contents = serde_json::to_vec_pretty(&artifact)
+            .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+        entries.push((format!("{}{}.json", ARTIFACTS_PREFIX, artifact_id), contents));
+        exported_artifacts.push(artifact_id);
+    }
+
+    // Referenced design packs, skipping built-in and missing ones
+    let mut exported_packs = Vec::new();
+    for pack_id in pack_ids {
+        task.check_cancelled()?;
+        step += 1;
+        task.progress(step, total, Some("Exporting design packs"));
+        let Ok(pack) = design_pack_storage::find_pack(app_handle, &pack_id).await else {
+            continue;
+        };
+        if pack.get("builtIn").and_then(|v| v.as_bool()) == Some(true) {
+            continue;
+        }
+        let contents = design_pack_storage::build_pack_zip(app_handle, &pack_id).await?;
+        entries.push((format!("{}{}.zip", PACKS_PREFIX, pack_id), contents));
+        exported_packs.push(pack_id);
+    }
+
+    let manifest = serde_json::json!({
+        "format": BUNDLE_FORMAT,
+        "version": BUNDLE_VERSION,
+        "appVersion": app_handle.package_info().version.to_string(),
+        "exportedAt": chrono::Utc::now().to_rfc3339(),
+        "projectId": project_id,
+        "artifactIds": exported_artifacts,
+        "packIds": exported_packs,
+    });
+    let manifest_content = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    entries.insert(0, (MANIFEST_ENTRY.to_string(), manifest_content));
+
+    task.check_cancelled()?;
+    task.progress(step + 1, total, Some("Writing bundle"));
+    let bundle = archive::build_zip(&entries)?;
+    disk_space::ensure_available(Path::new(output_path), bundle.len() as u64)?;
+
+    // Create parent directories if they don't exist
+    if let Some(parent) = Path::new(output_path).parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+
+    task.cancellable(async {
+        fs::write(output_path, bundle)
+            .await
+            .map_err(|e| format!("Failed to write project bundle: {}", e))
+    })
+    .await
+}
+
+// ============================================
+// Project Bundle Commands
+// ============================================
+
+/// Export a project, its artifacts, and the design packs they use as a
+/// single zip bundle. Built-in packs are left out since every install has
+/// them. Runs as an "export" task that can be cancelled between steps,
+/// using `operation_id` as its ID when given.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn export_project_bundle(
+    app_handle: tauri::AppHandle,
+    project_id: String,
+    output_path: String,
+    operation_id: Option<String>,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let task =
+        task_manager::start_task(&app_handle, operation_id, "export", "Export project bundle")?;
+    let result = write_project_bundle(&app_handle, &task, &project_id, &output_path).await;
+    task.finish(&result);
+    result?;
+
+    audit_log::record(&app_handle, "export_project_bundle", "project", &[&project_id]).await;
+
+    Ok(())
+}
+
+/// Import a project bundle produced by `export_project_bundle`.
+///
+/// The project is always added as a new project; it keeps its ID unless that
+/// ID is already in use. Artifacts and design packs already in the library
+/// with the same content are reused, and ones whose IDs collide with
+/// different content are imported under fresh IDs. A learner link is kept
+/// only if that learner exists on this machine. Returns a JSON summary.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn import_project_bundle(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let bytes = fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read project bundle: {}", e))?;
+    let entries: HashMap<String, Vec<u8>> = archive::read_zip(&bytes)?.into_iter().collect();
+
+    // Validate the manifest
+    let manifest: Value = entries
+        .get(MANIFEST_ENTRY)
+        .ok_or("Project bundle is missing its manifest")
+        .and_then(|b| serde_json::from_slice(b).map_err(|_| "Invalid project bundle manifest"))?;
+    if manifest.get("format").and_then(|v| v.as_str()) != Some(BUNDLE_FORMAT) {
+        return Err("File is not a project bundle".to_string());
+    }
+    let version = manifest.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > BUNDLE_VERSION {
+        return Err(format!("Unsupported project bundle version: {}", version));
+    }
+
+    let mut project: Value = entries
+        .get(PROJECT_ENTRY)
+        .ok_or("Project bundle is missing its project")
+        .and_then(|b| serde_json::from_slice(b).map_err(|_| "Invalid project bundle project"))?;
+    let bundle_project_id = project
+        .get("projectId")
+        .and_then(|v| v.as_str())
+        .ok_or("Project must have a projectId")?
+        .to_string();
+
+    let mut id_map: HashMap<String, String> = HashMap::new();
+    if !project_storage::is_valid_project_id(&bundle_project_id)
+        || project_storage::read_project(&app_handle, &bundle_project_id)
+            .await
+            .is_ok()
+    {
+        id_map.insert(bundle_project_id.clone(), uuid::Uuid::new_v4().to_string());
+    }
+
+    // Validate artifacts before writing anything, remapping colliding IDs
+    let mut artifacts: Vec<Value> = Vec::new();
+    for (name, contents) in entries.iter().filter(|(n, _)| n.starts_with(ARTIFACTS_PREFIX)) {
+        let artifact: Value = serde_json::from_slice(contents)
+            .map_err(|e| format!("Invalid artifact {} in bundle: {}", name, e))?;
+        let artifact_id = artifact
+            .get("artifactId")
+            .and_then(|v| v.as_str())
+            .ok_or("Artifact must have an artifactId")?
+            .to_string();
+        if !library_storage::is_safe_id(&artifact_id) {
+            id_map.insert(artifact_id, uuid::Uuid::new_v4().to_string());
+        } else if let Some(existing) = read_artifact(&app_handle, &artifact_id).await {
+            if existing == artifact {
+                // Already in the library, nothing to import
+                continue;
+            }
+            id_map.insert(artifact_id, uuid::Uuid::new_v4().to_string());
+        }
+        artifacts.push(artifact);
+    }
+
+    // Install design packs, reusing identical ones
+    let mut imported_packs = Vec::new();
+    for (name, contents) in entries.iter().filter(|(n, _)| n.starts_with(PACKS_PREFIX)) {
+        let pack_entries: HashMap<String, Vec<u8>> =
+            archive::read_zip(contents)?.into_iter().collect();
+        let bundled_pack: Value = pack_entries
+            .get(design_pack_storage::PACK_ENTRY)
+            .and_then(|b| serde_json::from_slice(b).ok())
+            .ok_or_else(|| format!("Invalid design pack {} in bundle", name))?;
+        let bundled_id = bundled_pack
+            .get("packId")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        if let Ok(existing) = design_pack_storage::find_pack(&app_handle, &bundled_id).await {
+            if same_pack(&existing, &bundled_pack) {
+                continue;
+            }
+        }
+        let summary = design_pack_storage::install_pack_zip(&app_handle, contents).await?;
+        let pack_id = summary
+            .get("packId")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        if pack_id != bundled_id {
+            id_map.insert(bundled_id, pack_id.clone());
+        }
+        imported_packs.push(pack_id);
+    }
+
+    // Write artifacts with remapped IDs
+    let mut imported_artifacts = Vec::new();
+    for mut artifact in artifacts {
+        learner_bundle::remap_ids(&mut artifact, &id_map);
+        let content = serde_json::to_string_pretty(&artifact)
+            .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+        library_storage::write_artifact(&app_handle, &artifact, &content).await?;
+        if let Some(artifact_id) = artifact.get("artifactId").and_then(|v| v.as_str()) {
+            imported_artifacts.push(artifact_id.to_string());
+        }
+    }
+
+    // Save the project, dropping a link to a learner this machine doesn't have
+    learner_bundle::remap_ids(&mut project, &id_map);
+    let learner_id = project.get("learnerId").and_then(|v| v.as_str()).map(String::from);
+    let learner_exists = match &learner_id {
+        Some(learner_id) => learner_bundle::read_learner_profile(&app_handle, learner_id)
+            .await
+            .is_ok(),
+        None => false,
+    };
+    let now = chrono::Utc::now().to_rfc3339();
+    if let Some(obj) = project.as_object_mut() {
+        if !learner_exists {
+            obj.insert("learnerId".to_string(), Value::Null);
+        }
+        obj.insert("updatedAt".to_string(), Value::String(now));
+    }
+    let project_id = project
+        .get("projectId")
+        .and_then(|v| v.as_str())
+        .unwrap_or(&bundle_project_id)
+        .to_string();
+    project_storage::write_project(&app_handle, &project).await?;
+
+    let mut audited_ids = vec![project_id.as_str()];
+    audited_ids.extend(imported_artifacts.iter().map(String::as_str));
+    audit_log::record(&app_handle, "import_project_bundle", "project", &audited_ids).await;
+
+    let summary = serde_json::json!({
+        "projectId": project_id,
+        "importedArtifactIds": imported_artifacts,
+        "importedPackIds": imported_packs,
+        "learnerLinked": learner_exists,
+        "remappedIds": id_map,
+    });
+    Ok(summary.to_string())
+}
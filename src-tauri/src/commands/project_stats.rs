@@ -0,0 +1,148 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use tokio::fs;
+
+use crate::commands::{
+    app_lock, learner_storage, library_storage, project_activity, project_storage, session_mode,
+};
+
+// Helper to read the string values of an array field
+fn strings(value: &Value, key: &str) -> Vec<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Read a learner's mastery records keyed by objective ID; empty if none
+/// are stored
+pub(crate) async fn read_mastery(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+) -> Result<serde_json::Map<String, Value>, String> {
+    let learner_dir = learner_storage::get_learner_dir(app_handle, learner_id)?;
+    let mastery_path = learner_dir.join(learner_storage::MASTERY_FILE);
+    let Ok(content) = fs::read_to_string(&mastery_path).await else {
+        return Ok(serde_json::Map::new());
+    };
+    let mastery: Value = serde_json::from_str(&content).unwrap_or(Value::Null);
+    Ok(mastery
+        .get("objectives")
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default())
+}
+
+// ============================================
+// Project Statistics Commands
+// ============================================
+
+/// Compute dashboard statistics for a project: artifact counts by type,
+/// planned objectives and which ones its artifacts cover, the learner's
+/// mastery of those objectives, and total generation time recorded on the
+/// project's timeline
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_project_stats(
+    app_handle: tauri::AppHandle,
+    project_id: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let project = project_storage::read_project(&app_handle, &project_id).await?;
+    let artifact_ids: BTreeSet<String> = strings(&project, "artifactIds").into_iter().collect();
+
+    // Artifacts listed by the project or pointing back at it
+    let artifacts: Vec<Value> = library_storage::read_index_entries(&app_handle)
+        .await?
+        .into_iter()
+        .filter(|a| {
+            let listed = a
+                .get("artifactId")
+                .and_then(|v| v.as_str())
+                .is_some_and(|id| artifact_ids.contains(id));
+            listed || a.get("projectId").and_then(|v| v.as_str()) == Some(&project_id)
+        })
+        .collect();
+
+    let mut by_type: BTreeMap<String, usize> = BTreeMap::new();
+    let mut covered: BTreeSet<String> = BTreeSet::new();
+    for artifact in &artifacts {
+        let artifact_type = artifact.get("type").and_then(|v| v.as_str()).unwrap_or("unknown");
+        *by_type.entry(artifact_type.to_string()).or_default() += 1;
+        covered.extend(strings(artifact, "objectiveTags"));
+    }
+
+    // Objectives: planned on the project vs. covered by its artifacts
+    let planned: Vec<String> = strings(&project, "linkedObjectiveIds");
+    let planned_covered: Vec<&String> = planned.iter().filter(|id| covered.contains(*id)).collect();
+    let not_covered: Vec<&String> = planned.iter().filter(|id| !covered.contains(*id)).collect();
+    let unplanned: Vec<&String> = covered.iter().filter(|id| !planned.contains(id)).collect();
+
+    // Learner mastery on the planned objectives
+    let mastery = match project.get("learnerId").and_then(|v| v.as_str()) {
+        Some(learner_id) => {
+            let records = read_mastery(&app_handle, learner_id).await?;
+            let mut by_state: BTreeMap<String, usize> = BTreeMap::new();
+            let objectives: Vec<Value> = planned
+                .iter()
+                .map(|objective_id| {
+                    let record = records.get(objective_id);
+                    let state = record
+                        .and_then(|r| r.get("state"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("not_started");
+                    *by_state.entry(state.to_string()).or_default() += 1;
+                    serde_json::json!({
+                        "objectiveId": objective_id,
+                        "state": state,
+                        "lastScore": record.and_then(|r| r.get("lastScore")),
+                        "attempts": record.and_then(|r| r.get("attempts")),
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "learnerId": learner_id,
+                "byState": by_state,
+                "objectives": objectives,
+            })
+        }
+        None => Value::Null,
+    };
+
+    // Generation time from `generation_run` events (`details.durationMs`)
+    let timeline = project_activity::read_timeline(&app_handle, &project_id).await;
+    let generation_runs: Vec<&Value> = timeline
+        .iter()
+        .filter(|e| {
+            e.get("type").and_then(|v| v.as_str()) == Some(project_activity::GENERATION_RUN)
+        })
+        .collect();
+    let generation_ms: u64 = generation_runs
+        .iter()
+        .filter_map(|e| e.get("details").and_then(|d| d.get("durationMs")))
+        .filter_map(|v| v.as_u64())
+        .sum();
+
+    let response = serde_json::json!({
+        "projectId": project_id,
+        "artifacts": {
+            "total": artifacts.len(),
+            "byType": by_type,
+        },
+        "objectives": {
+            "planned": planned.len(),
+            "covered": planned_covered.len(),
+            "notCovered": not_covered,
+            "unplanned": unplanned,
+        },
+        "mastery": mastery,
+        "generation": {
+            "runs": generation_runs.len(),
+            "totalMs": generation_ms,
+        },
+    });
+    Ok(response.to_string())
+}
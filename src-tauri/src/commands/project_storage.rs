@@ -1,12 +1,42 @@
 use serde_json::Value;
+use std::collections::BTreeSet;
 use std::path::PathBuf;
+use tauri::Manager;
 use tokio::fs;
 
+use crate::commands::storage_events::{self, StorageEvent};
+use crate::commands::{
+    app_lock, audit_log, collections, index_cache, library_storage, project_activity, session_mode,
+};
+
 const PROJECTS_DIR: &str = "projects";
 const INDEX_FILE: &str = "projects.json";
 
+// What `delete_local_project` does with the project's artifacts
+const KEEP_ARTIFACTS: &str = "keep";
+const CASCADE_ARTIFACTS: &str = "cascade";
+
+// Fields copied into the index; the full project lives in projects/{id}.json
+const INDEX_KEYS: &[&str] = &[
+    "projectId",
+    "type",
+    "name",
+    "description",
+    "grade",
+    "gradeBand",
+    "subjectFocus",
+    "learnerId",
+    "linkedObjectiveIds",
+    "defaultDesignPackId",
+    "artifactIds",
+    "status",
+    "lastActivityDate",
+    "createdAt",
+    "updatedAt",
+];
+
 // Helper to get the projects directory
-fn get_projects_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn get_projects_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     let app_data_dir = app_handle
         .path()
         .app_data_dir()
@@ -15,70 +45,289 @@ fn get_projects_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
 }
 
 // Helper to get the index file path
-fn get_index_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn get_index_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(get_projects_dir(app_handle)?.join(INDEX_FILE))
 }
 
-// ============================================
-// Local Project Commands
-// ============================================
+// Helper to check a project ID is safe to use as a file name
+pub(crate) fn is_valid_project_id(project_id: &str) -> bool {
+    !project_id.is_empty()
+        && project_id.len() <= 128
+        && project_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+}
 
-/// Get all local projects
-#[tauri::command]
-pub async fn get_local_projects(app_handle: tauri::AppHandle) -> Result<String, String> {
-    let index_path = get_index_path(&app_handle)?;
+// Helper to get the path of a project's own file
+pub(crate) fn get_project_path(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+) -> Result<PathBuf, String> {
+    if !is_valid_project_id(project_id) {
+        return Err(format!("Invalid project ID: {}", project_id));
+    }
+    Ok(get_projects_dir(app_handle)?.join(format!("{}.json", project_id)))
+}
 
-    // If file doesn't exist, return empty array
-    if !index_path.exists() {
-        return Ok("[]".to_string());
+/// Build the lightweight index entry for a project. Anything not listed in
+/// `INDEX_KEYS` (such as embedded plans) only lives in the project's file.
+pub(crate) fn index_entry_for(project: &Value) -> Value {
+    let entry: serde_json::Map<String, Value> = INDEX_KEYS
+        .iter()
+        .filter_map(|key| project.get(*key).map(|v| (key.to_string(), v.clone())))
+        .collect();
+    Value::Object(entry)
+}
+
+// Helper to read the project index
+pub(crate) async fn read_index(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let index_path = get_index_path(app_handle)?;
+    let index = index_cache::read_index(app_handle, &index_path, "projects").await?;
+    Ok(index
+        .as_deref()
+        .and_then(|index| index.as_array())
+        .cloned()
+        .unwrap_or_default())
+}
+
+// Helper to write the project index
+async fn write_index(app_handle: &tauri::AppHandle, projects: &[Value]) -> Result<(), String> {
+    let index_path = get_index_path(app_handle)?;
+    index_cache::write_index(app_handle, &index_path, Value::from(projects), "projects").await
+}
+
+/// Read a full project. Falls back to the index entry for projects whose ID
+/// can't be used as a file name.
+pub(crate) async fn read_project(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+) -> Result<Value, String> {
+    if is_valid_project_id(project_id) {
+        let project_path = get_project_path(app_handle, project_id)?;
+        if project_path.exists() {
+            let content = fs::read_to_string(&project_path)
+                .await
+                .map_err(|e| format!("Failed to read project: {}", e))?;
+            return serde_json::from_str(&content).map_err(|e| format!("Invalid project: {}", e));
+        }
     }
 
-    fs::read_to_string(&index_path)
+    read_index(app_handle)
+        .await?
+        .into_iter()
+        .find(|p| p.get("projectId").and_then(|v| v.as_str()) == Some(project_id))
+        .ok_or_else(|| format!("Project not found: {}", project_id))
+}
+
+/// Write a full project to its own file and upsert its index entry
+pub(crate) async fn write_project(
+    app_handle: &tauri::AppHandle,
+    project: &Value,
+) -> Result<(), String> {
+    let projects_dir = get_projects_dir(app_handle)?;
+
+    // Create directory if it doesn't exist
+    fs::create_dir_all(&projects_dir)
+        .await
+        .map_err(|e| format!("Failed to create projects directory: {}", e))?;
+
+    let project_id = project
+        .get("projectId")
+        .and_then(|v| v.as_str())
+        .ok_or("Project must have a projectId")?;
+
+    // Save the full project to its own file
+    let content = serde_json::to_string_pretty(project)
+        .map_err(|e| format!("Failed to serialize project: {}", e))?;
+    fs::write(get_project_path(app_handle, project_id)?, content)
         .await
-        .map_err(|e| format!("Failed to read projects: {}", e))
+        .map_err(|e| format!("Failed to write project: {}", e))?;
+
+    // Find and update the index entry, or add a new one
+    let mut projects = read_index(app_handle).await?;
+    let entry = index_entry_for(project);
+    match projects
+        .iter_mut()
+        .find(|p| p.get("projectId").and_then(|v| v.as_str()) == Some(project_id))
+    {
+        Some(existing) => *existing = entry,
+        None => projects.push(entry),
+    }
+    write_index(app_handle, &projects).await?;
+    storage_events::emit(app_handle, StorageEvent::ProjectUpdated(project_id));
+    Ok(())
 }
 
-/// Get a specific project by ID
-#[tauri::command]
-pub async fn get_local_project(
-    app_handle: tauri::AppHandle,
-    project_id: String,
-) -> Result<String, String> {
-    let index_path = get_index_path(&app_handle)?;
+/// Remove a project's file and index entry. Returns whether it existed.
+pub(crate) async fn remove_project(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+) -> Result<bool, String> {
+    let mut removed = false;
+
+    if is_valid_project_id(project_id) {
+        let project_path = get_project_path(app_handle, project_id)?;
+        if project_path.exists() {
+            fs::remove_file(&project_path)
+                .await
+                .map_err(|e| format!("Failed to delete project: {}", e))?;
+            removed = true;
+        }
+    }
 
-    if !index_path.exists() {
-        return Err(format!("Project not found: {}", project_id));
+    if is_valid_project_id(project_id) {
+        project_activity::remove_timeline(app_handle, project_id).await?;
     }
 
-    let content = fs::read_to_string(&index_path)
-        .await
-        .map_err(|e| format!("Failed to read projects: {}", e))?;
+    let mut projects = read_index(app_handle).await?;
+    let before = projects.len();
+    projects.retain(|p| p.get("projectId").and_then(|v| v.as_str()) != Some(project_id));
+    if projects.len() != before {
+        write_index(app_handle, &projects).await?;
+        removed = true;
+    }
 
-    let projects: Vec<Value> = serde_json::from_str(&content).unwrap_or_else(|_| Vec::new());
+    if removed {
+        storage_events::emit(app_handle, StorageEvent::ProjectDeleted(project_id));
+    }
+    Ok(removed)
+}
 
-    for project in projects {
-        if project.get("projectId").and_then(|v| v.as_str()) == Some(&project_id) {
-            return serde_json::to_string(&project)
-                .map_err(|e| format!("Failed to serialize project: {}", e));
+// Helper to read a string array field from a project
+fn string_array(project: &Value, key: &str) -> Vec<String> {
+    project
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+// Helper to bump a project's activity timestamps
+fn touch_project(obj: &mut serde_json::Map<String, Value>) {
+    let now = Value::String(chrono::Utc::now().to_rfc3339());
+    obj.insert("lastActivityDate".to_string(), now.clone());
+    obj.insert("updatedAt".to_string(), now);
+}
+
+// Helper to record artifact_added / artifact_removed events; failures are
+// only logged since the project itself is already saved
+async fn record_artifact_events(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+    event_type: &str,
+    artifact_ids: &[String],
+) {
+    for artifact_id in artifact_ids {
+        let details = serde_json::json!({ "artifactId": artifact_id });
+        let recorded = project_activity::record(app_handle, project_id, event_type, details).await;
+        if let Err(e) = recorded {
+            tracing::warn!(error = %e, "Failed to record project activity");
         }
     }
+}
+
+/// Remove an artifact ID from every project that lists it. Returns the IDs
+/// of the projects that changed.
+pub(crate) async fn remove_artifact_references(
+    app_handle: &tauri::AppHandle,
+    artifact_id: &str,
+) -> Result<Vec<String>, String> {
+    let referencing: Vec<String> = read_index(app_handle)
+        .await?
+        .iter()
+        .filter(|p| string_array(p, "artifactIds").iter().any(|id| id == artifact_id))
+        .filter_map(|p| p.get("projectId").and_then(|v| v.as_str()).map(String::from))
+        .collect();
+
+    for project_id in &referencing {
+        let mut project = read_project(app_handle, project_id).await?;
+        if let Some(obj) = project.as_object_mut() {
+            if let Some(arr) = obj.get_mut("artifactIds").and_then(|v| v.as_array_mut()) {
+                arr.retain(|id| id.as_str() != Some(artifact_id));
+            }
+            obj.insert(
+                "updatedAt".to_string(),
+                Value::String(chrono::Utc::now().to_rfc3339()),
+            );
+        }
+        write_project(app_handle, &project).await?;
+    }
+
+    Ok(referencing)
+}
+
+/// Add an artifact ID to a project's artifact list and bump its activity
+/// timestamps. Returns whether the artifact was newly added.
+pub(crate) async fn link_artifact(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+    artifact_id: &str,
+) -> Result<bool, String> {
+    let mut project = read_project(app_handle, project_id).await?;
+    let mut added = false;
+
+    if let Some(obj) = project.as_object_mut() {
+        // Get or create artifactIds array
+        let artifact_ids = obj
+            .entry("artifactIds")
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Some(arr) = artifact_ids.as_array_mut() {
+            // Only add if not already present
+            let artifact_value = Value::String(artifact_id.to_string());
+            if !arr.contains(&artifact_value) {
+                arr.push(artifact_value);
+                added = true;
+            }
+        }
+
+        // Update lastActivityDate
+        touch_project(obj);
+    }
+
+    write_project(app_handle, &project).await?;
+
+    if added {
+        let added_ids = [artifact_id.to_string()];
+        let event_type = project_activity::ARTIFACT_ADDED;
+        record_artifact_events(app_handle, project_id, event_type, &added_ids).await;
+    }
+
+    Ok(added)
+}
+
+// ============================================
+// Local Project Commands
+// ============================================
 
-    Err(format!("Project not found: {}", project_id))
+/// Get all local projects (index entries; use `get_local_project` for the
+/// full project)
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_local_projects(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let projects = read_index(&app_handle).await?;
+    serde_json::to_string(&projects).map_err(|e| format!("Failed to serialize projects: {}", e))
+}
+
+/// Get a specific project by ID
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_local_project(
+    app_handle: tauri::AppHandle,
+    project_id: String,
+) -> Result<String, String> {
+    let project = read_project(&app_handle, &project_id).await?;
+    serde_json::to_string(&project).map_err(|e| format!("Failed to serialize project: {}", e))
 }
 
 /// Save a local project (create or update)
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn save_local_project(
     app_handle: tauri::AppHandle,
     project: String,
 ) -> Result<(), String> {
-    let projects_dir = get_projects_dir(&app_handle)?;
-    let index_path = get_index_path(&app_handle)?;
-
-    // Create directory if it doesn't exist
-    fs::create_dir_all(&projects_dir)
-        .await
-        .map_err(|e| format!("Failed to create projects directory: {}", e))?;
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
 
     // Parse the incoming project
     let new_project: Value =
@@ -87,89 +336,101 @@ pub async fn save_local_project(
     let project_id = new_project
         .get("projectId")
         .and_then(|v| v.as_str())
-        .ok_or("Project must have a projectId")?;
+        .ok_or("Project must have a projectId")?
+        .to_string();
 
-    // Read existing projects
-    let mut projects: Vec<Value> = if index_path.exists() {
-        let content = fs::read_to_string(&index_path)
-            .await
-            .map_err(|e| format!("Failed to read projects: {}", e))?;
-        serde_json::from_str(&content).unwrap_or_else(|_| Vec::new())
-    } else {
-        Vec::new()
-    };
+    write_project(&app_handle, &new_project).await?;
 
-    // Find and update existing project, or add new one
-    let mut found = false;
-    for project in projects.iter_mut() {
-        if project.get("projectId").and_then(|v| v.as_str()) == Some(project_id) {
-            *project = new_project.clone();
-            found = true;
-            break;
-        }
-    }
-    if !found {
-        projects.push(new_project);
-    }
-
-    // Write projects back
-    let content = serde_json::to_string_pretty(&projects)
-        .map_err(|e| format!("Failed to serialize projects: {}", e))?;
-    fs::write(&index_path, content)
-        .await
-        .map_err(|e| format!("Failed to write projects: {}", e))?;
+    audit_log::record(&app_handle, "save_local_project", "project", &[&project_id]).await;
 
     Ok(())
 }
 
-/// Delete a local project
+/// Delete a local project. `artifacts` decides what happens to its
+/// artifacts: "keep" (default) leaves them in the library unassigned,
+/// "cascade" deletes them unless another project also lists them.
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn delete_local_project(
     app_handle: tauri::AppHandle,
     project_id: String,
+    artifacts: Option<String>,
 ) -> Result<(), String> {
-    let index_path = get_index_path(&app_handle)?;
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
 
-    if !index_path.exists() {
-        return Ok(());
-    }
+    let cascade = match artifacts.as_deref().unwrap_or(KEEP_ARTIFACTS) {
+        KEEP_ARTIFACTS => false,
+        CASCADE_ARTIFACTS => true,
+        other => return Err(format!("Invalid artifacts option: {}", other)),
+    };
 
-    let content = fs::read_to_string(&index_path)
-        .await
-        .map_err(|e| format!("Failed to read projects: {}", e))?;
+    // Artifacts the project lists plus any that point back at it
+    let mut artifact_ids: BTreeSet<String> = match read_project(&app_handle, &project_id).await {
+        Ok(project) => string_array(&project, "artifactIds").into_iter().collect(),
+        Err(_) => BTreeSet::new(),
+    };
+    artifact_ids.extend(
+        library_storage::read_index_entries(&app_handle)
+            .await?
+            .iter()
+            .filter(|a| a.get("projectId").and_then(|v| v.as_str()) == Some(&project_id))
+            .filter_map(|a| a.get("artifactId").and_then(|v| v.as_str()).map(String::from)),
+    );
+
+    remove_project(&app_handle, &project_id).await?;
+
+    // Artifacts still listed by another project are never deleted
+    let shared: BTreeSet<String> = read_index(&app_handle)
+        .await?
+        .iter()
+        .flat_map(|p| string_array(p, "artifactIds"))
+        .collect();
 
-    let mut projects: Vec<Value> = serde_json::from_str(&content).unwrap_or_else(|_| Vec::new());
+    let artifacts_dir = library_storage::get_artifacts_dir(&app_handle)?;
+    for artifact_id in &artifact_ids {
+        if cascade && !shared.contains(artifact_id) {
+            library_storage::remove_artifact(&app_handle, artifact_id).await?;
+            collections::remove_artifact_references(&app_handle, artifact_id).await?;
+            continue;
+        }
 
-    // Remove the project
-    projects.retain(|p| p.get("projectId").and_then(|v| v.as_str()) != Some(&project_id));
+        // Unassign artifacts that still point at the deleted project
+        let artifact_path = artifacts_dir.join(format!("{}.json", artifact_id));
+        let Ok(content) = fs::read_to_string(&artifact_path).await else {
+            continue;
+        };
+        let Ok(mut artifact) = serde_json::from_str::<Value>(&content) else {
+            continue;
+        };
+        if artifact.get("projectId").and_then(|v| v.as_str()) != Some(&project_id) {
+            continue;
+        }
+        if let Some(obj) = artifact.as_object_mut() {
+            obj.insert("projectId".to_string(), Value::Null);
+        }
+        let content = serde_json::to_string_pretty(&artifact)
+            .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+        library_storage::write_artifact(&app_handle, &artifact, &content).await?;
+    }
 
-    // Write projects back
-    let content = serde_json::to_string_pretty(&projects)
-        .map_err(|e| format!("Failed to serialize projects: {}", e))?;
-    fs::write(&index_path, content)
-        .await
-        .map_err(|e| format!("Failed to write projects: {}", e))?;
+    let mut ids: Vec<&str> = vec![&project_id];
+    if cascade {
+        ids.extend(artifact_ids.iter().filter(|id| !shared.contains(*id)).map(String::as_str));
+    }
+    audit_log::record(&app_handle, "delete_local_project", "project", &ids).await;
 
     Ok(())
 }
 
 /// Get projects by type (learning_path or quick_create)
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn get_projects_by_type(
     app_handle: tauri::AppHandle,
     project_type: String,
 ) -> Result<String, String> {
-    let index_path = get_index_path(&app_handle)?;
-
-    if !index_path.exists() {
-        return Ok("[]".to_string());
-    }
-
-    let content = fs::read_to_string(&index_path)
-        .await
-        .map_err(|e| format!("Failed to read projects: {}", e))?;
-
-    let projects: Vec<Value> = serde_json::from_str(&content).unwrap_or_else(|_| Vec::new());
+    let projects = read_index(&app_handle).await?;
 
     let filtered: Vec<&Value> = projects
         .iter()
@@ -181,64 +442,117 @@ pub async fn get_projects_by_type(
 
 /// Add artifact ID to project's artifact list
 #[tauri::command]
+#[tracing::instrument(skip_all, err)]
 pub async fn add_artifact_to_project(
     app_handle: tauri::AppHandle,
     project_id: String,
     artifact_id: String,
 ) -> Result<(), String> {
-    let index_path = get_index_path(&app_handle)?;
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
 
-    if !index_path.exists() {
-        return Err(format!("Project not found: {}", project_id));
-    }
+    link_artifact(&app_handle, &project_id, &artifact_id).await?;
 
-    let content = fs::read_to_string(&index_path)
-        .await
-        .map_err(|e| format!("Failed to read projects: {}", e))?;
-
-    let mut projects: Vec<Value> = serde_json::from_str(&content).unwrap_or_else(|_| Vec::new());
-
-    let mut found = false;
-    for project in projects.iter_mut() {
-        if project.get("projectId").and_then(|v| v.as_str()) == Some(&project_id) {
-            // Get or create artifactIds array
-            if let Some(obj) = project.as_object_mut() {
-                let artifact_ids = obj
-                    .entry("artifactIds")
-                    .or_insert_with(|| Value::Array(Vec::new()));
-                if let Some(arr) = artifact_ids.as_array_mut() {
-                    // Only add if not already present
-                    let artifact_value = Value::String(artifact_id.clone());
-                    if !arr.contains(&artifact_value) {
-                        arr.push(artifact_value);
-                    }
-                }
-
-                // Update lastActivityDate
-                obj.insert(
-                    "lastActivityDate".to_string(),
-                    Value::String(chrono::Utc::now().to_rfc3339()),
-                );
-                obj.insert(
-                    "updatedAt".to_string(),
-                    Value::String(chrono::Utc::now().to_rfc3339()),
-                );
-            }
-            found = true;
-            break;
+    audit_log::record(
+        &app_handle,
+        "add_artifact_to_project",
+        "project",
+        &[&project_id, &artifact_id],
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Remove an artifact ID from a project's artifact list. The artifact stays
+/// in the library.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn remove_artifact_from_project(
+    app_handle: tauri::AppHandle,
+    project_id: String,
+    artifact_id: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut project = read_project(&app_handle, &project_id).await?;
+    let mut removed = false;
+
+    if let Some(obj) = project.as_object_mut() {
+        if let Some(arr) = obj.get_mut("artifactIds").and_then(|v| v.as_array_mut()) {
+            let before = arr.len();
+            arr.retain(|id| id.as_str() != Some(artifact_id.as_str()));
+            removed = arr.len() != before;
         }
+        touch_project(obj);
     }
 
-    if !found {
-        return Err(format!("Project not found: {}", project_id));
+    // Project file and index entry are written together
+    write_project(&app_handle, &project).await?;
+
+    if removed {
+        let removed_ids = [artifact_id.clone()];
+        let event_type = project_activity::ARTIFACT_REMOVED;
+        record_artifact_events(&app_handle, &project_id, event_type, &removed_ids).await;
     }
 
-    // Write projects back
-    let content = serde_json::to_string_pretty(&projects)
-        .map_err(|e| format!("Failed to serialize projects: {}", e))?;
-    fs::write(&index_path, content)
-        .await
-        .map_err(|e| format!("Failed to write projects: {}", e))?;
+    audit_log::record(
+        &app_handle,
+        "remove_artifact_from_project",
+        "project",
+        &[&project_id, &artifact_id],
+    )
+    .await;
 
     Ok(())
 }
+
+/// Replace a project's artifact list in one write, for bulk reassignment.
+/// Duplicate IDs are dropped and order is kept. Returns the new list.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn set_project_artifacts(
+    app_handle: tauri::AppHandle,
+    project_id: String,
+    artifact_ids: Vec<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut project = read_project(&app_handle, &project_id).await?;
+    let previous: BTreeSet<String> = string_array(&project, "artifactIds").into_iter().collect();
+
+    let mut seen = BTreeSet::new();
+    let artifact_ids: Vec<String> = artifact_ids
+        .into_iter()
+        .filter(|id| seen.insert(id.clone()))
+        .collect();
+    let added: Vec<String> = artifact_ids
+        .iter()
+        .filter(|id| !previous.contains(*id))
+        .cloned()
+        .collect();
+    let removed: Vec<String> = previous
+        .iter()
+        .filter(|id| !seen.contains(*id))
+        .cloned()
+        .collect();
+
+    if let Some(obj) = project.as_object_mut() {
+        obj.insert("artifactIds".to_string(), serde_json::json!(artifact_ids));
+        touch_project(obj);
+    }
+
+    write_project(&app_handle, &project).await?;
+
+    let event_type = project_activity::ARTIFACT_ADDED;
+    record_artifact_events(&app_handle, &project_id, event_type, &added).await;
+    let event_type = project_activity::ARTIFACT_REMOVED;
+    record_artifact_events(&app_handle, &project_id, event_type, &removed).await;
+
+    audit_log::record(&app_handle, "set_project_artifacts", "project", &[&project_id]).await;
+
+    serde_json::to_string(&artifact_ids)
+        .map_err(|e| format!("Failed to serialize artifact IDs: {}", e))
+}
@@ -0,0 +1,271 @@
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Instant;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, ollama, session_mode, settings_storage, task_manager};
+
+const COMPARISONS_DIR: &str = "prompt-comparisons";
+const COMPARISONS_FILE: &str = "comparisons.json";
+
+// Labels for the two variants, in request order
+const VARIANT_LABELS: [&str; 2] = ["a", "b"];
+
+const PREFERENCES: &[&str] = &["a", "b", "tie"];
+
+// Helper to get the comparisons file path
+fn get_comparisons_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(COMPARISONS_DIR).join(COMPARISONS_FILE))
+}
+
+// Helper to read every stored comparison
+async fn read_comparisons(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let comparisons_path = get_comparisons_path(app_handle)?;
+    if !comparisons_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&comparisons_path)
+        .await
+        .map_err(|e| format!("Failed to read prompt comparisons: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
+// Helper to write every stored comparison
+async fn write_comparisons(
+    app_handle: &tauri::AppHandle,
+    comparisons: &[Value],
+) -> Result<(), String> {
+    let comparisons_path = get_comparisons_path(app_handle)?;
+    if let Some(parent) = comparisons_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create prompt comparisons directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(comparisons)
+        .map_err(|e| format!("Failed to serialize prompt comparisons: {}", e))?;
+    fs::write(&comparisons_path, content)
+        .await
+        .map_err(|e| format!("Failed to write prompt comparisons: {}", e))
+}
+
+// Helper to fill `{{name}}` placeholders in a template from the inputs
+fn fill_template(template: &str, inputs: &Value) -> Result<String, String> {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + end].trim();
+        let value = match inputs.get(name) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => return Err(format!("Missing input: {}", name)),
+            Some(other) => other.to_string(),
+        };
+        filled.push_str(&rest[..start]);
+        filled.push_str(&value);
+        rest = &rest[start + end + 2..];
+    }
+    filled.push_str(rest);
+    Ok(filled)
+}
+
+// Helper to run every (system, prompt) pair on every model, checking for
+// cancellation between runs. A run that fails keeps its `error`; fails only
+// when every run did.
+async fn run_variants(
+    task: &task_manager::TaskHandle,
+    endpoint: &str,
+    models: &[String],
+    prompts: &[(String, String)],
+) -> Result<Vec<Value>, String> {
+    let total = Some((models.len() * prompts.len()) as u64);
+    let mut results = Vec::new();
+    for model in models {
+        for (label, (system, prompt)) in VARIANT_LABELS.iter().zip(prompts) {
+            task.check_cancelled()?;
+            task.progress(results.len() as u64, total, Some(model));
+            let started = Instant::now();
+            let output = task.cancellable(ollama::generate(endpoint, model, system, prompt)).await;
+            task.check_cancelled()?;
+            let duration_ms = started.elapsed().as_millis() as u64;
+            results.push(match output {
+                Ok(output) => serde_json::json!({
+                    "model": model,
+                    "variant": label,
+                    "output": output,
+                    "durationMs": duration_ms,
+                }),
+                Err(error) => serde_json::json!({
+                    "model": model,
+                    "variant": label,
+                    "error": error,
+                    "durationMs": duration_ms,
+                }),
+            });
+        }
+    }
+    if results.iter().all(|r| r.get("error").is_some()) {
+        let error = results[0].get("error").and_then(|v| v.as_str()).unwrap_or("");
+        return Err(format!("Every run failed: {}", error));
+    }
+    Ok(results)
+}
+
+// ============================================
+// Prompt Comparison Commands
+// ============================================
+
+/// Run two prompt variants against the same inputs on the local model and
+/// store the outputs side by side.
+///
+/// The request holds `variants` (exactly two, each with a `prompt` template
+/// and optional `system` and `name`), `inputs` used to fill `{{name}}`
+/// placeholders, optional `models` (defaults to the configured model), and
+/// an optional `operationId`. Every variant runs on every model, as a
+/// cancellable "batch_generation" task; a run that fails keeps its `error`
+/// in place of `output`. Returns the stored comparison.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn compare_prompts(
+    app_handle: tauri::AppHandle,
+    request: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let request: Value =
+        serde_json::from_str(&request).map_err(|e| format!("Invalid request JSON: {}", e))?;
+    let variants = request
+        .get("variants")
+        .and_then(|v| v.as_array())
+        .filter(|v| v.len() == VARIANT_LABELS.len())
+        .ok_or("A comparison needs exactly two variants")?;
+    let inputs = request
+        .get("inputs")
+        .cloned()
+        .unwrap_or_else(|| Value::Object(serde_json::Map::new()));
+
+    let settings = settings_storage::load_settings(&app_handle).await?;
+    let mut models: Vec<String> = request
+        .get("models")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|m| m.as_str()).map(String::from).collect())
+        .unwrap_or_default();
+    if models.is_empty() {
+        models.push(
+            settings
+                .ollama
+                .default_model
+                .clone()
+                .unwrap_or_else(|| ollama::RECOMMENDED_MODEL.to_string()),
+        );
+    }
+
+    // Fill both templates before running anything, so a missing input fails fast
+    let mut prompts = Vec::new();
+    for variant in variants {
+        let template = variant.get("prompt").and_then(|v| v.as_str()).unwrap_or("");
+        if template.trim().is_empty() {
+            return Err("Each variant must have a prompt".to_string());
+        }
+        let system = variant.get("system").and_then(|v| v.as_str()).unwrap_or("");
+        prompts.push((fill_template(system, &inputs)?, fill_template(template, &inputs)?));
+    }
+
+    let operation_id = request.get("operationId").and_then(|v| v.as_str()).map(String::from);
+    let task =
+        task_manager::start_task(&app_handle, operation_id, "batch_generation", "Compare prompts")?;
+    let results = run_variants(&task, &settings.ollama.endpoint, &models, &prompts).await;
+    task.finish(&results);
+    let results = results?;
+
+    let comparison_id = uuid::Uuid::new_v4().to_string();
+    let variants: Vec<Value> = VARIANT_LABELS
+        .iter()
+        .zip(variants)
+        .map(|(label, variant)| {
+            serde_json::json!({
+                "label": label,
+                "name": variant.get("name"),
+                "system": variant.get("system"),
+                "prompt": variant.get("prompt"),
+            })
+        })
+        .collect();
+    let comparison = serde_json::json!({
+        "comparisonId": comparison_id,
+        "variants": variants,
+        "inputs": inputs,
+        "models": models,
+        "results": results,
+        "preferred": Value::Null,
+        "createdAt": chrono::Utc::now().to_rfc3339(),
+    });
+
+    let mut comparisons = read_comparisons(&app_handle).await?;
+    comparisons.push(comparison.clone());
+    write_comparisons(&app_handle, &comparisons).await?;
+
+    audit_log::record(&app_handle, "compare_prompts", "prompt_comparison", &[&comparison_id])
+        .await;
+
+    Ok(comparison.to_string())
+}
+
+/// Record which variant of a comparison the teacher preferred: `"a"`,
+/// `"b"`, or `"tie"`, with optional notes
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn record_prompt_preference(
+    app_handle: tauri::AppHandle,
+    comparison_id: String,
+    preferred: String,
+    notes: Option<String>,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    if !PREFERENCES.contains(&preferred.as_str()) {
+        return Err(format!("Invalid preference: {}", preferred));
+    }
+
+    let mut comparisons = read_comparisons(&app_handle).await?;
+    let comparison = comparisons
+        .iter_mut()
+        .find(|c| c.get("comparisonId").and_then(|v| v.as_str()) == Some(&comparison_id))
+        .ok_or_else(|| format!("Comparison not found: {}", comparison_id))?;
+    if let Some(obj) = comparison.as_object_mut() {
+        obj.insert("preferred".to_string(), Value::String(preferred));
+        obj.insert("notes".to_string(), serde_json::json!(notes));
+        obj.insert(
+            "decidedAt".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        );
+    }
+    write_comparisons(&app_handle, &comparisons).await?;
+
+    audit_log::record(
+        &app_handle,
+        "record_prompt_preference",
+        "prompt_comparison",
+        &[&comparison_id],
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Get every stored prompt comparison, newest first
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_prompt_comparisons(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let mut comparisons = read_comparisons(&app_handle).await?;
+    comparisons.reverse();
+    serde_json::to_string(&comparisons)
+        .map_err(|e| format!("Failed to serialize prompt comparisons: {}", e))
+}
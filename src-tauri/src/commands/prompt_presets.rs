@@ -0,0 +1,248 @@
+use serde_json::Value;
+use std::path::PathBuf;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, reading_level, session_mode};
+
+const PRESETS_DIR: &str = "prompt-presets";
+const OVERRIDES_FILE: &str = "overrides.json";
+
+// Built-in presets compiled into the binary
+const DEFAULT_PRESETS: &[u8] = include_bytes!("../../prompt-presets/default-presets.json");
+
+// Grade bands and the grades (kindergarten as 0) each covers
+const GRADE_BANDS: &[(&str, u32, u32)] = &[
+    ("K-1", 0, 1),
+    ("2-3", 2, 3),
+    ("4-6", 4, 6),
+    ("7-8", 7, 8),
+    ("9-12", 9, 12),
+];
+
+// Subject of a band-wide preset
+const ANY_SUBJECT: &str = "*";
+
+// Helper to get the preset overrides file path
+fn get_overrides_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(PRESETS_DIR).join(OVERRIDES_FILE))
+}
+
+// Helper to read the teacher's preset overrides
+async fn read_overrides(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let overrides_path = get_overrides_path(app_handle)?;
+    if !overrides_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&overrides_path)
+        .await
+        .map_err(|e| format!("Failed to read prompt presets: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
+// Helper to write the teacher's preset overrides
+async fn write_overrides(app_handle: &tauri::AppHandle, overrides: &[Value]) -> Result<(), String> {
+    let overrides_path = get_overrides_path(app_handle)?;
+    if let Some(parent) = overrides_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create prompt presets directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(overrides)
+        .map_err(|e| format!("Failed to serialize prompt presets: {}", e))?;
+    fs::write(&overrides_path, content)
+        .await
+        .map_err(|e| format!("Failed to write prompt presets: {}", e))
+}
+
+// Helper to get a preset's ID
+fn preset_id(preset: &Value) -> Option<&str> {
+    preset.get("presetId").and_then(|v| v.as_str())
+}
+
+// Helper to parse the built-in presets
+fn default_presets() -> Result<Vec<Value>, String> {
+    serde_json::from_slice(DEFAULT_PRESETS).map_err(|e| format!("Invalid built-in presets: {}", e))
+}
+
+// Helper to merge built-in presets with overrides. Each preset is marked
+// `builtIn` (ships with the app) and `overridden` (the teacher changed it).
+async fn merged_presets(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let mut overrides = read_overrides(app_handle).await?;
+    let mut presets = Vec::new();
+    for default in default_presets()? {
+        let position = overrides.iter().position(|o| preset_id(o) == preset_id(&default));
+        let (mut preset, overridden) = match position {
+            Some(i) => (overrides.remove(i), true),
+            None => (default, false),
+        };
+        if let Some(obj) = preset.as_object_mut() {
+            obj.insert("builtIn".to_string(), Value::Bool(true));
+            obj.insert("overridden".to_string(), Value::Bool(overridden));
+        }
+        presets.push(preset);
+    }
+    for mut custom in overrides {
+        if let Some(obj) = custom.as_object_mut() {
+            obj.insert("builtIn".to_string(), Value::Bool(false));
+            obj.insert("overridden".to_string(), Value::Bool(true));
+        }
+        presets.push(custom);
+    }
+    Ok(presets)
+}
+
+/// The grade band ("K-1", "2-3", "4-6", "7-8", "9-12") a grade falls in
+pub(crate) fn grade_band(grade: &str) -> Option<&'static str> {
+    let grade = reading_level::parse_grade(grade).ok()?;
+    GRADE_BANDS
+        .iter()
+        .find(|(_, low, high)| (*low..=*high).contains(&grade))
+        .map(|(band, _, _)| *band)
+}
+
+/// Find the preset for a grade and subject: the subject's own preset in
+/// that grade band, else the band-wide one. Subjects match case-insensitively.
+pub(crate) async fn resolve_preset(
+    app_handle: &tauri::AppHandle,
+    grade: Option<&str>,
+    subject: Option<&str>,
+) -> Result<Option<Value>, String> {
+    let Some(band) = grade.and_then(grade_band) else {
+        return Ok(None);
+    };
+    let presets = merged_presets(app_handle).await?;
+    let in_band = |wanted: &str| {
+        presets.iter().find(|p| {
+            p.get("gradeBand").and_then(|v| v.as_str()) == Some(band)
+                && p.get("subject")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|s| s.eq_ignore_ascii_case(wanted))
+        })
+    };
+    Ok(subject
+        .and_then(in_band)
+        .or_else(|| in_band(ANY_SUBJECT))
+        .cloned())
+}
+
+// ============================================
+// Prompt Preset Commands
+// ============================================
+
+/// List every prompt preset: the built-in ones (with the teacher's changes
+/// applied) followed by any the teacher added
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_prompt_presets(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let presets = merged_presets(&app_handle).await?;
+    serde_json::to_string(&presets).map_err(|e| format!("Failed to serialize presets: {}", e))
+}
+
+/// Get the preset that applies to a grade and subject, or `null`. The
+/// frontend passes its `systemPrompt` and `parameters` along with
+/// generation requests; local model commands apply it directly.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_prompt_preset(
+    app_handle: tauri::AppHandle,
+    grade: String,
+    subject: Option<String>,
+) -> Result<String, String> {
+    let preset = resolve_preset(&app_handle, Some(&grade), subject.as_deref()).await?;
+    Ok(preset.unwrap_or(Value::Null).to_string())
+}
+
+/// Override a preset, or add one for a new band/subject pair. The preset
+/// needs a `gradeBand`, a `subject` (`"*"` for the whole band), and a
+/// `systemPrompt`; `parameters` holds model options such as `temperature`.
+/// Returns the saved preset.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn override_prompt_preset(
+    app_handle: tauri::AppHandle,
+    preset: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let preset: Value =
+        serde_json::from_str(&preset).map_err(|e| format!("Invalid preset JSON: {}", e))?;
+    let band = preset.get("gradeBand").and_then(|v| v.as_str()).unwrap_or("");
+    if !GRADE_BANDS.iter().any(|(b, _, _)| *b == band) {
+        return Err(format!("Invalid grade band: {}", band));
+    }
+    let subject = preset
+        .get("subject")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or("Preset must have a subject")?;
+    let system_prompt = preset
+        .get("systemPrompt")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .ok_or("Preset must have a system prompt")?;
+    let parameters = preset
+        .get("parameters")
+        .cloned()
+        .unwrap_or_else(|| Value::Object(serde_json::Map::new()));
+    if !parameters.is_object() {
+        return Err("Preset parameters must be an object".to_string());
+    }
+
+    // Match built-in IDs regardless of the subject's capitalization
+    let defaults = default_presets()?;
+    let id = defaults
+        .iter()
+        .filter_map(preset_id)
+        .find(|id| id.eq_ignore_ascii_case(&format!("{}:{}", band, subject)))
+        .map_or_else(|| format!("{}:{}", band, subject), String::from);
+    let saved = serde_json::json!({
+        "presetId": id,
+        "gradeBand": band,
+        "subject": subject,
+        "systemPrompt": system_prompt,
+        "parameters": parameters,
+        "updatedAt": chrono::Utc::now().to_rfc3339(),
+    });
+
+    let mut overrides = read_overrides(&app_handle).await?;
+    match overrides.iter().position(|o| preset_id(o) == Some(id.as_str())) {
+        Some(i) => overrides[i] = saved.clone(),
+        None => overrides.push(saved.clone()),
+    }
+    write_overrides(&app_handle, &overrides).await?;
+
+    audit_log::record(&app_handle, "override_prompt_preset", "prompt_preset", &[&id]).await;
+
+    Ok(saved.to_string())
+}
+
+/// Drop the teacher's change to a preset. Built-in presets go back to the
+/// shipped version; added presets are removed.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn reset_prompt_preset(
+    app_handle: tauri::AppHandle,
+    preset_id: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut overrides = read_overrides(&app_handle).await?;
+    let before = overrides.len();
+    overrides.retain(|o| self::preset_id(o) != Some(preset_id.as_str()));
+    if overrides.len() == before {
+        return Ok(());
+    }
+    write_overrides(&app_handle, &overrides).await?;
+
+    audit_log::record(&app_handle, "reset_prompt_preset", "prompt_preset", &[&preset_id]).await;
+
+    Ok(())
+}
@@ -0,0 +1,259 @@
+use serde_json::Value;
+use std::path::PathBuf;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, session_mode};
+
+const QUESTION_BANK_DIR: &str = "question-bank";
+const QUESTIONS_FILE: &str = "questions.json";
+
+// Question types, matching the generator's worksheet item types plus
+// `numeric` for templated number questions
+pub(crate) const QUESTION_TYPES: &[&str] = &[
+    "multiple_choice",
+    "fill_blank",
+    "short_answer",
+    "matching",
+    "true_false",
+    "word_problem",
+    "drawing",
+    "numeric",
+];
+pub(crate) const DIFFICULTIES: &[&str] = &["easy", "medium", "hard"];
+
+// Helper to get the question bank directory
+pub(crate) fn get_question_bank_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(QUESTION_BANK_DIR))
+}
+
+// Helper to get the questions file path
+fn get_questions_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(get_question_bank_dir(app_handle)?.join(QUESTIONS_FILE))
+}
+
+// Helper to read every question in the bank
+pub(crate) async fn read_questions(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let questions_path = get_questions_path(app_handle)?;
+    if !questions_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&questions_path)
+        .await
+        .map_err(|e| format!("Failed to read question bank: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
+// Helper to write the whole question bank
+pub(crate) async fn write_questions(
+    app_handle: &tauri::AppHandle,
+    questions: &[Value],
+) -> Result<(), String> {
+    fs::create_dir_all(get_question_bank_dir(app_handle)?)
+        .await
+        .map_err(|e| format!("Failed to create question bank directory: {}", e))?;
+    let content = serde_json::to_string_pretty(questions)
+        .map_err(|e| format!("Failed to serialize question bank: {}", e))?;
+    fs::write(get_questions_path(app_handle)?, content)
+        .await
+        .map_err(|e| format!("Failed to write question bank: {}", e))
+}
+
+// Helper to check a question has a stem, a known type and difficulty, and
+// choices when its type needs them
+fn validate_question(question: &Value) -> Result<(), String> {
+    let stem = question.get("stem").and_then(|v| v.as_str()).unwrap_or("");
+    if stem.trim().is_empty() {
+        return Err("Question must have a stem".to_string());
+    }
+
+    let question_type = question.get("type").and_then(|v| v.as_str()).unwrap_or("");
+    if !QUESTION_TYPES.contains(&question_type) {
+        return Err(format!("Unsupported question type: {}", question_type));
+    }
+
+    if let Some(difficulty) = question.get("difficulty").filter(|v| !v.is_null()) {
+        let difficulty = difficulty.as_str().unwrap_or("");
+        if !DIFFICULTIES.contains(&difficulty) {
+            return Err(format!("Invalid difficulty: {}", difficulty));
+        }
+    }
+
+    if question_type == "multiple_choice" {
+        let choices = question.get("choices").and_then(|v| v.as_array());
+        if choices.is_none_or(|c| c.len() < 2) {
+            return Err("Multiple choice questions need at least two choices".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+// Helper to check whether a question matches a search query
+fn matches_query(question: &Value, query: &Value) -> bool {
+    let field_matches = |key: &str| match query.get(key).and_then(|v| v.as_str()) {
+        Some(wanted) => question.get(key).and_then(|v| v.as_str()) == Some(wanted),
+        None => true,
+    };
+    if !field_matches("type") || !field_matches("difficulty") {
+        return false;
+    }
+
+    // Objective tag filter
+    if let Some(objective_tag) = query.get("objectiveTag").and_then(|v| v.as_str()) {
+        let has_tag = question
+            .get("objectiveTags")
+            .and_then(|v| v.as_array())
+            .is_some_and(|tags| tags.iter().any(|t| t.as_str() == Some(objective_tag)));
+        if !has_tag {
+            return false;
+        }
+    }
+
+    // Search text filter (stem)
+    if let Some(search_text) = query.get("searchText").and_then(|v| v.as_str()) {
+        let search_lower = search_text.to_lowercase();
+        let stem = question.get("stem").and_then(|v| v.as_str()).unwrap_or("");
+        if !stem.to_lowercase().contains(&search_lower) {
+            return false;
+        }
+    }
+
+    true
+}
+
+// ============================================
+// Question Bank Commands
+// ============================================
+
+/// Get all questions in the bank. Teacher mode only, since questions
+/// carry their answers.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_questions(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let questions = read_questions(&app_handle).await?;
+    serde_json::to_string(&questions).map_err(|e| format!("Failed to serialize questions: {}", e))
+}
+
+/// Get a specific question by ID
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_question(
+    app_handle: tauri::AppHandle,
+    question_id: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let question = read_questions(&app_handle)
+        .await?
+        .into_iter()
+        .find(|q| q.get("questionId").and_then(|v| v.as_str()) == Some(&question_id))
+        .ok_or_else(|| format!("Question not found: {}", question_id))?;
+    serde_json::to_string(&question).map_err(|e| format!("Failed to serialize question: {}", e))
+}
+
+/// Save a question (create or update). Questions hold `stem`, `type`,
+/// `choices`, `answer`, `objectiveTags`, and `difficulty`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn save_question(
+    app_handle: tauri::AppHandle,
+    question: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut new_question: Value =
+        serde_json::from_str(&question).map_err(|e| format!("Invalid question JSON: {}", e))?;
+    validate_question(&new_question)?;
+
+    let question_id = new_question
+        .get("questionId")
+        .and_then(|v| v.as_str())
+        .ok_or("Question must have a questionId")?
+        .to_string();
+
+    let mut questions = read_questions(&app_handle).await?;
+    let existing = questions
+        .iter()
+        .position(|q| q.get("questionId").and_then(|v| v.as_str()) == Some(&question_id));
+
+    let now = chrono::Utc::now().to_rfc3339();
+    if let Some(obj) = new_question.as_object_mut() {
+        let created_at = existing
+            .and_then(|i| questions[i].get("createdAt").cloned())
+            .unwrap_or_else(|| Value::String(now.clone()));
+        obj.entry("createdAt").or_insert(created_at);
+        obj.insert("updatedAt".to_string(), Value::String(now));
+    }
+
+    match existing {
+        Some(i) => questions[i] = new_question,
+        None => questions.push(new_question),
+    }
+    write_questions(&app_handle, &questions).await?;
+
+    audit_log::record(&app_handle, "save_question", "question", &[&question_id]).await;
+
+    Ok(())
+}
+
+/// Delete a question from the bank
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_question(
+    app_handle: tauri::AppHandle,
+    question_id: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut questions = read_questions(&app_handle).await?;
+    let before = questions.len();
+    questions.retain(|q| q.get("questionId").and_then(|v| v.as_str()) != Some(&question_id));
+    if questions.len() == before {
+        return Ok(());
+    }
+    write_questions(&app_handle, &questions).await?;
+
+    audit_log::record(&app_handle, "delete_question", "question", &[&question_id]).await;
+
+    Ok(())
+}
+
+/// Search the question bank. The query may filter by `objectiveTag`,
+/// `type`, `difficulty`, and `searchText` (matched against the stem), and
+/// cap results with `limit`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn search_questions(
+    app_handle: tauri::AppHandle,
+    query: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let query_value: Value =
+        serde_json::from_str(&query).map_err(|e| format!("Invalid query JSON: {}", e))?;
+    let limit = query_value
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(usize::MAX, |l| l as usize);
+
+    let questions = read_questions(&app_handle).await?;
+    let filtered: Vec<&Value> = questions
+        .iter()
+        .filter(|q| matches_query(q, &query_value))
+        .take(limit)
+        .collect();
+
+    serde_json::to_string(&filtered).map_err(|e| format!("Failed to serialize results: {}", e))
+}
@@ -0,0 +1,338 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::commands::seeded_random::{self, SplitMix64};
+use crate::commands::{app_lock, audit_log, question_bank, session_mode};
+
+// Question types that can carry a variant template
+const VARIANT_TYPES: &[&str] = &["numeric", "fill_blank", "short_answer", "word_problem"];
+
+// Re-rolls per variant before giving up on a template's constraints
+const MAX_ATTEMPTS: usize = 1000;
+
+// Most variants generated per call
+const MAX_VARIANTS: usize = 100;
+
+/// A template parameter: a numeric range or a list of values
+enum Param {
+    Range { min: f64, max: f64, step: f64 },
+    Values(Vec<String>),
+}
+
+// Helper to parse a template's `params`
+fn parse_params(template: &Value) -> Result<BTreeMap<String, Param>, String> {
+    let params = template
+        .get("params")
+        .and_then(|v| v.as_object())
+        .ok_or("Variant template must have params")?;
+
+    let mut parsed = BTreeMap::new();
+    for (name, spec) in params {
+        let param = if let Some(values) = spec.get("values").and_then(|v| v.as_array()) {
+            let values: Vec<String> = values
+                .iter()
+                .map(|v| v.as_str().map(String::from).unwrap_or_else(|| v.to_string()))
+                .collect();
+            if values.is_empty() {
+                return Err(format!("Template parameter {} has no values", name));
+            }
+            Param::Values(values)
+        } else {
+            let number = |key: &str| spec.get(key).and_then(|v| v.as_f64());
+            let (Some(min), Some(max)) = (number("min"), number("max")) else {
+                return Err(format!("Template parameter {} needs min and max", name));
+            };
+            let step = number("step").unwrap_or(1.0);
+            if max < min || step <= 0.0 {
+                return Err(format!("Invalid range for template parameter {}", name));
+            }
+            Param::Range { min, max, step }
+        };
+        parsed.insert(name.clone(), param);
+    }
+    Ok(parsed)
+}
+
+// Helper to format a number without a trailing `.0`
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        let formatted = format!("{:.4}", value);
+        formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+// Helper to draw a value for each parameter
+fn roll(params: &BTreeMap<String, Param>, rng: &mut SplitMix64) -> BTreeMap<String, String> {
+    params
+        .iter()
+        .map(|(name, param)| {
+            let value = match param {
+                Param::Range { min, max, step } => {
+                    let steps = ((max - min) / step).floor() as u64 + 1;
+                    format_number(min + rng.below(steps) as f64 * step)
+                }
+                Param::Values(values) => values[rng.below(values.len() as u64) as usize].clone(),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+// Helper to replace `{name}` placeholders with rolled values
+fn substitute(text: &str, values: &BTreeMap<String, String>) -> String {
+    values.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// Evaluator for the arithmetic in template answers and constraints:
+/// numbers, `+ - * / %`, parentheses, and one optional comparison
+/// (`< <= > >= == !=`, yielding 1 or 0)
+struct Expr<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Expr<'a> {
+    fn evaluate(source: &'a str) -> Option<f64> {
+        let mut expr = Expr {
+            chars: source.chars().peekable(),
+        };
+        let value = expr.comparison()?;
+        expr.skip_spaces();
+        expr.chars.peek().is_none().then_some(value)
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn comparison(&mut self) -> Option<f64> {
+        let left = self.sum()?;
+        self.skip_spaces();
+        let mut op = String::new();
+        while let Some(c) = self.chars.next_if(|c| matches!(c, '<' | '>' | '=' | '!')) {
+            op.push(c);
+        }
+        if op.is_empty() {
+            return Some(left);
+        }
+        let right = self.sum()?;
+        let result = match op.as_str() {
+            "<" => left < right,
+            "<=" => left <= right,
+            ">" => left > right,
+            ">=" => left >= right,
+            "==" => (left - right).abs() < 1e-9,
+            "!=" => (left - right).abs() >= 1e-9,
+            _ => return None,
+        };
+        Some(if result { 1.0 } else { 0.0 })
+    }
+
+    fn sum(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        loop {
+            self.skip_spaces();
+            match self.chars.next_if(|c| matches!(c, '+' | '-')) {
+                Some('+') => value += self.term()?,
+                Some(_) => value -= self.term()?,
+                None => return Some(value),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.factor()?;
+        loop {
+            self.skip_spaces();
+            match self.chars.next_if(|c| matches!(c, '*' | '/' | '%')) {
+                Some('*') => value *= self.factor()?,
+                Some('/') => {
+                    let divisor = self.factor()?;
+                    if divisor == 0.0 {
+                        return None;
+                    }
+                    value /= divisor;
+                }
+                Some(_) => {
+                    let divisor = self.factor()?;
+                    if divisor == 0.0 {
+                        return None;
+                    }
+                    value %= divisor;
+                }
+                None => return Some(value),
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Option<f64> {
+        self.skip_spaces();
+        if self.chars.next_if_eq(&'-').is_some() {
+            return Some(-self.factor()?);
+        }
+        if self.chars.next_if_eq(&'(').is_some() {
+            let value = self.comparison()?;
+            self.skip_spaces();
+            self.chars.next_if_eq(&')')?;
+            return Some(value);
+        }
+        let mut number = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+            number.push(c);
+        }
+        number.parse().ok()
+    }
+}
+
+// Helper to build one variant of a templated question
+fn build_variant(
+    question: &Value,
+    template: &Value,
+    params: &BTreeMap<String, Param>,
+    seed: u64,
+    index: usize,
+) -> Result<Value, String> {
+    let question_id = question
+        .get("questionId")
+        .and_then(|v| v.as_str())
+        .ok_or("Question must have a questionId")?;
+    let constraints: Vec<&str> = template
+        .get("constraints")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c.as_str())
+        .collect();
+
+    // Each variant gets its own stream so regenerating one index is stable
+    let mut rng = SplitMix64(seed ^ (index as u64).wrapping_mul(0xA24B_AED4_963E_E407));
+    let values = (0..MAX_ATTEMPTS)
+        .map(|_| roll(params, &mut rng))
+        .find(|values| {
+            constraints
+                .iter()
+                .all(|c| Expr::evaluate(&substitute(c, values)).is_some_and(|v| v != 0.0))
+        })
+        .ok_or("Could not satisfy the template's constraints")?;
+
+    // Answers are evaluated as arithmetic when possible, else filled in as text
+    let answer_source = template
+        .get("answer")
+        .or_else(|| question.get("answer"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let answer_text = substitute(answer_source, &values);
+    let answer = match Expr::evaluate(&answer_text) {
+        Some(number) => format_number(number),
+        None => answer_text,
+    };
+
+    let mut variant = question.clone();
+    let obj = variant.as_object_mut().ok_or("Invalid question")?;
+    let stem = obj.get("stem").and_then(|v| v.as_str()).unwrap_or("");
+    obj.insert("stem".to_string(), Value::String(substitute(stem, &values)));
+    if let Some(choices) = obj.get_mut("choices").and_then(|v| v.as_array_mut()) {
+        for choice in choices.iter_mut() {
+            if let Some(text) = choice.as_str() {
+                *choice = Value::String(substitute(text, &values));
+            }
+        }
+    }
+    obj.insert("answer".to_string(), Value::String(answer));
+    obj.insert(
+        "questionId".to_string(),
+        Value::String(format!("{}-v{}-{}", question_id, seed, index)),
+    );
+    obj.insert("variantOf".to_string(), Value::String(question_id.to_string()));
+    obj.insert("variantSeed".to_string(), Value::String(seed.to_string()));
+    obj.insert("variantIndex".to_string(), Value::from(index));
+    obj.insert("variantValues".to_string(), serde_json::json!(values));
+    for key in ["template", "createdAt", "updatedAt", "lastUsedAt", "useCount"] {
+        obj.remove(key);
+    }
+    Ok(variant)
+}
+
+// ============================================
+// Question Variant Commands
+// ============================================
+
+/// Generate parallel forms of a templated question by re-rolling its
+/// parameters.
+///
+/// The question's `template` holds `params` (each `{ min, max, step }` or
+/// `{ values }`), optional `constraints` (expressions like `"{a} > {b}"`),
+/// and an optional `answer` expression (`"{a} - {b}"`); the stem and
+/// choices use `{name}` placeholders. The same `seed` always yields the
+/// same variants; one is picked and returned when omitted. Variant IDs are
+/// derived from the seed, so saving (`save`, default true) a regenerated set
+/// updates the earlier copies. Returns `{ seed, variants }`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn generate_variants(
+    app_handle: tauri::AppHandle,
+    question_id: String,
+    count: usize,
+    seed: Option<String>,
+    save: Option<bool>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    if count == 0 || count > MAX_VARIANTS {
+        return Err(format!("Variant count must be between 1 and {}", MAX_VARIANTS));
+    }
+    let seed = seeded_random::parse_seed(seed.as_deref())?;
+
+    let mut questions = question_bank::read_questions(&app_handle).await?;
+    let question = questions
+        .iter()
+        .find(|q| q.get("questionId").and_then(|v| v.as_str()) == Some(&question_id))
+        .ok_or_else(|| format!("Question not found: {}", question_id))?;
+    let question_type = question.get("type").and_then(|v| v.as_str()).unwrap_or("");
+    if !VARIANT_TYPES.contains(&question_type) {
+        return Err(format!("Variants aren't supported for {} questions", question_type));
+    }
+    let template = question
+        .get("template")
+        .ok_or("Question has no variant template")?;
+    let params = parse_params(template)?;
+
+    let variants: Vec<Value> = (0..count)
+        .map(|index| build_variant(question, template, &params, seed, index))
+        .collect::<Result<_, _>>()?;
+
+    if save.unwrap_or(true) {
+        let now = chrono::Utc::now().to_rfc3339();
+        for variant in &variants {
+            let variant_id = variant.get("questionId").and_then(|v| v.as_str());
+            let existing = questions
+                .iter()
+                .position(|q| q.get("questionId").and_then(|v| v.as_str()) == variant_id);
+            let mut variant = variant.clone();
+            if let Some(obj) = variant.as_object_mut() {
+                let created_at = existing
+                    .and_then(|i| questions[i].get("createdAt").cloned())
+                    .unwrap_or_else(|| Value::String(now.clone()));
+                obj.insert("createdAt".to_string(), created_at);
+                obj.insert("updatedAt".to_string(), Value::String(now.clone()));
+            }
+            match existing {
+                Some(i) => questions[i] = variant,
+                None => questions.push(variant),
+            }
+        }
+        question_bank::write_questions(&app_handle, &questions).await?;
+
+        audit_log::record(&app_handle, "generate_variants", "question", &[&question_id]).await;
+    }
+
+    let response = serde_json::json!({
+        "seed": seed.to_string(),
+        "variants": variants,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,467 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::commands::{
+    app_lock, audit_log, learner_storage, library_storage, question_bank, session_mode,
+};
+
+// Extra time allowed for IPC latency before a submission counts as late
+const SUBMISSION_GRACE: Duration = Duration::from_secs(5);
+
+// Finished sessions are dropped from memory after this long
+const SESSION_RETENTION: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// A quick check question including its answer key
+#[derive(Clone)]
+struct QuickCheckQuestion {
+    question_id: String,
+    question_text: String,
+    options: Vec<String>,
+    correct_index: usize,
+}
+
+/// A question as shown to the learner (answer key withheld)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicQuestion<'a> {
+    question_id: &'a str,
+    question_text: &'a str,
+    options: &'a [String],
+}
+
+struct QuickCheckSession {
+    learner_id: String,
+    objective_id: String,
+    questions: Vec<QuickCheckQuestion>,
+    started_at: chrono::DateTime<chrono::Utc>,
+    started: Instant,
+    time_limit: Option<Duration>,
+    submitted: bool,
+}
+
+/// A timed assessment run by the frontend (such as a fluency drill) whose
+/// clock is kept here so its timing can be trusted
+struct TimedSession {
+    learner_id: String,
+    started_at: chrono::DateTime<chrono::Utc>,
+    started: Instant,
+    time_limit: Duration,
+    // Questions answered after the deadline, in answer order
+    late_answers: Vec<String>,
+}
+
+/// Managed state holding in-progress quick check sessions and their answer
+/// keys, and the clocks of timed sessions
+#[derive(Default)]
+pub struct QuickCheckSessionState {
+    sessions: Mutex<HashMap<String, QuickCheckSession>>,
+    timed: Mutex<HashMap<String, TimedSession>>,
+}
+
+// Helper to get the index of a multiple choice question's answer from its
+// letter (`A` for the first choice)
+fn answer_index(question: &serde_json::Value) -> Option<usize> {
+    let letter = question.get("answer")?.as_str()?.trim().to_ascii_uppercase();
+    match letter.as_bytes() {
+        [b] if b.is_ascii_uppercase() => Some((b - b'A') as usize),
+        _ => None,
+    }
+}
+
+// Helper to load the questions of an assembled worksheet from the question
+// bank, answer key included. Only multiple choice questions with an answer
+// can be graded, so the others are left out.
+async fn load_questions(
+    app_handle: &tauri::AppHandle,
+    artifact_id: &str,
+) -> Result<Vec<QuickCheckQuestion>, String> {
+    let artifact = library_storage::read_artifact(app_handle, artifact_id).await?;
+    let question_ids: Vec<&str> = artifact
+        .get("questionIds")
+        .and_then(|v| v.as_array())
+        .map(|ids| ids.iter().filter_map(|id| id.as_str()).collect())
+        .unwrap_or_default();
+    let bank = question_bank::read_questions(app_handle).await?;
+
+    let mut questions = Vec::new();
+    for question_id in question_ids {
+        let Some(question) = bank
+            .iter()
+            .find(|q| q.get("questionId").and_then(|v| v.as_str()) == Some(question_id))
+        else {
+            continue;
+        };
+        if question.get("type").and_then(|v| v.as_str()) != Some("multiple_choice") {
+            continue;
+        }
+        let options: Vec<String> = question
+            .get("choices")
+            .and_then(|v| v.as_array())
+            .map(|c| c.iter().filter_map(|c| c.as_str()).map(String::from).collect())
+            .unwrap_or_default();
+        let Some(correct_index) = answer_index(question).filter(|&i| i < options.len()) else {
+            continue;
+        };
+        questions.push(QuickCheckQuestion {
+            question_id: question_id.to_string(),
+            question_text: question
+                .get("stem")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            options,
+            correct_index,
+        });
+    }
+    Ok(questions)
+}
+
+// Helper to check whether a submission `elapsed` into a session is past its
+// time limit, allowing for IPC latency
+fn is_late(elapsed: Duration, limit: Duration) -> bool {
+    elapsed > limit + SUBMISSION_GRACE
+}
+
+/// End a timed session for `learner_id` and record its timing in their
+/// quick check result, replacing any the frontend sent: `startedAt`,
+/// `deadline`, `timeLimitSeconds`, `elapsedSeconds`, and `late` are set, and
+/// each item is marked `late` if it was answered after the deadline
+pub(crate) fn apply_timing(
+    app_handle: &tauri::AppHandle,
+    token: &str,
+    learner_id: &str,
+    result: &mut serde_json::Value,
+) -> Result<(), String> {
+    let obj = result.as_object_mut().ok_or("Result must be a JSON object")?;
+    let state = app_handle.state::<QuickCheckSessionState>();
+    let mut timed = state.timed.lock().unwrap();
+    let owner = timed
+        .get(token)
+        .map(|session| session.learner_id.as_str())
+        .ok_or_else(|| format!("Timed session not found: {}", token))?;
+    if owner != learner_id {
+        return Err("This timed session belongs to another learner".to_string());
+    }
+    let session = timed.remove(token).ok_or("Timed session not found")?;
+
+    let elapsed = session.started.elapsed();
+    let deadline = session.started_at + session.time_limit;
+    let timing = serde_json::json!({
+        "startedAt": session.started_at.to_rfc3339(),
+        "deadline": deadline.to_rfc3339(),
+        "timeLimitSeconds": session.time_limit.as_secs(),
+        "elapsedSeconds": elapsed.as_secs(),
+        "late": is_late(elapsed, session.time_limit),
+    });
+    if let Some(timing) = timing.as_object() {
+        obj.extend(timing.clone());
+    }
+    if let Some(items) = obj.get_mut("items").and_then(|v| v.as_array_mut()) {
+        for item in items.iter_mut().filter_map(|item| item.as_object_mut()) {
+            let question_id = item.get("questionId").and_then(|v| v.as_str());
+            let late = question_id.is_some_and(|id| session.late_answers.iter().any(|l| l == id));
+            item.insert("late".to_string(), serde_json::Value::Bool(late));
+        }
+    }
+    Ok(())
+}
+
+/// Check that an assembled worksheet has questions a quick check can grade,
+/// before a session is opened on it
+pub(crate) async fn check_questions(
+    app_handle: &tauri::AppHandle,
+    artifact_id: &str,
+) -> Result<(), String> {
+    if load_questions(app_handle, artifact_id).await?.is_empty() {
+        return Err("A quick check needs at least one multiple choice question".to_string());
+    }
+    Ok(())
+}
+
+/// Open quick check session `session_id` on the multiple choice questions
+/// of an assembled worksheet (`artifact_id`), loaded with their answers from
+/// the question bank. Its clock starts now. The answer key stays in the
+/// backend; the returned JSON holds the session ID and the questions without
+/// answers.
+pub(crate) async fn open_session(
+    app_handle: &tauri::AppHandle,
+    session_id: String,
+    learner_id: String,
+    objective_id: String,
+    artifact_id: &str,
+    time_limit_seconds: Option<u64>,
+) -> Result<serde_json::Value, String> {
+    let questions = load_questions(app_handle, artifact_id).await?;
+    if questions.is_empty() {
+        return Err("A quick check needs at least one multiple choice question".to_string());
+    }
+
+    let public_questions: Vec<PublicQuestion> = questions
+        .iter()
+        .map(|q| PublicQuestion {
+            question_id: &q.question_id,
+            question_text: &q.question_text,
+            options: &q.options,
+        })
+        .collect();
+    let started_at = chrono::Utc::now();
+    let response = serde_json::json!({
+        "sessionId": session_id,
+        "learnerId": learner_id,
+        "objectiveId": objective_id,
+        "questions": public_questions,
+        "startedAt": started_at.to_rfc3339(),
+        "timeLimitSeconds": time_limit_seconds,
+    });
+
+    let session = QuickCheckSession {
+        learner_id,
+        objective_id,
+        questions,
+        started_at,
+        started: Instant::now(),
+        time_limit: time_limit_seconds.map(Duration::from_secs),
+        submitted: false,
+    };
+
+    let state = app_handle.state::<QuickCheckSessionState>();
+    let mut sessions = state.sessions.lock().unwrap();
+    sessions.retain(|_, s| s.started.elapsed() < SESSION_RETENTION);
+    sessions.insert(session_id, session);
+
+    Ok(response)
+}
+
+/// The learner a quick check session is for
+pub(crate) fn session_learner(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+) -> Result<String, String> {
+    let state = app_handle.state::<QuickCheckSessionState>();
+    let sessions = state.sessions.lock().unwrap();
+    sessions
+        .get(session_id)
+        .map(|session| session.learner_id.clone())
+        .ok_or_else(|| format!("Quick check session not found: {}", session_id))
+}
+
+/// Grade answers for a quick check session and save the result to the
+/// learner's quick check history. Late or duplicate submissions are
+/// rejected. Returns `{ result, answerKey }`.
+pub(crate) async fn submit_session(
+    app_handle: &tauri::AppHandle,
+    session_id: &str,
+    answers: &HashMap<String, usize>,
+) -> Result<serde_json::Value, String> {
+    let (result, answer_key, learner_id) = {
+        let state = app_handle.state::<QuickCheckSessionState>();
+        let mut sessions = state.sessions.lock().unwrap();
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| format!("Quick check session not found: {}", session_id))?;
+
+        if session.submitted {
+            return Err("This quick check has already been submitted".to_string());
+        }
+
+        let elapsed = session.started.elapsed();
+        if let Some(limit) = session.time_limit {
+            if is_late(elapsed, limit) {
+                session.submitted = true;
+                return Err("Time is up for this quick check".to_string());
+            }
+        }
+        session.submitted = true;
+
+        let items: Vec<serde_json::Value> = session
+            .questions
+            .iter()
+            .map(|q| {
+                let selected = answers.get(&q.question_id).copied();
+                serde_json::json!({
+                    "questionId": q.question_id,
+                    "selectedIndex": selected,
+                    "correct": selected == Some(q.correct_index),
+                })
+            })
+            .collect();
+        let total_questions = session.questions.len();
+        let correct_answers = items
+            .iter()
+            .filter(|i| i.get("correct").and_then(|v| v.as_bool()) == Some(true))
+            .count();
+        let score = (correct_answers as f64 / total_questions as f64 * 100.0).round() as u64;
+
+        let result = serde_json::json!({
+            "resultId": uuid::Uuid::new_v4().to_string(),
+            "sessionId": session_id,
+            "learnerId": session.learner_id,
+            "objectiveId": session.objective_id,
+            "score": score,
+            "totalQuestions": total_questions,
+            "correctAnswers": correct_answers,
+            "items": items,
+            "startedAt": session.started_at.to_rfc3339(),
+            "elapsedSeconds": elapsed.as_secs(),
+            "createdAt": chrono::Utc::now().to_rfc3339(),
+        });
+        let answer_key: HashMap<&str, usize> = session
+            .questions
+            .iter()
+            .map(|q| (q.question_id.as_str(), q.correct_index))
+            .collect();
+        let answer_key = serde_json::to_value(answer_key)
+            .map_err(|e| format!("Failed to serialize answer key: {}", e))?;
+
+        (result, answer_key, session.learner_id.clone())
+    };
+
+    learner_storage::append_quick_check_result(app_handle, &learner_id, result.clone()).await?;
+    audit_log::record(
+        app_handle,
+        "submit_quick_check_session",
+        "quick_check",
+        &[&learner_id, session_id],
+    )
+    .await;
+
+    let response = serde_json::json!({
+        "result": result,
+        "answerKey": answer_key,
+    });
+    Ok(response)
+}
+
+// ============================================
+// Quick Check Session Commands
+// ============================================
+
+/// Start a quick check session on an assembled worksheet's multiple choice
+/// questions. The questions and answer key are loaded here, and the key
+/// stays in the backend; the returned JSON contains the session ID and the
+/// questions without answers. In a learner session `time_limit_seconds` is
+/// ignored and the limit the teacher set when starting it applies.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn start_quick_check_session(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    objective_id: String,
+    artifact_id: String,
+    time_limit_seconds: Option<u64>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_learner_allowed(&app_handle, &learner_id)?;
+
+    let time_limit_seconds = match session_mode::active_learner_id(&app_handle) {
+        Some(_) => session_mode::quick_check_time_limit(&app_handle),
+        None => time_limit_seconds,
+    };
+    let response = open_session(
+        &app_handle,
+        uuid::Uuid::new_v4().to_string(),
+        learner_id,
+        objective_id,
+        &artifact_id,
+        time_limit_seconds,
+    )
+    .await?;
+    Ok(response.to_string())
+}
+
+/// Submit answers for a quick check session. Late or duplicate submissions
+/// are rejected. On success the result is graded, saved to the learner's
+/// quick check history, and returned together with the answer key.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn submit_quick_check_session(
+    app_handle: tauri::AppHandle,
+    session_id: String,
+    answers: HashMap<String, usize>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    let learner_id = session_learner(&app_handle, &session_id)?;
+    session_mode::ensure_learner_allowed(&app_handle, &learner_id)?;
+
+    let response = submit_session(&app_handle, &session_id, &answers).await?;
+    Ok(response.to_string())
+}
+
+/// Start the clock for a timed assessment the frontend runs itself, such as
+/// a fluency drill. Answers reported with `record_timed_answer` after the
+/// deadline are flagged, and passing the token to `save_quick_check_result`
+/// records the elapsed time and late flags in the result (in learner mode,
+/// results can only be saved this way). Returns `{ token,
+/// startedAt, deadline, timeLimitSeconds }`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn start_timed_session(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    time_limit_seconds: u64,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_learner_allowed(&app_handle, &learner_id)?;
+
+    if time_limit_seconds == 0 {
+        return Err("A timed session needs a time limit".to_string());
+    }
+    let token = uuid::Uuid::new_v4().to_string();
+    let time_limit = Duration::from_secs(time_limit_seconds);
+    let started_at = chrono::Utc::now();
+    let response = serde_json::json!({
+        "token": token,
+        "startedAt": started_at.to_rfc3339(),
+        "deadline": (started_at + time_limit).to_rfc3339(),
+        "timeLimitSeconds": time_limit_seconds,
+    });
+
+    let session = TimedSession {
+        learner_id,
+        started_at,
+        started: Instant::now(),
+        time_limit,
+        late_answers: Vec::new(),
+    };
+    let state = app_handle.state::<QuickCheckSessionState>();
+    let mut timed = state.timed.lock().unwrap();
+    timed.retain(|_, s| s.started.elapsed() < SESSION_RETENTION);
+    timed.insert(token, session);
+
+    Ok(response.to_string())
+}
+
+/// Report that a question in a timed session was answered, as the learner
+/// answers it. Returns `{ elapsedSeconds, late }`; late answers are listed
+/// in the saved result.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn record_timed_answer(
+    app_handle: tauri::AppHandle,
+    token: String,
+    question_id: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let state = app_handle.state::<QuickCheckSessionState>();
+    let mut timed = state.timed.lock().unwrap();
+    let session = timed
+        .get_mut(&token)
+        .ok_or_else(|| format!("Timed session not found: {}", token))?;
+    session_mode::ensure_learner_allowed(&app_handle, &session.learner_id)?;
+
+    let elapsed = session.started.elapsed();
+    let late = is_late(elapsed, session.time_limit);
+    if late && !session.late_answers.contains(&question_id) {
+        session.late_answers.push(question_id);
+    }
+
+    let response = serde_json::json!({
+        "elapsedSeconds": elapsed.as_secs(),
+        "late": late,
+    });
+    Ok(response.to_string())
+}
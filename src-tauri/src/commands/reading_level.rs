@@ -0,0 +1,307 @@
+use serde_json::Value;
+use std::ops::Range;
+
+use crate::commands::{
+    app_lock, artifact_analysis, artifact_clone, audit_log, library_storage, ollama,
+    project_storage, prompt_presets, session_mode, settings_storage, task_manager, vocabulary,
+    worksheet_assembly,
+};
+
+// Text runs shorter than this (labels, single answers) are left alone
+const MIN_BLOCK_WORDS: usize = 6;
+
+// How far above the target grade a rewrite may score and still pass
+const GRADE_TOLERANCE: f64 = 1.0;
+
+// Rewrites tried per block before settling for the easiest one
+const MAX_ATTEMPTS: usize = 2;
+
+const SYSTEM_PROMPT: &str = "You rewrite classroom text so younger students can read it. \
+    Keep every fact, number, and name. Keep blanks like ____ exactly as they are. \
+    Reply with only the rewritten text: no title, quotes, or commentary.";
+
+/// Parse a grade ("K" or 1-12) as a number, kindergarten being 0
+pub(crate) fn parse_grade(grade: &str) -> Result<u32, String> {
+    let grade = grade.trim();
+    if grade.eq_ignore_ascii_case("k") {
+        return Ok(0);
+    }
+    grade
+        .parse::<u32>()
+        .ok()
+        .filter(|g| (1..=12).contains(g))
+        .ok_or_else(|| format!("Invalid target grade: {}", grade))
+}
+
+/// Score text with the Flesch-Kincaid grade level formula
+pub(crate) fn readability_grade(text: &str) -> f64 {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic() && c != '\'')
+        .filter(|w| w.chars().any(char::is_alphabetic))
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        return 0.0;
+    }
+    let sentences = text
+        .split(['.', '!', '?'])
+        .filter(|s| s.chars().any(char::is_alphabetic))
+        .count()
+        .max(1);
+    let syllables: usize = words.iter().map(|w| vocabulary::syllable_count(w)).sum();
+
+    let words_per_sentence = words.len() as f64 / sentences as f64;
+    let syllables_per_word = syllables as f64 / words.len() as f64;
+    0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59
+}
+
+// Helper to describe the constraints for a reading level
+fn level_constraints(grade: u32) -> String {
+    let (label, max_sentence_words) = match grade {
+        0 => ("kindergarten".to_string(), 6),
+        1 => ("grade 1".to_string(), 8),
+        2..=3 => (format!("grade {}", grade), 12),
+        4..=5 => (format!("grade {}", grade), 15),
+        _ => (format!("grade {}", grade), 20),
+    };
+    format!(
+        "Rewrite it for a {label} reader. Use short, common words and sentences of at \
+         most {max_sentence_words} words. Split long sentences instead of dropping ideas."
+    )
+}
+
+// Helper to clean up a model reply: one line of plain text, no wrapping quotes
+fn clean_reply(reply: &str) -> String {
+    let text = reply.split_whitespace().collect::<Vec<_>>().join(" ");
+    text.trim_matches(|c| c == '"' || c == '\u{201c}' || c == '\u{201d}')
+        .trim()
+        .to_string()
+}
+
+/// The model, prompts, and target grade a rewrite runs with
+struct Rewriter<'a> {
+    task: &'a task_manager::TaskHandle,
+    endpoint: String,
+    model: String,
+    system: String,
+    options: Value,
+    target: u32,
+}
+
+impl Rewriter<'_> {
+    fn above_target(&self, score: f64) -> bool {
+        score > self.target as f64 + GRADE_TOLERANCE
+    }
+
+    // Rewrite one block of text. Returns the easiest version produced and
+    // its score, or `None` if no attempt beat the original.
+    async fn rewrite_block(&self, text: &str) -> Result<Option<(String, f64)>, String> {
+        let mut best: Option<(String, f64)> = None;
+        let mut score = readability_grade(text);
+        for attempt in 0..MAX_ATTEMPTS {
+            let mut prompt = level_constraints(self.target);
+            if attempt > 0 {
+                prompt.push_str(&format!(
+                    " Your last version still read at about grade {:.0}; make it simpler.",
+                    score
+                ));
+            }
+            prompt.push_str("\n\nText:\n");
+            prompt.push_str(text);
+
+            let request = ollama::generate_with_options(
+                &self.endpoint,
+                &self.model,
+                &self.system,
+                &prompt,
+                &self.options,
+            );
+            let rewritten = clean_reply(&self.task.cancellable(request).await?);
+            // A rewrite that loses a fill-in blank would break the worksheet
+            if rewritten.is_empty() || (text.contains("___") && !rewritten.contains("___")) {
+                continue;
+            }
+            score = readability_grade(&rewritten);
+            if best.as_ref().is_none_or(|(_, s)| score < *s) {
+                best = Some((rewritten, score));
+            }
+            if !self.above_target(score) {
+                break;
+            }
+        }
+        Ok(best.filter(|(_, s)| *s < readability_grade(text)))
+    }
+
+    // Rewrite every text block of `html` that reads above the target.
+    // Returns the replacements (byte range and new text) and how many
+    // blocks are still above the target.
+    async fn rewrite_html(
+        &self,
+        html: &str,
+    ) -> Result<(Vec<(Range<usize>, String)>, usize), String> {
+        let blocks: Vec<(Range<usize>, String)> = artifact_analysis::text_spans(html)
+            .into_iter()
+            .map(|span| {
+                let decoded = artifact_analysis::decode_entities(&html[span.clone()]);
+                (span, decoded.split_whitespace().collect::<Vec<_>>().join(" "))
+            })
+            .filter(|(_, text)| text.split_whitespace().count() >= MIN_BLOCK_WORDS)
+            .filter(|(_, text)| self.above_target(readability_grade(text)))
+            .collect();
+
+        let total = Some(blocks.len() as u64);
+        let mut replacements = Vec::new();
+        let mut blocks_above_target = 0;
+        for (i, (span, text)) in blocks.into_iter().enumerate() {
+            self.task.check_cancelled()?;
+            self.task.progress(i as u64, total, None);
+            let Some((rewritten, score)) = self.rewrite_block(&text).await? else {
+                blocks_above_target += 1;
+                continue;
+            };
+            if self.above_target(score) {
+                blocks_above_target += 1;
+            }
+            // Keep the surrounding whitespace so the layout doesn't shift
+            let raw = &html[span.clone()];
+            let leading = &raw[..raw.len() - raw.trim_start().len()];
+            let trailing = &raw[raw.trim_end().len()..];
+            let escaped = worksheet_assembly::escape_html(&rewritten);
+            replacements.push((span, format!("{leading}{escaped}{trailing}")));
+        }
+        Ok((replacements, blocks_above_target))
+    }
+}
+
+// Helper to round a readability score for display
+fn round_score(score: f64) -> f64 {
+    (score * 10.0).round() / 10.0
+}
+
+// ============================================
+// Reading Level Commands
+// ============================================
+
+/// Rewrite an artifact for a lower reading level with the local model.
+///
+/// Each text block above `target_grade` ("K" or 1-12) is sent through
+/// Ollama with level-appropriate constraints and the grade's prompt preset,
+/// and the result's Flesch-Kincaid grade is checked; a block that stays too
+/// hard is retried once and then given its easiest version. Runs as a
+/// cancellable "generation" task (ID `operation_id` when given). The leveled
+/// copy is saved as a new artifact with `leveledFrom` pointing at the
+/// source and is added to the source's project. Returns `{ artifactId,
+/// sourceArtifactId, targetGrade, originalScore, resultScore,
+/// blocksRewritten, blocksAboveTarget }`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn simplify_artifact(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    target_grade: String,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    let target = parse_grade(&target_grade)?;
+
+    let source = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    let html = source
+        .get("htmlContent")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+
+    let settings = settings_storage::load_settings(&app_handle).await?;
+    let model = settings
+        .ollama
+        .default_model
+        .clone()
+        .unwrap_or_else(|| ollama::RECOMMENDED_MODEL.to_string());
+
+    // The grade/subject preset sets the tone; the rewrite rules always apply
+    let subject = source.get("subject").and_then(|v| v.as_str());
+    let preset = prompt_presets::resolve_preset(&app_handle, Some(&target_grade), subject).await?;
+    let system = match preset.as_ref().and_then(|p| p.get("systemPrompt")?.as_str()) {
+        Some(preset_prompt) => format!("{}\n\n{}", preset_prompt, SYSTEM_PROMPT),
+        None => SYSTEM_PROMPT.to_string(),
+    };
+    let options = preset
+        .as_ref()
+        .and_then(|p| p.get("parameters").cloned())
+        .unwrap_or(Value::Null);
+
+    let task =
+        task_manager::start_task(&app_handle, operation_id, "generation", "Simplify artifact")?;
+    let rewriter = Rewriter {
+        task: &task,
+        endpoint: settings.ollama.endpoint.clone(),
+        model,
+        system,
+        options,
+        target,
+    };
+    let result = rewriter.rewrite_html(html).await;
+    task.finish(&result);
+    let (replacements, blocks_above_target) = result?;
+
+    let original_text = artifact_analysis::text_runs(html).join(" ");
+    if replacements.is_empty() {
+        return Err("Nothing in this artifact needed rewriting for that grade".to_string());
+    }
+
+    let mut leveled_html = html.to_string();
+    for (span, text) in replacements.iter().rev() {
+        leveled_html.replace_range(span.clone(), text);
+    }
+    let result_text = artifact_analysis::text_runs(&leveled_html).join(" ");
+
+    let mut leveled = source.clone();
+    let obj = leveled.as_object_mut().ok_or("Invalid artifact")?;
+    for key in artifact_clone::RESET_KEYS {
+        obj.remove(*key);
+    }
+    let leveled_id = uuid::Uuid::new_v4().to_string();
+    let title = source.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled");
+    let grade_label = if target == 0 { "K".to_string() } else { target.to_string() };
+    obj.insert("artifactId".to_string(), Value::String(leveled_id.clone()));
+    obj.insert(
+        "title".to_string(),
+        Value::String(format!("{} (Grade {})", title, grade_label)),
+    );
+    obj.insert("htmlContent".to_string(), Value::String(leveled_html));
+    obj.insert("grade".to_string(), Value::String(grade_label.clone()));
+    obj.insert("leveledFrom".to_string(), Value::String(artifact_id.clone()));
+    obj.insert(
+        "createdAt".to_string(),
+        Value::String(chrono::Utc::now().to_rfc3339()),
+    );
+
+    let content = serde_json::to_string_pretty(&leveled)
+        .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+    library_storage::write_artifact(&app_handle, &leveled, &content).await?;
+
+    if let Some(project_id) = leveled.get("projectId").and_then(|v| v.as_str()) {
+        if let Err(e) = project_storage::link_artifact(&app_handle, project_id, &leveled_id).await {
+            tracing::warn!(error = %e, "Failed to add leveled artifact to project");
+        }
+    }
+
+    audit_log::record(
+        &app_handle,
+        "simplify_artifact",
+        "artifact",
+        &[&leveled_id, &artifact_id],
+    )
+    .await;
+
+    let response = serde_json::json!({
+        "artifactId": leveled_id,
+        "sourceArtifactId": artifact_id,
+        "targetGrade": grade_label,
+        "originalScore": round_score(readability_grade(&original_text)),
+        "resultScore": round_score(readability_grade(&result_text)),
+        "blocksRewritten": replacements.len(),
+        "blocksAboveTarget": blocks_above_target,
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,358 @@
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, factory_reset, session_mode, settings_storage};
+
+const REMINDERS_DIR: &str = "reminders";
+const REMINDERS_FILE: &str = "reminders.json";
+
+/// Event emitted with the reminder when one comes due
+pub(crate) const REMINDER_DUE_EVENT: &str = "reminder://due";
+
+// How often the scheduler looks for due reminders
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const KINDS: &[&str] = &["review_due", "assignment_due", "backup_overdue", "custom"];
+
+// Kind of the reminder the scheduler adds when backups fall behind
+const BACKUP_OVERDUE: &str = "backup_overdue";
+
+/// Managed state serializing changes to the reminders file between the
+/// commands and the scheduler
+#[derive(Default)]
+pub struct RemindersState {
+    lock: tokio::sync::Mutex<()>,
+}
+
+// Helper to get the reminders file path
+fn get_reminders_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(REMINDERS_DIR).join(REMINDERS_FILE))
+}
+
+/// Read every stored reminder, including dismissed ones
+pub(crate) async fn read_reminders(app_handle: &tauri::AppHandle) -> Result<Vec<Value>, String> {
+    let reminders_path = get_reminders_path(app_handle)?;
+    if !reminders_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&reminders_path)
+        .await
+        .map_err(|e| format!("Failed to read reminders: {}", e))?;
+    Ok(serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()))
+}
+
+/// Write every stored reminder
+pub(crate) async fn write_reminders(
+    app_handle: &tauri::AppHandle,
+    reminders: &[Value],
+) -> Result<(), String> {
+    let reminders_path = get_reminders_path(app_handle)?;
+    if let Some(parent) = reminders_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create reminders directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(reminders)
+        .map_err(|e| format!("Failed to serialize reminders: {}", e))?;
+    fs::write(&reminders_path, content)
+        .await
+        .map_err(|e| format!("Failed to write reminders: {}", e))
+}
+
+// Helper to read a reminder's ID
+fn reminder_id(reminder: &Value) -> Option<&str> {
+    reminder.get("reminderId").and_then(|v| v.as_str())
+}
+
+// Helper to read a reminder's due time
+fn due_at(reminder: &Value) -> Option<chrono::DateTime<chrono::Utc>> {
+    reminder
+        .get("dueAt")
+        .and_then(|v| v.as_str())
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&chrono::Utc))
+}
+
+// Helper to check whether a reminder is still waiting to fire
+fn is_pending(reminder: &Value) -> bool {
+    reminder.get("notifiedAt").is_none_or(Value::is_null)
+        && reminder.get("dismissedAt").is_none_or(Value::is_null)
+}
+
+// Helper to check a reminder from the frontend has what the scheduler needs
+fn validate_reminder(reminder: &Value) -> Result<(), String> {
+    let kind = reminder.get("kind").and_then(|v| v.as_str()).unwrap_or("");
+    if !KINDS.contains(&kind) {
+        return Err(format!("Invalid reminder kind: {}", kind));
+    }
+    let title = reminder.get("title").and_then(|v| v.as_str()).unwrap_or("");
+    if title.trim().is_empty() {
+        return Err("Reminder must have a title".to_string());
+    }
+    if due_at(reminder).is_none() {
+        return Err("Reminder must have an RFC 3339 dueAt".to_string());
+    }
+    Ok(())
+}
+
+// Helper to find when the newest backup was written, if any
+async fn last_backup_at(app_handle: &tauri::AppHandle) -> Option<std::time::SystemTime> {
+    let backup_dir = factory_reset::get_backup_dir(app_handle).await.ok()?;
+    let mut dir = fs::read_dir(&backup_dir).await.ok()?;
+    let mut newest = None;
+    while let Ok(Some(entry)) = dir.next_entry().await {
+        let Ok(metadata) = entry.metadata().await else {
+            continue;
+        };
+        if let Ok(modified) = metadata.modified() {
+            newest = newest.max(Some(modified));
+        }
+    }
+    newest
+}
+
+// Helper to add a backup reminder when automatic backups are enabled but
+// the newest backup is older than the interval. Returns whether one was
+// added.
+async fn check_backup_overdue(app_handle: &tauri::AppHandle, reminders: &mut Vec<Value>) -> bool {
+    let Ok(settings) = settings_storage::load_settings(app_handle).await else {
+        return false;
+    };
+    if !settings.backup.enabled {
+        return false;
+    }
+    let interval = Duration::from_secs(u64::from(settings.backup.interval_hours) * 3600);
+
+    // Remind at most once per interval, even after a dismissal
+    let since = chrono::Utc::now() - interval;
+    let already_reminded = reminders.iter().any(|r| {
+        r.get("kind").and_then(|v| v.as_str()) == Some(BACKUP_OVERDUE)
+            && r.get("createdAt")
+                .and_then(|v| v.as_str())
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .is_some_and(|created| created > since)
+    });
+    if already_reminded {
+        return false;
+    }
+    let overdue = match last_backup_at(app_handle).await {
+        Some(modified) => modified.elapsed().is_ok_and(|age| age > interval),
+        None => true,
+    };
+    if !overdue {
+        return false;
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    reminders.push(serde_json::json!({
+        "reminderId": uuid::Uuid::new_v4().to_string(),
+        "kind": BACKUP_OVERDUE,
+        "title": "Backup overdue",
+        "body": "Your teaching materials haven't been backed up recently.",
+        "dueAt": now,
+        "notifiedAt": null,
+        "dismissedAt": null,
+        "createdAt": now,
+        "updatedAt": now,
+    }));
+    true
+}
+
+// Helper to announce a due reminder in the app and as an OS notification
+fn fire(app_handle: &tauri::AppHandle, reminder: &Value) {
+    if let Err(e) = app_handle.emit(REMINDER_DUE_EVENT, reminder) {
+        tracing::warn!(error = %e, "Failed to emit reminder");
+    }
+    let title = reminder.get("title").and_then(|v| v.as_str()).unwrap_or("Reminder");
+    let mut notification = app_handle.notification().builder().title(title);
+    if let Some(body) = reminder.get("body").and_then(|v| v.as_str()) {
+        notification = notification.body(body);
+    }
+    if let Err(e) = notification.show() {
+        tracing::warn!(error = %e, "Failed to show reminder notification");
+    }
+}
+
+// Helper to fire every pending reminder that has come due, adding a backup
+// reminder first if backups are overdue
+async fn check_due(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let state = app_handle.state::<RemindersState>();
+    let _guard = state.lock.lock().await;
+
+    let mut reminders = read_reminders(app_handle).await?;
+    let mut changed = check_backup_overdue(app_handle, &mut reminders).await;
+
+    let now = chrono::Utc::now();
+    for reminder in reminders.iter_mut() {
+        if !is_pending(reminder) || due_at(reminder).is_none_or(|due| due > now) {
+            continue;
+        }
+        if let Some(obj) = reminder.as_object_mut() {
+            obj.insert("notifiedAt".to_string(), Value::String(now.to_rfc3339()));
+        }
+        fire(app_handle, reminder);
+        changed = true;
+    }
+
+    if changed {
+        write_reminders(app_handle, &reminders).await?;
+    }
+    Ok(())
+}
+
+/// Start the background task that fires due reminders. Called from the
+/// app's setup.
+pub fn start_scheduler(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = check_due(&app_handle).await {
+                tracing::warn!(error = %e, "Failed to check reminders");
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// ============================================
+// Reminder Commands
+// ============================================
+
+/// Get reminders ordered by due time. Dismissed reminders are left out
+/// unless `include_dismissed` is set.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_reminders(
+    app_handle: tauri::AppHandle,
+    include_dismissed: Option<bool>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut reminders = read_reminders(&app_handle).await?;
+    if !include_dismissed.unwrap_or(false) {
+        reminders.retain(|r| r.get("dismissedAt").is_none_or(Value::is_null));
+    }
+    reminders.sort_by_key(due_at);
+    serde_json::to_string(&reminders).map_err(|e| format!("Failed to serialize reminders: {}", e))
+}
+
+/// Save a reminder (create or update). It needs a `kind` (`review_due`,
+/// `assignment_due`, `backup_overdue`, or `custom`), a `title`, and a
+/// `dueAt` time, and may have a `body` and related `learnerId`,
+/// `artifactId`, or `projectId`. A missing `reminderId` creates a new
+/// reminder; moving `dueAt` lets it fire again. Returns the saved reminder.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn save_reminder(
+    app_handle: tauri::AppHandle,
+    reminder: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let mut new_reminder: Value =
+        serde_json::from_str(&reminder).map_err(|e| format!("Invalid reminder JSON: {}", e))?;
+    validate_reminder(&new_reminder)?;
+
+    let state = app_handle.state::<RemindersState>();
+    let _guard = state.lock.lock().await;
+    let mut reminders = read_reminders(&app_handle).await?;
+
+    let id = reminder_id(&new_reminder)
+        .map(String::from)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let existing = reminders.iter().position(|r| reminder_id(r) == Some(id.as_str()));
+    let now = chrono::Utc::now().to_rfc3339();
+    let new_due = due_at(&new_reminder);
+    if let Some(obj) = new_reminder.as_object_mut() {
+        let stored = existing.map(|i| &reminders[i]);
+        // Keep delivery state unless the reminder was moved to a new time
+        let rescheduled = stored.is_none_or(|s| due_at(s) != new_due);
+        for key in ["notifiedAt", "dismissedAt"] {
+            let kept = stored
+                .filter(|_| !rescheduled)
+                .and_then(|s| s.get(key).cloned())
+                .unwrap_or(Value::Null);
+            obj.insert(key.to_string(), kept);
+        }
+        let created_at = stored
+            .and_then(|s| s.get("createdAt").cloned())
+            .unwrap_or_else(|| Value::String(now.clone()));
+        obj.insert("reminderId".to_string(), Value::String(id.clone()));
+        obj.insert("createdAt".to_string(), created_at);
+        obj.insert("updatedAt".to_string(), Value::String(now));
+    }
+
+    match existing {
+        Some(i) => reminders[i] = new_reminder.clone(),
+        None => reminders.push(new_reminder.clone()),
+    }
+    write_reminders(&app_handle, &reminders).await?;
+
+    audit_log::record(&app_handle, "save_reminder", "reminder", &[&id]).await;
+
+    Ok(new_reminder.to_string())
+}
+
+/// Dismiss a reminder so it no longer fires or shows in `get_reminders`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn dismiss_reminder(
+    app_handle: tauri::AppHandle,
+    reminder_id: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let state = app_handle.state::<RemindersState>();
+    let _guard = state.lock.lock().await;
+    let mut reminders = read_reminders(&app_handle).await?;
+    let reminder = reminders
+        .iter_mut()
+        .find(|r| self::reminder_id(r) == Some(reminder_id.as_str()))
+        .ok_or_else(|| format!("Reminder not found: {}", reminder_id))?;
+    if let Some(obj) = reminder.as_object_mut() {
+        obj.insert(
+            "dismissedAt".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339()),
+        );
+    }
+    write_reminders(&app_handle, &reminders).await?;
+
+    audit_log::record(&app_handle, "dismiss_reminder", "reminder", &[&reminder_id]).await;
+
+    Ok(())
+}
+
+/// Delete a reminder
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_reminder(
+    app_handle: tauri::AppHandle,
+    reminder_id: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let state = app_handle.state::<RemindersState>();
+    let _guard = state.lock.lock().await;
+    let mut reminders = read_reminders(&app_handle).await?;
+    let before = reminders.len();
+    reminders.retain(|r| self::reminder_id(r) != Some(reminder_id.as_str()));
+    if reminders.len() != before {
+        write_reminders(&app_handle, &reminders).await?;
+    }
+
+    audit_log::record(&app_handle, "delete_reminder", "reminder", &[&reminder_id]).await;
+
+    Ok(())
+}
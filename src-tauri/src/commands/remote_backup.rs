@@ -0,0 +1,113 @@
+use tauri::Manager;
+use tokio::fs;
+
+use crate::archive;
+use crate::commands::{
+    disk_space, factory_reset, image_cache, index_cache, local_image, logging,
+};
+
+/// File name prefix of backups uploaded to a remote target
+pub(crate) const BACKUP_PREFIX: &str = "ta-backup-";
+
+/// Name for a new backup archive, e.g. `ta-backup-20261016-154500.zip`
+pub(crate) fn backup_file_name() -> String {
+    format!("{}{}.zip", BACKUP_PREFIX, chrono::Utc::now().format("%Y%m%d-%H%M%S"))
+}
+
+/// Whether a remote file name looks like one of our backup archives
+pub(crate) fn is_backup_name(name: &str) -> bool {
+    name.starts_with(BACKUP_PREFIX) && name.ends_with(".zip")
+}
+
+// Directory of the optional data history repository, which backups leave out
+const HISTORY_DIR: &str = ".git";
+
+// Helper to get the logs directory name relative to the app data directory,
+// which backups leave out
+fn logs_dir_name(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let logs_dir = logging::get_logs_dir(app_handle)?;
+    Ok(logs_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default())
+}
+
+/// Zip the app data directory (without logs, history, image models, or
+/// cached images) into a backup archive, writing pending index changes first
+pub(crate) async fn build_backup(app_handle: &tauri::AppHandle) -> Result<Vec<u8>, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    index_cache::flush(app_handle).await?;
+
+    let entries = if app_data_dir.exists() {
+        let logs = logs_dir_name(app_handle)?;
+        let skipped = [
+            logs.as_str(),
+            HISTORY_DIR,
+            local_image::MODELS_DIR,
+            image_cache::CACHE_DIR,
+        ];
+        archive::collect_dir(&app_data_dir, "", &skipped).await?
+    } else {
+        Vec::new()
+    };
+    archive::build_zip(&entries)
+}
+
+/// Restore a backup archive over the app data directory. The current data
+/// is first saved as a `ta-pre-restore-*.zip` in the backup folder; files
+/// missing from the archive are kept. Returns that safety backup's path and
+/// the number of files restored. Caches held in memory may be stale
+/// afterwards, so the app should be restarted.
+pub(crate) async fn restore_backup(
+    app_handle: &tauri::AppHandle,
+    bytes: &[u8],
+) -> Result<(String, usize), String> {
+    // Fail on a bad archive before touching anything
+    let entries = archive::read_zip(bytes)?;
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let backup_dir = factory_reset::get_backup_dir(app_handle).await?;
+    fs::create_dir_all(&backup_dir)
+        .await
+        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    let safety_path = backup_dir.join(format!(
+        "ta-pre-restore-{}.zip",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    let current = build_backup(app_handle).await?;
+    // Room for the safety backup and every restored file, before either
+    // is written
+    let restoring: u64 = entries.iter().map(|(_, contents)| contents.len() as u64).sum();
+    disk_space::ensure_available(&app_data_dir, current.len() as u64 + restoring)?;
+    fs::write(&safety_path, current)
+        .await
+        .map_err(|e| format!("Failed to write backup: {}", e))?;
+
+    let logs_prefix = format!("{}/", logs_dir_name(app_handle)?);
+    let history_prefix = format!("{}/", HISTORY_DIR);
+    let mut restored = 0;
+    for (name, contents) in entries {
+        if name.starts_with(&logs_prefix) || name.starts_with(&history_prefix) {
+            continue;
+        }
+        let path = app_data_dir.join(&name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create directory for {}: {}", name, e))?;
+        }
+        fs::write(&path, contents)
+            .await
+            .map_err(|e| format!("Failed to restore {}: {}", name, e))?;
+        index_cache::invalidate(app_handle, &path);
+        restored += 1;
+    }
+
+    Ok((safety_path.to_string_lossy().to_string(), restored))
+}
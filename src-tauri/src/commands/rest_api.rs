@@ -0,0 +1,334 @@
+use axum::extract::{Path, Query, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+use crate::commands::{
+    app_lock, audit_log, learner_storage, library_storage, local_http, project_stats, secrets,
+    session_mode, settings_storage,
+};
+
+/// Keychain key holding the API token
+pub(crate) const TOKEN_KEY: &str = "api.token";
+
+// Time allowed for one request, so a stalled one can't hold a connection
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+const DEFAULT_LIMIT: usize = 100;
+
+// Query parameters passed through to `search_artifacts`
+const SEARCH_PARAMS: &[&str] = &[
+    "searchText",
+    "projectId",
+    "grade",
+    "subject",
+    "type",
+    "objectiveTag",
+    "designPackId",
+    "dateFrom",
+    "dateTo",
+    "sortBy",
+];
+
+/// The running API server
+struct ApiServer {
+    port: u16,
+    cancel: CancellationToken,
+}
+
+/// Managed state holding the API server, if it's running, and why it last
+/// failed to start
+#[derive(Default)]
+pub struct RestApiState {
+    server: Mutex<Option<ApiServer>>,
+    error: Mutex<Option<String>>,
+}
+
+// Helper to read the API token, creating one the first time
+async fn api_token() -> Result<String, String> {
+    if let Some(token) = secrets::read_secret(TOKEN_KEY).await? {
+        return Ok(token);
+    }
+    let token = local_http::new_token();
+    secrets::write_secret(TOKEN_KEY, &token).await?;
+    Ok(token)
+}
+
+/// What the API's handlers share
+#[derive(Clone)]
+struct ApiContext {
+    app_handle: tauri::AppHandle,
+    port: u16,
+}
+
+// Helper to make a JSON error response
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+// Helper to answer with a resource: 404 when it doesn't exist, 500 when
+// reading it failed
+fn respond(resource: Result<Option<Value>, String>) -> Response {
+    match resource {
+        Ok(Some(body)) => Json(body).into_response(),
+        Ok(None) => error(StatusCode::NOT_FOUND, "Not found"),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &e),
+    }
+}
+
+// Helper to check every request's host and token, and that the app isn't
+// locked, before it's routed
+async fn authorize(State(context): State<ApiContext>, request: Request, next: Next) -> Response {
+    if !local_http::is_local_host(request.headers(), context.port) {
+        return error(StatusCode::FORBIDDEN, "Unexpected host");
+    }
+    let token = match api_token().await {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read API token");
+            return error(StatusCode::INTERNAL_SERVER_ERROR, "Token unavailable");
+        }
+    };
+    if !local_http::has_token(request.headers(), &token) {
+        return error(StatusCode::UNAUTHORIZED, "Missing or wrong API token");
+    }
+    if app_lock::ensure_unlocked(&context.app_handle).is_err() {
+        return error(StatusCode::LOCKED, "The app is locked");
+    }
+    next.run(request).await
+}
+
+// Helper to answer `GET /api/v1/status`
+async fn get_status() -> Json<Value> {
+    Json(serde_json::json!({
+        "app": "teachers-assistant",
+        "version": env!("CARGO_PKG_VERSION"),
+    }))
+}
+
+// Helper to answer `GET /api/v1/artifacts`
+async fn get_artifacts(
+    State(context): State<ApiContext>,
+    Query(params): Query<BTreeMap<String, String>>,
+) -> Response {
+    let query: serde_json::Map<String, Value> = params
+        .iter()
+        .filter(|(key, _)| SEARCH_PARAMS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+        .collect();
+    let limit = params
+        .get("limit")
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(DEFAULT_LIMIT);
+    let results =
+        library_storage::search_artifacts(context.app_handle, Value::Object(query).to_string())
+            .await
+            .map(|results| {
+                let results: Vec<Value> = serde_json::from_str(&results).unwrap_or_default();
+                Some(Value::Array(results.into_iter().take(limit).collect()))
+            });
+    respond(results)
+}
+
+// Helper to answer `GET /api/v1/artifacts/{artifact_id}`
+async fn get_artifact(
+    State(context): State<ApiContext>,
+    Path(artifact_id): Path<String>,
+) -> Response {
+    respond(Ok(library_storage::read_artifact(&context.app_handle, &artifact_id).await.ok()))
+}
+
+// Helper to answer `GET /api/v1/learners`
+async fn get_learners(State(context): State<ApiContext>) -> Response {
+    let learners = learner_storage::read_profiles(&context.app_handle)
+        .await
+        .map(|profiles| {
+            let learners = profiles
+                .iter()
+                .map(|p| {
+                    serde_json::json!({
+                        "learnerId": p.get("learnerId"),
+                        "displayName": p.get("displayName"),
+                        "grade": p.get("grade"),
+                    })
+                })
+                .collect();
+            Some(Value::Array(learners))
+        });
+    respond(learners)
+}
+
+// Helper to read a learner's mastery summary, or None for an unknown
+// learner
+async fn read_mastery(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+) -> Result<Option<Value>, String> {
+    let profiles = learner_storage::read_profiles(app_handle).await?;
+    let known = profiles
+        .iter()
+        .any(|p| p.get("learnerId").and_then(|v| v.as_str()) == Some(learner_id));
+    if !known {
+        return Ok(None);
+    }
+    let objectives = project_stats::read_mastery(app_handle, learner_id).await?;
+    let mut summary: BTreeMap<&str, usize> = BTreeMap::new();
+    for record in objectives.values() {
+        let state = record.get("state").and_then(|v| v.as_str()).unwrap_or("not_started");
+        *summary.entry(state).or_default() += 1;
+    }
+    Ok(Some(serde_json::json!({
+        "learnerId": learner_id,
+        "summary": summary,
+        "objectives": objectives,
+    })))
+}
+
+// Helper to answer `GET /api/v1/learners/{learner_id}/mastery`
+async fn get_mastery(
+    State(context): State<ApiContext>,
+    Path(learner_id): Path<String>,
+) -> Response {
+    respond(read_mastery(&context.app_handle, &learner_id).await)
+}
+
+// Helper to serve requests until the server is stopped
+async fn serve(
+    app_handle: tauri::AppHandle,
+    listener: TcpListener,
+    port: u16,
+    cancel: CancellationToken,
+) {
+    let context = ApiContext { app_handle, port };
+    let router = Router::new()
+        .route("/api/v1/status", get(get_status))
+        .route("/api/v1/artifacts", get(get_artifacts))
+        .route("/api/v1/artifacts/{artifact_id}", get(get_artifact))
+        .route("/api/v1/learners", get(get_learners))
+        .route("/api/v1/learners/{learner_id}/mastery", get(get_mastery))
+        .fallback(|| async { error(StatusCode::NOT_FOUND, "Not found") })
+        .layer(middleware::from_fn_with_state(context.clone(), authorize))
+        .with_state(context);
+    if let Err(e) = local_http::serve(listener, router, REQUEST_TIMEOUT, cancel).await {
+        tracing::warn!(error = %e, "API server failed");
+    }
+    tracing::info!("API server stopped");
+}
+
+// Helper to start the server on `port`, or stop it when `port` is None,
+// leaving it alone when it's already as wanted
+async fn run_on(app_handle: &tauri::AppHandle, port: Option<u16>) -> Result<(), String> {
+    let state = app_handle.state::<RestApiState>();
+    {
+        let mut server = state.server.lock().unwrap();
+        if server.as_ref().map(|s| s.port) == port {
+            return Ok(());
+        }
+        if let Some(server) = server.take() {
+            server.cancel.cancel();
+        }
+    }
+    let Some(port) = port else {
+        return Ok(());
+    };
+
+    let listener = TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port))
+        .await
+        .map_err(|e| format!("Failed to start API server on port {}: {}", port, e))?;
+    let cancel = CancellationToken::new();
+    *state.server.lock().unwrap() = Some(ApiServer {
+        port,
+        cancel: cancel.clone(),
+    });
+    tauri::async_runtime::spawn(serve(app_handle.clone(), listener, port, cancel));
+    tracing::info!(port, "API server started");
+    Ok(())
+}
+
+/// Start, stop, or move the API server to match settings. A failure (e.g.
+/// the port is taken) is kept for `get_api_server_status` rather than
+/// failing the caller.
+pub(crate) async fn apply(app_handle: &tauri::AppHandle) {
+    let result = match settings_storage::load_settings(app_handle).await {
+        Ok(settings) => run_on(app_handle, settings.api.enabled.then_some(settings.api.port)).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = &result {
+        tracing::warn!(error = %e, "Failed to apply API server settings");
+    }
+    *app_handle.state::<RestApiState>().error.lock().unwrap() = result.err();
+}
+
+/// Start the API server at startup if it's enabled in settings
+pub fn init(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        apply(&app_handle).await;
+    });
+}
+
+// ============================================
+// REST API Commands
+// ============================================
+
+/// Get the state of the local API server: `{ enabled, running, url, error
+/// }`. The server is turned on with `api.enabled` in settings and serves
+/// `GET` endpoints under `http://127.0.0.1:<api.port>/api/v1/`: `status`,
+/// `artifacts` (query parameters as for `search_artifacts`, plus `limit`),
+/// `artifacts/<id>`, `learners`, and `learners/<id>/mastery`. Requests need
+/// an `Authorization: Bearer <token>` header with the token from
+/// `get_api_token`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_api_server_status(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let settings = settings_storage::load_settings(&app_handle).await?;
+    let state = app_handle.state::<RestApiState>();
+    let port = state.server.lock().unwrap().as_ref().map(|s| s.port);
+    let error = state.error.lock().unwrap().clone();
+    let status = serde_json::json!({
+        "enabled": settings.api.enabled,
+        "running": port.is_some(),
+        "url": port.map(|port| format!("http://127.0.0.1:{}/api/v1/", port)),
+        "error": error,
+    });
+    Ok(status.to_string())
+}
+
+/// Get the token external tools send to the API, creating it the first
+/// time
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_api_token(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    api_token().await
+}
+
+/// Replace the API token, so tools using the old one lose access. Returns
+/// the new token.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn regenerate_api_token(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let token = local_http::new_token();
+    secrets::write_secret(TOKEN_KEY, &token).await?;
+
+    audit_log::record(&app_handle, "regenerate_api_token", "api", &[]).await;
+
+    Ok(token)
+}
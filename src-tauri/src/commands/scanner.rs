@@ -0,0 +1,353 @@
+use serde_json::Value;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::commands::{
+    app_lock, audit_log, image_library, session_mode, settings_storage, task_manager,
+};
+
+// A high-resolution color scan can take a few minutes
+const SCAN_TIMEOUT: Duration = Duration::from_secs(300);
+
+// Tag added to every scanned page
+const SCAN_TAG: &str = "scan";
+
+// Windows Image Acquisition scripts, run with PowerShell. Parameters come in
+// through environment variables so nothing needs quoting.
+const WIA_LIST_SCRIPT: &str = r#"
+$manager = New-Object -ComObject WIA.DeviceManager
+foreach ($info in $manager.DeviceInfos) {
+  if ($info.Type -eq 1) { "{0}`t{1}" -f $info.DeviceID, $info.Properties.Item('Name').Value }
+}
+"#;
+const WIA_SCAN_SCRIPT: &str = r#"
+$ErrorActionPreference = 'Stop'
+$manager = New-Object -ComObject WIA.DeviceManager
+$device = $null
+foreach ($info in $manager.DeviceInfos) {
+  if ($info.DeviceID -eq $env:TA_SCANNER_ID) { $device = $info.Connect() }
+}
+if (-not $device) { throw 'Scanner not found' }
+$item = $device.Items.Item(1)
+foreach ($id in '6147', '6148') { $item.Properties.Item($id).Value = [int]$env:TA_SCAN_DPI }
+$image = $item.Transfer('{B96B3CAF-0728-11D3-9D7B-0000F81EF32E}')
+$image.SaveFile($env:TA_SCAN_PATH)
+"#;
+
+// ImageCaptureCore scripts for macOS, run with osascript as JavaScript.
+// The prelude browses for scanners, leaving them in `scanners`; a delegate
+// records the device's callbacks in `state`, waited on by spinning the run
+// loop. Parameters come in through environment variables.
+const ICA_PRELUDE: &str = r#"
+ObjC.import('Foundation');
+ObjC.import('ImageCaptureCore');
+const env = $.NSProcessInfo.processInfo.environment;
+const param = (name) => ObjC.unwrap(env.objectForKey(name));
+const state = { opened: false, selected: false, done: false, error: null };
+const fail = (error) => { state.error = error.isNil() ? null : error.localizedDescription.js; };
+const ignore = { types: ['void', ['id', 'id', 'bool']], implementation: () => {} };
+ObjC.registerSubclass({
+  name: 'TAScannerDelegate',
+  protocols: ['ICDeviceBrowserDelegate', 'ICScannerDeviceDelegate'],
+  methods: {
+    'deviceBrowser:didAddDevice:moreComing:': ignore,
+    'deviceBrowser:didRemoveDevice:moreGoing:': ignore,
+    'didRemoveDevice:': { types: ['void', ['id']], implementation: () => {} },
+    'device:didOpenSessionWithError:': {
+      types: ['void', ['id', 'id']],
+      implementation: (device, error) => { state.opened = true; fail(error); },
+    },
+    'device:didCloseSessionWithError:': {
+      types: ['void', ['id', 'id']],
+      implementation: () => {},
+    },
+    'scannerDevice:didSelectFunctionalUnit:error:': {
+      types: ['void', ['id', 'id', 'id']],
+      implementation: (device, unit, error) => { state.selected = true; fail(error); },
+    },
+    'scannerDevice:didCompleteScanWithError:': {
+      types: ['void', ['id', 'id']],
+      implementation: (device, error) => { state.done = true; fail(error); },
+    },
+  },
+});
+const delegate = $.TAScannerDelegate.alloc.init;
+const wait = (done, seconds) => {
+  const until = Date.now() + seconds * 1000;
+  while (!done() && Date.now() < until) {
+    $.NSRunLoop.currentRunLoop.runUntilDate($.NSDate.dateWithTimeIntervalSinceNow(0.1));
+  }
+};
+const browser = $.ICDeviceBrowser.alloc.init;
+browser.delegate = delegate;
+// Scanners, wherever they're connected
+browser.browsedDeviceTypeMask = 0x0002 | 0xFF00;
+browser.start;
+wait(() => false, 3);
+const scanners = ObjC.unwrap(browser.devices);
+browser.stop;
+"#;
+const ICA_LIST_SCRIPT: &str = r#"
+scanners.map((device) => device.UUIDString.js + '\t' + device.name.js).join('\n');
+"#;
+const ICA_SCAN_SCRIPT: &str = r#"
+const device = scanners.find((d) => d.UUIDString.js === param('TA_SCANNER_ID'));
+if (!device) throw new Error('Scanner not found');
+device.delegate = delegate;
+device.requestOpenSession;
+wait(() => state.opened, 30);
+if (!state.opened || state.error) throw new Error(state.error || 'The scanner did not respond');
+// Flatbed
+device.requestSelectFunctionalUnit(0);
+wait(() => state.selected, 30);
+if (state.error) throw new Error(state.error);
+const unit = device.selectedFunctionalUnit;
+// Inches, 8-bit RGB over the whole bed
+unit.measurementUnit = 0;
+unit.resolution = Number(param('TA_SCAN_DPI'));
+unit.pixelDataType = 2;
+unit.bitDepth = 8;
+const size = unit.physicalSize;
+unit.scanArea = $.NSMakeRect(0, 0, size.width, size.height);
+// Saved as a file in the given folder
+device.transferMode = 0;
+device.downloadsDirectory = $.NSURL.fileURLWithPath(param('TA_SCAN_DIR'));
+device.documentName = 'scan';
+device.documentUTI = 'public.png';
+device.requestScan;
+wait(() => state.done, 280);
+device.requestCloseSession;
+if (state.error) throw new Error(state.error);
+if (!state.done) throw new Error('The scanner did not finish');
+'';
+"#;
+
+/// A scanner as `list_scanners` reports it
+struct Scanner {
+    id: String,
+    name: String,
+}
+
+// Helper to run a scanning tool, returning its stdout. Fails with a hint
+// when the tool isn't installed, and with its last error line when it fails.
+async fn run_tool(
+    program: &str,
+    args: &[&str],
+    envs: &[(&str, String)],
+) -> Result<Vec<u8>, String> {
+    let child = Command::new(program)
+        .args(args)
+        .envs(envs.iter().map(|(k, v)| (*k, v.as_str())))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound if program == "scanimage" => {
+                "Scanning needs SANE. Install it (the sane-backends package).".to_string()
+            }
+            _ => format!("Failed to start {}: {}", program, e),
+        })?;
+    let output = tokio::time::timeout(SCAN_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| "The scanner didn't respond".to_string())?
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().rfind(|l| !l.trim().is_empty()).unwrap_or("unknown error");
+        return Err(format!("Scanner error: {}", reason.trim()));
+    }
+    Ok(output.stdout)
+}
+
+// Helper to parse scanners listed as `id<TAB>name` lines
+fn parse_scanners(output: &[u8]) -> Vec<Scanner> {
+    String::from_utf8_lossy(output)
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(id, name)| Scanner {
+            id: id.trim().to_string(),
+            name: name.trim().to_string(),
+        })
+        .collect()
+}
+
+// Helper to list scanners: WIA on Windows, ImageCaptureCore on macOS, SANE
+// on Linux
+async fn find_scanners() -> Result<Vec<Scanner>, String> {
+    if cfg!(target_os = "windows") {
+        let args = ["-NoProfile", "-NonInteractive", "-Command", WIA_LIST_SCRIPT];
+        let output = run_tool("powershell", &args, &[]).await?;
+        return Ok(parse_scanners(&output));
+    }
+    if cfg!(target_os = "macos") {
+        let script = format!("{}{}", ICA_PRELUDE, ICA_LIST_SCRIPT);
+        let output = run_tool("osascript", &["-l", "JavaScript", "-e", &script], &[]).await?;
+        return Ok(parse_scanners(&output));
+    }
+
+
+    // Lines look like: device `epson2:libusb:001:004' is a Epson flatbed scanner
+    let output = run_tool("scanimage", &["-L"], &[]).await?;
+    Ok(String::from_utf8_lossy(&output)
+        .lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix("device `")?;
+            let (id, description) = rest.split_once("' is a ")?;
+            Some(Scanner {
+                id: id.to_string(),
+                name: description.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+// Helper to read the PNG ImageCaptureCore saved in `dir`, whatever it
+// named it
+async fn read_scan(dir: &std::path::Path) -> Result<Vec<u8>, String> {
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .map_err(|e| format!("Failed to read scan: {}", e))?;
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| format!("Failed to read scan: {}", e))?
+    {
+        let path = entry.path();
+        if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("png")) {
+            return tokio::fs::read(&path)
+                .await
+                .map_err(|e| format!("Failed to read scan: {}", e));
+        }
+    }
+    Err("The scanner returned no image".to_string())
+}
+
+// Helper to scan one page in color, returning PNG bytes
+async fn scan_png(device: &str, resolution: u32) -> Result<Vec<u8>, String> {
+    if cfg!(target_os = "windows") {
+        let temp_path =
+            std::env::temp_dir().join(format!("ta-scan-{}.png", uuid::Uuid::new_v4()));
+        let envs = [
+            ("TA_SCANNER_ID", device.to_string()),
+            ("TA_SCAN_DPI", resolution.to_string()),
+            ("TA_SCAN_PATH", temp_path.to_string_lossy().to_string()),
+        ];
+        let args = ["-NoProfile", "-NonInteractive", "-Command", WIA_SCAN_SCRIPT];
+        let result = run_tool("powershell", &args, &envs).await;
+        let png = tokio::fs::read(&temp_path).await;
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        result?;
+        return png.map_err(|e| format!("Failed to read scan: {}", e));
+    }
+    if cfg!(target_os = "macos") {
+        let temp_dir = std::env::temp_dir().join(format!("ta-scan-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&temp_dir)
+            .await
+            .map_err(|e| format!("Failed to create scan folder: {}", e))?;
+        let envs = [
+            ("TA_SCANNER_ID", device.to_string()),
+            ("TA_SCAN_DPI", resolution.to_string()),
+            ("TA_SCAN_DIR", temp_dir.to_string_lossy().to_string()),
+        ];
+        let script = format!("{}{}", ICA_PRELUDE, ICA_SCAN_SCRIPT);
+        let result = run_tool("osascript", &["-l", "JavaScript", "-e", &script], &envs).await;
+        let png = read_scan(&temp_dir).await;
+        let _ = tokio::fs::remove_dir_all(&temp_dir).await;
+        result?;
+        return png;
+    }
+
+    let resolution = resolution.to_string();
+    let args = [
+        "-d",
+        device,
+        "--format=png",
+        "--mode",
+        "Color",
+        "--resolution",
+        &resolution,
+    ];
+    let png = run_tool("scanimage", &args, &[]).await?;
+    if png.is_empty() {
+        return Err("The scanner returned no image".to_string());
+    }
+    Ok(png)
+}
+
+// ============================================
+// Scanner Commands
+// ============================================
+
+/// List connected scanners as `[{id, name}]`. Uses Windows Image
+/// Acquisition on Windows, ImageCaptureCore on macOS, and SANE
+/// (`scanimage`, which must be installed) on Linux.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_scanners(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let scanners: Vec<Value> = find_scanners()
+        .await?
+        .into_iter()
+        .map(|s| serde_json::json!({ "id": s.id, "name": s.name }))
+        .collect();
+    serde_json::to_string(&scanners).map_err(|e| format!("Failed to serialize scanners: {}", e))
+}
+
+/// Scan a page from the flatbed and add it to the image library, tagged
+/// "scan" plus `tags`, ready for import or grading. `device`, an ID from
+/// `list_scanners`, defaults to the scanner in settings, then to the first
+/// one found; `resolution`
+/// (DPI) defaults to the setting (300). Runs as a cancellable "scan" task
+/// (ID `operation_id` when given). Returns the new image library entry.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn scan_document(
+    app_handle: tauri::AppHandle,
+    device: Option<String>,
+    resolution: Option<u32>,
+    tags: Option<Vec<String>>,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let capture = settings_storage::load_settings(&app_handle).await?.capture;
+    let resolution = resolution.unwrap_or(capture.scan_resolution).clamp(75, 1200);
+
+    let task = task_manager::start_task(&app_handle, operation_id, "scan", "Scan document")?;
+    let result = task
+        .cancellable(async {
+            let scanners = find_scanners().await?;
+            // The device ends up in the scanning tool's arguments, so only a
+            // scanner that was found is accepted
+            let device = match device.or(capture.scanner_device).filter(|d| !d.trim().is_empty())
+            {
+                Some(device) => scanners
+                    .into_iter()
+                    .find(|s| s.id == device)
+                    .map(|s| s.id)
+                    .ok_or_else(|| format!("Scanner not found: {}", device))?,
+                None => scanners.into_iter().next().map(|s| s.id).ok_or("No scanner found")?,
+            };
+            task.progress(0, None, Some("Scanning"));
+            scan_png(&device, resolution).await
+        })
+        .await;
+    task.finish(&result);
+    let png = result?;
+
+    let mut tags = tags.unwrap_or_default();
+    tags.push(SCAN_TAG.to_string());
+    let file_name = format!("Scan {}.png", chrono::Local::now().format("%Y-%m-%d %H.%M.%S"));
+    let entry = image_library::add_image(&app_handle, png, &file_name, &tags).await?;
+
+    let image_id = entry.get("imageId").and_then(|v| v.as_str()).unwrap_or_default();
+    audit_log::record(&app_handle, "scan_document", "image", &[image_id]).await;
+
+    Ok(image_library::with_urls(entry).to_string())
+}
@@ -0,0 +1,130 @@
+use keyring::Entry;
+
+use crate::commands::{app_lock, audit_log, session_mode};
+
+// Keychain service name; matches the bundle identifier in tauri.conf.json
+const SERVICE_NAME: &str = "com.ta.teachers-assistant";
+
+// Prefix of the keys the secret commands can use. The app's own entries
+// (the teacher PIN, server tokens, passwords, and sign-in tokens) are
+// outside it, so the frontend can't read or replace them.
+const USER_PREFIX: &str = "user.";
+
+// Helper to validate a secret key name
+fn validate_key(key: &str) -> Result<(), String> {
+    let valid = !key.is_empty()
+        && key.len() <= 128
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if !valid {
+        return Err(format!("Invalid secret key: {}", key));
+    }
+    Ok(())
+}
+
+// Helper to validate a key given to the secret commands
+fn validate_user_key(key: &str) -> Result<(), String> {
+    validate_key(key)?;
+    if !key.starts_with(USER_PREFIX) {
+        return Err(format!("Secret keys must start with \"{}\": {}", USER_PREFIX, key));
+    }
+    Ok(())
+}
+
+// Helper to open the keychain entry for a key
+fn get_entry(key: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE_NAME, key).map_err(|e| format!("Failed to open keychain: {}", e))
+}
+
+/// Read a secret from the OS keychain, if present
+pub(crate) async fn read_secret(key: &str) -> Result<Option<String>, String> {
+    validate_key(key)?;
+    let key = key.to_string();
+
+    tokio::task::spawn_blocking(move || match get_entry(&key)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read secret: {}", e)),
+    })
+    .await
+    .map_err(|e| format!("Keychain task failed: {}", e))?
+}
+
+/// Write a secret to the OS keychain
+pub(crate) async fn write_secret(key: &str, value: &str) -> Result<(), String> {
+    validate_key(key)?;
+    let key = key.to_string();
+    let value = value.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        get_entry(&key)?
+            .set_password(&value)
+            .map_err(|e| format!("Failed to store secret: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Keychain task failed: {}", e))?
+}
+
+/// Delete a secret from the OS keychain (no-op if not set)
+pub(crate) async fn remove_secret(key: &str) -> Result<(), String> {
+    validate_key(key)?;
+    let key = key.to_string();
+
+    tokio::task::spawn_blocking(move || match get_entry(&key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete secret: {}", e)),
+    })
+    .await
+    .map_err(|e| format!("Keychain task failed: {}", e))?
+}
+
+// ============================================
+// Secrets Commands
+// ============================================
+
+/// Store a secret (API key, encryption key) in the OS keychain. Keys must
+/// start with `user.`; the app's own secrets have their own commands.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn set_secret(
+    app_handle: tauri::AppHandle,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    validate_user_key(&key)?;
+
+    write_secret(&key, &value).await?;
+
+    audit_log::record(&app_handle, "set_secret", "secret", &[&key]).await;
+
+    Ok(())
+}
+
+/// Get a secret stored with `set_secret` (null if not set)
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_secret(app_handle: tauri::AppHandle, key: String) -> Result<Option<String>, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    validate_user_key(&key)?;
+
+    read_secret(&key).await
+}
+
+/// Delete a secret stored with `set_secret` (no-op if not set)
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_secret(app_handle: tauri::AppHandle, key: String) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    validate_user_key(&key)?;
+
+    remove_secret(&key).await?;
+
+    audit_log::record(&app_handle, "delete_secret", "secret", &[&key]).await;
+
+    Ok(())
+}
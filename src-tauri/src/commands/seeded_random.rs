@@ -0,0 +1,39 @@
+/// Small deterministic PRNG (SplitMix64). Kept in-tree so the same seed
+/// produces the same variants and shuffles across app and dependency
+/// versions.
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index in 0..n (n > 0)
+    pub(crate) fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// Shuffle `items` in place (Fisher-Yates)
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/// Parse a seed passed over IPC, or pick a fresh one when there's none.
+/// Seeds cross IPC as strings since JavaScript numbers can't hold a u64.
+pub(crate) fn parse_seed(seed: Option<&str>) -> Result<u64, String> {
+    match seed {
+        Some(seed) => seed
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid seed: {}", seed)),
+        None => Ok(uuid::Uuid::new_v4().as_u64_pair().0),
+    }
+}
@@ -0,0 +1,189 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Mutex;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{app_lock, learner_bundle, learner_storage, locale};
+
+const SESSIONS_FILE: &str = "sessions.json";
+
+/// An active learner (kiosk) session
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LearnerSession {
+    session_id: String,
+    learner_id: String,
+    started_at: String,
+    // Time limit the teacher set for quick checks during the session
+    quick_check_time_limit_seconds: Option<u64>,
+}
+
+/// Managed state holding the active learner session, if any.
+/// With no session the app is in teacher mode.
+#[derive(Default)]
+pub struct SessionModeState {
+    active: Mutex<Option<LearnerSession>>,
+}
+
+// Helper to get the active learner session
+fn active_session(app_handle: &tauri::AppHandle) -> Option<LearnerSession> {
+    app_handle
+        .state::<SessionModeState>()
+        .active
+        .lock()
+        .unwrap()
+        .clone()
+}
+
+/// Guard for teacher-only commands (deletes, exports, settings, raw file access)
+pub(crate) fn ensure_teacher_mode(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    if active_session(app_handle).is_some() {
+        return Err(locale::text(app_handle, "error.learnerSession"));
+    }
+    Ok(())
+}
+
+/// Guard for learner data: during a learner session only that learner's
+/// data can be read or written
+pub(crate) fn ensure_learner_allowed(
+    app_handle: &tauri::AppHandle,
+    learner_id: &str,
+) -> Result<(), String> {
+    match active_session(app_handle) {
+        Some(session) if session.learner_id != learner_id => {
+            Err(locale::text(app_handle, "error.learnerSession"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The learner ID of the active session, if one is running
+pub(crate) fn active_learner_id(app_handle: &tauri::AppHandle) -> Option<String> {
+    active_session(app_handle).map(|s| s.learner_id)
+}
+
+/// End any learner session without the PIN or a session log entry, for a
+/// factory reset that has wiped the logs
+pub(crate) fn reset(app_handle: &tauri::AppHandle) {
+    *app_handle.state::<SessionModeState>().active.lock().unwrap() = None;
+}
+
+/// The quick check time limit the teacher set for the active session, if
+/// one is running and has a limit
+pub(crate) fn quick_check_time_limit(app_handle: &tauri::AppHandle) -> Option<u64> {
+    active_session(app_handle).and_then(|s| s.quick_check_time_limit_seconds)
+}
+
+// Helper to append or update a session record in the learner's session log
+async fn write_session_log(
+    app_handle: &tauri::AppHandle,
+    session: &LearnerSession,
+    ended_at: Option<&str>,
+) -> Result<(), String> {
+    let learner_dir = learner_storage::get_learner_dir(app_handle, &session.learner_id)?;
+    let sessions_path = learner_dir.join(SESSIONS_FILE);
+
+    fs::create_dir_all(&learner_dir)
+        .await
+        .map_err(|e| format!("Failed to create learner directory: {}", e))?;
+
+    let mut sessions: Vec<Value> = if sessions_path.exists() {
+        let content = fs::read_to_string(&sessions_path)
+            .await
+            .map_err(|e| format!("Failed to read session log: {}", e))?;
+        serde_json::from_str(&content).unwrap_or_else(|_| Vec::new())
+    } else {
+        Vec::new()
+    };
+
+    let duration_seconds = ended_at.and_then(|end| {
+        let start = chrono::DateTime::parse_from_rfc3339(&session.started_at).ok()?;
+        let end = chrono::DateTime::parse_from_rfc3339(end).ok()?;
+        Some((end - start).num_seconds())
+    });
+    let record = serde_json::json!({
+        "sessionId": session.session_id,
+        "learnerId": session.learner_id,
+        "startedAt": session.started_at,
+        "endedAt": ended_at,
+        "durationSeconds": duration_seconds,
+    });
+
+    sessions.retain(|s| s.get("sessionId").and_then(|v| v.as_str()) != Some(&session.session_id));
+    sessions.push(record);
+
+    let content = serde_json::to_string_pretty(&sessions)
+        .map_err(|e| format!("Failed to serialize session log: {}", e))?;
+    fs::write(&sessions_path, content)
+        .await
+        .map_err(|e| format!("Failed to write session log: {}", e))?;
+
+    Ok(())
+}
+
+// ============================================
+// Session Mode Commands
+// ============================================
+
+/// Get the current mode: teacher, or learner with the active session
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_session_mode(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let status = match active_session(&app_handle) {
+        Some(session) => serde_json::json!({ "mode": "learner", "session": session }),
+        None => serde_json::json!({ "mode": "teacher", "session": null }),
+    };
+    Ok(status.to_string())
+}
+
+/// Start a learner (kiosk) session. Requires a teacher PIN to be set so the
+/// session can't be exited without it. `quick_check_time_limit_seconds`
+/// is the time limit for quick checks the learner starts during the
+/// session (none when omitted).
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn start_learner_session(
+    app_handle: tauri::AppHandle,
+    learner_id: String,
+    quick_check_time_limit_seconds: Option<u64>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    ensure_teacher_mode(&app_handle)?;
+
+    if !app_lock::has_pin(&app_handle).await? {
+        return Err("Set a teacher PIN before starting a learner session".to_string());
+    }
+
+    // Make sure the learner exists
+    learner_bundle::read_learner_profile(&app_handle, &learner_id).await?;
+
+    let session = LearnerSession {
+        session_id: uuid::Uuid::new_v4().to_string(),
+        learner_id,
+        started_at: chrono::Utc::now().to_rfc3339(),
+        quick_check_time_limit_seconds: quick_check_time_limit_seconds.filter(|&s| s > 0),
+    };
+    write_session_log(&app_handle, &session, None).await?;
+
+    *app_handle.state::<SessionModeState>().active.lock().unwrap() = Some(session.clone());
+
+    serde_json::to_string(&session).map_err(|e| format!("Failed to serialize session: {}", e))
+}
+
+/// End the learner session and return to teacher mode (requires the teacher PIN)
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn end_learner_session(app_handle: tauri::AppHandle, pin: String) -> Result<(), String> {
+    let session = active_session(&app_handle).ok_or("No learner session is active")?;
+
+    if !app_lock::verify_with_cooldown(&app_handle, &pin).await? {
+        return Err(locale::text(&app_handle, "error.incorrectPin"));
+    }
+
+    let ended_at = chrono::Utc::now().to_rfc3339();
+    write_session_log(&app_handle, &session, Some(&ended_at)).await?;
+
+    *app_handle.state::<SessionModeState>().active.lock().unwrap() = None;
+    Ok(())
+}
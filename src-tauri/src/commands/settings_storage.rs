@@ -0,0 +1,593 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{
+    app_lock, audit_log, http_client, locale, mcp_server, plugins, rest_api, session_mode,
+};
+
+const SETTINGS_DIR: &str = "settings";
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Current settings file version. Bump when the shape changes and handle the
+/// upgrade in `upgrade_settings`.
+const SETTINGS_VERSION: u32 = 1;
+
+/// Local AI (Ollama) settings
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OllamaSettings {
+    pub endpoint: String,
+    pub default_model: Option<String>,
+}
+
+impl Default for OllamaSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:11434".to_string(),
+            default_model: None,
+        }
+    }
+}
+
+/// Defaults applied when exporting artifacts
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportSettings {
+    pub default_directory: Option<String>,
+    pub default_format: String,
+    pub include_answer_key: bool,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            default_directory: None,
+            default_format: "pdf".to_string(),
+            include_answer_key: true,
+        }
+    }
+}
+
+/// Automatic backup schedule
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub interval_hours: u32,
+    pub directory: Option<String>,
+    pub keep_count: u32,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+            directory: None,
+            keep_count: 7,
+        }
+    }
+}
+
+/// Backend log verbosity
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoggingSettings {
+    pub level: String,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+        }
+    }
+}
+
+/// Opt-in sharing of aggregated usage counts
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TelemetrySettings {
+    pub share_usage_stats: bool,
+    pub report_endpoint: Option<String>,
+}
+
+/// What the app does while its window is closed
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackgroundSettings {
+    pub keep_running_in_tray: bool,
+    pub notify_when_ready: bool,
+}
+
+impl Default for BackgroundSettings {
+    fn default() -> Self {
+        Self {
+            keep_running_in_tray: false,
+            notify_when_ready: true,
+        }
+    }
+}
+
+/// Outgoing email. Without an SMTP host, email opens prefilled in the
+/// user's mail app instead. The SMTP password lives in the OS keychain.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmailSettings {
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    /// "starttls", "tls", or "none"
+    pub security: String,
+    pub username: Option<String>,
+    pub from_address: Option<String>,
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            smtp_host: None,
+            smtp_port: 587,
+            security: "starttls".to_string(),
+            username: None,
+            from_address: None,
+        }
+    }
+}
+
+/// Weekly digest reports. When scheduled, last week's digests are written
+/// on `weekday` and emailed to `email_to` if SMTP is set up.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DigestSettings {
+    pub scheduled: bool,
+    pub weekday: String,
+    pub directory: Option<String>,
+    pub email_to: Vec<String>,
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        Self {
+            scheduled: false,
+            weekday: "monday".to_string(),
+            directory: None,
+            email_to: Vec::new(),
+        }
+    }
+}
+
+/// Optional version history of the app data, kept as a git repository in
+/// the app data directory
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistorySettings {
+    pub enabled: bool,
+}
+
+/// Scheduled export of selected projects (and optionally learner reports)
+/// to a network folder, e.g. a mounted district share or a UNC path
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShareExportSettings {
+    pub enabled: bool,
+    pub directory: Option<String>,
+    pub interval_hours: u32,
+    pub project_ids: Vec<String>,
+    pub include_reports: bool,
+}
+
+impl Default for ShareExportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: None,
+            interval_hours: 24,
+            project_ids: Vec::new(),
+            include_reports: false,
+        }
+    }
+}
+
+/// Size cap of the generated image cache; the least recently used images
+/// are evicted past it
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImageCacheSettings {
+    pub max_size_mb: u64,
+}
+
+impl Default for ImageCacheSettings {
+    fn default() -> Self {
+        Self { max_size_mb: 512 }
+    }
+}
+
+/// Camera and scanner capture. Photos are taken with ffmpeg, which must be
+/// installed (or its path given); `webcam_device` picks a camera from
+/// `list_webcams` and `scanner_device` a scanner from `list_scanners`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptureSettings {
+    pub ffmpeg_path: Option<String>,
+    pub webcam_device: Option<String>,
+    pub scanner_device: Option<String>,
+    pub scan_resolution: u32,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            ffmpeg_path: None,
+            webcam_device: None,
+            scanner_device: None,
+            scan_resolution: 300,
+        }
+    }
+}
+
+/// Page layout for PDF export and print preview. `page_size` is "letter" or
+/// "a4" and `orientation` "portrait" or "landscape"; margins are in inches.
+/// The header, footer, and watermark are templates where `{learner}`,
+/// `{date}`, `{title}`, and `{artifactId}` are filled in; an empty template
+/// leaves that band blank. The watermark is set in light gray across the
+/// middle of each page, e.g. "Property of Smith Homeschool, not for resale".
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrintLayoutSettings {
+    pub page_size: String,
+    pub orientation: String,
+    pub margin_top: f32,
+    pub margin_bottom: f32,
+    pub margin_left: f32,
+    pub margin_right: f32,
+    pub header: String,
+    pub footer: String,
+    pub page_numbers: bool,
+    pub watermark: String,
+}
+
+impl Default for PrintLayoutSettings {
+    fn default() -> Self {
+        Self {
+            page_size: "letter".to_string(),
+            orientation: "portrait".to_string(),
+            margin_top: 0.75,
+            margin_bottom: 0.75,
+            margin_left: 0.75,
+            margin_right: 0.75,
+            header: "{title}".to_string(),
+            footer: "{learner}   {date}".to_string(),
+            page_numbers: true,
+            watermark: String::new(),
+        }
+    }
+}
+
+/// Proxy for outgoing HTTP requests. `mode` is "system" (the proxy from
+/// the environment or OS), "none", or "manual" with `url` (an http:// or
+/// https:// proxy) and `bypass`, a comma-separated list of hosts reached
+/// directly. Local addresses are always reached directly. With `username`
+/// set, the password is read from the keychain under `proxy.password`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProxySettings {
+    pub mode: String,
+    pub url: String,
+    pub bypass: String,
+    pub username: String,
+}
+
+impl Default for ProxySettings {
+    fn default() -> Self {
+        Self {
+            mode: "system".to_string(),
+            url: String::new(),
+            bypass: String::new(),
+            username: String::new(),
+        }
+    }
+}
+
+/// Language for backend-generated text such as reports and error
+/// messages; see the `locale` module
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocaleSettings {
+    pub language: String,
+}
+
+impl Default for LocaleSettings {
+    fn default() -> Self {
+        Self {
+            language: locale::DEFAULT_LANGUAGE.to_string(),
+        }
+    }
+}
+
+/// Plugins: `trusted_keys` are the minisign public keys (base64) a plugin
+/// manifest must be signed with to load; see the `plugins` module
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PluginSettings {
+    pub trusted_keys: Vec<String>,
+}
+
+/// Opt-in HTTP API on localhost for external tools; see the `rest_api`
+/// module. Requests need the token from `get_api_token`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8765,
+        }
+    }
+}
+
+/// What one MCP tool may do: `permission` is "off", "ask" (the teacher
+/// approves each call), or "allow", and `project_ids`, when not empty,
+/// limits the tool to those projects.
+///
+/// `get_generation_status` has no entry of its own: it's available when
+/// `enqueue_generation` isn't "off" and is never asked about, even when
+/// that's "ask". It only reports on generations the teacher already let an
+/// assistant queue, so asking again for each check would only add prompts.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct McpToolSettings {
+    pub permission: String,
+    pub project_ids: Vec<String>,
+}
+
+impl Default for McpToolSettings {
+    fn default() -> Self {
+        Self {
+            permission: "off".to_string(),
+            project_ids: Vec::new(),
+        }
+    }
+}
+
+/// Opt-in Model Context Protocol server on localhost for desktop AI
+/// assistants; see the `mcp_server` module. `tools` is keyed by tool name,
+/// and tools not listed are off.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct McpSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub tools: BTreeMap<String, McpToolSettings>,
+}
+
+impl Default for McpSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8766,
+            tools: BTreeMap::new(),
+        }
+    }
+}
+
+/// Application settings persisted to `settings/settings.json`
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub version: u32,
+    pub ollama: OllamaSettings,
+    pub export: ExportSettings,
+    pub backup: BackupSettings,
+    pub logging: LoggingSettings,
+    pub telemetry: TelemetrySettings,
+    pub background: BackgroundSettings,
+    pub email: EmailSettings,
+    pub digest: DigestSettings,
+    pub history: HistorySettings,
+    pub share_export: ShareExportSettings,
+    pub image_cache: ImageCacheSettings,
+    pub capture: CaptureSettings,
+    pub print_layout: PrintLayoutSettings,
+    pub proxy: ProxySettings,
+    pub locale: LocaleSettings,
+    pub plugins: PluginSettings,
+    pub api: ApiSettings,
+    pub mcp: McpSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            ollama: OllamaSettings::default(),
+            export: ExportSettings::default(),
+            backup: BackupSettings::default(),
+            logging: LoggingSettings::default(),
+            telemetry: TelemetrySettings::default(),
+            background: BackgroundSettings::default(),
+            email: EmailSettings::default(),
+            digest: DigestSettings::default(),
+            history: HistorySettings::default(),
+            share_export: ShareExportSettings::default(),
+            image_cache: ImageCacheSettings::default(),
+            capture: CaptureSettings::default(),
+            print_layout: PrintLayoutSettings::default(),
+            proxy: ProxySettings::default(),
+            locale: LocaleSettings::default(),
+            plugins: PluginSettings::default(),
+            api: ApiSettings::default(),
+            mcp: McpSettings::default(),
+        }
+    }
+}
+
+// Helper to get the settings file path
+fn get_settings_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(SETTINGS_DIR).join(SETTINGS_FILE))
+}
+
+// Helper to upgrade settings written by an older version of the app.
+// Missing fields are already filled with defaults by serde.
+fn upgrade_settings(mut settings: Settings) -> Settings {
+    settings.version = SETTINGS_VERSION;
+    settings
+}
+
+// Helper to recursively merge a JSON patch into a value
+fn merge_patch(target: &mut Value, patch: Value) {
+    match (target, patch) {
+        (Value::Object(target_obj), Value::Object(patch_obj)) => {
+            for (key, value) in patch_obj {
+                match target_obj.get_mut(&key) {
+                    Some(existing) if existing.is_object() && value.is_object() => {
+                        merge_patch(existing, value)
+                    }
+                    _ => {
+                        target_obj.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+/// Load settings from disk, falling back to defaults if missing. A file that
+/// can't be parsed is moved aside to `settings.json.corrupt-<time>` (so
+/// neither the next save nor a later corruption overwrites it) and defaults
+/// are used.
+pub(crate) async fn load_settings(app_handle: &tauri::AppHandle) -> Result<Settings, String> {
+    let settings_path = get_settings_path(app_handle)?;
+
+    if !settings_path.exists() {
+        return Ok(Settings::default());
+    }
+
+    let content = fs::read_to_string(&settings_path)
+        .await
+        .map_err(|e| format!("Failed to read settings: {}", e))?;
+    let settings: Settings = match serde_json::from_str(&content) {
+        Ok(settings) => settings,
+        Err(e) => {
+            let corrupt_path = settings_path.with_extension(format!(
+                "json.corrupt-{}",
+                chrono::Utc::now().format("%Y%m%d-%H%M%S%.3f")
+            ));
+            fs::rename(&settings_path, &corrupt_path)
+                .await
+                .map_err(|e| format!("Failed to move aside corrupt settings: {}", e))?;
+            tracing::error!(
+                error = %e,
+                path = %corrupt_path.display(),
+                "Settings file is corrupt; moved it aside and using defaults"
+            );
+            Settings::default()
+        }
+    };
+
+    Ok(upgrade_settings(settings))
+}
+
+/// Write settings to disk
+pub(crate) async fn write_settings(
+    app_handle: &tauri::AppHandle,
+    settings: &Settings,
+) -> Result<(), String> {
+    let settings_path = get_settings_path(app_handle)?;
+    if let Some(parent) = settings_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(&settings_path, content)
+        .await
+        .map_err(|e| format!("Failed to write settings: {}", e))
+}
+
+// ============================================
+// Settings Commands
+// ============================================
+
+/// Get the current settings
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_settings(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let settings = load_settings(&app_handle).await?;
+    serde_json::to_string(&settings).map_err(|e| format!("Failed to serialize settings: {}", e))
+}
+
+/// Update settings with a partial JSON patch (nested objects are merged).
+/// Returns the updated settings.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn update_settings(app_handle: tauri::AppHandle, patch: String) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let patch: Value =
+        serde_json::from_str(&patch).map_err(|e| format!("Invalid settings JSON: {}", e))?;
+
+    let current = load_settings(&app_handle).await?;
+    let mut merged = serde_json::to_value(&current)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    merge_patch(&mut merged, patch);
+
+    let mut settings: Settings =
+        serde_json::from_value(merged).map_err(|e| format!("Invalid settings: {}", e))?;
+    settings.version = SETTINGS_VERSION;
+    let proxy = http_client::proxy_config(&settings.proxy).await?;
+    locale::validate_language(&settings.locale.language)?;
+    plugins::validate_trusted_keys(&settings.plugins.trusted_keys)?;
+    mcp_server::validate_settings(&settings.mcp)?;
+
+    write_settings(&app_handle, &settings).await?;
+    http_client::apply(proxy);
+    if let Err(e) = locale::reload(&app_handle).await {
+        tracing::warn!(error = %e, "Failed to load locale strings");
+    }
+    rest_api::apply(&app_handle).await;
+    mcp_server::apply(&app_handle).await;
+
+    audit_log::record(&app_handle, "update_settings", "settings", &[]).await;
+
+    serde_json::to_string(&settings).map_err(|e| format!("Failed to serialize settings: {}", e))
+}
+
+/// Reset all settings to their defaults. Returns the default settings.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn reset_settings(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let settings = Settings::default();
+    write_settings(&app_handle, &settings).await?;
+    http_client::apply(http_client::proxy_config(&settings.proxy).await?);
+    if let Err(e) = locale::reload(&app_handle).await {
+        tracing::warn!(error = %e, "Failed to load locale strings");
+    }
+    rest_api::apply(&app_handle).await;
+    mcp_server::apply(&app_handle).await;
+
+    audit_log::record(&app_handle, "reset_settings", "settings", &[]).await;
+
+    serde_json::to_string(&settings).map_err(|e| format!("Failed to serialize settings: {}", e))
+}
@@ -0,0 +1,352 @@
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{
+    app_lock, audit_log, learner_storage, library_storage, project_stats, project_storage,
+    session_mode, settings_storage, task_manager,
+};
+
+const SHARE_EXPORT_DIR: &str = "share-export";
+const LAST_RUN_FILE: &str = "last-run.json";
+
+// Folder in the share for learner reports
+const REPORTS_DIR: &str = "Reports";
+
+// How often the scheduler checks whether an export is due; also how soon a
+// failed export (e.g. the share was offline) is retried
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Managed state keeping the scheduler and `run_share_export` from
+/// writing to the share at the same time
+#[derive(Default)]
+pub struct ShareExportState {
+    lock: tokio::sync::Mutex<()>,
+}
+
+// Helper to read a string field, or "" when missing
+fn text<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// Turn a title into a file name that works on network shares (no
+/// characters Windows forbids), falling back to `fallback`
+pub(crate) fn file_name(title: &str, fallback: &str) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim().trim_end_matches('.').trim();
+    if cleaned.is_empty() {
+        fallback.to_string()
+    } else {
+        cleaned.chars().take(80).collect()
+    }
+}
+
+// Helper to quote a CSV field when it needs it
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Helper to render rows as CSV text
+fn csv(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut out = headers.join(",");
+    out.push_str("\r\n");
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Write a file so readers of the share never see it half written: write
+/// a temporary file next to it, then rename it into place
+pub(crate) async fn write_file(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, contents)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    fs::rename(&temp_path, path)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// Helper to export one project: each artifact as a printable HTML file,
+// plus an artifacts.csv listing them. Returns the number of files written.
+async fn export_project(
+    app_handle: &tauri::AppHandle,
+    project_id: &str,
+    share_dir: &Path,
+) -> Result<usize, String> {
+    let project = project_storage::read_project(app_handle, project_id).await?;
+    let project_dir = share_dir.join(file_name(text(&project, "name"), project_id));
+    fs::create_dir_all(&project_dir)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", project_dir.display(), e))?;
+
+    let artifact_ids: Vec<String> = project
+        .get("artifactIds")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+    let mut written = 0;
+    let mut rows = Vec::new();
+    for artifact in library_storage::read_artifacts(app_handle, artifact_ids).await {
+        if artifact.is_null() {
+            continue;
+        }
+        let artifact_id = text(&artifact, "artifactId");
+        // The ID keeps artifacts with the same title apart
+        let short_id: String = artifact_id.chars().take(8).collect();
+        let title = file_name(text(&artifact, "title"), "Untitled");
+        let name = format!("{} ({}).html", title, short_id);
+        let html = text(&artifact, "htmlContent");
+        if !html.is_empty() {
+            write_file(&project_dir.join(&name), html.as_bytes()).await?;
+            written += 1;
+        }
+        let objectives: Vec<&str> = artifact
+            .get("objectiveTags")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+            .collect();
+        rows.push(vec![
+            text(&artifact, "title").to_string(),
+            text(&artifact, "type").to_string(),
+            text(&artifact, "grade").to_string(),
+            text(&artifact, "subject").to_string(),
+            objectives.join(" "),
+            text(&artifact, "createdAt").to_string(),
+            if html.is_empty() { String::new() } else { name },
+        ]);
+    }
+
+    let listing = csv(
+        &["Title", "Type", "Grade", "Subject", "Objectives", "Created", "File"],
+        &rows,
+    );
+    write_file(&project_dir.join("artifacts.csv"), listing.as_bytes()).await?;
+    Ok(written + 1)
+}
+
+// Helper to export a mastery report of every learner as CSV
+async fn export_reports(app_handle: &tauri::AppHandle, share_dir: &Path) -> Result<usize, String> {
+    let reports_dir = share_dir.join(REPORTS_DIR);
+    fs::create_dir_all(&reports_dir)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", reports_dir.display(), e))?;
+
+    let mut rows = Vec::new();
+    for profile in learner_storage::read_profiles(app_handle).await? {
+        let learner_id = text(&profile, "learnerId");
+        let mastery = project_stats::read_mastery(app_handle, learner_id).await?;
+        for (objective_id, record) in &mastery {
+            let score = record
+                .get("lastScore")
+                .and_then(|v| v.as_f64())
+                .map(|s| format!("{:.0}", s))
+                .unwrap_or_default();
+            rows.push(vec![
+                text(&profile, "displayName").to_string(),
+                objective_id.clone(),
+                text(record, "subject").to_string(),
+                text(record, "state").to_string(),
+                score,
+                text(record, "lastUpdated").to_string(),
+            ]);
+        }
+    }
+    let report = csv(
+        &["Learner", "Objective", "Subject", "State", "Last Score", "Updated"],
+        &rows,
+    );
+    write_file(&reports_dir.join("mastery.csv"), report.as_bytes()).await?;
+    Ok(1)
+}
+
+// Helper to get the file recording the last export
+fn get_last_run_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(SHARE_EXPORT_DIR).join(LAST_RUN_FILE))
+}
+
+// Helper to read the record of the last export, or null
+async fn read_last_run(app_handle: &tauri::AppHandle) -> Result<Value, String> {
+    let last_run_path = get_last_run_path(app_handle)?;
+    Ok(match fs::read_to_string(&last_run_path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or(Value::Null),
+        Err(_) => Value::Null,
+    })
+}
+
+/// Export everything selected in settings to the share, and record the
+/// outcome (kept on failure too, so the frontend can show it). Returns the
+/// number of files written.
+pub(crate) async fn run_export(
+    app_handle: &tauri::AppHandle,
+    task: &task_manager::TaskHandle,
+) -> Result<usize, String> {
+    let state = app_handle.state::<ShareExportState>();
+    let _guard = state.lock.lock().await;
+
+    let settings = settings_storage::load_settings(app_handle).await?.share_export;
+    let attempted_at = chrono::Utc::now().to_rfc3339();
+    let result = async {
+        let share_dir = settings
+            .directory
+            .as_deref()
+            .filter(|d| !d.trim().is_empty())
+            .map(PathBuf::from)
+            .ok_or("Choose a network folder for scheduled exports")?;
+        // A share that isn't mounted or reachable shows up as a missing folder
+        if !share_dir.is_dir() {
+            return Err(format!("Network folder isn't reachable: {}", share_dir.display()));
+        }
+
+        let steps = settings.project_ids.len() as u64 + u64::from(settings.include_reports);
+        let mut written = 0;
+        for (i, project_id) in settings.project_ids.iter().enumerate() {
+            task.check_cancelled()?;
+            task.progress(i as u64, Some(steps), Some("Exporting projects"));
+            written += export_project(app_handle, project_id, &share_dir).await?;
+        }
+        if settings.include_reports {
+            task.check_cancelled()?;
+            task.progress(steps - 1, Some(steps), Some("Exporting reports"));
+            written += export_reports(app_handle, &share_dir).await?;
+        }
+        Ok(written)
+    }
+    .await;
+
+    let mut record = read_last_run(app_handle).await?;
+    if !record.is_object() {
+        record = serde_json::json!({});
+    }
+    if let Some(obj) = record.as_object_mut() {
+        obj.insert("lastAttemptAt".to_string(), Value::String(attempted_at.clone()));
+        match &result {
+            Ok(files) => {
+                obj.insert("lastSuccessAt".to_string(), Value::String(attempted_at));
+                obj.insert("files".to_string(), Value::from(*files));
+                obj.insert("lastError".to_string(), Value::Null);
+            }
+            Err(e) => {
+                obj.insert("lastError".to_string(), Value::String(e.clone()));
+            }
+        }
+    }
+    let last_run_path = get_last_run_path(app_handle)?;
+    if let Some(parent) = last_run_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create share export directory: {}", e))?;
+    }
+    fs::write(&last_run_path, record.to_string())
+        .await
+        .map_err(|e| format!("Failed to record share export: {}", e))?;
+
+    result
+}
+
+// Helper to run the export when it is enabled and the interval has passed
+// since the last successful one
+async fn run_if_due(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let settings = settings_storage::load_settings(app_handle).await?.share_export;
+    if !settings.enabled {
+        return Ok(());
+    }
+    let interval = chrono::Duration::hours(i64::from(settings.interval_hours));
+    let last_success = read_last_run(app_handle)
+        .await?
+        .get("lastSuccessAt")
+        .and_then(|v| v.as_str())
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok());
+    if last_success.is_some_and(|t| chrono::Utc::now() - t.with_timezone(&chrono::Utc) < interval)
+    {
+        return Ok(());
+    }
+
+    let task = task_manager::start_task(app_handle, None, "export", "Scheduled share export")?;
+    let result = run_export(app_handle, &task).await;
+    task.finish(&result);
+    let files = result?;
+    tracing::info!(files, "Wrote scheduled share export");
+    Ok(())
+}
+
+/// Start the background task that writes scheduled exports to the network
+/// folder. Called from the app's setup.
+pub fn start_scheduler(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = run_if_due(&app_handle).await {
+                tracing::warn!(error = %e, "Failed to write scheduled share export");
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// ============================================
+// Share Export Commands
+// ============================================
+
+/// Get the outcome of the last export to the network folder, as
+/// `{lastAttemptAt, lastSuccessAt, files, lastError}`, or null if none has
+/// run
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_share_export_status(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    Ok(read_last_run(&app_handle).await?.to_string())
+}
+
+/// Export now to the network folder set in `shareExport` settings,
+/// whether or not the schedule is enabled. Each selected project gets a
+/// folder of printable HTML artifacts with an `artifacts.csv` listing;
+/// with `includeReports`, a `Reports/mastery.csv` covers every learner.
+/// Runs as a cancellable "export" task (ID `operation_id` when given).
+/// Returns the number of files written.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn run_share_export(
+    app_handle: tauri::AppHandle,
+    operation_id: Option<String>,
+) -> Result<usize, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let task = task_manager::start_task(&app_handle, operation_id, "export", "Share export")?;
+    let result = run_export(&app_handle, &task).await;
+    task.finish(&result);
+    let files = result?;
+
+    audit_log::record(&app_handle, "run_share_export", "export", &[]).await;
+
+    Ok(files)
+}
@@ -0,0 +1,322 @@
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::commands::{app_lock, artifact_analysis, library_storage, ollama, settings_storage};
+
+// The curriculum packs the frontend bundles, so objective descriptions are
+// available offline
+const CURRICULUM_PACKS: &[&[u8]] = &[
+    include_bytes!("../../../src/data/curriculum-packs/k3_math.json"),
+    include_bytes!("../../../src/data/curriculum-packs/k3_reading.json"),
+    include_bytes!("../../../src/data/curriculum-packs/k3_writing.json"),
+    include_bytes!("../../../src/data/curriculum-packs/k3_science.json"),
+    include_bytes!("../../../src/data/curriculum-packs/k3_social_studies.json"),
+];
+
+// Ollama model used for embeddings when it's installed
+const EMBEDDING_MODEL: &str = "nomic-embed-text";
+
+// Words too general to show an objective is being covered
+const STOP_WORDS: &[&str] = &[
+    "about", "and", "are", "can", "for", "from", "how", "into", "its", "like", "more", "not",
+    "one", "that", "the", "their", "them", "then", "these", "this", "through", "use", "using",
+    "what", "when", "with", "within", "you", "your",
+];
+
+// Endings stripped so word forms match ("counting" matches "count")
+const INFLECTIONS: &[&str] = &["ing", "ed", "es", "s"];
+
+// Vocabulary words an artifact needs to use for full vocabulary credit
+const VOCABULARY_TARGET: usize = 3;
+
+// Share of the keyword score from the objective's description; the rest
+// comes from its vocabulary
+const DESCRIPTION_WEIGHT: f64 = 0.6;
+
+// Cosine similarities mapped to 0 and 1; embedding models rarely score
+// unrelated text below the floor or matching text above the ceiling
+const SIMILARITY_FLOOR: f64 = 0.3;
+const SIMILARITY_CEILING: f64 = 0.7;
+
+// Combined scores at or above ALIGNED count as aligned; below MISALIGNED
+// are flagged
+const ALIGNED: f64 = 0.5;
+const MISALIGNED: f64 = 0.25;
+
+// Text beyond this many characters isn't embedded
+const MAX_EMBED_CHARS: usize = 8000;
+
+const MAX_SUGGESTIONS: usize = 3;
+
+/// A curriculum objective and the words that show it's being taught
+struct Objective {
+    id: String,
+    description: String,
+    subject: String,
+    grade: String,
+    keywords: BTreeSet<String>,
+    vocabulary: BTreeSet<String>,
+}
+
+// Helper to reduce a word to a rough stem
+fn stem(word: &str) -> String {
+    INFLECTIONS
+        .iter()
+        .find_map(|ending| word.strip_suffix(ending).filter(|stem| stem.len() >= 3))
+        .unwrap_or(word)
+        .to_string()
+}
+
+// Helper to split text into lowercase stemmed words, leaving out short and
+// stop words
+fn words(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphabetic())
+        .map(str::to_lowercase)
+        .filter(|w| w.len() >= 3 && !STOP_WORDS.contains(&w.as_str()))
+        .map(|w| stem(&w))
+        .collect()
+}
+
+// Helper to load every objective in the curriculum packs
+fn catalog() -> Vec<Objective> {
+    let mut objectives = Vec::new();
+    for pack in CURRICULUM_PACKS {
+        let Ok(pack) = serde_json::from_slice::<Value>(pack) else {
+            tracing::warn!("Skipping unreadable curriculum pack");
+            continue;
+        };
+        let subject = pack.get("subject").and_then(|v| v.as_str()).unwrap_or("");
+        let units = pack.get("units").and_then(|v| v.as_array());
+        for unit in units.into_iter().flatten() {
+            let grade = unit.get("grade").and_then(|v| v.as_str()).unwrap_or("");
+            let unit_objectives = unit.get("objectives").and_then(|v| v.as_array());
+            for objective in unit_objectives.into_iter().flatten() {
+                let (Some(id), Some(description)) = (
+                    objective.get("id").and_then(|v| v.as_str()),
+                    objective.get("text").and_then(|v| v.as_str()),
+                ) else {
+                    continue;
+                };
+                let vocabulary = objective
+                    .get("vocabulary")
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| v.as_str())
+                    .flat_map(words)
+                    .collect();
+                objectives.push(Objective {
+                    id: id.to_string(),
+                    description: description.to_string(),
+                    subject: subject.to_string(),
+                    grade: grade.to_string(),
+                    keywords: words(description),
+                    vocabulary,
+                });
+            }
+        }
+    }
+    objectives
+}
+
+// Helper to score how much of an objective's wording appears in the
+// artifact's words. Returns the 0-1 score with the matched and missing
+// description keywords.
+fn keyword_score(
+    objective: &Objective,
+    text_words: &BTreeSet<String>,
+) -> (f64, Vec<String>, Vec<String>) {
+    let (matched, missing): (Vec<String>, Vec<String>) = objective
+        .keywords
+        .iter()
+        .cloned()
+        .partition(|k| text_words.contains(k));
+    let description_score = if objective.keywords.is_empty() {
+        0.0
+    } else {
+        matched.len() as f64 / objective.keywords.len() as f64
+    };
+
+    let score = if objective.vocabulary.is_empty() {
+        description_score
+    } else {
+        let used = objective.vocabulary.intersection(text_words).count();
+        let target = VOCABULARY_TARGET.min(objective.vocabulary.len());
+        let vocabulary_score = (used as f64 / target as f64).min(1.0);
+        DESCRIPTION_WEIGHT * description_score + (1.0 - DESCRIPTION_WEIGHT) * vocabulary_score
+    };
+    (score, matched, missing)
+}
+
+// Helper to compute the cosine similarity of two vectors
+fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| *x as f64 * *y as f64).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+// Helper to embed the artifact text followed by each objective description
+// with the local Ollama server. Returns the similarity of each description
+// to the text, or why embeddings couldn't be used.
+async fn similarities(
+    app_handle: &tauri::AppHandle,
+    text: &str,
+    objectives: &[&Objective],
+) -> Result<Vec<f64>, String> {
+    let settings = settings_storage::load_settings(app_handle).await?;
+    let endpoint = &settings.ollama.endpoint;
+    let installed = ollama::list_models(endpoint).await?;
+    if !ollama::has_model(&installed, EMBEDDING_MODEL) {
+        return Err(format!("Embedding model {} isn't installed", EMBEDDING_MODEL));
+    }
+
+    let mut inputs: Vec<String> = vec![text.chars().take(MAX_EMBED_CHARS).collect()];
+    inputs.extend(objectives.iter().map(|o| o.description.clone()));
+    let embeddings = ollama::embed(endpoint, EMBEDDING_MODEL, &inputs).await?;
+    let (text_embedding, descriptions) = embeddings.split_first().ok_or("No embeddings")?;
+    Ok(descriptions.iter().map(|d| cosine(text_embedding, d)).collect())
+}
+
+// Helper to turn a combined score into an alignment status
+fn status(score: f64) -> &'static str {
+    if score >= ALIGNED {
+        "aligned"
+    } else if score < MISALIGNED {
+        "misaligned"
+    } else {
+        "uncertain"
+    }
+}
+
+// ============================================
+// Standards Alignment Commands
+// ============================================
+
+/// Check whether an artifact's content matches the objectives it's tagged
+/// with, to catch generations that drifted off-objective. Each tag found in
+/// the curriculum packs is scored by keyword overlap with its description
+/// and vocabulary and, when Ollama has the `nomic-embed-text` model, by
+/// embedding similarity; without it the check runs on keywords alone and
+/// `embeddingError` says why. Each objective gets a `status` of `aligned`,
+/// `uncertain`, or `misaligned`. When any is misaligned, `suggestions`
+/// lists curriculum objectives the content matches better.
+///
+/// Returns `{ artifactId, objectives, unknownTags, misaligned, suggestions,
+/// embeddingModel, embeddingError, checkedAt }`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn check_alignment(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+
+    let artifact = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    let tags: Vec<&str> = artifact
+        .get("objectiveTags")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .collect();
+    if tags.is_empty() {
+        return Err("This artifact has no objective tags to check".to_string());
+    }
+    let html = artifact
+        .get("htmlContent")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let title = artifact.get("title").and_then(|v| v.as_str()).unwrap_or("");
+    let text = format!("{}\n{}", title, artifact_analysis::text_blocks(html).join("\n"));
+    let text_words = words(&text);
+
+    let catalog = catalog();
+    let (tagged, unknown): (Vec<&str>, Vec<&str>) = tags
+        .iter()
+        .partition(|tag| catalog.iter().any(|o| o.id == **tag));
+    let tagged: Vec<&Objective> = tagged
+        .iter()
+        .filter_map(|tag| catalog.iter().find(|o| o.id == *tag))
+        .collect();
+
+    let (similarities, embedding_error) = if tagged.is_empty() {
+        (None, None)
+    } else {
+        match similarities(&app_handle, &text, &tagged).await {
+            Ok(similarities) => (Some(similarities), None),
+            Err(e) => {
+                tracing::info!(error = %e, "Checking alignment without embeddings");
+                (None, Some(e))
+            }
+        }
+    };
+
+    let round = |v: f64| (v * 100.0).round() / 100.0;
+    let mut misaligned = false;
+    let mut best_tagged = 0.0_f64;
+    let mut objectives = Vec::new();
+    for (index, objective) in tagged.iter().enumerate() {
+        let (keywords, matched, missing) = keyword_score(objective, &text_words);
+        let similarity = similarities.as_ref().map(|s| s[index]);
+        let score = match similarity {
+            Some(similarity) => {
+                let range = SIMILARITY_CEILING - SIMILARITY_FLOOR;
+                let scaled = ((similarity - SIMILARITY_FLOOR) / range).clamp(0.0, 1.0);
+                (keywords + scaled) / 2.0
+            }
+            None => keywords,
+        };
+        misaligned |= score < MISALIGNED;
+        best_tagged = best_tagged.max(keywords);
+        objectives.push(serde_json::json!({
+            "objectiveId": objective.id,
+            "description": objective.description,
+            "subject": objective.subject,
+            "grade": objective.grade,
+            "status": status(score),
+            "score": round(score),
+            "keywordScore": round(keywords),
+            "similarity": similarity.map(round),
+            "matchedKeywords": matched,
+            "missingKeywords": missing,
+        }));
+    }
+
+    // Objectives the content matches better than any it's tagged with
+    let mut suggestions = Vec::new();
+    if misaligned {
+        let mut candidates: Vec<(f64, &Objective)> = catalog
+            .iter()
+            .filter(|o| !tags.contains(&o.id.as_str()))
+            .map(|o| (keyword_score(o, &text_words).0, o))
+            .filter(|(score, _)| *score >= ALIGNED && *score > best_tagged)
+            .collect();
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        for (score, objective) in candidates.into_iter().take(MAX_SUGGESTIONS) {
+            suggestions.push(serde_json::json!({
+                "objectiveId": objective.id,
+                "description": objective.description,
+                "subject": objective.subject,
+                "grade": objective.grade,
+                "keywordScore": round(score),
+            }));
+        }
+    }
+
+    let response = serde_json::json!({
+        "artifactId": artifact_id,
+        "objectives": objectives,
+        "unknownTags": unknown,
+        "misaligned": misaligned,
+        "suggestions": suggestions,
+        "embeddingModel": similarities.as_ref().map(|_| EMBEDDING_MODEL),
+        "embeddingError": embedding_error,
+        "checkedAt": chrono::Utc::now().to_rfc3339(),
+    });
+    Ok(response.to_string())
+}
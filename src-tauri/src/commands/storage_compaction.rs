@@ -0,0 +1,209 @@
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::{app_lock, audit_log, index_cache, session_mode};
+
+// Leftover files with these suffixes are removed once they are old enough
+const TEMP_SUFFIXES: &[&str] = &[".tmp", ".partial"];
+// Younger temp files may still be in use by a write in progress
+const STALE_TEMP_AGE: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Default)]
+struct CompactionReport {
+    json_files_compacted: u64,
+    assets_recompressed: u64,
+    temp_files_removed: u64,
+    bytes_reclaimed: u64,
+    errors: Vec<String>,
+}
+
+// Helper to list every file under a directory
+async fn collect_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let Ok(mut entries) = fs::read_dir(&current).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    files
+}
+
+// Helper to rewrite a JSON file without whitespace. Returns bytes saved.
+async fn compact_json(path: &Path) -> Result<u64, String> {
+    let original = fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let Ok(value) = serde_json::from_slice::<serde_json::Value>(&original) else {
+        // Not valid JSON; leave it for the integrity checks to report
+        return Ok(0);
+    };
+    let compact = serde_json::to_vec(&value)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+
+    if compact.len() >= original.len() {
+        return Ok(0);
+    }
+    fs::write(path, &compact)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok((original.len() - compact.len()) as u64)
+}
+
+// Helper to re-encode a PNG at the highest compression level.
+// Returns None if the result isn't smaller than the original.
+fn recompress_png(original: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    let mut decoder = png::Decoder::new(Cursor::new(original));
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| format!("Invalid PNG: {}", e))?;
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let frame = reader
+        .next_frame(&mut pixels)
+        .map_err(|e| format!("Invalid PNG: {}", e))?;
+    pixels.truncate(frame.buffer_size());
+
+    let mut output = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut output, frame.width, frame.height);
+        encoder.set_color(frame.color_type);
+        encoder.set_depth(frame.bit_depth);
+        encoder.set_compression(png::Compression::Best);
+        encoder.set_adaptive_filter(png::AdaptiveFilterType::Adaptive);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+        writer
+            .write_image_data(&pixels)
+            .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    }
+
+    Ok((output.len() < original.len()).then_some(output))
+}
+
+// Helper to recompress an image asset in place. Returns bytes saved.
+async fn recompress_asset(path: &Path) -> Result<u64, String> {
+    let original = fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let original_len = original.len();
+
+    // Encoding is CPU-bound, keep it off the async runtime
+    let recompressed = tauri::async_runtime::spawn_blocking(move || recompress_png(&original))
+        .await
+        .map_err(|e| format!("Failed to recompress {}: {}", path.display(), e))?
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let Some(recompressed) = recompressed else {
+        return Ok(0);
+    };
+    fs::write(path, &recompressed)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok((original_len - recompressed.len()) as u64)
+}
+
+// Helper to remove a temp file if it is stale. Returns bytes freed.
+async fn remove_stale_temp(path: &Path) -> Result<Option<u64>, String> {
+    let metadata = fs::metadata(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let age = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .unwrap_or_default();
+    if age < STALE_TEMP_AGE {
+        return Ok(None);
+    }
+
+    fs::remove_file(path)
+        .await
+        .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    Ok(Some(metadata.len()))
+}
+
+// ============================================
+// Storage Compaction Commands
+// ============================================
+
+/// Compact app storage: rewrite JSON without pretty-printing, recompress PNG
+/// assets, and remove stale temp files. Returns what was done and the bytes
+/// reclaimed. Files that fail are listed under `errors` and left untouched.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn compact_storage(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    // Compact the indices as they'll stay, not as they were last written
+    index_cache::flush(&app_handle).await?;
+
+    let mut report = CompactionReport::default();
+
+    for path in collect_files(&app_data_dir).await {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if TEMP_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)) {
+            match remove_stale_temp(&path).await {
+                Ok(Some(bytes)) => {
+                    report.temp_files_removed += 1;
+                    report.bytes_reclaimed += bytes;
+                }
+                Ok(None) => {}
+                Err(e) => report.errors.push(e),
+            }
+        } else if name.ends_with(".json") {
+            match compact_json(&path).await {
+                Ok(0) => {}
+                Ok(saved) => {
+                    report.json_files_compacted += 1;
+                    report.bytes_reclaimed += saved;
+                }
+                Err(e) => report.errors.push(e),
+            }
+        } else if name.ends_with(".png") {
+            match recompress_asset(&path).await {
+                Ok(0) => {}
+                Ok(saved) => {
+                    report.assets_recompressed += 1;
+                    report.bytes_reclaimed += saved;
+                }
+                Err(e) => report.errors.push(e),
+            }
+        }
+    }
+
+    audit_log::record(&app_handle, "compact_storage", "storage", &[]).await;
+
+    let response = serde_json::json!({
+        "jsonFilesCompacted": report.json_files_compacted,
+        "assetsRecompressed": report.assets_recompressed,
+        "tempFilesRemoved": report.temp_files_removed,
+        "bytesReclaimed": report.bytes_reclaimed,
+        "errors": report.errors,
+    });
+    Ok(response.to_string())
+}
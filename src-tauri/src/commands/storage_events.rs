@@ -0,0 +1,68 @@
+use serde_json::Value;
+use tauri::Emitter;
+
+/// A change to stored data, emitted as a Tauri event so every window can
+/// refresh what it shows without re-fetching after each call it makes. The
+/// payload names the changed record, e.g. `{"artifactId": "..."}`.
+#[derive(Clone, Copy)]
+pub(crate) enum StorageEvent<'a> {
+    ArtifactSaved(&'a str),
+    ArtifactDeleted(&'a str),
+    ProjectUpdated(&'a str),
+    ProjectDeleted(&'a str),
+    /// A learner's profile, mastery, or quick check history changed
+    LearnerUpdated(&'a str),
+    LearnerDeleted(&'a str),
+    DesignPackUpdated(&'a str),
+    DesignPackDeleted(&'a str),
+    CollectionUpdated(&'a str),
+    CollectionDeleted(&'a str),
+}
+
+impl StorageEvent<'_> {
+    /// The Tauri event name
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            StorageEvent::ArtifactSaved(_) => "artifact:saved",
+            StorageEvent::ArtifactDeleted(_) => "artifact:deleted",
+            StorageEvent::ProjectUpdated(_) => "project:updated",
+            StorageEvent::ProjectDeleted(_) => "project:deleted",
+            StorageEvent::LearnerUpdated(_) => "learner:updated",
+            StorageEvent::LearnerDeleted(_) => "learner:deleted",
+            StorageEvent::DesignPackUpdated(_) => "design-pack:updated",
+            StorageEvent::DesignPackDeleted(_) => "design-pack:deleted",
+            StorageEvent::CollectionUpdated(_) => "collection:updated",
+            StorageEvent::CollectionDeleted(_) => "collection:deleted",
+        }
+    }
+
+    // Helper to build the event payload, keyed like the record's ID field
+    fn payload(self) -> Value {
+        let (key, id) = match self {
+            StorageEvent::ArtifactSaved(id) | StorageEvent::ArtifactDeleted(id) => {
+                ("artifactId", id)
+            }
+            StorageEvent::ProjectUpdated(id) | StorageEvent::ProjectDeleted(id) => {
+                ("projectId", id)
+            }
+            StorageEvent::LearnerUpdated(id) | StorageEvent::LearnerDeleted(id) => {
+                ("learnerId", id)
+            }
+            StorageEvent::DesignPackUpdated(id) | StorageEvent::DesignPackDeleted(id) => {
+                ("packId", id)
+            }
+            StorageEvent::CollectionUpdated(id) | StorageEvent::CollectionDeleted(id) => {
+                ("collectionId", id)
+            }
+        };
+        serde_json::json!({ key: id })
+    }
+}
+
+/// Tell every window that stored data changed. Failures are only logged; the
+/// change itself has already been saved.
+pub(crate) fn emit(app_handle: &tauri::AppHandle, event: StorageEvent) {
+    if let Err(e) = app_handle.emit(event.name(), event.payload()) {
+        tracing::warn!(event = event.name(), error = %e, "Failed to emit storage event");
+    }
+}
@@ -0,0 +1,163 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use tokio::fs;
+
+use crate::commands::storage_events::{self, StorageEvent};
+use crate::commands::{app_lock, audit_log, library_storage, session_mode};
+
+// Helper to read an artifact's objective tags
+fn objective_tags(artifact: &Value) -> Vec<String> {
+    artifact
+        .get("objectiveTags")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+// Helper to apply a tag mapping to every artifact that has one of the
+// affected tags. `map` returns the replacement tag, or `None` to drop it.
+// Artifact files are rewritten and the index is written once at the end.
+// Returns the IDs of the artifacts that changed.
+async fn retag(
+    app_handle: &tauri::AppHandle,
+    affected: &BTreeSet<String>,
+    map: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<String>, String> {
+    let artifacts_dir = library_storage::get_artifacts_dir(app_handle)?;
+    let mut entries = library_storage::read_index_entries(app_handle).await?;
+    let mut changed = Vec::new();
+
+    for entry in entries.iter_mut() {
+        if !objective_tags(entry).iter().any(|t| affected.contains(t)) {
+            continue;
+        }
+        let Some(artifact_id) = entry.get("artifactId").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let artifact_id = artifact_id.to_string();
+        let Ok(mut artifact) = library_storage::read_artifact(app_handle, &artifact_id).await
+        else {
+            tracing::warn!(artifact_id = %artifact_id, "Skipping unreadable artifact");
+            continue;
+        };
+
+        // Map tags, dropping duplicates created by a merge
+        let mut seen = BTreeSet::new();
+        let tags: Vec<String> = objective_tags(&artifact)
+            .iter()
+            .filter_map(|t| if affected.contains(t) { map(t) } else { Some(t.clone()) })
+            .filter(|t| seen.insert(t.clone()))
+            .collect();
+        if let Some(obj) = artifact.as_object_mut() {
+            obj.insert("objectiveTags".to_string(), serde_json::json!(tags));
+        }
+
+        let content = serde_json::to_string_pretty(&artifact)
+            .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+        fs::write(artifacts_dir.join(format!("{}.json", artifact_id)), content)
+            .await
+            .map_err(|e| format!("Failed to write artifact: {}", e))?;
+        *entry = library_storage::index_entry_for(&artifact);
+        changed.push(artifact_id);
+    }
+
+    if !changed.is_empty() {
+        library_storage::write_index_entries(app_handle, entries).await?;
+    }
+    for artifact_id in &changed {
+        storage_events::emit(app_handle, StorageEvent::ArtifactSaved(artifact_id));
+    }
+    Ok(changed)
+}
+
+// Helper to validate a tag name
+fn validate_tag(tag: &str) -> Result<(), String> {
+    if tag.trim().is_empty() {
+        return Err("Tag cannot be empty".to_string());
+    }
+    Ok(())
+}
+
+// ============================================
+// Tag Management Commands
+// ============================================
+
+/// List every objective tag in the library with how many artifacts use it,
+/// sorted case-insensitively so near-duplicates sit together
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_all_tags(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for entry in library_storage::read_index_entries(&app_handle).await? {
+        for tag in objective_tags(&entry) {
+            *counts.entry(tag).or_default() += 1;
+        }
+    }
+
+    let mut tags: Vec<(String, usize)> = counts.into_iter().collect();
+    tags.sort_by_key(|(tag, _)| tag.to_lowercase());
+    let tags: Vec<Value> = tags
+        .into_iter()
+        .map(|(tag, count)| serde_json::json!({ "tag": tag, "count": count }))
+        .collect();
+    serde_json::to_string(&tags).map_err(|e| format!("Failed to serialize tags: {}", e))
+}
+
+/// Rename a tag on every artifact. Returns the number of artifacts changed.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn rename_tag(
+    app_handle: tauri::AppHandle,
+    from: String,
+    to: String,
+) -> Result<usize, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    validate_tag(&to)?;
+
+    let affected = BTreeSet::from([from.clone()]);
+    let changed = retag(&app_handle, &affected, |_| Some(to.clone())).await?;
+
+    let ids: Vec<&str> = changed.iter().map(String::as_str).collect();
+    audit_log::record(&app_handle, "rename_tag", "artifact", &ids).await;
+
+    Ok(changed.len())
+}
+
+/// Merge several tags into one (e.g. "Fractions" and "fraction basics" into
+/// "fractions"). Returns the number of artifacts changed.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn merge_tags(
+    app_handle: tauri::AppHandle,
+    tags: Vec<String>,
+    into: String,
+) -> Result<usize, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+    validate_tag(&into)?;
+
+    let affected: BTreeSet<String> = tags.into_iter().collect();
+    let changed = retag(&app_handle, &affected, |_| Some(into.clone())).await?;
+
+    let ids: Vec<&str> = changed.iter().map(String::as_str).collect();
+    audit_log::record(&app_handle, "merge_tags", "artifact", &ids).await;
+
+    Ok(changed.len())
+}
+
+/// Remove a tag from every artifact. Returns the number of artifacts changed.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn delete_tag(app_handle: tauri::AppHandle, tag: String) -> Result<usize, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let affected = BTreeSet::from([tag]);
+    let changed = retag(&app_handle, &affected, |_| None).await?;
+
+    let ids: Vec<&str> = changed.iter().map(String::as_str).collect();
+    audit_log::record(&app_handle, "delete_tag", "artifact", &ids).await;
+
+    Ok(changed.len())
+}
@@ -0,0 +1,233 @@
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tokio_util::sync::CancellationToken;
+
+/// Event emitted whenever a background task starts, progresses, or ends
+pub(crate) const PROGRESS_EVENT: &str = "task://progress";
+
+// Finished tasks kept for `list_tasks`, oldest dropped first
+const MAX_FINISHED: usize = 50;
+
+/// Error returned by a task that stopped because it was cancelled
+pub(crate) const CANCELLED: &str = "Cancelled";
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum TaskState {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// What the frontend sees of a task, in `list_tasks` and progress events
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskInfo {
+    task_id: String,
+    kind: String,
+    label: String,
+    state: TaskState,
+    current: u64,
+    total: Option<u64>,
+    message: Option<String>,
+    error: Option<String>,
+    cancel_requested: bool,
+    started_at: String,
+    finished_at: Option<String>,
+}
+
+struct Task {
+    info: TaskInfo,
+    cancel: CancellationToken,
+}
+
+/// Managed state holding running and recently finished tasks
+#[derive(Default)]
+pub struct TaskManagerState {
+    tasks: Mutex<Vec<Task>>,
+}
+
+/// Handle a long operation holds to report progress and to stop when it is
+/// cancelled. Call `finish` with the operation's result. A clone can report
+/// from a blocking thread.
+#[derive(Clone)]
+pub(crate) struct TaskHandle {
+    app_handle: tauri::AppHandle,
+    task_id: String,
+    cancel: CancellationToken,
+}
+
+// Helper to update a task's info and emit it as a progress event
+fn update(app_handle: &tauri::AppHandle, task_id: &str, change: impl FnOnce(&mut TaskInfo)) {
+    let state = app_handle.state::<TaskManagerState>();
+    let mut tasks = state.tasks.lock().unwrap();
+    let Some(task) = tasks.iter_mut().find(|t| t.info.task_id == task_id) else {
+        return;
+    };
+    change(&mut task.info);
+    if let Err(e) = app_handle.emit(PROGRESS_EVENT, &task.info) {
+        tracing::warn!(error = %e, "Failed to emit task progress");
+    }
+}
+
+/// Register a long operation as a task. `operation_id` lets the frontend
+/// name the task up front so it can cancel a command it is still awaiting;
+/// a fresh ID is used when it's `None`. `kind` groups tasks for the
+/// frontend (e.g. "export", "batch_generation"); `label` describes this one.
+pub(crate) fn start_task(
+    app_handle: &tauri::AppHandle,
+    operation_id: Option<String>,
+    kind: &str,
+    label: &str,
+) -> Result<TaskHandle, String> {
+    let task_id = operation_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let cancel = CancellationToken::new();
+    let state = app_handle.state::<TaskManagerState>();
+    let mut tasks = state.tasks.lock().unwrap();
+    if tasks
+        .iter()
+        .any(|t| t.info.task_id == task_id && t.info.state == TaskState::Running)
+    {
+        return Err(format!("Operation already running: {}", task_id));
+    }
+    // A finished task with the same ID is replaced
+    tasks.retain(|t| t.info.task_id != task_id);
+
+    let info = TaskInfo {
+        task_id: task_id.clone(),
+        kind: kind.to_string(),
+        label: label.to_string(),
+        state: TaskState::Running,
+        current: 0,
+        total: None,
+        message: None,
+        error: None,
+        cancel_requested: false,
+        started_at: chrono::Utc::now().to_rfc3339(),
+        finished_at: None,
+    };
+    if let Err(e) = app_handle.emit(PROGRESS_EVENT, &info) {
+        tracing::warn!(error = %e, "Failed to emit task progress");
+    }
+    tasks.push(Task {
+        info,
+        cancel: cancel.clone(),
+    });
+    // Forget the oldest finished tasks
+    let finished = tasks.iter().filter(|t| t.info.state != TaskState::Running).count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED);
+    tasks.retain(|t| {
+        let drop = excess > 0 && t.info.state != TaskState::Running;
+        if drop {
+            excess -= 1;
+        }
+        !drop
+    });
+
+    Ok(TaskHandle {
+        app_handle: app_handle.clone(),
+        task_id,
+        cancel,
+    })
+}
+
+// Helper to cancel a running task. Returns false if it isn't running.
+fn cancel(app_handle: &tauri::AppHandle, task_id: &str) -> bool {
+    let token = {
+        let state = app_handle.state::<TaskManagerState>();
+        let tasks = state.tasks.lock().unwrap();
+        tasks
+            .iter()
+            .find(|t| t.info.task_id == task_id && t.info.state == TaskState::Running)
+            .map(|t| t.cancel.clone())
+    };
+    let Some(token) = token else {
+        return false;
+    };
+    token.cancel();
+    update(app_handle, task_id, |info| info.cancel_requested = true);
+    true
+}
+
+impl TaskHandle {
+    /// Report progress: `current` of `total` steps, with an optional message
+    pub(crate) fn progress(&self, current: u64, total: Option<u64>, message: Option<&str>) {
+        update(&self.app_handle, &self.task_id, |info| {
+            info.current = current;
+            info.total = total;
+            info.message = message.map(String::from);
+        });
+    }
+
+    /// Whether the task has been cancelled
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Fail with `CANCELLED` if the task has been cancelled; call between steps
+    pub(crate) fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            return Err(CANCELLED.to_string());
+        }
+        Ok(())
+    }
+
+    /// Run a step that can't check in between (an Ollama request, a large
+    /// write), dropping it with `CANCELLED` as soon as the task is cancelled
+    pub(crate) async fn cancellable<T>(
+        &self,
+        step: impl Future<Output = Result<T, String>>,
+    ) -> Result<T, String> {
+        self.cancel
+            .run_until_cancelled(step)
+            .await
+            .unwrap_or_else(|| Err(CANCELLED.to_string()))
+    }
+
+    /// Mark the task finished with the operation's result
+    pub(crate) fn finish<T>(self, result: &Result<T, String>) {
+        let cancelled = self.is_cancelled();
+        update(&self.app_handle, &self.task_id, |info| {
+            info.state = match result {
+                Ok(_) => TaskState::Completed,
+                Err(_) if cancelled => TaskState::Cancelled,
+                Err(_) => TaskState::Failed,
+            };
+            info.error = result.as_ref().err().cloned();
+            info.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        });
+    }
+}
+
+// ============================================
+// Task Manager Commands
+// ============================================
+
+/// List running and recently finished background tasks, oldest first
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_tasks(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let state = app_handle.state::<TaskManagerState>();
+    let tasks: Vec<TaskInfo> = state
+        .tasks
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|t| t.info.clone())
+        .collect();
+    serde_json::to_string(&tasks).map_err(|e| format!("Failed to serialize tasks: {}", e))
+}
+
+/// Ask a running task to stop, by its ID or the `operationId` its command
+/// was started with (generation, export, backup, ...). It stops at its next
+/// checkpoint, or right away while waiting on the local model, and ends in
+/// the `cancelled` state with its command failing with "Cancelled". Returns
+/// false if the task isn't running.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn cancel_task(app_handle: tauri::AppHandle, task_id: String) -> Result<bool, String> {
+    Ok(cancel(&app_handle, &task_id))
+}
@@ -0,0 +1,221 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{Listener, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::commands::{settings_storage, task_manager};
+
+const TRAY_ID: &str = "main";
+const MAIN_WINDOW: &str = "main";
+const DEFAULT_TOOLTIP: &str = "TA - Teacher's Assistant";
+
+// Menu item IDs
+const SHOW_ITEM: &str = "show";
+const QUIT_ITEM: &str = "quit";
+
+// Task kinds whose completion means a worksheet is ready
+const GENERATION_KINDS: &[&str] = &["generation", "batch_generation"];
+
+/// The fields of a task progress event the tray uses
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskUpdate {
+    task_id: String,
+    kind: String,
+    label: String,
+    state: String,
+    current: u64,
+    total: Option<u64>,
+}
+
+/// Managed state behind the tray tooltip
+#[derive(Default)]
+pub struct TrayState {
+    // Status reported by the frontend for work it runs itself
+    status: Mutex<Option<String>>,
+    // One line per running background task, by task ID
+    tasks: Mutex<BTreeMap<String, String>>,
+}
+
+/// Bring the main window back from the tray (or from behind other windows)
+pub(crate) fn show_main_window(app_handle: &tauri::AppHandle) {
+    let Some(window) = app_handle.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    let shown = window
+        .show()
+        .and_then(|()| window.unminimize())
+        .and_then(|()| window.set_focus());
+    if let Err(e) = shown {
+        tracing::warn!(error = %e, "Failed to show main window");
+    }
+}
+
+// Helper to check whether the teacher is looking at the app
+fn main_window_focused(app_handle: &tauri::AppHandle) -> bool {
+    app_handle.get_webview_window(MAIN_WINDOW).is_some_and(|window| {
+        window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false)
+    })
+}
+
+// Helper to rebuild the tooltip from the reported status and running tasks
+fn refresh_tooltip(app_handle: &tauri::AppHandle) {
+    let Some(tray) = app_handle.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let state = app_handle.state::<TrayState>();
+    let mut lines: Vec<String> = state.status.lock().unwrap().iter().cloned().collect();
+    lines.extend(state.tasks.lock().unwrap().values().cloned());
+    let tooltip = if lines.is_empty() {
+        DEFAULT_TOOLTIP.to_string()
+    } else {
+        format!("{}\n{}", DEFAULT_TOOLTIP, lines.join("\n"))
+    };
+    if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+        tracing::warn!(error = %e, "Failed to update tray tooltip");
+    }
+}
+
+/// Show a desktop notification unless the main window has focus, and only
+/// if the teacher hasn't turned them off in settings
+pub(crate) async fn notify_in_background(app_handle: &tauri::AppHandle, title: &str, body: &str) {
+    if main_window_focused(app_handle) {
+        return;
+    }
+    match settings_storage::load_settings(app_handle).await {
+        Ok(settings) if !settings.background.notify_when_ready => return,
+        Ok(_) => {}
+        Err(e) => tracing::warn!(error = %e, "Failed to load settings"),
+    }
+    if let Err(e) = app_handle.notification().builder().title(title).body(body).show() {
+        tracing::warn!(error = %e, "Failed to show notification");
+    }
+}
+
+// Helper to track a task progress event in the tooltip, and to notify when
+// a generation finishes
+fn on_task_update(app_handle: &tauri::AppHandle, update: TaskUpdate) {
+    {
+        let state = app_handle.state::<TrayState>();
+        let mut tasks = state.tasks.lock().unwrap();
+        if update.state == "running" {
+            let line = match update.total {
+                Some(total) => format!("{} ({}/{})", update.label, update.current, total),
+                None => update.label.clone(),
+            };
+            tasks.insert(update.task_id.clone(), line);
+        } else {
+            tasks.remove(&update.task_id);
+        }
+    }
+    refresh_tooltip(app_handle);
+
+    if update.state == "completed" && GENERATION_KINDS.contains(&update.kind.as_str()) {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            notify_in_background(&app_handle, "Worksheet ready", &update.label).await;
+        });
+    }
+}
+
+/// Create the tray icon. Its menu reopens or quits the app, and its tooltip
+/// lists the background tasks still running. Called from the app's setup.
+pub fn init(app_handle: &tauri::AppHandle) -> tauri::Result<()> {
+    let show = MenuItem::with_id(
+        app_handle,
+        SHOW_ITEM,
+        "Show Teacher's Assistant",
+        true,
+        None::<&str>,
+    )?;
+    let quit = MenuItem::with_id(app_handle, QUIT_ITEM, "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(app_handle, &[&show, &quit])?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(DEFAULT_TOOLTIP)
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app_handle, event| match event.id.as_ref() {
+            SHOW_ITEM => show_main_window(app_handle),
+            QUIT_ITEM => app_handle.exit(0),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app_handle.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app_handle)?;
+
+    let listener_handle = app_handle.clone();
+    app_handle.listen(task_manager::PROGRESS_EVENT, move |event| {
+        match serde_json::from_str::<TaskUpdate>(event.payload()) {
+            Ok(update) => on_task_update(&listener_handle, update),
+            Err(e) => tracing::warn!(error = %e, "Unreadable task progress event"),
+        }
+    });
+    Ok(())
+}
+
+/// Hide the main window to the tray instead of closing it when the teacher
+/// has chosen to keep the app running, so queued work carries on. Off by
+/// default, and the window closes when settings can't be read.
+pub fn handle_window_event(window: &tauri::Window, event: &tauri::WindowEvent) {
+    let tauri::WindowEvent::CloseRequested { api, .. } = event else {
+        return;
+    };
+    if window.label() != MAIN_WINDOW {
+        return;
+    }
+    let app_handle = window.app_handle();
+    let keep_running = tauri::async_runtime::block_on(settings_storage::load_settings(app_handle))
+        .map(|settings| settings.background.keep_running_in_tray)
+        .unwrap_or(false);
+    if !keep_running {
+        return;
+    }
+    api.prevent_close();
+    if let Err(e) = window.hide() {
+        tracing::warn!(error = %e, "Failed to hide main window");
+    }
+}
+
+// ============================================
+// Tray Commands
+// ============================================
+
+/// Show a line of progress in the tray tooltip for work the frontend runs
+/// itself (e.g. "Generating worksheet 2 of 5"), or clear it with `None`
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn set_tray_status(
+    app_handle: tauri::AppHandle,
+    status: Option<String>,
+) -> Result<(), String> {
+    *app_handle.state::<TrayState>().status.lock().unwrap() = status;
+    refresh_tooltip(&app_handle);
+    Ok(())
+}
+
+/// Notify the teacher that a worksheet finished generating, unless the app
+/// is in front of them or notifications are turned off in settings
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn notify_worksheet_ready(
+    app_handle: tauri::AppHandle,
+    title: String,
+) -> Result<(), String> {
+    notify_in_background(&app_handle, "Worksheet ready", &title).await;
+    Ok(())
+}
@@ -0,0 +1,313 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokio::fs;
+use tracing::span;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::commands::{app_lock, http_client, session_mode, settings_storage};
+
+const USAGE_DIR: &str = "usage";
+const USAGE_FILE: &str = "usage-stats.json";
+
+// How often in-memory counts are merged into the stats file
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+const REPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Prefix of the span targets created by `#[tracing::instrument]` on commands
+const COMMAND_TARGET_PREFIX: &str = concat!(env!("CARGO_CRATE_NAME"), "::commands::");
+
+/// Invocation count and total time for one command or event
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct UsageCounter {
+    count: u64,
+    total_duration_ms: u64,
+}
+
+impl UsageCounter {
+    fn add(&mut self, count: u64, duration_ms: u64) {
+        self.count += count;
+        self.total_duration_ms += duration_ms;
+    }
+}
+
+/// Aggregated usage counts. Holds names and numbers only, never arguments
+/// or IDs.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct UsageStats {
+    since: Option<String>,
+    last_reported_at: Option<String>,
+    commands: BTreeMap<String, UsageCounter>,
+    events: BTreeMap<String, UsageCounter>,
+}
+
+impl UsageStats {
+    fn is_empty(&self) -> bool {
+        self.commands.is_empty() && self.events.is_empty()
+    }
+
+    fn merge(&mut self, other: UsageStats) {
+        for (name, counter) in other.commands {
+            self.commands
+                .entry(name)
+                .or_default()
+                .add(counter.count, counter.total_duration_ms);
+        }
+        for (name, counter) in other.events {
+            self.events
+                .entry(name)
+                .or_default()
+                .add(counter.count, counter.total_duration_ms);
+        }
+    }
+}
+
+/// Managed state holding usage counted since the last flush
+pub struct UsageState {
+    pending: Arc<Mutex<UsageStats>>,
+    // Serializes read-modify-write of the stats file
+    flush_lock: tokio::sync::Mutex<()>,
+}
+
+// Start time stored in a command span's extensions
+struct SpanStart(Instant);
+
+/// Tracing layer that counts command spans and their durations
+pub struct UsageLayer {
+    pending: Arc<Mutex<UsageStats>>,
+}
+
+impl<S> Layer<S> for UsageLayer
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanStart(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(start) = extensions.get::<SpanStart>() else {
+            return;
+        };
+        let duration_ms = start.0.elapsed().as_millis() as u64;
+        self.pending
+            .lock()
+            .unwrap()
+            .commands
+            .entry(span.name().to_string())
+            .or_default()
+            .add(1, duration_ms);
+    }
+}
+
+/// Create the usage layer (to install in the subscriber) and the state that
+/// reads its counts. The layer only sees command spans.
+pub fn layer<S>() -> (impl Layer<S>, UsageState)
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    let pending = Arc::new(Mutex::new(UsageStats::default()));
+    let layer = UsageLayer {
+        pending: pending.clone(),
+    }
+    .with_filter(tracing_subscriber::filter::filter_fn(|metadata| {
+        metadata.is_span() && metadata.target().starts_with(COMMAND_TARGET_PREFIX)
+    }));
+    let state = UsageState {
+        pending,
+        flush_lock: tokio::sync::Mutex::new(()),
+    };
+    (layer, state)
+}
+
+/// Periodically write in-memory counts to disk. Called once during app setup.
+pub fn start_flush_task(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            if let Err(e) = flush(&app_handle).await {
+                tracing::warn!(error = %e, "Failed to save usage stats");
+            }
+        }
+    });
+}
+
+// Helper to get the usage stats file path
+fn get_usage_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(USAGE_DIR).join(USAGE_FILE))
+}
+
+// Helper to read the stats file, treating a missing or invalid file as empty
+async fn read_stats(usage_path: &Path) -> UsageStats {
+    match fs::read_to_string(usage_path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => UsageStats::default(),
+    }
+}
+
+// Helper to write the stats file
+async fn write_stats(usage_path: &Path, stats: &UsageStats) -> Result<(), String> {
+    if let Some(parent) = usage_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create usage directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(stats)
+        .map_err(|e| format!("Failed to serialize usage stats: {}", e))?;
+    fs::write(usage_path, content)
+        .await
+        .map_err(|e| format!("Failed to write usage stats: {}", e))
+}
+
+// Helper to merge in-memory counts into the stats file and return the totals
+async fn flush(app_handle: &tauri::AppHandle) -> Result<UsageStats, String> {
+    let state = app_handle.state::<UsageState>();
+    let _guard = state.flush_lock.lock().await;
+
+    let usage_path = get_usage_path(app_handle)?;
+    let mut stats = read_stats(&usage_path).await;
+    let pending = std::mem::take(&mut *state.pending.lock().unwrap());
+
+    if pending.is_empty() {
+        return Ok(stats);
+    }
+
+    if stats.since.is_none() {
+        stats.since = Some(chrono::Utc::now().to_rfc3339());
+    }
+    stats.merge(pending.clone());
+
+    if let Err(e) = write_stats(&usage_path, &stats).await {
+        // Put the counts back so they're retried on the next flush
+        state.pending.lock().unwrap().merge(pending);
+        return Err(e);
+    }
+    Ok(stats)
+}
+
+// Helper to validate an event name (a short identifier, never free text)
+fn validate_event_name(event: &str) -> Result<(), String> {
+    let valid = !event.is_empty()
+        && event.len() <= 64
+        && event
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid usage event name: {}", event))
+    }
+}
+
+// ============================================
+// Usage Stats Commands
+// ============================================
+
+/// Get locally collected usage stats and whether sharing is turned on
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn get_usage_stats(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let stats = flush(&app_handle).await?;
+    let settings = settings_storage::load_settings(&app_handle).await?;
+
+    let response = serde_json::json!({
+        "sharingEnabled": settings.telemetry.share_usage_stats,
+        "stats": stats,
+    });
+    Ok(response.to_string())
+}
+
+/// Count a frontend feature event (e.g. "generation"), with an optional duration
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn record_usage_event(
+    app_handle: tauri::AppHandle,
+    event: String,
+    duration_ms: Option<u64>,
+) -> Result<(), String> {
+    validate_event_name(&event)?;
+
+    let state = app_handle.state::<UsageState>();
+    state
+        .pending
+        .lock()
+        .unwrap()
+        .events
+        .entry(event)
+        .or_default()
+        .add(1, duration_ms.unwrap_or(0));
+    Ok(())
+}
+
+/// Send aggregated, anonymized usage counts to the configured endpoint.
+/// Fails unless the user has opted in to sharing.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn send_usage_report(app_handle: tauri::AppHandle) -> Result<(), String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let settings = settings_storage::load_settings(&app_handle).await?;
+    if !settings.telemetry.share_usage_stats {
+        return Err("Usage sharing is turned off".to_string());
+    }
+    let endpoint = settings
+        .telemetry
+        .report_endpoint
+        .ok_or("No usage report endpoint configured")?;
+
+    let stats = flush(&app_handle).await?;
+
+    // No install ID or user data: app version, platform, and counts only
+    let report = serde_json::json!({
+        "appVersion": app_handle.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "since": stats.since,
+        "commands": stats.commands,
+        "events": stats.events,
+    });
+
+    let client = http_client::builder()
+        .timeout(REPORT_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .post(&endpoint)
+        .json(&report)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send usage report: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Usage report rejected with HTTP {}",
+            response.status().as_u16()
+        ));
+    }
+
+    let state = app_handle.state::<UsageState>();
+    let _guard = state.flush_lock.lock().await;
+    let usage_path = get_usage_path(&app_handle)?;
+    let mut stats = read_stats(&usage_path).await;
+    stats.last_reported_at = Some(chrono::Utc::now().to_rfc3339());
+    write_stats(&usage_path, &stats).await
+}
@@ -0,0 +1,263 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::commands::{
+    app_lock, artifact_analysis, audit_log, design_pack_storage, library_storage,
+    project_storage, session_mode, worksheet_assembly,
+};
+
+// Sight words early readers already know; never vocabulary candidates
+const COMMON_WORDS: &str = include_str!("../../word-lists/common-words.txt");
+
+// Endings stripped when checking whether a word is a form of a common word
+const INFLECTIONS: &[&str] = &["ing", "ed", "es", "s", "er", "ly"];
+
+const DEFAULT_MAX_WORDS: usize = 15;
+const DEFAULT_MIN_LENGTH: usize = 4;
+
+/// A candidate vocabulary word and how often the artifact uses it
+struct Candidate {
+    word: String,
+    count: usize,
+    syllables: usize,
+}
+
+// Helper to load the common word list (comment lines start with `#`)
+fn common_words() -> BTreeSet<&'static str> {
+    COMMON_WORDS
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .flat_map(str::split_whitespace)
+        .collect()
+}
+
+// Helper to check whether a word, or its stem, is a common word
+fn is_common(word: &str, common: &BTreeSet<&str>) -> bool {
+    common.contains(word)
+        || INFLECTIONS.iter().any(|ending| {
+            word.strip_suffix(ending)
+                .is_some_and(|stem| common.contains(stem) || common.contains(&*format!("{stem}e")))
+        })
+}
+
+/// Estimate a word's syllables by counting vowel groups
+pub(crate) fn syllable_count(word: &str) -> usize {
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    // A trailing silent "e" ("plane"), but not "-le" ("table")
+    if word.ends_with('e') && !word.ends_with("le") && count > 1 {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+// Helper to rank the artifact's uncommon words: most used first, then
+// longest (harder) first, then alphabetically
+fn candidates(html: &str, min_length: usize, max_words: usize) -> Vec<Candidate> {
+    let common = common_words();
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for run in artifact_analysis::text_runs(html) {
+        for word in run.split(|c: char| !c.is_alphabetic() && c != '\'') {
+            let word = word.trim_matches('\'').to_lowercase();
+            if word.chars().count() < min_length || word.contains('\'') {
+                continue;
+            }
+            if !is_common(&word, &common) {
+                *counts.entry(word).or_default() += 1;
+            }
+        }
+    }
+
+    let mut ranked: Vec<Candidate> = counts
+        .into_iter()
+        .map(|(word, count)| Candidate {
+            syllables: syllable_count(&word),
+            word,
+            count,
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then(b.word.len().cmp(&a.word.len()))
+            .then(a.word.cmp(&b.word))
+    });
+    ranked.truncate(max_words);
+    ranked
+}
+
+// Helper to render a sheet of fold-over flashcards: the word on the front,
+// a space for its meaning on the back
+fn render_flashcards(words: &[Candidate]) -> String {
+    let cards: String = words
+        .iter()
+        .map(|c| {
+            format!(
+                "<div class=\"flashcard\"><p class=\"front\">{}</p>\
+                 <p class=\"back\">Meaning:</p><div class=\"answer-line\"></div></div>\n",
+                worksheet_assembly::escape_html(&c.word)
+            )
+        })
+        .collect();
+    format!(
+        "<style>\n\
+         .flashcards {{ display: grid; grid-template-columns: 1fr 1fr; gap: 0.25in; }}\n\
+         .flashcard {{ border: 2px dashed; padding: 0.2in; break-inside: avoid; }}\n\
+         .flashcard .front {{ font-size: 1.5em; font-weight: bold; text-align: center; }}\n\
+         </style>\n<div class=\"flashcards\">\n{}</div>\n",
+        cards
+    )
+}
+
+// Helper to render a spelling list: each word with lines to practice it
+fn render_spelling_list(words: &[Candidate]) -> String {
+    let items: String = words
+        .iter()
+        .map(|c| {
+            format!(
+                "<li class=\"question\" data-question-type=\"fill_blank\">\
+                 <p class=\"stem\">{}</p><div class=\"answer-line\"></div></li>",
+                worksheet_assembly::escape_html(&c.word)
+            )
+        })
+        .collect();
+    format!("<ol class=\"questions\">{}</ol>\n", items)
+}
+
+// ============================================
+// Vocabulary Commands
+// ============================================
+
+/// Pull candidate vocabulary words from an artifact's text. Words on the
+/// common sight-word list (and their plain inflections) are skipped; the
+/// rest are ranked by how often they appear.
+///
+/// `options` may set `maxWords` (default 15), `minLength` (default 4), and
+/// `emit` (`"flashcards"` or `"spelling"`) to save a companion artifact in
+/// the source's design pack and project. Returns `{ artifactId, words,
+/// companionArtifactId }`, each word as `{ word, count, syllables }`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn extract_vocabulary(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    options: Option<String>,
+) -> Result<String, String> {
+    let options: Value = match options {
+        Some(options) => serde_json::from_str(&options)
+            .map_err(|e| format!("Invalid options JSON: {}", e))?,
+        None => Value::Object(serde_json::Map::new()),
+    };
+    let max_words = options
+        .get("maxWords")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_MAX_WORDS, |n| n as usize);
+    let min_length = options
+        .get("minLength")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_MIN_LENGTH, |n| n as usize);
+    let emit = options.get("emit").and_then(|v| v.as_str());
+    if emit.is_some_and(|e| e != "flashcards" && e != "spelling") {
+        return Err("emit must be \"flashcards\" or \"spelling\"".to_string());
+    }
+
+    let artifact = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    let html = artifact
+        .get("htmlContent")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let words = candidates(html, min_length, max_words);
+
+    let mut companion_id = None;
+    if let Some(emit) = emit {
+        app_lock::ensure_unlocked(&app_handle)?;
+        session_mode::ensure_teacher_mode(&app_handle)?;
+        if words.is_empty() {
+            return Err("No vocabulary words found in this artifact".to_string());
+        }
+
+        let source_title = artifact
+            .get("title")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Untitled");
+        let (title, body) = match emit {
+            "flashcards" => (
+                format!("{} - Vocabulary Flashcards", source_title),
+                render_flashcards(&words),
+            ),
+            _ => (
+                format!("{} - Spelling List", source_title),
+                render_spelling_list(&words),
+            ),
+        };
+        // A pack deleted since the source was made falls back to plain styling
+        let pack_id = artifact.get("designPackId").and_then(|v| v.as_str());
+        let pack = match pack_id {
+            Some(pack_id) => design_pack_storage::find_pack(&app_handle, pack_id).await.ok(),
+            None => None,
+        };
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let word_list: Vec<&str> = words.iter().map(|c| c.word.as_str()).collect();
+        let companion = serde_json::json!({
+            "artifactId": id,
+            "projectId": artifact.get("projectId"),
+            "learnerId": artifact.get("learnerId"),
+            "jobId": Value::Null,
+            "type": "student_page",
+            "title": title,
+            "htmlContent": worksheet_assembly::render_document(&title, &body, pack.as_ref()),
+            "grade": artifact.get("grade"),
+            "subject": artifact.get("subject"),
+            "objectiveTags": artifact.get("objectiveTags"),
+            "designPackId": pack_id,
+            "companionOf": artifact_id,
+            "companionKind": emit,
+            "vocabulary": word_list,
+            "source": "vocabulary",
+            "createdAt": chrono::Utc::now().to_rfc3339(),
+        });
+        let content = serde_json::to_string_pretty(&companion)
+            .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+        library_storage::write_artifact(&app_handle, &companion, &content).await?;
+
+        if let Some(project_id) = artifact.get("projectId").and_then(|v| v.as_str()) {
+            if let Err(e) = project_storage::link_artifact(&app_handle, project_id, &id).await {
+                tracing::warn!(error = %e, "Failed to add vocabulary artifact to project");
+            }
+        }
+
+        audit_log::record(
+            &app_handle,
+            "extract_vocabulary",
+            "artifact",
+            &[&id, &artifact_id],
+        )
+        .await;
+        companion_id = Some(id);
+    }
+
+    let words: Vec<Value> = words
+        .iter()
+        .map(|c| {
+            serde_json::json!({
+                "word": c.word,
+                "count": c.count,
+                "syllables": c.syllables,
+            })
+        })
+        .collect();
+    let response = serde_json::json!({
+        "artifactId": artifact_id,
+        "words": words,
+        "companionArtifactId": companion_id,
+    });
+    Ok(response.to_string())
+}
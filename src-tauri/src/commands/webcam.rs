@@ -0,0 +1,219 @@
+use serde_json::Value;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::commands::{app_lock, audit_log, image_library, session_mode, settings_storage};
+
+// Give up on a camera that doesn't deliver a frame in time
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(20);
+
+// Frames from the first moments are often dark while the camera adjusts
+// its exposure; this much video is skipped before the photo is taken
+const WARM_UP_SECONDS: &str = "1";
+
+// Tag added to every captured photo
+const CAPTURE_TAG: &str = "webcam";
+
+/// A camera as `list_webcams` reports it
+struct Webcam {
+    id: String,
+    name: String,
+}
+
+// Helper to get the ffmpeg program from settings, or "ffmpeg" on the PATH
+async fn ffmpeg_program(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let settings = settings_storage::load_settings(app_handle).await?;
+    Ok(settings
+        .capture
+        .ffmpeg_path
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "ffmpeg".to_string()))
+}
+
+// Helper to run ffmpeg, returning its stdout and stderr. Fails with a hint
+// when ffmpeg isn't installed.
+async fn run_ffmpeg(program: &str, args: &[&str]) -> Result<(Vec<u8>, String), String> {
+    let child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                "The camera needs ffmpeg. Install it, or set its path in settings.".to_string()
+            }
+            _ => format!("Failed to start ffmpeg: {}", e),
+        })?;
+    let output = tokio::time::timeout(CAPTURE_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| "The camera didn't respond".to_string())?
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    Ok((output.stdout, stderr))
+}
+
+// Helper to parse cameras from ffmpeg's device listing on Windows
+// (`"Name" (video)` lines) or macOS (`[0] Name` lines under the video
+// heading)
+fn parse_device_listing(listing: &str) -> Vec<Webcam> {
+    let mut cameras = Vec::new();
+    let mut in_video = false;
+    for line in listing.lines() {
+        // Drop the "[dshow @ 0x...]" or "[AVFoundation indev @ 0x...]" prefix
+        let text = line.split_once("] ").map_or(line, |(_, rest)| rest).trim();
+        if cfg!(target_os = "windows") {
+            if let Some(name) = text.strip_suffix(" (video)") {
+                let name = name.trim_matches('"').to_string();
+                cameras.push(Webcam {
+                    id: name.clone(),
+                    name,
+                });
+            }
+        } else if text.contains("video devices:") {
+            in_video = true;
+        } else if text.contains("audio devices:") {
+            in_video = false;
+        } else if in_video {
+            if let Some((index, name)) =
+                text.strip_prefix('[').and_then(|rest| rest.split_once("] "))
+            {
+                cameras.push(Webcam {
+                    id: index.to_string(),
+                    name: name.to_string(),
+                });
+            }
+        }
+    }
+    cameras
+}
+
+// Helper to list the cameras: video4linux devices on Linux, ffmpeg's
+// device listing elsewhere
+async fn find_webcams(program: &str) -> Result<Vec<Webcam>, String> {
+    if cfg!(target_os = "linux") {
+        let mut cameras = Vec::new();
+        let mut read_dir = tokio::fs::read_dir("/dev")
+            .await
+            .map_err(|e| format!("Failed to list cameras: {}", e))?;
+        while let Ok(Some(entry)) = read_dir.next_entry().await {
+            let node = entry.file_name().to_string_lossy().to_string();
+            if !node.starts_with("video") {
+                continue;
+            }
+            let name_path = format!("/sys/class/video4linux/{}/name", node);
+            let name = tokio::fs::read_to_string(&name_path)
+                .await
+                .map(|n| n.trim().to_string())
+                .unwrap_or_else(|_| node.clone());
+            cameras.push(Webcam {
+                id: format!("/dev/{}", node),
+                name,
+            });
+        }
+        cameras.sort_by(|a, b| a.id.cmp(&b.id));
+        return Ok(cameras);
+    }
+
+    let args: &[&str] = if cfg!(target_os = "windows") {
+        &["-hide_banner", "-list_devices", "true", "-f", "dshow", "-i", "dummy"]
+    } else {
+        &["-hide_banner", "-list_devices", "true", "-f", "avfoundation", "-i", ""]
+    };
+    // ffmpeg exits with an error after listing; the listing is on stderr
+    let (_, listing) = run_ffmpeg(program, args).await?;
+    Ok(parse_device_listing(&listing))
+}
+
+// Helper to take one photo with a camera, returning PNG bytes
+async fn capture_png(program: &str, device: &str) -> Result<Vec<u8>, String> {
+    let (format, input) = if cfg!(target_os = "windows") {
+        ("dshow", format!("video={}", device))
+    } else if cfg!(target_os = "macos") {
+        ("avfoundation", device.to_string())
+    } else {
+        ("v4l2", device.to_string())
+    };
+    let mut args = vec!["-hide_banner", "-loglevel", "error", "-f", format];
+    if format == "avfoundation" {
+        // AVFoundation refuses the default rate on most built-in cameras
+        args.extend(["-framerate", "30"]);
+    }
+    args.extend(["-i", &input, "-ss", WARM_UP_SECONDS, "-frames:v", "1"]);
+    args.extend(["-f", "image2pipe", "-c:v", "png", "pipe:1"]);
+
+    let (png, stderr) = run_ffmpeg(program, &args).await?;
+    if png.is_empty() {
+        let reason = stderr.lines().last().unwrap_or("no image").trim().to_string();
+        return Err(format!("Failed to capture a photo: {}", reason));
+    }
+    Ok(png)
+}
+
+// ============================================
+// Webcam Commands
+// ============================================
+
+/// List the cameras that can take photos, as `[{id, name}]`. Pass an `id`
+/// to `capture_webcam_photo`, or save it as `capture.webcamDevice`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn list_webcams(app_handle: tauri::AppHandle) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let program = ffmpeg_program(&app_handle).await?;
+    let cameras: Vec<Value> = find_webcams(&program)
+        .await?
+        .into_iter()
+        .map(|c| serde_json::json!({ "id": c.id, "name": c.name }))
+        .collect();
+    serde_json::to_string(&cameras).map_err(|e| format!("Failed to serialize cameras: {}", e))
+}
+
+/// Take a photo with a camera and add it to the image library, tagged
+/// "webcam" plus `tags` (e.g. to document finished work or to grade a
+/// worksheet from a photo). `device` must be an ID from `list_webcams`; it
+/// defaults to the camera in settings, then to the first camera found.
+/// Needs ffmpeg. Returns the new image library entry.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn capture_webcam_photo(
+    app_handle: tauri::AppHandle,
+    device: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let program = ffmpeg_program(&app_handle).await?;
+    let configured = settings_storage::load_settings(&app_handle).await?.capture.webcam_device;
+    let cameras = find_webcams(&program).await?;
+    // The device ends up in ffmpeg's arguments, so only a camera that was
+    // found is accepted
+    let device = match device.or(configured).filter(|d| !d.trim().is_empty()) {
+        Some(device) => cameras
+            .into_iter()
+            .find(|c| c.id == device)
+            .map(|c| c.id)
+            .ok_or_else(|| format!("Camera not found: {}", device))?,
+        None => cameras
+            .into_iter()
+            .next()
+            .map(|c| c.id)
+            .ok_or("No camera found")?,
+    };
+
+    let png = capture_png(&program, &device).await?;
+    let mut tags = tags.unwrap_or_default();
+    tags.push(CAPTURE_TAG.to_string());
+    let file_name = format!("Webcam {}.png", chrono::Local::now().format("%Y-%m-%d %H.%M.%S"));
+    let entry = image_library::add_image(&app_handle, png, &file_name, &tags).await?;
+
+    let image_id = entry.get("imageId").and_then(|v| v.as_str()).unwrap_or_default();
+    audit_log::record(&app_handle, "capture_webcam_photo", "image", &[image_id]).await;
+
+    Ok(image_library::with_urls(entry).to_string())
+}
@@ -0,0 +1,402 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::fs;
+
+use crate::commands::parent_portal::{display_date, state_label, table};
+use crate::commands::worksheet_assembly::escape_html;
+use crate::commands::{
+    app_lock, audit_log, calendar, email, learner_storage, locale, project_stats, session_mode,
+    settings_storage,
+};
+
+const DIGEST_DIR: &str = "digests";
+const LAST_RUN_FILE: &str = "last-run.json";
+
+// Default output folder (inside Documents) when settings name none
+const DEFAULT_OUTPUT_DIR: &str = "TA Digests";
+
+// How often the scheduler checks whether last week's digest is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Helper to read a string field, or "" when missing
+fn text<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+// Helper to get the Monday starting the week that contains `date`
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(u64::from(date.weekday().num_days_from_monday()))
+}
+
+// Helper to check whether an RFC 3339 timestamp falls in the week (local
+// time) starting on `start`
+fn in_week(timestamp: &str, start: NaiveDate) -> bool {
+    chrono::DateTime::parse_from_rfc3339(timestamp).is_ok_and(|t| {
+        let date = t.with_timezone(&chrono::Local).date_naive();
+        date >= start && date < start + Days::new(7)
+    })
+}
+
+// Helper to pick the output folder: the one given, the one in settings, or
+// a folder in Documents
+async fn output_dir(
+    app_handle: &tauri::AppHandle,
+    out_dir: Option<String>,
+) -> Result<PathBuf, String> {
+    let settings = settings_storage::load_settings(app_handle).await?;
+    if let Some(dir) = out_dir.or(settings.digest.directory) {
+        let dir = PathBuf::from(dir);
+        if !dir.is_absolute() {
+            return Err(format!("Output folder must be an absolute path: {}", dir.display()));
+        }
+        return Ok(dir);
+    }
+    let documents_dir = app_handle
+        .path()
+        .document_dir()
+        .map_err(|e| format!("Failed to get documents directory: {}", e))?;
+    Ok(documents_dir.join(DEFAULT_OUTPUT_DIR))
+}
+
+// Helper to render one learner's week as a printable HTML page
+async fn render_digest(
+    app_handle: &tauri::AppHandle,
+    profile: &Value,
+    start: NaiveDate,
+) -> Result<String, String> {
+    let learner_id = text(profile, "learnerId");
+    let learner_name = Some(text(profile, "displayName"))
+        .filter(|n| !n.is_empty())
+        .unwrap_or("Learner");
+    let end = start + Days::new(6);
+
+    let learner_dir = learner_storage::get_learner_dir(app_handle, learner_id)?;
+    let checks_path = learner_dir.join(learner_storage::QUICK_CHECKS_FILE);
+    let checks: Vec<Value> = match fs::read_to_string(&checks_path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|_| Vec::new()),
+        Err(_) => Vec::new(),
+    };
+    let mut week_checks: Vec<&Value> =
+        checks.iter().filter(|c| in_week(text(c, "createdAt"), start)).collect();
+    week_checks.sort_by_key(|c| text(c, "createdAt"));
+    let check_rows: Vec<Vec<String>> = week_checks
+        .iter()
+        .map(|c| {
+            let score = c.get("score").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let date = chrono::DateTime::parse_from_rfc3339(text(c, "createdAt"))
+                .map(|t| t.with_timezone(&chrono::Local).date_naive());
+            vec![
+                date.map_or_else(
+                    |_| display_date(text(c, "createdAt")),
+                    |d| locale::format_date(app_handle, "format.date", d),
+                ),
+                text(c, "objectiveId").to_string(),
+                format!("{:.0}%", score),
+            ]
+        })
+        .collect();
+    let scores: Vec<f64> = week_checks
+        .iter()
+        .filter_map(|c| c.get("score").and_then(|v| v.as_f64()))
+        .collect();
+    let check_summary = match scores.len() {
+        0 => String::new(),
+        n => {
+            let key = if n == 1 { "digest.checkSummaryOne" } else { "digest.checkSummaryOther" };
+            let average = format!("{:.0}", scores.iter().sum::<f64>() / n as f64);
+            let count = n.to_string();
+            let summary =
+                locale::format(app_handle, key, &[("count", &count), ("average", &average)]);
+            format!("<p>{}</p>\n", escape_html(&summary))
+        }
+    };
+
+    // Mastery keeps only each objective's latest state, so "changes" are
+    // the objectives updated this week, shown as they now stand
+    let mastery = project_stats::read_mastery(app_handle, learner_id).await?;
+    let mut changed: Vec<(&String, &Value)> = mastery
+        .iter()
+        .filter(|(_, record)| in_week(text(record, "lastUpdated"), start))
+        .collect();
+    changed.sort_by_key(|(objective_id, record)| (text(record, "subject"), *objective_id));
+    let mastery_rows: Vec<Vec<String>> = changed
+        .iter()
+        .map(|(objective_id, record)| {
+            vec![
+                objective_id.to_string(),
+                text(record, "subject").to_string(),
+                state_label(app_handle, text(record, "state")),
+            ]
+        })
+        .collect();
+
+    let mut completed: Vec<(NaiveDate, &str)> = Vec::new();
+    let entries = calendar::read_entries(app_handle, learner_id).await?;
+    for entry in &entries {
+        let done = entry.get("completed").and_then(|v| v.as_bool()).unwrap_or(false);
+        let date = NaiveDate::parse_from_str(text(entry, "date"), DATE_FORMAT).ok();
+        if let Some(date) = date.filter(|d| done && *d >= start && *d <= end) {
+            completed.push((date, text(entry, "title")));
+        }
+    }
+    completed.sort();
+    let completed_rows: Vec<Vec<String>> = completed
+        .into_iter()
+        .map(|(date, title)| {
+            vec![locale::format_date(app_handle, "format.shortDate", date), title.to_string()]
+        })
+        .collect();
+
+    let week_of = locale::format_date(app_handle, "format.date", start);
+    let title = locale::format(
+        app_handle,
+        "digest.title",
+        &[("learner", learner_name), ("date", &week_of)],
+    );
+    let range = locale::format(
+        app_handle,
+        "digest.range",
+        &[
+            ("from", &locale::format_date(app_handle, "format.longDate", start)),
+            ("to", &locale::format_date(app_handle, "format.longDate", end)),
+        ],
+    );
+    let t = |key: &str| locale::text(app_handle, key);
+    Ok(format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>\n\
+         @page {{ margin: 0.75in; }}\n\
+         body {{ font-family: Arial, Helvetica, sans-serif; color: #1F2937; max-width: 48em; }}\n\
+         table {{ border-collapse: collapse; width: 100%; margin-bottom: 1.5em; }}\n\
+         th, td {{ text-align: left; padding: 0.4em; border-bottom: 1px solid #E5E7EB; }}\n\
+         .empty {{ color: #9CA3AF; }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n\
+         <p>{range}</p>\n\
+         <h2>{checks_heading}</h2>\n{check_summary}{checks}\
+         <h2>{mastery_heading}</h2>\n{mastery}\
+         <h2>{completed_heading}</h2>\n{completed}\
+         </body>\n</html>\n",
+        title = escape_html(&title),
+        range = escape_html(&range),
+        checks_heading = escape_html(&t("digest.quickChecks")),
+        mastery_heading = escape_html(&t("digest.masteryChanges")),
+        completed_heading = escape_html(&t("digest.completedWork")),
+        checks = table(
+            &[&t("column.date"), &t("column.objective"), &t("column.score")],
+            &check_rows,
+            &t("digest.noChecks"),
+        ),
+        mastery = table(
+            &[&t("column.objective"), &t("column.subject"), &t("column.status")],
+            &mastery_rows,
+            &t("digest.noMasteryChanges"),
+        ),
+        completed = table(
+            &[&t("column.date"), &t("column.work")],
+            &completed_rows,
+            &t("digest.noCompletedWork"),
+        ),
+    ))
+}
+
+// Helper to write the digests for the week starting on `start`, one file
+// per learner (all learners when `learner_ids` is None). Returns each
+// learner ID with its file.
+async fn write_digests(
+    app_handle: &tauri::AppHandle,
+    start: NaiveDate,
+    learner_ids: Option<&[String]>,
+    out_dir: &std::path::Path,
+) -> Result<Vec<(String, PathBuf)>, String> {
+    let profiles = learner_storage::read_profiles(app_handle).await?;
+    let selected: Vec<&Value> = match learner_ids {
+        Some(ids) => ids
+            .iter()
+            .map(|id| {
+                profiles
+                    .iter()
+                    .find(|p| text(p, "learnerId") == id)
+                    .ok_or_else(|| format!("Learner not found: {}", id))
+            })
+            .collect::<Result<_, _>>()?,
+        None => profiles.iter().collect(),
+    };
+
+    fs::create_dir_all(out_dir)
+        .await
+        .map_err(|e| format!("Failed to create output folder: {}", e))?;
+    let mut written = Vec::new();
+    for profile in selected {
+        let learner_id = text(profile, "learnerId");
+        let html = render_digest(app_handle, profile, start).await?;
+        let path = out_dir.join(format!("weekly-digest-{}-{}.html", start, learner_id));
+        fs::write(&path, html)
+            .await
+            .map_err(|e| format!("Failed to write digest: {}", e))?;
+        written.push((learner_id.to_string(), path));
+    }
+    Ok(written)
+}
+
+// Helper to email the digest files as attachments
+async fn email_digests(
+    app_handle: &tauri::AppHandle,
+    start: NaiveDate,
+    to: Vec<String>,
+    written: &[(String, PathBuf)],
+) -> Result<Value, String> {
+    let outgoing = email::OutgoingEmail {
+        to,
+        subject: locale::format(
+            app_handle,
+            "digest.emailSubject",
+            &[("date", &locale::format_date(app_handle, "format.date", start))],
+        ),
+        body: locale::text(app_handle, "digest.emailBody"),
+        attachments: written.iter().map(|(_, path)| path.clone()).collect(),
+    };
+    email::send(app_handle, &outgoing).await
+}
+
+// Helper to get the file recording the last week the scheduler covered
+fn get_last_run_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(DIGEST_DIR).join(LAST_RUN_FILE))
+}
+
+// Helper to write last week's digests if they are scheduled, due, and not
+// written yet. Scheduled digests are only emailed over SMTP; opening the
+// mail app unprompted would be surprising.
+async fn run_if_due(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let settings = settings_storage::load_settings(app_handle).await?;
+    if !settings.digest.scheduled {
+        return Ok(());
+    }
+    let weekday: Weekday = settings
+        .digest
+        .weekday
+        .parse()
+        .map_err(|_| format!("Invalid digest weekday: {}", settings.digest.weekday))?;
+    let today = chrono::Local::now().date_naive();
+    let this_week = week_start(today);
+    if today < this_week + Days::new(u64::from(weekday.num_days_from_monday())) {
+        return Ok(());
+    }
+
+    let last_week = this_week - Days::new(7);
+    let last_run_path = get_last_run_path(app_handle)?;
+    let last_run: Value = match fs::read_to_string(&last_run_path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or(Value::Null),
+        Err(_) => Value::Null,
+    };
+    let last_covered = NaiveDate::parse_from_str(text(&last_run, "weekStart"), DATE_FORMAT).ok();
+    if last_covered.is_some_and(|covered| covered >= last_week) {
+        return Ok(());
+    }
+
+    let out_dir = output_dir(app_handle, None).await?;
+    let written = write_digests(app_handle, last_week, None, &out_dir).await?;
+    let smtp_configured = settings.email.smtp_host.is_some_and(|h| !h.trim().is_empty());
+    if !written.is_empty() && !settings.digest.email_to.is_empty() && smtp_configured {
+        email_digests(app_handle, last_week, settings.digest.email_to, &written).await?;
+    }
+
+    // Recorded last, so a failed run is tried again on the next check
+    if let Some(parent) = last_run_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create digest directory: {}", e))?;
+    }
+    let record = serde_json::json!({
+        "weekStart": last_week.to_string(),
+        "generatedAt": chrono::Utc::now().to_rfc3339(),
+    });
+    fs::write(&last_run_path, record.to_string())
+        .await
+        .map_err(|e| format!("Failed to record digest run: {}", e))?;
+    tracing::info!(week = %last_week, learners = written.len(), "Wrote scheduled weekly digests");
+    Ok(())
+}
+
+/// Start the background task that writes scheduled weekly digests. Called
+/// from the app's setup.
+pub fn start_scheduler(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = run_if_due(&app_handle).await {
+                tracing::warn!(error = %e, "Failed to write weekly digests");
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// ============================================
+// Weekly Digest Commands
+// ============================================
+
+/// Write a weekly digest for each learner (or just `learner_ids`): the
+/// quick checks taken, objectives whose mastery changed, and calendar work
+/// completed in the Monday-to-Sunday week containing `week_of`
+/// (`YYYY-MM-DD`, default last week). Each digest is a print-ready HTML
+/// file in `out_dir` (default: the digest folder in settings, then "TA
+/// Digests" in Documents) that can be saved as PDF from the print dialog.
+/// When `email_to` is given the files are emailed as well. Returns
+/// `{weekStart, files: [{learnerId, path}], email}`, with `email` as from
+/// `send_artifact_email` or null.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn generate_weekly_digest(
+    app_handle: tauri::AppHandle,
+    week_of: Option<String>,
+    learner_ids: Option<Vec<String>>,
+    out_dir: Option<String>,
+    email_to: Option<Vec<String>>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let start = match week_of {
+        Some(date) => week_start(
+            NaiveDate::parse_from_str(&date, DATE_FORMAT)
+                .map_err(|_| format!("Invalid date: {}", date))?,
+        ),
+        None => week_start(chrono::Local::now().date_naive()) - Days::new(7),
+    };
+    let out_dir = output_dir(&app_handle, out_dir).await?;
+    let written = write_digests(&app_handle, start, learner_ids.as_deref(), &out_dir).await?;
+
+    let email = match email_to.filter(|to| !to.is_empty()) {
+        Some(_) if written.is_empty() => return Err("No learners to send digests for".to_string()),
+        Some(to) => Some(email_digests(&app_handle, start, to, &written).await?),
+        None => None,
+    };
+
+    let ids: Vec<&str> = written.iter().map(|(id, _)| id.as_str()).collect();
+    audit_log::record(&app_handle, "generate_weekly_digest", "learner", &ids).await;
+
+    let files: Vec<Value> = written
+        .iter()
+        .map(|(learner_id, path)| {
+            serde_json::json!({
+                "learnerId": learner_id,
+                "path": path.to_string_lossy(),
+            })
+        })
+        .collect();
+    let result = serde_json::json!({
+        "weekStart": start.to_string(),
+        "files": files,
+        "email": email,
+    });
+    Ok(result.to_string())
+}
@@ -0,0 +1,547 @@
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::commands::seeded_random::{self, SplitMix64};
+use crate::commands::{
+    app_lock, artifact_clone, audit_log, clipart, design_pack_preview, design_pack_storage,
+    library_storage, project_storage, question_bank, session_mode, settings_storage,
+};
+
+// Questions per worksheet when the request doesn't say
+const DEFAULT_QUESTION_COUNT: usize = 10;
+
+// Colors used when there's no pack or its palette has fewer entries
+const FALLBACK_PALETTE: [&str; 4] = ["#1F2937", "#4B5563", "#E5E7EB", "#9CA3AF"];
+
+// Body font when the pack names none
+const FALLBACK_FONT: &str = "Arial, Helvetica, sans-serif";
+
+/// Escape text for use in HTML content or attribute values
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Helper to read a string array field
+fn strings(value: &Value, key: &str) -> Vec<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+// Helper to pick questions: matching objectives and difficulty, skipping
+// recently used ones, least recently used first
+fn select_questions(questions: &[Value], request: &Value) -> Vec<Value> {
+    let objective_tags = strings(request, "objectiveTags");
+    let difficulty = request.get("difficulty").and_then(|v| v.as_str());
+    let count = request
+        .get("count")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_QUESTION_COUNT, |c| c as usize);
+    let recent_cutoff = request
+        .get("avoidRecentDays")
+        .and_then(|v| v.as_i64())
+        .map(|days| (chrono::Utc::now() - chrono::Duration::days(days)).to_rfc3339());
+
+    let mut candidates: Vec<&Value> = questions
+        .iter()
+        .filter(|q| {
+            objective_tags.is_empty()
+                || strings(q, "objectiveTags").iter().any(|t| objective_tags.contains(t))
+        })
+        .filter(|q| {
+            difficulty.is_none() || q.get("difficulty").and_then(|v| v.as_str()) == difficulty
+        })
+        .filter(|q| {
+            let last_used = q.get("lastUsedAt").and_then(|v| v.as_str());
+            match (&recent_cutoff, last_used) {
+                (Some(cutoff), Some(last_used)) => last_used < cutoff.as_str(),
+                _ => true,
+            }
+        })
+        .collect();
+
+    // Never-used questions sort first (None < Some); the sort is stable so
+    // bank order breaks ties
+    candidates.sort_by_key(|q| q.get("lastUsedAt").and_then(|v| v.as_str()));
+    candidates.into_iter().take(count).cloned().collect()
+}
+
+// Helper to put the selected questions in their printed order: shuffled
+// when `shuffle_questions` is set, and with multiple choice options
+// shuffled when `shuffle_choices` is. The same seed always gives the same
+// order. Letter answers ("B") follow their choice to its new position.
+fn arrange(
+    selected: &[Value],
+    seed: u64,
+    shuffle_questions: bool,
+    shuffle_choices: bool,
+) -> Vec<Value> {
+    let mut rng = SplitMix64(seed);
+    let mut questions = selected.to_vec();
+    if shuffle_questions {
+        rng.shuffle(&mut questions);
+    }
+    if !shuffle_choices {
+        return questions;
+    }
+
+    for question in questions.iter_mut() {
+        if question.get("type").and_then(|v| v.as_str()) != Some("multiple_choice") {
+            continue;
+        }
+        let Some(obj) = question.as_object_mut() else {
+            continue;
+        };
+        let Some(choices) = obj.get("choices").and_then(|v| v.as_array()).cloned() else {
+            continue;
+        };
+        let mut order: Vec<usize> = (0..choices.len()).collect();
+        rng.shuffle(&mut order);
+        let shuffled = order.iter().map(|&i| choices[i].clone()).collect();
+        obj.insert("choices".to_string(), Value::Array(shuffled));
+
+        let letter = obj
+            .get("answer")
+            .and_then(|v| v.as_str())
+            .map(|a| a.trim().to_ascii_uppercase())
+            .filter(|a| a.len() == 1)
+            .and_then(|a| a.bytes().next())
+            .filter(|b| b.is_ascii_uppercase())
+            .map(|b| (b - b'A') as usize);
+        if let Some(new_index) = letter.and_then(|old| order.iter().position(|&i| i == old)) {
+            let answer = ((b'A' + new_index as u8) as char).to_string();
+            obj.insert("answer".to_string(), Value::String(answer));
+        }
+    }
+    questions
+}
+
+/// The pack's palette (primary, secondary, tint, accent) as `#RRGGBB`
+/// strings, padded with fallback colors
+pub(crate) fn pack_palette(pack: Option<&Value>) -> [String; 4] {
+    let mut palette = FALLBACK_PALETTE.map(String::from);
+    let colors = pack
+        .and_then(|p| p.get("parsedSummary"))
+        .and_then(|s| s.get("palette"))
+        .and_then(|p| p.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c.as_str().and_then(design_pack_preview::parse_hex_color))
+        .map(|[r, g, b]| format!("#{:02X}{:02X}{:02X}", r, g, b));
+    for (slot, color) in palette.iter_mut().zip(colors) {
+        *slot = color;
+    }
+    palette
+}
+
+/// The CSS font stack for a pack's first named font
+pub(crate) fn pack_font(pack: Option<&Value>) -> String {
+    let family = pack
+        .and_then(|p| p.get("fonts"))
+        .and_then(|v| v.as_array())
+        .and_then(|fonts| fonts.first())
+        .and_then(|font| font.as_str().or_else(|| font.get("family")?.as_str()))
+        .filter(|family| !family.contains(['\'', '"', ';', '<', '>', '{', '}']));
+    match family {
+        Some(family) => format!("'{}', {}", family, FALLBACK_FONT),
+        None => FALLBACK_FONT.to_string(),
+    }
+}
+
+// Helper to render one question's response area
+fn render_question(question: &Value) -> String {
+    let stem = question.get("stem").and_then(|v| v.as_str()).unwrap_or("");
+    let question_type = question.get("type").and_then(|v| v.as_str()).unwrap_or("");
+    let mut html = format!(
+        "<li class=\"question\" data-question-type=\"{}\"><p class=\"stem\">{}</p>",
+        escape_html(question_type),
+        escape_html(stem)
+    );
+
+    let choices = strings(question, "choices");
+    match question_type {
+        "multiple_choice" | "matching" => {
+            html.push_str("<ol class=\"choices\" type=\"A\">");
+            for choice in &choices {
+                html.push_str(&format!("<li>{}</li>", escape_html(choice)));
+            }
+            html.push_str("</ol>");
+        }
+        "true_false" => {
+            html.push_str("<p class=\"choices\">True &nbsp;&nbsp;/&nbsp;&nbsp; False</p>");
+        }
+        "drawing" => html.push_str("<div class=\"drawing-box\"></div>"),
+        // Fill-in questions carry their blanks in the stem
+        "fill_blank" => {}
+        _ => html.push_str("<div class=\"answer-line\"></div>"),
+    }
+
+    html.push_str("</li>");
+    html
+}
+
+/// Render a full printable document (worksheet, answer key, or companion
+/// page) styled with a design pack
+pub(crate) fn render_document(title: &str, body: &str, pack: Option<&Value>) -> String {
+    let [primary, secondary, tint, accent] = pack_palette(pack);
+    let font = pack_font(pack);
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>\n\
+         body {{ font-family: {font}; color: {primary}; margin: 0.75in; }}\n\
+         header {{ border-bottom: 3px solid {primary}; margin-bottom: 1.5em; }}\n\
+         h1 {{ color: {primary}; margin: 0 0 0.25em; }}\n\
+         .name-line {{ color: {secondary}; margin-bottom: 0.5em; }}\n\
+         .question {{ margin-bottom: 1.5em; break-inside: avoid; }}\n\
+         .question::marker {{ color: {accent}; font-weight: bold; }}\n\
+         .choices li {{ margin: 0.25em 0; }}\n\
+         .answer-line {{ border-bottom: 1px solid {secondary}; height: 2em; }}\n\
+         .drawing-box {{ border: 2px solid {secondary}; background: {tint}; height: 2.5in; }}\n\
+         .answer {{ color: {secondary}; font-weight: bold; }}\n\
+         .clipart {{ display: flex; justify-content: center; gap: 0.5in; margin-bottom: 1em; }}\n\
+         .clipart img {{ height: 0.9in; }}\n\
+         </style>\n</head>\n<body>\n<header>\n<h1>{title}</h1>\n\
+         <p class=\"name-line\">Name: ____________________ &nbsp; Date: __________</p>\n\
+         </header>\n{body}</body>\n</html>\n",
+        title = escape_html(title),
+    )
+}
+
+/// The content between the header and `</body>` of a document made by
+/// `render_document`, or `None` for HTML from anywhere else
+pub(crate) fn document_body(html: &str) -> Option<&str> {
+    let name_line = html.find("<p class=\"name-line\">")?;
+    let header_end = name_line + html[name_line..].find("</header>\n")? + "</header>\n".len();
+    let body_end = html.rfind("</body>")?;
+    html.get(header_end..body_end)
+}
+
+// Helper to render the student page for the selected questions, with any
+// clipart decorations above them
+fn render_worksheet(
+    title: &str,
+    questions: &[Value],
+    decorations: &str,
+    pack: Option<&Value>,
+) -> String {
+    let items: String = questions.iter().map(render_question).collect();
+    let body = format!("{}<ol class=\"questions\">{}</ol>\n", decorations, items);
+    render_document(title, &body, pack)
+}
+
+// Helper to render the answer key for the selected questions
+fn render_answer_key(title: &str, questions: &[Value], pack: Option<&Value>) -> String {
+    let items: String = questions
+        .iter()
+        .map(|q| {
+            let stem = q.get("stem").and_then(|v| v.as_str()).unwrap_or("");
+            let answer = match q.get("answer") {
+                Some(Value::String(answer)) => answer.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(other) => other.to_string(),
+            };
+            format!(
+                "<li class=\"question\"><p class=\"stem\">{}</p><p class=\"answer\">{}</p></li>",
+                escape_html(stem),
+                escape_html(&answer)
+            )
+        })
+        .collect();
+    render_document(title, &format!("<ol class=\"questions\">{}</ol>\n", items), pack)
+}
+
+// ============================================
+// Worksheet Assembly Commands
+// ============================================
+
+/// Assemble a worksheet from the question bank without calling a model.
+///
+/// The request holds `title`, optional `objectiveTags` (questions matching
+/// any tag), `difficulty`, `count`, `avoidRecentDays` (skip questions used
+/// within that many days), `designPackId`, `clipartIds` (decorations shown
+/// above the questions, from `search_clipart`), `projectId`, `grade`,
+/// `subject`, and `includeAnswerKey` (defaults to the export setting). Least recently
+/// used questions are picked first and are marked as used.
+///
+/// With `shuffleQuestions` or `shuffleChoices` the questions, or the options
+/// of multiple choice questions, are shuffled using `seed` (a fresh one when
+/// omitted). The seed and options are saved on the artifacts under
+/// `randomization`, so `regenerate_with_seed` can print them again
+/// identically. Returns `{ artifactId, answerKeyArtifactId, questionIds,
+/// seed }`, with `seed` null when nothing was shuffled.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn assemble_worksheet(
+    app_handle: tauri::AppHandle,
+    request: String,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let request: Value =
+        serde_json::from_str(&request).map_err(|e| format!("Invalid request JSON: {}", e))?;
+    let title = request
+        .get("title")
+        .and_then(|v| v.as_str())
+        .filter(|t| !t.trim().is_empty())
+        .ok_or("Worksheet must have a title")?
+        .to_string();
+    let project_id = request.get("projectId").and_then(|v| v.as_str());
+    if let Some(project_id) = project_id {
+        // Fails with "Project not found" before anything is written
+        project_storage::read_project(&app_handle, project_id).await?;
+    }
+
+    let pack_id = request.get("designPackId").and_then(|v| v.as_str());
+    let pack = match pack_id {
+        Some(pack_id) => Some(design_pack_storage::find_pack(&app_handle, pack_id).await?),
+        None => None,
+    };
+    let include_answer_key = match request.get("includeAnswerKey").and_then(|v| v.as_bool()) {
+        Some(include) => include,
+        None => settings_storage::load_settings(&app_handle).await?.export.include_answer_key,
+    };
+
+    let shuffle_questions = request.get("shuffleQuestions").and_then(|v| v.as_bool());
+    let shuffle_choices = request.get("shuffleChoices").and_then(|v| v.as_bool());
+    let shuffle_questions = shuffle_questions.unwrap_or(false);
+    let shuffle_choices = shuffle_choices.unwrap_or(false);
+    let seed = if shuffle_questions || shuffle_choices {
+        Some(seeded_random::parse_seed(request.get("seed").and_then(|v| v.as_str()))?)
+    } else {
+        None
+    };
+
+    let clipart_ids = strings(&request, "clipartIds");
+    let clipart_items = clipart::find_items(&app_handle, &clipart_ids).await?;
+    let decorations = clipart::render_decorations(&clipart_items);
+
+    let mut bank = question_bank::read_questions(&app_handle).await?;
+    let selected = select_questions(&bank, &request);
+    if selected.is_empty() {
+        return Err("No questions in the bank match this worksheet".to_string());
+    }
+    let question_ids: Vec<String> = selected
+        .iter()
+        .filter_map(|q| q.get("questionId").and_then(|v| v.as_str()).map(String::from))
+        .collect();
+    let objective_tags: BTreeSet<String> =
+        selected.iter().flat_map(|q| strings(q, "objectiveTags")).collect();
+    let printed = match seed {
+        Some(seed) => arrange(&selected, seed, shuffle_questions, shuffle_choices),
+        None => selected.clone(),
+    };
+    let randomization = seed.map(|seed| {
+        serde_json::json!({
+            "seed": seed.to_string(),
+            "shuffleQuestions": shuffle_questions,
+            "shuffleChoices": shuffle_choices,
+        })
+    });
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let base_artifact = |artifact_type: &str, title: &str, html: String| {
+        serde_json::json!({
+            "artifactId": uuid::Uuid::new_v4().to_string(),
+            "projectId": project_id,
+            "jobId": Value::Null,
+            "type": artifact_type,
+            "title": title,
+            "htmlContent": html,
+            "grade": request.get("grade"),
+            "subject": request.get("subject"),
+            "objectiveTags": objective_tags,
+            "designPackId": pack_id,
+            "questionIds": question_ids,
+            "clipartIds": clipart_ids,
+            "randomization": randomization,
+            "source": "question_bank",
+            "createdAt": now,
+        })
+    };
+
+    let mut artifacts = vec![base_artifact(
+        "student_page",
+        &title,
+        render_worksheet(&title, &printed, &decorations, pack.as_ref()),
+    )];
+    if include_answer_key {
+        let key_title = format!("{} - Answer Key", title);
+        let html = render_answer_key(&key_title, &printed, pack.as_ref());
+        artifacts.push(base_artifact("answer_key", &key_title, html));
+    }
+
+    let mut artifact_ids = Vec::new();
+    for artifact in &artifacts {
+        let content = serde_json::to_string_pretty(artifact)
+            .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+        library_storage::write_artifact(&app_handle, artifact, &content).await?;
+        if let Some(artifact_id) = artifact.get("artifactId").and_then(|v| v.as_str()) {
+            artifact_ids.push(artifact_id.to_string());
+        }
+    }
+    if let Some(project_id) = project_id {
+        for artifact_id in &artifact_ids {
+            project_storage::link_artifact(&app_handle, project_id, artifact_id).await?;
+        }
+    }
+
+    // Mark the selected questions as used
+    for question in bank.iter_mut() {
+        let selected_id = question
+            .get("questionId")
+            .and_then(|v| v.as_str())
+            .is_some_and(|id| question_ids.iter().any(|q| q == id));
+        if !selected_id {
+            continue;
+        }
+        if let Some(obj) = question.as_object_mut() {
+            let use_count = obj.get("useCount").and_then(|v| v.as_u64()).unwrap_or(0);
+            obj.insert("useCount".to_string(), Value::from(use_count + 1));
+            obj.insert("lastUsedAt".to_string(), Value::String(now.clone()));
+        }
+    }
+    question_bank::write_questions(&app_handle, &bank).await?;
+
+    let audited_ids: Vec<&str> = artifact_ids.iter().map(String::as_str).collect();
+    audit_log::record(&app_handle, "assemble_worksheet", "artifact", &audited_ids).await;
+
+    let response = serde_json::json!({
+        "artifactId": artifact_ids.first(),
+        "answerKeyArtifactId": artifact_ids.get(1),
+        "questionIds": question_ids,
+        "seed": seed.map(|seed| seed.to_string()),
+    });
+    Ok(response.to_string())
+}
+
+/// Print an assembled worksheet or answer key again from its questions,
+/// shuffled with `seed`, or with its saved seed when omitted so a lost copy
+/// comes out identical. Another seed gives a new form with the same
+/// questions; a worksheet that wasn't shuffled is given a seed this way,
+/// shuffling both its questions and its options. Questions are read from the
+/// bank as they are now, so edited questions print as edited. The copy is
+/// saved as a new artifact with `regeneratedFrom` pointing at the original
+/// and is added to its project. Returns `{ artifactId, sourceArtifactId,
+/// seed }`.
+#[tauri::command]
+#[tracing::instrument(skip_all, err)]
+pub async fn regenerate_with_seed(
+    app_handle: tauri::AppHandle,
+    artifact_id: String,
+    seed: Option<String>,
+) -> Result<String, String> {
+    app_lock::ensure_unlocked(&app_handle)?;
+    session_mode::ensure_teacher_mode(&app_handle)?;
+
+    let source = library_storage::read_artifact(&app_handle, &artifact_id).await?;
+    if source.get("source").and_then(|v| v.as_str()) != Some("question_bank") {
+        return Err(
+            "Only worksheets assembled from the question bank can be regenerated".to_string(),
+        );
+    }
+    let randomization = source.get("randomization").filter(|v| v.is_object());
+    let saved_seed = randomization
+        .and_then(|r| r.get("seed"))
+        .and_then(|v| v.as_str());
+    let seed = match (seed.as_deref(), saved_seed) {
+        (Some(seed), _) | (None, Some(seed)) => seeded_random::parse_seed(Some(seed))?,
+        (None, None) => return Err("The artifact wasn't shuffled, so it has no seed".to_string()),
+    };
+    // An unshuffled worksheet given a seed is shuffled both ways
+    let (shuffle_questions, shuffle_choices) = match randomization {
+        Some(r) => {
+            let flag = |key: &str| r.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+            (flag("shuffleQuestions"), flag("shuffleChoices"))
+        }
+        None => (true, true),
+    };
+
+    let bank = question_bank::read_questions(&app_handle).await?;
+    let selected = strings(&source, "questionIds")
+        .iter()
+        .map(|question_id| {
+            bank.iter()
+                .find(|q| q.get("questionId").and_then(|v| v.as_str()) == Some(question_id))
+                .cloned()
+                .ok_or_else(|| format!("Question not found: {}", question_id))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let printed = arrange(&selected, seed, shuffle_questions, shuffle_choices);
+
+    let pack = match source.get("designPackId").and_then(|v| v.as_str()) {
+        Some(pack_id) => Some(design_pack_storage::find_pack(&app_handle, pack_id).await?),
+        None => None,
+    };
+    let title = source.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled");
+    let html = match source.get("type").and_then(|v| v.as_str()) {
+        Some("answer_key") => render_answer_key(title, &printed, pack.as_ref()),
+        _ => {
+            let clipart_items =
+                clipart::find_items(&app_handle, &strings(&source, "clipartIds")).await?;
+            let decorations = clipart::render_decorations(&clipart_items);
+            render_worksheet(title, &printed, &decorations, pack.as_ref())
+        }
+    };
+
+    let mut regenerated = source.clone();
+    let obj = regenerated.as_object_mut().ok_or("Invalid artifact")?;
+    for key in artifact_clone::RESET_KEYS {
+        obj.remove(*key);
+    }
+    let regenerated_id = uuid::Uuid::new_v4().to_string();
+    obj.insert("artifactId".to_string(), Value::String(regenerated_id.clone()));
+    obj.insert("htmlContent".to_string(), Value::String(html));
+    obj.insert(
+        "randomization".to_string(),
+        serde_json::json!({
+            "seed": seed.to_string(),
+            "shuffleQuestions": shuffle_questions,
+            "shuffleChoices": shuffle_choices,
+        }),
+    );
+    obj.insert("regeneratedFrom".to_string(), Value::String(artifact_id.clone()));
+    obj.insert(
+        "createdAt".to_string(),
+        Value::String(chrono::Utc::now().to_rfc3339()),
+    );
+
+    let content = serde_json::to_string_pretty(&regenerated)
+        .map_err(|e| format!("Failed to serialize artifact: {}", e))?;
+    library_storage::write_artifact(&app_handle, &regenerated, &content).await?;
+
+    if let Some(project_id) = regenerated.get("projectId").and_then(|v| v.as_str()) {
+        let linked = project_storage::link_artifact(&app_handle, project_id, &regenerated_id).await;
+        if let Err(e) = linked {
+            tracing::warn!(error = %e, "Failed to add regenerated artifact to project");
+        }
+    }
+
+    audit_log::record(
+        &app_handle,
+        "regenerate_with_seed",
+        "artifact",
+        &[&regenerated_id, &artifact_id],
+    )
+    .await;
+
+    let response = serde_json::json!({
+        "artifactId": regenerated_id,
+        "sourceArtifactId": artifact_id,
+        "seed": seed.to_string(),
+    });
+    Ok(response.to_string())
+}
@@ -0,0 +1,112 @@
+use serde::de::{DeserializeSeed, Deserializer as _, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::Value;
+use std::fmt;
+use std::io::{BufReader, ErrorKind};
+use std::path::Path;
+
+/// List-shaped stores larger than this are filtered as they are read rather
+/// than parsed whole
+pub(crate) const STREAMING_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+// Visits a JSON array, keeping the elements `keep` accepts
+struct FilterSeq<'a, F> {
+    keep: &'a mut F,
+    matches: &'a mut Vec<Value>,
+}
+
+impl<'de, F: FnMut(&Value) -> bool> Visitor<'de> for FilterSeq<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(item) = seq.next_element::<Value>()? {
+            if (self.keep)(&item) {
+                self.matches.push(item);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'de, F: FnMut(&Value) -> bool> DeserializeSeed<'de> for FilterSeq<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+// Visits a JSON object, filtering the array under `key` and skipping the rest
+struct FilterField<'a, F> {
+    key: &'static str,
+    keep: &'a mut F,
+    matches: &'a mut Vec<Value>,
+}
+
+impl<'de, F: FnMut(&Value) -> bool> Visitor<'de> for FilterField<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an object with an array under \"{}\"", self.key)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(name) = map.next_key::<String>()? {
+            if name == self.key {
+                map.next_value_seed(FilterSeq {
+                    keep: &mut *self.keep,
+                    matches: &mut *self.matches,
+                })?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Read the entries of a list-shaped JSON file that `keep` accepts, parsing
+/// one entry at a time so only the matches are held in memory. With `key`,
+/// the file is an object and the list is under that field (as in the
+/// library index). A missing file, or one that isn't valid JSON, has no
+/// entries.
+pub(crate) async fn filter_array<F>(
+    path: &Path,
+    key: Option<&'static str>,
+    mut keep: F,
+) -> Result<Vec<Value>, String>
+where
+    F: FnMut(&Value) -> bool + Send + 'static,
+{
+    let path = path.to_path_buf();
+    let display = path.display().to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        let file = match std::fs::File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(file));
+        let mut matches = Vec::new();
+        let parsed = match key {
+            Some(key) => deserializer.deserialize_map(FilterField {
+                key,
+                keep: &mut keep,
+                matches: &mut matches,
+            }),
+            None => deserializer.deserialize_seq(FilterSeq {
+                keep: &mut keep,
+                matches: &mut matches,
+            }),
+        };
+        if parsed.and_then(|()| deserializer.end()).is_err() {
+            return Ok(Vec::new());
+        }
+        Ok(matches)
+    })
+    .await
+    .map_err(|e| format!("Failed to read {}: {}", display, e))?
+}
@@ -1,16 +1,98 @@
+mod archive;
 mod commands;
+mod json_stream;
 
-use commands::{file_system, dialog, learner_storage, library_storage, design_pack_storage, project_storage};
+use tauri::Manager;
+use commands::{
+    file_system, dialog, learner_storage, library_storage, design_pack_storage, project_storage,
+    learner_bundle, learner_privacy, app_lock, secrets, session_mode, quick_check_session,
+    audit_log, settings_storage, logging, diagnostics, health_check, factory_reset, demo_data,
+    usage_stats, crash_reporter, performance, storage_compaction, library_integrity, migrations,
+    legacy_migration, design_pack_assets, design_pack_preview, fonts, project_activity,
+    project_stats, project_bundle, question_bank, worksheet_assembly, question_variants,
+    artifact_usage, tag_management, collections, artifact_analysis, artifact_clone, vocabulary,
+    reading_level, design_pack_reapply, generation_history, generation_usage, prompt_comparison,
+    generation_evaluation, prompt_presets, task_manager, index_cache, binary_ipc, tray, deep_link,
+    reminders, calendar, pacing, lesson_plans, google_drive, onedrive, email, parent_portal,
+    weekly_digest, data_history, share_export, local_image, image_cache, image_compression,
+    clipart, image_library, webcam, scanner, palette_extraction, braille, accessibility,
+    print_layout, pdf_export, print_preview, lan_quick_check, standards_alignment,
+    mastery_prediction, moderation_log, disk_space, http_client, connectivity, locale, plugins,
+    automation, rest_api, mcp_server, library_site,
+};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+
+    // A second launch (e.g. from a link) hands its link to this instance instead
+    #[cfg(any(windows, target_os = "linux"))]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(|app_handle, _args, _cwd| {
+        tray::show_main_window(app_handle);
+    }));
+
+    builder
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
-        .invoke_handler(tauri::generate_handler![
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .manage(app_lock::AppLockState::default())
+        .manage(session_mode::SessionModeState::default())
+        .manage(quick_check_session::QuickCheckSessionState::default())
+        .manage(factory_reset::FactoryResetState::default())
+        .manage(legacy_migration::LegacyMigrationState::default())
+        .register_uri_scheme_protocol(
+            design_pack_assets::ASSET_SCHEME,
+            design_pack_assets::handle_asset_request,
+        )
+        .register_uri_scheme_protocol(
+            image_library::IMAGE_SCHEME,
+            image_library::handle_image_request,
+        )
+        .manage(task_manager::TaskManagerState::default())
+        .manage(index_cache::IndexCacheState::default())
+        .manage(tray::TrayState::default())
+        .manage(deep_link::DeepLinkState::default())
+        .manage(reminders::RemindersState::default())
+        .manage(data_history::DataHistoryState::default())
+        .manage(share_export::ShareExportState::default())
+        .manage(image_cache::ImageCacheState::default())
+        .manage(print_layout::PrintLayoutState::default())
+        .manage(pdf_export::PdfExportState::default())
+        .manage(lan_quick_check::LanQuickCheckState::default())
+        .manage(connectivity::ConnectivityState::default())
+        .manage(locale::LocaleState::default())
+        .manage(automation::AutomationState::default())
+        .manage(rest_api::RestApiState::default())
+        .manage(mcp_server::McpServerState::default())
+        .setup(|app| {
+            let logging_state = logging::init(app.handle())?;
+            app.manage(logging_state);
+            crash_reporter::init(app.handle())?;
+            migrations::run(app.handle());
+            design_pack_storage::seed_default_packs(app.handle());
+            legacy_migration::start(app.handle());
+            usage_stats::start_flush_task(app.handle());
+            app_lock::init(app.handle());
+            tray::init(app.handle())?;
+            deep_link::init(app.handle());
+            reminders::start_scheduler(app.handle());
+            weekly_digest::start_scheduler(app.handle());
+            share_export::start_scheduler(app.handle());
+            http_client::init(app.handle());
+            connectivity::start_monitor(app.handle());
+            locale::init(app.handle());
+            automation::start_scheduler(app.handle());
+            rest_api::init(app.handle());
+            mcp_server::init(app.handle());
+            Ok(())
+        })
+        .on_window_event(tray::handle_window_event)
+        .invoke_handler(performance::track_payloads(tauri::generate_handler![
             file_system::save_file,
             file_system::read_file,
             dialog::open_folder,
@@ -22,10 +104,38 @@ pub fn run() {
             learner_storage::save_learner_mastery,
             learner_storage::get_quick_check_history,
             learner_storage::save_quick_check_result,
+            // Learner bundle commands
+            learner_bundle::export_learner_bundle,
+            learner_bundle::import_learner_bundle,
+            // Learner privacy commands
+            learner_privacy::anonymize_learner,
+            learner_privacy::export_all_data_for_learner,
+            // App lock commands
+            app_lock::get_lock_status,
+            app_lock::set_pin,
+            app_lock::clear_pin,
+            app_lock::verify_pin,
+            app_lock::lock_app,
+            app_lock::unlock_app,
+            // Secrets commands
+            secrets::set_secret,
+            secrets::get_secret,
+            secrets::delete_secret,
+            // Session mode (kiosk) commands
+            session_mode::get_session_mode,
+            session_mode::start_learner_session,
+            session_mode::end_learner_session,
+            // Quick check session commands
+            quick_check_session::start_quick_check_session,
+            quick_check_session::submit_quick_check_session,
+            quick_check_session::start_timed_session,
+            quick_check_session::record_timed_answer,
             // Library storage commands (Issue #20)
             library_storage::get_library_index,
             library_storage::save_library_index,
             library_storage::get_artifact,
+            library_storage::get_artifact_stream,
+            library_storage::get_artifacts,
             library_storage::save_artifact,
             library_storage::delete_artifact,
             library_storage::search_artifacts,
@@ -34,6 +144,9 @@ pub fn run() {
             design_pack_storage::get_design_pack,
             design_pack_storage::save_design_pack,
             design_pack_storage::delete_design_pack,
+            design_pack_storage::import_design_pack_zip,
+            design_pack_storage::export_design_pack,
+            design_pack_storage::restore_default_packs,
             // Local project storage commands (Issue #20)
             project_storage::get_local_projects,
             project_storage::get_local_project,
@@ -41,7 +154,282 @@ pub fn run() {
             project_storage::delete_local_project,
             project_storage::get_projects_by_type,
             project_storage::add_artifact_to_project,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+            project_storage::remove_artifact_from_project,
+            project_storage::set_project_artifacts,
+            // Audit log commands
+            audit_log::get_audit_log,
+            // Settings commands
+            settings_storage::get_settings,
+            settings_storage::update_settings,
+            settings_storage::reset_settings,
+            // Logging commands
+            logging::get_log_level,
+            logging::set_log_level,
+            logging::export_logs,
+            // Diagnostics commands
+            diagnostics::create_diagnostics_bundle,
+            // Health check commands
+            health_check::run_health_check,
+            // Factory reset commands
+            factory_reset::request_factory_reset,
+            factory_reset::factory_reset,
+            // Demo data commands
+            demo_data::seed_demo_data,
+            demo_data::remove_demo_data,
+            // Usage stats commands
+            usage_stats::get_usage_stats,
+            usage_stats::record_usage_event,
+            usage_stats::send_usage_report,
+            // Crash report commands
+            crash_reporter::get_last_crash_report,
+            crash_reporter::dismiss_crash_report,
+            // Performance metrics commands
+            performance::get_performance_metrics,
+            // Storage compaction commands
+            storage_compaction::compact_storage,
+            // Library integrity commands
+            library_integrity::verify_library_integrity,
+            // Schema migration commands
+            migrations::get_schema_versions,
+            // Legacy migration commands
+            legacy_migration::get_legacy_migration_status,
+            // Design pack asset commands
+            design_pack_assets::add_design_pack_asset,
+            design_pack_assets::remove_design_pack_asset,
+            design_pack_assets::resolve_design_pack_assets,
+            // Design pack preview commands
+            design_pack_preview::render_pack_preview,
+            // Font commands
+            fonts::get_fonts,
+            fonts::import_font_file,
+            fonts::sync_pack_fonts,
+            fonts::delete_font,
+            fonts::embed_fonts_in_html,
+            // Project activity commands
+            project_activity::get_project_timeline,
+            project_activity::record_project_activity,
+            // Project statistics commands
+            project_stats::get_project_stats,
+            // Project bundle commands
+            project_bundle::export_project_bundle,
+            project_bundle::import_project_bundle,
+            // Question bank commands
+            question_bank::get_questions,
+            question_bank::get_question,
+            question_bank::save_question,
+            question_bank::delete_question,
+            question_bank::search_questions,
+            // Worksheet assembly commands
+            worksheet_assembly::assemble_worksheet,
+            worksheet_assembly::regenerate_with_seed,
+            // Question variant commands
+            question_variants::generate_variants,
+            // Artifact usage commands
+            artifact_usage::set_artifact_favorite,
+            artifact_usage::set_artifact_rating,
+            artifact_usage::record_artifact_use,
+            // Tag management commands
+            tag_management::list_all_tags,
+            tag_management::rename_tag,
+            tag_management::merge_tags,
+            tag_management::delete_tag,
+            // Collection commands
+            collections::get_collections,
+            collections::save_collection,
+            collections::delete_collection,
+            collections::add_to_collection,
+            collections::remove_from_collection,
+            collections::get_artifact_collections,
+            // Artifact analysis commands
+            artifact_analysis::analyze_artifact,
+            // Artifact clone commands
+            artifact_clone::clone_artifact,
+            // Vocabulary commands
+            vocabulary::extract_vocabulary,
+            // Reading level commands
+            reading_level::simplify_artifact,
+            // Design pack reapply commands
+            design_pack_reapply::reapply_design_pack,
+            // Generation history commands
+            generation_history::record_generation,
+            generation_history::get_generation_history,
+            generation_history::replay_generation,
+            // Generation usage commands
+            generation_usage::get_generation_usage,
+            // Prompt comparison commands
+            prompt_comparison::compare_prompts,
+            prompt_comparison::record_prompt_preference,
+            prompt_comparison::get_prompt_comparisons,
+            // Generation evaluation commands
+            generation_evaluation::evaluate_generation,
+            // Prompt preset commands
+            prompt_presets::list_prompt_presets,
+            prompt_presets::get_prompt_preset,
+            prompt_presets::override_prompt_preset,
+            prompt_presets::reset_prompt_preset,
+            // Task manager commands
+            task_manager::list_tasks,
+            task_manager::cancel_task,
+            // Index cache commands
+            index_cache::flush_indices,
+            // Binary IPC commands
+            binary_ipc::get_artifact_msgpack,
+            binary_ipc::get_artifacts_msgpack,
+            binary_ipc::get_generation_history_msgpack,
+            // Tray commands
+            tray::set_tray_status,
+            tray::notify_worksheet_ready,
+            // Deep link commands
+            deep_link::take_pending_deep_link,
+            // Reminder commands
+            reminders::get_reminders,
+            reminders::save_reminder,
+            reminders::dismiss_reminder,
+            reminders::delete_reminder,
+            // Calendar commands
+            calendar::get_calendar_week,
+            calendar::get_calendar_month,
+            calendar::save_calendar_entry,
+            calendar::reschedule_calendar_entry,
+            calendar::delete_calendar_entry,
+            calendar::export_calendar_ics,
+            // Pacing guide commands
+            pacing::get_pacing_guides,
+            pacing::save_pacing_guide,
+            pacing::delete_pacing_guide,
+            pacing::get_pacing_status,
+            // Lesson plan commands
+            lesson_plans::get_lesson_plan,
+            // Google Drive
+            google_drive::get_google_drive_status,
+            google_drive::start_google_drive_sign_in,
+            google_drive::finish_google_drive_sign_in,
+            google_drive::disconnect_google_drive,
+            google_drive::upload_to_drive,
+            google_drive::backup_to_drive,
+            google_drive::list_remote_backups,
+            google_drive::restore_from_drive,
+            // OneDrive
+            onedrive::get_onedrive_status,
+            onedrive::start_onedrive_sign_in,
+            onedrive::finish_onedrive_sign_in,
+            onedrive::disconnect_onedrive,
+            onedrive::upload_to_onedrive,
+            onedrive::backup_to_onedrive,
+            onedrive::list_onedrive_backups,
+            onedrive::restore_from_onedrive,
+            // Email
+            email::set_smtp_password,
+            email::send_artifact_email,
+            // Parent Portal
+            parent_portal::export_parent_portal,
+            // Weekly Digest
+            weekly_digest::generate_weekly_digest,
+            // Data History
+            data_history::list_history,
+            data_history::checkout_snapshot,
+            // Share Export
+            share_export::get_share_export_status,
+            share_export::run_share_export,
+            // Local Image Generation
+            local_image::get_local_image_model_status,
+            local_image::download_local_image_model,
+            local_image::delete_local_image_model,
+            local_image::generate_image_local,
+            // Image Cache
+            image_cache::get_cached_image,
+            image_cache::put_cached_image,
+            image_cache::get_image_cache_stats,
+            image_cache::clear_image_cache,
+            // Image Compression
+            image_compression::compress_image,
+            // Clipart
+            clipart::list_clipart_packs,
+            clipart::search_clipart,
+            clipart::import_clipart_pack,
+            clipart::delete_clipart_pack,
+            // Image Library
+            image_library::import_images,
+            image_library::list_images,
+            image_library::get_image_tags,
+            image_library::get_image_data,
+            image_library::update_image,
+            image_library::delete_image,
+            // Webcam
+            webcam::list_webcams,
+            webcam::capture_webcam_photo,
+            // Scanner
+            scanner::list_scanners,
+            scanner::scan_document,
+            // Palette Extraction
+            palette_extraction::extract_palette,
+            // Braille
+            braille::export_brf,
+            // Accessibility
+            accessibility::transform_accessibility,
+            // PDF Export
+            pdf_export::export_pdf,
+            // Print Preview
+            print_preview::print_preview,
+            // LAN Quick Check
+            lan_quick_check::start_lan_quick_check,
+            lan_quick_check::get_lan_quick_check_status,
+            lan_quick_check::stop_lan_quick_check,
+            // Standards Alignment
+            standards_alignment::check_alignment,
+            // Mastery Prediction
+            mastery_prediction::predict_mastery_eta,
+            // Moderation Log
+            moderation_log::record_moderation_decision,
+            moderation_log::get_moderation_log,
+            moderation_log::override_moderation_decision,
+            // Disk Space
+            disk_space::check_disk_space,
+            // HTTP Client
+            http_client::set_proxy_password,
+            http_client::test_proxy,
+            // Connectivity
+            connectivity::is_online,
+            // Locale
+            locale::get_available_locales,
+            locale::get_locale_strings,
+            locale::get_locale_overrides,
+            locale::save_locale_overrides,
+            // Plugins
+            plugins::list_plugins,
+            plugins::install_plugin,
+            plugins::uninstall_plugin,
+            plugins::set_plugin_capabilities,
+            plugins::set_plugin_enabled,
+            plugins::get_plugin_contributions,
+            plugins::run_plugin_exporter,
+            plugins::run_plugin_grader,
+            // Automation
+            automation::list_automation_scripts,
+            automation::save_automation_script,
+            automation::delete_automation_script,
+            automation::run_automation_script,
+            automation::get_automation_runs,
+            // REST API
+            rest_api::get_api_server_status,
+            rest_api::get_api_token,
+            rest_api::regenerate_api_token,
+            // MCP Server
+            mcp_server::get_mcp_server_status,
+            mcp_server::get_mcp_token,
+            mcp_server::regenerate_mcp_token,
+            mcp_server::answer_mcp_consent,
+            mcp_server::take_mcp_generation_requests,
+            mcp_server::complete_mcp_generation_request,
+            // Library Site
+            library_site::export_library_site,
+        ]))
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                index_cache::flush_on_exit(app_handle);
+                crash_reporter::mark_clean_exit(app_handle);
+            }
+        });
 }